@@ -115,6 +115,7 @@ impl Agent {
             include_experience: true,
             include_important: true,
             max_important: 10,
+            ..Default::default()
         };
 
         self.cortex.build_context(&config).await
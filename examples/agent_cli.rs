@@ -74,6 +74,7 @@ impl CliAgent {
             include_experience: true,
             include_important: true,
             max_important: 8,
+            ..Default::default()
         };
         let context = self.cortex.build_context(&config).await?;
         println!("\n----- LLM Context -----\n{}\n-----------------------", context);
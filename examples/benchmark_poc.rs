@@ -377,6 +377,7 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_profile(
     cortex: &MemoryCortex,
     dataset: &Dataset,
@@ -30,7 +30,6 @@ impl Chatbot {
                 .split("my name is")
                 .nth(1)
                 .unwrap_or("")
-                .trim()
                 .split_whitespace()
                 .next()
                 .unwrap_or("")
@@ -10,8 +10,8 @@
 //! Run: cargo run --example comprehensive
 
 use goldfish::{
-    print_results, run_standard_eval, EvalHarness, Experience, HybridSearchConfig, Memory,
-    MemoryCortex, MemorySystem, MemoryType, StorageBackend,
+    print_results, run_embedding_eval, run_search_replica_eval, EvalHarness, Memory, MemoryCortex,
+    MemorySystem, MemoryType,
 };
 
 #[tokio::main]
@@ -147,7 +147,9 @@ async fn main() -> anyhow::Result<()> {
     harness.add_test_case("preferences", vec![], "Find user preferences");
 
     // Run comparison
-    let results = harness.compare_baselines().await?;
+    let mut results = harness.compare_baselines().await?;
+    results.push(run_embedding_eval(3).await?);
+    results.push(run_search_replica_eval(4, 8, 2_000).await?);
     print_results(&results);
 
     // Phase 7: Full Context for LLM
@@ -18,7 +18,7 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use goldfish::{Memory, MemoryCortex, MemoryType};
+use goldfish::{Memory, MemoryCortex, MemoryError, MemoryType};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -110,6 +110,9 @@ pub struct StartEpisodeRequest {
     pub title: String,
     #[serde(default)]
     pub context: Option<String>,
+    /// Start the episode as part of this one
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 /// Episode response
@@ -263,10 +266,19 @@ async fn start_episode(
     Json(req): Json<StartEpisodeRequest>,
 ) -> Result<Json<EpisodeResponse>, StatusCode> {
     let cortex = state.cortex.read().await;
-    let id = cortex
-        .start_episode(&req.title, &req.context.unwrap_or_default())
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let context = req.context.unwrap_or_default();
+    let id = match &req.parent_id {
+        Some(parent_id) => {
+            cortex
+                .start_sub_episode(parent_id, &req.title, &context)
+                .await
+        }
+        None => cortex.start_episode(&req.title, &context).await,
+    }
+    .map_err(|e| match e {
+        MemoryError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
 
     Ok(Json(EpisodeResponse {
         id,
@@ -1,17 +1,151 @@
-use crate::models::{ContextResponse, CreateMemoryRequest, MemoryResponse, SearchRequest};
+use crate::models::{
+    ContextResponse, CreateMemoryRequest, IngestRejection, IngestRequest, IngestResponse,
+    ListRequest, MemoryResponse, SearchRequest, StatsRequest,
+};
 use crate::state::AppState;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
     Json,
 };
-use goldfish::{ContextWindow, Memory, MemoryType};
+use goldfish::{ContextWindow, Memory, MemoryPage, MemorySearchResult, MemoryType, StoreAnalytics};
 use std::sync::Arc;
 
 pub async fn health_check() -> StatusCode {
     StatusCode::OK
 }
 
+/// Reject requests without the configured API key, sent either as
+/// `Authorization: Bearer <key>` or `X-Api-Key: <key>`
+pub async fn require_api_key(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let Some(expected) = &state.api_key else {
+        return Ok(next.run(request).await);
+    };
+    let headers = request.headers();
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()));
+    if given.map(str::trim) != Some(expected.as_str()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+fn internal_error(context: &str, e: goldfish::MemoryError) -> StatusCode {
+    tracing::error!("{} failed: {}", context, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Full memory by ID, without touching working memory
+pub async fn get_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Memory>, StatusCode> {
+    state
+        .cortex
+        .store()
+        .load(&id)
+        .await
+        .map_err(|e| internal_error("Loading memory", e))?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn delete_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let exists = state
+        .cortex
+        .store()
+        .load(&id)
+        .await
+        .map_err(|e| internal_error("Loading memory", e))?
+        .is_some();
+    if !exists {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    state
+        .cortex
+        .delete(&id)
+        .await
+        .map_err(|e| internal_error("Deleting memory", e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn forget_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    match state.cortex.forget(&id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => Err(internal_error("Forgetting memory", e)),
+    }
+}
+
+pub async fn pin_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    set_pinned(&state, &id, true).await
+}
+
+pub async fn unpin_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    set_pinned(&state, &id, false).await
+}
+
+async fn set_pinned(state: &AppState, id: &str, pinned: bool) -> Result<StatusCode, StatusCode> {
+    match state.cortex.set_pinned(id, pinned).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => Err(internal_error("Pinning memory", e)),
+    }
+}
+
+/// Page through memories, newest first; pass `next_cursor` back as `cursor`
+pub async fn list_memories(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ListRequest>,
+) -> Result<Json<MemoryPage>, StatusCode> {
+    let limit = params.limit.unwrap_or(20).min(1000);
+    state
+        .cortex
+        .store()
+        .list_page(params.cursor.as_deref(), limit)
+        .await
+        .map(Json)
+        .map_err(|e| match e {
+            goldfish::MemoryError::InvalidOperation(_) => StatusCode::BAD_REQUEST,
+            e => internal_error("Listing memories", e),
+        })
+}
+
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<StatsRequest>,
+) -> Result<Json<StoreAnalytics>, StatusCode> {
+    let since = chrono::Utc::now() - chrono::Duration::days(params.days.unwrap_or(7));
+    state
+        .cortex
+        .store()
+        .aggregate_stats(since)
+        .await
+        .map(Json)
+        .map_err(|e| internal_error("Computing statistics", e))
+}
+
 // Fix Create Memory
 pub async fn create_memory(
     State(state): State<Arc<AppState>>,
@@ -24,7 +158,11 @@ pub async fn create_memory(
         "preference" => MemoryType::Preference,
         "experience" => MemoryType::Event,
         "decision" => MemoryType::Decision,
-        _ => MemoryType::Fact,
+        other => MemoryType::ALL
+            .iter()
+            .copied()
+            .find(|t| t.to_string() == other)
+            .unwrap_or(MemoryType::Fact),
     };
 
     // 2. Create Memory object
@@ -56,16 +194,37 @@ pub async fn create_memory(
     }
 }
 
+/// Search memories. Responses carry the store generation as `ETag`; a
+/// matching `If-None-Match` gets `304 Not Modified` without running the search.
 pub async fn search_memories(
     State(state): State<Arc<AppState>>,
     Query(params): Query<SearchRequest>,
-) -> Result<Json<Vec<MemoryResponse>>, StatusCode> {
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
     let limit = params.limit.unwrap_or(10);
+    let memory_type = parse_memory_type(params.memory_type.as_deref())?;
+
+    let generation = state
+        .cortex
+        .generation()
+        .await
+        .map_err(|e| internal_error("Reading store generation", e))?;
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    {
+        if generation.matches_if_none_match(if_none_match) {
+            return Ok((
+                StatusCode::NOT_MODIFIED,
+                [(header::ETAG, generation.etag())],
+            )
+                .into_response());
+        }
+    }
 
-    // Use cortex.recall instead of search
-    match state.cortex.recall(&params.q, limit).await {
-        Ok(results) => {
-            let responses = results
+    match recall(&state, &params.q, memory_type, limit).await {
+        Ok(found) => {
+            let responses: Vec<MemoryResponse> = found
                 .into_iter()
                 .map(|m| MemoryResponse {
                     id: m.memory.id,
@@ -75,7 +234,7 @@ pub async fn search_memories(
                     created_at: m.memory.created_at.to_rfc3339(),
                 })
                 .collect();
-            Ok(Json(responses))
+            Ok(([(header::ETAG, generation.etag())], Json(responses)).into_response())
         }
         Err(e) => {
             tracing::error!("Search failed: {}", e);
@@ -84,6 +243,45 @@ pub async fn search_memories(
     }
 }
 
+/// Ranked hits with full memories and scores, as [`MemorySearchResult`]s
+pub async fn recall_memories(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchRequest>,
+) -> Result<Json<Vec<MemorySearchResult>>, StatusCode> {
+    let memory_type = parse_memory_type(params.memory_type.as_deref())?;
+    recall(&state, &params.q, memory_type, params.limit.unwrap_or(10))
+        .await
+        .map(Json)
+        .map_err(|e| internal_error("Recall", e))
+}
+
+/// Recall `q`, restricted to `memory_type` when given
+async fn recall(
+    state: &AppState,
+    q: &str,
+    memory_type: Option<MemoryType>,
+    limit: usize,
+) -> goldfish::Result<Vec<MemorySearchResult>> {
+    match memory_type {
+        Some(memory_type) => state.cortex.recall_of_type(q, memory_type, limit).await,
+        None => state.cortex.recall(q, limit).await,
+    }
+}
+
+/// A `type` query parameter such as `fact`; unknown types are a bad request
+fn parse_memory_type(name: Option<&str>) -> Result<Option<MemoryType>, StatusCode> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    let name = name.to_lowercase();
+    MemoryType::ALL
+        .iter()
+        .copied()
+        .find(|t| t.to_string() == name)
+        .map(Some)
+        .ok_or(StatusCode::BAD_REQUEST)
+}
+
 pub async fn get_context(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<ContextResponse>, StatusCode> {
@@ -117,3 +315,172 @@ pub async fn get_context(
         current_episode: episode_id,
     }))
 }
+
+/// Most events accepted in one ingest request
+const MAX_INGEST_BATCH: usize = 1000;
+
+/// Map pushed events to memories with the template of the bearer token
+pub async fn ingest_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<IngestRequest>,
+) -> Result<Json<IngestResponse>, StatusCode> {
+    let template = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| state.ingest.template(token.trim()))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let events = payload.into_events();
+    if events.len() > MAX_INGEST_BATCH {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut response = IngestResponse {
+        accepted: 0,
+        ids: Vec::new(),
+        rejected: Vec::new(),
+    };
+    for (index, event) in events.iter().enumerate() {
+        let memory = match template.apply(event) {
+            Ok(memory) => memory,
+            Err(error) => {
+                response.rejected.push(IngestRejection { index, error });
+                continue;
+            }
+        };
+        match state.cortex.remember(&memory).await {
+            Ok(()) => {
+                response.accepted += 1;
+                response.ids.push(memory.id);
+            }
+            Err(e) => {
+                tracing::warn!("Ingested event {} rejected: {}", index, e);
+                response.rejected.push(IngestRejection {
+                    index,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::IngestConfig;
+    use goldfish::MemoryCortex;
+
+    async fn test_state(dir: &std::path::Path) -> Arc<AppState> {
+        Arc::new(AppState {
+            cortex: Arc::new(MemoryCortex::new(dir).await.unwrap()),
+            ingest: Arc::new(IngestConfig::default()),
+            api_key: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn memory_endpoints_get_list_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+        let memory = Memory::new("Deploys happen on Tuesdays", MemoryType::Fact);
+        state.cortex.remember(&memory).await.unwrap();
+
+        let Json(loaded) = get_memory(State(Arc::clone(&state)), Path(memory.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(loaded.content, memory.content);
+
+        let Json(page) = list_memories(
+            State(Arc::clone(&state)),
+            Query(ListRequest {
+                cursor: None,
+                limit: Some(10),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.memories.len(), 1);
+
+        let status = delete_memory(State(Arc::clone(&state)), Path(memory.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let missing = get_memory(State(Arc::clone(&state)), Path(memory.id.clone())).await;
+        assert_eq!(missing.unwrap_err(), StatusCode::NOT_FOUND);
+        let missing = delete_memory(State(state), Path(memory.id)).await;
+        assert_eq!(missing.unwrap_err(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn recall_filters_by_type_before_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+        for i in 0..3 {
+            let fact = Memory::new(format!("Deploy fact number {i}"), MemoryType::Fact);
+            state.cortex.remember(&fact).await.unwrap();
+        }
+        let goal = Memory::new("Deploy less often", MemoryType::Goal);
+        state.cortex.remember(&goal).await.unwrap();
+
+        let search = |memory_type: Option<&str>| SearchRequest {
+            q: "deploy".to_string(),
+            limit: Some(1),
+            memory_type: memory_type.map(str::to_string),
+        };
+        let Json(hits) = recall_memories(State(Arc::clone(&state)), Query(search(Some("goal"))))
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].memory.id, goal.id);
+
+        let unknown = recall_memories(State(state), Query(search(Some("rumour")))).await;
+        assert_eq!(unknown.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn ingest_needs_a_known_token_and_reports_rejected_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: IngestConfig = serde_json::from_value(serde_json::json!({
+            "tokens": { "gh-7f3a": { "content": "$.message", "memory_type": "event" } }
+        }))
+        .unwrap();
+        let state = Arc::new(AppState {
+            cortex: Arc::new(MemoryCortex::new(dir.path()).await.unwrap()),
+            ingest: Arc::new(config),
+            api_key: None,
+        });
+
+        let events = || {
+            IngestRequest::Events(vec![
+                serde_json::json!({ "message": "Deployed v2" }),
+                serde_json::json!({ "title": "no message" }),
+            ])
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        let denied = ingest_events(State(Arc::clone(&state)), headers, Json(events())).await;
+        assert_eq!(denied.unwrap_err(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer gh-7f3a".parse().unwrap());
+        let Json(response) = ingest_events(State(Arc::clone(&state)), headers, Json(events()))
+            .await
+            .unwrap();
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected.len(), 1);
+        assert_eq!(response.rejected[0].index, 1);
+
+        let stored = state
+            .cortex
+            .store()
+            .load(&response.ids[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.content, "Deployed v2");
+        assert_eq!(stored.memory_type, MemoryType::Event);
+    }
+}
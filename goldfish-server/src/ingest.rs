@@ -0,0 +1,340 @@
+use goldfish::{Memory, MemoryType};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Env var naming the JSON file with the ingest tokens
+pub const INGEST_CONFIG_ENV: &str = "GOLDFISH_INGEST_CONFIG";
+
+/// Ingest tokens and the template each one maps events with
+///
+/// ```json
+/// {
+///   "tokens": {
+///     "gh-7f3a": {
+///       "content": "$.head_commit.message",
+///       "memory_type": "event",
+///       "importance": "$.priority",
+///       "tags": "$.labels[*].name",
+///       "source": "$.repository.html_url"
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct IngestConfig {
+    #[serde(default)]
+    pub tokens: HashMap<String, IngestTemplate>,
+}
+
+impl IngestConfig {
+    /// Load the config named by [`INGEST_CONFIG_ENV`]; ingestion is disabled
+    /// when it is unset
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var(INGEST_CONFIG_ENV) {
+            Ok(path) => Self::load(path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        let config: Self = serde_json::from_str(&raw)?;
+        for (token, template) in &config.tokens {
+            template
+                .validate()
+                .map_err(|e| anyhow::anyhow!("ingest template for token {token}: {e}"))?;
+        }
+        Ok(config)
+    }
+
+    pub fn template(&self, token: &str) -> Option<&IngestTemplate> {
+        self.tokens.get(token)
+    }
+}
+
+/// Where each memory field comes from in an event. Every field but
+/// `memory_type` is a JSONPath; `memory_type` may also be a literal type name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IngestTemplate {
+    /// Text of the memory; several matches are joined by newlines
+    pub content: String,
+    /// Type name, or a path to one; defaults to `fact`
+    #[serde(default)]
+    pub memory_type: Option<String>,
+    #[serde(default)]
+    pub importance: Option<String>,
+    #[serde(default)]
+    pub tags: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub session: Option<String>,
+    /// Stored under the memory's metadata as is
+    #[serde(default)]
+    pub metadata: Option<String>,
+}
+
+impl IngestTemplate {
+    fn validate(&self) -> Result<(), String> {
+        let paths = [
+            Some(&self.content),
+            self.importance.as_ref(),
+            self.tags.as_ref(),
+            self.source.as_ref(),
+            self.session.as_ref(),
+            self.metadata.as_ref(),
+        ];
+        for path in paths.into_iter().flatten() {
+            parse_path(path)?;
+        }
+        match &self.memory_type {
+            Some(t) if t.starts_with('$') => parse_path(t).map(|_| ()),
+            Some(t) => parse_memory_type(t).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Map one event to a memory
+    pub fn apply(&self, event: &Value) -> Result<Memory, String> {
+        let content = select(event, &self.content)?
+            .into_iter()
+            .filter_map(text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.trim().is_empty() {
+            return Err(format!("no content at {}", self.content));
+        }
+
+        let memory_type = match &self.memory_type {
+            Some(path) if path.starts_with('$') => match first_text(event, path)? {
+                Some(name) => parse_memory_type(&name)?,
+                None => MemoryType::Fact,
+            },
+            Some(name) => parse_memory_type(name)?,
+            None => MemoryType::Fact,
+        };
+
+        let mut memory = Memory::new(content, memory_type);
+        if let Some(path) = &self.importance {
+            if let Some(value) = select(event, path)?.first() {
+                let importance = value
+                    .as_f64()
+                    .ok_or_else(|| format!("importance at {path} is not a number"))?;
+                memory = memory.with_importance(importance as f32);
+            }
+        }
+        if let Some(path) = &self.tags {
+            memory.tags = select(event, path)?
+                .into_iter()
+                .flat_map(|v| match v {
+                    Value::Array(items) => items.iter().filter_map(text).collect(),
+                    v => text(v).into_iter().collect::<Vec<_>>(),
+                })
+                .collect();
+        }
+        if let Some(source) = self.source.as_ref().map(|p| first_text(event, p)) {
+            if let Some(source) = source? {
+                memory = memory.with_source(source);
+            }
+        }
+        if let Some(session) = self.session.as_ref().map(|p| first_text(event, p)) {
+            if let Some(session) = session? {
+                memory = memory.with_session_id(session);
+            }
+        }
+        if let Some(path) = &self.metadata {
+            if let Some(value) = select(event, path)?.first() {
+                memory = memory.with_metadata((*value).clone());
+            }
+        }
+        Ok(memory)
+    }
+}
+
+fn parse_memory_type(name: &str) -> Result<MemoryType, String> {
+    serde_json::from_value(Value::String(name.trim().to_lowercase()))
+        .map_err(|_| format!("unknown memory type {name:?}"))
+}
+
+fn first_text(event: &Value, path: &str) -> Result<Option<String>, String> {
+    Ok(select(event, path)?.into_iter().find_map(text))
+}
+
+/// Scalars as text; objects, arrays and null have none
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parse the JSONPath subset templates use: `$`, `.name`, `['name']`,
+/// `[0]` and `[*]` / `.*`
+fn parse_path(path: &str) -> Result<Vec<Step>, String> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| format!("path {path:?} must start with $"))?;
+    let bad = || format!("invalid path {path:?}");
+
+    let mut steps = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == '.' || c == '[' {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                match name.as_str() {
+                    "" => return Err(bad()),
+                    "*" => steps.push(Step::Wildcard),
+                    _ => steps.push(Step::Field(name)),
+                }
+            }
+            '[' => {
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                let inner = inner.trim();
+                if inner == "*" {
+                    steps.push(Step::Wildcard);
+                } else if let Some(name) = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    steps.push(Step::Field(name.to_string()));
+                } else {
+                    steps.push(Step::Index(inner.parse().map_err(|_| bad())?));
+                }
+            }
+            _ => return Err(bad()),
+        }
+    }
+    Ok(steps)
+}
+
+/// Every value `path` matches in `value`
+fn select<'a>(value: &'a Value, path: &str) -> Result<Vec<&'a Value>, String> {
+    let mut current = vec![value];
+    for step in parse_path(path)? {
+        current = current
+            .into_iter()
+            .flat_map(|v| -> Vec<&Value> {
+                match (&step, v) {
+                    (Step::Field(name), Value::Object(map)) => map.get(name).into_iter().collect(),
+                    (Step::Index(i), Value::Array(items)) => items.get(*i).into_iter().collect(),
+                    (Step::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    (Step::Wildcard, Value::Object(map)) => map.values().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn push_template() -> IngestTemplate {
+        serde_json::from_value(json!({
+            "content": "$.commits[*].message",
+            "memory_type": "$.kind",
+            "importance": "$.priority",
+            "tags": "$.labels[*].name",
+            "source": "$['repository']['html_url']",
+            "metadata": "$.sender"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn templates_map_events_to_memories() {
+        let event = json!({
+            "kind": "Event",
+            "priority": 0.7,
+            "commits": [{ "message": "Fix login" }, { "message": "Bump deps" }],
+            "labels": [{ "name": "auth" }, { "name": "deps" }],
+            "repository": { "html_url": "https://example.com/acme/api" },
+            "sender": { "login": "dana" }
+        });
+        let memory = push_template().apply(&event).unwrap();
+        assert_eq!(memory.content, "Fix login\nBump deps");
+        assert_eq!(memory.memory_type, MemoryType::Event);
+        assert_eq!(memory.importance, 0.7);
+        assert_eq!(memory.tags, ["auth", "deps"]);
+        assert_eq!(
+            memory.source.as_deref(),
+            Some("https://example.com/acme/api")
+        );
+        assert_eq!(memory.metadata, Some(json!({ "login": "dana" })));
+    }
+
+    #[test]
+    fn events_without_content_or_with_bad_fields_are_rejected() {
+        let template = push_template();
+        assert!(template.apply(&json!({ "commits": [] })).is_err());
+        assert!(template
+            .apply(&json!({ "commits": [{ "message": "x" }], "kind": "rumour" }))
+            .is_err());
+        assert!(template
+            .apply(&json!({ "commits": [{ "message": "x" }], "priority": "high" }))
+            .is_err());
+
+        // Missing optional fields fall back to the defaults
+        let memory = template
+            .apply(&json!({ "commits": [{ "message": "x" }] }))
+            .unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Fact);
+        assert!(memory.tags.is_empty());
+    }
+
+    #[test]
+    fn paths_parse_the_supported_subset() {
+        assert_eq!(
+            parse_path("$.a[0]['b c'].*[*]").unwrap(),
+            [
+                Step::Field("a".to_string()),
+                Step::Index(0),
+                Step::Field("b c".to_string()),
+                Step::Wildcard,
+                Step::Wildcard,
+            ]
+        );
+        assert_eq!(parse_path("$").unwrap(), []);
+        for bad in ["a.b", "$..a", "$[x]", "$a"] {
+            assert!(parse_path(bad).is_err(), "{bad} should not parse");
+        }
+
+        let template = IngestTemplate {
+            memory_type: Some("rumour".to_string()),
+            ..push_template()
+        };
+        assert!(template.validate().is_err());
+        assert!(push_template().validate().is_ok());
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -8,9 +9,11 @@ use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod api;
+mod ingest;
 mod models;
 mod state;
 
+use crate::ingest::IngestConfig;
 use crate::state::AppState;
 
 #[tokio::main]
@@ -29,16 +32,43 @@ async fn main() {
     let cortex = MemoryCortex::new("./goldfish_data")
         .await
         .expect("Failed to initialize Cortex");
+    let ingest = IngestConfig::from_env().expect("Failed to load ingest config");
+    tracing::info!("{} ingest token(s) configured", ingest.tokens.len());
+    let api_key = std::env::var("GOLDFISH_API_KEY")
+        .ok()
+        .filter(|k| !k.trim().is_empty());
+    if api_key.is_none() {
+        tracing::warn!("GOLDFISH_API_KEY is not set; the API is open to anyone who can reach it");
+    }
     let state = Arc::new(AppState {
         cortex: Arc::new(cortex),
+        ingest: Arc::new(ingest),
+        api_key,
     });
 
     // Build Router
     let app = Router::new()
-        .route("/health", get(api::health_check))
         .route("/v1/memory", post(api::create_memory))
+        .route(
+            "/v1/memory/:id",
+            get(api::get_memory).delete(api::delete_memory),
+        )
+        .route("/v1/memory/:id/forget", post(api::forget_memory))
+        .route(
+            "/v1/memory/:id/pin",
+            post(api::pin_memory).delete(api::unpin_memory),
+        )
+        .route("/v1/memories", get(api::list_memories))
         .route("/v1/search", get(api::search_memories))
+        .route("/v1/recall", get(api::recall_memories))
         .route("/v1/context", get(api::get_context))
+        .route("/v1/stats", get(api::get_stats))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            api::require_api_key,
+        ))
+        .route("/health", get(api::health_check))
+        .route("/v1/ingest", post(api::ingest_events))
         .with_state(state);
 
     // Run Server
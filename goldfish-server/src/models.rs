@@ -7,6 +7,8 @@ pub struct CreateMemoryRequest {
     pub importance: Option<f32>,
 }
 
+/// Reserved for the episode endpoints, which are not routed yet
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct StartEpisodeRequest {
     pub title: String,
@@ -17,6 +19,9 @@ pub struct StartEpisodeRequest {
 pub struct SearchRequest {
     pub q: String,
     pub limit: Option<usize>,
+    /// Only memories of this type, applied before `limit`
+    #[serde(default, rename = "type")]
+    pub memory_type: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,6 +33,20 @@ pub struct MemoryResponse {
     pub created_at: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListRequest {
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsRequest {
+    /// Days covered by the per-day counts
+    pub days: Option<i64>,
+}
+
+/// Reserved for the episode endpoints, which are not routed yet
+#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 pub struct EpisodeResponse {
     pub id: String,
@@ -35,6 +54,36 @@ pub struct EpisodeResponse {
     pub duration_seconds: i64,
 }
 
+/// Events for `POST /v1/ingest`: `{ "events": [...] }` or a bare array
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IngestRequest {
+    Batch { events: Vec<serde_json::Value> },
+    Events(Vec<serde_json::Value>),
+}
+
+impl IngestRequest {
+    pub fn into_events(self) -> Vec<serde_json::Value> {
+        match self {
+            IngestRequest::Batch { events } | IngestRequest::Events(events) => events,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestResponse {
+    pub accepted: usize,
+    pub ids: Vec<String>,
+    pub rejected: Vec<IngestRejection>,
+}
+
+/// An event the template could not map or the cortex refused
+#[derive(Debug, Serialize)]
+pub struct IngestRejection {
+    pub index: usize,
+    pub error: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ContextResponse {
     pub thinking_about: Vec<MemoryResponse>,
@@ -1,7 +1,11 @@
+use crate::ingest::IngestConfig;
 use goldfish::MemoryCortex;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub cortex: Arc<MemoryCortex>,
+    pub ingest: Arc<IngestConfig>,
+    /// `GOLDFISH_API_KEY`; when set, `/v1` routes other than ingest require it
+    pub api_key: Option<String>,
 }
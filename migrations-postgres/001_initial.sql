@@ -0,0 +1,72 @@
+-- Migration: Initial Postgres schema (memories, graph, episodes, summaries)
+--
+-- Mirrors the SQLite schema in ../migrations as of 008_namespace. JSON columns stay
+-- TEXT so rows round-trip identically between backends.
+
+CREATE TABLE IF NOT EXISTS memories (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    content TEXT NOT NULL,
+    memory_type TEXT NOT NULL,
+    importance REAL NOT NULL DEFAULT 0.5,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    last_accessed_at TIMESTAMPTZ NOT NULL,
+    access_count BIGINT NOT NULL DEFAULT 0,
+    source TEXT,
+    session_id TEXT,
+    forgotten BOOLEAN NOT NULL DEFAULT FALSE,
+    metadata TEXT,
+    confidence_score REAL NOT NULL DEFAULT 0.5,
+    confidence_data TEXT,
+    verification_status TEXT NOT NULL DEFAULT 'unverified',
+    tags TEXT,
+    pinned BOOLEAN NOT NULL DEFAULT FALSE,
+    emotional_valence REAL NOT NULL DEFAULT 0.0
+);
+
+CREATE TABLE IF NOT EXISTS associations (
+    id TEXT PRIMARY KEY,
+    source_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    target_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    relation_type TEXT NOT NULL,
+    weight REAL NOT NULL DEFAULT 0.5,
+    created_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (source_id, target_id, relation_type)
+);
+
+CREATE TABLE IF NOT EXISTS experiences (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    title TEXT NOT NULL,
+    context TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,
+    importance REAL NOT NULL DEFAULT 0.5
+);
+
+CREATE TABLE IF NOT EXISTS experience_memories (
+    experience_id TEXT NOT NULL REFERENCES experiences(id) ON DELETE CASCADE,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (experience_id, memory_id)
+);
+
+CREATE TABLE IF NOT EXISTS memory_summaries (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    summary_text TEXT NOT NULL,
+    original_memory_ids TEXT NOT NULL,
+    memory_type TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    importance REAL NOT NULL DEFAULT 0.5
+);
+
+CREATE INDEX IF NOT EXISTS idx_memories_namespace ON memories(namespace, forgotten);
+CREATE INDEX IF NOT EXISTS idx_memories_type ON memories(namespace, memory_type);
+CREATE INDEX IF NOT EXISTS idx_memories_created ON memories(namespace, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_associations_source ON associations(source_id);
+CREATE INDEX IF NOT EXISTS idx_associations_target ON associations(target_id);
+CREATE INDEX IF NOT EXISTS idx_experiences_namespace ON experiences(namespace, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_experience_memories_mem ON experience_memories(memory_id);
+CREATE INDEX IF NOT EXISTS idx_summaries_namespace ON memory_summaries(namespace, created_at DESC);
@@ -0,0 +1,5 @@
+-- Migration: Language of each memory, as in ../migrations/019_language.sql
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS language TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_memories_language ON memories(namespace, language);
@@ -0,0 +1,6 @@
+-- Migration: Episode hierarchy, as in ../migrations/021_episode_hierarchy.sql
+
+ALTER TABLE experiences ADD COLUMN IF NOT EXISTS parent_id TEXT;
+ALTER TABLE experiences ADD COLUMN IF NOT EXISTS summary TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_experiences_parent ON experiences(namespace, parent_id);
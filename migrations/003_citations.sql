@@ -0,0 +1,12 @@
+-- Migration: Citation tracking
+
+-- Citations: which memories were used to produce a generated answer
+CREATE TABLE IF NOT EXISTS citations (
+    answer_id TEXT NOT NULL,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    cited_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (answer_id, memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_citations_memory ON citations(memory_id);
+CREATE INDEX IF NOT EXISTS idx_citations_cited ON citations(cited_at DESC);
@@ -0,0 +1,3 @@
+-- Migration: Persist memory tags (JSON array)
+
+ALTER TABLE memories ADD COLUMN tags TEXT;
@@ -0,0 +1,5 @@
+-- Migration: Persistent pinning (pinned memories are never pruned or consolidated)
+
+ALTER TABLE memories ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_memories_pinned ON memories(pinned) WHERE pinned = 1;
@@ -0,0 +1,5 @@
+-- Migration: Persist emotional valence (-1.0 negative .. 1.0 positive)
+
+ALTER TABLE memories ADD COLUMN emotional_valence REAL NOT NULL DEFAULT 0.0;
+
+CREATE INDEX IF NOT EXISTS idx_memories_valence_created ON memories(created_at, emotional_valence);
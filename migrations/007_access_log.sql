@@ -0,0 +1,9 @@
+-- Migration: Per-access event log for activity analytics
+
+CREATE TABLE IF NOT EXISTS memory_accesses (
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    accessed_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_accesses_at ON memory_accesses(accessed_at);
+CREATE INDEX IF NOT EXISTS idx_memory_accesses_mem ON memory_accesses(memory_id);
@@ -0,0 +1,9 @@
+-- Migration: Namespaces, so many cortices (agents, users) can share one database
+
+ALTER TABLE memories ADD COLUMN namespace TEXT NOT NULL DEFAULT '';
+ALTER TABLE experiences ADD COLUMN namespace TEXT NOT NULL DEFAULT '';
+ALTER TABLE memory_summaries ADD COLUMN namespace TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX IF NOT EXISTS idx_memories_namespace ON memories(namespace, forgotten);
+CREATE INDEX IF NOT EXISTS idx_experiences_namespace ON experiences(namespace, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_summaries_namespace ON memory_summaries(namespace);
@@ -0,0 +1,14 @@
+-- Migration: Saved searches, standing queries evaluated against new memories
+
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    config TEXT NOT NULL,
+    webhook_url TEXT,
+    created_at TIMESTAMP NOT NULL,
+    last_hit_at TIMESTAMP,
+    hit_count INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (namespace, name)
+);
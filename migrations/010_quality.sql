@@ -0,0 +1,7 @@
+-- Migration: Memory quality scores and the curation queue
+
+ALTER TABLE memories ADD COLUMN quality REAL;
+ALTER TABLE memories ADD COLUMN quality_data TEXT;
+ALTER TABLE memories ADD COLUMN quality_reviewed INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_memories_quality ON memories(namespace, forgotten, quality);
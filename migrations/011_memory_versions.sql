@@ -0,0 +1,16 @@
+-- Migration: Memory version history, for time-travel reads and audits
+
+CREATE TABLE IF NOT EXISTS memory_versions (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    memory_id TEXT NOT NULL,
+    version_number INTEGER NOT NULL,
+    snapshot TEXT NOT NULL,
+    author TEXT NOT NULL,
+    change_reason TEXT,
+    previous_version_id TEXT,
+    created_at TIMESTAMP NOT NULL,
+    UNIQUE (namespace, memory_id, version_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_versions_created ON memory_versions(namespace, created_at);
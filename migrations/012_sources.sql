@@ -0,0 +1,22 @@
+-- Migration: Source documents and the memories derived from them
+
+CREATE TABLE IF NOT EXISTS sources (
+    namespace TEXT NOT NULL DEFAULT '',
+    uri TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    title TEXT,
+    memory_type TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    last_synced TIMESTAMP NOT NULL,
+    PRIMARY KEY (namespace, uri)
+);
+
+CREATE TABLE IF NOT EXISTS source_memories (
+    namespace TEXT NOT NULL DEFAULT '',
+    uri TEXT NOT NULL,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    section INTEGER NOT NULL,
+    PRIMARY KEY (namespace, uri, memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_source_memories_memory ON source_memories(memory_id);
@@ -0,0 +1,9 @@
+-- Migration: Checkpoints of scheduled ingestion connectors
+
+CREATE TABLE IF NOT EXISTS connector_checkpoints (
+    namespace TEXT NOT NULL DEFAULT '',
+    connector TEXT NOT NULL,
+    checkpoint TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (namespace, connector)
+);
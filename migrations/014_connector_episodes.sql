@@ -0,0 +1,9 @@
+-- Migration: Episodes created by ingestion connectors, keyed by the source's thread ID
+
+CREATE TABLE IF NOT EXISTS connector_episodes (
+    namespace TEXT NOT NULL DEFAULT '',
+    connector TEXT NOT NULL,
+    episode_key TEXT NOT NULL,
+    experience_id TEXT NOT NULL REFERENCES experiences(id) ON DELETE CASCADE,
+    PRIMARY KEY (namespace, connector, episode_key)
+);
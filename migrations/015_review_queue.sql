@@ -0,0 +1,16 @@
+-- Migration: Review queue for memories from low-trust sources
+
+CREATE TABLE IF NOT EXISTS review_queue (
+    namespace TEXT NOT NULL DEFAULT '',
+    memory_id TEXT NOT NULL,
+    memory TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending_review',
+    reason TEXT NOT NULL,
+    submitted_at TIMESTAMP NOT NULL,
+    reviewed_at TIMESTAMP,
+    reviewer TEXT,
+    note TEXT,
+    PRIMARY KEY (namespace, memory_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_queue_status ON review_queue(namespace, status, submitted_at);
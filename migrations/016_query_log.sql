@@ -0,0 +1,16 @@
+-- Migration: Sampled log of search queries for slow/empty query analysis
+
+CREATE TABLE IF NOT EXISTS query_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    namespace TEXT NOT NULL DEFAULT '',
+    query TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    config_hash TEXT NOT NULL,
+    latency_ms REAL NOT NULL,
+    result_count INTEGER NOT NULL,
+    top_ids TEXT NOT NULL DEFAULT '[]',
+    timed_out BOOLEAN NOT NULL DEFAULT 0,
+    logged_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_query_log_logged_at ON query_log(namespace, logged_at);
@@ -0,0 +1,14 @@
+-- Migration: Content-addressed snapshots of built context windows
+
+CREATE TABLE IF NOT EXISTS context_traces (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    content_hash TEXT NOT NULL,
+    context TEXT NOT NULL,
+    memories TEXT NOT NULL DEFAULT '[]',
+    experience_id TEXT,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_context_traces_hash ON context_traces(namespace, content_hash);
+CREATE INDEX IF NOT EXISTS idx_context_traces_created ON context_traces(namespace, created_at);
@@ -0,0 +1,38 @@
+-- Migration: Index memory tags for filtering
+--
+-- `memories.tags` stays the source of truth (a JSON array); triggers keep one
+-- row per tag here so lookups by tag don't scan every memory.
+
+CREATE TABLE IF NOT EXISTS memory_tags (
+    memory_id TEXT NOT NULL,
+    namespace TEXT NOT NULL DEFAULT '',
+    tag TEXT NOT NULL,
+    PRIMARY KEY (memory_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_tags_tag ON memory_tags(namespace, tag);
+
+INSERT OR IGNORE INTO memory_tags (memory_id, namespace, tag)
+SELECT m.id, m.namespace, t.value
+FROM memories m, json_each(m.tags) t
+WHERE m.tags IS NOT NULL AND json_valid(m.tags);
+
+CREATE TRIGGER IF NOT EXISTS memory_tags_insert AFTER INSERT ON memories
+WHEN NEW.tags IS NOT NULL AND json_valid(NEW.tags)
+BEGIN
+    INSERT OR IGNORE INTO memory_tags (memory_id, namespace, tag)
+    SELECT NEW.id, NEW.namespace, value FROM json_each(NEW.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memory_tags_update AFTER UPDATE OF tags, namespace ON memories
+BEGIN
+    DELETE FROM memory_tags WHERE memory_id = OLD.id;
+    INSERT OR IGNORE INTO memory_tags (memory_id, namespace, tag)
+    SELECT NEW.id, NEW.namespace, value
+    FROM json_each(CASE WHEN json_valid(NEW.tags) THEN NEW.tags ELSE '[]' END);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memory_tags_delete AFTER DELETE ON memories
+BEGIN
+    DELETE FROM memory_tags WHERE memory_id = OLD.id;
+END;
@@ -0,0 +1,5 @@
+-- Migration: Language of each memory (ISO 639-1, detected on save when not given)
+
+ALTER TABLE memories ADD COLUMN language TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_memories_language ON memories(namespace, language);
@@ -0,0 +1,3 @@
+-- Migration: When an association's weight was last decayed or reinforced (NULL: never, use created_at)
+
+ALTER TABLE associations ADD COLUMN decayed_at TIMESTAMP;
@@ -0,0 +1,6 @@
+-- Migration: Episode hierarchy (projects containing sessions) and rolled-up summaries
+
+ALTER TABLE experiences ADD COLUMN parent_id TEXT;
+ALTER TABLE experiences ADD COLUMN summary TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_experiences_parent ON experiences(namespace, parent_id);
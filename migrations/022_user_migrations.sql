@@ -0,0 +1,8 @@
+-- Migration: Applied user-defined migrations (see MigrationHook), tracked apart from _sqlx_migrations
+
+CREATE TABLE IF NOT EXISTS user_migrations (
+    version INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    sql TEXT NOT NULL,
+    applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
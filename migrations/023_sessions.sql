@@ -0,0 +1,13 @@
+-- Migration: Sessions (conversations) that memories can be saved under
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    namespace TEXT NOT NULL DEFAULT '',
+    metadata TEXT,
+    started_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP,
+    -- Written by end_session from the session's memories
+    summary TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_started ON sessions(namespace, started_at);
@@ -0,0 +1,76 @@
+-- Migration: Store generation, bumped by triggers on every mutation
+--
+-- Read APIs return the generation so polling clients can tell "nothing
+-- changed" without diffing results. Access bookkeeping (access_count,
+-- last_accessed_at) is a side effect of reads and does not bump it.
+
+CREATE TABLE IF NOT EXISTS store_generation (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    generation INTEGER NOT NULL
+);
+
+INSERT OR IGNORE INTO store_generation (id, generation) VALUES (1, 0);
+
+CREATE TRIGGER IF NOT EXISTS generation_memories_insert AFTER INSERT ON memories
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_memories_update AFTER UPDATE OF
+    content, memory_type, importance, updated_at, source, session_id, forgotten, metadata,
+    confidence_score, confidence_data, verification_status, tags, pinned,
+    emotional_valence, namespace, quality, quality_data, quality_reviewed, language
+ON memories
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_memories_delete AFTER DELETE ON memories
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_associations_insert AFTER INSERT ON associations
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_associations_update AFTER UPDATE ON associations
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_associations_delete AFTER DELETE ON associations
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_experiences_insert AFTER INSERT ON experiences
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_experiences_update AFTER UPDATE ON experiences
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_experiences_delete AFTER DELETE ON experiences
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_experience_memories_insert AFTER INSERT ON experience_memories
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_experience_memories_delete AFTER DELETE ON experience_memories
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS generation_summaries_insert AFTER INSERT ON memory_summaries
+BEGIN
+    UPDATE store_generation SET generation = generation + 1 WHERE id = 1;
+END;
@@ -0,0 +1,13 @@
+-- Migration: Chunk offsets for memories embedded as several vectors
+--
+-- Long memories get one vector per chunk, stored under `<memory id>#<chunk>`.
+-- The offsets (bytes into the content) let searches report the matching
+-- span and let updates and deletes find every chunk vector.
+
+CREATE TABLE IF NOT EXISTS vector_chunks (
+    memory_id TEXT NOT NULL,
+    chunk INTEGER NOT NULL,
+    start_offset INTEGER NOT NULL,
+    end_offset INTEGER NOT NULL,
+    PRIMARY KEY (memory_id, chunk)
+);
@@ -0,0 +1,12 @@
+-- Migration: When each memory was forgotten
+--
+-- Retention purges memories by how long ago they were forgotten. Other edits
+-- to a forgotten memory move `updated_at`, so it cannot stand in for that.
+-- Rows forgotten before this migration take their last update as the best
+-- available guess.
+
+ALTER TABLE memories ADD COLUMN forgotten_at TIMESTAMP;
+
+UPDATE memories SET forgotten_at = updated_at WHERE forgotten = 1;
+
+CREATE INDEX IF NOT EXISTS idx_memories_forgotten_at ON memories(namespace, forgotten_at);
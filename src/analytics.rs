@@ -0,0 +1,546 @@
+//! Memory analytics
+//!
+//! Aggregate views over the store: tag trends, memory creation velocity, type
+//! distribution over time, top entities and most-recalled memories.
+//!
+//! Counting happens in SQLite; [`MemoryAnalytics`] caches each result for a short
+//! TTL so dashboards can poll without re-scanning the table on every request.
+
+use crate::cache::CacheKey;
+use crate::error::{MemoryError, Result};
+use crate::store::SortOrder;
+use crate::types::{Memory, MemoryType};
+use crate::MemoryStore;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+
+/// Direction of a tag's usage compared to the previous period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Up,
+    Down,
+    Stable,
+}
+
+impl std::fmt::Display for TrendDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrendDirection::Up => write!(f, "up"),
+            TrendDirection::Down => write!(f, "down"),
+            TrendDirection::Stable => write!(f, "stable"),
+        }
+    }
+}
+
+/// Usage of one tag in the current period vs. the one before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagTrend {
+    pub tag: String,
+    /// Memories tagged within the current period
+    pub count: u64,
+    /// Memories tagged within the previous period of equal length
+    pub previous_count: u64,
+    pub direction: TrendDirection,
+}
+
+impl TagTrend {
+    /// Relative change below which a tag counts as stable
+    const STABLE_BAND: f32 = 0.2;
+
+    fn new(tag: String, count: u64, previous_count: u64) -> Self {
+        let base = previous_count.max(1) as f32;
+        let change = (count as f32 - previous_count as f32) / base;
+        let direction = if change > Self::STABLE_BAND {
+            TrendDirection::Up
+        } else if change < -Self::STABLE_BAND {
+            TrendDirection::Down
+        } else {
+            TrendDirection::Stable
+        };
+        Self {
+            tag,
+            count,
+            previous_count,
+            direction,
+        }
+    }
+}
+
+/// Width of the time buckets used by velocity and distribution queries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeBucket {
+    Hour,
+    #[default]
+    Day,
+    /// Weeks start on Monday (UTC)
+    Week,
+}
+
+impl TimeBucket {
+    fn seconds(self) -> i64 {
+        match self {
+            TimeBucket::Hour => 3_600,
+            TimeBucket::Day => 86_400,
+            TimeBucket::Week => 7 * 86_400,
+        }
+    }
+
+    /// Longest window, in days, that [`MemoryAnalytics::activity`] accepts for
+    /// this width; it keeps the dense series to a few thousand buckets
+    pub fn max_days(self) -> i64 {
+        match self {
+            TimeBucket::Hour => 90,
+            TimeBucket::Day | TimeBucket::Week => 3_650,
+        }
+    }
+
+    /// Offset from the Unix epoch (a Thursday) that buckets are aligned to
+    fn origin(self) -> i64 {
+        match self {
+            TimeBucket::Week => 4 * 86_400,
+            TimeBucket::Hour | TimeBucket::Day => 0,
+        }
+    }
+}
+
+/// Number of memories created within one bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VelocityPoint {
+    pub bucket_start: DateTime<Utc>,
+    pub count: u64,
+}
+
+/// Creation and access counts for one bucket of an activity heatmap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityBucket {
+    pub bucket_start: DateTime<Utc>,
+    /// Memories created (the agent learning)
+    pub created: u64,
+    /// Memory accesses (the agent recalling)
+    pub accessed: u64,
+}
+
+/// Memories created within one bucket, split by type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeDistributionPoint {
+    pub bucket_start: DateTime<Utc>,
+    pub counts: BTreeMap<String, u64>,
+}
+
+/// An entity (proper noun phrase) and the number of memories mentioning it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityCount {
+    pub entity: String,
+    pub count: u64,
+}
+
+/// All analytics views for one period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsReport {
+    pub generated_at: DateTime<Utc>,
+    pub period_days: i64,
+    pub tag_trends: Vec<TagTrend>,
+    pub velocity: Vec<VelocityPoint>,
+    pub type_distribution: Vec<TypeDistributionPoint>,
+    pub top_entities: Vec<EntityCount>,
+    pub most_recalled: Vec<Memory>,
+}
+
+/// Words that are capitalized for grammatical reasons rather than because they name something
+fn is_entity_stopword(word: &str) -> bool {
+    matches!(
+        word,
+        "I" | "I'm"
+            | "I've"
+            | "A"
+            | "An"
+            | "The"
+            | "This"
+            | "That"
+            | "These"
+            | "Those"
+            | "It"
+            | "We"
+            | "You"
+            | "He"
+            | "She"
+            | "They"
+            | "My"
+            | "Our"
+            | "Your"
+            | "Decision"
+            | "Context"
+            | "Options"
+    )
+}
+
+/// Extract proper-noun phrases: runs of capitalized words that do not start a sentence.
+///
+/// Sentence-initial words are only kept when they continue into another capitalized word
+/// (e.g. "New York is ..."). Each entity is returned once per text.
+pub fn extract_entities(text: &str) -> Vec<String> {
+    let mut entities: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_starts_sentence = false;
+    let mut sentence_start = true;
+
+    let mut flush = |current: &mut Vec<&str>, starts_sentence: bool| {
+        if !current.is_empty() && (current.len() > 1 || !starts_sentence) {
+            let entity = current.join(" ");
+            if !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+        current.clear();
+    };
+
+    for raw in text.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'');
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+
+        if capitalized && !is_entity_stopword(word) {
+            if current.is_empty() {
+                current_starts_sentence = sentence_start;
+            }
+            current.push(word);
+        } else {
+            flush(&mut current, current_starts_sentence);
+        }
+
+        let ends_sentence = raw.ends_with(['.', '!', '?', ':', ';']);
+        if ends_sentence || raw.ends_with(',') {
+            flush(&mut current, current_starts_sentence);
+        }
+        sentence_start = ends_sentence;
+    }
+    flush(&mut current, current_starts_sentence);
+
+    entities
+}
+
+/// Aggregated analytics over a [`MemoryStore`] with a TTL cache
+pub struct MemoryAnalytics {
+    store: Arc<MemoryStore>,
+    ttl: std::time::Duration,
+    cache: RwLock<HashMap<CacheKey, (Instant, Vec<u8>)>>,
+}
+
+impl std::fmt::Debug for MemoryAnalytics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryAnalytics")
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl MemoryAnalytics {
+    /// Number of recent memories scanned for entities
+    const ENTITY_SCAN_LIMIT: i64 = 2_000;
+
+    pub fn new(store: Arc<MemoryStore>) -> Self {
+        Self {
+            store,
+            ttl: std::time::Duration::from_secs(60),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// How long computed results are reused (0 disables caching)
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Drop all cached results
+    pub async fn invalidate(&self) {
+        self.cache.write().await.clear();
+    }
+
+    async fn cached<T, F, Fut>(&self, key: CacheKey, compute: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some((at, data)) = self.cache.read().await.get(&key) {
+            if at.elapsed() < self.ttl {
+                if let Ok(value) = serde_json::from_slice(data) {
+                    return Ok(value);
+                }
+            }
+        }
+
+        let value = compute().await?;
+        if !self.ttl.is_zero() {
+            let data = serde_json::to_vec(&value)
+                .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+            self.cache.write().await.insert(key, (Instant::now(), data));
+        }
+        Ok(value)
+    }
+
+    /// Tags used over the last `days`, compared with the `days` before that
+    pub async fn tag_trends(&self, days: i64, limit: usize) -> Result<Vec<TagTrend>> {
+        let key = CacheKey(format!("analytics:tags:{days}:{limit}"));
+        self.cached(key, || async {
+            let now = Utc::now();
+            let start = now - Duration::days(days);
+            let previous_start = start - Duration::days(days);
+
+            let current = self
+                .store
+                .tag_counts_between(start, now, limit as i64)
+                .await?;
+            let previous: HashMap<String, i64> = self
+                .store
+                .tag_counts_between(previous_start, start, i64::MAX)
+                .await?
+                .into_iter()
+                .collect();
+
+            Ok(current
+                .into_iter()
+                .map(|(tag, count)| {
+                    let before = previous.get(&tag).copied().unwrap_or(0);
+                    TagTrend::new(tag, count.max(0) as u64, before.max(0) as u64)
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Per-bucket creation counts by type over the last `days`, oldest first
+    pub async fn type_distribution(
+        &self,
+        days: i64,
+        bucket: TimeBucket,
+    ) -> Result<Vec<TypeDistributionPoint>> {
+        let key = CacheKey(format!("analytics:types:{days}:{bucket:?}"));
+        self.cached(key, || async {
+            let since = Utc::now() - Duration::days(days);
+            let rows = self
+                .store
+                .creation_counts_by_bucket(since, bucket.seconds(), bucket.origin())
+                .await?;
+
+            let mut points: Vec<TypeDistributionPoint> = Vec::new();
+            for (start, memory_type, count) in rows {
+                let bucket_start = Utc.timestamp_opt(start, 0).single().unwrap_or(since);
+                if points.last().map(|p| p.bucket_start) != Some(bucket_start) {
+                    points.push(TypeDistributionPoint {
+                        bucket_start,
+                        counts: BTreeMap::new(),
+                    });
+                }
+                if let Some(point) = points.last_mut() {
+                    *point.counts.entry(memory_type.to_string()).or_insert(0) +=
+                        count.max(0) as u64;
+                }
+            }
+            Ok(points)
+        })
+        .await
+    }
+
+    /// Memories created per bucket over the last `days`, oldest first
+    pub async fn velocity(&self, days: i64, bucket: TimeBucket) -> Result<Vec<VelocityPoint>> {
+        Ok(self
+            .type_distribution(days, bucket)
+            .await?
+            .into_iter()
+            .map(|point| VelocityPoint {
+                bucket_start: point.bucket_start,
+                count: point.counts.values().sum(),
+            })
+            .collect())
+    }
+
+    /// Creation/access counts per bucket over the last `days`, oldest first.
+    ///
+    /// The series is dense: buckets without activity are included with zero counts.
+    /// `days` must lie in `1..=bucket.max_days()`.
+    pub async fn activity(&self, days: i64, bucket: TimeBucket) -> Result<Vec<ActivityBucket>> {
+        if !(1..=bucket.max_days()).contains(&days) {
+            return Err(MemoryError::InvalidOperation(format!(
+                "activity covers 1 to {} days at {bucket:?} granularity, not {days}",
+                bucket.max_days()
+            )));
+        }
+        let key = CacheKey(format!("analytics:activity:{days}:{bucket:?}"));
+        self.cached(key, || async {
+            let now = Utc::now();
+            let since = now - Duration::days(days);
+            let (width, origin) = (bucket.seconds(), bucket.origin());
+            let counts: HashMap<i64, (i64, i64)> = self
+                .store
+                .activity_counts(since, width, origin)
+                .await?
+                .into_iter()
+                .map(|(start, created, accessed)| (start, (created, accessed)))
+                .collect();
+
+            let align = |ts: i64| (ts - origin).div_euclid(width) * width + origin;
+            let mut buckets = Vec::new();
+            let mut start = align(since.timestamp());
+            while start <= now.timestamp() {
+                let (created, accessed) = counts.get(&start).copied().unwrap_or((0, 0));
+                buckets.push(ActivityBucket {
+                    bucket_start: Utc.timestamp_opt(start, 0).single().unwrap_or(since),
+                    created: created.max(0) as u64,
+                    accessed: accessed.max(0) as u64,
+                });
+                start += width;
+            }
+            Ok(buckets)
+        })
+        .await
+    }
+
+    /// Most frequently mentioned entities in memories created over the last `days`
+    pub async fn top_entities(&self, days: i64, limit: usize) -> Result<Vec<EntityCount>> {
+        let key = CacheKey(format!("analytics:entities:{days}:{limit}"));
+        self.cached(key, || async {
+            let since = Utc::now() - Duration::days(days);
+            let contents = self
+                .store
+                .contents_since(since, Self::ENTITY_SCAN_LIMIT)
+                .await?;
+
+            let mut counts: HashMap<String, u64> = HashMap::new();
+            for content in &contents {
+                for entity in extract_entities(content) {
+                    *counts.entry(entity).or_insert(0) += 1;
+                }
+            }
+
+            let mut entities: Vec<EntityCount> = counts
+                .into_iter()
+                .map(|(entity, count)| EntityCount { entity, count })
+                .collect();
+            entities.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.entity.cmp(&b.entity)));
+            entities.truncate(limit);
+            Ok(entities)
+        })
+        .await
+    }
+
+    /// Memories recalled most often, optionally restricted to one type
+    pub async fn most_recalled(
+        &self,
+        limit: usize,
+        memory_type: Option<MemoryType>,
+    ) -> Result<Vec<Memory>> {
+        let key = CacheKey(format!("analytics:recalled:{limit}:{memory_type:?}"));
+        self.cached(key, || async {
+            let memories = self
+                .store
+                .get_sorted(SortOrder::MostAccessed, limit as i64, memory_type)
+                .await?;
+            Ok(memories
+                .into_iter()
+                .filter(|m| m.access_count > 0)
+                .collect())
+        })
+        .await
+    }
+
+    /// Every view for the last `days`
+    pub async fn report(&self, days: i64, limit: usize) -> Result<AnalyticsReport> {
+        let bucket = if days <= 2 {
+            TimeBucket::Hour
+        } else if days <= 60 {
+            TimeBucket::Day
+        } else {
+            TimeBucket::Week
+        };
+
+        let type_distribution = self.type_distribution(days, bucket).await?;
+        let velocity = type_distribution
+            .iter()
+            .map(|point| VelocityPoint {
+                bucket_start: point.bucket_start,
+                count: point.counts.values().sum(),
+            })
+            .collect();
+
+        Ok(AnalyticsReport {
+            generated_at: Utc::now(),
+            period_days: days,
+            tag_trends: self.tag_trends(days, limit).await?,
+            velocity,
+            type_distribution,
+            top_entities: self.top_entities(days, limit).await?,
+            most_recalled: self.most_recalled(limit, None).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entities_skip_sentence_starts_and_pronouns() {
+        let entities = extract_entities(
+            "Yesterday I met Alice at Acme Corp. New York was cold. She liked it.",
+        );
+        assert_eq!(entities, vec!["Alice", "Acme Corp", "New York"]);
+    }
+
+    #[tokio::test]
+    async fn activity_counts_creations_and_accesses() {
+        let store = MemoryStore::connect_in_memory().await;
+        let memory = Memory::new("note", MemoryType::Fact);
+        store.save(&memory).await.unwrap();
+        store.record_access(&memory.id).await.unwrap();
+        store.record_access(&memory.id).await.unwrap();
+
+        let analytics = MemoryAnalytics::new(store);
+        let activity = analytics.activity(2, TimeBucket::Hour).await.unwrap();
+        assert!(activity.len() >= 48);
+        let last = activity.last().unwrap();
+        assert_eq!((last.created, last.accessed), (1, 2));
+
+        for (days, bucket) in [
+            (0, TimeBucket::Day),
+            (-5, TimeBucket::Week),
+            (91, TimeBucket::Hour),
+            (i64::MAX, TimeBucket::Day),
+        ] {
+            assert!(matches!(
+                analytics.activity(days, bucket).await,
+                Err(MemoryError::InvalidOperation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn tag_trends_count_current_period() {
+        let store = MemoryStore::connect_in_memory().await;
+        for tags in [
+            vec!["rust"],
+            vec!["rust", "sqlite"],
+            vec!["sqlite"],
+            vec!["rust"],
+        ] {
+            let mut memory = Memory::new("note", MemoryType::Fact);
+            memory.tags = tags.into_iter().map(String::from).collect();
+            store.save(&memory).await.unwrap();
+        }
+
+        let analytics = MemoryAnalytics::new(store);
+        let trends = analytics.tag_trends(7, 10).await.unwrap();
+        assert_eq!(trends[0].tag, "rust");
+        assert_eq!(trends[0].count, 3);
+        assert_eq!(trends[0].direction, TrendDirection::Up);
+        assert_eq!(trends[1].count, 2);
+    }
+}
@@ -0,0 +1,317 @@
+//! Pseudonymized copies of a dataset for sharing
+//!
+//! A retrieval bug report is only reproducible with the data behind it, which
+//! is usually private. An [`Anonymizer`] learns the sensitive terms of a
+//! corpus (proper-noun entities as found by
+//! [`extract_entities`](crate::analytics::extract_entities), and the parts of
+//! email addresses) and rewrites every text with stable pseudonyms:
+//!
+//! - the same term gets the same pseudonym everywhere, whatever its case, so
+//!   "Alice", "alice" and `alice@acme.io` stay one person across memories and
+//!   queries;
+//! - pseudonyms keep the shape of the original (length, case, letters vs.
+//!   digits), and email top-level domains are kept, so tokenization and
+//!   lengths behave the same;
+//! - pseudonyms are derived from a salt: the same salt gives the same output,
+//!   and without it they cannot be reversed by hashing candidate names.
+//!
+//! [`MemorySystem::export_anonymized`] writes an anonymized archive, and
+//! `goldfish anonymize --in data --out data_anon` builds a data directory
+//! from one, including the query log and an optional benchmark query file.
+//!
+//! [`MemorySystem::export_anonymized`]: crate::MemorySystem::export_anonymized
+
+use crate::analytics::extract_entities;
+use crate::cortex::{Experience, MemorySummary};
+use crate::embedding::{fnv1a, FNV_OFFSET};
+use crate::query_log::QueryLogEntry;
+use crate::types::Memory;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Terms shorter than this are never replaced ("s" of "Alice's", initials)
+const MIN_TERM_CHARS: usize = 2;
+
+/// Learns sensitive terms and replaces them with consistent pseudonyms
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    salt: u64,
+    /// Lowercased term to lowercased pseudonym
+    terms: HashMap<String, String>,
+    /// Pseudonyms handed out, so two terms never share one
+    used: HashSet<String>,
+    /// Lowercased terms that are never replaced
+    keep: HashSet<String>,
+}
+
+impl Anonymizer {
+    pub fn new(salt: &str) -> Self {
+        Self {
+            salt: fnv1a(salt.as_bytes(), FNV_OFFSET),
+            terms: HashMap::new(),
+            used: HashSet::new(),
+            keep: HashSet::new(),
+        }
+    }
+
+    /// Leave `term` as is, e.g. a product name the bug report is about
+    pub fn with_kept_term(mut self, term: &str) -> Self {
+        self.keep.insert(term.to_lowercase());
+        self
+    }
+
+    /// Number of terms learned
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Original to pseudonym, lowercased. Anyone holding it can undo the
+    /// anonymization, so keep it private.
+    pub fn mapping(&self) -> BTreeMap<String, String> {
+        self.terms
+            .iter()
+            .map(|(term, pseudonym)| (term.clone(), pseudonym.clone()))
+            .collect()
+    }
+
+    /// Learn the entities and email addresses in `text`
+    pub fn learn(&mut self, text: &str) {
+        for entity in extract_entities(text) {
+            for word in words(&entity) {
+                self.learn_term(word);
+            }
+        }
+        for token in text.split_whitespace() {
+            let Some(email) = email_in(token) else {
+                continue;
+            };
+            let (local, domain) = email.split_once('@').unwrap_or((email, ""));
+            let labels: Vec<&str> = domain.split('.').collect();
+            // Keep the top-level domain
+            let domain = &labels[..labels.len() - 1];
+            for word in words(local).chain(domain.iter().flat_map(|label| words(label))) {
+                self.learn_term(word);
+            }
+        }
+    }
+
+    /// Learn the texts of a memory: content, source, tags and metadata
+    pub fn learn_memory(&mut self, memory: &Memory) {
+        self.learn(&memory.content);
+        if let Some(source) = &memory.source {
+            self.learn(source);
+        }
+        for tag in &memory.tags {
+            self.learn(tag);
+        }
+        if let Some(metadata) = &memory.metadata {
+            self.learn_json(metadata);
+        }
+    }
+
+    /// Learn every string in a JSON value
+    pub fn learn_json(&mut self, value: &Value) {
+        match value {
+            Value::String(text) => self.learn(text),
+            Value::Array(items) => items.iter().for_each(|item| self.learn_json(item)),
+            Value::Object(fields) => fields.values().for_each(|field| self.learn_json(field)),
+            _ => {}
+        }
+    }
+
+    fn learn_term(&mut self, word: &str) {
+        let term = word.to_lowercase();
+        if term.chars().count() < MIN_TERM_CHARS
+            || term.chars().all(|c| c.is_ascii_digit())
+            || self.keep.contains(&term)
+            || self.terms.contains_key(&term)
+        {
+            return;
+        }
+        let mut attempt = 0u64;
+        let pseudonym = loop {
+            let candidate = pseudonym(&term, self.salt ^ attempt);
+            if candidate != term && !self.used.contains(&candidate) {
+                break candidate;
+            }
+            attempt += 1;
+        };
+        self.used.insert(pseudonym.clone());
+        self.terms.insert(term, pseudonym);
+    }
+
+    /// `text` with every learned term replaced, keeping the case of each
+    /// occurrence
+    pub fn text(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut word_start = None;
+        for (i, c) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+        {
+            if c.is_alphanumeric() && i < text.len() {
+                word_start.get_or_insert(i);
+                continue;
+            }
+            if let Some(start) = word_start.take() {
+                let word = &text[start..i];
+                match self.terms.get(&word.to_lowercase()) {
+                    Some(pseudonym) => out.push_str(&recase(pseudonym, word)),
+                    None => out.push_str(word),
+                }
+            }
+            if i < text.len() {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// A JSON value with every string anonymized; keys are kept
+    pub fn json(&self, value: &Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.text(text)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.json(v)).collect()),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), self.json(field)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// A memory with its texts anonymized. IDs, types, scores and times are
+    /// kept, so labelled queries still point at the right memories.
+    pub fn memory(&self, memory: &Memory) -> Memory {
+        let mut memory = memory.clone();
+        memory.content = self.text(&memory.content);
+        memory.source = memory.source.as_deref().map(|s| self.text(s));
+        memory.tags = memory.tags.iter().map(|t| self.text(t)).collect();
+        memory.metadata = memory.metadata.as_ref().map(|m| self.json(m));
+        for change in &mut memory.confidence.history {
+            change.reason = self.text(&change.reason);
+        }
+        memory
+    }
+
+    pub fn experience(&self, experience: &Experience) -> Experience {
+        let mut experience = experience.clone();
+        experience.title = self.text(&experience.title);
+        experience.context = self.text(&experience.context);
+        experience.summary = experience.summary.as_deref().map(|s| self.text(s));
+        experience
+    }
+
+    pub fn summary(&self, summary: &MemorySummary) -> MemorySummary {
+        let mut summary = summary.clone();
+        summary.summary_text = self.text(&summary.summary_text);
+        summary
+    }
+
+    pub fn query_log_entry(&self, entry: &QueryLogEntry) -> QueryLogEntry {
+        QueryLogEntry {
+            query: self.text(&entry.query),
+            ..entry.clone()
+        }
+    }
+}
+
+/// Alphanumeric runs of `text`
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// The email address in a whitespace-separated token, without surrounding
+/// punctuation
+fn email_in(token: &str) -> Option<&str> {
+    let email = token.trim_matches(|c: char| !c.is_alphanumeric());
+    let (local, domain) = email.split_once('@')?;
+    (!local.is_empty() && domain.contains('.') && !domain.contains('@')).then_some(email)
+}
+
+/// Lowercase pseudonym of the same shape as `term`: letters become ASCII
+/// letters, digits digits, anything else is kept
+fn pseudonym(term: &str, salt: u64) -> String {
+    let mut state = fnv1a(term.as_bytes(), FNV_OFFSET ^ salt) | 1;
+    let mut next = || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    term.chars()
+        .map(|c| {
+            if c.is_ascii_digit() {
+                char::from(b'0' + (next() % 10) as u8)
+            } else if c.is_alphabetic() {
+                char::from(b'a' + (next() % 26) as u8)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// `pseudonym` with the case pattern of `original`
+fn recase(pseudonym: &str, original: &str) -> String {
+    let mut cases = original.chars().map(char::is_uppercase);
+    pseudonym
+        .chars()
+        .map(|c| match cases.next() {
+            Some(true) => c.to_ascii_uppercase(),
+            _ => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_map_consistently_across_case_and_emails() {
+        let mut anonymizer = Anonymizer::new("salt").with_kept_term("Postgres");
+        let memory = Memory::new(
+            "Yesterday Alice Smith moved billing to Postgres. Mail alice.smith@acme.io for access.",
+            crate::types::MemoryType::Event,
+        );
+        anonymizer.learn_memory(&memory);
+        anonymizer.learn("what did alice decide about ACME?");
+
+        let content = anonymizer.memory(&memory).content;
+        let query = anonymizer.text("what did alice decide about ACME?");
+        let alice = &anonymizer.mapping()["alice"];
+
+        assert!(!content.contains("Alice") && !content.contains("acme"));
+        assert!(content.contains("Postgres"), "kept terms stay");
+        assert!(
+            content.ends_with(".io for access."),
+            "TLD and punctuation stay"
+        );
+        assert!(content.contains(&recase(alice, "Alice")));
+        assert!(content.contains(&format!("{alice}.")));
+        assert!(query.contains(&format!("what did {alice} decide")));
+        assert_eq!(
+            query.len(),
+            "what did alice decide about ACME?".len(),
+            "pseudonyms keep the shape"
+        );
+        assert!(query.ends_with(&format!("{}?", anonymizer.mapping()["acme"].to_uppercase())));
+
+        let again = {
+            let mut other = Anonymizer::new("salt");
+            // Mid-sentence, so "Alice" alone is still taken for a name
+            other.learn("ask Alice");
+            other.mapping()["alice"].clone()
+        };
+        assert_eq!(&again, alice, "same salt, same pseudonym");
+    }
+}
@@ -0,0 +1,154 @@
+//! Portable archives of a memory system
+//!
+//! [`MemorySystem::export_archive`] writes everything an agent knows into one
+//! file: memories (forgotten ones included), associations, experiences,
+//! summaries and the vectors of active memories. Unlike a
+//! [backup](crate::backup) it does not depend on the database or index
+//! layout, so it can be imported into another machine, build or namespace
+//! with [`MemorySystem::import_archive`], which rebuilds the text and vector
+//! indexes.
+//!
+//! The file is JSON lines: an [`ArchiveHeader`] first, then one
+//! [`ArchiveRecord`] per line.
+//!
+//! [`MemorySystem::export_archive`]: crate::MemorySystem::export_archive
+//! [`MemorySystem::import_archive`]: crate::MemorySystem::import_archive
+
+use crate::cortex::{Experience, MemorySummary};
+use crate::error::{MemoryError, Result};
+use crate::types::{Association, Memory, MemoryId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Format name in every archive header
+pub const ARCHIVE_FORMAT: &str = "goldfish-archive";
+
+/// Archive version written by this build
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// First line of an archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+    pub format: String,
+    pub version: u32,
+    /// Crate version that wrote the archive
+    pub goldfish_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Namespace the archive was exported from
+    pub namespace: String,
+}
+
+impl ArchiveHeader {
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            goldfish_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            namespace: namespace.into(),
+        }
+    }
+
+    /// Parse the first line of an archive, rejecting other files and newer
+    /// versions
+    pub fn parse(line: &str) -> Result<Self> {
+        let header: Self = serde_json::from_str(line)
+            .ok()
+            .filter(|h: &Self| h.format == ARCHIVE_FORMAT)
+            .ok_or_else(|| MemoryError::InvalidOperation("Not a goldfish archive".to_string()))?;
+        if header.version > ARCHIVE_VERSION {
+            return Err(MemoryError::Configuration(format!(
+                "Archive version {} was written by goldfish {} and is newer than this build supports ({})",
+                header.version, header.goldfish_version, ARCHIVE_VERSION
+            )));
+        }
+        Ok(header)
+    }
+}
+
+/// One line of an archive after the header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchiveRecord {
+    Memory(Box<Memory>),
+    Association(Association),
+    Experience(Experience),
+    Summary(MemorySummary),
+    /// Embedding of a memory. Imported as is when the target embeds its type
+    /// with the same provider and dimension, re-embedded otherwise.
+    Vector {
+        memory_id: MemoryId,
+        provider: String,
+        vector: Vec<f32>,
+    },
+}
+
+/// What [`MemorySystem::export_archive`](crate::MemorySystem::export_archive) wrote
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveReport {
+    pub memories: usize,
+    pub associations: usize,
+    pub experiences: usize,
+    pub summaries: usize,
+    pub vectors: usize,
+    pub bytes: u64,
+}
+
+/// What [`MemorySystem::import_archive`](crate::MemorySystem::import_archive) loaded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub memories: usize,
+    pub associations: usize,
+    pub experiences: usize,
+    pub summaries: usize,
+    /// Vectors taken from the archive
+    pub vectors_imported: usize,
+    /// Memories embedded again because the archived vector didn't fit
+    pub vectors_reembedded: usize,
+    /// Records whose ID already existed in the target
+    pub skipped: usize,
+    /// Memories added to the text index
+    pub reindexed: usize,
+}
+
+/// Write `value` as one JSON line
+pub(crate) async fn write_line<W, T>(out: &mut W, value: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line =
+        serde_json::to_vec(value).map_err(|e| MemoryError::Serialization(e.to_string()))?;
+    line.push(b'\n');
+    out.write_all(&line).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    #[test]
+    fn records_round_trip_and_newer_versions_are_refused() {
+        let memory = Memory::new("Deploys happen on Tuesdays", MemoryType::Fact);
+        let line = serde_json::to_string(&ArchiveRecord::Memory(Box::new(memory.clone()))).unwrap();
+        assert!(line.starts_with(r#"{"kind":"memory""#));
+        match serde_json::from_str(&line).unwrap() {
+            ArchiveRecord::Memory(loaded) => assert_eq!(*loaded, memory),
+            other => panic!("unexpected record {other:?}"),
+        }
+
+        let header = ArchiveHeader::new("default");
+        let line = serde_json::to_string(&header).unwrap();
+        assert_eq!(ArchiveHeader::parse(&line).unwrap().namespace, "default");
+
+        let newer = ArchiveHeader {
+            version: ARCHIVE_VERSION + 1,
+            ..header
+        };
+        assert!(ArchiveHeader::parse(&serde_json::to_string(&newer).unwrap()).is_err());
+        assert!(ArchiveHeader::parse("{}").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Online backups of a data directory
+//!
+//! [`MemorySystem::backup_to`] snapshots a running system into a fresh
+//! directory laid out like a data directory: the SQLite database is copied
+//! with `VACUUM INTO` (a consistent snapshot taken while writers continue),
+//! and the manifest and vector index directories are copied alongside it.
+//! The Tantivy index is left out: its segments are merged and deleted under
+//! a live writer, and [`MemorySystem::new`] rebuilds it from SQLite anyway.
+//! The backup can be opened directly with [`MemorySystem::new`], or loaded
+//! into a running system with [`MemorySystem::restore_from`].
+//!
+//! [`MemorySystem::backup_to`]: crate::MemorySystem::backup_to
+//! [`MemorySystem::new`]: crate::MemorySystem::new
+//! [`MemorySystem::restore_from`]: crate::MemorySystem::restore_from
+
+use crate::error::{MemoryError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Database file inside a data directory or backup
+pub const DATABASE_FILE: &str = "memories.db";
+
+/// Index directories copied next to the database, when present
+pub const INDEX_DIRS: &[&str] = &["vectors", "vectors_lancedb"];
+
+/// What [`MemorySystem::backup_to`](crate::MemorySystem::backup_to) wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupReport {
+    pub path: PathBuf,
+    /// Size of the database snapshot
+    pub database_bytes: u64,
+    /// Index directories and files copied besides the database
+    pub copied: Vec<String>,
+    /// Total size of the backup
+    pub total_bytes: u64,
+}
+
+/// What [`MemorySystem::restore_from`](crate::MemorySystem::restore_from) rebuilt
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    /// Active memories after the restore
+    pub memories: usize,
+    /// Memories re-added to the text index
+    pub reindexed: usize,
+    /// Memories re-embedded into the vector backends
+    pub vectors_upserted: usize,
+    /// Vectors of memories that are not in the backup
+    pub vectors_removed: usize,
+}
+
+/// Fail unless `path` is missing or an empty directory
+pub(crate) fn ensure_empty_target(path: &Path) -> Result<()> {
+    if path.exists() && std::fs::read_dir(path)?.next().is_some() {
+        return Err(MemoryError::InvalidOperation(format!(
+            "backup target {} is not empty",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Recursively copy `from` into `to`, returning the bytes copied
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> Result<u64> {
+    std::fs::create_dir_all(to)?;
+    let mut bytes = 0;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            bytes += copy_dir(&entry.path(), &target)?;
+        } else {
+            bytes += std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_nested_directories_into_an_empty_target() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(src.path().join("vectors/shard")).unwrap();
+        std::fs::write(src.path().join("vectors/a.bin"), b"abc").unwrap();
+        std::fs::write(src.path().join("vectors/shard/b.bin"), b"de").unwrap();
+
+        let dst = tempfile::tempdir().unwrap();
+        let target = dst.path().join("backup");
+        ensure_empty_target(&target).unwrap();
+        let bytes = copy_dir(&src.path().join("vectors"), &target.join("vectors")).unwrap();
+
+        assert_eq!(bytes, 5);
+        assert_eq!(
+            std::fs::read(target.join("vectors/shard/b.bin")).unwrap(),
+            b"de"
+        );
+        assert!(ensure_empty_target(&target).is_err());
+    }
+
+    #[tokio::test]
+    async fn backups_leave_out_the_text_index_and_rebuild_it_on_open() {
+        use crate::{Memory, MemorySystem, MemoryType};
+
+        let data = tempfile::tempdir().unwrap();
+        let system = MemorySystem::new(data.path()).await.unwrap();
+        let memory = Memory::new(
+            "The staging database rotates keys on Monday",
+            MemoryType::Fact,
+        );
+        system.save(&memory).await.unwrap();
+
+        let dst = tempfile::tempdir().unwrap();
+        let target = dst.path().join("backup");
+        let report = system.backup_to(&target).await.unwrap();
+        assert!(!report.copied.iter().any(|c| c == "tantivy_index"));
+        assert!(!target.join("tantivy_index").exists());
+
+        let restored = MemorySystem::new(&target).await.unwrap();
+        let hits = restored.search("staging").await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].memory.id, memory.id);
+    }
+}
@@ -4,9 +4,18 @@
 //! - Recall@1/3/5
 //! - MRR
 //! - nDCG@k
+//!
+//! `goldfish bench` runs these against a data directory (or a JSONL dataset)
+//! and can gate on minimum metrics or on regressions against an earlier report.
 
+use crate::cortex::MemoryCortex;
+use crate::error::{MemoryError, Result};
+use crate::types::MemoryType;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkQuery {
@@ -28,6 +37,16 @@ impl BenchmarkQuery {
     }
 }
 
+/// A memory record of a benchmark dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkMemory {
+    pub id: String,
+    pub content: String,
+    pub memory_type: MemoryType,
+    #[serde(default)]
+    pub importance: Option<f32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct QueryMetrics {
     pub query_id: String,
@@ -64,6 +83,149 @@ pub struct BenchmarkReport {
     pub per_query: Vec<QueryMetrics>,
 }
 
+impl RetrievalMetrics {
+    /// Names accepted by [`metric`](Self::metric) and [`MetricGate`]
+    pub const NAMES: [&'static str; 5] =
+        ["recall_at_1", "recall_at_3", "recall_at_5", "mrr", "ndcg"];
+
+    /// A quality metric by name (latencies are not gated)
+    pub fn metric(&self, name: &str) -> Option<f32> {
+        match name {
+            "recall_at_1" => Some(self.recall_at_1),
+            "recall_at_3" => Some(self.recall_at_3),
+            "recall_at_5" => Some(self.recall_at_5),
+            "mrr" => Some(self.mrr),
+            "ndcg" | "ndcg_at_k" => Some(self.ndcg_at_k),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum for one metric, parsed from `name=min` (e.g. `recall_at_5=0.8`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricGate {
+    pub metric: String,
+    pub min: f32,
+}
+
+impl std::str::FromStr for MetricGate {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (metric, min) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected METRIC=MIN, got '{s}'"))?;
+        let metric = metric.trim().to_string();
+        if RetrievalMetrics::default().metric(&metric).is_none() {
+            return Err(format!(
+                "unknown metric '{metric}', expected one of {}",
+                RetrievalMetrics::NAMES.join(", ")
+            ));
+        }
+        let min = min
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid minimum '{min}'"))?;
+        Ok(Self { metric, min })
+    }
+}
+
+/// Gates that `metrics` misses, described for the user
+pub fn failed_gates(metrics: &RetrievalMetrics, gates: &[MetricGate]) -> Vec<String> {
+    gates
+        .iter()
+        .filter_map(|gate| {
+            let value = metrics.metric(&gate.metric)?;
+            (value < gate.min).then(|| format!("{} {:.4} < {:.4}", gate.metric, value, gate.min))
+        })
+        .collect()
+}
+
+/// Metrics that dropped more than `tolerance` below `baseline`
+pub fn regressions(
+    baseline: &RetrievalMetrics,
+    current: &RetrievalMetrics,
+    tolerance: f32,
+) -> Vec<String> {
+    RetrievalMetrics::NAMES
+        .iter()
+        .filter_map(|name| {
+            let before = baseline.metric(name)?;
+            let now = current.metric(name)?;
+            (now < before - tolerance).then(|| format!("{name} {before:.4} -> {now:.4}"))
+        })
+        .collect()
+}
+
+/// Read a JSONL file, skipping blank lines and `#` comments
+pub fn load_jsonl<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let text = std::fs::read_to_string(path)?;
+    let mut values = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let value = serde_json::from_str(line).map_err(|e| {
+            MemoryError::Validation(format!("{} line {}: {e}", path.display(), i + 1))
+        })?;
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// Recall every query once and score the results
+pub async fn run_queries(
+    cortex: &MemoryCortex,
+    queries: &[BenchmarkQuery],
+    top_k: usize,
+    ndcg_k: usize,
+) -> Result<Vec<QueryMetrics>> {
+    let mut per_query = Vec::with_capacity(queries.len());
+    for q in queries {
+        let start = Instant::now();
+        let hits = cortex.recall(&q.query, top_k).await?;
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+        let retrieved_ids = hits.into_iter().map(|h| h.memory.id).collect();
+        per_query.push(evaluate_query(
+            q.query_id.clone(),
+            retrieved_ids,
+            q.relevance_map(),
+            latency_ms,
+            ndcg_k,
+        ));
+    }
+    Ok(per_query)
+}
+
+/// Markdown table of one or more reports, one row each
+pub fn render_markdown(reports: &[BenchmarkReport]) -> String {
+    let mut out = String::from("# Goldfish Benchmark Report\n\n");
+    if let Some(first) = reports.first() {
+        out.push_str(&format!(
+            "- Generated: `{}`\n- Dataset: `{}`\n- Backend: `{}`\n- Queries: {}\n\n",
+            first.generated_at_utc, first.dataset, first.backend, first.metrics.evaluated_queries
+        ));
+    }
+    out.push_str("| Suite | Recall@1 | Recall@3 | Recall@5 | MRR | nDCG | Avg (ms) | P95 (ms) |\n");
+    out.push_str("|---|---:|---:|---:|---:|---:|---:|---:|\n");
+    for report in reports {
+        let m = &report.metrics;
+        out.push_str(&format!(
+            "| {} | {:.4} | {:.4} | {:.4} | {:.4} | {:.4} | {:.2} | {:.2} |\n",
+            report.suite_name,
+            m.recall_at_1,
+            m.recall_at_3,
+            m.recall_at_5,
+            m.mrr,
+            m.ndcg_at_k,
+            m.avg_latency_ms,
+            m.p95_latency_ms
+        ));
+    }
+    out
+}
+
 pub fn evaluate_query(
     query_id: impl Into<String>,
     retrieved_ids: Vec<String>,
@@ -211,6 +373,29 @@ mod tests {
         assert!((q.ndcg_at_k - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn gates_and_regressions_name_the_failing_metrics() {
+        let gate: MetricGate = "recall_at_5=0.8".parse().unwrap();
+        assert_eq!(gate.min, 0.8);
+        assert!("latency=3".parse::<MetricGate>().is_err());
+        assert!("mrr".parse::<MetricGate>().is_err());
+
+        let baseline = RetrievalMetrics {
+            recall_at_5: 0.9,
+            mrr: 0.7,
+            ..Default::default()
+        };
+        let current = RetrievalMetrics {
+            recall_at_5: 0.75,
+            mrr: 0.69,
+            ..Default::default()
+        };
+        assert_eq!(failed_gates(&current, &[gate]).len(), 1);
+        let dropped = regressions(&baseline, &current, 0.02);
+        assert_eq!(dropped.len(), 1);
+        assert!(dropped[0].starts_with("recall_at_5"));
+    }
+
     #[test]
     fn metrics_no_hits() {
         let retrieved = vec!["x".to_string(), "y".to_string()];
@@ -0,0 +1,507 @@
+//! Slack and Discord ingestion
+//!
+//! [`ChatConnector`] is a [`Connector`] that reads new messages from configured
+//! Slack or Discord channels. Each message becomes an `Event` memory (or
+//! another type set with [`ChatConnector::with_memory_type`]) with the author,
+//! channel and message ID in its metadata. Messages of one thread share a
+//! session ID, `slack:<channel>:<thread>`, and unthreaded messages use
+//! `slack:<channel>` (likewise `discord:`).
+//!
+//! The checkpoint keeps, per channel, the newest message seen and the IDs of
+//! recent messages, so messages are never ingested twice. Slack thread replies
+//! are not part of the channel history: threads seen recently are tracked and
+//! polled for new replies.
+//!
+//! Needs the `chat-connectors` feature.
+//!
+//! ```rust,ignore
+//! let slack = ChatConnector::slack(std::env::var("SLACK_TOKEN")?, ["C024BE91L"]);
+//! let handle = ConnectorScheduler::new(memory.clone()).with_connector(slack).spawn();
+//! ```
+
+use crate::connectors::{fetch, Connector, ConnectorBatch};
+use crate::error::{MemoryError, Result};
+use crate::types::{CreateMemoryInput, MemoryType};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+/// Message IDs remembered per channel for deduplication
+const SEEN_PER_CHANNEL: usize = 500;
+
+/// Slack threads polled for new replies, per channel
+const TRACKED_THREADS: usize = 50;
+
+/// Where the messages come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    Slack,
+    Discord,
+}
+
+impl std::fmt::Display for ChatPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChatPlatform::Slack => write!(f, "slack"),
+            ChatPlatform::Discord => write!(f, "discord"),
+        }
+    }
+}
+
+/// A message read from a channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Slack `ts` or Discord snowflake
+    pub id: String,
+    pub channel: String,
+    pub author: String,
+    pub text: String,
+    /// Slack `thread_ts`, or the message a Discord reply refers to
+    pub thread: Option<String>,
+    /// The message starts a Slack thread with replies
+    pub has_replies: bool,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl ChatMessage {
+    /// Not a reply inside a thread
+    fn is_top_level(&self) -> bool {
+        self.thread.as_ref().is_none_or(|t| *t == self.id)
+    }
+}
+
+/// Per-channel progress stored as the connector checkpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatCheckpoint {
+    pub channels: HashMap<String, ChannelCursor>,
+}
+
+/// Progress in one channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelCursor {
+    /// Newest top-level message ingested
+    pub latest: Option<String>,
+    /// Recently ingested message IDs
+    pub seen: VecDeque<String>,
+    /// Slack threads and their newest reply, most recently active last
+    pub threads: VecDeque<(String, String)>,
+}
+
+impl ChannelCursor {
+    fn remember(&mut self, message: &ChatMessage, platform: ChatPlatform) {
+        self.seen.push_back(message.id.clone());
+        while self.seen.len() > SEEN_PER_CHANNEL {
+            self.seen.pop_front();
+        }
+
+        let advances = self
+            .latest
+            .as_ref()
+            .is_none_or(|latest| is_after(&message.id, latest));
+        if advances && (platform == ChatPlatform::Discord || message.is_top_level()) {
+            self.latest = Some(message.id.clone());
+        }
+
+        if platform == ChatPlatform::Slack && (message.has_replies || message.thread.is_some()) {
+            let thread = message.thread.clone().unwrap_or_else(|| message.id.clone());
+            let newest = match self.threads.iter().position(|(t, _)| *t == thread) {
+                Some(i) => {
+                    let (_, reply) = self.threads.remove(i).unwrap_or_default();
+                    if is_after(&message.id, &reply) {
+                        message.id.clone()
+                    } else {
+                        reply
+                    }
+                }
+                None => message.id.clone(),
+            };
+            self.threads.push_back((thread, newest));
+            while self.threads.len() > TRACKED_THREADS {
+                self.threads.pop_front();
+            }
+        }
+    }
+}
+
+/// Whether message ID `a` is newer than `b`. Slack `ts` values ("1700000000.000100")
+/// and Discord snowflakes both order by their integer parts, then fractions.
+fn is_after(a: &str, b: &str) -> bool {
+    fn key(id: &str) -> (u64, u64) {
+        let (whole, fraction) = id.split_once('.').unwrap_or((id, "0"));
+        (whole.parse().unwrap_or(0), fraction.parse().unwrap_or(0))
+    }
+    key(a) > key(b)
+}
+
+/// Polls Slack or Discord channels for new messages
+#[derive(Debug, Clone)]
+pub struct ChatConnector {
+    name: String,
+    platform: ChatPlatform,
+    token: String,
+    channels: Vec<String>,
+    api_base: String,
+    interval: Duration,
+    memory_type: MemoryType,
+    timeout: Duration,
+}
+
+impl ChatConnector {
+    /// Read Slack channels with a bot token (`xoxb-...`) that has `channels:history`
+    pub fn slack(
+        token: impl Into<String>,
+        channels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self::new(
+            ChatPlatform::Slack,
+            "https://slack.com/api",
+            token,
+            channels,
+        )
+    }
+
+    /// Read Discord channels with a bot token that can read message history
+    pub fn discord(
+        token: impl Into<String>,
+        channels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self::new(
+            ChatPlatform::Discord,
+            "https://discord.com/api/v10",
+            token,
+            channels,
+        )
+    }
+
+    fn new(
+        platform: ChatPlatform,
+        api_base: &str,
+        token: impl Into<String>,
+        channels: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: platform.to_string(),
+            platform,
+            token: token.into(),
+            channels: channels.into_iter().map(Into::into).collect(),
+            api_base: api_base.to_string(),
+            interval: Duration::from_secs(60),
+            memory_type: MemoryType::Event,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Checkpoint key; set it when running several connectors on one platform
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Base URL of the API, e.g. for a proxy
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into();
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Type of the memories, `Event` by default (`Observation` also fits)
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_type = memory_type;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Turn messages into memories, oldest first, skipping messages already in
+    /// `checkpoint` and advancing it
+    pub fn batch(
+        &self,
+        mut messages: Vec<ChatMessage>,
+        checkpoint: &mut ChatCheckpoint,
+    ) -> Vec<CreateMemoryInput> {
+        messages.sort_by(|a, b| {
+            if is_after(&a.id, &b.id) {
+                std::cmp::Ordering::Greater
+            } else if is_after(&b.id, &a.id) {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        });
+
+        let mut items = Vec::new();
+        for message in messages {
+            let cursor = checkpoint
+                .channels
+                .entry(message.channel.clone())
+                .or_default();
+            if cursor.seen.contains(&message.id) {
+                continue;
+            }
+            cursor.remember(&message, self.platform);
+            if message.text.trim().is_empty() {
+                continue;
+            }
+
+            let session = match &message.thread {
+                Some(thread) => format!("{}:{}:{}", self.platform, message.channel, thread),
+                None => format!("{}:{}", self.platform, message.channel),
+            };
+            items.push(
+                CreateMemoryInput::new(message.text.clone(), self.memory_type)
+                    .with_source(format!("{}:{}", self.platform, message.channel))
+                    .with_session_id(session)
+                    .with_metadata(serde_json::json!({
+                        "connector": self.name,
+                        "platform": self.platform,
+                        "channel": message.channel,
+                        "author": message.author,
+                        "message_id": message.id,
+                        "thread": message.thread,
+                        "sent_at": message.sent_at,
+                    })),
+            );
+        }
+        items
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        let auth = match self.platform {
+            ChatPlatform::Slack => format!("Bearer {}", self.token),
+            ChatPlatform::Discord => format!("Bot {}", self.token),
+        };
+        vec![("Authorization".to_string(), auth)]
+    }
+
+    async fn get(&self, url: &str) -> Result<serde_json::Value> {
+        let body = fetch(url, &self.headers(), self.timeout).await?;
+        serde_json::from_str(&body)
+            .map_err(|e| MemoryError::Connector(format!("{}: invalid JSON: {e}", self.name)))
+    }
+
+    async fn poll_slack(
+        &self,
+        channel: &str,
+        cursor: Option<&ChannelCursor>,
+    ) -> Result<Vec<ChatMessage>> {
+        let mut url = format!(
+            "{}/conversations.history?channel={channel}&limit=200",
+            self.api_base
+        );
+        if let Some(latest) = cursor.and_then(|c| c.latest.as_ref()) {
+            url.push_str(&format!("&oldest={latest}"));
+        }
+        let mut messages = parse_slack_messages(channel, &self.get(&url).await?)?;
+
+        let mut threads: Vec<(String, Option<String>)> = cursor
+            .map(|c| {
+                c.threads
+                    .iter()
+                    .map(|(t, reply)| (t.clone(), Some(reply.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for message in &messages {
+            if message.has_replies && !threads.iter().any(|(t, _)| *t == message.id) {
+                threads.push((message.id.clone(), None));
+            }
+        }
+
+        for (thread, newest) in threads {
+            let mut url = format!(
+                "{}/conversations.replies?channel={channel}&ts={thread}&limit=200",
+                self.api_base
+            );
+            if let Some(newest) = newest {
+                url.push_str(&format!("&oldest={newest}"));
+            }
+            messages.extend(parse_slack_messages(channel, &self.get(&url).await?)?);
+        }
+        Ok(messages)
+    }
+
+    async fn poll_discord(
+        &self,
+        channel: &str,
+        cursor: Option<&ChannelCursor>,
+    ) -> Result<Vec<ChatMessage>> {
+        let mut url = format!("{}/channels/{channel}/messages?limit=100", self.api_base);
+        if let Some(latest) = cursor.and_then(|c| c.latest.as_ref()) {
+            url.push_str(&format!("&after={latest}"));
+        }
+        parse_discord_messages(channel, &self.get(&url).await?)
+    }
+}
+
+#[async_trait]
+impl Connector for ChatConnector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch> {
+        let mut state: ChatCheckpoint = checkpoint
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_default();
+
+        let mut messages = Vec::new();
+        for channel in &self.channels {
+            let cursor = state.channels.get(channel);
+            messages.extend(match self.platform {
+                ChatPlatform::Slack => self.poll_slack(channel, cursor).await?,
+                ChatPlatform::Discord => self.poll_discord(channel, cursor).await?,
+            });
+        }
+
+        let items = self.batch(messages, &mut state);
+        Ok(ConnectorBatch {
+            items,
+            checkpoint: serde_json::to_string(&state).ok(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Messages of a Slack `conversations.history` or `conversations.replies`
+/// response. Join/leave notices and other system messages are skipped.
+pub fn parse_slack_messages(channel: &str, body: &serde_json::Value) -> Result<Vec<ChatMessage>> {
+    if body["ok"].as_bool() != Some(true) {
+        return Err(MemoryError::Connector(format!(
+            "Slack: {}",
+            body["error"].as_str().unwrap_or("request failed")
+        )));
+    }
+
+    let Some(messages) = body["messages"].as_array() else {
+        return Ok(Vec::new());
+    };
+    Ok(messages
+        .iter()
+        .filter(|m| {
+            matches!(
+                m["subtype"].as_str(),
+                None | Some("thread_broadcast") | Some("bot_message")
+            )
+        })
+        .filter_map(|m| {
+            let id = m["ts"].as_str()?.to_string();
+            let seconds = id.split('.').next()?.parse::<i64>().ok()?;
+            Some(ChatMessage {
+                channel: channel.to_string(),
+                author: ["user", "username", "bot_id"]
+                    .iter()
+                    .find_map(|k| m[*k].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                text: m["text"].as_str().unwrap_or_default().to_string(),
+                thread: m["thread_ts"].as_str().map(str::to_string),
+                has_replies: m["reply_count"].as_u64().unwrap_or(0) > 0,
+                sent_at: Utc.timestamp_opt(seconds, 0).single(),
+                id,
+            })
+        })
+        .collect())
+}
+
+/// Messages of a Discord `GET /channels/{id}/messages` response. Only plain
+/// messages and replies are kept.
+pub fn parse_discord_messages(channel: &str, body: &serde_json::Value) -> Result<Vec<ChatMessage>> {
+    let messages = body.as_array().ok_or_else(|| {
+        MemoryError::Connector(format!(
+            "Discord: {}",
+            body["message"].as_str().unwrap_or("unexpected response")
+        ))
+    })?;
+
+    let mut seen = HashSet::new();
+    Ok(messages
+        .iter()
+        .filter(|m| matches!(m["type"].as_u64(), Some(0) | Some(19)))
+        .filter_map(|m| {
+            let id = m["id"].as_str()?.to_string();
+            if !seen.insert(id.clone()) {
+                return None;
+            }
+            Some(ChatMessage {
+                channel: channel.to_string(),
+                author: m["author"]["username"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                text: m["content"].as_str().unwrap_or_default().to_string(),
+                thread: m["message_reference"]["message_id"]
+                    .as_str()
+                    .map(str::to_string),
+                has_replies: false,
+                sent_at: m["timestamp"]
+                    .as_str()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc)),
+                id,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_discord_messages, parse_slack_messages, ChatCheckpoint, ChatConnector};
+    use serde_json::json;
+
+    #[test]
+    fn slack_and_discord_messages_dedup_and_group_by_thread() {
+        let history = json!({"ok": true, "messages": [
+            {"ts": "1700000002.000200", "user": "U2", "text": "Rolled back",
+             "thread_ts": "1700000001.000100"},
+            {"ts": "1700000001.000100", "user": "U1", "text": "Deploy failed",
+             "thread_ts": "1700000001.000100", "reply_count": 1},
+            {"ts": "1700000000.000050", "subtype": "channel_join", "text": "joined"},
+        ]});
+        let messages = parse_slack_messages("C1", &history).unwrap();
+        assert_eq!(messages.len(), 2);
+
+        let slack = ChatConnector::slack("xoxb", ["C1"]);
+        let mut checkpoint = ChatCheckpoint::default();
+        let items = slack.batch(messages.clone(), &mut checkpoint);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "Deploy failed");
+        assert_eq!(
+            items[1].session_id.as_deref(),
+            Some("slack:C1:1700000001.000100")
+        );
+        let cursor = &checkpoint.channels["C1"];
+        assert_eq!(cursor.latest.as_deref(), Some("1700000001.000100"));
+        assert_eq!(cursor.threads.len(), 1);
+        assert!(slack.batch(messages, &mut checkpoint).is_empty());
+
+        let body = json!([
+            {"id": "1002", "type": 19, "content": "Same here", "author": {"username": "bo"},
+             "message_reference": {"message_id": "1001"}},
+            {"id": "1001", "type": 0, "content": "CI is red", "author": {"username": "al"},
+             "timestamp": "2026-01-02T03:04:05+00:00"},
+            {"id": "1000", "type": 7, "content": "", "author": {"username": "al"}},
+        ]);
+        let messages = parse_discord_messages("D1", &body).unwrap();
+        let discord = ChatConnector::discord("token", ["D1"]);
+        let mut checkpoint = ChatCheckpoint::default();
+        let items = discord.batch(messages, &mut checkpoint);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].metadata.as_ref().unwrap()["author"], "al");
+        assert_eq!(items[1].session_id.as_deref(), Some("discord:D1:1001"));
+        assert_eq!(checkpoint.channels["D1"].latest.as_deref(), Some("1002"));
+    }
+}
@@ -0,0 +1,367 @@
+//! Automatic memory type classification
+//!
+//! Agents tend to store everything as [`MemoryType::Fact`]. When a
+//! [`CreateMemoryInput`](crate::types::CreateMemoryInput) leaves `memory_type`
+//! unspecified, a [`MemoryClassifier`] picks a type and an initial importance.
+//! The result (and its confidence) is recorded in the memory's metadata under
+//! the `classification` key.
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::Result;
+use crate::types::MemoryType;
+use crate::vector_search::cosine_similarity;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Result of classifying a piece of content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Classification {
+    /// Assigned memory type
+    pub memory_type: MemoryType,
+    /// Initial importance (0.0 - 1.0)
+    pub importance: f32,
+    /// How sure the classifier is (0.0 - 1.0)
+    pub confidence: f32,
+    /// Name of the classifier that produced this result
+    pub classifier: String,
+}
+
+impl Classification {
+    /// Metadata entry recorded on classified memories
+    pub fn to_metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "memory_type": self.memory_type,
+            "confidence": self.confidence,
+            "classifier": self.classifier,
+        })
+    }
+}
+
+/// Assigns a memory type to raw content.
+///
+/// Implement this to plug in an LLM-backed classifier.
+#[async_trait]
+pub trait MemoryClassifier: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn classify(&self, content: &str) -> Result<Classification>;
+}
+
+/// Keyword/phrase rules for one memory type
+#[derive(Debug, Clone)]
+pub struct ClassificationRule {
+    pub memory_type: MemoryType,
+    pub patterns: Vec<String>,
+}
+
+impl ClassificationRule {
+    pub fn new(memory_type: MemoryType, patterns: &[&str]) -> Self {
+        Self {
+            memory_type,
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+/// Zero-config classifier based on keyword and phrase matching.
+///
+/// Single-word patterns match whole tokens; multi-word patterns match as
+/// substrings of the lowercased content. Content with no matches falls back to
+/// [`MemoryType::Fact`] with low confidence.
+#[derive(Debug, Clone)]
+pub struct RuleBasedClassifier {
+    rules: Vec<ClassificationRule>,
+}
+
+impl Default for RuleBasedClassifier {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                ClassificationRule::new(
+                    MemoryType::Identity,
+                    &[
+                        "my name is",
+                        "i am a",
+                        "i'm a",
+                        "i work as",
+                        "i was born",
+                        "call me",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Preference,
+                    &[
+                        "prefer",
+                        "prefers",
+                        "like",
+                        "likes",
+                        "love",
+                        "loves",
+                        "hate",
+                        "hates",
+                        "favorite",
+                        "favourite",
+                        "dislike",
+                        "dislikes",
+                        "rather",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Goal,
+                    &[
+                        "goal",
+                        "want to",
+                        "wants to",
+                        "aim to",
+                        "plan to",
+                        "hope to",
+                        "objective",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Todo,
+                    &[
+                        "todo",
+                        "need to",
+                        "needs to",
+                        "must",
+                        "remember to",
+                        "don't forget",
+                        "deadline",
+                        "due",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Decision,
+                    &[
+                        "decided",
+                        "decision",
+                        "chose",
+                        "going with",
+                        "settled on",
+                        "agreed",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Event,
+                    &[
+                        "yesterday",
+                        "today",
+                        "happened",
+                        "meeting",
+                        "met",
+                        "attended",
+                        "last week",
+                        "this morning",
+                    ],
+                ),
+                ClassificationRule::new(
+                    MemoryType::Observation,
+                    &[
+                        "noticed", "seems", "appears", "tends to", "usually", "often",
+                    ],
+                ),
+            ],
+        }
+    }
+}
+
+impl RuleBasedClassifier {
+    pub fn new(rules: Vec<ClassificationRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Classify without going through the async trait
+    pub fn classify_sync(&self, content: &str) -> Classification {
+        let lower = content.to_lowercase();
+        let tokens: HashSet<&str> = lower
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let mut best: Option<(MemoryType, usize)> = None;
+        let mut total = 0usize;
+        for rule in &self.rules {
+            let hits = rule
+                .patterns
+                .iter()
+                .filter(|p| {
+                    if p.contains(' ') {
+                        lower.contains(p.as_str())
+                    } else {
+                        tokens.contains(p.as_str())
+                    }
+                })
+                .count();
+            total += hits;
+            let better = match best {
+                Some((_, b)) => hits > b,
+                None => true,
+            };
+            if hits > 0 && better {
+                best = Some((rule.memory_type, hits));
+            }
+        }
+
+        let (memory_type, confidence) = match best {
+            // Confidence grows with the share of matches won and with the number of hits.
+            Some((memory_type, hits)) => {
+                let share = hits as f32 / total as f32;
+                let strength = (hits.min(3) as f32) / 3.0;
+                (memory_type, (0.4 + 0.6 * share * strength).min(0.95))
+            }
+            None => (MemoryType::Fact, 0.3),
+        };
+
+        Classification {
+            memory_type,
+            importance: memory_type.default_importance(),
+            confidence,
+            classifier: self.name().to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl MemoryClassifier for RuleBasedClassifier {
+    fn name(&self) -> &'static str {
+        "rules"
+    }
+
+    async fn classify(&self, content: &str) -> Result<Classification> {
+        Ok(self.classify_sync(content))
+    }
+}
+
+/// Nearest-prototype classifier using an embedding provider.
+///
+/// Each memory type is described by one or more example sentences; content is
+/// assigned to the type whose examples it is most similar to. If the best
+/// similarity is below `min_similarity` the rule-based classifier is used instead.
+pub struct EmbeddingClassifier {
+    embedder: Arc<dyn EmbeddingProvider>,
+    examples: Vec<(MemoryType, String)>,
+    min_similarity: f32,
+    fallback: RuleBasedClassifier,
+}
+
+impl EmbeddingClassifier {
+    pub fn new(embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        let examples = [
+            (
+                MemoryType::Identity,
+                "My name is Sam and I am a software engineer",
+            ),
+            (
+                MemoryType::Preference,
+                "I prefer dark mode and like concise answers",
+            ),
+            (
+                MemoryType::Goal,
+                "I want to learn Rust and ship the project this year",
+            ),
+            (
+                MemoryType::Todo,
+                "Need to send the report to the team by Friday",
+            ),
+            (
+                MemoryType::Decision,
+                "We decided to use SQLite for the storage layer",
+            ),
+            (
+                MemoryType::Event,
+                "Yesterday we had a meeting with the customer",
+            ),
+            (
+                MemoryType::Observation,
+                "The user usually asks questions in the morning",
+            ),
+            (MemoryType::Fact, "Paris is the capital of France"),
+        ];
+        Self {
+            embedder,
+            examples: examples
+                .into_iter()
+                .map(|(t, s)| (t, s.to_string()))
+                .collect(),
+            min_similarity: 0.2,
+            fallback: RuleBasedClassifier::default(),
+        }
+    }
+
+    /// Replace the example sentences used as type prototypes
+    pub fn with_examples(mut self, examples: Vec<(MemoryType, String)>) -> Self {
+        self.examples = examples;
+        self
+    }
+
+    /// Minimum similarity required before trusting the embedding match
+    pub fn with_min_similarity(mut self, min_similarity: f32) -> Self {
+        self.min_similarity = min_similarity;
+        self
+    }
+}
+
+#[async_trait]
+impl MemoryClassifier for EmbeddingClassifier {
+    fn name(&self) -> &'static str {
+        "embedding"
+    }
+
+    async fn classify(&self, content: &str) -> Result<Classification> {
+        let mut texts = vec![content.to_string()];
+        texts.extend(self.examples.iter().map(|(_, s)| s.clone()));
+        let vectors = self.embedder.embed(&texts).await?;
+
+        let Some((query, prototypes)) = vectors.split_first() else {
+            return Ok(self.fallback.classify_sync(content));
+        };
+
+        let best = self
+            .examples
+            .iter()
+            .zip(prototypes)
+            .map(|((t, _), v)| (*t, cosine_similarity(query, v)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        match best {
+            Some((memory_type, similarity)) if similarity >= self.min_similarity => {
+                Ok(Classification {
+                    memory_type,
+                    importance: memory_type.default_importance(),
+                    confidence: similarity.clamp(0.0, 1.0),
+                    classifier: self.name().to_string(),
+                })
+            }
+            _ => Ok(self.fallback.classify_sync(content)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_pick_obvious_types() {
+        let classifier = RuleBasedClassifier::default();
+
+        let c = classifier.classify_sync("I prefer tea over coffee");
+        assert_eq!(c.memory_type, MemoryType::Preference);
+
+        let c = classifier.classify_sync("We decided to go with Postgres");
+        assert_eq!(c.memory_type, MemoryType::Decision);
+
+        let c = classifier.classify_sync("Remember to renew the passport, deadline is May");
+        assert_eq!(c.memory_type, MemoryType::Todo);
+    }
+
+    #[test]
+    fn unmatched_content_falls_back_to_fact() {
+        let c = RuleBasedClassifier::default().classify_sync("Water boils at 100 degrees");
+        assert_eq!(c.memory_type, MemoryType::Fact);
+        assert!(c.confidence < 0.5);
+    }
+}
@@ -3,20 +3,49 @@
 //! Usage:
 //!   goldfish init                    Initialize a new project
 //!   goldfish add "content"           Add a new memory
+//!   goldfish add --stdin --jsonl < memories.jsonl
+//!                                    Add one memory per JSON line
 //!   goldfish search "query"          Search memories
 //!   goldfish list                    List all memories
 //!   goldfish get <id>                Show memory details
 //!   goldfish delete <id>             Delete a memory
+//!   goldfish forget --type observation --older-than 90d
+//!                                    Forget every matching memory
 //!   goldfish update <id>             Update a memory
+//!   goldfish pin <id>                Pin a memory (never pruned)
+//!   goldfish unpin <id>              Unpin a memory
 //!   goldfish associate               Create an association
 //!   goldfish stats                   Show statistics
 //!   goldfish maintenance             Run maintenance tasks
+//!   goldfish doctor --report         Memory hygiene report with suggested fixes
+//!   goldfish slow-queries --p95      Slowest and emptiest logged queries
 //!   goldfish export --format json    Export memories
 //!   goldfish import --format json    Import memories
+//!   goldfish history <id>            Show a memory's versions
+//!   goldfish ids <fragment>          Full IDs for a partial ID or content
+//!   goldfish completions bash        Print a shell completion script
+//!   goldfish repl                    Interactive shell over one data directory
+//!   goldfish bench --queries q.jsonl Retrieval quality of the data directory
+//!
+//! Commands taking a memory ID also accept a unique ID prefix or a content
+//! fragment matching a single memory.
+//!
+//! `--remote http://host:3000 --api-key <key>` runs `add`, `search`, `list`,
+//! `get`, `delete`, `pin`, `unpin` and `stats` against a goldfish-server
+//! instead of the data directory (needs the `remote` feature).
+//!
+//! `goldfish --output json <command>` makes `search`, `list`, `get`, `stats`,
+//! `history`, `doctor` and `timeline` print JSON instead of text.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use colored::*;
-use goldfish::{Memory, MemorySystem, MemoryType, RelationType, TemporalQuery};
+use goldfish::{
+    aggregate_metrics, normalize_language, BenchmarkMemory, BenchmarkQuery, BenchmarkReport,
+    ContextWindow, CreateMemoryInput, Memory, MemoryCortex, MemoryQuery, MemorySearchResult,
+    MemorySystem, MemoryType, RecallWeights, RelationType, SampleFilter, StoreAnalytics,
+    TemporalQuery,
+};
+use serde::Serialize;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -32,6 +61,18 @@ struct Cli {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Output format of commands that print data (before the subcommand)
+    #[arg(long, value_enum, default_value = "text")]
+    output: OutputFormat,
+
+    /// Run against a goldfish-server at this URL instead of the data directory
+    #[arg(long, env = "GOLDFISH_REMOTE")]
+    remote: Option<String>,
+
+    /// API key for --remote
+    #[arg(long, env = "GOLDFISH_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -48,7 +89,22 @@ enum Commands {
     /// Add a new memory
     Add {
         /// Memory content
-        content: String,
+        #[arg(required_unless_present = "stdin")]
+        content: Option<String>,
+
+        /// Read the content from stdin
+        #[arg(long, conflicts_with = "content")]
+        stdin: bool,
+
+        /// With --stdin, read one memory record per line, e.g.
+        /// {"content": "...", "memory_type": "fact", "importance": 0.7};
+        /// records without a memory_type are classified
+        #[arg(long, requires = "stdin")]
+        jsonl: bool,
+
+        /// Records saved per batch with --jsonl
+        #[arg(long, default_value = "100")]
+        batch_size: usize,
 
         /// Memory type
         #[arg(short, long, value_enum, default_value = "fact")]
@@ -73,7 +129,7 @@ enum Commands {
         memory_type: Option<CliMemoryType>,
 
         /// Minimum confidence
-        #[arg(short, long)]
+        #[arg(long)]
         min_confidence: Option<f32>,
 
         /// Maximum results
@@ -83,6 +139,10 @@ enum Commands {
         /// Temporal filter (today, yesterday, last_week, etc.)
         #[arg(short, long)]
         temporal: Option<String>,
+
+        /// Only memories in this language (ISO 639-1, e.g. de)
+        #[arg(long)]
+        language: Option<String>,
     },
 
     /// List all memories
@@ -102,6 +162,30 @@ enum Commands {
         /// Show forgotten memories
         #[arg(long)]
         include_forgotten: bool,
+
+        /// Continue after this cursor (printed at the end of the previous page).
+        /// Type-filtered listings are not paged.
+        #[arg(long, conflicts_with = "memory_type")]
+        cursor: Option<String>,
+    },
+
+    /// Show a random sample of memories for review
+    Sample {
+        /// Sample size
+        #[arg(short = 'n', long, default_value = "20")]
+        count: usize,
+
+        /// Filter by memory type
+        #[arg(short, long, value_enum)]
+        memory_type: Option<CliMemoryType>,
+
+        /// Stratify by memory type so every type is represented
+        #[arg(short, long)]
+        stratified: bool,
+
+        /// Include forgotten memories
+        #[arg(long)]
+        include_forgotten: bool,
     },
 
     /// Get/show memory details
@@ -128,6 +212,37 @@ enum Commands {
         permanent: bool,
     },
 
+    /// Forget (soft delete) every memory matching the filters
+    Forget {
+        /// Only memories of this type
+        #[arg(long = "type", value_enum)]
+        memory_type: Vec<CliMemoryType>,
+
+        /// Only memories created longer ago than this (e.g. 90d, 12h, 2w)
+        #[arg(long)]
+        older_than: Option<String>,
+
+        /// Only memories with every one of these tags
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Only memories with importance at most this (0.0-1.0)
+        #[arg(long)]
+        max_importance: Option<f32>,
+
+        /// Forget matching pinned memories too (they are left alone by default)
+        #[arg(long)]
+        include_pinned: bool,
+
+        /// Only show how many memories would be forgotten
+        #[arg(short, long)]
+        dry_run: bool,
+
+        /// Forget without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+
     /// Update a memory
     Update {
         /// Memory ID
@@ -142,6 +257,18 @@ enum Commands {
         importance: Option<f32>,
     },
 
+    /// Pin a memory so maintenance never prunes or consolidates it
+    Pin {
+        /// Memory ID
+        id: String,
+    },
+
+    /// Remove a memory's pin
+    Unpin {
+        /// Memory ID
+        id: String,
+    },
+
     /// Create association between memories
     Associate {
         /// Source memory ID
@@ -169,6 +296,147 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Check memory hygiene: duplicates, contradictions, stale and huge memories
+    Doctor {
+        /// List every issue with its suggested fix, not just the counts
+        #[arg(short, long)]
+        report: bool,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Full memory IDs for a partial ID or a content fragment
+    Ids {
+        /// ID prefix or words from the content
+        query: String,
+
+        /// Maximum matches
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Print a shell completion script, e.g. `goldfish completions zsh > _goldfish`
+    Completions { shell: clap_complete::Shell },
+
+    /// Interactive shell for add/search/recall/context/link with live pulses
+    Repl,
+
+    /// Measure retrieval quality (recall, MRR, nDCG, latency) on labelled queries
+    Bench {
+        /// Queries JSONL, one `{"query_id", "query", "relevant_ids"}` per line
+        #[arg(long)]
+        queries: PathBuf,
+
+        /// Memories JSONL to benchmark instead of a snapshot of the data directory
+        #[arg(long)]
+        memories: Option<PathBuf>,
+
+        /// Vector backend to recall with
+        #[arg(long, value_enum, default_value = "auto")]
+        backend: BenchBackend,
+
+        /// Memories retrieved per query
+        #[arg(long, default_value = "10")]
+        top_k: usize,
+
+        /// Cutoff for nDCG
+        #[arg(long, default_value = "10")]
+        ndcg_k: usize,
+
+        /// Also run text-heavy and vector-heavy recall weights
+        #[arg(long)]
+        sweep: bool,
+
+        /// Write the JSON report here
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Write a Markdown report here
+        #[arg(long)]
+        markdown: Option<PathBuf>,
+
+        /// Fail unless a metric reaches a minimum, e.g. `--gate recall_at_5=0.8` (repeatable)
+        #[arg(long = "gate")]
+        gates: Vec<goldfish::MetricGate>,
+
+        /// Fail if a metric drops below this earlier JSON report by more than --tolerance
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Allowed drop against --baseline
+        #[arg(long, default_value = "0.02")]
+        tolerance: f32,
+    },
+
+    /// Copy a data directory with names, emails and entities pseudonymized
+    Anonymize {
+        /// Data directory to read
+        #[arg(long = "in")]
+        input: PathBuf,
+
+        /// New data directory to write; must not exist or be empty
+        #[arg(long = "out")]
+        output: PathBuf,
+
+        /// Benchmark queries JSONL to anonymize consistently with the data
+        #[arg(long)]
+        queries: Option<PathBuf>,
+
+        /// Where to write the anonymized queries [default: <out>/queries.jsonl]
+        #[arg(long)]
+        queries_out: Option<PathBuf>,
+
+        /// Secret the pseudonyms derive from; reuse it for identical output
+        /// [default: random]
+        #[arg(long, env = "GOLDFISH_ANONYMIZE_SALT", hide_env_values = true)]
+        salt: Option<String>,
+
+        /// Term to leave as is, e.g. a product name (repeatable)
+        #[arg(long = "keep")]
+        keep: Vec<String>,
+
+        /// Write the original-to-pseudonym mapping here. Keep it private.
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+    },
+
+    /// Show a memory's recorded versions, oldest first
+    History {
+        /// Memory ID
+        id: String,
+    },
+
+    /// Show everything that happened to a memory, oldest first
+    Timeline {
+        /// Memory ID
+        id: String,
+
+        /// Print the timeline as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Analyze the query log: slow and consistently empty queries
+    SlowQueries {
+        /// Rank by 95th percentile latency instead of mean latency
+        #[arg(long)]
+        p95: bool,
+
+        /// Only list queries that found nothing at least this often (0.0-1.0)
+        #[arg(long)]
+        min_empty_rate: Option<f64>,
+
+        /// Look back this many days
+        #[arg(long, default_value = "7")]
+        days: i64,
+
+        /// Maximum queries shown
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
     /// Export memories
     Export {
         /// Output file
@@ -253,6 +521,14 @@ impl From<CliRelationType> for RelationType {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum BenchBackend {
+    /// Whatever the data directory uses
+    Auto,
+    File,
+    Lancedb,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum SortBy {
     Created,
@@ -262,6 +538,12 @@ enum SortBy {
     Accessed,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum ExportFormat {
     Json,
@@ -272,21 +554,41 @@ enum ExportFormat {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let json = cli.output == OutputFormat::Json;
+
+    if let Some(url) = &cli.remote {
+        return remote::run(url, cli.api_key.as_deref(), cli.command, json).await;
+    }
 
     match cli.command {
         Commands::Init { name } => cmd_init(name).await,
+        Commands::Add {
+            jsonl: true,
+            batch_size,
+            ..
+        } => cmd_add_jsonl(&cli.data_dir, batch_size, json).await,
         Commands::Add {
             content,
             memory_type,
             importance,
             tags,
-        } => cmd_add(&cli.data_dir, content, memory_type, importance, tags).await,
+            ..
+        } => {
+            let content = match content {
+                Some(content) => content,
+                None => std::io::read_to_string(std::io::stdin())?
+                    .trim()
+                    .to_string(),
+            };
+            cmd_add(&cli.data_dir, content, memory_type, importance, tags).await
+        }
         Commands::Search {
             query,
             memory_type,
             min_confidence,
             limit,
             temporal,
+            language,
         } => {
             cmd_search(
                 &cli.data_dir,
@@ -295,6 +597,8 @@ async fn main() -> anyhow::Result<()> {
                 min_confidence,
                 limit,
                 temporal,
+                language,
+                json,
             )
             .await
         }
@@ -303,27 +607,160 @@ async fn main() -> anyhow::Result<()> {
             sort,
             limit,
             include_forgotten,
-        } => cmd_list(&cli.data_dir, memory_type, sort, limit, include_forgotten).await,
-        Commands::Get { id, verbose } => cmd_get(&cli.data_dir, id, verbose).await,
+            cursor,
+        } => {
+            cmd_list(
+                &cli.data_dir,
+                memory_type,
+                sort,
+                limit,
+                include_forgotten,
+                cursor,
+                json,
+            )
+            .await
+        }
+        Commands::Sample {
+            count,
+            memory_type,
+            stratified,
+            include_forgotten,
+        } => {
+            cmd_sample(
+                &cli.data_dir,
+                count,
+                memory_type,
+                stratified,
+                include_forgotten,
+            )
+            .await
+        }
+        Commands::Get { id, verbose } => cmd_get(&cli.data_dir, id, verbose, json).await,
         Commands::Delete {
             id,
             force,
             permanent,
         } => cmd_delete(&cli.data_dir, id, force, permanent).await,
+        Commands::Forget {
+            memory_type,
+            older_than,
+            tag,
+            max_importance,
+            include_pinned,
+            dry_run,
+            force,
+        } => {
+            let mut query = MemoryQuery::new()
+                .with_types(memory_type.into_iter().map(MemoryType::from))
+                .with_tags(tag);
+            if let Some(age) = older_than {
+                query = query.before(chrono::Utc::now() - parse_age(&age)?);
+            }
+            if let Some(max) = max_importance {
+                query = query.with_max_importance(max);
+            }
+            if !include_pinned {
+                query = query.without_pinned();
+            }
+            cmd_forget(&cli.data_dir, query, dry_run, force).await
+        }
         Commands::Update {
             id,
             content,
             importance,
         } => cmd_update(&cli.data_dir, id, content, importance).await,
+        Commands::Pin { id } => cmd_pin(&cli.data_dir, id, true).await,
+        Commands::Unpin { id } => cmd_pin(&cli.data_dir, id, false).await,
         Commands::Associate {
             source,
             target,
             relation,
         } => cmd_associate(&cli.data_dir, source, target, relation).await,
-        Commands::Stats => cmd_stats(&cli.data_dir).await,
+        Commands::Stats => cmd_stats(&cli.data_dir, json).await,
         Commands::Maintenance { dry_run, verbose } => {
             cmd_maintenance(&cli.data_dir, dry_run, verbose).await
         }
+        Commands::Doctor {
+            report,
+            json: doctor_json,
+        } => cmd_doctor(&cli.data_dir, report, json || doctor_json).await,
+        Commands::History { id } => cmd_history(&cli.data_dir, &id, json).await,
+        Commands::Ids { query, limit } => cmd_ids(&cli.data_dir, &query, limit, json).await,
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "goldfish",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+        Commands::Repl => cmd_repl(&cli.data_dir).await,
+        Commands::Bench {
+            queries,
+            memories,
+            backend,
+            top_k,
+            ndcg_k,
+            sweep,
+            report,
+            markdown,
+            gates,
+            baseline,
+            tolerance,
+        } => {
+            cmd_bench(
+                &cli.data_dir,
+                BenchOptions {
+                    queries,
+                    memories,
+                    backend,
+                    top_k,
+                    ndcg_k,
+                    sweep,
+                    report,
+                    markdown,
+                    gates,
+                    baseline,
+                    tolerance,
+                },
+                json,
+            )
+            .await
+        }
+        Commands::Anonymize {
+            input,
+            output,
+            queries,
+            queries_out,
+            salt,
+            keep,
+            mapping,
+        } => {
+            cmd_anonymize(
+                AnonymizeOptions {
+                    input,
+                    output,
+                    queries,
+                    queries_out,
+                    salt,
+                    keep,
+                    mapping,
+                },
+                json,
+            )
+            .await
+        }
+        Commands::Timeline {
+            id,
+            json: timeline_json,
+        } => cmd_timeline(&cli.data_dir, &id, json || timeline_json).await,
+        Commands::SlowQueries {
+            p95,
+            min_empty_rate,
+            days,
+            limit,
+        } => cmd_slow_queries(&cli.data_dir, p95, min_empty_rate, days, limit).await,
         Commands::Export {
             output,
             format,
@@ -418,6 +855,102 @@ async fn cmd_add(
     Ok(())
 }
 
+/// Save JSON lines from stdin in batches, reporting failures by line number
+async fn cmd_add_jsonl(data_dir: &PathBuf, batch_size: usize, json: bool) -> anyhow::Result<()> {
+    use std::io::{BufRead, IsTerminal};
+
+    let memory_system = MemorySystem::new(data_dir).await?;
+
+    let mut inputs = Vec::new();
+    let mut line_numbers = Vec::new();
+    let mut failures: Vec<(usize, String)> = Vec::new();
+    for (i, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<CreateMemoryInput>(&line) {
+            Ok(input) => {
+                inputs.push(input);
+                line_numbers.push(i + 1);
+            }
+            Err(e) => failures.push((i + 1, e.to_string())),
+        }
+    }
+
+    let total = inputs.len() + failures.len();
+    let progress = !json && std::io::stderr().is_terminal();
+    let mut saved = Vec::new();
+    let mut done = 0;
+    let mut remaining = inputs.into_iter();
+    loop {
+        let batch: Vec<CreateMemoryInput> = remaining.by_ref().take(batch_size.max(1)).collect();
+        if batch.is_empty() {
+            break;
+        }
+        let count = batch.len();
+        let report = memory_system.save_batch(batch).await;
+        saved.extend(report.saved);
+        failures.extend(
+            report
+                .failed
+                .into_iter()
+                .map(|f| (line_numbers[done + f.index], f.error)),
+        );
+        done += count;
+        if progress {
+            eprint!("\r{}", progress_bar(done, line_numbers.len()));
+        }
+    }
+    if progress {
+        eprintln!();
+    }
+    failures.sort_by_key(|(line, _)| *line);
+
+    if json {
+        let failed: Vec<serde_json::Value> = failures
+            .iter()
+            .map(|(line, error)| serde_json::json!({ "line": line, "error": error }))
+            .collect();
+        print_json(&serde_json::json!({ "saved": saved, "failed": failed }))?;
+    } else {
+        println!(
+            "{}",
+            format!("Saved {} memories", saved.len()).green().bold()
+        );
+        if !failures.is_empty() {
+            println!(
+                "{}",
+                format!("{} records failed:", failures.len()).red().bold()
+            );
+            for (line, error) in failures.iter().take(20) {
+                println!("  line {}: {}", line, error);
+            }
+            if failures.len() > 20 {
+                println!("  ... and {} more", failures.len() - 20);
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("{} of {} records failed", failures.len(), total);
+    }
+    Ok(())
+}
+
+fn progress_bar(done: usize, total: usize) -> String {
+    const WIDTH: usize = 30;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    format!(
+        "[{}{}] {}/{}",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        done,
+        total
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn cmd_search(
     data_dir: &PathBuf,
     query: String,
@@ -425,6 +958,8 @@ async fn cmd_search(
     min_confidence: Option<f32>,
     limit: usize,
     temporal: Option<String>,
+    language: Option<String>,
+    json: bool,
 ) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
 
@@ -446,8 +981,26 @@ async fn cmd_search(
         results.retain(|r| r.memory.confidence.score >= min_conf);
     }
 
+    if let Some(language) = language {
+        let language = normalize_language(&language);
+        results.retain(|r| r.memory.language.as_deref() == Some(language.as_str()));
+    }
+
     results.truncate(limit);
 
+    if json {
+        let results: Vec<SearchHitJson> = results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| SearchHitJson {
+                rank: i + 1,
+                score: r.score,
+                memory: MemoryJson::from(&r.memory),
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "results": results }));
+    }
+
     if results.is_empty() {
         println!("{}", "No memories found".yellow());
         return Ok(());
@@ -488,15 +1041,29 @@ async fn cmd_list(
     _sort: SortBy,
     limit: usize,
     _include_forgotten: bool,
+    cursor: Option<String>,
+    json: bool,
 ) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
 
-    let memories = if let Some(mt) = memory_type {
-        memory_system.get_by_type(mt.into(), limit as i64).await?
+    let (memories, next_cursor) = if let Some(mt) = memory_type {
+        (
+            memory_system.get_by_type(mt.into(), limit as i64).await?,
+            None,
+        )
     } else {
-        memory_system.get_last_days(3650).await?
+        let page = memory_system.list_page(cursor.as_deref(), limit).await?;
+        (page.memories, page.next_cursor)
     };
 
+    if json {
+        let memories: Vec<MemoryJson> = memories.iter().take(limit).map(MemoryJson::from).collect();
+        return print_json(&serde_json::json!({
+            "memories": memories,
+            "next_cursor": next_cursor,
+        }));
+    }
+
     if memories.is_empty() {
         println!("{}", "No memories found".yellow());
         return Ok(());
@@ -509,52 +1076,114 @@ async fn cmd_list(
     println!();
 
     for memory in memories.iter().take(limit) {
-        let status_icon = if memory.confidence.score >= 0.8 {
-            "*".green()
-        } else if memory.confidence.score >= 0.5 {
-            "~".yellow()
-        } else {
-            "?".red()
-        };
+        print_memory_line(memory);
+    }
+
+    if let Some(next) = next_cursor {
+        println!();
+        println!(
+            "{}",
+            format!("More: goldfish list --cursor {next}").dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+fn print_memory_line(memory: &Memory) {
+    let status_icon = if memory.confidence.score >= 0.8 {
+        "*".green()
+    } else if memory.confidence.score >= 0.5 {
+        "~".yellow()
+    } else {
+        "?".red()
+    };
+
+    println!(
+        "{} {} {} | {:.2} | {}",
+        status_icon,
+        memory.id[..8].to_string().dimmed(),
+        format!("{:?}", memory.memory_type).cyan(),
+        memory.confidence.score,
+        memory.content.chars().take(50).collect::<String>()
+    );
+}
+
+async fn cmd_sample(
+    data_dir: &PathBuf,
+    n: usize,
+    memory_type: Option<CliMemoryType>,
+    stratified: bool,
+    include_forgotten: bool,
+) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+
+    let mut filter = SampleFilter::new().with_forgotten(include_forgotten);
+    if let Some(mt) = memory_type {
+        filter = filter.with_memory_type(mt.into());
+    }
+
+    let memories = if stratified {
+        memory_system.sample_stratified(n, &filter).await?
+    } else {
+        memory_system.sample(n, &filter).await?
+    };
+
+    if memories.is_empty() {
+        println!("{}", "No memories found".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Random sample of {} memories:", memories.len()).bold()
+    );
+    println!();
 
+    for memory in &memories {
         println!(
-            "{} {} {} | {:.2} | {}",
-            status_icon,
+            "{} {} | {:.2} | {}",
             memory.id[..8].to_string().dimmed(),
             format!("{:?}", memory.memory_type).cyan(),
-            memory.confidence.score,
-            memory.content.chars().take(50).collect::<String>()
+            memory.importance,
+            memory.content.chars().take(80).collect::<String>()
         );
     }
 
     Ok(())
 }
 
-async fn cmd_get(data_dir: &PathBuf, id: String, verbose: bool) -> anyhow::Result<()> {
+async fn cmd_get(data_dir: &PathBuf, id: String, verbose: bool, json: bool) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
+    let id = resolve_id(&memory_system, &id).await?;
 
     let memory = memory_system.load(&id).await?;
 
+    if json {
+        let Some(m) = memory else {
+            anyhow::bail!("Memory '{}' not found", id);
+        };
+        let associations: Vec<AssociationJson> = memory_system
+            .get_associations(&m.id)
+            .await?
+            .into_iter()
+            .map(|a| AssociationJson {
+                id: a.id,
+                source_id: a.source_id,
+                target_id: a.target_id,
+                relation_type: a.relation_type.to_string(),
+                weight: a.weight,
+            })
+            .collect();
+        return print_json(&serde_json::json!({
+            "memory": MemoryJson::from(&m),
+            "associations": associations,
+        }));
+    }
+
     match memory {
         Some(m) => {
-            println!("{}", "Memory Details".bold().underline());
-            println!("  ID:          {}", m.id.cyan());
-            println!("  Content:     {}", m.content);
-            println!("  Type:        {:?}", m.memory_type);
-            println!("  Importance:  {:.2}", m.importance);
-            println!(
-                "  Confidence:  {:.2} ({})",
-                m.confidence.score, m.confidence.status
-            );
-            println!(
-                "  Created:     {}",
-                m.created_at.format("%Y-%m-%d %H:%M:%S")
-            );
-            println!(
-                "  Updated:     {}",
-                m.updated_at.format("%Y-%m-%d %H:%M:%S")
-            );
-            println!("  Accessed:    {} times", m.access_count);
+            print_memory(&m);
 
             if verbose {
                 let associations = memory_system.get_associations(&m.id).await?;
@@ -574,6 +1203,30 @@ async fn cmd_get(data_dir: &PathBuf, id: String, verbose: bool) -> anyhow::Resul
     Ok(())
 }
 
+fn print_memory(m: &Memory) {
+    println!("{}", "Memory Details".bold().underline());
+    println!("  ID:          {}", m.id.cyan());
+    println!("  Content:     {}", m.content);
+    println!("  Type:        {:?}", m.memory_type);
+    println!("  Importance:  {:.2}", m.importance);
+    println!(
+        "  Confidence:  {:.2} ({})",
+        m.confidence.score, m.confidence.status
+    );
+    println!(
+        "  Created:     {}",
+        m.created_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    println!(
+        "  Updated:     {}",
+        m.updated_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    println!("  Accessed:    {} times", m.access_count);
+    if m.pinned {
+        println!("  Pinned:      {}", "yes".yellow());
+    }
+}
+
 async fn cmd_delete(
     data_dir: &PathBuf,
     id: String,
@@ -581,6 +1234,7 @@ async fn cmd_delete(
     permanent: bool,
 ) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
+    let id = resolve_id(&memory_system, &id).await?;
 
     if !force {
         let memory = memory_system.load(&id).await?;
@@ -618,13 +1272,83 @@ async fn cmd_delete(
     Ok(())
 }
 
-async fn cmd_update(
+async fn cmd_forget(
     data_dir: &PathBuf,
-    id: String,
+    query: MemoryQuery,
+    dry_run: bool,
+    force: bool,
+) -> anyhow::Result<()> {
+    let unfiltered = query.memory_types.is_empty()
+        && query.tags.is_empty()
+        && query.before == std::ops::Bound::Unbounded
+        && query.max_importance == std::ops::Bound::Unbounded;
+    if unfiltered {
+        anyhow::bail!(
+            "Refusing to forget every memory; pass --type, --older-than, --tag or --max-importance"
+        );
+    }
+
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let matching = memory_system.forget_where(&query, true).await?;
+
+    if dry_run || matching.is_empty() {
+        println!("{} memories would be forgotten", matching.len());
+        return Ok(());
+    }
+
+    if !force {
+        println!(
+            "About to forget {} memories. Are you sure? (yes/no)",
+            matching.len()
+        );
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if input.trim() != "yes" {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    let forgotten = memory_system.forget_where(&query, false).await?;
+    println!(
+        "{}",
+        format!("{} memories forgotten (soft delete)", forgotten.len()).green()
+    );
+
+    Ok(())
+}
+
+async fn cmd_pin(data_dir: &PathBuf, id: String, pinned: bool) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let id = resolve_id(&memory_system, &id).await?;
+
+    let found = if pinned {
+        memory_system.pin(&id).await?
+    } else {
+        memory_system.unpin(&id).await?
+    };
+
+    if !found {
+        println!("{}", format!("Memory '{}' not found", id).red());
+    } else if pinned {
+        println!("{}", "Memory pinned".green());
+    } else {
+        println!("{}", "Memory unpinned".green());
+    }
+
+    Ok(())
+}
+
+async fn cmd_update(
+    data_dir: &PathBuf,
+    id: String,
     content: Option<String>,
     importance: Option<f32>,
 ) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
+    let id = resolve_id(&memory_system, &id).await?;
 
     let mut memory = match memory_system.load(&id).await? {
         Some(m) => m,
@@ -656,6 +1380,8 @@ async fn cmd_associate(
     relation: CliRelationType,
 ) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
+    let source = resolve_id(&memory_system, &source).await?;
+    let target = resolve_id(&memory_system, &target).await?;
 
     memory_system
         .associate(&source, &target, relation.into())
@@ -685,6 +1411,703 @@ async fn cmd_maintenance(data_dir: &PathBuf, dry_run: bool, verbose: bool) -> an
     Ok(())
 }
 
+async fn cmd_doctor(data_dir: &PathBuf, report: bool, json: bool) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let hygiene = memory_system.hygiene_report().await?;
+
+    if json {
+        println!("{}", hygiene.to_json()?);
+        return Ok(());
+    }
+
+    println!("{}", "Memory Hygiene".bold().underline());
+    if hygiene.is_clean() {
+        println!("{}", "No issues found".green());
+        return Ok(());
+    }
+
+    if report {
+        print!("{}", hygiene.to_text());
+    } else {
+        for (kind, count) in hygiene.counts() {
+            println!("  {}: {}", kind, count.to_string().yellow());
+        }
+        println!(
+            "
+Run {} for details",
+            "goldfish doctor --report".cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Memories `query` may refer to: the exact ID, else IDs starting with it,
+/// else memories whose content matches it
+async fn id_candidates(
+    memory_system: &MemorySystem,
+    query: &str,
+    limit: usize,
+) -> anyhow::Result<Vec<Memory>> {
+    if let Some(memory) = memory_system.load(query).await? {
+        return Ok(vec![memory]);
+    }
+    let by_prefix = memory_system.find_by_id_prefix(query, limit as i64).await?;
+    if !by_prefix.is_empty() {
+        return Ok(by_prefix);
+    }
+    Ok(memory_system
+        .search(query)
+        .await?
+        .into_iter()
+        .take(limit)
+        .map(|r| r.memory)
+        .collect())
+}
+
+/// Expand a partial ID or content fragment to a full memory ID. Input nothing
+/// matches is returned as is; ambiguous input fails listing the candidates.
+async fn resolve_id(memory_system: &MemorySystem, query: &str) -> anyhow::Result<String> {
+    let candidates = id_candidates(memory_system, query, 5).await?;
+    match candidates.as_slice() {
+        [] => Ok(query.to_string()),
+        [only] => Ok(only.id.clone()),
+        many => {
+            let listed: Vec<String> = many
+                .iter()
+                .map(|m| {
+                    format!(
+                        "  {}  {}",
+                        m.id,
+                        m.content.chars().take(50).collect::<String>()
+                    )
+                })
+                .collect();
+            anyhow::bail!(
+                "'{}' matches several memories:\n{}",
+                query,
+                listed.join("\n")
+            )
+        }
+    }
+}
+
+struct BenchOptions {
+    queries: PathBuf,
+    memories: Option<PathBuf>,
+    backend: BenchBackend,
+    top_k: usize,
+    ndcg_k: usize,
+    sweep: bool,
+    report: Option<PathBuf>,
+    markdown: Option<PathBuf>,
+    gates: Vec<goldfish::MetricGate>,
+    baseline: Option<PathBuf>,
+    tolerance: f32,
+}
+
+/// Recall weights compared by `bench --sweep`, the first being the default
+fn bench_profiles(sweep: bool) -> Vec<(&'static str, RecallWeights)> {
+    let mut profiles = vec![("default", RecallWeights::default())];
+    if sweep {
+        profiles.push((
+            "text-heavy",
+            RecallWeights {
+                text: 0.5,
+                importance: 0.2,
+                vector: 0.3,
+            },
+        ));
+        profiles.push((
+            "vector-heavy",
+            RecallWeights {
+                text: 0.15,
+                importance: 0.1,
+                vector: 0.75,
+            },
+        ));
+    }
+    profiles
+}
+
+/// Benchmark recall on a scratch copy, so access counts in the data directory
+/// stay untouched. Gates and baseline regressions make the command fail.
+async fn cmd_bench(data_dir: &PathBuf, options: BenchOptions, json: bool) -> anyhow::Result<()> {
+    use anyhow::Context;
+    use goldfish::benchmark_suites::{load_jsonl, render_markdown, run_queries};
+
+    match options.backend {
+        BenchBackend::Auto => {}
+        BenchBackend::File => std::env::set_var("GOLDFISH_VECTOR_BACKEND", "file"),
+        BenchBackend::Lancedb => std::env::set_var("GOLDFISH_VECTOR_BACKEND", "lancedb"),
+    }
+
+    let queries: Vec<BenchmarkQuery> = load_jsonl(&options.queries)?;
+    let scratch = tempfile::tempdir()?;
+    let cortex = match &options.memories {
+        Some(path) => {
+            let cortex = MemoryCortex::new(scratch.path()).await?;
+            for record in load_jsonl::<BenchmarkMemory>(path)? {
+                let mut memory = Memory::new(record.content, record.memory_type);
+                memory.id = record.id;
+                if let Some(importance) = record.importance {
+                    memory.importance = importance.clamp(0.0, 1.0);
+                }
+                cortex.remember(&memory).await?;
+            }
+            cortex
+        }
+        None => {
+            let snapshot = scratch.path().join("data");
+            MemorySystem::new(data_dir)
+                .await?
+                .backup_to(&snapshot)
+                .await
+                .with_context(|| format!("snapshotting {}", data_dir.display()))?;
+            MemoryCortex::new(snapshot).await?
+        }
+    };
+    let dataset = options
+        .memories
+        .as_ref()
+        .unwrap_or(data_dir)
+        .display()
+        .to_string();
+    let backend = cortex.vector_backend_name().to_string();
+    if options.backend == BenchBackend::Lancedb && backend != "lancedb" {
+        anyhow::bail!(
+            "lancedb requested but the active backend is '{backend}'; build with --features lancedb"
+        );
+    }
+
+    let mut reports = Vec::new();
+    for (name, weights) in bench_profiles(options.sweep) {
+        cortex.set_recall_weights(weights.normalized()).await;
+        let per_query = run_queries(&cortex, &queries, options.top_k, options.ndcg_k).await?;
+        reports.push(BenchmarkReport {
+            suite_name: name.to_string(),
+            generated_at_utc: chrono::Utc::now().to_rfc3339(),
+            dataset: dataset.clone(),
+            backend: backend.clone(),
+            top_k: options.top_k,
+            ndcg_k: options.ndcg_k,
+            metrics: aggregate_metrics(&per_query),
+            per_query,
+        });
+    }
+
+    if let Some(path) = &options.report {
+        std::fs::write(path, serde_json::to_string_pretty(&reports)?)
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+    if let Some(path) = &options.markdown {
+        std::fs::write(path, render_markdown(&reports))
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+
+    let mut failures = Vec::new();
+    for report in &reports {
+        for failure in goldfish::failed_gates(&report.metrics, &options.gates) {
+            failures.push(format!("[{}] gate {}", report.suite_name, failure));
+        }
+    }
+    if let Some(path) = &options.baseline {
+        let earlier = load_bench_reports(path)?;
+        for report in &reports {
+            let Some(before) = earlier
+                .iter()
+                .find(|r| r.suite_name == report.suite_name)
+                .or(earlier.first())
+            else {
+                continue;
+            };
+            for drop in goldfish::regressions(&before.metrics, &report.metrics, options.tolerance) {
+                failures.push(format!("[{}] regression {}", report.suite_name, drop));
+            }
+        }
+    }
+
+    if json {
+        print_json(&serde_json::json!({
+            "reports": reports,
+            "failures": failures,
+        }))?;
+    } else {
+        println!(
+            "{} {} queries, backend {}",
+            "Benchmark:".bold(),
+            queries.len(),
+            backend.cyan()
+        );
+        for report in &reports {
+            let m = &report.metrics;
+            println!(
+                "  {:<12} R@1 {:.3} | R@3 {:.3} | R@5 {:.3} | MRR {:.3} | nDCG@{} {:.3} | avg {:.1} ms | p95 {:.1} ms",
+                report.suite_name,
+                m.recall_at_1,
+                m.recall_at_3,
+                m.recall_at_5,
+                m.mrr,
+                report.ndcg_k,
+                m.ndcg_at_k,
+                m.avg_latency_ms,
+                m.p95_latency_ms
+            );
+        }
+        for failure in &failures {
+            println!("  {} {}", "FAIL".red().bold(), failure);
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("{} benchmark check(s) failed", failures.len());
+    }
+    Ok(())
+}
+
+/// Reports written by `bench --report`, or a single report from the examples
+fn load_bench_reports(path: &PathBuf) -> anyhow::Result<Vec<BenchmarkReport>> {
+    let text = std::fs::read_to_string(path)?;
+    if let Ok(reports) = serde_json::from_str::<Vec<BenchmarkReport>>(&text) {
+        return Ok(reports);
+    }
+    Ok(vec![serde_json::from_str(&text)?])
+}
+
+struct AnonymizeOptions {
+    input: PathBuf,
+    output: PathBuf,
+    queries: Option<PathBuf>,
+    queries_out: Option<PathBuf>,
+    salt: Option<String>,
+    keep: Vec<String>,
+    mapping: Option<PathBuf>,
+}
+
+/// Build a pseudonymized copy of a data directory through an anonymized
+/// archive, so the text index and vectors are rebuilt from the new text
+async fn cmd_anonymize(options: AnonymizeOptions, json: bool) -> anyhow::Result<()> {
+    use goldfish::benchmark_suites::load_jsonl;
+    use goldfish::Anonymizer;
+
+    if options.output.exists() && std::fs::read_dir(&options.output)?.next().is_some() {
+        anyhow::bail!(
+            "{} is not empty; anonymize writes a new data directory",
+            options.output.display()
+        );
+    }
+
+    let source = MemorySystem::new(&options.input).await?;
+    let salt = options
+        .salt
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let mut anonymizer = options
+        .keep
+        .iter()
+        .fold(Anonymizer::new(&salt), |a, term| a.with_kept_term(term));
+
+    let log = source
+        .store()
+        .query_log_since(chrono::DateTime::<chrono::Utc>::default(), i64::MAX)
+        .await?;
+    for entry in &log {
+        anonymizer.learn(&entry.query);
+    }
+    let mut queries: Vec<serde_json::Value> = match &options.queries {
+        Some(path) => load_jsonl(path)?,
+        None => Vec::new(),
+    };
+    for query in &queries {
+        if let Some(text) = query.get("query").and_then(|q| q.as_str()) {
+            anonymizer.learn(text);
+        }
+    }
+
+    let scratch = tempfile::tempdir()?;
+    let archive = scratch.path().join("anonymized.jsonl");
+    let exported = source.export_anonymized(&archive, &mut anonymizer).await?;
+
+    let target = MemorySystem::new(&options.output).await?;
+    let imported = target.import_archive(&archive).await?;
+    for entry in &log {
+        target
+            .store()
+            .log_query(&anonymizer.query_log_entry(entry))
+            .await?;
+    }
+
+    let queries_out = match &options.queries {
+        Some(_) => {
+            let path = options
+                .queries_out
+                .clone()
+                .unwrap_or_else(|| options.output.join("queries.jsonl"));
+            let mut lines = String::new();
+            for query in &mut queries {
+                let text = query.get("query").and_then(|q| q.as_str());
+                if let Some(anonymized) = text.map(|text| anonymizer.text(text)) {
+                    query["query"] = anonymized.into();
+                }
+                lines.push_str(&serde_json::to_string(query)?);
+                lines.push('\n');
+            }
+            std::fs::write(&path, lines)?;
+            Some(path)
+        }
+        None => None,
+    };
+    if let Some(path) = &options.mapping {
+        std::fs::write(path, serde_json::to_string_pretty(&anonymizer.mapping())?)?;
+    }
+
+    if json {
+        return print_json(&serde_json::json!({
+            "output": options.output,
+            "memories": imported.memories,
+            "associations": imported.associations,
+            "experiences": imported.experiences,
+            "summaries": imported.summaries,
+            "logged_queries": log.len(),
+            "benchmark_queries": queries.len(),
+            "queries_out": queries_out,
+            "terms": anonymizer.len(),
+        }));
+    }
+    println!(
+        "{} {} -> {}",
+        "Anonymized".green().bold(),
+        options.input.display(),
+        options.output.display()
+    );
+    println!(
+        "  {} memories, {} associations, {} episodes, {} summaries ({} bytes of archive)",
+        imported.memories,
+        imported.associations,
+        imported.experiences,
+        imported.summaries,
+        exported.bytes
+    );
+    println!("  {} logged queries", log.len());
+    if let Some(path) = &queries_out {
+        println!(
+            "  {} benchmark queries -> {}",
+            queries.len(),
+            path.display()
+        );
+    }
+    println!("  {} terms pseudonymized", anonymizer.len());
+    if let Some(path) = &options.mapping {
+        println!(
+            "  {} mapping written to {}; do not share it",
+            "Warning:".yellow(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+const REPL_HELP: &str = "\
+  add [type:] <content>           Save a memory, e.g. `add preference: dark mode`
+  search <query>                  Text search
+  recall <query>                  Cortex recall (text, vectors, working memory)
+  context                         Build the current context window
+  link <from> <to> [relation]     Associate two memories (IDs, prefixes or content)
+  get <id>                        Show one memory
+  help                            This list
+  quit                            Leave (also Ctrl-D)";
+
+/// Read-eval-print loop over one data directory. Pulses from each command are
+/// echoed after its output; history persists in `<data_dir>/.repl_history`.
+async fn cmd_repl(data_dir: &PathBuf) -> anyhow::Result<()> {
+    use rustyline::error::ReadlineError;
+
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let cortex = MemoryCortex::new(data_dir).await?;
+    let mut pulses = memory_system.pulses().subscribe();
+
+    let history_path = data_dir.join(".repl_history");
+    let mut editor = rustyline::DefaultEditor::new()?;
+    let _ = editor.load_history(&history_path);
+
+    println!(
+        "{} {}",
+        "goldfish repl".bold(),
+        format!("({}) - type `help` for commands", data_dir.display()).dimmed()
+    );
+
+    loop {
+        let line = match editor.readline("goldfish> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let outcome = match command {
+            "quit" | "exit" => break,
+            "help" | "?" => {
+                println!("{REPL_HELP}");
+                Ok(())
+            }
+            "add" => repl_add(&memory_system, &cortex, rest).await,
+            "search" => repl_search(&memory_system, rest).await,
+            "recall" => repl_recall(&cortex, rest).await,
+            "context" => cortex
+                .build_context(&ContextWindow::new(2000))
+                .await
+                .map(|context| println!("{context}"))
+                .map_err(Into::into),
+            "link" => repl_link(&memory_system, rest).await,
+            "get" => match resolve_id(&memory_system, rest).await {
+                Ok(id) => match memory_system.load(&id).await {
+                    Ok(Some(memory)) => {
+                        print_memory(&memory);
+                        Ok(())
+                    }
+                    Ok(None) => Err(anyhow::anyhow!("memory not found: {rest}")),
+                    Err(e) => Err(e.into()),
+                },
+                Err(e) => Err(e),
+            },
+            other => Err(anyhow::anyhow!("unknown command '{other}', try `help`")),
+        };
+
+        if let Err(e) = outcome {
+            println!("{} {}", "error:".red().bold(), e);
+        }
+        while let Ok(pulse) = pulses.try_recv() {
+            println!("  {}", format!("~ {}", pulse.description()).dimmed());
+        }
+    }
+
+    editor.save_history(&history_path)?;
+    Ok(())
+}
+
+async fn repl_add(
+    memory_system: &MemorySystem,
+    cortex: &MemoryCortex,
+    rest: &str,
+) -> anyhow::Result<()> {
+    let (memory_type, content) = match rest.split_once(':') {
+        Some((name, content)) => match CliMemoryType::from_str(name.trim(), true) {
+            Ok(memory_type) => (memory_type.into(), content.trim()),
+            Err(_) => (MemoryType::Fact, rest),
+        },
+        None => (MemoryType::Fact, rest),
+    };
+    if content.is_empty() {
+        anyhow::bail!("usage: add [type:] <content>");
+    }
+
+    let memory = Memory::new(content, memory_type);
+    memory_system.save(&memory).await?;
+    cortex.think_about(&memory.id).await?;
+    println!("{} {}", "added".green(), memory.id.cyan());
+    Ok(())
+}
+
+async fn repl_search(memory_system: &MemorySystem, query: &str) -> anyhow::Result<()> {
+    if query.is_empty() {
+        anyhow::bail!("usage: search <query>");
+    }
+    let results = memory_system.search(query).await?;
+    print_repl_results(&results);
+    Ok(())
+}
+
+async fn repl_recall(cortex: &MemoryCortex, query: &str) -> anyhow::Result<()> {
+    if query.is_empty() {
+        anyhow::bail!("usage: recall <query>");
+    }
+    let results = cortex.recall(query, 10).await?;
+    print_repl_results(&results);
+    Ok(())
+}
+
+fn print_repl_results(results: &[MemorySearchResult]) {
+    if results.is_empty() {
+        println!("{}", "No memories found".yellow());
+    }
+    for result in results.iter().take(10) {
+        print!("{} ", format!("{:.2}", result.score).dimmed());
+        print_memory_line(&result.memory);
+    }
+}
+
+async fn repl_link(memory_system: &MemorySystem, rest: &str) -> anyhow::Result<()> {
+    let mut args = rest.split_whitespace();
+    let (Some(from), Some(to)) = (args.next(), args.next()) else {
+        anyhow::bail!("usage: link <from> <to> [relation]");
+    };
+    let relation = match args.next() {
+        Some(name) => CliRelationType::from_str(name, true).map_err(|e| anyhow::anyhow!(e))?,
+        None => CliRelationType::Related,
+    };
+
+    let from = resolve_id(memory_system, from).await?;
+    let to = resolve_id(memory_system, to).await?;
+    memory_system.associate(&from, &to, relation.into()).await?;
+    println!("{} {} -> {}", "linked".green(), from.cyan(), to.cyan());
+    Ok(())
+}
+
+async fn cmd_ids(data_dir: &PathBuf, query: &str, limit: usize, json: bool) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let candidates = id_candidates(&memory_system, query, limit).await?;
+
+    if json {
+        let ids: Vec<serde_json::Value> = candidates
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "id": m.id,
+                    "memory_type": m.memory_type.to_string(),
+                    "content": m.content,
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "ids": ids }));
+    }
+
+    if candidates.is_empty() {
+        println!("{}", format!("No memory matches '{}'", query).yellow());
+    }
+    for m in &candidates {
+        println!(
+            "{}  {}  {}",
+            m.id,
+            m.memory_type.to_string().cyan(),
+            m.content.chars().take(60).collect::<String>()
+        );
+    }
+    Ok(())
+}
+
+async fn cmd_history(data_dir: &PathBuf, id: &str, json: bool) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let id = &resolve_id(&memory_system, id).await?;
+    let versions = memory_system.history(id).await?;
+
+    if versions.is_empty() {
+        if json {
+            anyhow::bail!("No history for memory '{}'", id);
+        }
+        println!("{}", format!("No history for memory '{}'", id).red());
+        return Ok(());
+    }
+
+    if json {
+        let versions: Vec<VersionJson> = versions
+            .iter()
+            .map(|v| VersionJson {
+                version_number: v.version_number,
+                created_at: v.created_at,
+                author: v.author.to_string(),
+                reason: v.change_reason.clone(),
+                memory: MemoryJson::from(&v.memory),
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "memory_id": id, "versions": versions }));
+    }
+
+    println!("{}", format!("History of {}", id).bold().underline());
+    for v in &versions {
+        println!(
+            "  v{} {} {} {}",
+            v.version_number,
+            v.created_at.format("%Y-%m-%d %H:%M:%S"),
+            v.author.to_string().cyan(),
+            v.change_reason.as_deref().unwrap_or("").dimmed()
+        );
+        println!(
+            "     {}",
+            v.memory.content.chars().take(70).collect::<String>()
+        );
+    }
+
+    Ok(())
+}
+
+async fn cmd_timeline(data_dir: &PathBuf, id: &str, json: bool) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let id = &resolve_id(&memory_system, id).await?;
+
+    match memory_system.get_memory_timeline(id).await? {
+        Some(timeline) if json => println!("{}", timeline.to_json()?),
+        Some(timeline) => print!("{}", timeline.to_text()),
+        None => println!("{}", format!("Memory '{}' not found", id).red()),
+    }
+
+    Ok(())
+}
+
+async fn cmd_slow_queries(
+    data_dir: &PathBuf,
+    p95: bool,
+    min_empty_rate: Option<f64>,
+    days: i64,
+    limit: usize,
+) -> anyhow::Result<()> {
+    let memory_system = MemorySystem::new(data_dir).await?;
+    let mut stats = memory_system
+        .slow_queries(chrono::Duration::days(days))
+        .await?;
+
+    if let Some(rate) = min_empty_rate {
+        stats.retain(|s| s.empty_rate >= rate);
+        stats.sort_by(|a, b| b.empty_rate.total_cmp(&a.empty_rate));
+    } else if !p95 {
+        stats.sort_by(|a, b| b.mean_ms.total_cmp(&a.mean_ms));
+    }
+
+    if stats.is_empty() {
+        println!(
+            "{}",
+            "No logged queries (enable with MemorySystem::with_query_log)".yellow()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Logged queries, last {days} days")
+            .bold()
+            .underline()
+    );
+    println!(
+        "  {:>8} {:>8} {:>8} {:>6} {:>6}  query",
+        "p50 ms", "p95 ms", "max ms", "runs", "empty"
+    );
+    for s in stats.iter().take(limit) {
+        let p95_ms = format!("{:>8.1}", s.p95_ms);
+        let empty = format!("{:>5.0}%", s.empty_rate * 100.0);
+        println!(
+            "  {:>8.1} {} {:>8.1} {:>6} {}  {} {}",
+            s.p50_ms,
+            if p95 { p95_ms.red() } else { p95_ms.normal() },
+            s.max_ms,
+            s.count,
+            if s.empty_rate >= 0.5 {
+                empty.yellow()
+            } else {
+                empty.normal()
+            },
+            s.query,
+            format!("({})", s.kind).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
 async fn cmd_export(
     data_dir: &PathBuf,
     output: PathBuf,
@@ -728,35 +2151,159 @@ async fn cmd_import(
     Ok(())
 }
 
-async fn cmd_stats(data_dir: &PathBuf) -> anyhow::Result<()> {
+async fn cmd_stats(data_dir: &PathBuf, json: bool) -> anyhow::Result<()> {
     let memory_system = MemorySystem::new(data_dir).await?;
 
-    println!("{}", "Goldfish Statistics".bold().underline());
+    let stats = memory_system
+        .store()
+        .aggregate_stats(chrono::Utc::now() - chrono::Duration::days(7))
+        .await?;
 
-    let memories = memory_system.get_last_days(3650).await?;
+    if json {
+        return print_json(&stats);
+    }
+    print_stats(&stats);
+    Ok(())
+}
 
-    println!("  Total memories: {}", memories.len());
+fn print_stats(stats: &StoreAnalytics) {
+    println!("{}", "Goldfish Statistics".bold().underline());
 
-    use std::collections::HashMap;
-    let mut by_type: HashMap<MemoryType, usize> = HashMap::new();
-    for m in &memories {
-        *by_type.entry(m.memory_type).or_insert(0) += 1;
+    println!("  Total memories: {}", stats.active);
+    if stats.forgotten > 0 {
+        println!("  Forgotten: {}", stats.forgotten);
+    }
+    if stats.pinned > 0 {
+        println!("  Pinned: {}", stats.pinned);
     }
 
     println!("\n{}", "By Type:".bold());
-    for (mem_type, count) in by_type {
-        println!("  {:?}: {}", mem_type, count);
+    for t in &stats.by_type {
+        println!("  {:?}: {}", t.memory_type, t.count);
+    }
+
+    if stats.active > 0 {
+        println!("\n  Average confidence: {:.2}", stats.avg_confidence);
+        println!("  Average importance: {:.2}", stats.avg_importance);
+    }
+
+    if !stats.per_day.is_empty() {
+        println!("\n{}", "Last 7 Days:".bold());
+        for day in &stats.per_day {
+            println!("  {}: {}", day.day, day.count);
+        }
     }
 
-    if !memories.is_empty() {
-        let avg_confidence: f32 =
-            memories.iter().map(|m| m.confidence.score).sum::<f32>() / memories.len() as f32;
-        println!("\n  Average confidence: {:.2}", avg_confidence);
+    println!("\n{}", "Accesses:".bold());
+    for bucket in &stats.access_histogram {
+        let range = match bucket.max_accesses {
+            Some(max) if max == bucket.min_accesses => max.to_string(),
+            Some(max) => format!("{}-{}", bucket.min_accesses, max),
+            None => format!("{}+", bucket.min_accesses),
+        };
+        println!("  {:>7}: {}", range, bucket.count);
     }
+}
+
+/// A memory as printed by `--output json`. Field names are part of the CLI's
+/// interface and only ever gain new fields.
+#[derive(Serialize)]
+struct MemoryJson {
+    id: String,
+    content: String,
+    memory_type: String,
+    importance: f32,
+    confidence: f32,
+    verification_status: String,
+    tags: Vec<String>,
+    pinned: bool,
+    forgotten: bool,
+    access_count: i64,
+    language: Option<String>,
+    source: Option<String>,
+    session_id: Option<String>,
+    metadata: Option<serde_json::Value>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+    last_accessed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&Memory> for MemoryJson {
+    fn from(m: &Memory) -> Self {
+        Self {
+            id: m.id.clone(),
+            content: m.content.clone(),
+            memory_type: m.memory_type.to_string(),
+            importance: m.importance,
+            confidence: m.confidence.score,
+            verification_status: m.confidence.status.to_string(),
+            tags: m.tags.clone(),
+            pinned: m.pinned,
+            forgotten: m.forgotten,
+            access_count: m.access_count,
+            language: m.language.clone(),
+            source: m.source.clone(),
+            session_id: m.session_id.clone(),
+            metadata: m.metadata.clone(),
+            created_at: m.created_at,
+            updated_at: m.updated_at,
+            last_accessed_at: m.last_accessed_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchHitJson {
+    rank: usize,
+    score: f32,
+    memory: MemoryJson,
+}
+
+#[derive(Serialize)]
+struct AssociationJson {
+    id: String,
+    source_id: String,
+    target_id: String,
+    relation_type: String,
+    weight: f32,
+}
+
+#[derive(Serialize)]
+struct VersionJson {
+    version_number: u32,
+    created_at: chrono::DateTime<chrono::Utc>,
+    author: String,
+    reason: Option<String>,
+    memory: MemoryJson,
+}
 
+fn print_json(value: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
 
+/// Parse an age like `90d`, `12h`, `2w` or `30m`
+fn parse_age(age: &str) -> anyhow::Result<chrono::Duration> {
+    let age = age.trim();
+    let split = age
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow::anyhow!("Missing unit in age '{}' (use m, h, d or w)", age))?;
+    let (number, unit) = age.split_at(split);
+    let number: i64 = number.parse()?;
+
+    match unit {
+        "m" => Ok(chrono::Duration::minutes(number)),
+        "h" => Ok(chrono::Duration::hours(number)),
+        "d" => Ok(chrono::Duration::days(number)),
+        "w" => Ok(chrono::Duration::weeks(number)),
+        _ => anyhow::bail!(
+            "Unknown unit '{}' in age '{}' (use m, h, d or w)",
+            unit,
+            age
+        ),
+    }
+}
+
 fn parse_temporal(temp: &str) -> anyhow::Result<TemporalQuery> {
     let query = match temp.to_lowercase().as_str() {
         "today" => TemporalQuery::today(),
@@ -776,3 +2323,417 @@ fn parse_temporal(temp: &str) -> anyhow::Result<TemporalQuery> {
 
     Ok(query)
 }
+
+/// `--remote`: the commands goldfish-server supports, over its REST API
+#[cfg(feature = "remote")]
+mod remote {
+    use super::*;
+    use goldfish::RemoteClient;
+
+    pub async fn run(
+        url: &str,
+        api_key: Option<&str>,
+        command: Commands,
+        json: bool,
+    ) -> anyhow::Result<()> {
+        let mut client = RemoteClient::new(url)?;
+        if let Some(key) = api_key {
+            client = client.with_api_key(key);
+        }
+
+        match command {
+            Commands::Add {
+                content,
+                jsonl: false,
+                memory_type,
+                importance,
+                ..
+            } => {
+                let content = match content {
+                    Some(content) => content,
+                    None => std::io::read_to_string(std::io::stdin())?
+                        .trim()
+                        .to_string(),
+                };
+                let saved = client
+                    .create(&content, memory_type.into(), importance)
+                    .await?;
+                if json {
+                    return print_json(&saved);
+                }
+                println!("{}", "Memory added successfully".green().bold());
+                println!("  ID: {}", saved.id.cyan());
+                println!("  Type: {}", saved.memory_type);
+                Ok(())
+            }
+            Commands::Search {
+                query,
+                memory_type,
+                limit,
+                min_confidence: None,
+                temporal: None,
+                language: None,
+            } => search(&client, &query, memory_type, limit, json).await,
+            Commands::List {
+                memory_type: None,
+                limit,
+                cursor,
+                ..
+            } => {
+                let page = client.list_page(cursor.as_deref(), limit).await?;
+                if json {
+                    let memories: Vec<MemoryJson> =
+                        page.memories.iter().map(MemoryJson::from).collect();
+                    return print_json(&serde_json::json!({
+                        "memories": memories,
+                        "next_cursor": page.next_cursor,
+                    }));
+                }
+                if page.memories.is_empty() {
+                    println!("{}", "No memories found".yellow());
+                }
+                for memory in &page.memories {
+                    print_memory_line(memory);
+                }
+                if let Some(next) = page.next_cursor {
+                    println!();
+                    println!(
+                        "{}",
+                        format!("More: goldfish --remote {url} list --cursor {next}").dimmed()
+                    );
+                }
+                Ok(())
+            }
+            Commands::Get { id, .. } => {
+                let Some(memory) = client.get(&id).await? else {
+                    anyhow::bail!("Memory '{}' not found", id);
+                };
+                if json {
+                    return print_json(&serde_json::json!({ "memory": MemoryJson::from(&memory) }));
+                }
+                print_memory(&memory);
+                Ok(())
+            }
+            Commands::Delete {
+                id,
+                force,
+                permanent,
+            } => {
+                if !force {
+                    let Some(memory) = client.get(&id).await? else {
+                        anyhow::bail!("Memory '{}' not found", id);
+                    };
+                    println!(
+                        "About to delete: {}",
+                        memory.content.chars().take(50).collect::<String>()
+                    );
+                    println!("Are you sure? (yes/no)");
+                    let mut input = String::new();
+                    std::io::stdin().read_line(&mut input)?;
+                    if input.trim() != "yes" {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                }
+                let found = if permanent {
+                    client.delete(&id).await?
+                } else {
+                    client.forget(&id).await?
+                };
+                if !found {
+                    anyhow::bail!("Memory '{}' not found", id);
+                }
+                println!("{}", "Memory deleted".green());
+                Ok(())
+            }
+            Commands::Pin { id } => pin(&client, &id, true).await,
+            Commands::Unpin { id } => pin(&client, &id, false).await,
+            Commands::Stats => {
+                let stats = client.stats(7).await?;
+                if json {
+                    return print_json(&stats);
+                }
+                print_stats(&stats);
+                Ok(())
+            }
+            _ => anyhow::bail!(
+                "This command or option is not available with --remote; the server supports \
+                 add, search, list, get, delete, pin, unpin and stats"
+            ),
+        }
+    }
+
+    async fn search(
+        client: &RemoteClient,
+        query: &str,
+        memory_type: Option<CliMemoryType>,
+        limit: usize,
+        json: bool,
+    ) -> anyhow::Result<()> {
+        let results = client
+            .search(query, memory_type.map(MemoryType::from), limit)
+            .await?;
+
+        if json {
+            let results: Vec<SearchHitJson> = results
+                .iter()
+                .map(|r| SearchHitJson {
+                    rank: r.rank,
+                    score: r.score,
+                    memory: MemoryJson::from(&r.memory),
+                })
+                .collect();
+            return print_json(&serde_json::json!({ "results": results }));
+        }
+
+        if results.is_empty() {
+            println!("{}", "No memories found".yellow());
+            return Ok(());
+        }
+        for result in &results {
+            println!(
+                "{}. {} ({} - {})",
+                result.rank,
+                result.memory.content.chars().take(60).collect::<String>(),
+                result.memory.memory_type.to_string().cyan(),
+                result
+                    .memory
+                    .id
+                    .chars()
+                    .take(8)
+                    .collect::<String>()
+                    .dimmed()
+            );
+        }
+        Ok(())
+    }
+
+    async fn pin(client: &RemoteClient, id: &str, pinned: bool) -> anyhow::Result<()> {
+        if !client.set_pinned(id, pinned).await? {
+            anyhow::bail!("Memory '{}' not found", id);
+        }
+        println!(
+            "{}",
+            if pinned {
+                "Memory pinned"
+            } else {
+                "Memory unpinned"
+            }
+            .green()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "remote"))]
+mod remote {
+    use super::Commands;
+
+    pub async fn run(
+        _url: &str,
+        _api_key: Option<&str>,
+        _command: Commands,
+        _json: bool,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("--remote needs goldfish built with the `remote` feature")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_cursor_conflicts_with_a_type_filter() {
+        let paged = Cli::try_parse_from(["goldfish", "list", "--cursor", "1:abc"]);
+        assert!(paged.is_ok());
+        let filtered = Cli::try_parse_from([
+            "goldfish",
+            "list",
+            "--memory-type",
+            "fact",
+            "--cursor",
+            "1:abc",
+        ]);
+        assert_eq!(
+            filtered.err().map(|e| e.kind()),
+            Some(clap::error::ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[tokio::test]
+    async fn forget_dry_runs_change_nothing_and_real_runs_skip_pinned() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_path_buf();
+        let memory_system = MemorySystem::new(&data_dir).await.unwrap();
+        let stale = Memory::new("Old sprint goal", MemoryType::Goal);
+        let pinned = Memory::new("Quarterly goal", MemoryType::Goal);
+        let fact = Memory::new("The CI runs on every push", MemoryType::Fact);
+        for memory in [&stale, &pinned, &fact] {
+            memory_system.save(memory).await.unwrap();
+        }
+        memory_system.pin(&pinned.id).await.unwrap();
+        drop(memory_system);
+
+        let goals = || MemoryQuery::new().with_type(MemoryType::Goal);
+        let is_forgotten = |id: String| {
+            let data_dir = data_dir.clone();
+            async move {
+                let memory_system = MemorySystem::new(&data_dir).await.unwrap();
+                memory_system.load(&id).await.unwrap().unwrap().forgotten
+            }
+        };
+
+        cmd_forget(&data_dir, goals().without_pinned(), true, true)
+            .await
+            .unwrap();
+        assert!(!is_forgotten(stale.id.clone()).await);
+
+        cmd_forget(&data_dir, goals().without_pinned(), false, true)
+            .await
+            .unwrap();
+        assert!(is_forgotten(stale.id.clone()).await);
+        assert!(!is_forgotten(pinned.id.clone()).await);
+        assert!(!is_forgotten(fact.id.clone()).await);
+
+        cmd_forget(&data_dir, goals(), false, true).await.unwrap();
+        assert!(is_forgotten(pinned.id.clone()).await);
+    }
+
+    #[test]
+    fn forget_leaves_pinned_memories_out_unless_asked() {
+        let parse = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Commands::Forget { include_pinned, .. } => include_pinned,
+            _ => unreachable!(),
+        };
+        assert!(!parse(&["goldfish", "forget", "--type", "goal"]));
+        assert!(parse(&[
+            "goldfish",
+            "forget",
+            "--type",
+            "goal",
+            "--include-pinned"
+        ]));
+    }
+
+    #[test]
+    fn json_output_is_selected_before_the_subcommand() {
+        let cli = Cli::try_parse_from(["goldfish", "--output", "json", "stats"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        let cli = Cli::try_parse_from(["goldfish", "stats"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Text);
+        assert!(Cli::try_parse_from(["goldfish", "--output", "xml", "stats"]).is_err());
+    }
+
+    #[test]
+    fn memories_print_with_stable_json_fields() {
+        let mut memory = Memory::new("Deploys happen on Tuesday", MemoryType::Fact)
+            .with_importance(0.7)
+            .with_source("runbook");
+        memory.tags = vec!["ops".to_string()];
+        let hit = SearchHitJson {
+            rank: 1,
+            score: 0.5,
+            memory: MemoryJson::from(&memory),
+        };
+        let value = serde_json::to_value(&hit).unwrap();
+        assert_eq!(value["rank"], 1);
+        assert_eq!(value["memory"]["id"], memory.id.as_str());
+        assert_eq!(value["memory"]["memory_type"], "fact");
+        assert_eq!(value["memory"]["tags"], serde_json::json!(["ops"]));
+        assert_eq!(value["memory"]["source"], "runbook");
+        assert_eq!(value["memory"]["session_id"], serde_json::Value::Null);
+        assert!(value["memory"]["importance"].as_f64().unwrap() > 0.69);
+        assert_eq!(value["memory"].as_object().unwrap().len(), 17);
+    }
+
+    #[tokio::test]
+    async fn partial_ids_resolve_to_a_single_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+        let with_id = |id: &str, content: &str| {
+            let mut memory = Memory::new(content, MemoryType::Fact);
+            memory.id = id.to_string();
+            memory
+        };
+        for memory in [
+            with_id("ab12-deploy", "Deploys happen on Tuesday"),
+            with_id("ab34-coffee", "The team drinks coffee at ten"),
+            with_id("c_d-billing", "Billing runs nightly"),
+        ] {
+            memory_system.save(&memory).await.unwrap();
+        }
+
+        let resolve = |query: &'static str| resolve_id(&memory_system, query);
+        assert_eq!(resolve("ab34-coffee").await.unwrap(), "ab34-coffee");
+        assert_eq!(resolve("ab1").await.unwrap(), "ab12-deploy");
+        assert_eq!(resolve("c_d").await.unwrap(), "c_d-billing");
+        // `_` is not a wildcard, so nothing has this prefix and the search
+        // finds nothing either
+        assert_eq!(resolve("a_").await.unwrap(), "a_");
+        assert_eq!(resolve("coffee").await.unwrap(), "ab34-coffee");
+
+        let ambiguous = resolve("ab").await.unwrap_err().to_string();
+        assert!(ambiguous.contains("ab12-deploy") && ambiguous.contains("ab34-coffee"));
+    }
+
+    #[test]
+    fn completions_are_generated_for_every_subcommand() {
+        let mut script = Vec::new();
+        clap_complete::generate(
+            clap_complete::Shell::Bash,
+            &mut Cli::command(),
+            "goldfish",
+            &mut script,
+        );
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("goldfish__subcmd__ids"));
+        assert!(script.contains("goldfish__subcmd__completions"));
+        assert!(Cli::try_parse_from(["goldfish", "completions", "fish"]).is_ok());
+        assert!(Cli::try_parse_from(["goldfish", "completions", "tcsh"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn repl_adds_typed_memories_and_links_them_by_fragment() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+        let cortex = MemoryCortex::new(dir.path()).await.unwrap();
+
+        repl_add(&memory_system, &cortex, "preference: dark mode everywhere")
+            .await
+            .unwrap();
+        // Unknown type names are part of the content
+        repl_add(&memory_system, &cortex, "note: standup moved to ten")
+            .await
+            .unwrap();
+        assert!(repl_add(&memory_system, &cortex, "goal:").await.is_err());
+
+        let memories = memory_system.find_by_id_prefix("", 10).await.unwrap();
+        let added = |content: &str| {
+            memories
+                .iter()
+                .find(|m| m.content == content)
+                .unwrap_or_else(|| panic!("{content} was not added"))
+                .clone()
+        };
+        let preference = added("dark mode everywhere");
+        assert_eq!(preference.memory_type, MemoryType::Preference);
+        let note = added("note: standup moved to ten");
+        assert_eq!(note.memory_type, MemoryType::Fact);
+        assert_eq!(memories.len(), 2);
+
+        repl_link(&memory_system, "standup dark caused-by")
+            .await
+            .unwrap();
+        let links = memory_system.get_associations(&note.id).await.unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target_id, preference.id);
+        assert_eq!(links[0].relation_type, RelationType::CausedBy);
+
+        assert!(repl_link(&memory_system, "standup").await.is_err());
+        assert!(repl_link(&memory_system, "standup dark sideways")
+            .await
+            .is_err());
+        assert!(repl_search(&memory_system, "").await.is_err());
+    }
+}
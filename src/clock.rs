@@ -0,0 +1,167 @@
+//! Injectable time and ID sources
+//!
+//! Decay, recency boosts, TTLs and maintenance cutoffs all depend on "now". The
+//! store, cortex, working memory and maintenance read it from a [`Clock`] and mint
+//! IDs from an [`IdGenerator`], so tests can swap in a [`MockClock`] and
+//! [`SequentialIds`] and get the same result on every run:
+//!
+//! ```rust,ignore
+//! let clock = Arc::new(MockClock::default());
+//! let memory = MemorySystem::new("./data")
+//!     .await?
+//!     .with_clock(clock.clone())
+//!     .with_id_generator(Arc::new(SequentialIds::new("mem")));
+//!
+//! memory.save(&memory.new_memory("Standup is at 9", MemoryType::Fact)).await?;
+//! clock.advance(Duration::days(30));
+//! memory.run_maintenance(&MaintenanceConfig::default()).await?;
+//! ```
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Source of the current time
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl Default for MockClock {
+    /// Starts at 2024-01-01 00:00:00 UTC
+    fn default() -> Self {
+        Self::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    }
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DateTime<Utc>> {
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Move the clock forward (or back, for a negative duration)
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.lock();
+        *now += by;
+    }
+
+    /// Jump to a specific time
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.lock() = at;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.lock()
+    }
+}
+
+/// Source of new memory, episode and association IDs
+pub trait IdGenerator: Send + Sync + std::fmt::Debug {
+    fn next_id(&self) -> String;
+}
+
+/// Random v4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidIds;
+
+impl IdGenerator for UuidIds {
+    fn next_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+/// `{prefix}-1`, `{prefix}-2`, ... Clones share the same counter.
+#[derive(Debug, Clone)]
+pub struct SequentialIds {
+    prefix: String,
+    next: Arc<AtomicU64>,
+}
+
+impl SequentialIds {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: Arc::new(AtomicU64::new(1)),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self) -> String {
+        format!(
+            "{}-{}",
+            self.prefix,
+            self.next.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, IdGenerator, MockClock, SequentialIds};
+    use crate::{MaintenanceConfigBuilder, MemoryStore, MemoryType};
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    #[test]
+    fn mock_clock_and_ids_are_deterministic() {
+        let clock = MockClock::default();
+        let start = clock.now();
+        clock.clone().advance(Duration::hours(2));
+        assert_eq!(clock.now() - start, Duration::hours(2));
+
+        let ids = SequentialIds::new("m");
+        assert_eq!(ids.next_id(), "m-1");
+        assert_eq!(ids.clone().next_id(), "m-2");
+    }
+
+    #[tokio::test]
+    async fn maintenance_follows_the_mock_clock() {
+        let clock = Arc::new(MockClock::default());
+        let store = MemoryStore::connect_in_memory()
+            .await
+            .with_clock(clock.clone())
+            .with_id_generator(Arc::new(SequentialIds::new("mem")));
+
+        let memory = store
+            .new_memory("Scratch note", MemoryType::Fact)
+            .with_importance(0.05);
+        assert_eq!(memory.id, "mem-1");
+        assert_eq!(memory.created_at, clock.now());
+        store.save(&memory).await.unwrap();
+
+        let config = MaintenanceConfigBuilder::new()
+            .prune_threshold(0.1)
+            .min_age_days(30)
+            .build();
+        let report = crate::run_maintenance(&store, &config).await.unwrap();
+        assert_eq!(report.pruned, 0);
+
+        clock.advance(Duration::days(31));
+        let report = crate::run_maintenance(&store, &config).await.unwrap();
+        assert_eq!(report.pruned, 1);
+    }
+}
@@ -0,0 +1,825 @@
+//! Scheduled ingestion connectors
+//!
+//! A [`Connector`] polls an external source and yields [`CreateMemoryInput`]
+//! items plus an opaque checkpoint. The [`ConnectorScheduler`] runs each
+//! connector on its own interval, saves the items through
+//! [`MemorySystem::create`] and persists the checkpoint per namespace, so a
+//! restarted agent continues where it stopped. Delivery is at-least-once: if
+//! saving fails part way, the checkpoint is not advanced and the batch is
+//! polled again.
+//!
+//! Two connectors are built in. [`FeedConnector`] reads RSS and Atom feeds and
+//! [`JsonHttpConnector`] reads an array of items from a JSON HTTP endpoint.
+//! Both need the `connectors` feature to fetch over HTTP.
+//!
+//! ```rust,ignore
+//! let handle = ConnectorScheduler::new(memory.clone())
+//!     .with_connector(FeedConnector::new("rust-blog", "https://blog.rust-lang.org/feed.xml"))
+//!     .with_connector(
+//!         JsonHttpConnector::new("tickets", "https://tracker.example.com/api/recent")
+//!             .with_items_pointer("/data")
+//!             .with_content_pointer("/summary"),
+//!     )
+//!     .spawn();
+//! // ...
+//! handle.stop();
+//! ```
+
+use crate::cortex::Experience;
+use crate::error::{MemoryError, Result};
+use crate::sources::content_hash;
+use crate::types::{CreateMemoryInput, MemoryId, MemoryType};
+use crate::MemorySystem;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Default time between polls
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How many item IDs the built-in connectors remember to skip repeats
+const SEEN_IDS_LIMIT: usize = 1000;
+
+/// Items found by one poll
+#[derive(Debug, Clone, Default)]
+pub struct ConnectorBatch {
+    pub items: Vec<CreateMemoryInput>,
+    /// Saved once every item is stored and passed to the next poll; `None`
+    /// keeps the previous checkpoint
+    pub checkpoint: Option<String>,
+    /// Items to group into episodes, e.g. the messages of one thread
+    pub episodes: Vec<ConnectorEpisode>,
+}
+
+/// Items of a batch that belong to one episode. Episodes are keyed per
+/// connector, so items from later polls join the same episode.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectorEpisode {
+    /// Stable key from the source, e.g. a thread ID
+    pub key: String,
+    /// Title of the episode when it is first created
+    pub title: String,
+    /// Indices into [`ConnectorBatch::items`]
+    pub items: Vec<usize>,
+}
+
+/// A source of memories polled on a schedule
+#[async_trait]
+pub trait Connector: Send + Sync {
+    /// Unique name, used as the checkpoint key
+    fn name(&self) -> &str;
+
+    /// Time between polls
+    fn interval(&self) -> Duration {
+        DEFAULT_POLL_INTERVAL
+    }
+
+    /// Fetch items that are new since `checkpoint` (`None` on the first poll)
+    async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch>;
+}
+
+/// Outcome of running a connector once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectorRun {
+    pub connector: String,
+    pub started_at: DateTime<Utc>,
+    /// IDs of the memories saved
+    pub ingested: Vec<MemoryId>,
+    /// Why the poll or a save failed
+    pub error: Option<String>,
+}
+
+/// Runs connectors and stores what they find
+pub struct ConnectorScheduler {
+    memory: MemorySystem,
+    connectors: Vec<Arc<dyn Connector>>,
+}
+
+impl ConnectorScheduler {
+    pub fn new(memory: MemorySystem) -> Self {
+        Self {
+            memory,
+            connectors: Vec::new(),
+        }
+    }
+
+    pub fn with_connector(mut self, connector: impl Connector + 'static) -> Self {
+        self.connectors.push(Arc::new(connector));
+        self
+    }
+
+    pub fn add_connector(&mut self, connector: Arc<dyn Connector>) {
+        self.connectors.push(connector);
+    }
+
+    pub fn connectors(&self) -> &[Arc<dyn Connector>] {
+        &self.connectors
+    }
+
+    /// Poll every connector once, in order
+    pub async fn run_once(&self) -> Vec<ConnectorRun> {
+        let mut runs = Vec::with_capacity(self.connectors.len());
+        for connector in &self.connectors {
+            runs.push(run_connector(&self.memory, connector.as_ref()).await);
+        }
+        runs
+    }
+
+    /// Poll each connector on its own interval in background tasks, starting now
+    pub fn spawn(self) -> ConnectorHandle {
+        let tasks = self
+            .connectors
+            .into_iter()
+            .map(|connector| {
+                let memory = self.memory.clone();
+                tokio::spawn(async move {
+                    let mut ticks = tokio::time::interval(connector.interval());
+                    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    loop {
+                        ticks.tick().await;
+                        run_connector(&memory, connector.as_ref()).await;
+                    }
+                })
+            })
+            .collect();
+        ConnectorHandle { tasks }
+    }
+}
+
+/// Background connector tasks started by [`ConnectorScheduler::spawn`]
+pub struct ConnectorHandle {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ConnectorHandle {
+    /// Stop polling. A save in progress is abandoned and its batch polled again
+    /// on the next start.
+    pub fn stop(self) {
+        for task in self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn run_connector(memory: &MemorySystem, connector: &dyn Connector) -> ConnectorRun {
+    let name = connector.name().to_string();
+    let mut run = ConnectorRun {
+        connector: name.clone(),
+        started_at: memory.store.now(),
+        ingested: Vec::new(),
+        error: None,
+    };
+
+    let result: Result<()> = async {
+        let checkpoint = memory.store.connector_checkpoint(&name).await?;
+        let batch = connector.poll(checkpoint.as_deref()).await?;
+        for item in batch.items {
+            run.ingested.push(memory.create(item).await?.id);
+        }
+        for episode in &batch.episodes {
+            let ids: Vec<&MemoryId> = episode
+                .items
+                .iter()
+                .filter_map(|&i| run.ingested.get(i))
+                .collect();
+            add_to_episode(memory, &name, episode, &ids).await?;
+        }
+        if let Some(checkpoint) = batch.checkpoint {
+            memory
+                .store
+                .set_connector_checkpoint(&name, &checkpoint)
+                .await?;
+        }
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) if !run.ingested.is_empty() => {
+            tracing::info!("Connector '{}' ingested {} items", name, run.ingested.len());
+        }
+        Ok(()) => {}
+        Err(e) => {
+            tracing::warn!("Connector '{}' failed: {}", name, e);
+            run.error = Some(e.to_string());
+        }
+    }
+    run
+}
+
+/// Add memories to the episode for `episode.key`, creating it on first use
+async fn add_to_episode(
+    memory: &MemorySystem,
+    connector: &str,
+    episode: &ConnectorEpisode,
+    ids: &[&MemoryId],
+) -> Result<()> {
+    let store = &memory.store;
+    let experience_id = match store.connector_episode(connector, &episode.key).await? {
+        Some(id) => id,
+        None => {
+            let mut experience = Experience::new(episode.title.clone(), connector);
+            experience.id = store.next_id();
+            experience.started_at = store.now();
+            store.save_experience(&experience).await?;
+            store
+                .set_connector_episode(connector, &episode.key, &experience.id)
+                .await?;
+            experience.id
+        }
+    };
+
+    for id in ids {
+        store.add_memory_to_experience(&experience_id, id).await?;
+    }
+    Ok(())
+}
+
+/// Recently ingested item IDs, stored as the checkpoint of the built-in
+/// connectors. Works whatever order a source lists its items in.
+#[derive(Debug, Clone, Default)]
+struct SeenIds(VecDeque<String>);
+
+impl SeenIds {
+    fn from_checkpoint(checkpoint: Option<&str>) -> Self {
+        Self(
+            checkpoint
+                .and_then(|c| serde_json::from_str(c).ok())
+                .unwrap_or_default(),
+        )
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.0.iter().any(|seen| seen == id)
+    }
+
+    fn insert(&mut self, id: String) {
+        self.0.push_back(id);
+        while self.0.len() > SEEN_IDS_LIMIT {
+            self.0.pop_front();
+        }
+    }
+
+    fn to_checkpoint(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_default()
+    }
+}
+
+// ─── RSS / Atom ────────────────────────────────────────────────────────────
+
+/// An RSS `<item>` or Atom `<entry>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedEntry {
+    /// `<guid>` or `<id>`, falling back to the link, then a hash of the entry
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// `<description>`, `<summary>` or `<content>`, with markup removed
+    pub summary: String,
+    /// `<pubDate>`, `<published>` or `<updated>`, as written in the feed
+    pub published: Option<String>,
+}
+
+/// Parse the entries of an RSS or Atom feed. Malformed entries are skipped.
+pub fn parse_feed(xml: &str) -> Vec<FeedEntry> {
+    let (tag, blocks) = if xml.contains("<entry") {
+        ("entry", elements(xml, "entry"))
+    } else {
+        ("item", elements(xml, "item"))
+    };
+
+    blocks
+        .into_iter()
+        .filter_map(|block| {
+            let title = element_text(block, "title").unwrap_or_default();
+            let summary = ["description", "summary", "content", "content:encoded"]
+                .iter()
+                .find_map(|name| element_text(block, name))
+                .map(|text| strip_markup(&text))
+                .unwrap_or_default();
+            if title.is_empty() && summary.is_empty() {
+                return None;
+            }
+
+            let link = element_text(block, "link")
+                .filter(|l| !l.is_empty())
+                .or_else(|| element_attr(block, "link", "href"));
+            let id = element_text(block, if tag == "entry" { "id" } else { "guid" })
+                .filter(|id| !id.is_empty())
+                .or_else(|| link.clone())
+                .unwrap_or_else(|| content_hash(block));
+            let published = ["pubDate", "published", "updated", "dc:date"]
+                .iter()
+                .find_map(|name| element_text(block, name));
+
+            Some(FeedEntry {
+                id,
+                title,
+                link,
+                summary,
+                published,
+            })
+        })
+        .collect()
+}
+
+/// The inner text of each `<name ...>...</name>` element
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let close = format!("</{name}>");
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some((_, body_start)) = open_tag(rest, name) {
+        let body = &rest[body_start..];
+        let Some(end) = body.find(&close) else {
+            break;
+        };
+        found.push(&body[..end]);
+        rest = &body[end + close.len()..];
+    }
+    found
+}
+
+/// Position of the next `<name>` or `<name attr...>` start tag and of the text after it
+fn open_tag(xml: &str, name: &str) -> Option<(usize, usize)> {
+    let open = format!("<{name}");
+    let mut offset = 0;
+    while let Some(pos) = xml[offset..].find(&open) {
+        let start = offset + pos;
+        let after = start + open.len();
+        match xml[after..].chars().next() {
+            Some('>') => return Some((start, after + 1)),
+            Some(c) if c.is_whitespace() || c == '/' => {
+                let end = after + xml[after..].find('>')?;
+                return Some((start, end + 1));
+            }
+            _ => offset = after,
+        }
+    }
+    None
+}
+
+/// Decoded text of the first `<name>` element
+fn element_text(xml: &str, name: &str) -> Option<String> {
+    let (start, body_start) = open_tag(xml, name)?;
+    if xml[start..body_start].ends_with("/>") {
+        return None;
+    }
+    let close = format!("</{name}>");
+    let end = body_start + xml[body_start..].find(&close)?;
+    Some(decode_text(&xml[body_start..end]))
+}
+
+/// Value of `attr` on the first `<name>` element
+fn element_attr(xml: &str, name: &str, attr: &str) -> Option<String> {
+    let (start, body_start) = open_tag(xml, name)?;
+    let tag = &xml[start..body_start];
+    let key = format!("{attr}=");
+    let value = &tag[tag.find(&key)? + key.len()..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(decode_entities(&value[..value.find(quote)?]))
+}
+
+fn decode_text(text: &str) -> String {
+    let text = text.trim();
+    match text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.trim().to_string(),
+        None => decode_entities(text),
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Remove HTML tags and collapse whitespace
+fn strip_markup(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Polls an RSS or Atom feed. Each new entry becomes a memory of its title and
+/// summary, with the entry link as source.
+#[derive(Debug, Clone)]
+pub struct FeedConnector {
+    name: String,
+    url: String,
+    interval: Duration,
+    memory_type: Option<MemoryType>,
+    importance: Option<f32>,
+    timeout: Duration,
+}
+
+impl FeedConnector {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            interval: DEFAULT_POLL_INTERVAL,
+            memory_type: None,
+            importance: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Type of the memories; unset leaves it to the classifier
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_type = Some(memory_type);
+        self
+    }
+
+    pub fn with_importance(mut self, importance: f32) -> Self {
+        self.importance = Some(importance);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Turn parsed entries into a batch, skipping entries seen before
+    pub fn batch(&self, entries: Vec<FeedEntry>, checkpoint: Option<&str>) -> ConnectorBatch {
+        let mut seen = SeenIds::from_checkpoint(checkpoint);
+        let mut items = Vec::new();
+        for entry in entries {
+            if seen.contains(&entry.id) {
+                continue;
+            }
+            let content = match (entry.title.is_empty(), entry.summary.is_empty()) {
+                (false, false) => format!("{}\n\n{}", entry.title, entry.summary),
+                (false, true) => entry.title.clone(),
+                _ => entry.summary.clone(),
+            };
+            let mut input = match self.memory_type {
+                Some(memory_type) => CreateMemoryInput::new(content, memory_type),
+                None => CreateMemoryInput::untyped(content),
+            }
+            .with_source(entry.link.clone().unwrap_or_else(|| self.url.clone()))
+            .with_metadata(serde_json::json!({
+                "connector": self.name,
+                "feed_url": self.url,
+                "entry_id": entry.id,
+                "published": entry.published,
+            }));
+            if let Some(importance) = self.importance {
+                input = input.with_importance(importance);
+            }
+            items.push(input);
+            seen.insert(entry.id);
+        }
+
+        ConnectorBatch {
+            items,
+            checkpoint: Some(seen.to_checkpoint()),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for FeedConnector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch> {
+        let body = fetch(&self.url, &[], self.timeout).await?;
+        Ok(self.batch(parse_feed(&body), checkpoint))
+    }
+}
+
+// ─── JSON HTTP ─────────────────────────────────────────────────────────────
+
+/// Polls a JSON endpoint that returns an array of items. Fields are picked with
+/// JSON pointers (RFC 6901), e.g. `/data` and `/fields/body`.
+#[derive(Debug, Clone)]
+pub struct JsonHttpConnector {
+    name: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    interval: Duration,
+    /// Where the item array is in the response; empty for a top-level array
+    items_pointer: String,
+    content_pointer: String,
+    /// Item ID; items without one are identified by a hash of their content
+    id_pointer: String,
+    memory_type: Option<MemoryType>,
+    timeout: Duration,
+}
+
+impl JsonHttpConnector {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            headers: Vec::new(),
+            interval: DEFAULT_POLL_INTERVAL,
+            items_pointer: String::new(),
+            content_pointer: "/content".to_string(),
+            id_pointer: "/id".to_string(),
+            memory_type: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Send a header with every request, e.g. `Authorization`
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_items_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.items_pointer = pointer.into();
+        self
+    }
+
+    pub fn with_content_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.content_pointer = pointer.into();
+        self
+    }
+
+    pub fn with_id_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.id_pointer = pointer.into();
+        self
+    }
+
+    /// Type of the memories; unset leaves it to the classifier
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_type = Some(memory_type);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Turn a response body into a batch, skipping items seen before and items
+    /// without text content
+    pub fn batch(
+        &self,
+        body: &serde_json::Value,
+        checkpoint: Option<&str>,
+    ) -> Result<ConnectorBatch> {
+        let items = body
+            .pointer(&self.items_pointer)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                MemoryError::Connector(format!(
+                    "{}: no array at '{}' in response",
+                    self.name, self.items_pointer
+                ))
+            })?;
+
+        let mut seen = SeenIds::from_checkpoint(checkpoint);
+        let mut batch = Vec::new();
+        for item in items {
+            let Some(content) = item
+                .pointer(&self.content_pointer)
+                .and_then(|v| v.as_str())
+                .filter(|c| !c.trim().is_empty())
+            else {
+                continue;
+            };
+            let id = match item.pointer(&self.id_pointer) {
+                Some(serde_json::Value::String(id)) => id.clone(),
+                Some(serde_json::Value::Number(id)) => id.to_string(),
+                _ => content_hash(content),
+            };
+            if seen.contains(&id) {
+                continue;
+            }
+
+            let input = match self.memory_type {
+                Some(memory_type) => CreateMemoryInput::new(content, memory_type),
+                None => CreateMemoryInput::untyped(content),
+            }
+            .with_source(self.url.clone())
+            .with_metadata(serde_json::json!({
+                "connector": self.name,
+                "item_id": id,
+            }));
+            batch.push(input);
+            seen.insert(id);
+        }
+
+        Ok(ConnectorBatch {
+            items: batch,
+            checkpoint: Some(seen.to_checkpoint()),
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl Connector for JsonHttpConnector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch> {
+        let body = fetch(&self.url, &self.headers, self.timeout).await?;
+        let body: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| MemoryError::Connector(format!("{}: invalid JSON: {e}", self.name)))?;
+        self.batch(&body, checkpoint)
+    }
+}
+
+/// GET `url` and return the response body
+#[cfg(feature = "connectors")]
+pub(crate) async fn fetch(
+    url: &str,
+    headers: &[(String, String)],
+    timeout: Duration,
+) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| MemoryError::Connector(format!("GET {url} failed: {e}")))?
+        .text()
+        .await
+        .map_err(|e| MemoryError::Connector(format!("GET {url} failed: {e}")))
+}
+
+#[cfg(not(feature = "connectors"))]
+pub(crate) async fn fetch(
+    url: &str,
+    _headers: &[(String, String)],
+    _timeout: Duration,
+) -> Result<String> {
+    Err(MemoryError::Configuration(format!(
+        "Fetching {url} requires the `connectors` feature"
+    )))
+}
+
+/// Send a JSON `body` with `method` (e.g. `POST`, `PATCH`) and return the JSON response
+#[cfg(feature = "connectors")]
+pub(crate) async fn send_json(
+    method: &str,
+    url: &str,
+    headers: &[(String, String)],
+    body: &serde_json::Value,
+    timeout: Duration,
+) -> Result<serde_json::Value> {
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|e| MemoryError::Configuration(format!("Invalid HTTP method {method}: {e}")))?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+    let mut request = client.request(method.clone(), url).json(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| MemoryError::Connector(format!("{method} {url} failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| MemoryError::Connector(format!("{method} {url} failed: {e}")))
+}
+
+#[cfg(not(feature = "connectors"))]
+pub(crate) async fn send_json(
+    method: &str,
+    url: &str,
+    _headers: &[(String, String)],
+    _body: &serde_json::Value,
+    _timeout: Duration,
+) -> Result<serde_json::Value> {
+    Err(MemoryError::Configuration(format!(
+        "{method} {url} requires the `connectors` feature"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_feed, Connector, ConnectorBatch, ConnectorScheduler, FeedConnector};
+    use crate::{CreateMemoryInput, MemorySystem, MemoryType, Result};
+    use async_trait::async_trait;
+
+    #[test]
+    fn parses_rss_and_atom() {
+        let rss = r#"<rss><channel><title>Blog</title>
+            <item><title>Release 1.2</title><link>https://x.dev/1.2</link>
+              <guid isPermaLink="false">rel-1.2</guid>
+              <description><![CDATA[<p>Faster <b>search</b></p>]]></description></item>
+            <item><title>Q&amp;A</title><link>https://x.dev/qa</link></item>
+            </channel></rss>"#;
+        let entries = parse_feed(rss);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "rel-1.2");
+        assert_eq!(entries[0].summary, "Faster search");
+        assert_eq!(entries[1].title, "Q&A");
+        assert_eq!(entries[1].id, "https://x.dev/qa");
+
+        let atom = r#"<feed><title>Log</title>
+            <entry><id>tag:x,1</id><title>Outage</title>
+              <link rel="alternate" href="https://x.dev/outage"/>
+              <summary>DB failover at 02:00</summary><updated>2026-01-02</updated></entry>
+            </feed>"#;
+        let entries = parse_feed(atom);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].link.as_deref(), Some("https://x.dev/outage"));
+        assert_eq!(entries[0].published.as_deref(), Some("2026-01-02"));
+
+        let connector = FeedConnector::new("log", "https://x.dev/atom");
+        let first = connector.batch(entries.clone(), None);
+        assert_eq!(first.items.len(), 1);
+        let again = connector.batch(entries, first.checkpoint.as_deref());
+        assert!(again.items.is_empty());
+    }
+
+    /// Yields one item per poll, numbered from the checkpoint
+    struct Counter;
+
+    #[async_trait]
+    impl Connector for Counter {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch> {
+            let n: u32 = checkpoint.and_then(|c| c.parse().ok()).unwrap_or(0) + 1;
+            Ok(ConnectorBatch {
+                items: vec![CreateMemoryInput::new(
+                    format!("tick {n}"),
+                    MemoryType::Event,
+                )],
+                checkpoint: Some(n.to_string()),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn scheduler_ingests_and_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(dir.path()).await.unwrap();
+        let scheduler = ConnectorScheduler::new(memory.clone()).with_connector(Counter);
+
+        scheduler.run_once().await;
+        let runs = scheduler.run_once().await;
+        assert!(runs[0].error.is_none());
+        let second = memory.load(&runs[0].ingested[0]).await.unwrap().unwrap();
+        assert_eq!(second.content, "tick 2");
+        assert_eq!(
+            memory.store.connector_checkpoint("counter").await.unwrap(),
+            Some("2".to_string())
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Reproducible context windows
+//!
+//! [`ContextWindow::build_traced`] renders a context window like
+//! [`build`](ContextWindow::build) and also records a [`ContextTrace`]: the
+//! rendered text, its content hash and the exact version of every memory that
+//! went into it. Traces are kept in the `context_traces` table, so the memory
+//! inputs of a past LLM call can be looked up by the hash logged with the call
+//! and reconstructed with [`MemoryStore::trace_memories`], even after the
+//! memories were edited or forgotten.
+//!
+//! ```rust,ignore
+//! let trace = ContextWindow::new(2000).build_traced(&cortex).await?;
+//! tracing::info!(context_hash = %trace.content_hash, "calling model");
+//!
+//! // During incident review
+//! let trace = store.context_traces_by_hash(&hash).await?.remove(0);
+//! let inputs = store.trace_memories(&trace).await?;
+//! ```
+//!
+//! [`ContextWindow::build_traced`]: crate::ContextWindow::build_traced
+//! [`ContextWindow`]: crate::ContextWindow
+//! [`MemoryStore::trace_memories`]: crate::MemoryStore::trace_memories
+
+use crate::embedding::{fnv1a, FNV_OFFSET};
+use crate::types::MemoryId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One memory as it was when a context window was built
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracedMemory {
+    pub memory_id: MemoryId,
+    /// Version number in the memory's history; `None` for working-memory
+    /// items without a stored memory
+    pub version: Option<u32>,
+}
+
+/// A built context window and everything needed to rebuild its inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextTrace {
+    pub id: String,
+    /// [`context_hash`] of `context`
+    pub content_hash: String,
+    /// The rendered context, exactly as returned to the caller
+    pub context: String,
+    /// Memories included, in render order
+    pub memories: Vec<TracedMemory>,
+    /// The current experience, when its section was rendered
+    pub experience_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stable hash of a rendered context, as 16 hex digits
+pub fn context_hash(context: &str) -> String {
+    format!("{:016x}", fnv1a(context.as_bytes(), FNV_OFFSET))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_contexts_share_a_hash() {
+        let a = context_hash("## Important Memories\n- [Fact] Deploys on Tuesdays\n");
+        let b = context_hash("## Important Memories\n- [Fact] Deploys on Tuesdays\n");
+        let c = context_hash("## Important Memories\n- [Fact] Deploys on Fridays\n");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 16);
+    }
+}
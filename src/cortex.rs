@@ -7,15 +7,34 @@
 //! - Context Windows: Token-budgeted context for LLMs
 //! - Memory Summaries: Consolidation of old memories
 
+use crate::clock::{Clock, IdGenerator, SystemClock};
+use crate::context_trace::{context_hash, ContextTrace, TracedMemory};
+use crate::deadline::{Deadline, TimedResults};
+use crate::decisions::{DecisionOutcome, DecisionRecord, DECISION_METADATA_KEY};
 use crate::error::{MemoryError, Result};
+use crate::injection::{InjectionScreen, Screened};
+use crate::language::{detect_language, normalize_language, Translator};
+use crate::manifest::DataDirManifest;
+use crate::memory_query::MemoryQuery;
+use crate::middleware::{MemoryMiddleware, MiddlewareChain};
+use crate::pulses::{AttentionItem, GoldfishPulses, Pulse};
 use crate::store::SortOrder;
+use crate::store_generation::{Generational, StoreGeneration};
+use crate::temporal::{
+    RecurrenceDetector, RecurringPattern, RECURRENCE_METADATA_KEY, RECURRING_TAG,
+};
 use crate::types::{Association, Memory, MemoryId, MemorySearchResult, MemoryType, RelationType};
-use crate::vector_backend::{FileVectorBackend, VectorBackend};
-use crate::vector_search::generate_embedding;
+use crate::validation::ContentValidation;
+use crate::vector_backend::{
+    vector_payload, FileVectorBackend, VectorBackend, VectorIndexStats, VectorMaintenanceReport,
+};
+use crate::vector_chunks::{aggregate_chunk_hits, chunk_vector_id};
+use crate::vector_search::{generate_embedding, EMBEDDING_DIM};
 use crate::MemoryStore;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -28,6 +47,9 @@ use tokio::sync::RwLock;
 pub struct WorkingMemory {
     items: Vec<WorkingMemoryItem>,
     max_items: usize,
+    clock: Arc<dyn Clock>,
+    auto_unpin_after: Option<Duration>,
+    pin_warning_after: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +63,20 @@ pub struct WorkingMemoryItem {
     pub expires_at: Option<DateTime<Utc>>,
     /// Pinned items survive decay and eviction
     pub pinned: bool,
+    /// When the item was last pinned
+    #[serde(default)]
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// If set, the pin lapses at this time; the item stays, unpinned
+    #[serde(default)]
+    pub pin_expires_at: Option<DateTime<Utc>>,
+}
+
+/// A pin older than the configured warning age
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StalePin {
+    pub memory_id: MemoryId,
+    pub content: String,
+    pub pinned_at: DateTime<Utc>,
 }
 
 impl WorkingMemory {
@@ -48,15 +84,37 @@ impl WorkingMemory {
         Self {
             items: Vec::new(),
             max_items,
+            clock: Arc::new(SystemClock),
+            auto_unpin_after: None,
+            pin_warning_after: None,
         }
     }
 
+    /// Unpin items that have been pinned for longer than `max_age`
+    pub fn with_auto_unpin_after(mut self, max_age: Option<Duration>) -> Self {
+        self.auto_unpin_after = max_age;
+        self
+    }
+
+    /// Report pins older than `age` from [`stale_pins`](Self::stale_pins)
+    pub fn with_pin_warning_after(mut self, age: Option<Duration>) -> Self {
+        self.pin_warning_after = age;
+        self
+    }
+
+    /// Read TTLs and access times from `clock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Add or update an item in working memory with optional TTL
     pub fn remember(&mut self, memory: &Memory, ttl: Option<Duration>) {
-        let expires_at = ttl.map(|d| Utc::now() + d);
+        let now = self.clock.now();
+        let expires_at = ttl.map(|d| now + d);
 
         if let Some(item) = self.items.iter_mut().find(|i| i.memory_id == memory.id) {
-            item.accessed_at = Utc::now();
+            item.accessed_at = now;
             item.attention_score = (item.attention_score + 0.1).min(1.0);
             item.content = memory.content.clone();
             if let Some(exp) = expires_at {
@@ -67,10 +125,12 @@ impl WorkingMemory {
                 memory_id: memory.id.clone(),
                 content: memory.content.clone(),
                 memory_type: memory.memory_type,
-                accessed_at: Utc::now(),
+                accessed_at: now,
                 attention_score: 0.5,
                 expires_at,
                 pinned: false,
+                pinned_at: None,
+                pin_expires_at: None,
             });
         }
 
@@ -81,7 +141,7 @@ impl WorkingMemory {
     pub fn focus(&mut self, memory_id: &str) -> bool {
         if let Some(item) = self.items.iter_mut().find(|i| i.memory_id == memory_id) {
             item.attention_score = 1.0;
-            item.accessed_at = Utc::now();
+            item.accessed_at = self.clock.now();
             true
         } else {
             false
@@ -90,8 +150,16 @@ impl WorkingMemory {
 
     /// Pin a memory so it survives decay and eviction
     pub fn pin(&mut self, memory_id: &str) -> bool {
+        self.pin_for(memory_id, None)
+    }
+
+    /// Pin a memory; with a `ttl`, the pin lapses after that long
+    pub fn pin_for(&mut self, memory_id: &str, ttl: Option<Duration>) -> bool {
+        let now = self.clock.now();
         if let Some(item) = self.items.iter_mut().find(|i| i.memory_id == memory_id) {
             item.pinned = true;
+            item.pinned_at = Some(now);
+            item.pin_expires_at = ttl.map(|d| now + d);
             true
         } else {
             false
@@ -102,17 +170,76 @@ impl WorkingMemory {
     pub fn unpin(&mut self, memory_id: &str) -> bool {
         if let Some(item) = self.items.iter_mut().find(|i| i.memory_id == memory_id) {
             item.pinned = false;
+            item.pinned_at = None;
+            item.pin_expires_at = None;
             true
         } else {
             false
         }
     }
 
+    /// Whether the item's pin has passed its TTL or the auto-unpin age
+    fn pin_lapsed(&self, item: &WorkingMemoryItem, now: DateTime<Utc>) -> bool {
+        item.pinned
+            && (item.pin_expires_at.is_some_and(|exp| exp <= now)
+                || self
+                    .auto_unpin_after
+                    .zip(item.pinned_at)
+                    .is_some_and(|(max_age, at)| at + max_age <= now))
+    }
+
+    /// Unpin items whose pin lapsed. Returns how many were unpinned.
+    pub fn expire_pins(&mut self) -> usize {
+        let now = self.clock.now();
+        let lapsed: Vec<MemoryId> = self
+            .items
+            .iter()
+            .filter(|i| self.pin_lapsed(i, now))
+            .map(|i| i.memory_id.clone())
+            .collect();
+        for id in &lapsed {
+            self.unpin(id);
+        }
+        lapsed.len()
+    }
+
+    /// Live pins older than the warning age, oldest first
+    pub fn stale_pins(&self) -> Vec<StalePin> {
+        let Some(warn_after) = self.pin_warning_after else {
+            return Vec::new();
+        };
+        let now = self.clock.now();
+        let mut stale: Vec<StalePin> = self
+            .items
+            .iter()
+            .filter(|i| i.pinned && !self.pin_lapsed(i, now))
+            .filter_map(|i| {
+                let pinned_at = i.pinned_at.filter(|at| *at + warn_after <= now)?;
+                Some(StalePin {
+                    memory_id: i.memory_id.clone(),
+                    content: i.content.clone(),
+                    pinned_at,
+                })
+            })
+            .collect();
+        stale.sort_by_key(|p| p.pinned_at);
+        stale
+    }
+
+    /// Drop a memory from working memory, pinned or not
+    pub fn remove(&mut self, memory_id: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|i| i.memory_id != memory_id);
+        self.items.len() != before
+    }
+
     /// Get current context (what agent is thinking about)
-    /// Returns pinned items first, then by attention score, filtering expired
-    pub fn get_context(&self) -> Vec<&WorkingMemoryItem> {
-        let now = Utc::now();
-        let mut live: Vec<&WorkingMemoryItem> = self
+    /// Returns pinned items first, then by attention score, filtering expired.
+    /// Items whose pin has lapsed come back unpinned, even before
+    /// [`expire_pins`](Self::expire_pins) runs.
+    pub fn get_context(&self) -> Vec<WorkingMemoryItem> {
+        let now = self.clock.now();
+        let mut live: Vec<WorkingMemoryItem> = self
             .items
             .iter()
             .filter(|i| {
@@ -122,6 +249,15 @@ impl WorkingMemory {
                     true
                 }
             })
+            .map(|i| {
+                let mut item = i.clone();
+                if self.pin_lapsed(i, now) {
+                    item.pinned = false;
+                    item.pinned_at = None;
+                    item.pin_expires_at = None;
+                }
+                item
+            })
             .collect();
 
         // Pinned first, then by attention score
@@ -147,7 +283,8 @@ impl WorkingMemory {
     /// Decay attention scores (call periodically)
     /// Pinned items don't decay. Expired items are removed.
     pub fn decay(&mut self) {
-        let now = Utc::now();
+        self.expire_pins();
+        let now = self.clock.now();
 
         // Remove expired non-pinned items
         self.items.retain(|i| {
@@ -175,7 +312,8 @@ impl WorkingMemory {
 
     /// Cleanup: remove expired items and enforce capacity
     fn cleanup(&mut self) {
-        let now = Utc::now();
+        self.expire_pins();
+        let now = self.clock.now();
 
         // Remove expired non-pinned items
         self.items.retain(|i| {
@@ -227,6 +365,12 @@ pub struct Experience {
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
     pub importance: f32,
+    /// The episode this one is part of, e.g. the project a session belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Summary rolled up from sub-episodes, see [`MemoryCortex::roll_up_episode`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
 }
 
 impl Experience {
@@ -239,9 +383,16 @@ impl Experience {
             started_at: Utc::now(),
             ended_at: None,
             importance: 0.5,
+            parent_id: None,
+            summary: None,
         }
     }
 
+    pub fn with_parent(mut self, parent_id: impl Into<String>) -> Self {
+        self.parent_id = Some(parent_id.into());
+        self
+    }
+
     pub fn add_memory(&mut self, memory_id: MemoryId) {
         if !self.memory_ids.contains(&memory_id) {
             self.memory_ids.push(memory_id);
@@ -258,6 +409,41 @@ impl Experience {
     }
 }
 
+/// An episode and its sub-episodes, see [`MemoryCortex::episode_tree`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodeTree {
+    pub episode: Experience,
+    /// Sub-episodes, oldest first
+    pub children: Vec<EpisodeTree>,
+}
+
+impl EpisodeTree {
+    fn assemble(episode: Experience, children: &mut HashMap<String, Vec<Experience>>) -> Self {
+        let kids = children.remove(&episode.id).unwrap_or_default();
+        Self {
+            children: kids
+                .into_iter()
+                .map(|kid| Self::assemble(kid, children))
+                .collect(),
+            episode,
+        }
+    }
+
+    /// Number of episodes in the tree, this one included
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(EpisodeTree::count).sum::<usize>()
+    }
+
+    /// Every episode in the tree, depth first, this one first
+    pub fn episodes(&self) -> Vec<&Experience> {
+        let mut episodes = vec![&self.episode];
+        for child in &self.children {
+            episodes.extend(child.episodes());
+        }
+        episodes
+    }
+}
+
 // ─── Importance Scoring ───────────────────────────────────────────────────────
 
 /// Configurable weights for importance calculation
@@ -304,11 +490,16 @@ impl ImportanceCalculator {
 
     /// Calculate with custom weights
     pub fn calculate_with_weights(memory: &Memory, weights: &ImportanceWeights) -> f32 {
+        Self::calculate_at(memory, weights, Utc::now())
+    }
+
+    /// Calculate with custom weights as of `now`
+    pub fn calculate_at(memory: &Memory, weights: &ImportanceWeights, now: DateTime<Utc>) -> f32 {
         // Base importance from the memory itself
         let base = memory.importance;
 
         // Recency: exponential decay e^(-λt) where t is hours since last access
-        let hours_since_access = (Utc::now() - memory.last_accessed_at).num_hours() as f32;
+        let hours_since_access = (now - memory.last_accessed_at).num_hours() as f32;
         let recency = (-weights.decay_lambda * hours_since_access).exp();
 
         // Access frequency: logarithmic scaling
@@ -340,8 +531,13 @@ impl ImportanceCalculator {
 
     /// Calculate importance with query relevance factored in
     pub fn calculate_with_query(memory: &Memory, query: &str) -> f32 {
+        Self::calculate_with_query_at(memory, query, Utc::now())
+    }
+
+    /// Calculate importance with query relevance factored in, as of `now`
+    pub fn calculate_with_query_at(memory: &Memory, query: &str, now: DateTime<Utc>) -> f32 {
         let weights = ImportanceWeights::default();
-        let base_score = Self::calculate_with_weights(memory, &weights);
+        let base_score = Self::calculate_at(memory, &weights, now);
 
         // Simple word overlap relevance
         let query_lower = query.to_lowercase();
@@ -365,8 +561,13 @@ impl ImportanceCalculator {
 
     /// Should this memory be consolidated (summarized)?
     pub fn should_consolidate(memory: &Memory, threshold: f32) -> bool {
-        let age_days = (Utc::now() - memory.created_at).num_days() as f32;
-        let importance = Self::calculate(memory);
+        Self::should_consolidate_at(memory, threshold, Utc::now())
+    }
+
+    /// Should this memory be consolidated (summarized) as of `now`?
+    pub fn should_consolidate_at(memory: &Memory, threshold: f32, now: DateTime<Utc>) -> bool {
+        let age_days = (now - memory.created_at).num_days() as f32;
+        let importance = Self::calculate_at(memory, &ImportanceWeights::default(), now);
 
         age_days > 30.0 && importance < threshold
     }
@@ -374,6 +575,85 @@ impl ImportanceCalculator {
 
 // ─── Context Window ───────────────────────────────────────────────────────────
 
+/// How the "Important Memories" section spreads its slots across memory types
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TypeSelection {
+    /// Highest importance first, whatever the type. Type bonuses tend to fill the
+    /// section with goals and identity.
+    ByImportance,
+    /// The most important remaining memory of each type in turn
+    #[default]
+    RoundRobin,
+    /// Highest importance first, but at most `per_type[t]` memories of type `t`
+    /// (`default` for types not listed)
+    Quotas {
+        per_type: HashMap<MemoryType, usize>,
+        default: usize,
+    },
+}
+
+impl TypeSelection {
+    /// Pick up to `max` of `ranked` (most important first)
+    pub fn select(&self, ranked: Vec<Memory>, max: usize) -> Vec<Memory> {
+        match self {
+            Self::ByImportance => ranked.into_iter().take(max).collect(),
+            Self::RoundRobin => {
+                // Types in order of their best memory, each queue most important first
+                let mut queues: Vec<(MemoryType, std::collections::VecDeque<Memory>)> = Vec::new();
+                for memory in ranked {
+                    match queues.iter_mut().find(|(t, _)| *t == memory.memory_type) {
+                        Some((_, queue)) => queue.push_back(memory),
+                        None => queues.push((memory.memory_type, [memory].into())),
+                    }
+                }
+
+                let mut selected = Vec::new();
+                while selected.len() < max && queues.iter().any(|(_, q)| !q.is_empty()) {
+                    for (_, queue) in &mut queues {
+                        if selected.len() == max {
+                            break;
+                        }
+                        if let Some(memory) = queue.pop_front() {
+                            selected.push(memory);
+                        }
+                    }
+                }
+                selected
+            }
+            Self::Quotas { per_type, default } => {
+                let mut taken: HashMap<MemoryType, usize> = HashMap::new();
+                ranked
+                    .into_iter()
+                    .filter(|m| {
+                        let count = taken.entry(m.memory_type).or_default();
+                        let quota = per_type.get(&m.memory_type).copied().unwrap_or(*default);
+                        if *count < quota {
+                            *count += 1;
+                            true
+                        } else {
+                            false
+                        }
+                    })
+                    .take(max)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A section of a built context window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContextSection {
+    /// Pinned memories and working memory
+    ActiveContext,
+    /// The current experience
+    Experience,
+    /// Highest-importance memories
+    Important,
+    /// Most recently created memories
+    Recent,
+}
+
 /// Context window builder for LLM consumption
 /// Assembles the most relevant memories within a token budget
 #[derive(Debug, Clone)]
@@ -388,6 +668,19 @@ pub struct ContextWindow {
     pub include_important: bool,
     /// Maximum number of important memories to include
     pub max_important: usize,
+    /// How important memories are balanced across types
+    pub type_selection: TypeSelection,
+    /// Sections in render order; the budget is spent in this order
+    pub sections: Vec<ContextSection>,
+    /// Only include memories of these types (empty = all types)
+    pub memory_types: Vec<MemoryType>,
+    /// Token caps for single sections, on top of `max_tokens`
+    pub section_budgets: HashMap<ContextSection, usize>,
+    /// Maximum number of memories in the recent section
+    pub max_recent: usize,
+    /// Conversation language (ISO 639-1); memories in other languages are
+    /// translated when the cortex has a [`Translator`]
+    pub language: Option<String>,
 }
 
 impl Default for ContextWindow {
@@ -398,6 +691,16 @@ impl Default for ContextWindow {
             include_experience: true,
             include_important: true,
             max_important: 10,
+            type_selection: TypeSelection::default(),
+            sections: vec![
+                ContextSection::ActiveContext,
+                ContextSection::Experience,
+                ContextSection::Important,
+            ],
+            memory_types: Vec::new(),
+            section_budgets: HashMap::new(),
+            max_recent: 10,
+            language: None,
         }
     }
 }
@@ -410,6 +713,91 @@ impl ContextWindow {
         }
     }
 
+    pub fn with_type_selection(mut self, selection: TypeSelection) -> Self {
+        self.type_selection = selection;
+        self
+    }
+
+    /// Render these sections, in this order
+    pub fn with_sections(mut self, sections: impl IntoIterator<Item = ContextSection>) -> Self {
+        self.sections = sections.into_iter().collect();
+        self
+    }
+
+    pub fn with_memory_types(mut self, types: impl IntoIterator<Item = MemoryType>) -> Self {
+        self.memory_types = types.into_iter().collect();
+        self
+    }
+
+    /// Spend at most `max_tokens` on `section`
+    pub fn with_section_budget(mut self, section: ContextSection, max_tokens: usize) -> Self {
+        self.section_budgets.insert(section, max_tokens);
+        self
+    }
+
+    pub fn with_max_important(mut self, max: usize) -> Self {
+        self.max_important = max;
+        self
+    }
+
+    pub fn with_max_recent(mut self, max: usize) -> Self {
+        self.max_recent = max;
+        self
+    }
+
+    /// Translate memories into `language` before including them
+    pub fn with_language(mut self, language: impl AsRef<str>) -> Self {
+        self.language = Some(normalize_language(language.as_ref()));
+        self
+    }
+
+    fn includes_type(&self, memory_type: MemoryType) -> bool {
+        self.memory_types.is_empty() || self.memory_types.contains(&memory_type)
+    }
+
+    /// Memories with their content in the window's language
+    async fn localize(&self, cortex: &MemoryCortex, mut memories: Vec<Memory>) -> Vec<Memory> {
+        for memory in &mut memories {
+            let language = memory.language.as_deref();
+            if let Some(text) = self.translated(cortex, &memory.content, language).await {
+                memory.content = text;
+            }
+        }
+        memories
+    }
+
+    /// `content` translated into the window's language, `None` when it is
+    /// already in it, no translation is configured or the translator fails
+    async fn translated(
+        &self,
+        cortex: &MemoryCortex,
+        content: &str,
+        language: Option<&str>,
+    ) -> Option<String> {
+        let (Some(target), Some(translator)) = (&self.language, &cortex.translator) else {
+            return None;
+        };
+        let source = language
+            .map(normalize_language)
+            .or_else(|| detect_language(content));
+        if source.as_deref() == Some(target.as_str()) {
+            return None;
+        }
+        match translator
+            .translate(content, source.as_deref(), target)
+            .await
+        {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!(
+                    "Translator {} failed, keeping the original text: {e}",
+                    translator.name()
+                );
+                None
+            }
+        }
+    }
+
     /// Rough token estimation (~0.75 tokens per character)
     pub fn estimate_tokens(text: &str) -> usize {
         (text.len() as f64 * 0.75).ceil() as usize
@@ -417,87 +805,316 @@ impl ContextWindow {
 
     /// Build context string from the cortex within token budget
     pub async fn build(&self, cortex: &MemoryCortex) -> Result<String> {
-        let mut output = String::new();
+        Ok(self.render(cortex).await?.text)
+    }
+
+    /// Like [`build`](Self::build), also recording which version of each
+    /// memory went into the context in the `context_traces` table, see
+    /// [`context_trace`](crate::context_trace). Memories whose current state
+    /// isn't in their version history yet get a version recorded first.
+    pub async fn build_traced(&self, cortex: &MemoryCortex) -> Result<ContextTrace> {
+        let rendered = self.render(cortex).await?;
+
+        let mut memories = Vec::with_capacity(rendered.memory_ids.len());
+        for memory_id in rendered.memory_ids {
+            let version = cortex.store.traced_version(&memory_id).await?;
+            memories.push(TracedMemory { memory_id, version });
+        }
+
+        let trace = ContextTrace {
+            id: cortex.store.next_id(),
+            content_hash: context_hash(&rendered.text),
+            context: rendered.text,
+            memories,
+            experience_id: rendered.experience_id,
+            created_at: cortex.store.now(),
+        };
+        cortex.store.save_context_trace(&trace).await?;
+        Ok(trace)
+    }
+
+    async fn render(&self, cortex: &MemoryCortex) -> Result<Rendered> {
+        let mut output = Rendered::default();
         let mut remaining_tokens = self.max_tokens;
 
-        // Layer 1: Pinned working memory (always included)
-        if self.include_working_memory {
-            let context_items = cortex.get_context().await;
+        for section in &self.sections {
+            let budget = match self.section_budgets.get(section) {
+                Some(cap) => (*cap).min(remaining_tokens),
+                None => remaining_tokens,
+            };
+            let rendered = match section {
+                ContextSection::ActiveContext => self.active_context(cortex, budget).await?,
+                ContextSection::Experience => self.experience(cortex, budget).await,
+                ContextSection::Important => self.important(cortex, budget).await?,
+                ContextSection::Recent => self.recent(cortex, budget).await?,
+            };
+            remaining_tokens -= rendered.tokens;
+            output.append(rendered);
+        }
 
-            if !context_items.is_empty() {
-                let mut section = String::from("## Active Context\n");
+        Ok(output)
+    }
 
-                // Pinned items first
-                let pinned: Vec<_> = context_items.iter().filter(|i| i.pinned).collect();
-                if !pinned.is_empty() {
-                    section.push_str("### Pinned\n");
-                    for item in &pinned {
-                        let line = format!("- [{}] {}\n", item.memory_type, item.content);
-                        section.push_str(&line);
-                    }
-                }
+    /// Pinned memories and working memory, all or nothing
+    async fn active_context(&self, cortex: &MemoryCortex, budget: usize) -> Result<Rendered> {
+        if !self.include_working_memory {
+            return Ok(Rendered::default());
+        }
 
-                // Other active items
-                let active: Vec<_> = context_items.iter().filter(|i| !i.pinned).collect();
-                if !active.is_empty() {
-                    section.push_str("### Working Memory\n");
-                    for item in &active {
-                        let line = format!(
-                            "- [{}] {} (attn: {:.2})\n",
-                            item.memory_type, item.content, item.attention_score
-                        );
-                        section.push_str(&line);
+        let mut context_items: Vec<_> = cortex
+            .get_context()
+            .await
+            .into_iter()
+            .filter(|i| self.includes_type(i.memory_type))
+            .collect();
+        if let Some(screen) = &cortex.injection_screen {
+            let mut kept = Vec::with_capacity(context_items.len());
+            for mut item in context_items {
+                match screen
+                    .screen_content(&item.memory_id, item.memory_type, &item.content)
+                    .await?
+                {
+                    Screened::Clean => kept.push(item),
+                    Screened::Flagged(content) => {
+                        item.content = content;
+                        kept.push(item);
                     }
+                    Screened::Dropped => {}
                 }
+            }
+            context_items = kept;
+        }
+        for item in &mut context_items {
+            if let Some(text) = self.translated(cortex, &item.content, None).await {
+                item.content = text;
+            }
+        }
+        let persistent: Vec<_> = cortex
+            .get_pinned(self.max_important)
+            .await?
+            .into_iter()
+            .filter(|m| self.includes_type(m.memory_type))
+            .collect();
+        let persistent = cortex.screen_memories(persistent).await?;
+        let persistent = self.localize(cortex, persistent).await;
+        let stale = cortex.stale_pins().await;
 
-                let tokens = Self::estimate_tokens(&section);
-                if tokens <= remaining_tokens {
-                    output.push_str(&section);
-                    remaining_tokens -= tokens;
-                }
+        if context_items.is_empty() && persistent.is_empty() {
+            return Ok(Rendered::default());
+        }
+
+        let mut section = String::from("## Active Context\n");
+        let mut memory_ids = Vec::new();
+
+        // Pinned items first: working-memory pins, then persistently pinned memories
+        let mut pinned: Vec<(&MemoryId, MemoryType, &str, bool)> = context_items
+            .iter()
+            .filter(|i| i.pinned)
+            .map(|i| {
+                let is_stale = stale.iter().any(|p| p.memory_id == i.memory_id);
+                (&i.memory_id, i.memory_type, i.content.as_str(), is_stale)
+            })
+            .collect();
+        for mem in &persistent {
+            let already = context_items
+                .iter()
+                .any(|i| i.pinned && i.memory_id == mem.id);
+            if !already {
+                pinned.push((&mem.id, mem.memory_type, mem.content.as_str(), false));
+            }
+        }
+        if !pinned.is_empty() {
+            section.push_str("### Pinned\n");
+            for (memory_id, memory_type, content, is_stale) in &pinned {
+                memory_ids.push((*memory_id).clone());
+                let line = if *is_stale {
+                    format!("- [{}] {} (stale pin)\n", memory_type, content)
+                } else {
+                    format!("- [{}] {}\n", memory_type, content)
+                };
+                section.push_str(&line);
             }
         }
 
-        // Layer 2: Current experience
-        if self.include_experience {
-            if let Some(ep) = cortex.get_current_experience().await {
-                let section = format!(
-                    "\n## Current Experience: {}\n{}\n- Memories in this experience: {}\n",
-                    ep.title,
-                    ep.context,
-                    ep.memory_ids.len()
+        // Other active items
+        let active: Vec<_> = context_items
+            .iter()
+            .filter(|i| !i.pinned && !persistent.iter().any(|m| m.id == i.memory_id))
+            .collect();
+        if !active.is_empty() {
+            section.push_str("### Working Memory\n");
+            for item in &active {
+                let line = format!(
+                    "- [{}] {} (attn: {:.2})\n",
+                    item.memory_type, item.content, item.attention_score
                 );
-                let tokens = Self::estimate_tokens(&section);
-                if tokens <= remaining_tokens {
-                    output.push_str(&section);
-                    remaining_tokens -= tokens;
-                }
+                section.push_str(&line);
+                memory_ids.push(item.memory_id.clone());
             }
         }
 
-        // Layer 3: High-importance memories
-        if self.include_important && remaining_tokens > 100 {
-            let important = cortex.get_important(self.max_important).await?;
+        let tokens = Self::estimate_tokens(&section);
+        if tokens <= budget {
+            Ok(Rendered {
+                text: section,
+                tokens,
+                memory_ids,
+                experience_id: None,
+            })
+        } else {
+            Ok(Rendered::default())
+        }
+    }
+
+    /// The current experience, all or nothing
+    async fn experience(&self, cortex: &MemoryCortex, budget: usize) -> Rendered {
+        if !self.include_experience {
+            return Rendered::default();
+        }
+        let Some(ep) = cortex.get_current_experience().await else {
+            return Rendered::default();
+        };
 
-            if !important.is_empty() {
-                let mut section = String::from("\n## Important Memories\n");
-                for mem in &important {
-                    let line = format!(
-                        "- [{}] {} (importance: {:.2})\n",
-                        mem.memory_type, mem.content, mem.importance
-                    );
-                    let line_tokens = Self::estimate_tokens(&line);
-                    if line_tokens > remaining_tokens {
-                        break;
-                    }
-                    section.push_str(&line);
-                    remaining_tokens -= line_tokens;
-                }
-                output.push_str(&section);
+        let section = format!(
+            "\n## Current Experience: {}\n{}\n- Memories in this experience: {}\n",
+            ep.title,
+            ep.context,
+            ep.memory_ids.len()
+        );
+        let tokens = Self::estimate_tokens(&section);
+        if tokens <= budget {
+            Rendered {
+                text: section,
+                tokens,
+                memory_ids: Vec::new(),
+                experience_id: Some(ep.id),
             }
+        } else {
+            Rendered::default()
         }
+    }
 
-        Ok(output)
+    /// High-importance memories, as many lines as fit
+    async fn important(&self, cortex: &MemoryCortex, budget: usize) -> Result<Rendered> {
+        if !self.include_important || budget <= 100 {
+            return Ok(Rendered::default());
+        }
+
+        // Over-fetch so less important types still have candidates to balance with,
+        // and so a type filter still leaves enough to choose from
+        let candidates = match (&self.type_selection, self.memory_types.is_empty()) {
+            (TypeSelection::ByImportance, true) => self.max_important,
+            _ => self.max_important.saturating_mul(5),
+        };
+        let ranked = cortex
+            .get_important(candidates)
+            .await?
+            .into_iter()
+            .filter(|m| self.includes_type(m.memory_type))
+            .collect();
+        let important = self.type_selection.select(ranked, self.max_important);
+        let important = cortex.screen_memories(important).await?;
+        let important = self.localize(cortex, important).await;
+        if important.is_empty() {
+            return Ok(Rendered::default());
+        }
+
+        let mut section = Rendered::section("\n## Important Memories\n");
+        for mem in &important {
+            let line = format!(
+                "- [{}] {} (importance: {:.2})\n",
+                mem.memory_type, mem.content, mem.importance
+            );
+            if !section.push_line(&line, &mem.id, budget) {
+                break;
+            }
+        }
+        Ok(section)
+    }
+
+    /// Newest memories first, as many lines as fit
+    async fn recent(&self, cortex: &MemoryCortex, budget: usize) -> Result<Rendered> {
+        if self.max_recent == 0 {
+            return Ok(Rendered::default());
+        }
+
+        let limit = self.max_recent as i64;
+        let mut recent = if self.memory_types.is_empty() {
+            cortex
+                .store
+                .get_sorted(SortOrder::Recent, limit, None)
+                .await?
+        } else {
+            let mut merged = Vec::new();
+            for memory_type in &self.memory_types {
+                merged.extend(
+                    cortex
+                        .store
+                        .get_sorted(SortOrder::Recent, limit, Some(*memory_type))
+                        .await?,
+                );
+            }
+            merged.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+            merged
+        };
+        recent.truncate(self.max_recent);
+        let recent = cortex.screen_memories(recent).await?;
+        let recent = self.localize(cortex, recent).await;
+        if recent.is_empty() {
+            return Ok(Rendered::default());
+        }
+
+        let mut section = Rendered::section("\n## Recent Memories\n");
+        for mem in &recent {
+            let line = format!(
+                "- [{}] {} ({})\n",
+                mem.memory_type,
+                mem.content,
+                mem.created_at.format("%Y-%m-%d %H:%M")
+            );
+            if !section.push_line(&line, &mem.id, budget) {
+                break;
+            }
+        }
+        Ok(section)
+    }
+}
+
+/// Text of rendered context sections and what went into them
+#[derive(Debug, Default)]
+struct Rendered {
+    text: String,
+    /// Tokens counted against the budget (section headings are free)
+    tokens: usize,
+    memory_ids: Vec<MemoryId>,
+    experience_id: Option<String>,
+}
+
+impl Rendered {
+    fn section(heading: &str) -> Self {
+        Self {
+            text: heading.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a memory's line if it fits in `budget`
+    fn push_line(&mut self, line: &str, memory_id: &MemoryId, budget: usize) -> bool {
+        let line_tokens = ContextWindow::estimate_tokens(line);
+        if self.tokens + line_tokens > budget {
+            return false;
+        }
+        self.text.push_str(line);
+        self.tokens += line_tokens;
+        self.memory_ids.push(memory_id.clone());
+        true
+    }
+
+    fn append(&mut self, other: Rendered) {
+        self.text.push_str(&other.text);
+        self.tokens += other.tokens;
+        self.memory_ids.extend(other.memory_ids);
+        self.experience_id = self.experience_id.take().or(other.experience_id);
     }
 }
 
@@ -562,66 +1179,543 @@ impl RecallWeights {
     }
 }
 
-// ─── Memory Cortex ────────────────────────────────────────────────────────────
+/// Results of one query in [`MemoryCortex::recall_many`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRecall {
+    pub query: String,
+    pub results: Vec<MemorySearchResult>,
+}
 
-/// Memory cortex - the main agentic memory system
-pub struct MemoryCortex {
-    store: Arc<MemoryStore>,
-    working_memory: RwLock<WorkingMemory>,
-    current_experience: RwLock<Option<Experience>>,
-    data_dir: std::path::PathBuf,
-    vector_backend: Arc<dyn VectorBackend>,
-    recall_weights: RwLock<RecallWeights>,
+/// Results of [`MemoryCortex::recall_many`], grouped per query
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchRecall {
+    /// One group per query, in the order the queries were given
+    pub groups: Vec<QueryRecall>,
 }
 
-impl MemoryCortex {
-    pub async fn new(data_dir: impl Into<std::path::PathBuf>) -> Result<Self> {
-        let data_dir = data_dir.into();
-        std::fs::create_dir_all(&data_dir)?;
+impl BatchRecall {
+    /// Results for `query`, if it was part of the batch
+    pub fn results_for(&self, query: &str) -> Option<&[MemorySearchResult]> {
+        self.groups
+            .iter()
+            .find(|g| g.query == query)
+            .map(|g| g.results.as_slice())
+    }
 
-        // Initialize SQLite
-        let sqlite_path = data_dir.join("memories.db");
-        let options = sqlx::sqlite::SqliteConnectOptions::new()
-            .filename(&sqlite_path)
-            .create_if_missing(true);
+    /// Every recalled memory once, at its best score across queries, best first
+    pub fn merged(&self) -> Vec<MemorySearchResult> {
+        let mut best: HashMap<&str, &MemorySearchResult> = HashMap::new();
+        for result in self.groups.iter().flat_map(|g| &g.results) {
+            let entry = best.entry(result.memory.id.as_str()).or_insert(result);
+            if result.score > entry.score {
+                *entry = result;
+            }
+        }
+        let mut merged: Vec<MemorySearchResult> = best.into_values().cloned().collect();
+        merged.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        for (i, r) in merged.iter_mut().enumerate() {
+            r.rank = i + 1;
+        }
+        merged
+    }
+}
 
-        let pool = sqlx::SqlitePool::connect_with(options).await?;
+/// Per-memory recall signals gathered before ranking
+#[derive(Clone)]
+struct RecallParts {
+    memory: Memory,
+    text: f32,
+    importance: f32,
+    vector: f32,
+}
 
-        // Run migrations
-        sqlx::migrate!("./migrations")
-            .run(&pool)
-            .await
-            .map_err(|e| MemoryError::Database(e.into()))?;
+impl RecallParts {
+    fn new(memory: Memory) -> Self {
+        Self {
+            memory,
+            text: 0.0,
+            importance: 0.0,
+            vector: 0.0,
+        }
+    }
+}
 
-        let store = MemoryStore::new(pool);
-        let vector_backend = Self::build_default_vector_backend(&data_dir).await?;
+fn query_words(query_lower: &str) -> HashSet<&str> {
+    query_lower
+        .split_whitespace()
+        .filter(|w| !w.is_empty())
+        .collect()
+}
 
-        Ok(Self {
-            store,
-            working_memory: RwLock::new(WorkingMemory::new(20)),
-            current_experience: RwLock::new(None),
-            data_dir,
-            vector_backend,
-            recall_weights: RwLock::new(RecallWeights::default()),
-        })
+/// 1.0 if the content contains the whole query, else the share of query words it contains
+fn text_match_score(query_lower: &str, query_words: &HashSet<&str>, content: &str) -> f32 {
+    let content_lower = content.to_lowercase();
+    if content_lower.contains(query_lower) {
+        1.0
+    } else {
+        let content_words: HashSet<_> = content_lower.split_whitespace().collect();
+        let overlap = query_words.intersection(&content_words).count() as f32;
+        overlap / query_words.len().max(1) as f32
     }
+}
 
-    /// Build a cortex with a caller-provided vector backend.
-    pub async fn new_with_vector_backend(
-        data_dir: impl Into<std::path::PathBuf>,
-        vector_backend: Arc<dyn VectorBackend>,
-    ) -> Result<Self> {
-        let data_dir = data_dir.into();
-        std::fs::create_dir_all(&data_dir)?;
-
-        let sqlite_path = data_dir.join("memories.db");
-        let options = sqlx::sqlite::SqliteConnectOptions::new()
-            .filename(&sqlite_path)
-            .create_if_missing(true);
-        let pool = sqlx::SqlitePool::connect_with(options).await?;
+/// Load the active memories behind vector hits. Vectors whose memory was
+/// deleted behind the cortex's back (e.g. straight through the store) are
+/// dropped, chunk vectors included, so they stop taking slots; those of a
+/// forgotten memory are only skipped, as a restore needs them again.
+async fn load_vector_hits(
+    store: &MemoryStore,
+    vector_backend: &Arc<dyn VectorBackend>,
+    ids: &[String],
+) -> Result<HashMap<String, Memory>> {
+    let memories: HashMap<String, Memory> = store
+        .load_many(ids)
+        .await?
+        .into_iter()
+        .map(|m| (m.id.clone(), m))
+        .collect();
+
+    for id in ids {
+        if memories.contains_key(id) || store.exists(id).await? {
+            continue;
+        }
+        let mut vector_ids = vec![id.clone()];
+        for chunk in store.vector_chunks(id).await? {
+            vector_ids.push(chunk_vector_id(id, chunk.index));
+        }
+        for vector_id in &vector_ids {
+            if let Err(e) = vector_backend.delete(vector_id).await {
+                tracing::warn!("Failed to drop orphaned vector {vector_id}: {e}");
+            }
+        }
+        store.save_vector_chunks(id, &[]).await?;
+    }
+    Ok(memories)
+}
 
-        sqlx::migrate!("./migrations")
-            .run(&pool)
+/// Blend the signals each memory has into one score and keep the top `limit`
+fn rank_recall(
+    parts: HashMap<String, RecallParts>,
+    weights: &RecallWeights,
+    limit: usize,
+) -> Vec<MemorySearchResult> {
+    let mut results: Vec<MemorySearchResult> = parts
+        .into_values()
+        .map(|parts| {
+            let mut denom = 0.0f32;
+            let mut score = 0.0f32;
+            if parts.text > 0.0 {
+                score += weights.text * parts.text;
+                denom += weights.text;
+            }
+            if parts.importance > 0.0 {
+                score += weights.importance * parts.importance;
+                denom += weights.importance;
+            }
+            if parts.vector > 0.0 {
+                score += weights.vector * parts.vector;
+                denom += weights.vector;
+            }
+            if denom > f32::EPSILON {
+                score /= denom;
+            }
+
+            MemorySearchResult {
+                memory: parts.memory,
+                score,
+                rank: 0, // Will be set after sorting
+            }
+        })
+        .collect();
+
+    // Sort by combined score
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    results.truncate(limit);
+
+    // Update ranks
+    for (i, r) in results.iter_mut().enumerate() {
+        r.rank = i + 1;
+    }
+    results
+}
+
+// ─── Mood ──────────────────────────────────────────────────────────────────────
+
+/// Valence below this magnitude counts as neutral
+const NEUTRAL_VALENCE: f32 = 0.1;
+
+/// Average emotional valence of one episode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodeMood {
+    pub experience_id: String,
+    pub title: String,
+    pub average_valence: f32,
+    pub memory_count: usize,
+}
+
+/// Emotional tone of the memories created within a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodSummary {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub memory_count: usize,
+    /// Mean valence (-1.0 .. 1.0), 0.0 when the window is empty
+    pub average_valence: f32,
+    pub positive: usize,
+    pub negative: usize,
+    pub neutral: usize,
+    /// Average valence of the second half of the window minus the first half
+    pub trend: f32,
+    /// Per-episode averages for episodes started within the window
+    pub episodes: Vec<EpisodeMood>,
+}
+
+impl MoodSummary {
+    /// Coarse label for the average valence
+    pub fn label(&self) -> &'static str {
+        if self.average_valence > NEUTRAL_VALENCE {
+            "positive"
+        } else if self.average_valence < -NEUTRAL_VALENCE {
+            "negative"
+        } else {
+            "neutral"
+        }
+    }
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f32>() / values.len() as f32)
+    }
+}
+
+// ─── Shared Pool ──────────────────────────────────────────────────────────────
+
+/// Which memory pools [`MemoryCortex::recall_in`] searches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolSelection {
+    /// Only the cortex's own memories
+    Private,
+    /// Only the shared team pool
+    Shared,
+    /// Both pools
+    #[default]
+    All,
+}
+
+/// A team-wide memory pool several cortices read from and promote into
+#[derive(Clone)]
+pub struct SharedPool {
+    store: Arc<MemoryStore>,
+    vector_backend: Arc<dyn VectorBackend>,
+}
+
+impl SharedPool {
+    pub fn new(store: Arc<MemoryStore>, vector_backend: Arc<dyn VectorBackend>) -> Self {
+        Self {
+            store,
+            vector_backend,
+        }
+    }
+
+    pub fn store(&self) -> &Arc<MemoryStore> {
+        &self.store
+    }
+}
+
+impl std::fmt::Debug for SharedPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedPool")
+            .field("namespace", &self.store.namespace())
+            .field("vector_backend", &self.vector_backend.name())
+            .finish()
+    }
+}
+
+/// Metadata of `memory` as a JSON object (non-object metadata is kept under `value`)
+fn metadata_map(memory: &Memory) -> serde_json::Map<String, serde_json::Value> {
+    match memory.metadata.clone() {
+        Some(serde_json::Value::Object(map)) => map,
+        Some(other) => {
+            let mut map = serde_json::Map::new();
+            map.insert("value".to_string(), other);
+            map
+        }
+        None => serde_json::Map::new(),
+    }
+}
+
+/// Copy of `memory` with a new ID for another pool. Where it came from, when and
+/// how is recorded under the `provenance` metadata key.
+fn copy_with_provenance(
+    memory: &Memory,
+    action: &str,
+    source_namespace: &str,
+    id: MemoryId,
+    now: DateTime<Utc>,
+) -> Memory {
+    let mut metadata = metadata_map(memory);
+    metadata.remove("shared_id");
+    metadata.insert(
+        "provenance".to_string(),
+        serde_json::json!({
+            "action": action,
+            "source_id": memory.id,
+            "source_namespace": source_namespace,
+            "at": now,
+        }),
+    );
+
+    let mut copy = memory.clone();
+    copy.id = id;
+    copy.metadata = Some(serde_json::Value::Object(metadata));
+    copy.updated_at = now;
+    copy.last_accessed_at = now;
+    copy.access_count = 0;
+    copy.pinned = false;
+    copy.forgotten = false;
+    copy
+}
+
+// ─── Handoff ──────────────────────────────────────────────────────────────────
+
+/// Version of the [`HandoffBundle`] layout written by this build
+pub const HANDOFF_FORMAT_VERSION: u32 = 1;
+
+/// Episode memories listed in a handoff's `episode_summary`
+const HANDOFF_SUMMARY_ITEMS: usize = 5;
+
+/// Everything another agent needs to pick up a task, see
+/// [`MemoryCortex::export_handoff`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoffBundle {
+    pub format_version: u32,
+    /// The episode the task was worked in
+    pub task_id: String,
+    pub exported_at: DateTime<Utc>,
+    /// Namespace of the exporting cortex
+    pub source_namespace: String,
+    pub episode: Experience,
+    /// Short digest of the episode: title, context and its most important memories
+    pub episode_summary: String,
+    /// Episode memories, active goals and pinned memories, each once
+    pub memories: Vec<Memory>,
+    /// Associations among `memories`
+    pub associations: Vec<Association>,
+    /// IDs of the active goals in `memories`
+    pub goal_ids: Vec<MemoryId>,
+    /// IDs of the memories pinned in working memory
+    pub pinned_ids: Vec<MemoryId>,
+}
+
+/// Outcome of [`MemoryCortex::import_handoff`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HandoffImport {
+    /// Memories written to this cortex
+    pub imported: usize,
+    /// Memories this cortex already had
+    pub skipped: usize,
+    /// Memories whose ID was taken by another namespace, mapped to their new ID
+    pub remapped: HashMap<MemoryId, MemoryId>,
+    pub associations: usize,
+    /// ID of the imported episode
+    pub episode_id: String,
+}
+
+impl HandoffImport {
+    /// ID a bundle memory ended up under in this cortex
+    pub fn resolve<'a>(&'a self, id: &'a str) -> &'a str {
+        self.remapped.get(id).map(String::as_str).unwrap_or(id)
+    }
+}
+
+fn summarize_episode(episode: &Experience, memories: &[Memory]) -> String {
+    let mut summary = if episode.context.trim().is_empty() {
+        episode.title.clone()
+    } else {
+        format!("{}: {}", episode.title, episode.context)
+    };
+
+    let mut ranked: Vec<&Memory> = memories
+        .iter()
+        .filter(|m| episode.memory_ids.contains(&m.id))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.importance
+            .partial_cmp(&a.importance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    for memory in ranked.into_iter().take(HANDOFF_SUMMARY_ITEMS) {
+        let line = memory.content.lines().next().unwrap_or_default();
+        let line: String = line.chars().take(200).collect();
+        summary.push_str(&format!("\n- [{}] {}", memory.memory_type, line));
+    }
+    summary
+}
+
+fn is_unique_violation(err: &MemoryError) -> bool {
+    matches!(err, MemoryError::Database(sqlx::Error::Database(e)) if e.is_unique_violation())
+}
+
+// ─── Memory Cortex ────────────────────────────────────────────────────────────
+
+/// Behaviour settings applied with [`MemoryCortex::with_config`]
+#[derive(Debug, Clone, Default)]
+pub struct CortexConfig {
+    /// Unpin working-memory items pinned for longer than this. Agents often
+    /// forget to unpin, and stale pins crowd out the active context.
+    pub auto_unpin_after: Option<Duration>,
+    /// Warn (in `get_context` and `stale_pins`) about pins older than this
+    pub pin_warning_after: Option<Duration>,
+    /// Named context windows for [`MemoryCortex::build_context_for`]
+    pub context_profiles: HashMap<String, ContextWindow>,
+}
+
+impl CortexConfig {
+    pub fn with_auto_unpin_after(mut self, max_age: Duration) -> Self {
+        self.auto_unpin_after = Some(max_age);
+        self
+    }
+
+    pub fn with_pin_warning_after(mut self, age: Duration) -> Self {
+        self.pin_warning_after = Some(age);
+        self
+    }
+
+    /// Register a context window under `name`, e.g. `"planner"` or `"executor"`
+    pub fn with_context_profile(mut self, name: impl Into<String>, window: ContextWindow) -> Self {
+        self.context_profiles.insert(name.into(), window);
+        self
+    }
+}
+
+/// When a cortex attached with [`MemoryCortex::with_pulses`] emits
+/// [`Pulse::WorkingMemoryChanged`]
+#[derive(Debug, Clone)]
+pub struct WorkingMemoryPulseConfig {
+    /// Emit after each change to working memory (deduplicated against the
+    /// previous snapshot)
+    pub on_change: bool,
+    /// Also emit a snapshot this often, see
+    /// [`MemoryCortex::spawn_working_memory_pulses`]
+    pub interval: Option<std::time::Duration>,
+    /// Report attention scores; without them, decay alone does not count as a change
+    pub include_attention: bool,
+}
+
+impl Default for WorkingMemoryPulseConfig {
+    fn default() -> Self {
+        Self {
+            on_change: true,
+            interval: None,
+            include_attention: true,
+        }
+    }
+}
+
+impl WorkingMemoryPulseConfig {
+    pub fn with_on_change(mut self, on_change: bool) -> Self {
+        self.on_change = on_change;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: std::time::Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn with_attention(mut self, include_attention: bool) -> Self {
+        self.include_attention = include_attention;
+        self
+    }
+}
+
+/// Memory cortex - the main agentic memory system
+pub struct MemoryCortex {
+    store: Arc<MemoryStore>,
+    working_memory: RwLock<WorkingMemory>,
+    current_experience: RwLock<Option<Experience>>,
+    data_dir: std::path::PathBuf,
+    vector_backend: Arc<dyn VectorBackend>,
+    recall_weights: RwLock<RecallWeights>,
+    middleware: MiddlewareChain,
+    validation: ContentValidation,
+    shared: Option<SharedPool>,
+    recall_timeout: Option<std::time::Duration>,
+    context_profiles: HashMap<String, ContextWindow>,
+    injection_screen: Option<InjectionScreen>,
+    translator: Option<Arc<dyn Translator>>,
+    pulses: Option<(Arc<GoldfishPulses>, WorkingMemoryPulseConfig)>,
+    /// Last working-memory snapshot emitted on change
+    last_attention: RwLock<Option<Vec<AttentionItem>>>,
+}
+
+impl MemoryCortex {
+    pub async fn new(data_dir: impl Into<std::path::PathBuf>) -> Result<Self> {
+        let data_dir = crate::platform_fs::long_path(&data_dir.into());
+        DataDirManifest::open(&data_dir)?;
+
+        // Initialize SQLite
+        let sqlite_path = data_dir.join("memories.db");
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(&sqlite_path)
+            .create_if_missing(true);
+
+        let pool = sqlx::SqlitePool::connect_with(options).await?;
+
+        // Run migrations
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| MemoryError::Database(e.into()))?;
+
+        let store = MemoryStore::new(pool);
+        let vector_backend = Self::build_default_vector_backend(&data_dir).await?;
+        Self::record_vector_backend(&data_dir, vector_backend.as_ref())?;
+
+        Ok(Self {
+            store,
+            working_memory: RwLock::new(WorkingMemory::new(20)),
+            current_experience: RwLock::new(None),
+            data_dir,
+            vector_backend,
+            recall_weights: RwLock::new(RecallWeights::default()),
+            middleware: MiddlewareChain::new(),
+            validation: ContentValidation::default(),
+            shared: None,
+            recall_timeout: None,
+            context_profiles: HashMap::new(),
+            injection_screen: None,
+            translator: None,
+            pulses: None,
+            last_attention: RwLock::new(None),
+        })
+    }
+
+    /// Build a cortex with a caller-provided vector backend.
+    pub async fn new_with_vector_backend(
+        data_dir: impl Into<std::path::PathBuf>,
+        vector_backend: Arc<dyn VectorBackend>,
+    ) -> Result<Self> {
+        let data_dir = crate::platform_fs::long_path(&data_dir.into());
+        std::fs::create_dir_all(&data_dir)?;
+        Self::record_vector_backend(&data_dir, vector_backend.as_ref())?;
+
+        let sqlite_path = data_dir.join("memories.db");
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(&sqlite_path)
+            .create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options).await?;
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
             .await
             .map_err(|e| MemoryError::Database(e.into()))?;
 
@@ -632,10 +1726,94 @@ impl MemoryCortex {
             data_dir,
             vector_backend,
             recall_weights: RwLock::new(RecallWeights::default()),
+            middleware: MiddlewareChain::new(),
+            validation: ContentValidation::default(),
+            shared: None,
+            recall_timeout: None,
+            context_profiles: HashMap::new(),
+            injection_screen: None,
+            translator: None,
+            pulses: None,
+            last_attention: RwLock::new(None),
         })
     }
 
-    async fn build_default_vector_backend(data_dir: &Path) -> Result<Arc<dyn VectorBackend>> {
+    /// Build a cortex over an existing store and vector backend, typically a
+    /// namespace-scoped store and collection shared with other cortices.
+    ///
+    /// No database is opened and no migrations are run; the caller owns `data_dir`.
+    pub fn from_store(
+        data_dir: impl Into<std::path::PathBuf>,
+        store: Arc<MemoryStore>,
+        vector_backend: Arc<dyn VectorBackend>,
+    ) -> Self {
+        let working_memory = WorkingMemory::new(20).with_clock(Arc::clone(store.clock()));
+        Self {
+            store,
+            working_memory: RwLock::new(working_memory),
+            current_experience: RwLock::new(None),
+            data_dir: data_dir.into(),
+            vector_backend,
+            recall_weights: RwLock::new(RecallWeights::default()),
+            middleware: MiddlewareChain::new(),
+            validation: ContentValidation::default(),
+            shared: None,
+            recall_timeout: None,
+            context_profiles: HashMap::new(),
+            injection_screen: None,
+            translator: None,
+            pulses: None,
+            last_attention: RwLock::new(None),
+        }
+    }
+
+    /// Read the time from `clock` in the store, working memory and episodes
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.store = self.store.with_clock(Arc::clone(&clock));
+        self.working_memory = RwLock::new(self.working_memory.into_inner().with_clock(clock));
+        self
+    }
+
+    /// Apply pin-age settings to working memory and register context profiles
+    pub fn with_config(mut self, config: CortexConfig) -> Self {
+        self.working_memory = RwLock::new(
+            self.working_memory
+                .into_inner()
+                .with_auto_unpin_after(config.auto_unpin_after)
+                .with_pin_warning_after(config.pin_warning_after),
+        );
+        self.context_profiles.extend(config.context_profiles);
+        self
+    }
+
+    /// Mint memory, episode and summary IDs with `ids`
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.store = self.store.with_id_generator(ids);
+        self
+    }
+
+    /// A new memory stamped with the cortex's clock and ID generator
+    pub fn new_memory(&self, content: impl Into<String>, memory_type: MemoryType) -> Memory {
+        self.store.new_memory(content, memory_type)
+    }
+
+    /// Validate (and upgrade) the data directory layout and record the vector backend
+    pub(crate) fn record_vector_backend(
+        data_dir: &Path,
+        vector_backend: &dyn VectorBackend,
+    ) -> Result<()> {
+        let mut manifest = DataDirManifest::open(data_dir)?;
+        if manifest.record_vector_backend(vector_backend.name()) {
+            manifest.save(data_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Vector backend picked by `GOLDFISH_VECTOR_BACKEND`, sized for
+    /// [`generate_embedding`], which every cortex embeds with
+    pub(crate) async fn build_default_vector_backend(
+        data_dir: &Path,
+    ) -> Result<Arc<dyn VectorBackend>> {
         let preferred = std::env::var("GOLDFISH_VECTOR_BACKEND")
             .unwrap_or_default()
             .trim()
@@ -645,12 +1823,39 @@ impl MemoryCortex {
             return Self::build_file_backend(data_dir).await;
         }
 
+        #[cfg(feature = "sqlite-vec")]
+        {
+            if preferred == "sqlite-vec" || preferred == "sqlite_vec" {
+                let backend = crate::vector_backend::sqlite_vec::SqliteVecBackend::connect(
+                    data_dir.join("memories.db"),
+                    EMBEDDING_DIM,
+                )
+                .await?;
+                return Ok(Arc::new(backend));
+            }
+        }
+
+        #[cfg(feature = "pgvector")]
+        {
+            if preferred == "pgvector" {
+                let url = std::env::var("GOLDFISH_PGVECTOR_URL").map_err(|_| {
+                    MemoryError::Configuration(
+                        "GOLDFISH_VECTOR_BACKEND=pgvector needs GOLDFISH_PGVECTOR_URL".to_string(),
+                    )
+                })?;
+                let backend =
+                    crate::vector_backend::pgvector::PgVectorBackend::connect(&url, EMBEDDING_DIM)
+                        .await?;
+                return Ok(Arc::new(backend));
+            }
+        }
+
         #[cfg(feature = "lancedb")]
         {
             if preferred.is_empty() || preferred == "lancedb" {
                 let ldb = Arc::new(crate::vector_backend::lancedb::LanceDbVectorBackend::new(
                     data_dir.join("vectors_lancedb"),
-                    384,
+                    EMBEDDING_DIM,
                 ));
                 match ldb.ensure_ready().await {
                     Ok(()) => {
@@ -669,7 +1874,10 @@ impl MemoryCortex {
     }
 
     async fn build_file_backend(data_dir: &Path) -> Result<Arc<dyn VectorBackend>> {
-        let file = Arc::new(FileVectorBackend::new(data_dir.join("vectors"), 384));
+        let file = Arc::new(FileVectorBackend::new(
+            data_dir.join("vectors"),
+            EMBEDDING_DIM,
+        ));
         file.ensure_ready().await?;
         Ok(file)
     }
@@ -678,10 +1886,40 @@ impl MemoryCortex {
         &self.data_dir
     }
 
+    /// Underlying store, for reads that bypass recall and working memory
+    pub fn store(&self) -> &Arc<MemoryStore> {
+        &self.store
+    }
+
+    /// Namespace of the underlying store
+    pub fn namespace(&self) -> &str {
+        self.store.namespace()
+    }
+
+    /// Payload upserted with a memory's vector, for filtered vector search
+    fn vector_payload(&self, memory: &Memory) -> Option<serde_json::Value> {
+        Some(vector_payload(memory, self.store.namespace()))
+    }
+
     pub fn vector_backend_name(&self) -> &'static str {
         self.vector_backend.name()
     }
 
+    /// Layout statistics (row counts, fragmentation) of the vector backend
+    pub async fn vector_stats(&self) -> Result<VectorIndexStats> {
+        self.vector_backend.stats().await
+    }
+
+    /// Compact and optimize the vector backend
+    pub async fn maintain_vectors(&self) -> Result<VectorMaintenanceReport> {
+        self.vector_backend.maintain().await
+    }
+
+    /// Rebuild the vector backend's index structures
+    pub async fn rebuild_vectors(&self) -> Result<VectorMaintenanceReport> {
+        self.vector_backend.rebuild().await
+    }
+
     pub async fn set_recall_weights(&self, weights: RecallWeights) {
         let mut guard = self.recall_weights.write().await;
         *guard = weights.normalized();
@@ -691,21 +1929,112 @@ impl MemoryCortex {
         *self.recall_weights.read().await
     }
 
+    /// Append a middleware layer to the remember/recall/context pipeline
+    pub fn with_middleware(mut self, layer: Arc<dyn MemoryMiddleware>) -> Self {
+        self.middleware.push(layer);
+        self
+    }
+
+    /// Replace the content validation rules applied when remembering.
+    /// Oversized content is rejected or truncated here, never split.
+    pub fn with_validation(mut self, validation: ContentValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Attach a shared team pool: it is searched by [`recall`](Self::recall) and is
+    /// the target of [`promote_to_shared`](Self::promote_to_shared)
+    pub fn with_shared_pool(mut self, shared: SharedPool) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    /// Make [`recall`](Self::recall) return what it has found after `timeout`
+    /// instead of waiting on a slow vector backend
+    pub fn with_recall_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.recall_timeout = Some(timeout);
+        self
+    }
+
+    /// Screen recalled memories and context windows for prompt injection
+    pub fn with_injection_screen(mut self, screen: InjectionScreen) -> Self {
+        self.injection_screen = Some(screen);
+        self
+    }
+
+    /// Translate memories for context windows built with
+    /// [`ContextWindow::with_language`]
+    pub fn with_translator(mut self, translator: Arc<dyn Translator>) -> Self {
+        self.translator = Some(translator);
+        self
+    }
+
+    /// Emit [`Pulse::WorkingMemoryChanged`] on `pulses` as `config` says, so
+    /// dashboards can follow the agent's attention without polling
+    pub fn with_pulses(
+        mut self,
+        pulses: Arc<GoldfishPulses>,
+        config: WorkingMemoryPulseConfig,
+    ) -> Self {
+        self.pulses = Some((pulses, config));
+        self
+    }
+
+    /// Run memories through the injection screen, if any
+    async fn screen_memories(&self, memories: Vec<Memory>) -> Result<Vec<Memory>> {
+        match &self.injection_screen {
+            Some(screen) => screen.screen_all(memories).await,
+            None => Ok(memories),
+        }
+    }
+
+    /// Run recall results through the injection screen, if any
+    async fn screen_recalled(
+        &self,
+        results: Vec<MemorySearchResult>,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let Some(screen) = &self.injection_screen else {
+            return Ok(results);
+        };
+        let mut kept = Vec::with_capacity(results.len());
+        for mut result in results {
+            if let Some(memory) = screen.screen(result.memory).await? {
+                result.memory = memory;
+                kept.push(result);
+            }
+        }
+        Ok(kept)
+    }
+
+    pub fn shared_pool(&self) -> Option<&SharedPool> {
+        self.shared.as_ref()
+    }
+
+    fn require_shared(&self) -> Result<&SharedPool> {
+        self.shared.as_ref().ok_or_else(|| {
+            MemoryError::InvalidOperation("No shared memory pool is attached".to_string())
+        })
+    }
+
     // ─── Core Memory Operations ───────────────────────────────────────────
 
     /// Remember something - adds to working memory and optionally to current episode
     pub async fn remember(&self, memory: &Memory) -> Result<()> {
+        let mut memory = memory.clone();
+        self.middleware.before_save(&mut memory).await?;
+        memory.content = self.validation.apply_single(&memory.content)?;
+        let memory = &memory;
+
         self.store.save(memory).await?;
 
         // Store vector embedding for semantic search
         let embedding = generate_embedding(&memory.content);
         self.vector_backend
-            .upsert(&memory.id, &embedding, memory.metadata.clone())
+            .upsert(&memory.id, &embedding, self.vector_payload(memory))
             .await?;
 
         // Add to working memory
-        let mut wm = self.working_memory.write().await;
-        wm.remember(memory, None);
+        self.working_memory.write().await.remember(memory, None);
 
         // Add to current episode if active
         let mut episode = self.current_experience.write().await;
@@ -717,20 +2046,29 @@ impl MemoryCortex {
                 .add_memory_to_experience(&ep.id, &memory.id)
                 .await;
         }
+        drop(episode);
 
-        Ok(())
+        self.working_memory_changed().await;
+        self.middleware.after_save(memory).await
     }
 
     /// Remember with a TTL (auto-expires from working memory)
     pub async fn remember_with_ttl(&self, memory: &Memory, ttl: Duration) -> Result<()> {
+        let mut memory = memory.clone();
+        self.middleware.before_save(&mut memory).await?;
+        memory.content = self.validation.apply_single(&memory.content)?;
+        let memory = &memory;
+
         self.store.save(memory).await?;
         let embedding = generate_embedding(&memory.content);
         self.vector_backend
-            .upsert(&memory.id, &embedding, memory.metadata.clone())
+            .upsert(&memory.id, &embedding, self.vector_payload(memory))
             .await?;
 
-        let mut wm = self.working_memory.write().await;
-        wm.remember(memory, Some(ttl));
+        self.working_memory
+            .write()
+            .await
+            .remember(memory, Some(ttl));
 
         let mut episode = self.current_experience.write().await;
         if let Some(ep) = episode.as_mut() {
@@ -740,8 +2078,10 @@ impl MemoryCortex {
                 .add_memory_to_experience(&ep.id, &memory.id)
                 .await;
         }
+        drop(episode);
 
-        Ok(())
+        self.working_memory_changed().await;
+        self.middleware.after_save(memory).await
     }
 
     /// Think about something - brings into working memory without saving
@@ -749,13 +2089,13 @@ impl MemoryCortex {
         let memory = self.store.load(memory_id).await?;
 
         if let Some(ref mem) = memory {
-            let mut wm = self.working_memory.write().await;
-            wm.remember(mem, None);
+            self.working_memory.write().await.remember(mem, None);
+            self.working_memory_changed().await;
 
             // Update access count
             let mut m = mem.clone();
             m.access_count += 1;
-            m.last_accessed_at = Utc::now();
+            m.last_accessed_at = self.store.now();
             self.store.update(&m).await?;
         }
 
@@ -764,156 +2104,642 @@ impl MemoryCortex {
 
     /// Focus on a memory in working memory (boost attention to max)
     pub async fn focus(&self, memory_id: &str) -> bool {
-        let mut wm = self.working_memory.write().await;
-        wm.focus(memory_id)
+        let focused = self.working_memory.write().await.focus(memory_id);
+        self.working_memory_changed().await;
+        focused
     }
 
     /// Pin a memory in working memory
     pub async fn pin(&self, memory_id: &str) -> bool {
-        let mut wm = self.working_memory.write().await;
-        wm.pin(memory_id)
+        let pinned = self.working_memory.write().await.pin(memory_id);
+        self.working_memory_changed().await;
+        pinned
+    }
+
+    /// Pin a memory in working memory for `ttl`, after which it is unpinned
+    pub async fn pin_for(&self, memory_id: &str, ttl: Duration) -> bool {
+        let pinned = self
+            .working_memory
+            .write()
+            .await
+            .pin_for(memory_id, Some(ttl));
+        self.working_memory_changed().await;
+        pinned
+    }
+
+    /// Working-memory pins older than `CortexConfig::pin_warning_after`
+    pub async fn stale_pins(&self) -> Vec<StalePin> {
+        self.working_memory.read().await.stale_pins()
     }
 
     /// Unpin a memory in working memory
     pub async fn unpin(&self, memory_id: &str) -> bool {
+        let unpinned = self.working_memory.write().await.unpin(memory_id);
+        self.working_memory_changed().await;
+        unpinned
+    }
+
+    /// Persistently pin or unpin a memory.
+    ///
+    /// Unlike [`pin`](Self::pin), this survives restarts: pinned memories are never
+    /// pruned or consolidated and are always included in the context window.
+    pub async fn set_pinned(&self, memory_id: &str, pinned: bool) -> Result<bool> {
+        if !self.store.set_pinned(memory_id, pinned).await? {
+            return Ok(false);
+        }
+        {
+            let mut wm = self.working_memory.write().await;
+            if pinned {
+                wm.pin(memory_id);
+            } else {
+                wm.unpin(memory_id);
+            }
+        }
+        self.working_memory_changed().await;
+        Ok(true)
+    }
+
+    /// Persistently pinned memories
+    pub async fn get_pinned(&self, limit: usize) -> Result<Vec<Memory>> {
+        self.store.get_pinned(limit as i64).await
+    }
+
+    // ─── Forget / Delete / Restore ────────────────────────────────────────
+
+    /// Soft delete a memory.
+    ///
+    /// The row stays in the store (and in its episodes) so it can be restored, but it
+    /// leaves working memory and its vector is dropped so it no longer takes up recall slots.
+    pub async fn forget(&self, memory_id: &str) -> Result<bool> {
+        if !self.store.forget(memory_id).await? {
+            return Ok(false);
+        }
+        self.working_memory.write().await.remove(memory_id);
+        self.working_memory_changed().await;
+        self.vector_backend.delete(memory_id).await?;
+        Ok(true)
+    }
+
+    /// Bring back a forgotten memory and re-embed it
+    pub async fn restore(&self, memory_id: &str) -> Result<bool> {
+        if !self.store.restore(memory_id).await? {
+            return Ok(false);
+        }
+        if let Some(memory) = self.store.load(memory_id).await? {
+            let embedding = generate_embedding(&memory.content);
+            self.vector_backend
+                .upsert(&memory.id, &embedding, self.vector_payload(&memory))
+                .await?;
+        }
+        Ok(true)
+    }
+
+    /// Permanently delete a memory.
+    ///
+    /// The row, its associations and its episode links are removed in one transaction;
+    /// working memory, the current episode and the vector index are cleaned afterwards.
+    /// Should the vector delete fail, `recall` drops the orphaned vector when it next
+    /// surfaces.
+    pub async fn delete(&self, memory_id: &str) -> Result<()> {
+        self.store.delete(memory_id).await?;
+
+        self.working_memory.write().await.remove(memory_id);
+        self.working_memory_changed().await;
+        if let Some(ep) = self.current_experience.write().await.as_mut() {
+            ep.memory_ids.retain(|id| id != memory_id);
+        }
+        self.vector_backend.delete(memory_id).await
+    }
+
+    // ─── Shared Pool ──────────────────────────────────────────────────────
+
+    /// Copy a private memory into the shared pool, returning the shared copy's ID.
+    ///
+    /// The copy records its origin under `provenance` in its metadata, and the
+    /// private memory remembers the copy under `shared_id`, so promoting the same
+    /// memory again returns the existing copy.
+    pub async fn promote_to_shared(&self, memory_id: &str) -> Result<MemoryId> {
+        let shared = self.require_shared()?;
+        let mut memory = self
+            .store
+            .load(memory_id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(memory_id.to_string()))?;
+
+        let existing = memory
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("shared_id"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if let Some(shared_id) = existing {
+            if shared.store.exists(&shared_id).await? {
+                return Ok(shared_id);
+            }
+        }
+
+        let copy = copy_with_provenance(
+            &memory,
+            "promoted",
+            self.store.namespace(),
+            shared.store.next_id(),
+            self.store.now(),
+        );
+        shared.store.save(&copy).await?;
+        let embedding = generate_embedding(&copy.content);
+        shared
+            .vector_backend
+            .upsert(
+                &copy.id,
+                &embedding,
+                Some(vector_payload(&copy, shared.store.namespace())),
+            )
+            .await?;
+
+        let mut metadata = metadata_map(&memory);
+        metadata.insert("shared_id".to_string(), serde_json::json!(copy.id));
+        memory.metadata = Some(serde_json::Value::Object(metadata));
+        memory.updated_at = self.store.now();
+        self.store.update(&memory).await?;
+
+        Ok(copy.id)
+    }
+
+    /// Copy a shared memory into this cortex's private pool, returning the new ID.
+    /// The copy records its origin under `provenance` in its metadata.
+    pub async fn copy_from_shared(&self, memory_id: &str) -> Result<MemoryId> {
+        let shared = self.require_shared()?;
+        let memory = shared
+            .store
+            .load(memory_id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(memory_id.to_string()))?;
+
+        let copy = copy_with_provenance(
+            &memory,
+            "copied_from_shared",
+            shared.store.namespace(),
+            self.store.next_id(),
+            self.store.now(),
+        );
+        self.store.save(&copy).await?;
+        let embedding = generate_embedding(&copy.content);
+        self.vector_backend
+            .upsert(&copy.id, &embedding, self.vector_payload(&copy))
+            .await?;
+
+        Ok(copy.id)
+    }
+
+    // ─── Handoff ──────────────────────────────────────────────────────────
+
+    /// Bundle the context of the task worked in episode `task_id` so another agent
+    /// can continue it: the episode's memories, active goals, working-memory pins,
+    /// the links between them and a short episode summary.
+    pub async fn export_handoff(&self, task_id: &str) -> Result<HandoffBundle> {
+        let episode = self
+            .store
+            .load_experience(task_id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(format!("Episode {task_id}")))?;
+
+        let mut memories = self.store.load_many(&episode.memory_ids).await?;
+        let mut seen: HashSet<MemoryId> = memories.iter().map(|m| m.id.clone()).collect();
+
+        let mut goal_ids = Vec::new();
+        for goal in self.get_goals().await? {
+            goal_ids.push(goal.id.clone());
+            if seen.insert(goal.id.clone()) {
+                memories.push(goal);
+            }
+        }
+
+        let pinned_ids: Vec<MemoryId> = {
+            let wm = self.working_memory.read().await;
+            wm.all()
+                .iter()
+                .filter(|item| item.pinned)
+                .map(|item| item.memory_id.clone())
+                .collect()
+        };
+        let missing: Vec<MemoryId> = pinned_ids
+            .iter()
+            .filter(|id| !seen.contains(*id))
+            .cloned()
+            .collect();
+        memories.extend(self.store.load_many(&missing).await?);
+
+        let ids: Vec<MemoryId> = memories.iter().map(|m| m.id.clone()).collect();
+        let associations = self.store.get_associations_between(&ids).await?;
+
+        Ok(HandoffBundle {
+            format_version: HANDOFF_FORMAT_VERSION,
+            task_id: task_id.to_string(),
+            exported_at: self.store.now(),
+            source_namespace: self.store.namespace().to_string(),
+            episode_summary: summarize_episode(&episode, &memories),
+            episode,
+            memories,
+            associations,
+            goal_ids,
+            pinned_ids,
+        })
+    }
+
+    /// Take over a task exported with [`export_handoff`](Self::export_handoff).
+    ///
+    /// Memories, the episode and their links keep their IDs. Memories this cortex
+    /// already has are left alone; an ID taken by another namespace of a shared
+    /// database is replaced and reported in [`HandoffImport::remapped`], with links
+    /// rewritten to match. Pinned memories are pinned in working memory.
+    pub async fn import_handoff(&self, bundle: &HandoffBundle) -> Result<HandoffImport> {
+        if bundle.format_version > HANDOFF_FORMAT_VERSION {
+            return Err(MemoryError::Validation(format!(
+                "Handoff bundle format {} is newer than the supported format {}",
+                bundle.format_version, HANDOFF_FORMAT_VERSION
+            )));
+        }
+
+        let mut report = HandoffImport::default();
+        for memory in &bundle.memories {
+            if self.store.exists(&memory.id).await? {
+                report.skipped += 1;
+                continue;
+            }
+
+            let mut memory = memory.clone();
+            if let Err(e) = self.store.save(&memory).await {
+                if !is_unique_violation(&e) {
+                    return Err(e);
+                }
+                let new_id = self.store.next_id();
+                report.remapped.insert(memory.id.clone(), new_id.clone());
+                memory.id = new_id;
+                self.store.save(&memory).await?;
+            }
+            if !memory.forgotten {
+                let embedding = generate_embedding(&memory.content);
+                self.vector_backend
+                    .upsert(&memory.id, &embedding, self.vector_payload(&memory))
+                    .await?;
+            }
+            report.imported += 1;
+        }
+
+        let mut associations = Vec::with_capacity(bundle.associations.len());
+        for association in &bundle.associations {
+            let mut association = association.clone();
+            let source = report.resolve(&association.source_id).to_string();
+            let target = report.resolve(&association.target_id).to_string();
+            if source != association.source_id || target != association.target_id {
+                association.id = self.store.next_id();
+                association.source_id = source;
+                association.target_id = target;
+            }
+            associations.push(association);
+        }
+        self.store.create_associations(&associations).await?;
+        report.associations += associations.len();
+
+        let mut episode = bundle.episode.clone();
+        episode.memory_ids = episode
+            .memory_ids
+            .iter()
+            .map(|id| report.resolve(id).to_string())
+            .collect();
+        if let Some(parent_id) = &episode.parent_id {
+            if self.store.load_experience(parent_id).await?.is_none() {
+                episode.parent_id = None;
+            }
+        }
+        if self.store.load_experience(&episode.id).await?.is_some() {
+            for memory_id in &episode.memory_ids {
+                self.store
+                    .add_memory_to_experience(&episode.id, memory_id)
+                    .await?;
+            }
+        } else if let Err(e) = self.store.save_experience(&episode).await {
+            if !is_unique_violation(&e) {
+                return Err(e);
+            }
+            episode.id = self.store.next_id();
+            self.store.save_experience(&episode).await?;
+        }
+        report.episode_id = episode.id;
+
         let mut wm = self.working_memory.write().await;
-        wm.unpin(memory_id)
+        for pinned_id in &bundle.pinned_ids {
+            let id = report.resolve(pinned_id);
+            if let Some(memory) = self.store.load(id).await? {
+                wm.remember(&memory, None);
+                wm.pin(id);
+            }
+        }
+        drop(wm);
+        self.working_memory_changed().await;
+
+        Ok(report)
     }
 
-    /// Get what agent is currently thinking about
+    /// Get what agent is currently thinking about.
+    ///
+    /// Lapsed pins are released first; pins past the warning age are logged.
     pub async fn get_context(&self) -> Vec<WorkingMemoryItem> {
-        let wm = self.working_memory.read().await;
-        wm.get_context().into_iter().cloned().collect()
+        let mut wm = self.working_memory.write().await;
+        let unpinned = wm.expire_pins();
+        if unpinned > 0 {
+            tracing::info!("Auto-unpinned {unpinned} working memory item(s)");
+        }
+        for pin in wm.stale_pins() {
+            tracing::warn!(
+                "Memory {} has been pinned since {}; consider unpinning it",
+                pin.memory_id,
+                pin.pinned_at
+            );
+        }
+        let context = wm.get_context();
+        drop(wm);
+        if unpinned > 0 {
+            self.working_memory_changed().await;
+        }
+        context
     }
 
     // ─── Search & Recall ──────────────────────────────────────────────────
 
-    /// Search memories with hybrid ranking (text + vector + importance + recency)
+    /// Search memories with hybrid ranking (text + vector + importance + recency).
+    ///
+    /// Includes the shared pool when one is attached; see [`recall_in`](Self::recall_in).
     pub async fn recall(&self, query: &str, limit: usize) -> Result<Vec<MemorySearchResult>> {
-        #[derive(Clone)]
-        struct ScoreParts {
-            memory: Memory,
-            text: f32,
-            importance: f32,
-            vector: f32,
+        self.recall_in(query, limit, PoolSelection::All).await
+    }
+
+    /// Generation of the private store, plus the shared pool's when one is
+    /// attached. Unchanged means no memory recall could see was written.
+    pub async fn generation(&self) -> Result<StoreGeneration> {
+        let generation = self.store.generation().await?;
+        match &self.shared {
+            Some(shared) => Ok(generation + shared.store.generation().await?),
+            None => Ok(generation),
         }
+    }
 
-        let text_candidate_limit = ((limit.saturating_mul(25)).max(200)).min(10_000) as i64;
-        let text_candidates = self
-            .store
-            .search_text_candidates(query, text_candidate_limit)
+    /// [`recall`](Self::recall) with the [`generation`](Self::generation) it was read at
+    pub async fn recall_with_generation(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Generational<Vec<MemorySearchResult>>> {
+        let generation = self.generation().await?;
+        Ok(Generational::new(
+            generation,
+            self.recall(query, limit).await?,
+        ))
+    }
+
+    /// Like [`recall`](Self::recall), restricted to the private pool, the shared pool,
+    /// or both. Without a shared pool, `Shared` finds nothing and `All` is `Private`.
+    pub async fn recall_in(
+        &self,
+        query: &str,
+        limit: usize,
+        pools: PoolSelection,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let deadline = Deadline::from_timeout(self.recall_timeout);
+        let found = self.recall_within(query, limit, pools, &deadline).await?;
+        if found.timed_out {
+            tracing::warn!("Recall for {query:?} timed out");
+        }
+        Ok(found.results)
+    }
+
+    /// Like [`recall_in`](Self::recall_in), giving up on the remaining pools and
+    /// stages once `deadline` expires or is cancelled. Candidates found before
+    /// then are still ranked and returned, with `timed_out` set.
+    pub async fn recall_within(
+        &self,
+        query: &str,
+        limit: usize,
+        pools: PoolSelection,
+        deadline: &Deadline,
+    ) -> Result<TimedResults<MemorySearchResult>> {
+        self.recall_matching(query, limit, pools, deadline, None)
+            .await
+    }
+
+    /// Like [`recall`](Self::recall), only returning memories of `memory_type`.
+    /// The type is applied before the limit, so up to `limit` matches come back.
+    pub async fn recall_of_type(
+        &self,
+        query: &str,
+        memory_type: MemoryType,
+        limit: usize,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let deadline = Deadline::from_timeout(self.recall_timeout);
+        let found = self
+            .recall_matching(
+                query,
+                limit,
+                PoolSelection::All,
+                &deadline,
+                Some(memory_type),
+            )
             .await?;
+        if found.timed_out {
+            tracing::warn!("Recall for {query:?} timed out");
+        }
+        Ok(found.results)
+    }
+
+    async fn recall_matching(
+        &self,
+        query: &str,
+        limit: usize,
+        pools: PoolSelection,
+        deadline: &Deadline,
+        memory_type: Option<MemoryType>,
+    ) -> Result<TimedResults<MemorySearchResult>> {
+        let now = self.store.now();
+        let mut timed_out = false;
 
+        let query = self.middleware.before_recall(query).await?;
+        let query = query.as_str();
+
+        let text_candidate_limit = limit.saturating_mul(25).clamp(200, 10_000) as i64;
         let query_lower = query.to_lowercase();
         let query_embedding = generate_embedding(query);
         let weights = self.recall_weights().await;
-        let mut scored_parts: HashMap<String, ScoreParts> = HashMap::new();
-        let query_words: HashSet<&str> = query_lower
-            .split_whitespace()
-            .filter(|w| !w.is_empty())
-            .collect();
-
-        // 1. Text candidate scoring
-        for memory in &text_candidates {
-            let content_lower = memory.content.to_lowercase();
-            let text_score = if content_lower.contains(&query_lower) {
-                1.0
-            } else {
-                let content_words: HashSet<_> = content_lower.split_whitespace().collect();
-                let overlap = query_words.intersection(&content_words).count() as f32;
-                overlap / query_words.len().max(1) as f32
+        let mut scored_parts: HashMap<String, RecallParts> = HashMap::new();
+        let words = query_words(&query_lower);
+
+        for (store, vector_backend) in self.searched_pools(pools) {
+            // 1. Text candidate scoring
+            let Some(text_candidates) = deadline
+                .run(store.search_text_candidates(query, text_candidate_limit))
+                .await
+            else {
+                timed_out = true;
+                break;
             };
+            for memory in &text_candidates? {
+                let text_score = text_match_score(&query_lower, &words, &memory.content);
+                if text_score > 0.0 {
+                    let importance =
+                        ImportanceCalculator::calculate_with_query_at(memory, query, now);
+                    let entry = scored_parts
+                        .entry(memory.id.clone())
+                        .or_insert_with(|| RecallParts::new(memory.clone()));
+                    entry.text = entry.text.max(text_score);
+                    entry.importance = entry.importance.max(importance);
+                }
+            }
 
-            if text_score > 0.0 {
-                let importance = ImportanceCalculator::calculate_with_query(memory, query);
-                let entry = scored_parts.entry(memory.id.clone()).or_insert(ScoreParts {
-                    memory: memory.clone(),
-                    text: 0.0,
-                    importance: 0.0,
-                    vector: 0.0,
-                });
-                entry.text = entry.text.max(text_score);
-                entry.importance = entry.importance.max(importance);
+            // 2. Vector search
+            let Some(vector_results) = deadline
+                .run(vector_backend.search(&query_embedding, limit * 4))
+                .await
+            else {
+                timed_out = true;
+                break;
+            };
+            let vector_results = aggregate_chunk_hits(vector_results?);
+            let vector_ids: Vec<String> =
+                vector_results.iter().map(|h| h.memory_id.clone()).collect();
+            let vector_memory_map = load_vector_hits(store, vector_backend, &vector_ids).await?;
+
+            for hit in vector_results {
+                if let Some(memory) = vector_memory_map.get(&hit.memory_id) {
+                    let entry = scored_parts
+                        .entry(hit.memory_id.clone())
+                        .or_insert_with(|| RecallParts::new(memory.clone()));
+                    entry.vector = entry.vector.max(hit.score);
+                }
             }
         }
 
-        // 2. Vector search
-        let vector_results = self.vector_backend.search(&query_embedding, limit * 4).await?;
-        let vector_ids: Vec<String> = vector_results.iter().map(|h| h.id.clone()).collect();
-        let vector_memories = self.store.load_many(&vector_ids).await?;
-        let vector_memory_map: HashMap<String, Memory> = vector_memories
-            .into_iter()
-            .map(|m| (m.id.clone(), m))
-            .collect();
-
-        for hit in vector_results {
-            if let Some(memory) = vector_memory_map.get(&hit.id) {
-                let entry = scored_parts
-                    .entry(hit.id.clone())
-                    .or_insert(ScoreParts {
-                        memory: memory.clone(),
-                        text: 0.0,
-                        importance: 0.0,
-                        vector: 0.0,
-                    });
-                entry.vector = entry.vector.max(hit.score);
-            }
+        if let Some(memory_type) = memory_type {
+            scored_parts.retain(|_, parts| parts.memory.memory_type == memory_type);
         }
 
         // 3. Convert to results and sort
-        let mut results: Vec<MemorySearchResult> = scored_parts
-            .into_values()
-            .map(|parts| {
-                let mut denom = 0.0f32;
-                let mut score = 0.0f32;
-                if parts.text > 0.0 {
-                    score += weights.text * parts.text;
-                    denom += weights.text;
-                }
-                if parts.importance > 0.0 {
-                    score += weights.importance * parts.importance;
-                    denom += weights.importance;
-                }
-                if parts.vector > 0.0 {
-                    score += weights.vector * parts.vector;
-                    denom += weights.vector;
-                }
-                if denom > f32::EPSILON {
-                    score /= denom;
-                }
+        let results = rank_recall(scored_parts, &weights, limit);
+        let results = self.screen_recalled(results).await?;
+        Ok(TimedResults { results, timed_out })
+    }
+
+    /// Recall several queries in one pass, e.g. the sub-questions of a plan.
+    ///
+    /// Query embeddings are computed up front, the recall weights and candidate
+    /// memories are shared across queries, and each vector hit is loaded once no
+    /// matter how many queries return it. Results come back grouped per query, in
+    /// the order given; [`BatchRecall::merged`] deduplicates them.
+    pub async fn recall_many(
+        &self,
+        queries: &[&str],
+        limit_per_query: usize,
+    ) -> Result<BatchRecall> {
+        let now = self.store.now();
+        let weights = self.recall_weights().await;
+        let text_candidate_limit = limit_per_query.saturating_mul(25).clamp(200, 10_000) as i64;
 
-                MemorySearchResult {
-                    memory: parts.memory,
-                    score,
-                    rank: 0, // Will be set after sorting
+        let mut rewritten = Vec::with_capacity(queries.len());
+        for query in queries {
+            rewritten.push(self.middleware.before_recall(query).await?);
+        }
+        let lowered: Vec<String> = rewritten.iter().map(|q| q.to_lowercase()).collect();
+        let embeddings: Vec<Vec<f32>> = rewritten.iter().map(|q| generate_embedding(q)).collect();
+        let mut scored_parts: Vec<HashMap<String, RecallParts>> =
+            vec![HashMap::new(); rewritten.len()];
+
+        for (store, vector_backend) in self.searched_pools(PoolSelection::All) {
+            for (i, query) in rewritten.iter().enumerate() {
+                let words = query_words(&lowered[i]);
+                for memory in store
+                    .search_text_candidates(query, text_candidate_limit)
+                    .await?
+                {
+                    let text_score = text_match_score(&lowered[i], &words, &memory.content);
+                    if text_score > 0.0 {
+                        let importance =
+                            ImportanceCalculator::calculate_with_query_at(&memory, query, now);
+                        let entry = scored_parts[i]
+                            .entry(memory.id.clone())
+                            .or_insert_with(|| RecallParts::new(memory));
+                        entry.text = entry.text.max(text_score);
+                        entry.importance = entry.importance.max(importance);
+                    }
                 }
-            })
-            .collect();
+            }
 
-        // Sort by combined score
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
-        results.truncate(limit);
+            let searches = embeddings
+                .iter()
+                .map(|embedding| vector_backend.search(embedding, limit_per_query * 4));
+            let vector_results: Vec<_> = futures::future::try_join_all(searches)
+                .await?
+                .into_iter()
+                .map(aggregate_chunk_hits)
+                .collect();
+
+            let mut vector_ids: Vec<String> = Vec::new();
+            for hit in vector_results.iter().flatten() {
+                if !vector_ids.contains(&hit.memory_id) {
+                    vector_ids.push(hit.memory_id.clone());
+                }
+            }
+            let vector_memory_map = load_vector_hits(store, vector_backend, &vector_ids).await?;
+
+            for (i, hits) in vector_results.into_iter().enumerate() {
+                for hit in hits {
+                    if let Some(memory) = vector_memory_map.get(&hit.memory_id) {
+                        let entry = scored_parts[i]
+                            .entry(hit.memory_id.clone())
+                            .or_insert_with(|| RecallParts::new(memory.clone()));
+                        entry.vector = entry.vector.max(hit.score);
+                    }
+                }
+            }
+        }
 
-        // Update ranks
-        for (i, r) in results.iter_mut().enumerate() {
-            r.rank = i + 1;
+        let mut groups = Vec::with_capacity(queries.len());
+        for (query, parts) in queries.iter().zip(scored_parts) {
+            let results = rank_recall(parts, &weights, limit_per_query);
+            groups.push(QueryRecall {
+                query: query.to_string(),
+                results: self.screen_recalled(results).await?,
+            });
         }
+        Ok(BatchRecall { groups })
+    }
 
-        Ok(results)
+    /// The stores and vector backends `pools` selects
+    fn searched_pools(
+        &self,
+        pools: PoolSelection,
+    ) -> Vec<(&Arc<MemoryStore>, &Arc<dyn VectorBackend>)> {
+        let mut searched = Vec::new();
+        if pools != PoolSelection::Shared {
+            searched.push((&self.store, &self.vector_backend));
+        }
+        if let (PoolSelection::Shared | PoolSelection::All, Some(shared)) = (pools, &self.shared) {
+            searched.push((&shared.store, &shared.vector_backend));
+        }
+        searched
     }
 
     /// Get important memories (what matters now)
     pub async fn get_important(&self, limit: usize) -> Result<Vec<Memory>> {
-        let candidate_limit = ((limit.saturating_mul(10)).max(100)).min(10_000) as i64;
+        let candidate_limit = limit.saturating_mul(10).clamp(100, 10_000) as i64;
         let all_memories = self
             .store
             .get_sorted(SortOrder::Importance, candidate_limit, None)
             .await?;
 
+        let weights = ImportanceWeights::default();
+        let now = self.store.now();
         let mut scored: Vec<(Memory, f32)> = all_memories
             .into_iter()
             .map(|m| {
-                let score = ImportanceCalculator::calculate(&m);
+                let score = ImportanceCalculator::calculate_at(&m, &weights, now);
                 (m, score)
             })
             .collect();
@@ -927,7 +2753,9 @@ impl MemoryCortex {
 
     /// Start a new episode (experience/context) - persisted to DB
     pub async fn start_episode(&self, title: &str, context: &str) -> Result<String> {
-        let experience = Experience::new(title, context);
+        let mut experience = Experience::new(title, context);
+        experience.id = self.store.next_id();
+        experience.started_at = self.store.now();
         let id = experience.id.clone();
 
         // Persist to DB
@@ -939,31 +2767,196 @@ impl MemoryCortex {
         Ok(id)
     }
 
-    /// End current episode - updates DB with end time and computed importance
+    /// Start a new episode as part of `parent_id`, e.g. a session within a project
+    pub async fn start_sub_episode(
+        &self,
+        parent_id: &str,
+        title: &str,
+        context: &str,
+    ) -> Result<String> {
+        if self.store.load_experience(parent_id).await?.is_none() {
+            return Err(MemoryError::NotFound(parent_id.to_string()));
+        }
+        let mut experience = Experience::new(title, context).with_parent(parent_id);
+        experience.id = self.store.next_id();
+        experience.started_at = self.store.now();
+        let id = experience.id.clone();
+
+        self.store.save_experience(&experience).await?;
+
+        let mut episode = self.current_experience.write().await;
+        *episode = Some(experience);
+
+        Ok(id)
+    }
+
+    /// End current episode - updates DB with end time and computed importance,
+    /// then rolls the result up into its parent episodes
     pub async fn end_episode(&self) -> Result<Option<Experience>> {
         let mut episode = self.current_experience.write().await;
         if let Some(ep) = episode.as_mut() {
-            ep.end();
+            let now = self.store.now();
+            ep.ended_at = Some(now);
 
             // Compute importance from constituent memories
-            let mut total_importance = 0.0;
-            let mut count = 0;
-            for mem_id in &ep.memory_ids {
-                if let Ok(Some(mem)) = self.store.load(mem_id).await {
-                    total_importance += ImportanceCalculator::calculate(&mem);
-                    count += 1;
-                }
-            }
-            if count > 0 {
-                ep.importance = total_importance / count as f32;
+            if let Some(importance) = self.episode_memory_importance(ep).await {
+                ep.importance = importance;
             }
 
             // Update in DB
             self.store.update_experience(ep).await?;
+            if let Some(parent_id) = &ep.parent_id {
+                self.roll_up_episode(parent_id).await?;
+            }
         }
         Ok(episode.take())
     }
 
+    /// Mean importance of an episode's own memories, `None` when it has none
+    async fn episode_memory_importance(&self, episode: &Experience) -> Option<f32> {
+        let now = self.store.now();
+        let mut total_importance = 0.0;
+        let mut count = 0;
+        for mem_id in &episode.memory_ids {
+            if let Ok(Some(mem)) = self.store.load(mem_id).await {
+                total_importance +=
+                    ImportanceCalculator::calculate_at(&mem, &ImportanceWeights::default(), now);
+                count += 1;
+            }
+        }
+        (count > 0).then(|| total_importance / count as f32)
+    }
+
+    /// Move an episode under `parent_id`, or to the top level with `None`.
+    /// Both the old and the new parent are rolled up again.
+    pub async fn set_episode_parent(
+        &self,
+        id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<Experience> {
+        let mut episode = self
+            .store
+            .load_experience(id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(id.to_string()))?;
+        if let Some(parent_id) = parent_id {
+            if self.store.load_experience(parent_id).await?.is_none() {
+                return Err(MemoryError::NotFound(parent_id.to_string()));
+            }
+            let cycle = parent_id == id
+                || self
+                    .episode_ancestors(parent_id)
+                    .await?
+                    .iter()
+                    .any(|ancestor| ancestor.id == id);
+            if cycle {
+                return Err(MemoryError::InvalidOperation(format!(
+                    "episode {parent_id} is {id} or one of its sub-episodes"
+                )));
+            }
+        }
+
+        let old_parent = episode.parent_id.take();
+        episode.parent_id = parent_id.map(str::to_string);
+        self.store.update_experience(&episode).await?;
+        for parent in old_parent.iter().chain(episode.parent_id.iter()) {
+            self.roll_up_episode(parent).await?;
+        }
+
+        Ok(episode)
+    }
+
+    /// Direct sub-episodes of an episode, oldest first
+    pub async fn episode_children(&self, id: &str) -> Result<Vec<Experience>> {
+        self.store.child_experiences(id).await
+    }
+
+    /// The episodes an episode is nested in, its parent first
+    pub async fn episode_ancestors(&self, id: &str) -> Result<Vec<Experience>> {
+        let mut ancestors: Vec<Experience> = Vec::new();
+        let mut next = match self.store.load_experience(id).await? {
+            Some(episode) => episode.parent_id,
+            None => return Ok(ancestors),
+        };
+        while let Some(parent_id) = next {
+            if ancestors.iter().any(|a| a.id == parent_id) {
+                break;
+            }
+            let Some(parent) = self.store.load_experience(&parent_id).await? else {
+                break;
+            };
+            next = parent.parent_id.clone();
+            ancestors.push(parent);
+        }
+        Ok(ancestors)
+    }
+
+    /// An episode with all its sub-episodes, nested
+    pub async fn episode_tree(&self, id: &str) -> Result<Option<EpisodeTree>> {
+        let Some(root) = self.store.load_experience(id).await? else {
+            return Ok(None);
+        };
+
+        let mut children: HashMap<String, Vec<Experience>> = HashMap::new();
+        let mut visited: HashSet<String> = HashSet::from([root.id.clone()]);
+        let mut frontier = vec![root.id.clone()];
+        while let Some(parent_id) = frontier.pop() {
+            let kids = self.store.child_experiences(&parent_id).await?;
+            let kids: Vec<Experience> = kids
+                .into_iter()
+                .filter(|kid| visited.insert(kid.id.clone()))
+                .collect();
+            frontier.extend(kids.iter().map(|kid| kid.id.clone()));
+            children.insert(parent_id, kids);
+        }
+
+        Ok(Some(EpisodeTree::assemble(root, &mut children)))
+    }
+
+    /// Recompute an episode's importance and summary from its sub-episodes,
+    /// then do the same for each of its ancestors. The importance becomes the
+    /// highest of its own memories' mean and its sub-episodes' importance; the
+    /// summary lists each sub-episode with its own summary (or context).
+    pub async fn roll_up_episode(&self, id: &str) -> Result<Option<Experience>> {
+        let mut rolled_up = None;
+        let mut visited = HashSet::new();
+        let mut next = Some(id.to_string());
+        while let Some(episode_id) = next.take() {
+            if !visited.insert(episode_id.clone()) {
+                break;
+            }
+            let Some(mut episode) = self.store.load_experience(&episode_id).await? else {
+                break;
+            };
+            let children = self.store.child_experiences(&episode_id).await?;
+            if !children.is_empty() {
+                let own = self.episode_memory_importance(&episode).await;
+                episode.importance = children
+                    .iter()
+                    .map(|child| child.importance)
+                    .chain(own)
+                    .fold(0.0, f32::max);
+                episode.summary = Some(
+                    children
+                        .iter()
+                        .map(|child| {
+                            let detail = child.summary.as_deref().unwrap_or(&child.context);
+                            format!("- {}: {}", child.title, detail.replace('\n', " "))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+                self.store.update_experience(&episode).await?;
+            }
+
+            next = episode.parent_id.clone();
+            if rolled_up.is_none() {
+                rolled_up = Some(episode);
+            }
+        }
+        Ok(rolled_up)
+    }
+
     /// Get current episode
     pub async fn get_current_experience(&self) -> Option<Experience> {
         let episode = self.current_experience.read().await;
@@ -995,32 +2988,161 @@ impl MemoryCortex {
 
     /// Get memories from a specific time
     pub async fn get_memories_since(&self, days_ago: i64) -> Result<Vec<Memory>> {
-        let since = Utc::now() - Duration::days(days_ago);
-        let filter = format!("created_at >= '{}'", since.format("%Y-%m-%d"));
-        self.store.query_with_filter(&filter, 1000).await
+        let since = (self.store.now() - Duration::days(days_ago)).date_naive();
+        self.store
+            .query(&MemoryQuery::new().since_day(since), 1000)
+            .await
     }
 
     // ─── Convenience Methods ──────────────────────────────────────────────
 
     /// Make a decision and remember it
     pub async fn decide(&self, decision: &str, context: &str, options: &[&str]) -> Result<Memory> {
-        let memory = Memory::new(
-            format!(
-                "Decision: {} - Context: {} - Options: {:?}",
-                decision, context, options
-            ),
-            MemoryType::Decision,
+        self.decide_with(
+            DecisionRecord::new(decision, context).with_options(options.iter().copied()),
         )
-        .with_importance(0.9);
+        .await
+    }
+
+    /// Remember a structured decision; the record is kept under `decision` in
+    /// the memory's metadata
+    pub async fn decide_with(&self, record: DecisionRecord) -> Result<Memory> {
+        let memory = self
+            .store
+            .new_memory(record.content(), MemoryType::Decision)
+            .with_importance(0.9)
+            .with_metadata(serde_json::json!({ DECISION_METADATA_KEY: record }));
 
         self.remember(&memory).await?;
 
         Ok(memory)
     }
 
+    /// Record what happened after a decision. The outcome is added to the
+    /// decision record and to the memory's text, so recall finds it too.
+    pub async fn record_outcome(
+        &self,
+        decision_id: &str,
+        actual_outcome: &str,
+        success: bool,
+    ) -> Result<Memory> {
+        let mut memory = self
+            .store
+            .load(decision_id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(decision_id.to_string()))?;
+        let mut record = DecisionRecord::from_memory(&memory).ok_or_else(|| {
+            MemoryError::InvalidOperation(format!("{decision_id} is not a decision record"))
+        })?;
+
+        let now = self.store.now();
+        record.outcome = Some(DecisionOutcome {
+            actual: actual_outcome.to_string(),
+            success,
+            recorded_at: now,
+        });
+
+        let mut metadata = metadata_map(&memory);
+        metadata.insert(
+            DECISION_METADATA_KEY.to_string(),
+            serde_json::to_value(&record).map_err(|e| MemoryError::Serialization(e.to_string()))?,
+        );
+        memory.metadata = Some(serde_json::Value::Object(metadata));
+        memory.content = record.content();
+        memory.updated_at = now;
+        self.store.update(&memory).await?;
+
+        let embedding = generate_embedding(&memory.content);
+        self.vector_backend
+            .upsert(&memory.id, &embedding, self.vector_payload(&memory))
+            .await?;
+
+        Ok(memory)
+    }
+
+    /// Decisions still waiting for an outcome, oldest first
+    pub async fn decisions_pending_outcome(&self, limit: usize) -> Result<Vec<Memory>> {
+        self.store.decisions_pending_outcome(limit as i64).await
+    }
+
+    /// Look for schedules among the event memories of the last `lookback_days`
+    /// and remember each one as an observation tagged `recurring`, with the
+    /// [`RecurringPattern`] under `recurrence` in its metadata. A pattern
+    /// already on record is refreshed rather than duplicated.
+    pub async fn detect_recurring_patterns(
+        &self,
+        detector: &RecurrenceDetector,
+        lookback_days: i64,
+    ) -> Result<Vec<Memory>> {
+        let since = self.store.now() - Duration::days(lookback_days);
+        let events = self
+            .store
+            .query(
+                &MemoryQuery::new().with_type(MemoryType::Event).since(since),
+                10_000,
+            )
+            .await?;
+        let patterns = detector.detect(&events);
+        if patterns.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let known = self
+            .store
+            .query(
+                &MemoryQuery::new()
+                    .with_type(MemoryType::Observation)
+                    .with_tag(RECURRING_TAG),
+                10_000,
+            )
+            .await?;
+        let mut recorded = Vec::new();
+        for pattern in patterns {
+            let existing = known.iter().find(|memory| {
+                RecurringPattern::from_memory(memory).is_some_and(|p| p.label == pattern.label)
+            });
+            let memory = match existing {
+                Some(existing) => {
+                    let mut memory = existing.clone();
+                    let mut metadata = metadata_map(&memory);
+                    metadata.insert(
+                        RECURRENCE_METADATA_KEY.to_string(),
+                        serde_json::to_value(&pattern)
+                            .map_err(|e| MemoryError::Serialization(e.to_string()))?,
+                    );
+                    memory.metadata = Some(serde_json::Value::Object(metadata));
+                    memory.content = pattern.content();
+                    memory.updated_at = self.store.now();
+                    self.store.update(&memory).await?;
+                    let embedding = generate_embedding(&memory.content);
+                    self.vector_backend
+                        .upsert(&memory.id, &embedding, self.vector_payload(&memory))
+                        .await?;
+                    memory
+                }
+                None => {
+                    let mut memory = self
+                        .store
+                        .new_memory(pattern.content(), MemoryType::Observation)
+                        .with_importance(0.4 + 0.4 * pattern.confidence)
+                        .with_metadata(serde_json::json!({ RECURRENCE_METADATA_KEY: pattern }));
+                    memory.tags = vec![RECURRING_TAG.to_string()];
+                    self.remember(&memory).await?;
+                    memory
+                }
+            };
+            recorded.push(memory);
+        }
+
+        Ok(recorded)
+    }
+
     /// Store a preference
     pub async fn prefer(&self, preference: &str, importance: f32) -> Result<Memory> {
-        let memory = Memory::new(preference, MemoryType::Preference).with_importance(importance);
+        let memory = self
+            .store
+            .new_memory(preference, MemoryType::Preference)
+            .with_importance(importance);
 
         self.remember(&memory).await?;
 
@@ -1029,7 +3151,10 @@ impl MemoryCortex {
 
     /// Set a goal
     pub async fn goal(&self, goal: &str) -> Result<Memory> {
-        let memory = Memory::new(goal, MemoryType::Goal).with_importance(0.95);
+        let memory = self
+            .store
+            .new_memory(goal, MemoryType::Goal)
+            .with_importance(0.95);
 
         self.remember(&memory).await?;
 
@@ -1051,27 +3176,111 @@ impl MemoryCortex {
 
     /// Working memory decay (call periodically)
     pub async fn decay(&self) {
-        let mut wm = self.working_memory.write().await;
-        wm.decay();
+        self.working_memory.write().await.decay();
+        self.working_memory_changed().await;
     }
 
     /// Clear working memory (for new context)
     pub async fn clear_context(&self) {
-        let mut wm = self.working_memory.write().await;
-        wm.clear();
+        self.working_memory.write().await.clear();
+        self.working_memory_changed().await;
+    }
+
+    /// Working-memory items, highest attention first, as reported by
+    /// [`Pulse::WorkingMemoryChanged`]
+    pub async fn attention_snapshot(&self, include_attention: bool) -> Vec<AttentionItem> {
+        let wm = self.working_memory.read().await;
+        let mut items: Vec<&WorkingMemoryItem> = wm.all().iter().collect();
+        items.sort_by(|a, b| {
+            b.attention_score
+                .partial_cmp(&a.attention_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        items
+            .into_iter()
+            .map(|item| AttentionItem {
+                memory_id: item.memory_id.clone(),
+                pinned: item.pinned,
+                attention_score: include_attention.then_some(item.attention_score),
+            })
+            .collect()
+    }
+
+    /// Emit a working-memory snapshot every `WorkingMemoryPulseConfig::interval`
+    /// until the cortex is dropped. `None` without pulses or an interval.
+    pub fn spawn_working_memory_pulses(self: &Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        let (_, config) = self.pulses.as_ref()?;
+        let period = config.interval?;
+        let cortex = Arc::downgrade(self);
+        Some(tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(period);
+            loop {
+                ticks.tick().await;
+                let Some(cortex) = cortex.upgrade() else {
+                    break;
+                };
+                let Some((pulses, config)) = &cortex.pulses else {
+                    break;
+                };
+                let items = cortex.attention_snapshot(config.include_attention).await;
+                pulses
+                    .emit(Pulse::WorkingMemoryChanged {
+                        items,
+                        timestamp: cortex.store.now(),
+                    })
+                    .await;
+            }
+        }))
+    }
+
+    /// Emit [`Pulse::WorkingMemoryChanged`] if on-change pulses are enabled and
+    /// working memory differs from the last snapshot emitted
+    async fn working_memory_changed(&self) {
+        let Some((pulses, config)) = &self.pulses else {
+            return;
+        };
+        if !config.on_change {
+            return;
+        }
+        let items = self.attention_snapshot(config.include_attention).await;
+        {
+            let mut last = self.last_attention.write().await;
+            if last.as_ref() == Some(&items) {
+                return;
+            }
+            *last = Some(items.clone());
+        }
+        pulses
+            .emit(Pulse::WorkingMemoryChanged {
+                items,
+                timestamp: self.store.now(),
+            })
+            .await;
     }
 
     // ─── Context Window ───────────────────────────────────────────────────
 
     /// Build a context window for LLM consumption
     pub async fn build_context(&self, config: &ContextWindow) -> Result<String> {
-        config.build(self).await
+        let context = config.build(self).await?;
+        self.middleware.context_build(context).await
+    }
+
+    /// Build the context window registered as `profile` in [`CortexConfig`]
+    pub async fn build_context_for(&self, profile: &str) -> Result<String> {
+        let config = self.context_profile(profile).ok_or_else(|| {
+            MemoryError::Configuration(format!("unknown context profile {profile:?}"))
+        })?;
+        self.build_context(config).await
+    }
+
+    pub fn context_profile(&self, name: &str) -> Option<&ContextWindow> {
+        self.context_profiles.get(name)
     }
 
     /// Full memory dump for context window (legacy API, delegates to ContextWindow)
     pub async fn get_full_context(&self, _max_memories: usize) -> Result<String> {
-        let config = ContextWindow::default();
-        config.build(self).await
+        self.build_context(&ContextWindow::default()).await
     }
 
     // ─── Memory Consolidation ─────────────────────────────────────────────
@@ -1079,14 +3288,13 @@ impl MemoryCortex {
     /// Consolidate old, low-importance memories into summaries
     /// Returns the number of memories consolidated
     pub async fn consolidate(&self, threshold: f32, max_age_days: i64) -> Result<usize> {
-        let cutoff = Utc::now() - Duration::days(max_age_days);
-        let filter = format!(
-            "created_at < '{}' AND importance < {} AND forgotten = 0",
-            cutoff.format("%Y-%m-%d"),
-            threshold
-        );
+        let cutoff = (self.store.now() - Duration::days(max_age_days)).date_naive();
+        let query = MemoryQuery::new()
+            .before_day(cutoff)
+            .with_importance_range(Bound::Unbounded, Bound::Excluded(threshold))
+            .without_pinned();
 
-        let candidates = self.store.query_with_filter(&filter, 1000).await?;
+        let candidates = self.store.query(&query, 1000).await?;
 
         if candidates.is_empty() {
             return Ok(0);
@@ -1129,7 +3337,9 @@ impl MemoryCortex {
             );
 
             // Create the summary memory
-            let summary_memory = Memory::new(&summary_text, MemoryType::Summary)
+            let summary_memory = self
+                .store
+                .new_memory(&summary_text, MemoryType::Summary)
                 .with_importance(0.5)
                 .with_metadata(serde_json::json!({
                     "original_ids": original_ids,
@@ -1140,7 +3350,9 @@ impl MemoryCortex {
             self.store.save(&summary_memory).await?;
 
             // Save summary record
-            let summary = MemorySummary::new(&summary_text, original_ids.clone(), *mem_type);
+            let mut summary = MemorySummary::new(&summary_text, original_ids.clone(), *mem_type);
+            summary.id = self.store.next_id();
+            summary.created_at = self.store.now();
             self.store.save_summary(&summary).await?;
 
             // Soft-delete originals
@@ -1162,4 +3374,307 @@ impl MemoryCortex {
     pub async fn get_summaries(&self) -> Result<Vec<MemorySummary>> {
         self.store.get_summaries().await
     }
+
+    /// Summarize the emotional tone of memories created within the last `window`
+    pub async fn mood_summary(&self, window: Duration) -> Result<MoodSummary> {
+        let window_end = self.store.now();
+        let window_start = window_end - window;
+        let midpoint = window_start + window / 2;
+
+        let rows = self.store.get_valence_since(window_start).await?;
+        let valences: Vec<f32> = rows.iter().map(|(_, v, _)| *v).collect();
+        let (earlier, later): (Vec<_>, Vec<_>) = rows.iter().partition(|(_, _, at)| *at < midpoint);
+        let earlier: Vec<f32> = earlier.iter().map(|(_, v, _)| *v).collect();
+        let later: Vec<f32> = later.iter().map(|(_, v, _)| *v).collect();
+        let trend = match (mean(&earlier), mean(&later)) {
+            (Some(a), Some(b)) => b - a,
+            _ => 0.0,
+        };
+
+        let episodes = self
+            .store
+            .episode_valence_since(window_start)
+            .await?
+            .into_iter()
+            .map(|(id, title, average_valence, count)| EpisodeMood {
+                experience_id: id,
+                title,
+                average_valence,
+                memory_count: count.max(0) as usize,
+            })
+            .collect();
+
+        Ok(MoodSummary {
+            window_start,
+            window_end,
+            memory_count: valences.len(),
+            average_valence: mean(&valences).unwrap_or(0.0),
+            positive: valences.iter().filter(|v| **v > NEUTRAL_VALENCE).count(),
+            negative: valences.iter().filter(|v| **v < -NEUTRAL_VALENCE).count(),
+            neutral: valences
+                .iter()
+                .filter(|v| v.abs() <= NEUTRAL_VALENCE)
+                .count(),
+            trend,
+            episodes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_backend::InMemoryVectorBackend;
+    use crate::vector_chunks::VectorChunk;
+
+    #[tokio::test]
+    async fn recall_drops_vectors_of_deleted_memories_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let dimension = generate_embedding("").len();
+        let vectors = Arc::new(InMemoryVectorBackend::new(dimension));
+        let cortex = MemoryCortex::new_with_vector_backend(dir.path(), vectors.clone())
+            .await
+            .unwrap();
+        let forgotten = Memory::new("Deploys happen on Tuesday", MemoryType::Fact);
+        let deleted = Memory::new("Deploy rollbacks need approval", MemoryType::Fact);
+        cortex.remember(&forgotten).await.unwrap();
+        cortex.remember(&deleted).await.unwrap();
+
+        // A chunk vector left by a MemorySystem sharing the backend
+        let chunk = VectorChunk {
+            index: 0,
+            start: 0,
+            end: deleted.content.len(),
+        };
+        vectors
+            .upsert(
+                &chunk_vector_id(&deleted.id, 0),
+                &generate_embedding(&deleted.content),
+                None,
+            )
+            .await
+            .unwrap();
+        cortex
+            .store()
+            .save_vector_chunks(&deleted.id, &[chunk])
+            .await
+            .unwrap();
+        assert_eq!(vectors.stats().await.unwrap().rows, 3);
+
+        cortex.store().forget(&forgotten.id).await.unwrap();
+        cortex.store().delete(&deleted.id).await.unwrap();
+        assert!(cortex.recall("deploy", 5).await.unwrap().is_empty());
+
+        assert_eq!(vectors.stats().await.unwrap().rows, 1);
+        assert!(cortex
+            .store()
+            .vector_chunks(&deleted.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(cortex.restore(&forgotten.id).await.unwrap());
+        assert_eq!(cortex.recall("deploy", 5).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lapsed_pins_come_back_unpinned() {
+        let clock = Arc::new(crate::clock::MockClock::default());
+        let mut wm = WorkingMemory::new(10).with_clock(clock.clone());
+        let pinned = Memory::new("Release freeze starts Friday", MemoryType::Fact);
+        let hot = Memory::new("Staging is down", MemoryType::Fact);
+        wm.remember(&pinned, None);
+        wm.remember(&hot, None);
+        wm.items[1].attention_score = 0.9;
+        wm.items[0].attention_score = 0.1;
+        wm.pin_for(&pinned.id, Some(Duration::minutes(5)));
+
+        let context = wm.get_context();
+        assert_eq!(context[0].memory_id, pinned.id);
+        assert!(context[0].pinned);
+
+        clock.advance(Duration::minutes(6));
+        let context = wm.get_context();
+        assert_eq!(context[0].memory_id, hot.id);
+        let lapsed = context.iter().find(|i| i.memory_id == pinned.id).unwrap();
+        assert!(!lapsed.pinned);
+        assert!(lapsed.pin_expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn batch_recall_groups_per_query_and_merges_at_best_score() {
+        let dir = tempfile::tempdir().unwrap();
+        let cortex = MemoryCortex::new(dir.path()).await.unwrap();
+        let deploy = Memory::new("Deploys happen on Tuesday", MemoryType::Fact);
+        let coffee = Memory::new("The team drinks coffee on Tuesday", MemoryType::Fact);
+        cortex.remember(&deploy).await.unwrap();
+        cortex.remember(&coffee).await.unwrap();
+
+        let batch = cortex
+            .recall_many(&["deploys", "coffee", "tuesday"], 5)
+            .await
+            .unwrap();
+        let queries: Vec<&str> = batch.groups.iter().map(|g| g.query.as_str()).collect();
+        assert_eq!(queries, ["deploys", "coffee", "tuesday"]);
+
+        let deploys = batch.results_for("deploys").unwrap();
+        assert_eq!(deploys[0].memory.id, deploy.id);
+        let coffees = batch.results_for("coffee").unwrap();
+        assert_eq!(coffees[0].memory.id, coffee.id);
+        assert!(batch.results_for("standup").is_none());
+
+        let merged = batch.merged();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged.iter().map(|r| r.rank).collect::<Vec<_>>(), [1, 2]);
+        assert!(merged[0].score >= merged[1].score);
+        for result in &merged {
+            let best = batch
+                .groups
+                .iter()
+                .flat_map(|g| &g.results)
+                .filter(|r| r.memory.id == result.memory.id)
+                .map(|r| r.score)
+                .fold(f32::MIN, f32::max);
+            assert_eq!(result.score, best);
+        }
+    }
+
+    #[test]
+    fn type_selection_balances_or_caps_types() {
+        let ranked: Vec<Memory> = [
+            ("Ship the v2 API", MemoryType::Goal),
+            ("Hire a second SRE", MemoryType::Goal),
+            ("Cut cloud spend", MemoryType::Goal),
+            ("Prefers short answers", MemoryType::Preference),
+            ("Chose Postgres over MySQL", MemoryType::Decision),
+        ]
+        .into_iter()
+        .map(|(content, memory_type)| Memory::new(content, memory_type))
+        .collect();
+        let types = |selected: Vec<Memory>| -> Vec<MemoryType> {
+            selected.iter().map(|m| m.memory_type).collect()
+        };
+
+        assert_eq!(
+            types(TypeSelection::ByImportance.select(ranked.clone(), 3)),
+            [MemoryType::Goal; 3]
+        );
+        assert_eq!(
+            types(TypeSelection::RoundRobin.select(ranked.clone(), 4)),
+            [
+                MemoryType::Goal,
+                MemoryType::Preference,
+                MemoryType::Decision,
+                MemoryType::Goal
+            ]
+        );
+        let quotas = TypeSelection::Quotas {
+            per_type: HashMap::from([(MemoryType::Goal, 1)]),
+            default: 5,
+        };
+        let selected = quotas.select(ranked.clone(), 10);
+        assert_eq!(
+            types(selected.clone()),
+            [
+                MemoryType::Goal,
+                MemoryType::Preference,
+                MemoryType::Decision
+            ]
+        );
+        assert_eq!(selected[0].content, "Ship the v2 API");
+    }
+
+    #[tokio::test]
+    async fn context_profiles_pick_their_own_sections_and_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CortexConfig::default()
+            .with_context_profile(
+                "planner",
+                ContextWindow::new(1500)
+                    .with_sections([ContextSection::Important])
+                    .with_memory_types([MemoryType::Goal, MemoryType::Decision]),
+            )
+            .with_context_profile(
+                "executor",
+                ContextWindow::new(1500)
+                    .with_sections([ContextSection::Recent, ContextSection::Important])
+                    .with_memory_types([MemoryType::Event])
+                    .with_section_budget(ContextSection::Recent, 60),
+            );
+        let cortex = MemoryCortex::new(dir.path())
+            .await
+            .unwrap()
+            .with_config(config);
+        let goal = Memory::new("Ship the v2 API", MemoryType::Goal).with_importance(0.9);
+        let first = Memory::new("Deployed build 41", MemoryType::Event).with_importance(0.8);
+        let second = Memory::new("Deployed build 42", MemoryType::Event).with_importance(0.8);
+        for memory in [&goal, &first, &second] {
+            cortex.remember(memory).await.unwrap();
+        }
+
+        let planner = cortex.build_context_for("planner").await.unwrap();
+        assert!(planner.starts_with("\n## Important Memories\n"));
+        assert!(planner.contains("Ship the v2 API"));
+        assert!(!planner.contains("Deployed"));
+
+        let executor = cortex.build_context_for("executor").await.unwrap();
+        let recent = executor.find("## Recent Memories").unwrap();
+        let important = executor.find("## Important Memories").unwrap();
+        assert!(recent < important);
+        assert!(!executor.contains("Ship the v2 API"));
+        // The recent section's budget only fits one line
+        let recent_section = &executor[recent..important];
+        assert_eq!(recent_section.matches("Deployed build").count(), 1);
+
+        let unknown = cortex.build_context_for("reviewer").await;
+        assert!(matches!(unknown, Err(MemoryError::Configuration(_))));
+        assert!(cortex.context_profile("planner").is_some());
+    }
+
+    #[tokio::test]
+    async fn sub_episodes_roll_up_into_their_parents_and_refuse_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let cortex = MemoryCortex::new(dir.path()).await.unwrap();
+        let launch = cortex
+            .start_episode("Launch v2", "Everything for the v2 launch")
+            .await
+            .unwrap();
+        let migration = cortex
+            .start_sub_episode(&launch, "Migrate billing", "Move billing to the v2 API")
+            .await
+            .unwrap();
+        cortex.end_episode().await.unwrap();
+        let cutover = cortex
+            .start_sub_episode(&migration, "Cut over", "Flip traffic\nto v2")
+            .await
+            .unwrap();
+        cortex.end_episode().await.unwrap();
+
+        let ancestors = cortex.episode_ancestors(&cutover).await.unwrap();
+        let ids: Vec<&str> = ancestors.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, [migration.as_str(), launch.as_str()]);
+
+        let tree = cortex.episode_tree(&launch).await.unwrap().unwrap();
+        assert_eq!(tree.count(), 3);
+        assert_eq!(tree.children[0].episode.id, migration);
+        assert_eq!(tree.children[0].children[0].episode.id, cutover);
+        let migration_summary = tree.children[0].episode.summary.as_deref().unwrap();
+        assert_eq!(migration_summary, "- Cut over: Flip traffic to v2");
+        assert_eq!(
+            tree.episode.summary.as_deref(),
+            Some("- Migrate billing: - Cut over: Flip traffic to v2")
+        );
+
+        let cycle = cortex.set_episode_parent(&launch, Some(&cutover)).await;
+        assert!(matches!(cycle, Err(MemoryError::InvalidOperation(_))));
+        let missing = cortex.start_sub_episode("missing", "Orphan", "").await;
+        assert!(matches!(missing, Err(MemoryError::NotFound(_))));
+
+        let detached = cortex.set_episode_parent(&cutover, None).await.unwrap();
+        assert!(detached.parent_id.is_none());
+        assert!(cortex
+            .episode_children(&migration)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
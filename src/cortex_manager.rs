@@ -0,0 +1,492 @@
+//! Many cortices over one database
+//!
+//! [`CortexManager`] serves a fleet of agents (or users) from a single data
+//! directory. Each agent gets its own [`MemoryCortex`] in a namespace: a
+//! namespace-scoped [`MemoryStore`] over the shared SQLite pool and a
+//! [`VectorCollection`] of the shared vector backend.
+//!
+//! Cortices are created on first use and kept in memory while active. Idle
+//! cortices are unloaded after [`CortexManagerConfig::idle_ttl`], and the least
+//! recently used one is unloaded when more than
+//! [`CortexManagerConfig::max_loaded`] would be resident. Unloading only drops
+//! in-memory state (working memory, the open episode); memories stay in the store.
+//!
+//! With [`CortexManagerConfig::shared_namespace`] set, every cortex also gets that
+//! namespace attached as its [`SharedPool`]: a team pool searched alongside its
+//! private memories and filled with [`MemoryCortex::promote_to_shared`].
+
+use crate::cortex::{MemoryCortex, SharedPool};
+use crate::error::{MemoryError, Result};
+use crate::maintenance::{self, MaintenanceConfig, MaintenanceReport, PurgeCleanup};
+use crate::manifest::DataDirManifest;
+use crate::store::{NamespaceStats, DEFAULT_NAMESPACE};
+use crate::vector_backend::{
+    validate_collection_name, VectorBackend, VectorCollection, VectorIndexStats,
+    VectorMaintenanceReport,
+};
+use crate::vector_chunks::chunk_vector_id;
+use crate::MemoryStore;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Limits on how many cortices a [`CortexManager`] keeps loaded
+#[derive(Debug, Clone)]
+pub struct CortexManagerConfig {
+    /// Maximum number of resident cortices before the least recently used is unloaded
+    pub max_loaded: usize,
+    /// Unload cortices not used for this long (`None` keeps them until evicted by size)
+    pub idle_ttl: Option<Duration>,
+    /// Namespace of the team pool shared by all cortices (`None` disables sharing)
+    pub shared_namespace: Option<String>,
+}
+
+impl Default for CortexManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_loaded: 1000,
+            idle_ttl: Some(Duration::from_secs(30 * 60)),
+            shared_namespace: None,
+        }
+    }
+}
+
+impl CortexManagerConfig {
+    pub fn with_max_loaded(mut self, max_loaded: usize) -> Self {
+        self.max_loaded = max_loaded.max(1);
+        self
+    }
+
+    pub fn with_idle_ttl(mut self, idle_ttl: Option<Duration>) -> Self {
+        self.idle_ttl = idle_ttl;
+        self
+    }
+
+    pub fn with_shared_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.shared_namespace = Some(namespace.into());
+        self
+    }
+}
+
+/// Result of [`CortexManager::maintain_all`]
+#[derive(Debug, Default)]
+pub struct FleetMaintenanceReport {
+    /// Store maintenance per namespace
+    pub namespaces: BTreeMap<String, MaintenanceReport>,
+    /// Maintenance of the shared vector backend, if enabled
+    pub vector: Option<VectorMaintenanceReport>,
+}
+
+/// Result of [`CortexManager::stats_all`]
+#[derive(Debug, Clone)]
+pub struct FleetStats {
+    /// Row counts of every namespace in the database
+    pub namespaces: Vec<NamespaceStats>,
+    /// Namespaces whose cortex is currently loaded
+    pub loaded: Vec<String>,
+    /// Layout of the shared vector backend
+    pub vectors: VectorIndexStats,
+}
+
+struct LoadedCortex {
+    cortex: Arc<MemoryCortex>,
+    last_used: Instant,
+}
+
+/// Drops a purged memory's vectors, chunks included, from its namespace's collection
+struct NamespaceVectors<'a> {
+    store: &'a MemoryStore,
+    vector: &'a Arc<dyn VectorBackend>,
+    namespace: &'a str,
+}
+
+#[async_trait::async_trait]
+impl PurgeCleanup for NamespaceVectors<'_> {
+    async fn before_purge(&self, id: &str) -> Result<()> {
+        let namespace = Some(self.namespace);
+        for chunk in self.store.vector_chunks(id).await? {
+            self.vector
+                .delete_in(namespace, &chunk_vector_id(id, chunk.index))
+                .await?;
+        }
+        self.vector.delete_in(namespace, id).await
+    }
+}
+
+/// Owns many named [`MemoryCortex`] instances over one store and vector backend
+pub struct CortexManager {
+    store: Arc<MemoryStore>,
+    vector_backend: Arc<dyn VectorBackend>,
+    data_dir: PathBuf,
+    config: CortexManagerConfig,
+    loaded: Mutex<HashMap<String, LoadedCortex>>,
+}
+
+impl std::fmt::Debug for CortexManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CortexManager")
+            .field("data_dir", &self.data_dir)
+            .field("vector_backend", &self.vector_backend.name())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl CortexManager {
+    /// Open (or create) a shared data directory with the default vector backend
+    pub async fn open(data_dir: impl Into<PathBuf>, config: CortexManagerConfig) -> Result<Self> {
+        let data_dir = data_dir.into();
+        DataDirManifest::open(&data_dir)?;
+
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(data_dir.join("memories.db"))
+            .create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options).await?;
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .map_err(|e| MemoryError::Database(e.into()))?;
+
+        let vector_backend = MemoryCortex::build_default_vector_backend(&data_dir).await?;
+        MemoryCortex::record_vector_backend(&data_dir, vector_backend.as_ref())?;
+
+        Self::new(data_dir, MemoryStore::new(pool), vector_backend, config)
+    }
+
+    /// Build a manager over an existing store and vector backend.
+    ///
+    /// The backend must support named collections, since every namespace keeps its
+    /// vectors in a collection of the same name.
+    pub fn new(
+        data_dir: impl Into<PathBuf>,
+        store: Arc<MemoryStore>,
+        vector_backend: Arc<dyn VectorBackend>,
+        config: CortexManagerConfig,
+    ) -> Result<Self> {
+        if !vector_backend.supports_collections() {
+            return Err(MemoryError::Configuration(format!(
+                "Vector backend '{}' does not support collections, which CortexManager needs \
+                 to keep namespaces apart",
+                vector_backend.name()
+            )));
+        }
+
+        Ok(Self {
+            store,
+            vector_backend,
+            data_dir: data_dir.into(),
+            config,
+            loaded: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn config(&self) -> &CortexManagerConfig {
+        &self.config
+    }
+
+    /// The cortex of `namespace`, loading it if needed.
+    ///
+    /// Namespace names follow the vector collection rules: 1-64 ASCII letters,
+    /// digits, `_` or `-`.
+    pub async fn get(&self, namespace: &str) -> Result<Arc<MemoryCortex>> {
+        validate_collection_name(namespace)?;
+
+        let mut loaded = self.loaded.lock().await;
+        if let Some(entry) = loaded.get_mut(namespace) {
+            entry.last_used = Instant::now();
+            return Ok(entry.cortex.clone());
+        }
+
+        self.evict_idle_locked(&mut loaded);
+        while loaded.len() >= self.config.max_loaded {
+            if !Self::evict_lru_locked(&mut loaded) {
+                break;
+            }
+        }
+
+        let collection = VectorCollection::new(self.vector_backend.clone(), namespace)?;
+        let mut cortex = MemoryCortex::from_store(
+            &self.data_dir,
+            self.store.scoped(namespace),
+            Arc::new(collection),
+        );
+        if let Some(shared) = self.shared_pool(namespace)? {
+            cortex = cortex.with_shared_pool(shared);
+        }
+        let cortex = Arc::new(cortex);
+        loaded.insert(
+            namespace.to_string(),
+            LoadedCortex {
+                cortex: cortex.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        tracing::debug!("Loaded cortex '{}' ({} resident)", namespace, loaded.len());
+
+        Ok(cortex)
+    }
+
+    /// The team pool for cortex `namespace`, unless sharing is off or it is the
+    /// shared namespace itself
+    fn shared_pool(&self, namespace: &str) -> Result<Option<SharedPool>> {
+        match self.config.shared_namespace.as_deref() {
+            Some(shared) if shared != namespace => {
+                let collection = VectorCollection::new(self.vector_backend.clone(), shared)?;
+                Ok(Some(SharedPool::new(
+                    self.store.scoped(shared),
+                    Arc::new(collection),
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Drop the in-memory cortex of `namespace`. Returns false if it wasn't loaded.
+    pub async fn unload(&self, namespace: &str) -> bool {
+        self.loaded.lock().await.remove(namespace).is_some()
+    }
+
+    /// Unload every cortex idle for longer than the configured TTL.
+    /// Returns how many were unloaded.
+    pub async fn evict_idle(&self) -> usize {
+        let mut loaded = self.loaded.lock().await;
+        self.evict_idle_locked(&mut loaded)
+    }
+
+    /// Namespaces whose cortex is currently loaded, sorted
+    pub async fn loaded_namespaces(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.loaded.lock().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Every namespace with data in the store, loaded or not
+    pub async fn namespaces(&self) -> Result<Vec<String>> {
+        Ok(self
+            .store
+            .list_namespaces()
+            .await?
+            .into_iter()
+            .filter(|ns| ns != DEFAULT_NAMESPACE)
+            .collect())
+    }
+
+    /// Run store maintenance for every namespace, then maintain the shared vector
+    /// backend once. Namespaces don't need to be loaded. Vectors of purged
+    /// forgotten memories are deleted from their namespace's collection.
+    pub async fn maintain_all(&self, config: &MaintenanceConfig) -> Result<FleetMaintenanceReport> {
+        let mut report = FleetMaintenanceReport::default();
+
+        for namespace in self.namespaces().await? {
+            let store = self.store.scoped(namespace.as_str());
+            let cleanup = NamespaceVectors {
+                store: &store,
+                vector: &self.vector_backend,
+                namespace: namespace.as_str(),
+            };
+            let namespace_report =
+                maintenance::run_maintenance_with(&store, config, &cleanup).await?;
+            report.namespaces.insert(namespace, namespace_report);
+        }
+
+        if config.enable_vector_maintenance {
+            report.vector = Some(self.vector_backend.maintain().await?);
+        }
+
+        Ok(report)
+    }
+
+    /// Row counts of every namespace plus the shared vector backend layout
+    pub async fn stats_all(&self) -> Result<FleetStats> {
+        let namespaces = self
+            .store
+            .namespace_stats()
+            .await?
+            .into_iter()
+            .filter(|stats| stats.namespace != DEFAULT_NAMESPACE)
+            .collect();
+
+        Ok(FleetStats {
+            namespaces,
+            loaded: self.loaded_namespaces().await,
+            vectors: self.vector_backend.stats().await?,
+        })
+    }
+
+    fn evict_idle_locked(&self, loaded: &mut HashMap<String, LoadedCortex>) -> usize {
+        let Some(ttl) = self.config.idle_ttl else {
+            return 0;
+        };
+        let before = loaded.len();
+        loaded.retain(|_, entry| !is_evictable(entry) || entry.last_used.elapsed() < ttl);
+        before - loaded.len()
+    }
+
+    /// Unload the least recently used cortex nobody else holds. Returns false if
+    /// every loaded cortex is still referenced by a caller.
+    fn evict_lru_locked(loaded: &mut HashMap<String, LoadedCortex>) -> bool {
+        let victim = loaded
+            .iter()
+            .filter(|(_, entry)| is_evictable(entry))
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(name, _)| name.clone());
+
+        match victim {
+            Some(name) => {
+                loaded.remove(&name);
+                tracing::debug!("Evicted least recently used cortex '{}'", name);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A cortex still held by a caller keeps its working memory alive, so don't drop it
+fn is_evictable(entry: &LoadedCortex) -> bool {
+    Arc::strong_count(&entry.cortex) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cortex::PoolSelection;
+    use crate::types::{Memory, MemoryType, RelationType};
+    use crate::vector_backend::FileVectorBackend;
+
+    async fn manager(dir: &Path, config: CortexManagerConfig) -> CortexManager {
+        let store = MemoryStore::connect_in_memory().await;
+        let vectors = Arc::new(FileVectorBackend::new(dir.join("vectors"), 384));
+        vectors.ensure_ready().await.unwrap();
+        CortexManager::new(dir, store, vectors, config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn namespaces_are_isolated() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path(), CortexManagerConfig::default()).await;
+
+        let alice = manager.get("alice").await.unwrap();
+        let bob = manager.get("bob").await.unwrap();
+        let memory = Memory::new("alice likes tea", MemoryType::Preference);
+        alice.remember(&memory).await.unwrap();
+
+        assert!(alice.think_about(&memory.id).await.unwrap().is_some());
+        assert!(bob.think_about(&memory.id).await.unwrap().is_none());
+
+        let stats = manager.stats_all().await.unwrap();
+        assert_eq!(stats.namespaces.len(), 1);
+        assert_eq!(stats.namespaces[0].namespace, "alice");
+        assert_eq!(stats.namespaces[0].memories, 1);
+
+        let report = manager
+            .maintain_all(&MaintenanceConfig::default())
+            .await
+            .unwrap();
+        assert!(report.namespaces.contains_key("alice"));
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CortexManagerConfig::default().with_max_loaded(2);
+        let manager = manager(dir.path(), config).await;
+
+        drop(manager.get("a").await.unwrap());
+        drop(manager.get("b").await.unwrap());
+        drop(manager.get("a").await.unwrap());
+        drop(manager.get("c").await.unwrap());
+        assert_eq!(manager.loaded_namespaces().await, vec!["a", "c"]);
+
+        // A cortex still held by a caller is never evicted
+        let held = manager.get("d").await.unwrap();
+        drop(manager.get("e").await.unwrap());
+        drop(manager.get("f").await.unwrap());
+        assert!(manager.loaded_namespaces().await.contains(&"d".to_string()));
+        drop(held);
+
+        assert!(manager.get("bad name!").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn promotes_to_and_copies_from_shared_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CortexManagerConfig::default().with_shared_namespace("team");
+        let manager = manager(dir.path(), config).await;
+        let alice = manager.get("alice").await.unwrap();
+        let bob = manager.get("bob").await.unwrap();
+
+        let memory = Memory::new("deploys happen on tuesdays", MemoryType::Fact);
+        alice.remember(&memory).await.unwrap();
+        let shared_id = alice.promote_to_shared(&memory.id).await.unwrap();
+        assert_eq!(
+            alice.promote_to_shared(&memory.id).await.unwrap(),
+            shared_id
+        );
+
+        let found = bob.recall("deploys tuesdays", 5).await.unwrap();
+        assert_eq!(found[0].memory.id, shared_id);
+        assert!(bob
+            .recall_in("deploys tuesdays", 5, PoolSelection::Private)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let copy_id = bob.copy_from_shared(&shared_id).await.unwrap();
+        let copy = bob.think_about(&copy_id).await.unwrap().unwrap();
+        let provenance = &copy.metadata.unwrap()["provenance"];
+        assert_eq!(provenance["source_id"], shared_id.as_str());
+        assert_eq!(provenance["source_namespace"], "team");
+    }
+
+    #[tokio::test]
+    async fn hands_off_a_task_between_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path(), CortexManagerConfig::default()).await;
+        let alice = manager.get("alice").await.unwrap();
+        let bob = manager.get("bob").await.unwrap();
+
+        alice.goal("Finish billing migration").await.unwrap();
+        let task_id = alice
+            .start_episode("Migrate billing", "v2 API")
+            .await
+            .unwrap();
+        let plan = Memory::new("Plan: dual-write then cut over", MemoryType::Decision);
+        let risk = Memory::new("Risk: webhook retries", MemoryType::Observation);
+        alice.remember(&plan).await.unwrap();
+        alice.remember(&risk).await.unwrap();
+        alice
+            .link(&risk.id, &plan.id, RelationType::RelatedTo)
+            .await
+            .unwrap();
+        alice.pin(&plan.id).await;
+
+        let bundle = alice.export_handoff(&task_id).await.unwrap();
+        assert_eq!(bundle.memories.len(), 3);
+        assert_eq!(bundle.goal_ids.len(), 1);
+        assert_eq!(bundle.pinned_ids, vec![plan.id.clone()]);
+        assert!(bundle.episode_summary.starts_with("Migrate billing"));
+
+        // Same database, so every ID is already taken by alice's namespace
+        let report = bob.import_handoff(&bundle).await.unwrap();
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.remapped.len(), 3);
+        let plan_id = report.resolve(&plan.id);
+        let related = bob.get_related(report.resolve(&risk.id), 1).await.unwrap();
+        assert_eq!(related[0].id, plan_id);
+        let episode = bob.get_episode(&report.episode_id).await.unwrap().unwrap();
+        assert_eq!(episode.memory_ids.len(), 2);
+        assert!(bob
+            .get_context()
+            .await
+            .iter()
+            .any(|i| i.memory_id == plan_id && i.pinned));
+    }
+}
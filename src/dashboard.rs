@@ -6,10 +6,14 @@
 //!
 //! ### Memories
 //! - `GET /api/memories` - List memories
+//! - `GET /api/memories/page?cursor={c}&limit={n}` - Page through memories, newest first;
+//!   pass the returned `next_cursor` to get the following page
 //! - `POST /api/memories` - Create memory
 //! - `GET /api/memories/{id}` - Get memory
 //! - `PUT /api/memories/{id}` - Update memory
 //! - `DELETE /api/memories/{id}` - Delete memory
+//! - `POST /api/memories/{id}/pin` - Pin memory
+//! - `DELETE /api/memories/{id}/pin` - Unpin memory
 //!
 //! ### Search
 //! - `GET /api/search?q={query}` - Search memories
@@ -23,6 +27,20 @@
 //! - `GET /api/temporal/today` - Today's memories
 //! - `GET /api/temporal/episode` - Episodes
 //!
+//! ### Analytics
+//! - `GET /api/analytics?days={n}` - Tag trends, velocity, entities, most recalled
+//! - `GET /api/analytics/activity?granularity=hour|day` - Creation/access heatmap
+//!
+//! ### Curation
+//! - `GET /api/curation?threshold={t}&limit={n}` - Low-quality memories awaiting review
+//! - `POST /api/curation/{id}` - Resolve one: `{"action": "keep"}`, `{"action": "forget"}`
+//!   or `{"action": "merge", "target": "<id>"}`
+//!
+//! ### Review
+//! - `GET /api/reviews?status={s}&limit={n}` - Review queue (default `pending_review`)
+//! - `POST /api/reviews/{id}/approve` - Approve and save: `{"reviewer": "..."}`
+//! - `POST /api/reviews/{id}/reject` - Reject: `{"reviewer": "...", "note": "..."}`
+//!
 //! ### Administration
 //! - `GET /api/stats` - System statistics
 //! - `POST /api/maintenance` - Run maintenance
@@ -42,6 +60,9 @@
 //! ```
 
 use crate::{
+    analytics::TimeBucket,
+    quality::CurationAction,
+    review::ReviewStatus,
     error::{MemoryError, Result},
     types::{Memory, MemoryId, MemoryType, RelationType, Association, CreateMemoryInput},
     search::{SearchConfig, SearchMode},
@@ -138,8 +159,10 @@ fn create_router(state: AppState, enable_cors: bool) -> Router {
     let mut router = Router::new()
         // Memory endpoints
         .route("/api/memories", get(list_memories).post(create_memory))
+        .route("/api/memories/page", get(list_memories_page))
         .route("/api/memories/:id", get(get_memory).put(update_memory).delete(delete_memory))
         .route("/api/memories/:id/associations", get(get_associations).post(create_association))
+        .route("/api/memories/:id/pin", post(pin_memory).delete(unpin_memory))
         
         // Search endpoints
         .route("/api/search", get(search_memories))
@@ -153,8 +176,19 @@ fn create_router(state: AppState, enable_cors: bool) -> Router {
         // Stats and dashboard
         .route("/api/stats", get(get_stats))
         .route("/api/dashboard", get(get_dashboard))
+        .route("/api/analytics", get(get_analytics))
+        .route("/api/analytics/activity", get(get_activity))
         .route("/api/maintenance", post(run_maintenance))
         
+        // Curation queue
+        .route("/api/curation", get(get_curation_queue))
+        .route("/api/curation/:id", post(resolve_curation))
+        
+        // Review queue
+        .route("/api/reviews", get(get_review_queue))
+        .route("/api/reviews/:id/approve", post(approve_review))
+        .route("/api/reviews/:id/reject", post(reject_review))
+        
         // Health check
         .route("/health", get(health_check))
         
@@ -191,6 +225,52 @@ pub struct SearchQuery {
     pub memory_type: Option<MemoryType>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+    pub days: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityQuery {
+    /// `hour`, `day` or `week`
+    pub granularity: Option<TimeBucket>,
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurationQuery {
+    /// Quality below which memories are listed (default 0.4)
+    pub threshold: Option<f32>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageQuery {
+    pub cursor: Option<String>,
+    /// Page size (default 100, at most 1000)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemoryPageResponse {
+    pub memories: Vec<MemoryResponse>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewQuery {
+    /// Defaults to `pending_review`
+    pub status: Option<ReviewStatus>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    pub reviewer: Option<String>,
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AdvancedSearchRequest {
     pub query: String,
@@ -221,6 +301,7 @@ pub struct MemoryResponse {
     pub content: String,
     pub memory_type: String,
     pub priority: f32,
+    pub pinned: bool,
     pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: Option<String>,
@@ -311,6 +392,21 @@ async fn list_memories(State(state): State<AppState>) -> impl IntoResponse {
     }
 }
 
+async fn list_memories_page(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(100).min(1000);
+    match state.memory.list_page(query.cursor.as_deref(), limit).await {
+        Ok(page) => Json(MemoryPageResponse {
+            memories: page.memories.into_iter().map(memory_to_response).collect(),
+            next_cursor: page.next_cursor,
+        }).into_response(),
+        Err(MemoryError::InvalidOperation(msg)) => error_response(StatusCode::BAD_REQUEST, msg),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
 async fn create_memory(
     State(state): State<AppState>,
     Json(req): Json<CreateMemoryRequest>,
@@ -377,6 +473,28 @@ async fn delete_memory(
     }
 }
 
+async fn pin_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.memory.pin(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("Memory {} not found", id)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn unpin_memory(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.memory.unpin(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("Memory {} not found", id)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
 async fn search_memories(
     State(state): State<AppState>,
     Query(query): Query<SearchQuery>,
@@ -505,6 +623,34 @@ async fn get_recent(
     }
 }
 
+async fn get_analytics(
+    State(state): State<AppState>,
+    Query(query): Query<AnalyticsQuery>,
+) -> impl IntoResponse {
+    let days = query.days.unwrap_or(7);
+    let limit = query.limit.unwrap_or(10);
+    match state.memory.analytics().report(days, limit).await {
+        Ok(report) => Json(report).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_activity(
+    State(state): State<AppState>,
+    Query(query): Query<ActivityQuery>,
+) -> impl IntoResponse {
+    let granularity = query.granularity.unwrap_or_default();
+    let days = query.days.unwrap_or(match granularity {
+        TimeBucket::Hour => 7,
+        TimeBucket::Day | TimeBucket::Week => 365,
+    });
+    match state.memory.analytics().activity(days, granularity).await {
+        Ok(buckets) => Json(buckets).into_response(),
+        Err(MemoryError::InvalidOperation(msg)) => error_response(StatusCode::BAD_REQUEST, msg),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
 async fn get_stats(State(state): State<AppState>) -> impl IntoResponse {
     let stats = state.stats.read().await.clone();
     Json(stats).into_response()
@@ -522,13 +668,24 @@ async fn get_dashboard(State(state): State<AppState>) -> impl IntoResponse {
         Err(_) => vec![],
     };
     
+    let trending = match state.memory.analytics().tag_trends(7, 10).await {
+        Ok(trends) => trends.into_iter()
+            .map(|t| TrendingTag {
+                tag: t.tag,
+                count: t.count,
+                trend: t.direction.to_string(),
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+    
     let stats = state.stats.read().await.clone();
     
     Json(DashboardData {
         stats,
         recent_memories: recent,
         high_priority_memories: high_priority,
-        trending_tags: vec![], // Would implement trend analysis
+        trending_tags: trending,
     }).into_response()
 }
 
@@ -553,6 +710,71 @@ async fn run_maintenance(
     }
 }
 
+async fn get_curation_queue(
+    State(state): State<AppState>,
+    Query(query): Query<CurationQuery>,
+) -> impl IntoResponse {
+    let threshold = query.threshold.unwrap_or(0.4);
+    match state.memory.curation_queue(threshold, query.limit.unwrap_or(50)).await {
+        Ok(items) => Json(items).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn resolve_curation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(action): Json<CurationAction>,
+) -> impl IntoResponse {
+    match state.memory.resolve_curation(&id, action).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("Memory {} not found", id)),
+        Err(MemoryError::InvalidOperation(msg)) => error_response(StatusCode::BAD_REQUEST, msg),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_review_queue(
+    State(state): State<AppState>,
+    Query(query): Query<ReviewQuery>,
+) -> impl IntoResponse {
+    let status = query.status.unwrap_or(ReviewStatus::PendingReview);
+    match state.memory.reviews(Some(status), query.limit.unwrap_or(50)).await {
+        Ok(items) => Json(items).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn approve_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    decision: Option<Json<ReviewDecision>>,
+) -> impl IntoResponse {
+    let decision = decision.map(|Json(d)| d).unwrap_or_default();
+    match state.memory.approve(&id, decision.reviewer.as_deref()).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("No pending review for {}", id)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn reject_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    decision: Option<Json<ReviewDecision>>,
+) -> impl IntoResponse {
+    let decision = decision.map(|Json(d)| d).unwrap_or_default();
+    match state
+        .memory
+        .reject(&id, decision.reviewer.as_deref(), decision.note.as_deref())
+        .await
+    {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, format!("No pending review for {}", id)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
 async fn health_check() -> impl IntoResponse {
     Json(serde_json::json!({
         "status": "healthy",
@@ -569,6 +791,7 @@ fn memory_to_response(memory: Memory) -> MemoryResponse {
         content: memory.content,
         memory_type: format!("{:?}", memory.memory_type),
         priority: memory.priority,
+        pinned: memory.pinned,
         tags: memory.tags,
         created_at: memory.created_at.to_rfc3339(),
         updated_at: memory.updated_at.map(|d| d.to_rfc3339()),
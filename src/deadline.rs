@@ -0,0 +1,211 @@
+//! Timeouts and cooperative cancellation for retrieval
+//!
+//! A slow vector backend or embedder should not hang an agent turn. Retrieval
+//! paths take a [`Deadline`] and run each stage (full-text, embedding, vector
+//! search, graph expansion) under it. A stage that overruns is abandoned, the
+//! remaining stages are skipped, and whatever was gathered so far is ranked and
+//! returned as [`TimedResults`] with `timed_out` set.
+//!
+//! Clones of a deadline share its cancellation, so another task can stop a
+//! search early:
+//!
+//! ```rust,ignore
+//! let deadline = Deadline::after(Duration::from_millis(300));
+//! let stop = deadline.clone();
+//! tokio::spawn(async move { user_pressed_escape().await; stop.cancel() });
+//! let found = memory.search_within("deploy window", &config, &deadline).await?;
+//! if found.timed_out { /* partial results */ }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+#[derive(Debug, Default)]
+struct Cancellation {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// When a retrieval call must give up. The default never expires.
+#[derive(Debug, Clone, Default)]
+pub struct Deadline {
+    at: Option<Instant>,
+    cancellation: Arc<Cancellation>,
+}
+
+impl Deadline {
+    /// A deadline that only ends when cancelled
+    pub fn never() -> Self {
+        Self::default()
+    }
+
+    /// A deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Some(Instant::now() + timeout),
+            ..Self::default()
+        }
+    }
+
+    /// `after(timeout)` when a timeout is configured, `never()` otherwise
+    pub fn from_timeout(timeout: Option<Duration>) -> Self {
+        timeout.map_or_else(Self::never, Self::after)
+    }
+
+    /// Stop every call running under this deadline (or a clone of it)
+    pub fn cancel(&self) {
+        self.cancellation.cancelled.store(true, Ordering::SeqCst);
+        self.cancellation.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Whether the deadline has passed or was cancelled
+    pub fn is_expired(&self) -> bool {
+        self.is_cancelled() || self.at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// Time left, `None` if the deadline never expires
+    pub fn remaining(&self) -> Option<Duration> {
+        self.at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Run `fut` until it completes or the deadline expires. Returns `None` (and
+    /// drops `fut`) if the deadline expired first.
+    pub async fn run<F: Future>(&self, fut: F) -> Option<F::Output> {
+        let cancelled = self.cancellation.notify.notified();
+        tokio::pin!(cancelled);
+        cancelled.as_mut().enable();
+        if self.is_expired() {
+            return None;
+        }
+
+        let expired = async {
+            match self.at {
+                Some(at) => tokio::time::sleep_until(at).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            out = fut => Some(out),
+            _ = cancelled => None,
+            _ = expired => None,
+        }
+    }
+}
+
+/// Results of a retrieval call that may have been cut short
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedResults<T> {
+    pub results: Vec<T>,
+    /// The deadline expired or was cancelled; `results` are what was found before
+    pub timed_out: bool,
+}
+
+impl<T> TimedResults<T> {
+    pub fn complete(results: Vec<T>) -> Self {
+        Self {
+            results,
+            timed_out: false,
+        }
+    }
+
+    pub fn partial(results: Vec<T>) -> Self {
+        Self {
+            results,
+            timed_out: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Deadline;
+    use crate::vector_backend::{VectorBackend, VectorSearchHit};
+    use crate::{Memory, MemoryCortex, MemoryType, PoolSelection, Result};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// A vector backend that never answers a search
+    struct HangingBackend;
+
+    #[async_trait]
+    impl VectorBackend for HangingBackend {
+        fn name(&self) -> &'static str {
+            "hanging"
+        }
+
+        fn dimension(&self) -> usize {
+            384
+        }
+
+        async fn upsert(
+            &self,
+            _id: &str,
+            _vector: &[f32],
+            _payload: Option<serde_json::Value>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn search(&self, _vector: &[f32], _limit: usize) -> Result<Vec<VectorSearchHit>> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn deadline_expires_and_cancels() {
+        let deadline = Deadline::after(Duration::from_millis(20));
+        assert_eq!(deadline.run(async { 1 }).await, Some(1));
+        assert_eq!(deadline.run(std::future::pending::<()>()).await, None);
+        assert!(deadline.is_expired());
+
+        let deadline = Deadline::never();
+        let stop = deadline.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            stop.cancel();
+        });
+        assert_eq!(deadline.run(std::future::pending::<()>()).await, None);
+        assert!(deadline.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn recall_returns_text_matches_when_vectors_hang() {
+        let dir = tempfile::tempdir().unwrap();
+        let cortex = MemoryCortex::new_with_vector_backend(dir.path(), Arc::new(HangingBackend))
+            .await
+            .unwrap()
+            .with_recall_timeout(Duration::from_millis(50));
+        let memory = Memory::new("Deploys happen on Tuesday", MemoryType::Fact);
+        cortex.remember(&memory).await.unwrap();
+
+        let found = cortex
+            .recall_within(
+                "deploys",
+                5,
+                PoolSelection::All,
+                &Deadline::after(Duration::from_millis(50)),
+            )
+            .await
+            .unwrap();
+        assert!(found.timed_out);
+        assert_eq!(found.results[0].memory.id, memory.id);
+
+        let results = cortex.recall("deploys", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+}
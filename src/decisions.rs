@@ -0,0 +1,151 @@
+//! Structured decision records
+//!
+//! [`MemoryCortex::decide_with`] stores a decision as a `decision` memory whose
+//! metadata holds a [`DecisionRecord`]: the options considered, the one chosen,
+//! why, and what was expected to happen. Once the result is known,
+//! [`MemoryCortex::record_outcome`] attaches a [`DecisionOutcome`], and
+//! [`MemoryCortex::decisions_pending_outcome`] lists the decisions still waiting
+//! for one, so an agent can look back at how its choices turned out.
+//!
+//! ```rust,ignore
+//! let decision = cortex
+//!     .decide_with(
+//!         DecisionRecord::new("Use Postgres", "Picking the primary database")
+//!             .with_options(["Postgres", "MySQL", "SQLite"])
+//!             .with_rationale("Team already runs it")
+//!             .with_expected_outcome("No new ops burden"),
+//!     )
+//!     .await?;
+//! // ... later
+//! cortex.record_outcome(&decision.id, "Migration went smoothly", true).await?;
+//! ```
+//!
+//! [`MemoryCortex::decide_with`]: crate::MemoryCortex::decide_with
+//! [`MemoryCortex::record_outcome`]: crate::MemoryCortex::record_outcome
+//! [`MemoryCortex::decisions_pending_outcome`]: crate::MemoryCortex::decisions_pending_outcome
+
+use crate::types::Memory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Metadata key a decision memory keeps its [`DecisionRecord`] under
+pub const DECISION_METADATA_KEY: &str = "decision";
+
+/// A decision with the options that were weighed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    /// The option that was chosen
+    pub chosen: String,
+    /// The situation the decision was made in
+    pub context: String,
+    /// Every option considered, including the chosen one
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub rationale: Option<String>,
+    #[serde(default)]
+    pub expected_outcome: Option<String>,
+    /// Filled in by `record_outcome`
+    #[serde(default)]
+    pub outcome: Option<DecisionOutcome>,
+}
+
+/// What actually happened after a decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionOutcome {
+    pub actual: String,
+    pub success: bool,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl DecisionRecord {
+    pub fn new(chosen: impl Into<String>, context: impl Into<String>) -> Self {
+        Self {
+            chosen: chosen.into(),
+            context: context.into(),
+            options: Vec::new(),
+            rationale: None,
+            expected_outcome: None,
+            outcome: None,
+        }
+    }
+
+    pub fn with_options<S: Into<String>>(mut self, options: impl IntoIterator<Item = S>) -> Self {
+        self.options = options.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_rationale(mut self, rationale: impl Into<String>) -> Self {
+        self.rationale = Some(rationale.into());
+        self
+    }
+
+    pub fn with_expected_outcome(mut self, expected: impl Into<String>) -> Self {
+        self.expected_outcome = Some(expected.into());
+        self
+    }
+
+    /// The record stored in `memory`'s metadata, if it is a decision record
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        let record = memory.metadata.as_ref()?.get(DECISION_METADATA_KEY)?;
+        serde_json::from_value(record.clone()).ok()
+    }
+
+    /// Whether the decision is still waiting for its outcome
+    pub fn is_pending(&self) -> bool {
+        self.outcome.is_none()
+    }
+
+    /// Searchable text of the decision memory
+    pub fn content(&self) -> String {
+        let mut content = format!("Decision: {} - Context: {}", self.chosen, self.context);
+        if !self.options.is_empty() {
+            content.push_str(&format!(" - Options: {}", self.options.join(", ")));
+        }
+        if let Some(rationale) = &self.rationale {
+            content.push_str(&format!(" - Rationale: {rationale}"));
+        }
+        if let Some(expected) = &self.expected_outcome {
+            content.push_str(&format!(" - Expected: {expected}"));
+        }
+        if let Some(outcome) = &self.outcome {
+            let verdict = if outcome.success {
+                "succeeded"
+            } else {
+                "failed"
+            };
+            content.push_str(&format!(" - Outcome ({verdict}): {}", outcome.actual));
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    #[test]
+    fn record_round_trips_through_metadata() {
+        let mut record = DecisionRecord::new("Postgres", "Primary database")
+            .with_options(["Postgres", "MySQL"])
+            .with_rationale("Team knows it");
+        let memory = Memory::new(record.content(), MemoryType::Decision)
+            .with_metadata(serde_json::json!({ DECISION_METADATA_KEY: record }));
+
+        let loaded = DecisionRecord::from_memory(&memory).unwrap();
+        assert_eq!(loaded, record);
+        assert!(loaded.is_pending());
+
+        record.outcome = Some(DecisionOutcome {
+            actual: "Smooth migration".into(),
+            success: true,
+            recorded_at: Utc::now(),
+        });
+        assert!(!record.is_pending());
+        assert!(record
+            .content()
+            .ends_with("Outcome (succeeded): Smooth migration"));
+        assert!(DecisionRecord::from_memory(&Memory::new("x", MemoryType::Decision)).is_none());
+    }
+}
@@ -0,0 +1,643 @@
+//! Email ingestion over IMAP
+//!
+//! [`ImapConnector`] is a [`Connector`] that reads new mail from selected
+//! folders. Each email becomes one or more memories holding the subject and
+//! the plain-text body, split into chunks of at most `max_chunk_chars`. The
+//! sender's address is the memory source, and all emails of a thread share a
+//! session ID and are grouped into one episode titled after the subject.
+//!
+//! The checkpoint keeps, per folder, the date of the newest email seen (IMAP
+//! `SEARCH SINCE` works on whole days) and the UIDs already ingested on that
+//! date range, so restarts neither miss nor repeat mail.
+//!
+//! Needs the `imap` feature.
+//!
+//! ```rust,ignore
+//! let mail = ImapConnector::new("imap.example.com", "me@example.com", password)
+//!     .with_folders(["INBOX", "Projects/Atlas"]);
+//! let handle = ConnectorScheduler::new(memory.clone()).with_connector(mail).spawn();
+//! ```
+
+use crate::connectors::{Connector, ConnectorBatch, ConnectorEpisode};
+use crate::error::{MemoryError, Result};
+use crate::types::{CreateMemoryInput, MemoryType};
+use crate::validation::split_content;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// UIDs remembered per folder for deduplication
+const SEEN_PER_FOLDER: usize = 2000;
+
+/// An email, reduced to what becomes a memory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Email {
+    pub message_id: Option<String>,
+    pub subject: String,
+    /// Sender address, without the display name
+    pub from: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    /// Plain-text body; HTML-only mail is converted to text
+    pub body: String,
+}
+
+impl Email {
+    /// The first message of the thread: the root of `References`, then
+    /// `In-Reply-To`, then the email's own `Message-ID`
+    pub fn thread_key(&self) -> Option<String> {
+        self.references
+            .first()
+            .or(self.in_reply_to.as_ref())
+            .or(self.message_id.as_ref())
+            .cloned()
+    }
+
+    /// Subject without `Re:`/`Fwd:` prefixes
+    pub fn thread_title(&self) -> String {
+        let mut subject = self.subject.trim();
+        loop {
+            let lower = subject.to_ascii_lowercase();
+            let Some(prefix) = ["re:", "fwd:", "fw:", "aw:"]
+                .iter()
+                .find(|p| lower.starts_with(*p))
+            else {
+                break;
+            };
+            subject = subject[prefix.len()..].trim_start();
+        }
+        subject.to_string()
+    }
+}
+
+/// Parse an RFC 5322 message. Multipart mail yields its first `text/plain`
+/// part, or its first `text/html` part with markup removed.
+pub fn parse_email(raw: &str) -> Email {
+    let (headers, body) = split_message(raw);
+    let header = |name: &str| headers.get(name).map(String::as_str);
+
+    Email {
+        message_id: header("message-id").and_then(|v| message_ids(v).into_iter().next()),
+        subject: header("subject").map(decode_words).unwrap_or_default(),
+        from: header("from").map(address),
+        date: header("date").and_then(parse_date),
+        in_reply_to: header("in-reply-to").and_then(|v| message_ids(v).into_iter().next()),
+        references: header("references").map(message_ids).unwrap_or_default(),
+        body: text_body(&headers, body).unwrap_or_default(),
+    }
+}
+
+/// Unfolded headers (lowercase names, first occurrence wins) and the body
+fn split_message(raw: &str) -> (HashMap<String, String>, &str) {
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .or_else(|| raw.split_once("\n\n"))
+        .unwrap_or((raw, ""));
+
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = current.as_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = current.take() {
+            headers.entry(name).or_insert(value);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            current = Some((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    if let Some((name, value)) = current {
+        headers.entry(name).or_insert(value);
+    }
+    (headers, body)
+}
+
+fn text_body(headers: &HashMap<String, String>, body: &str) -> Option<String> {
+    let content_type = headers
+        .get("content-type")
+        .map(String::as_str)
+        .unwrap_or("text/plain");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if mime.starts_with("multipart/") {
+        let boundary = parameter(content_type, "boundary")?;
+        let delimiter = format!("--{boundary}");
+        let parts: Vec<(HashMap<String, String>, &str)> = body
+            .split(delimiter.as_str())
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .map(|part| split_message(part.trim_start_matches(['\r', '\n'])))
+            .collect();
+
+        // Prefer plain text anywhere in the tree, then HTML
+        return parts
+            .iter()
+            .filter_map(|(h, b)| {
+                let is_text = h
+                    .get("content-type")
+                    .is_none_or(|t| t.to_ascii_lowercase().starts_with("text/plain"));
+                let nested = h
+                    .get("content-type")
+                    .is_some_and(|t| t.to_ascii_lowercase().starts_with("multipart/"));
+                (is_text || nested).then(|| text_body(h, b)).flatten()
+            })
+            .next()
+            .or_else(|| parts.iter().find_map(|(h, b)| text_body(h, b)));
+    }
+
+    if !mime.starts_with("text/") {
+        return None;
+    }
+    let encoding = headers
+        .get("content-transfer-encoding")
+        .map(|e| e.trim().to_ascii_lowercase());
+    let decoded = match encoding.as_deref() {
+        Some("base64") => String::from_utf8_lossy(&decode_base64(body)).into_owned(),
+        Some("quoted-printable") => {
+            String::from_utf8_lossy(&decode_quoted_printable(body)).into_owned()
+        }
+        _ => body.to_string(),
+    };
+    let text = if mime == "text/html" {
+        strip_html(&decoded)
+    } else {
+        decoded.trim().replace("\r\n", "\n")
+    };
+    Some(text)
+}
+
+/// A `name=value` parameter of a header like `Content-Type`
+fn parameter(header: &str, name: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Every `<id>` in a header value, without the brackets
+fn message_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('>').map(|(id, _)| id.trim().to_string()))
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// The address in `Name <user@host>`, or the whole value
+fn address(value: &str) -> String {
+    match value.rsplit_once('<') {
+        Some((_, rest)) => rest.trim_end_matches('>').trim().to_ascii_lowercase(),
+        None => value.trim().to_ascii_lowercase(),
+    }
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    // Drop trailing comments such as "(UTC)"
+    let value = value.split('(').next().unwrap_or(value).trim();
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Decode RFC 2047 encoded words (`=?UTF-8?B?...?=`, `=?UTF-8?Q?...?=`)
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        let decoded = (|| {
+            let (_charset, rest) = word[2..].split_once('?')?;
+            let (encoding, rest) = rest.split_once('?')?;
+            let (text, after) = rest.split_once("?=")?;
+            let bytes = match encoding.to_ascii_uppercase().as_str() {
+                "B" => decode_base64(text),
+                "Q" => decode_quoted_printable(&text.replace('_', " ")),
+                _ => return None,
+            };
+            Some((String::from_utf8_lossy(&bytes).into_owned(), after))
+        })();
+        match decoded {
+            Some((text, after)) => {
+                // Whitespace between adjacent encoded words is not part of the text
+                if !(out.ends_with(|c: char| !c.is_whitespace()) && before.trim().is_empty()) {
+                    out.push_str(before);
+                }
+                out.push_str(&text);
+                rest = after;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &word[2..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+fn decode_base64(text: &str) -> Vec<u8> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for v in text.bytes().filter_map(value) {
+        buffer = (buffer << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    out
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        // Soft line break
+        if bytes[i + 1..].starts_with(b"\r\n") {
+            i += 3;
+        } else if bytes[i + 1..].starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = text
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Per-folder progress stored as the connector checkpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImapCheckpoint {
+    pub folders: HashMap<String, FolderCursor>,
+}
+
+/// Progress in one folder
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderCursor {
+    /// Searched with `SINCE` on the next poll
+    pub since: Option<NaiveDate>,
+    /// The folder's `UIDVALIDITY`; when it changes, UIDs are reassigned
+    pub uid_validity: Option<u32>,
+    /// UIDs already ingested
+    pub seen: VecDeque<u32>,
+}
+
+/// Polls IMAP folders for new mail
+#[derive(Clone)]
+pub struct ImapConnector {
+    name: String,
+    host: String,
+    port: u16,
+    username: String,
+    password: String,
+    folders: Vec<String>,
+    since: NaiveDate,
+    memory_type: MemoryType,
+    max_chunk_chars: usize,
+    interval: Duration,
+}
+
+impl std::fmt::Debug for ImapConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImapConnector")
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("folders", &self.folders)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ImapConnector {
+    /// Connect to `host` over TLS on port 993
+    pub fn new(
+        host: impl Into<String>,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: "imap".to_string(),
+            host: host.into(),
+            port: 993,
+            username: username.into(),
+            password: password.into(),
+            folders: vec!["INBOX".to_string()],
+            since: (Utc::now() - chrono::Duration::days(30)).date_naive(),
+            memory_type: MemoryType::Event,
+            max_chunk_chars: 2000,
+            interval: Duration::from_secs(5 * 60),
+        }
+    }
+
+    /// Checkpoint key; set it when reading several accounts
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Folders to read, `INBOX` by default
+    pub fn with_folders(mut self, folders: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.folders = folders.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Oldest mail read on the first poll, 30 days ago by default
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = since;
+        self
+    }
+
+    /// Type of the memories, `Event` by default
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_type = memory_type;
+        self
+    }
+
+    /// Longest memory; longer emails are split into several
+    pub fn with_max_chunk_chars(mut self, max_chunk_chars: usize) -> Self {
+        self.max_chunk_chars = max_chunk_chars;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Turn emails read from `folder` into a batch: one item per chunk, and
+    /// one episode per thread
+    pub fn batch(&self, folder: &str, emails: &[Email], batch: &mut ConnectorBatch) {
+        for email in emails {
+            let text = match (email.subject.is_empty(), email.body.is_empty()) {
+                (_, true) => email.subject.clone(),
+                (true, false) => email.body.clone(),
+                (false, false) => format!("{}\n\n{}", email.subject, email.body),
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let thread = email.thread_key();
+            let chunks = split_content(&text, self.max_chunk_chars);
+            let total = chunks.len();
+            let first = batch.items.len();
+            for (i, chunk) in chunks.into_iter().enumerate() {
+                let mut input = CreateMemoryInput::new(chunk, self.memory_type).with_metadata(
+                    serde_json::json!({
+                        "connector": self.name,
+                        "folder": folder,
+                        "message_id": email.message_id,
+                        "subject": email.subject,
+                        "from": email.from,
+                        "date": email.date,
+                        "thread": thread,
+                        "chunk": i + 1,
+                        "chunks": total,
+                    }),
+                );
+                if let Some(from) = &email.from {
+                    input = input.with_source(from.clone());
+                }
+                if let Some(thread) = &thread {
+                    input = input.with_session_id(format!("email:{thread}"));
+                }
+                batch.items.push(input);
+            }
+
+            if let Some(key) = thread {
+                let items = first..batch.items.len();
+                match batch.episodes.iter_mut().find(|e| e.key == key) {
+                    Some(episode) => episode.items.extend(items),
+                    None => batch.episodes.push(ConnectorEpisode {
+                        key,
+                        title: email.thread_title(),
+                        items: items.collect(),
+                    }),
+                }
+            }
+        }
+    }
+
+    async fn read_folder(
+        &self,
+        session: &mut ImapSession,
+        folder: &str,
+        cursor: &mut FolderCursor,
+    ) -> Result<Vec<Email>> {
+        let mailbox = session.select(folder).await.map_err(imap_error)?;
+        if mailbox.uid_validity != cursor.uid_validity {
+            cursor.uid_validity = mailbox.uid_validity;
+            cursor.seen.clear();
+        }
+
+        let since = cursor.since.unwrap_or(self.since);
+        let uids = session
+            .uid_search(format!("SINCE {}", since.format("%d-%b-%Y")))
+            .await
+            .map_err(imap_error)?;
+        let mut new_uids: Vec<u32> = uids
+            .into_iter()
+            .filter(|uid| !cursor.seen.contains(uid))
+            .collect();
+        new_uids.sort_unstable();
+        if new_uids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let set = new_uids
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let fetched: Vec<_> = session
+            .uid_fetch(set, "(UID RFC822)")
+            .await
+            .map_err(imap_error)?
+            .try_collect()
+            .await
+            .map_err(imap_error)?;
+
+        let mut emails = Vec::with_capacity(fetched.len());
+        for message in &fetched {
+            let (Some(uid), Some(raw)) = (message.uid, message.body()) else {
+                continue;
+            };
+            let email = parse_email(&String::from_utf8_lossy(raw));
+            if let Some(date) = email.date.map(|d| d.date_naive()) {
+                if cursor.since.is_none_or(|since| date > since) {
+                    cursor.since = Some(date);
+                }
+            }
+            cursor.seen.push_back(uid);
+            emails.push(email);
+        }
+        while cursor.seen.len() > SEEN_PER_FOLDER {
+            cursor.seen.pop_front();
+        }
+        Ok(emails)
+    }
+}
+
+type ImapSession = async_imap::Session<async_native_tls::TlsStream<tokio::net::TcpStream>>;
+
+fn imap_error(e: impl std::fmt::Display) -> MemoryError {
+    MemoryError::Connector(format!("IMAP: {e}"))
+}
+
+#[async_trait]
+impl Connector for ImapConnector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(&self, checkpoint: Option<&str>) -> Result<ConnectorBatch> {
+        let mut state: ImapCheckpoint = checkpoint
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_default();
+
+        let tcp = tokio::net::TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let tls = async_native_tls::TlsConnector::new()
+            .connect(&self.host, tcp)
+            .await
+            .map_err(imap_error)?;
+        let mut session = async_imap::Client::new(tls)
+            .login(&self.username, &self.password)
+            .await
+            .map_err(|(e, _)| imap_error(e))?;
+
+        let mut batch = ConnectorBatch::default();
+        for folder in &self.folders {
+            let cursor = state.folders.entry(folder.clone()).or_default();
+            let emails = self.read_folder(&mut session, folder, cursor).await?;
+            self.batch(folder, &emails, &mut batch);
+        }
+        // The mail was read; a failed logout does not lose anything
+        let _ = session.logout().await;
+
+        batch.checkpoint = serde_json::to_string(&state).ok();
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_email, ImapConnector};
+    use crate::connectors::ConnectorBatch;
+
+    const REPLY: &str = "From: Ana Li <Ana@Example.com>\r\n\
+Subject: Re: =?UTF-8?Q?Launch_plan?=\r\n\
+Message-ID: <b2@example.com>\r\n\
+In-Reply-To: <a1@example.com>\r\n\
+References: <a1@example.com>\r\n\t<a2@example.com>\r\n\
+Date: Tue, 6 Jan 2026 10:00:00 +0000 (UTC)\r\n\
+Content-Type: multipart/alternative; boundary=\"XYZ\"\r\n\
+\r\n\
+--XYZ\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>ignored</p>\r\n\
+--XYZ\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Launch moves to Friday =E2=80=94 QA needs a day.\r\n\
+--XYZ--\r\n";
+
+    #[test]
+    fn parses_email_and_groups_thread() {
+        let email = parse_email(REPLY);
+        assert_eq!(email.subject, "Re: Launch plan");
+        assert_eq!(email.from.as_deref(), Some("ana@example.com"));
+        assert_eq!(email.references, vec!["a1@example.com", "a2@example.com"]);
+        assert_eq!(email.thread_key().as_deref(), Some("a1@example.com"));
+        assert_eq!(email.thread_title(), "Launch plan");
+        assert_eq!(
+            email.body,
+            "Launch moves to Friday \u{2014} QA needs a day."
+        );
+        assert!(email.date.is_some());
+
+        let connector = ImapConnector::new("imap.example.com", "me", "secret");
+        let mut batch = ConnectorBatch::default();
+        connector.batch("INBOX", &[email.clone(), email], &mut batch);
+        assert_eq!(batch.items.len(), 2);
+        assert_eq!(batch.items[0].source.as_deref(), Some("ana@example.com"));
+        assert_eq!(batch.episodes.len(), 1);
+        assert_eq!(batch.episodes[0].items, vec![0, 1]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::vector_search::is_stopword;
 use async_trait::async_trait;
 
 #[async_trait]
@@ -9,6 +10,71 @@ pub trait EmbeddingProvider: Send + Sync {
     async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
 }
 
+/// How embedding vectors are normalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingNormalization {
+    /// Unit length (cosine similarity == dot product)
+    #[default]
+    L2,
+    /// Raw feature counts
+    None,
+}
+
+/// Feature options for [`HashEmbeddingProvider`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashEmbeddingConfig {
+    /// Inclusive range of character n-gram sizes hashed alongside whole tokens.
+    /// N-grams make short queries and misspellings/inflections overlap. `None` disables.
+    pub char_ngrams: Option<(usize, usize)>,
+    /// Weight of each n-gram feature relative to a whole-token feature
+    pub ngram_weight: f32,
+    /// Drop stopwords (unless the text consists only of stopwords)
+    pub remove_stopwords: bool,
+    /// Lowercase tokens before hashing
+    pub lowercase: bool,
+    /// Output normalization
+    pub normalization: EmbeddingNormalization,
+}
+
+impl Default for HashEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            char_ngrams: Some((3, 4)),
+            ngram_weight: 0.5,
+            remove_stopwords: true,
+            lowercase: true,
+            normalization: EmbeddingNormalization::L2,
+        }
+    }
+}
+
+impl HashEmbeddingConfig {
+    /// Whole-token hashing only, matching the original provider's output
+    pub fn legacy() -> Self {
+        Self {
+            char_ngrams: None,
+            ngram_weight: 0.0,
+            remove_stopwords: false,
+            lowercase: false,
+            normalization: EmbeddingNormalization::L2,
+        }
+    }
+}
+
+pub(crate) const FNV_OFFSET: u64 = 1469598103934665603;
+const FNV_PRIME: u64 = 1099511628211;
+/// Offset used for n-gram features so they don't share buckets with equal whole tokens
+const NGRAM_OFFSET: u64 = FNV_OFFSET ^ 0x9e37_79b9_7f4a_7c15;
+
+pub(crate) fn fnv1a(bytes: &[u8], offset: u64) -> u64 {
+    let mut hash = offset;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
 /// Zero-config embedding provider.
 ///
 /// This is intentionally lightweight and deterministic (no network, no model downloads).
@@ -16,40 +82,81 @@ pub trait EmbeddingProvider: Send + Sync {
 #[derive(Debug, Clone)]
 pub struct HashEmbeddingProvider {
     dimension: usize,
+    config: HashEmbeddingConfig,
 }
 
 impl HashEmbeddingProvider {
+    /// Provider with the default features (n-grams, stopword removal). Its
+    /// vectors differ from the original whole-token hashing, so it reports a
+    /// different [`name`](EmbeddingProvider::name); use
+    /// [`HashEmbeddingConfig::legacy`] to keep reading older vector indexes.
     pub fn new(dimension: usize) -> Self {
-        Self { dimension }
+        Self::with_config(dimension, HashEmbeddingConfig::default())
+    }
+
+    pub fn with_config(dimension: usize, config: HashEmbeddingConfig) -> Self {
+        Self { dimension, config }
+    }
+
+    pub fn config(&self) -> &HashEmbeddingConfig {
+        &self.config
     }
 
     fn embed_one(&self, text: &str) -> Vec<f32> {
         let mut vec = vec![0.0f32; self.dimension];
-        let mut token_count = 0u32;
+        let cfg = &self.config;
 
-        for token in text
+        let raw: Vec<String> = text
             .split(|c: char| !c.is_alphanumeric())
             .filter(|t| !t.is_empty())
-        {
-            token_count += 1;
-            let mut hash = 1469598103934665603u64;
-            for b in token.as_bytes() {
-                hash ^= *b as u64;
-                hash = hash.wrapping_mul(1099511628211u64);
-            }
+            .map(|t| {
+                if cfg.lowercase {
+                    t.to_lowercase()
+                } else {
+                    t.to_string()
+                }
+            })
+            .collect();
 
-            let idx = (hash as usize) % self.dimension;
-            vec[idx] += 1.0;
+        let mut tokens: Vec<&str> = raw.iter().map(String::as_str).collect();
+        if cfg.remove_stopwords {
+            let kept: Vec<&str> = tokens
+                .iter()
+                .copied()
+                .filter(|t| !is_stopword(&t.to_lowercase()))
+                .collect();
+            // A query made only of stopwords still needs a non-zero vector.
+            if !kept.is_empty() {
+                tokens = kept;
+            }
         }
 
-        if token_count == 0 {
+        if tokens.is_empty() {
             return vec;
         }
 
-        let norm = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for v in &mut vec {
-                *v /= norm;
+        for token in &tokens {
+            let idx = (fnv1a(token.as_bytes(), FNV_OFFSET) as usize) % self.dimension;
+            vec[idx] += 1.0;
+
+            if let Some((min_n, max_n)) = cfg.char_ngrams {
+                let chars: Vec<char> = format!("^{token}$").chars().collect();
+                for n in min_n.max(1)..=max_n {
+                    for window in chars.windows(n) {
+                        let gram: String = window.iter().collect();
+                        let idx = (fnv1a(gram.as_bytes(), NGRAM_OFFSET) as usize) % self.dimension;
+                        vec[idx] += cfg.ngram_weight;
+                    }
+                }
+            }
+        }
+
+        if cfg.normalization == EmbeddingNormalization::L2 {
+            let norm = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for v in &mut vec {
+                    *v /= norm;
+                }
             }
         }
 
@@ -60,7 +167,11 @@ impl HashEmbeddingProvider {
 #[async_trait]
 impl EmbeddingProvider for HashEmbeddingProvider {
     fn name(&self) -> &'static str {
-        "hash"
+        if self.config == HashEmbeddingConfig::legacy() {
+            "hash"
+        } else {
+            "hash-ngram"
+        }
     }
 
     fn dimension(&self) -> usize {
@@ -71,3 +182,38 @@ impl EmbeddingProvider for HashEmbeddingProvider {
         Ok(texts.iter().map(|t| self.embed_one(t)).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_search::cosine_similarity;
+
+    #[test]
+    fn ngrams_match_inflections_and_typos() {
+        let legacy = HashEmbeddingProvider::with_config(384, HashEmbeddingConfig::legacy());
+        let improved = HashEmbeddingProvider::new(384);
+
+        let pair = |p: &HashEmbeddingProvider| {
+            cosine_similarity(
+                &p.embed_one("Kubernetes"),
+                &p.embed_one("kubernets deployment"),
+            )
+        };
+        assert!(pair(&improved) > pair(&legacy));
+        assert!(pair(&improved) > 0.3);
+    }
+
+    #[test]
+    fn stopword_only_text_is_not_zero() {
+        let p = HashEmbeddingProvider::new(64);
+        let v = p.embed_one("to be or");
+        assert!(v.iter().any(|x| *x != 0.0));
+    }
+
+    #[test]
+    fn changed_features_change_the_name() {
+        let legacy = HashEmbeddingProvider::with_config(64, HashEmbeddingConfig::legacy());
+        assert_eq!(legacy.name(), "hash");
+        assert_ne!(HashEmbeddingProvider::new(64).name(), legacy.name());
+    }
+}
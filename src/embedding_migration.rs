@@ -0,0 +1,225 @@
+//! Switching embedding models
+//!
+//! Vectors from different embedders cannot share an index: the manifest
+//! refuses to open a data directory with an embedder of another dimension,
+//! and one of the same dimension silently mixes incomparable vectors.
+//! [`MemorySystem::migrate_embeddings`] re-embeds every memory on the default
+//! vector route with the new embedder, in batches, into a fresh index. Only
+//! once all vectors are written does the system switch to it and record the
+//! new model in the manifest; a failure leaves the old index in use.
+//!
+//! Without a [`target`](EmbeddingMigrationConfig::with_target), the new index
+//! is a file backend built in `<data_dir>/vectors.migrating` and renamed over
+//! `<data_dir>/vectors` when complete.
+//!
+//! ```rust,ignore
+//! let report = memory
+//!     .migrate_embeddings(
+//!         Arc::new(OllamaEmbeddingProvider::new(OllamaConfig::new("mxbai-embed-large"), 1024)?),
+//!         EmbeddingMigrationConfig::default()
+//!             .with_progress(|p| eprintln!("{}/{} memories", p.done, p.total)),
+//!     )
+//!     .await?;
+//! ```
+//!
+//! [`MemorySystem::migrate_embeddings`]: crate::MemorySystem::migrate_embeddings
+
+use crate::manifest::EmbeddingInfo;
+use crate::platform_fs;
+use crate::vector_backend::VectorBackend;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Directory the file backend is staged in, next to `vectors`
+pub const STAGING_DIR: &str = "vectors.migrating";
+
+/// Directory the old index is moved to while the staged one is renamed in
+const PREVIOUS_DIR: &str = "vectors.previous";
+
+/// Rename the `staging` directory over `live`. Should that fail, the old
+/// index is moved back, so `live` is always one whole index or the other.
+pub(crate) async fn swap_in(staging: &Path, live: &Path) -> io::Result<()> {
+    let previous = live.with_file_name(PREVIOUS_DIR);
+    platform_fs::remove_dir_all_async(&previous).await?;
+    let had_live = live.exists();
+    if had_live {
+        platform_fs::rename_async(live, &previous).await?;
+    }
+    if let Err(e) = platform_fs::rename_async(staging, live).await {
+        if had_live {
+            platform_fs::rename_async(&previous, live).await?;
+        }
+        return Err(e);
+    }
+    // The new index is live; a leftover copy of the old one only costs disk
+    // space until the next swap clears it
+    if let Err(e) = platform_fs::remove_dir_all_async(&previous).await {
+        tracing::warn!("Failed to remove {}: {}", previous.display(), e);
+    }
+    Ok(())
+}
+
+type ProgressFn = Arc<dyn Fn(EmbeddingMigrationProgress) + Send + Sync>;
+
+/// Batching, target and progress reporting of an embedding migration
+#[derive(Clone)]
+pub struct EmbeddingMigrationConfig {
+    /// Memories embedded per call to the new embedder
+    pub batch_size: usize,
+    /// Backend to fill instead of a staged file backend; must match the new
+    /// embedder's dimension
+    pub target: Option<Arc<dyn VectorBackend>>,
+    progress: Option<ProgressFn>,
+}
+
+impl Default for EmbeddingMigrationConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 64,
+            target: None,
+            progress: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for EmbeddingMigrationConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddingMigrationConfig")
+            .field("batch_size", &self.batch_size)
+            .field("target", &self.target.as_ref().map(|t| t.name()))
+            .finish()
+    }
+}
+
+impl EmbeddingMigrationConfig {
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_target(mut self, target: Arc<dyn VectorBackend>) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Called after every batch
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(EmbeddingMigrationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub(crate) fn report_progress(&self, progress: EmbeddingMigrationProgress) {
+        if let Some(report) = &self.progress {
+            report(progress);
+        }
+    }
+}
+
+/// How far a migration has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingMigrationProgress {
+    /// Memories looked at so far
+    pub done: usize,
+    /// Active memories when the migration started
+    pub total: usize,
+}
+
+/// What [`MemorySystem::migrate_embeddings`](crate::MemorySystem::migrate_embeddings) did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMigrationReport {
+    /// Model recorded before, if any
+    pub from: Option<EmbeddingInfo>,
+    pub to: EmbeddingInfo,
+    /// Active memories re-embedded
+    pub memories: usize,
+    /// Vectors written, chunk vectors included
+    pub vectors: usize,
+    /// Memories routed to a type-specific backend, left alone
+    pub skipped: usize,
+    /// Vector backend now in use
+    pub backend: String,
+    pub duration_ms: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::HashEmbeddingProvider;
+    use crate::types::{Memory, MemoryType};
+    use crate::vector_backend::FileVectorBackend;
+    use crate::{HybridSearchConfig, MemorySystem};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn migration_reembeds_into_a_new_dimension_and_swaps_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let vectors = FileVectorBackend::new(dir.path().join("vectors"), 64);
+        vectors.ensure_ready().await.unwrap();
+        let mut memory = MemorySystem::new(dir.path())
+            .await
+            .unwrap()
+            .with_vector_backend(Arc::new(vectors), Arc::new(HashEmbeddingProvider::new(64)));
+        for i in 0..5 {
+            let note = Memory::new(
+                format!("Release train {i} leaves on Friday"),
+                MemoryType::Fact,
+            );
+            memory.save(&note).await.unwrap();
+        }
+
+        let batches = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&batches);
+        let report = memory
+            .migrate_embeddings(
+                Arc::new(HashEmbeddingProvider::new(128)),
+                EmbeddingMigrationConfig::default()
+                    .with_batch_size(2)
+                    .with_progress(move |p| {
+                        seen.fetch_add(1, Ordering::SeqCst);
+                        assert!(p.done <= p.total);
+                    }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(report.from.map(|f| f.dimension), Some(64));
+        assert_eq!(report.to.dimension, 128);
+        assert_eq!((report.memories, report.vectors), (5, 5));
+        assert_eq!(batches.load(Ordering::SeqCst), 3);
+        assert_eq!(memory.manifest().embedding.unwrap().dimension, 128);
+        assert!(!dir.path().join(STAGING_DIR).exists());
+
+        let results = memory
+            .hybrid_search("release train", &HybridSearchConfig::default(), None)
+            .await
+            .unwrap();
+        assert!(results.iter().any(|r| r.explanation.vector.is_some()));
+        let reopened = FileVectorBackend::new(dir.path().join("vectors"), 128);
+        reopened.ensure_ready().await.unwrap();
+        assert_eq!(reopened.stats().await.unwrap().rows, 5);
+    }
+
+    #[tokio::test]
+    async fn failed_swap_restores_the_live_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let live = dir.path().join("vectors");
+        std::fs::create_dir(&live).unwrap();
+        std::fs::write(live.join("old.bin"), b"old").unwrap();
+
+        let missing = dir.path().join(STAGING_DIR);
+        assert!(swap_in(&missing, &live).await.is_err());
+        assert_eq!(std::fs::read(live.join("old.bin")).unwrap(), b"old");
+        assert!(!dir.path().join(PREVIOUS_DIR).exists());
+
+        std::fs::create_dir(&missing).unwrap();
+        std::fs::write(missing.join("new.bin"), b"new").unwrap();
+        swap_in(&missing, &live).await.unwrap();
+        assert!(live.join("new.bin").exists() && !live.join("old.bin").exists());
+        assert!(!missing.exists() && !dir.path().join(PREVIOUS_DIR).exists());
+    }
+}
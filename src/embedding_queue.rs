@@ -0,0 +1,421 @@
+//! Background embedding queue
+//!
+//! With [`MemorySystem::with_embedding_queue`](crate::MemorySystem::with_embedding_queue),
+//! `save` and `update` return once a memory is in the store and the text index.
+//! Its vector is embedded and upserted by a background task, in batches, so a
+//! slow remote embedder no longer sits on the write path. Until the vector
+//! lands, hybrid search finds the memory through full text only.
+//!
+//! The queue is bounded: when it is full, writes wait for room. Failed embeds
+//! are retried with exponential backoff; memories that still fail are reported
+//! by the next [`flush`](EmbeddingQueue::flush).
+//!
+//! ```rust,ignore
+//! let memory = MemorySystem::new("./data")
+//!     .await?
+//!     .with_vector_backend(vectors, embedder)
+//!     .with_embedding_queue(EmbeddingQueueConfig::default());
+//! memory.save(&note).await?; // does not wait for the embedder
+//! memory.flush_embeddings().await?; // wait until every vector is written
+//! ```
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::{MemoryError, Result};
+use crate::store::MemoryStore;
+use crate::types::MemoryId;
+use crate::vector_backend::VectorBackend;
+use crate::vector_chunks::{replace_chunks, VectorChunk};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+/// Capacity, batching and retry settings for [`EmbeddingQueue`]
+#[derive(Debug, Clone)]
+pub struct EmbeddingQueueConfig {
+    /// Memories waiting at most; writes wait when the queue is full
+    pub capacity: usize,
+    /// Memories embedded per call to the embedder
+    pub batch_size: usize,
+    /// Retries after the first attempt before a batch is given up
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each further one
+    pub initial_backoff: Duration,
+    /// Upper bound on any single wait
+    pub max_backoff: Duration,
+}
+
+impl Default for EmbeddingQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            batch_size: 32,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl EmbeddingQueueConfig {
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Wait before retry number `retry` (0-based)
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// A memory to embed and the route its vector goes to
+pub(crate) struct EmbeddingJob {
+    pub id: MemoryId,
+    pub content: String,
+    /// See [`vector_payload`](crate::vector_backend::vector_payload)
+    pub payload: serde_json::Value,
+    pub vector: Arc<dyn VectorBackend>,
+    pub embedder: Arc<dyn EmbeddingProvider>,
+    /// Set on a memory's last vector: the chunks to record once it is written
+    pub chunks: Option<ChunkRecord>,
+}
+
+/// Chunks of a memory, recorded with [`replace_chunks`] after its vectors
+pub(crate) struct ChunkRecord {
+    pub store: Arc<MemoryStore>,
+    pub memory_id: MemoryId,
+    pub chunks: Vec<VectorChunk>,
+}
+
+struct Queued {
+    seq: u64,
+    job: EmbeddingJob,
+}
+
+impl Queued {
+    /// Jobs sharing an embedder and backend are embedded together
+    fn route(&self) -> (*const (), *const ()) {
+        (
+            Arc::as_ptr(&self.job.embedder) as *const (),
+            Arc::as_ptr(&self.job.vector) as *const (),
+        )
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    pending: AtomicUsize,
+    next_seq: AtomicU64,
+    /// Sequence number of the newest job per memory; older jobs are stale
+    latest: Mutex<HashMap<MemoryId, u64>>,
+    failed: Mutex<Vec<MemoryId>>,
+    idle: Notify,
+}
+
+impl Shared {
+    fn latest(&self) -> std::sync::MutexGuard<'_, HashMap<MemoryId, u64>> {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_latest(&self, queued: &Queued) -> bool {
+        self.latest().get(&queued.job.id) == Some(&queued.seq)
+    }
+
+    fn complete(&self, queued: &Queued) {
+        let mut latest = self.latest();
+        if latest.get(&queued.job.id) == Some(&queued.seq) {
+            latest.remove(&queued.job.id);
+        }
+    }
+
+    fn fail(&self, ids: impl IntoIterator<Item = MemoryId>) {
+        self.failed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(ids);
+    }
+
+    fn finish(&self, jobs: usize) {
+        if self.pending.fetch_sub(jobs, Ordering::AcqRel) == jobs {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+/// Bounded queue of vector upserts worked off by a background task.
+///
+/// Clones share the queue; the task stops once every clone is dropped.
+#[derive(Clone)]
+pub struct EmbeddingQueue {
+    sender: mpsc::Sender<Queued>,
+    shared: Arc<Shared>,
+}
+
+impl std::fmt::Debug for EmbeddingQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddingQueue")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl EmbeddingQueue {
+    /// Start the worker task; must be called within a Tokio runtime
+    pub fn spawn(config: EmbeddingQueueConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.capacity.max(1));
+        let shared = Arc::new(Shared::default());
+        tokio::spawn(run(receiver, Arc::clone(&shared), config));
+        Self { sender, shared }
+    }
+
+    /// Queue a memory, waiting while the queue is full. A newer job for the
+    /// same memory supersedes older ones still waiting.
+    pub(crate) async fn enqueue(&self, job: EmbeddingJob) -> Result<()> {
+        let seq = self.shared.next_seq.fetch_add(1, Ordering::Relaxed);
+        self.shared.latest().insert(job.id.clone(), seq);
+        self.shared.pending.fetch_add(1, Ordering::AcqRel);
+
+        if self.sender.send(Queued { seq, job }).await.is_err() {
+            self.shared.finish(1);
+            return Err(MemoryError::VectorDb(
+                "Embedding queue worker has stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drop any waiting job for a memory, e.g. because it was deleted
+    pub(crate) fn cancel(&self, id: &str) {
+        self.shared.latest().remove(id);
+    }
+
+    /// Memories queued or being embedded
+    pub fn pending(&self) -> usize {
+        self.shared.pending.load(Ordering::Acquire)
+    }
+
+    /// Wait until the queue is empty. Fails with the IDs of memories whose
+    /// vectors could not be written since the last flush.
+    pub async fn flush(&self) -> Result<()> {
+        loop {
+            let idle = self.shared.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            if self.pending() == 0 {
+                break;
+            }
+            idle.await;
+        }
+
+        let failed =
+            std::mem::take(&mut *self.shared.failed.lock().unwrap_or_else(|e| e.into_inner()));
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(MemoryError::EmbeddingFailed(format!(
+                "{} memories could not be embedded: {}",
+                failed.len(),
+                failed.join(", ")
+            )))
+        }
+    }
+}
+
+async fn run(
+    mut receiver: mpsc::Receiver<Queued>,
+    shared: Arc<Shared>,
+    config: EmbeddingQueueConfig,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut batch = vec![first];
+        while batch.len() < config.batch_size {
+            match receiver.try_recv() {
+                Ok(queued) => batch.push(queued),
+                Err(_) => break,
+            }
+        }
+        let received = batch.len();
+
+        let mut groups: Vec<Vec<Queued>> = Vec::new();
+        for queued in batch.into_iter().filter(|q| shared.is_latest(q)) {
+            match groups.iter_mut().find(|g| g[0].route() == queued.route()) {
+                Some(group) => group.push(queued),
+                None => groups.push(vec![queued]),
+            }
+        }
+        for group in groups {
+            embed_group(&shared, &config, group).await;
+        }
+
+        shared.finish(received);
+    }
+}
+
+/// Embed one route's jobs with retries and upsert the vectors still wanted
+async fn embed_group(shared: &Shared, config: &EmbeddingQueueConfig, group: Vec<Queued>) {
+    let texts: Vec<String> = group.iter().map(|q| q.job.content.clone()).collect();
+    let embedder = Arc::clone(&group[0].job.embedder);
+
+    let mut retry = 0;
+    let vectors = loop {
+        let error = match embedder.embed(&texts).await {
+            Ok(vectors) if vectors.len() == texts.len() => break vectors,
+            Ok(vectors) => format!(
+                "{} returned {} embeddings for {} inputs",
+                embedder.name(),
+                vectors.len(),
+                texts.len()
+            ),
+            Err(e) => e.to_string(),
+        };
+
+        if retry >= config.max_retries {
+            tracing::warn!("Giving up on {} embeddings: {}", texts.len(), error);
+            for queued in &group {
+                shared.complete(queued);
+            }
+            shared.fail(group.into_iter().map(|q| q.job.id));
+            return;
+        }
+        let wait = config.backoff(retry);
+        tracing::warn!("Embedding failed: {}; retrying in {:?}", error, wait);
+        tokio::time::sleep(wait).await;
+        retry += 1;
+    };
+
+    for (queued, vector) in group.iter().zip(vectors) {
+        // Superseded or cancelled while embedding
+        if !shared.is_latest(queued) {
+            continue;
+        }
+        let job = &queued.job;
+        match job
+            .vector
+            .upsert(&job.id, &vector, Some(job.payload.clone()))
+            .await
+        {
+            Ok(()) => {
+                if let Some(record) = &job.chunks {
+                    let replaced = replace_chunks(
+                        &record.store,
+                        job.vector.as_ref(),
+                        &record.memory_id,
+                        &record.chunks,
+                    )
+                    .await;
+                    if let Err(e) = replaced {
+                        tracing::warn!("Recording chunks of {} failed: {}", record.memory_id, e);
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Vector upsert for {} failed: {}", job.id, e);
+                shared.fail([job.id.clone()]);
+            }
+        }
+        shared.complete(queued);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::HashEmbeddingProvider;
+    use crate::types::{Memory, MemoryType};
+    use crate::vector_backend::InMemoryVectorBackend;
+    use crate::MemorySystem;
+    use async_trait::async_trait;
+
+    /// Fails the first `failures` calls
+    struct Flaky {
+        inner: HashEmbeddingProvider,
+        failures: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for Flaky {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        fn dimension(&self) -> usize {
+            self.inner.dimension()
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let left = self.failures.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures.store(left - 1, Ordering::SeqCst);
+                return Err(MemoryError::EmbeddingFailed("unavailable".to_string()));
+            }
+            self.inner.embed(texts).await
+        }
+    }
+
+    async fn system(
+        failures: usize,
+        max_retries: u32,
+    ) -> (MemorySystem, Arc<InMemoryVectorBackend>) {
+        let vectors = Arc::new(InMemoryVectorBackend::new(64));
+        let embedder = Arc::new(Flaky {
+            inner: HashEmbeddingProvider::new(64),
+            failures: AtomicUsize::new(failures),
+        });
+        let memory = MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_vector_backend(vectors.clone(), embedder)
+            .with_embedding_queue(
+                EmbeddingQueueConfig::default()
+                    .with_max_retries(max_retries)
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+            );
+        (memory, vectors)
+    }
+
+    #[tokio::test]
+    async fn saves_are_embedded_in_the_background_with_retries() {
+        let (memory, vectors) = system(2, 3).await;
+        for i in 0..5 {
+            let m = Memory::new(format!("Deploy note {i}"), MemoryType::Fact);
+            memory.save(&m).await.unwrap();
+        }
+
+        memory.flush_embeddings().await.unwrap();
+        assert_eq!(memory.pending_embeddings(), 0);
+        assert_eq!(vectors.stats().await.unwrap().rows, 5);
+    }
+
+    #[tokio::test]
+    async fn flush_reports_memories_that_could_not_be_embedded() {
+        let (memory, vectors) = system(usize::MAX, 1).await;
+        let m = Memory::new("Never embedded", MemoryType::Fact);
+        memory.save(&m).await.unwrap();
+
+        let error = memory.flush_embeddings().await.unwrap_err();
+        assert!(error.to_string().contains(&m.id));
+        assert_eq!(vectors.stats().await.unwrap().rows, 0);
+        // Reported once
+        memory.flush_embeddings().await.unwrap();
+    }
+}
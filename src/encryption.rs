@@ -0,0 +1,186 @@
+//! Encryption at rest
+//!
+//! With the `encryption` feature, a [`MemorySystem`] opened with an
+//! [`EncryptionKey`] in its [`MemorySystemOptions`] keeps its SQLite database
+//! encrypted with SQLCipher and its full-text index in memory only (it is
+//! rebuilt from the database on start). A [`FileVectorBackend`] given the same
+//! key with [`with_encryption`](FileVectorBackend::with_encryption) encrypts
+//! every vector file with ChaCha20-Poly1305 under a key derived from the
+//! passphrase and a per-directory salt.
+//!
+//! ```rust,ignore
+//! let key = EncryptionKey::new(std::env::var("GOLDFISH_KEY")?);
+//! let memory = MemorySystem::new_with_options(
+//!     "./data",
+//!     MemorySystemOptions::default().with_encryption_key(key.clone()),
+//! )
+//! .await?
+//! .with_vector_backend(
+//!     Arc::new(FileVectorBackend::new("./data/vectors", 384).with_encryption(&key)?),
+//!     Arc::new(HashEmbeddingProvider::new(384)),
+//! );
+//! ```
+//!
+//! [`MemorySystem`]: crate::MemorySystem
+//! [`MemorySystemOptions`]: crate::MemorySystemOptions
+//! [`FileVectorBackend`]: crate::FileVectorBackend
+
+use crate::error::{MemoryError, Result};
+use std::path::Path;
+
+/// Passphrase the database and vector files are encrypted with
+#[derive(Clone)]
+pub struct EncryptionKey(String);
+
+impl EncryptionKey {
+    pub fn new(passphrase: impl Into<String>) -> Self {
+        Self(passphrase.into())
+    }
+
+    /// Value for SQLCipher's `PRAGMA key`, quoted as a string literal
+    pub(crate) fn sqlcipher_pragma(&self) -> String {
+        format!("'{}'", self.0.replace('\'', "''"))
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+/// Prefix of encrypted vector files
+const MAGIC: &[u8] = b"GFENC1";
+
+/// Whether `data` was written by a [`VectorCipher`]
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+#[cfg(feature = "encryption")]
+mod imp {
+    use super::*;
+    use chacha20poly1305::aead::rand_core::RngCore;
+    use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+    /// Per-directory salt for the vector key
+    const SALT_FILE: &str = ".salt";
+    const SALT_LEN: usize = 16;
+    const NONCE_LEN: usize = 12;
+    const PBKDF2_ROUNDS: u32 = 100_000;
+
+    /// Encrypts vector files of one index directory
+    pub(crate) struct VectorCipher {
+        cipher: ChaCha20Poly1305,
+    }
+
+    impl VectorCipher {
+        /// Derive the key for `dir`, creating its salt on first use
+        pub(crate) fn open(dir: &Path, key: &EncryptionKey) -> Result<Self> {
+            std::fs::create_dir_all(dir)?;
+            let salt_path = dir.join(SALT_FILE);
+            let salt = match std::fs::read(&salt_path) {
+                Ok(salt) => salt,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    let mut salt = [0u8; SALT_LEN];
+                    OsRng.fill_bytes(&mut salt);
+                    std::fs::write(&salt_path, salt)?;
+                    salt.to_vec()
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut derived = [0u8; 32];
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+                key.0.as_bytes(),
+                &salt,
+                PBKDF2_ROUNDS,
+                &mut derived,
+            );
+            Ok(Self {
+                cipher: ChaCha20Poly1305::new(Key::from_slice(&derived)),
+            })
+        }
+
+        pub(crate) fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let ciphertext = self
+                .cipher
+                .encrypt(&nonce, plaintext)
+                .map_err(|_| MemoryError::Storage("Failed to encrypt vector".to_string()))?;
+            let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+            out.extend_from_slice(MAGIC);
+            out.extend_from_slice(&nonce);
+            out.extend_from_slice(&ciphertext);
+            Ok(out)
+        }
+
+        pub(crate) fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+            let body = data
+                .strip_prefix(MAGIC)
+                .filter(|b| b.len() > NONCE_LEN)
+                .ok_or_else(|| MemoryError::Storage("Vector file is not encrypted".to_string()))?;
+            let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+            self.cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| {
+                    MemoryError::Storage(
+                        "Failed to decrypt vector (wrong encryption key?)".to_string(),
+                    )
+                })
+        }
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+mod imp {
+    use super::*;
+
+    /// Stand-in when built without the `encryption` feature; never constructed
+    pub(crate) struct VectorCipher(());
+
+    impl VectorCipher {
+        pub(crate) fn open(_dir: &Path, _key: &EncryptionKey) -> Result<Self> {
+            Err(disabled())
+        }
+
+        pub(crate) fn encrypt(&self, _plaintext: &[u8]) -> Result<Vec<u8>> {
+            Err(disabled())
+        }
+
+        pub(crate) fn decrypt(&self, _data: &[u8]) -> Result<Vec<u8>> {
+            Err(disabled())
+        }
+    }
+}
+
+pub(crate) use imp::VectorCipher;
+
+/// Error for encryption requested in a build without the feature
+pub(crate) fn disabled() -> MemoryError {
+    MemoryError::Configuration(
+        "encryption requires building goldfish with the `encryption` feature".to_string(),
+    )
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector_files_only_open_with_the_right_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let cipher = VectorCipher::open(dir.path(), &EncryptionKey::new("s3cret")).unwrap();
+        let sealed = cipher.encrypt(b"vector bytes").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), b"vector bytes");
+
+        // Same directory, same salt: the key is derived again identically
+        let again = VectorCipher::open(dir.path(), &EncryptionKey::new("s3cret")).unwrap();
+        assert_eq!(again.decrypt(&sealed).unwrap(), b"vector bytes");
+
+        let wrong = VectorCipher::open(dir.path(), &EncryptionKey::new("guess")).unwrap();
+        assert!(wrong.decrypt(&sealed).is_err());
+    }
+}
@@ -17,6 +17,18 @@ pub enum MemoryError {
     #[error("Embedding failed: {0}")]
     EmbeddingFailed(String),
 
+    /// Text generation failed
+    #[error("Generation failed: {0}")]
+    Generation(String),
+
+    /// An ingestion connector failed to poll its source
+    #[error("Connector error: {0}")]
+    Connector(String),
+
+    /// A goldfish-server answered with an error or could not be reached
+    #[error("Remote error: {0}")]
+    Remote(String),
+
     /// Memory not found
     #[error("Memory not found: {0}")]
     NotFound(String),
@@ -4,11 +4,17 @@
 //! - Retrieval precision (does the right memory come back?)
 //! - Context quality (does build_context produce better prompts?)
 //! - End-to-end agent task success
+//! - Full-text search throughput with read replicas
 
-use crate::error::Result;
+use crate::embedding::{EmbeddingProvider, HashEmbeddingConfig, HashEmbeddingProvider};
+use crate::error::{MemoryError, Result};
 use crate::hybrid_retrieval::HybridSearchConfig;
+use crate::search::{MemorySearch, SearchConfig, SearcherPool};
 use crate::storage_backend::StorageBackend;
-use crate::types::MemoryType;
+use crate::types::{Memory, MemoryType};
+use crate::vector_search::cosine_similarity;
+use crate::MemoryStore;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Benchmark results
@@ -195,6 +201,263 @@ pub async fn run_standard_eval<B: StorageBackend>(backend: B) -> Result<Vec<Benc
     harness.compare_baselines().await
 }
 
+/// Recall@k of one embedding provider over a labelled corpus
+#[derive(Debug, Clone)]
+pub struct EmbeddingRecall {
+    pub provider: String,
+    pub k: usize,
+    pub recall_at_k: f32,
+}
+
+/// Side-by-side Recall@k of a baseline and a candidate embedding provider
+#[derive(Debug, Clone)]
+pub struct EmbeddingRecallComparison {
+    pub baseline: EmbeddingRecall,
+    pub candidate: EmbeddingRecall,
+    /// `candidate.recall_at_k - baseline.recall_at_k`
+    pub delta: f32,
+}
+
+/// Labelled query for embedding evaluation: `relevant` indexes into the corpus
+#[derive(Debug, Clone)]
+pub struct EmbeddingTestCase {
+    pub query: String,
+    pub relevant: Vec<usize>,
+}
+
+/// Measure Recall@k of `provider` by ranking every corpus document against each query
+pub async fn embedding_recall_at_k(
+    provider: &dyn EmbeddingProvider,
+    corpus: &[String],
+    cases: &[EmbeddingTestCase],
+    k: usize,
+) -> Result<EmbeddingRecall> {
+    let doc_vectors = provider.embed(corpus).await?;
+    let queries: Vec<String> = cases.iter().map(|c| c.query.clone()).collect();
+    let query_vectors = provider.embed(&queries).await?;
+
+    let mut total = 0.0f32;
+    for (case, qv) in cases.iter().zip(&query_vectors) {
+        let mut ranked: Vec<(usize, f32)> = doc_vectors
+            .iter()
+            .enumerate()
+            .map(|(i, dv)| (i, cosine_similarity(qv, dv)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let top: Vec<usize> = ranked.iter().take(k).map(|(i, _)| *i).collect();
+        let hits = case.relevant.iter().filter(|i| top.contains(i)).count();
+        total += if case.relevant.is_empty() {
+            1.0
+        } else {
+            hits as f32 / case.relevant.len() as f32
+        };
+    }
+
+    Ok(EmbeddingRecall {
+        provider: provider.name().to_string(),
+        k,
+        recall_at_k: if cases.is_empty() {
+            0.0
+        } else {
+            total / cases.len() as f32
+        },
+    })
+}
+
+/// Compare two embedding providers on the same corpus
+pub async fn compare_embedding_recall(
+    baseline: &dyn EmbeddingProvider,
+    candidate: &dyn EmbeddingProvider,
+    corpus: &[String],
+    cases: &[EmbeddingTestCase],
+    k: usize,
+) -> Result<EmbeddingRecallComparison> {
+    let baseline = embedding_recall_at_k(baseline, corpus, cases, k).await?;
+    let candidate = embedding_recall_at_k(candidate, corpus, cases, k).await?;
+    let delta = candidate.recall_at_k - baseline.recall_at_k;
+    Ok(EmbeddingRecallComparison {
+        baseline,
+        candidate,
+        delta,
+    })
+}
+
+/// Small built-in corpus with short queries, inflections and typos
+pub fn standard_embedding_suite() -> (Vec<String>, Vec<EmbeddingTestCase>) {
+    let corpus = [
+        "User prefers dark mode in every editor",
+        "The deployment pipeline runs on Kubernetes",
+        "Project deadline is the end of March",
+        "User is allergic to peanuts",
+        "We decided to migrate the database to Postgres",
+        "The team meets every Monday for planning",
+        "User enjoys hiking and running on weekends",
+        "Invoices are generated by the billing service",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    let case = |query: &str, relevant: &[usize]| EmbeddingTestCase {
+        query: query.to_string(),
+        relevant: relevant.to_vec(),
+    };
+    let cases = vec![
+        case("dark theme", &[0]),
+        case("kubernets", &[1]),
+        case("deadlines", &[2]),
+        case("peanut allergy", &[3]),
+        case("postgresql migration", &[4]),
+        case("weekly meeting", &[5]),
+        case("hikes", &[6]),
+        case("invoice billing", &[7]),
+    ];
+
+    (corpus, cases)
+}
+
+/// Recall@k delta between the legacy and the default `HashEmbeddingProvider`
+pub async fn run_embedding_eval(k: usize) -> Result<BenchmarkResults> {
+    let (corpus, cases) = standard_embedding_suite();
+    let legacy = HashEmbeddingProvider::with_config(384, HashEmbeddingConfig::legacy());
+    let improved = HashEmbeddingProvider::new(384);
+
+    let start = Instant::now();
+    let cmp = compare_embedding_recall(&legacy, &improved, &corpus, &cases, k).await?;
+    let latency = start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(BenchmarkResults {
+        name: format!("Hash Embedding Recall@{k}"),
+        retrieval_precision: cmp.candidate.recall_at_k,
+        context_quality_score: 0.0,
+        task_success_rate: 0.0,
+        avg_latency_ms: latency,
+        details: vec![
+            format!(
+                "Legacy (tokens only): {:.1}%",
+                cmp.baseline.recall_at_k * 100.0
+            ),
+            format!(
+                "Default (n-grams + stopwords): {:.1}%",
+                cmp.candidate.recall_at_k * 100.0
+            ),
+            format!("Delta: {:+.1} points", cmp.delta * 100.0),
+        ],
+    })
+}
+
+/// Throughput of concurrent full-text searches against a [`SearcherPool`]
+#[derive(Debug, Clone)]
+pub struct SearchQps {
+    pub replicas: usize,
+    pub concurrency: usize,
+    pub queries: usize,
+    pub elapsed_ms: f64,
+    pub qps: f64,
+}
+
+/// Run `total` searches, cycling through `queries`, from `concurrency` tasks
+/// sharing `pool`
+pub async fn search_qps(
+    pool: &SearcherPool,
+    queries: &[String],
+    config: &SearchConfig,
+    concurrency: usize,
+    total: usize,
+) -> Result<SearchQps> {
+    let concurrency = concurrency.max(1);
+    let queries = Arc::new(queries.to_vec());
+    let start = Instant::now();
+
+    let mut tasks = Vec::with_capacity(concurrency);
+    for worker in 0..concurrency {
+        let pool = pool.clone();
+        let queries = Arc::clone(&queries);
+        let config = config.clone();
+        tasks.push(tokio::spawn(async move {
+            for i in (worker..total).step_by(concurrency) {
+                if queries.is_empty() {
+                    break;
+                }
+                pool.search(&queries[i % queries.len()], &config).await?;
+            }
+            Ok::<(), MemoryError>(())
+        }));
+    }
+    for task in tasks {
+        task.await.map_err(anyhow::Error::from)??;
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    Ok(SearchQps {
+        replicas: pool.len(),
+        concurrency,
+        queries: total,
+        elapsed_ms: elapsed * 1000.0,
+        qps: if elapsed > 0.0 {
+            total as f64 / elapsed
+        } else {
+            0.0
+        },
+    })
+}
+
+/// QPS of a single Tantivy reader vs. `replicas` read replicas, both searched by
+/// `concurrency` tasks, over a synthetic corpus built from the embedding suite
+pub async fn run_search_replica_eval(
+    replicas: usize,
+    concurrency: usize,
+    total: usize,
+) -> Result<BenchmarkResults> {
+    let (corpus, cases) = standard_embedding_suite();
+    let store = MemoryStore::connect_in_memory().await;
+    for i in 0..2_000 {
+        let content = format!("{} (note {})", corpus[i % corpus.len()], i);
+        store.save(&Memory::new(content, MemoryType::Fact)).await?;
+    }
+    let search = MemorySearch::new(Arc::clone(&store));
+    search.reindex_all().await?;
+
+    let queries: Vec<String> = cases.into_iter().map(|c| c.query).collect();
+    let config = SearchConfig::default();
+    let single = search_qps(
+        &SearcherPool::from_search(&search, 1)?,
+        &queries,
+        &config,
+        concurrency,
+        total,
+    )
+    .await?;
+    let pooled = search_qps(
+        &SearcherPool::from_search(&search, replicas)?,
+        &queries,
+        &config,
+        concurrency,
+        total,
+    )
+    .await?;
+
+    let speedup = if single.qps > 0.0 {
+        pooled.qps / single.qps
+    } else {
+        0.0
+    };
+    Ok(BenchmarkResults {
+        name: format!("Search QPS: 1 vs {replicas} read replicas"),
+        retrieval_precision: 0.0,
+        context_quality_score: 0.0,
+        task_success_rate: 0.0,
+        avg_latency_ms: pooled.elapsed_ms / total.max(1) as f64,
+        details: vec![
+            format!("{concurrency} concurrent tasks, {total} queries"),
+            format!("1 replica: {:.0} QPS", single.qps),
+            format!("{replicas} replicas: {:.0} QPS", pooled.qps),
+            format!("Speedup: {speedup:.2}x"),
+        ],
+    })
+}
+
 /// Print benchmark results
 pub fn print_results(results: &[BenchmarkResults]) {
     println!("\n========================================");
@@ -0,0 +1,282 @@
+//! A/B experiments over hybrid retrieval configs
+//!
+//! An [`ExperimentRouter`] holds several named [`HybridSearchConfig`] variants
+//! and assigns every session to one of them by a stable hash of the session ID,
+//! so a user sees consistent results and the split survives restarts.
+//! [`MemorySystem::hybrid_search_experiment`] runs the query with the assigned
+//! variant and tags the results with it; feedback on those results is recorded
+//! against the same variant. [`ExperimentRouter::report`] then compares the
+//! variants on live traffic.
+//!
+//! ```rust,ignore
+//! let router = ExperimentRouter::new("graph-weight")
+//!     .with_variant("control", HybridSearchConfig::default())
+//!     .with_variant("more-graph", HybridSearchConfig { weight_graph: 0.3, ..Default::default() });
+//!
+//! let search = memory.hybrid_search_experiment(&router, &session_id, "deploy plan", None).await?;
+//! router.record_feedback(&search, &search.results[0].memory.id, true);
+//! for variant in router.report() {
+//!     println!("{}: precision {:.2}, MRR {:.2}", variant.variant, variant.precision, variant.mean_reciprocal_rank);
+//! }
+//! ```
+//!
+//! [`MemorySystem::hybrid_search_experiment`]: crate::MemorySystem::hybrid_search_experiment
+
+use crate::embedding::{fnv1a, FNV_OFFSET};
+use crate::hybrid_retrieval::{ExplainedSearchResult, HybridSearchConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// One arm of an experiment
+#[derive(Debug, Clone)]
+pub struct ExperimentVariant {
+    pub name: String,
+    pub config: HybridSearchConfig,
+    /// Relative share of sessions routed to this variant
+    pub weight: u32,
+}
+
+/// Results of a query run under an experiment, tagged with the variant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentSearch {
+    pub experiment: String,
+    pub variant: String,
+    pub session_id: String,
+    pub results: Vec<ExplainedSearchResult>,
+    pub timed_out: bool,
+}
+
+/// Live-traffic metrics of one variant, see [`ExperimentRouter::report`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantMetrics {
+    pub variant: String,
+    pub queries: u64,
+    /// Queries that returned nothing
+    pub empty_results: u64,
+    pub timed_out: u64,
+    pub mean_latency_ms: f64,
+    pub mean_results: f64,
+    pub feedback_relevant: u64,
+    pub feedback_irrelevant: u64,
+    /// Share of rated results that were relevant
+    pub precision: f64,
+    /// Mean of 1/rank over results rated relevant
+    pub mean_reciprocal_rank: f64,
+}
+
+#[derive(Debug, Default)]
+struct VariantStats {
+    queries: u64,
+    empty_results: u64,
+    timed_out: u64,
+    latency_ms: f64,
+    results: u64,
+    relevant: u64,
+    irrelevant: u64,
+    reciprocal_ranks: f64,
+}
+
+/// Routes sessions to retrieval config variants and collects their metrics
+#[derive(Debug, Clone)]
+pub struct ExperimentRouter {
+    name: String,
+    variants: Vec<ExperimentVariant>,
+    stats: Arc<Mutex<HashMap<String, VariantStats>>>,
+}
+
+impl ExperimentRouter {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variants: Vec::new(),
+            stats: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Add a variant with weight 1
+    pub fn with_variant(self, name: impl Into<String>, config: HybridSearchConfig) -> Self {
+        self.with_weighted_variant(name, config, 1)
+    }
+
+    pub fn with_weighted_variant(
+        mut self,
+        name: impl Into<String>,
+        config: HybridSearchConfig,
+        weight: u32,
+    ) -> Self {
+        self.variants.push(ExperimentVariant {
+            name: name.into(),
+            config,
+            weight,
+        });
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn variants(&self) -> &[ExperimentVariant] {
+        &self.variants
+    }
+
+    /// The variant `session_id` is routed to; the same session always gets
+    /// the same variant as long as the variants don't change
+    pub fn assign(&self, session_id: &str) -> Option<&ExperimentVariant> {
+        let total: u64 = self.variants.iter().map(|v| u64::from(v.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let key = format!("{}:{}", self.name, session_id);
+        let mut bucket = fnv1a(key.as_bytes(), FNV_OFFSET) % total;
+        for variant in &self.variants {
+            let weight = u64::from(variant.weight);
+            if bucket < weight {
+                return Some(variant);
+            }
+            bucket -= weight;
+        }
+        None
+    }
+
+    /// Count a query served by `variant`
+    pub fn record_query(
+        &self,
+        variant: &str,
+        result_count: usize,
+        timed_out: bool,
+        latency: Duration,
+    ) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.entry(variant.to_string()).or_default();
+        entry.queries += 1;
+        entry.results += result_count as u64;
+        entry.latency_ms += latency.as_secs_f64() * 1000.0;
+        if result_count == 0 {
+            entry.empty_results += 1;
+        }
+        if timed_out {
+            entry.timed_out += 1;
+        }
+    }
+
+    /// Rate a memory returned by `search`, counted against the variant that
+    /// served it. A relevant memory also adds 1/rank at its position in the
+    /// results (nothing if it was not among them).
+    pub fn record_feedback(&self, search: &ExperimentSearch, memory_id: &str, relevant: bool) {
+        let rank = search
+            .results
+            .iter()
+            .find(|r| r.memory.id == memory_id)
+            .map(|r| r.rank);
+
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.entry(search.variant.clone()).or_default();
+        if relevant {
+            entry.relevant += 1;
+            if let Some(rank) = rank.filter(|r| *r > 0) {
+                entry.reciprocal_ranks += 1.0 / rank as f64;
+            }
+        } else {
+            entry.irrelevant += 1;
+        }
+    }
+
+    /// Metrics of every variant, in the order they were added
+    pub fn report(&self) -> Vec<VariantMetrics> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        self.variants
+            .iter()
+            .map(|variant| {
+                let Some(s) = stats.get(&variant.name) else {
+                    return VariantMetrics {
+                        variant: variant.name.clone(),
+                        ..Default::default()
+                    };
+                };
+                let per_query = |total: f64| {
+                    if s.queries == 0 {
+                        0.0
+                    } else {
+                        total / s.queries as f64
+                    }
+                };
+                let rated = s.relevant + s.irrelevant;
+                VariantMetrics {
+                    variant: variant.name.clone(),
+                    queries: s.queries,
+                    empty_results: s.empty_results,
+                    timed_out: s.timed_out,
+                    mean_latency_ms: per_query(s.latency_ms),
+                    mean_results: per_query(s.results as f64),
+                    feedback_relevant: s.relevant,
+                    feedback_irrelevant: s.irrelevant,
+                    precision: if rated == 0 {
+                        0.0
+                    } else {
+                        s.relevant as f64 / rated as f64
+                    },
+                    mean_reciprocal_rank: if s.relevant == 0 {
+                        0.0
+                    } else {
+                        s.reciprocal_ranks / s.relevant as f64
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Drop collected metrics, e.g. after changing the variants
+    pub fn reset(&self) {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hybrid_retrieval::RetrievalExplanation;
+    use crate::types::{Memory, MemoryType};
+
+    #[test]
+    fn sessions_stick_to_a_variant_and_feedback_is_attributed() {
+        let router = ExperimentRouter::new("weights")
+            .with_variant("control", HybridSearchConfig::default())
+            .with_weighted_variant("treatment", HybridSearchConfig::default(), 3);
+
+        let first = router.assign("session-42").unwrap().name.clone();
+        for _ in 0..5 {
+            assert_eq!(router.assign("session-42").unwrap().name, first);
+        }
+        let assigned: std::collections::HashSet<_> = (0..200)
+            .map(|i| router.assign(&format!("s{i}")).unwrap().name.clone())
+            .collect();
+        assert_eq!(assigned.len(), 2);
+
+        let memory = Memory::new("deploy on fridays", MemoryType::Fact);
+        let search = ExperimentSearch {
+            experiment: "weights".into(),
+            variant: "treatment".into(),
+            session_id: "s1".into(),
+            results: vec![ExplainedSearchResult {
+                memory: memory.clone(),
+                score: 1.0,
+                rank: 2,
+                explanation: RetrievalExplanation::default(),
+            }],
+            timed_out: false,
+        };
+        router.record_query("treatment", 1, false, Duration::from_millis(10));
+        router.record_feedback(&search, &memory.id, true);
+        router.record_feedback(&search, "other", false);
+
+        let report = router.report();
+        assert_eq!(report[0].queries, 0);
+        let treatment = &report[1];
+        assert_eq!(treatment.queries, 1);
+        assert_eq!(treatment.precision, 0.5);
+        assert_eq!(treatment.mean_reciprocal_rank, 0.5);
+    }
+}
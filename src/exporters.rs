@@ -0,0 +1,773 @@
+//! Outbound sync for human review
+//!
+//! An [`Exporter`] mirrors selected memory types (by default decisions, goals
+//! and summaries) to a place people already read: a folder of markdown files
+//! ([`MarkdownExporter`]) or a Notion database ([`NotionExporter`]). Every
+//! exported document carries its memory ID and links to the memories it is
+//! associated with, so a reviewer can trace what the agent believes back to
+//! the store.
+//!
+//! The [`ExportScheduler`] runs each exporter on its own interval. Exporters
+//! keep their own state (what was written where) in a checkpoint per
+//! namespace, so unchanged memories are skipped and forgotten ones are removed
+//! from the mirror.
+//!
+//! ```rust,ignore
+//! let handle = ExportScheduler::new(memory.clone())
+//!     .with_exporter(MarkdownExporter::new("review", "./agent-notes"))
+//!     .with_exporter(NotionExporter::new("notion", token, database_id))
+//!     .spawn();
+//! ```
+
+use crate::connectors::{send_json, DEFAULT_POLL_INTERVAL};
+use crate::error::{MemoryError, Result};
+use crate::sources::content_hash;
+use crate::types::{Memory, MemoryId, MemoryType, RelationType};
+use crate::MemorySystem;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Memory types mirrored unless an exporter is configured otherwise
+pub const DEFAULT_EXPORT_TYPES: [MemoryType; 3] =
+    [MemoryType::Decision, MemoryType::Goal, MemoryType::Summary];
+
+/// Most memories of one type exported per run
+const EXPORT_LIMIT: i64 = 10_000;
+
+/// A memory to mirror, with the memories it links to
+#[derive(Debug, Clone)]
+pub struct ExportDocument {
+    pub memory: Memory,
+    /// Associated memories, from this memory's point of view
+    pub links: Vec<ExportLink>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportLink {
+    pub relation_type: RelationType,
+    pub memory_id: MemoryId,
+    /// Whether the link points from this memory to the other one
+    pub outgoing: bool,
+}
+
+impl ExportDocument {
+    /// First line of the content, shortened to a title
+    pub fn title(&self) -> String {
+        let line = self
+            .memory
+            .content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim();
+        if line.chars().count() > 80 {
+            format!("{}…", line.chars().take(79).collect::<String>())
+        } else {
+            line.to_string()
+        }
+    }
+
+    /// Changes whenever anything rendered from the document changes
+    pub fn fingerprint(&self) -> String {
+        let links: Vec<String> = self
+            .links
+            .iter()
+            .map(|l| format!("{}:{}:{}", l.relation_type, l.memory_id, l.outgoing))
+            .collect();
+        content_hash(&format!(
+            "{}\n{}\n{}\n{:.3}\n{}\n{}",
+            self.memory.content,
+            self.memory.memory_type,
+            self.memory.updated_at.to_rfc3339(),
+            self.memory.importance,
+            self.memory.tags.join(","),
+            links.join(",")
+        ))
+    }
+}
+
+/// What an export run wrote
+#[derive(Debug, Clone, Default)]
+pub struct ExportBatch {
+    /// Documents created or rewritten
+    pub written: usize,
+    /// Documents removed because their memory was forgotten or deleted
+    pub removed: usize,
+    /// Saved and passed to the next run
+    pub checkpoint: Option<String>,
+    /// Set when the export stopped part way; `checkpoint` still records what
+    /// was written before
+    pub error: Option<String>,
+}
+
+/// A destination memories are mirrored to on a schedule
+#[async_trait]
+pub trait Exporter: Send + Sync {
+    /// Unique name, used as the checkpoint key
+    fn name(&self) -> &str;
+
+    /// Time between exports
+    fn interval(&self) -> Duration {
+        DEFAULT_POLL_INTERVAL
+    }
+
+    /// Memory types this exporter mirrors
+    fn memory_types(&self) -> &[MemoryType] {
+        &DEFAULT_EXPORT_TYPES
+    }
+
+    /// Bring the destination in line with `documents`, the full current set.
+    /// `checkpoint` is what the previous run returned (`None` the first time).
+    async fn export(
+        &self,
+        documents: &[ExportDocument],
+        checkpoint: Option<&str>,
+    ) -> Result<ExportBatch>;
+}
+
+/// Outcome of running an exporter once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRun {
+    pub exporter: String,
+    pub started_at: DateTime<Utc>,
+    pub exported: usize,
+    pub written: usize,
+    pub removed: usize,
+    /// Why the export failed
+    pub error: Option<String>,
+}
+
+/// Runs exporters against a memory system
+pub struct ExportScheduler {
+    memory: MemorySystem,
+    exporters: Vec<Arc<dyn Exporter>>,
+}
+
+impl ExportScheduler {
+    pub fn new(memory: MemorySystem) -> Self {
+        Self {
+            memory,
+            exporters: Vec::new(),
+        }
+    }
+
+    pub fn with_exporter(mut self, exporter: impl Exporter + 'static) -> Self {
+        self.exporters.push(Arc::new(exporter));
+        self
+    }
+
+    pub fn add_exporter(&mut self, exporter: Arc<dyn Exporter>) {
+        self.exporters.push(exporter);
+    }
+
+    pub fn exporters(&self) -> &[Arc<dyn Exporter>] {
+        &self.exporters
+    }
+
+    /// Run every exporter once, in order
+    pub async fn run_once(&self) -> Vec<ExportRun> {
+        let mut runs = Vec::with_capacity(self.exporters.len());
+        for exporter in &self.exporters {
+            runs.push(run_exporter(&self.memory, exporter.as_ref()).await);
+        }
+        runs
+    }
+
+    /// Run each exporter on its own interval in background tasks, starting now
+    pub fn spawn(self) -> ExportHandle {
+        let tasks = self
+            .exporters
+            .into_iter()
+            .map(|exporter| {
+                let memory = self.memory.clone();
+                tokio::spawn(async move {
+                    let mut ticks = tokio::time::interval(exporter.interval());
+                    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    loop {
+                        ticks.tick().await;
+                        run_exporter(&memory, exporter.as_ref()).await;
+                    }
+                })
+            })
+            .collect();
+        ExportHandle { tasks }
+    }
+}
+
+/// Background export tasks started by [`ExportScheduler::spawn`]
+pub struct ExportHandle {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ExportHandle {
+    pub fn stop(self) {
+        for task in self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// The current documents for `memory_types`
+pub async fn export_documents(
+    memory: &MemorySystem,
+    memory_types: &[MemoryType],
+) -> Result<Vec<ExportDocument>> {
+    let mut documents = Vec::new();
+    for &memory_type in memory_types {
+        for m in memory.get_by_type(memory_type, EXPORT_LIMIT).await? {
+            let links = memory
+                .get_associations(&m.id)
+                .await?
+                .into_iter()
+                .map(|a| {
+                    let outgoing = a.source_id == m.id;
+                    ExportLink {
+                        relation_type: a.relation_type,
+                        memory_id: if outgoing { a.target_id } else { a.source_id },
+                        outgoing,
+                    }
+                })
+                .collect();
+            documents.push(ExportDocument { memory: m, links });
+        }
+    }
+    Ok(documents)
+}
+
+async fn run_exporter(memory: &MemorySystem, exporter: &dyn Exporter) -> ExportRun {
+    let name = exporter.name().to_string();
+    let key = format!("export:{name}");
+    let mut run = ExportRun {
+        exporter: name.clone(),
+        started_at: memory.store.now(),
+        exported: 0,
+        written: 0,
+        removed: 0,
+        error: None,
+    };
+
+    let result: Result<()> = async {
+        let documents = export_documents(memory, exporter.memory_types()).await?;
+        run.exported = documents.len();
+        let checkpoint = memory.store.connector_checkpoint(&key).await?;
+        let batch = exporter.export(&documents, checkpoint.as_deref()).await?;
+        run.written = batch.written;
+        run.removed = batch.removed;
+        if let Some(checkpoint) = batch.checkpoint {
+            memory
+                .store
+                .set_connector_checkpoint(&key, &checkpoint)
+                .await?;
+        }
+        match batch.error {
+            Some(error) => Err(MemoryError::Connector(error)),
+            None => Ok(()),
+        }
+    }
+    .await;
+
+    match result {
+        Ok(()) if run.written + run.removed > 0 => {
+            tracing::info!(
+                "Exporter '{}' wrote {} and removed {} documents",
+                name,
+                run.written,
+                run.removed
+            );
+        }
+        Ok(()) => {}
+        Err(e) => {
+            tracing::warn!("Exporter '{}' failed: {}", name, e);
+            run.error = Some(e.to_string());
+        }
+    }
+    run
+}
+
+/// Fingerprints of exported memories, plus the destination's ID for each
+/// (a Notion page ID; unused for markdown)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ExportState {
+    documents: BTreeMap<MemoryId, ExportedDocument>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ExportedDocument {
+    fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remote_id: Option<String>,
+}
+
+impl ExportState {
+    fn from_checkpoint(checkpoint: Option<&str>) -> Self {
+        checkpoint
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_default()
+    }
+
+    fn to_checkpoint(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// IDs exported before that are not in `documents` any more
+    fn stale(&self, documents: &[ExportDocument]) -> Vec<MemoryId> {
+        let current: HashSet<&str> = documents.iter().map(|d| d.memory.id.as_str()).collect();
+        self.documents
+            .keys()
+            .filter(|id| !current.contains(id.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+// ─── Markdown ──────────────────────────────────────────────────────────────
+
+/// Mirrors memories to `<dir>/<memory id>.md`, plus an `index.md` grouped by type
+#[derive(Debug, Clone)]
+pub struct MarkdownExporter {
+    name: String,
+    dir: PathBuf,
+    interval: Duration,
+    memory_types: Vec<MemoryType>,
+}
+
+impl MarkdownExporter {
+    pub fn new(name: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            dir: dir.into(),
+            interval: DEFAULT_POLL_INTERVAL,
+            memory_types: DEFAULT_EXPORT_TYPES.to_vec(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_memory_types(mut self, memory_types: impl Into<Vec<MemoryType>>) -> Self {
+        self.memory_types = memory_types.into();
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// File name of a memory's markdown document
+pub fn markdown_file_name(id: &str) -> String {
+    let stem: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{stem}.md")
+}
+
+/// Render a document as markdown with YAML front matter and backlinks
+pub fn render_markdown(document: &ExportDocument) -> String {
+    let memory = &document.memory;
+    let mut out = String::from("---\n");
+    out.push_str(&format!("id: {}\n", memory.id));
+    out.push_str(&format!("type: {}\n", memory.memory_type));
+    out.push_str(&format!("importance: {:.2}\n", memory.importance));
+    out.push_str(&format!("created: {}\n", memory.created_at.to_rfc3339()));
+    out.push_str(&format!("updated: {}\n", memory.updated_at.to_rfc3339()));
+    if !memory.tags.is_empty() {
+        out.push_str(&format!("tags: [{}]\n", memory.tags.join(", ")));
+    }
+    if let Some(source) = &memory.source {
+        out.push_str(&format!("source: {source}\n"));
+    }
+    out.push_str("---\n\n");
+    out.push_str(&format!("# {}\n\n", document.title()));
+    out.push_str(memory.content.trim_end());
+    out.push('\n');
+
+    if !document.links.is_empty() {
+        out.push_str("\n## Links\n\n");
+        for link in &document.links {
+            let direction = if link.outgoing { "→" } else { "←" };
+            out.push_str(&format!(
+                "- {direction} {} [{}]({})\n",
+                link.relation_type,
+                link.memory_id,
+                markdown_file_name(&link.memory_id)
+            ));
+        }
+    }
+    out
+}
+
+fn render_index(documents: &[ExportDocument], memory_types: &[MemoryType]) -> String {
+    let mut out = String::from("# Agent memory\n");
+    for memory_type in memory_types {
+        let mut of_type: Vec<&ExportDocument> = documents
+            .iter()
+            .filter(|d| d.memory.memory_type == *memory_type)
+            .collect();
+        if of_type.is_empty() {
+            continue;
+        }
+        of_type.sort_by_key(|d| std::cmp::Reverse(d.memory.updated_at));
+        out.push_str(&format!("\n## {memory_type}\n\n"));
+        for document in of_type {
+            out.push_str(&format!(
+                "- [{}]({})\n",
+                document.title(),
+                markdown_file_name(&document.memory.id)
+            ));
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl Exporter for MarkdownExporter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn memory_types(&self) -> &[MemoryType] {
+        &self.memory_types
+    }
+
+    async fn export(
+        &self,
+        documents: &[ExportDocument],
+        checkpoint: Option<&str>,
+    ) -> Result<ExportBatch> {
+        let mut state = ExportState::from_checkpoint(checkpoint);
+        let mut batch = ExportBatch::default();
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        for document in documents {
+            let fingerprint = document.fingerprint();
+            let path = self.dir.join(markdown_file_name(&document.memory.id));
+            let unchanged = state
+                .documents
+                .get(&document.memory.id)
+                .is_some_and(|d| d.fingerprint == fingerprint);
+            if unchanged && tokio::fs::try_exists(&path).await? {
+                continue;
+            }
+            tokio::fs::write(&path, render_markdown(document)).await?;
+            state.documents.insert(
+                document.memory.id.clone(),
+                ExportedDocument {
+                    fingerprint,
+                    remote_id: None,
+                },
+            );
+            batch.written += 1;
+        }
+
+        // Only files this exporter wrote are removed; anything else in the
+        // folder (notes by reviewers) is left alone
+        for id in state.stale(documents) {
+            match tokio::fs::remove_file(self.dir.join(markdown_file_name(&id))).await {
+                Ok(()) => batch.removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            state.documents.remove(&id);
+        }
+
+        tokio::fs::write(
+            self.dir.join("index.md"),
+            render_index(documents, &self.memory_types),
+        )
+        .await?;
+        batch.checkpoint = Some(state.to_checkpoint());
+        Ok(batch)
+    }
+}
+
+// ─── Notion ────────────────────────────────────────────────────────────────
+
+/// API version sent in the `Notion-Version` header
+pub const NOTION_API_VERSION: &str = "2022-06-28";
+
+/// Longest text Notion accepts in one rich text object
+const NOTION_TEXT_LIMIT: usize = 2000;
+
+/// Mirrors memories to pages of a Notion database. Needs the `connectors`
+/// feature.
+///
+/// The database needs these properties: `Name` (title), `Memory ID`,
+/// `Content` and `Links` (text), `Type` (select), `Importance` (number) and
+/// `Updated` (date). Pages of forgotten memories are archived.
+#[derive(Clone)]
+pub struct NotionExporter {
+    name: String,
+    token: String,
+    database_id: String,
+    api_base: String,
+    interval: Duration,
+    memory_types: Vec<MemoryType>,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for NotionExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotionExporter")
+            .field("name", &self.name)
+            .field("database_id", &self.database_id)
+            .field("memory_types", &self.memory_types)
+            .finish()
+    }
+}
+
+impl NotionExporter {
+    pub fn new(
+        name: impl Into<String>,
+        token: impl Into<String>,
+        database_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            token: token.into(),
+            database_id: database_id.into(),
+            api_base: "https://api.notion.com/v1".to_string(),
+            interval: DEFAULT_POLL_INTERVAL,
+            memory_types: DEFAULT_EXPORT_TYPES.to_vec(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Point at another API host, e.g. a test server
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_memory_types(mut self, memory_types: impl Into<Vec<MemoryType>>) -> Self {
+        self.memory_types = memory_types.into();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "Authorization".to_string(),
+                format!("Bearer {}", self.token),
+            ),
+            ("Notion-Version".to_string(), NOTION_API_VERSION.to_string()),
+        ]
+    }
+
+    /// Page properties for a document
+    pub fn properties(&self, document: &ExportDocument) -> serde_json::Value {
+        let memory = &document.memory;
+        let links: Vec<String> = document
+            .links
+            .iter()
+            .map(|l| {
+                let direction = if l.outgoing { "→" } else { "←" };
+                format!("{direction} {} {}", l.relation_type, l.memory_id)
+            })
+            .collect();
+        serde_json::json!({
+            "Name": { "title": rich_text(&document.title()) },
+            "Memory ID": { "rich_text": rich_text(&memory.id) },
+            "Type": { "select": { "name": memory.memory_type.to_string() } },
+            "Importance": { "number": memory.importance },
+            "Updated": { "date": { "start": memory.updated_at.to_rfc3339() } },
+            "Content": { "rich_text": rich_text(&memory.content) },
+            "Links": { "rich_text": rich_text(&links.join("\n")) },
+        })
+    }
+
+    async fn upsert_page(
+        &self,
+        document: &ExportDocument,
+        page_id: Option<&str>,
+    ) -> Result<String> {
+        let properties = self.properties(document);
+        let response = match page_id {
+            Some(page_id) => {
+                send_json(
+                    "PATCH",
+                    &format!("{}/pages/{page_id}", self.api_base),
+                    &self.headers(),
+                    &serde_json::json!({ "properties": properties }),
+                    self.timeout,
+                )
+                .await?
+            }
+            None => {
+                send_json(
+                    "POST",
+                    &format!("{}/pages", self.api_base),
+                    &self.headers(),
+                    &serde_json::json!({
+                        "parent": { "database_id": self.database_id },
+                        "properties": properties,
+                    }),
+                    self.timeout,
+                )
+                .await?
+            }
+        };
+        response
+            .get("id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| MemoryError::Connector("Notion response has no page id".to_string()))
+    }
+
+    async fn archive_page(&self, page_id: &str) -> Result<()> {
+        send_json(
+            "PATCH",
+            &format!("{}/pages/{page_id}", self.api_base),
+            &self.headers(),
+            &serde_json::json!({ "archived": true }),
+            self.timeout,
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+/// Notion rich text, split into objects of at most [`NOTION_TEXT_LIMIT`] chars
+fn rich_text(text: &str) -> serde_json::Value {
+    let chars: Vec<char> = text.chars().collect();
+    let parts: Vec<serde_json::Value> = chars
+        .chunks(NOTION_TEXT_LIMIT)
+        .take(100)
+        .map(|chunk| {
+            serde_json::json!({
+                "type": "text",
+                "text": { "content": chunk.iter().collect::<String>() },
+            })
+        })
+        .collect();
+    serde_json::Value::Array(parts)
+}
+
+#[async_trait]
+impl Exporter for NotionExporter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn memory_types(&self) -> &[MemoryType] {
+        &self.memory_types
+    }
+
+    /// A failed request stops the run, keeping the page IDs created so far so
+    /// the next run updates those pages instead of creating duplicates
+    async fn export(
+        &self,
+        documents: &[ExportDocument],
+        checkpoint: Option<&str>,
+    ) -> Result<ExportBatch> {
+        let mut state = ExportState::from_checkpoint(checkpoint);
+        let mut batch = ExportBatch::default();
+
+        let result: Result<()> = async {
+            for document in documents {
+                let fingerprint = document.fingerprint();
+                let previous = state.documents.get(&document.memory.id);
+                if previous.is_some_and(|d| d.fingerprint == fingerprint) {
+                    continue;
+                }
+                let page_id = previous.and_then(|d| d.remote_id.clone());
+                let page_id = self.upsert_page(document, page_id.as_deref()).await?;
+                state.documents.insert(
+                    document.memory.id.clone(),
+                    ExportedDocument {
+                        fingerprint,
+                        remote_id: Some(page_id),
+                    },
+                );
+                batch.written += 1;
+            }
+
+            for id in state.stale(documents) {
+                if let Some(page_id) = state.documents.get(&id).and_then(|d| d.remote_id.clone()) {
+                    self.archive_page(&page_id).await?;
+                    batch.removed += 1;
+                }
+                state.documents.remove(&id);
+            }
+            Ok(())
+        }
+        .await;
+
+        batch.error = result.err().map(|e| e.to_string());
+        batch.checkpoint = Some(state.to_checkpoint());
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{markdown_file_name, ExportScheduler, MarkdownExporter};
+    use crate::{Memory, MemorySystem, MemoryType, RelationType};
+
+    #[tokio::test]
+    async fn markdown_export_mirrors_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::in_memory().await.unwrap();
+        let decision = Memory::new("Use Postgres for billing", MemoryType::Decision);
+        let fact = Memory::new("Billing needs row-level locks", MemoryType::Fact);
+        memory.save(&decision).await.unwrap();
+        memory.save(&fact).await.unwrap();
+        memory
+            .associate(&decision.id, &fact.id, RelationType::CausedBy)
+            .await
+            .unwrap();
+
+        let out = dir.path().join("notes");
+        let scheduler =
+            ExportScheduler::new(memory.clone()).with_exporter(MarkdownExporter::new("md", &out));
+
+        let runs = scheduler.run_once().await;
+        assert_eq!(runs[0].error, None);
+        assert_eq!(runs[0].written, 1);
+        let page = std::fs::read_to_string(out.join(markdown_file_name(&decision.id))).unwrap();
+        assert!(page.contains(&format!("id: {}", decision.id)));
+        assert!(page.contains(&markdown_file_name(&fact.id)));
+        assert!(!out.join(markdown_file_name(&fact.id)).exists());
+
+        let again = scheduler.run_once().await;
+        assert_eq!(again[0].written, 0);
+
+        memory.forget(&decision.id).await.unwrap();
+        let pruned = scheduler.run_once().await;
+        assert_eq!(pruned[0].removed, 1);
+        assert!(!out.join(markdown_file_name(&decision.id)).exists());
+    }
+}
@@ -0,0 +1,63 @@
+//! Text generation providers
+//!
+//! A [`TextGenerator`] turns a prompt into text. It is the building block for
+//! LLM-backed summarization, answering and judging on top of the memory system.
+
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+/// Stream of generated text fragments, in order
+pub type TextStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Per-request generation options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationOptions {
+    /// System prompt
+    pub system: Option<String>,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate
+    pub max_tokens: Option<u32>,
+    /// Stop sequences
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+impl GenerationOptions {
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+#[async_trait]
+pub trait TextGenerator: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Generate the full completion for `prompt`
+    async fn generate(&self, prompt: &str, options: &GenerationOptions) -> Result<String>;
+
+    /// Generate incrementally. The default implementation yields the full
+    /// completion as a single fragment.
+    async fn generate_stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+    ) -> Result<TextStream> {
+        let text = self.generate(prompt, options).await?;
+        Ok(Box::pin(futures::stream::once(async move { Ok(text) })))
+    }
+}
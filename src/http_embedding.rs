@@ -0,0 +1,477 @@
+//! Embeddings from any REST endpoint
+//!
+//! [`HttpEmbeddingProvider`] calls a custom embedding service described by a
+//! [`HttpEmbeddingConfig`], which can live in a YAML or JSON file:
+//!
+//! ```yaml
+//! url: "https://embed.internal/v2/models/{model}/embed"
+//! vars:
+//!   model: e5-large
+//! auth:
+//!   header: X-Api-Key
+//!   value: "{env:EMBED_API_KEY}"
+//! request:
+//!   inputs: "{{texts}}"
+//!   truncate: true
+//! response: "result.vectors.*.values"
+//! dimension: 1024
+//! ```
+//!
+//! Strings in `url`, `headers`, `auth` and `request` may use `{name}` for an
+//! entry of `vars` and `{env:NAME}` for an environment variable. In `request`,
+//! the string `"{{texts}}"` is replaced by the batch of texts; an endpoint that
+//! takes one text per call uses `"{{text}}"` instead.
+//!
+//! `response` is a dotted path to the vectors: keys select object fields,
+//! numbers select array elements and `*` selects every element. It must lead
+//! to one vector per input, or to a single array of them.
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::{MemoryError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+const TEXTS: &str = "{{texts}}";
+const TEXT: &str = "{{text}}";
+
+/// Header carrying the service's credentials
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HttpAuth {
+    /// Header name
+    #[serde(default = "default_auth_header")]
+    pub header: String,
+    /// Header value, e.g. `"Bearer {env:EMBED_TOKEN}"`
+    pub value: String,
+}
+
+fn default_auth_header() -> String {
+    "Authorization".to_string()
+}
+
+impl std::fmt::Debug for HttpAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpAuth")
+            .field("header", &self.header)
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Request and response mapping for [`HttpEmbeddingProvider`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpEmbeddingConfig {
+    /// Endpoint URL template
+    pub url: String,
+    /// Values for `{name}` placeholders
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub auth: Option<HttpAuth>,
+    /// JSON body template containing `"{{texts}}"` or `"{{text}}"`
+    pub request: Value,
+    /// Dotted path to the vectors in the response body
+    pub response: String,
+    /// Size of the vectors the service returns
+    pub dimension: usize,
+    /// Texts per request when the body takes `"{{texts}}"`
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Request timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_batch_size() -> usize {
+    32
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl HttpEmbeddingConfig {
+    pub fn new(
+        url: impl Into<String>,
+        request: Value,
+        response: impl Into<String>,
+        dimension: usize,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            vars: BTreeMap::new(),
+            headers: BTreeMap::new(),
+            auth: None,
+            request,
+            response: response.into(),
+            dimension,
+            batch_size: default_batch_size(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    /// Parse a config file; `.json` files are read as JSON, anything else as YAML
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&text),
+            _ => Self::from_yaml(&text),
+        }
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        serde_yaml::from_str(text).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    pub fn with_var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_auth(mut self, header: impl Into<String>, value: impl Into<String>) -> Self {
+        self.auth = Some(HttpAuth {
+            header: header.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_secs = timeout.as_secs().max(1);
+        self
+    }
+
+    /// Replace `{name}` and `{env:NAME}` placeholders; `{{...}}` markers are left alone
+    fn expand(&self, template: &str) -> Result<String> {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start..];
+            if let Some(marker) = [TEXTS, TEXT].into_iter().find(|m| after.starts_with(m)) {
+                out.push_str(marker);
+                rest = &after[marker.len()..];
+                continue;
+            }
+            let end = after.find('}').ok_or_else(|| {
+                MemoryError::Configuration(format!("Unclosed placeholder in '{template}'"))
+            })?;
+            let name = &after[1..end];
+            let value = match name.strip_prefix("env:") {
+                Some(var) => std::env::var(var).map_err(|_| {
+                    MemoryError::Configuration(format!("Environment variable {var} is not set"))
+                })?,
+                None => self.vars.get(name).cloned().ok_or_else(|| {
+                    MemoryError::Configuration(format!("No value for placeholder {{{name}}}"))
+                })?,
+            };
+            out.push_str(&value);
+            rest = &after[end + 1..];
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn expand_json(&self, value: &Value) -> Result<Value> {
+        Ok(match value {
+            Value::String(s) => Value::String(self.expand(s)?),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|v| self.expand_json(v))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), self.expand_json(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+}
+
+/// Whether `value` contains the string `marker` anywhere
+fn contains_marker(value: &Value, marker: &str) -> bool {
+    match value {
+        Value::String(s) => s == marker,
+        Value::Array(items) => items.iter().any(|v| contains_marker(v, marker)),
+        Value::Object(map) => map.values().any(|v| contains_marker(v, marker)),
+        _ => false,
+    }
+}
+
+/// Copy of `template` with every `marker` string replaced by `replacement`
+fn fill(template: &Value, marker: &str, replacement: &Value) -> Value {
+    match template {
+        Value::String(s) if s == marker => replacement.clone(),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| fill(v, marker, replacement)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), fill(v, marker, replacement)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Every value `path` leads to in `value`
+fn select<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut current = vec![value];
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        current = current
+            .into_iter()
+            .flat_map(|v| -> Vec<&Value> {
+                match (segment, v) {
+                    ("*", Value::Array(items)) => items.iter().collect(),
+                    ("*", Value::Object(map)) => map.values().collect(),
+                    (_, Value::Array(items)) => segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| items.get(i))
+                        .into_iter()
+                        .collect(),
+                    (_, Value::Object(map)) => map.get(segment).into_iter().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+fn as_vector(value: &Value) -> Option<Vec<f32>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|n| n.as_f64().map(|f| f as f32))
+        .collect()
+}
+
+/// Embedding provider for arbitrary REST embedding services
+#[derive(Debug, Clone)]
+pub struct HttpEmbeddingProvider {
+    client: reqwest::Client,
+    config: HttpEmbeddingConfig,
+    url: String,
+    headers: Vec<(String, String)>,
+    request: Value,
+    batched: bool,
+}
+
+impl HttpEmbeddingProvider {
+    /// Resolve placeholders and check the mapping; fails on missing variables
+    /// or a request template without `"{{texts}}"` or `"{{text}}"`
+    pub fn new(config: HttpEmbeddingConfig) -> Result<Self> {
+        let request = config.expand_json(&config.request)?;
+        let batched = contains_marker(&request, TEXTS);
+        if !batched && !contains_marker(&request, TEXT) {
+            return Err(MemoryError::Configuration(format!(
+                "Request template must contain \"{TEXTS}\" or \"{TEXT}\""
+            )));
+        }
+
+        let url = config.expand(&config.url)?;
+        let mut headers = config
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), config.expand(value)?)))
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(auth) = &config.auth {
+            headers.push((auth.header.clone(), config.expand(&auth.value)?));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+
+        Ok(Self {
+            client,
+            config,
+            url,
+            headers,
+            request,
+            batched,
+        })
+    }
+
+    pub fn config(&self) -> &HttpEmbeddingConfig {
+        &self.config
+    }
+
+    async fn call(&self, body: &Value) -> Result<Value> {
+        let mut request = self.client.post(&self.url).json(body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Embedding request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Embedding service returned {status}: {text}"
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Invalid embedding response: {e}")))
+    }
+
+    /// Vectors `response` leads to in `body`, checked against the input count
+    fn extract(&self, body: &Value, inputs: usize) -> Result<Vec<Vec<f32>>> {
+        let mut found = select(body, &self.config.response);
+        if let [single] = found.as_slice() {
+            if single
+                .as_array()
+                .is_some_and(|a| a.iter().all(Value::is_array))
+            {
+                found = single.as_array().into_iter().flatten().collect();
+            }
+        }
+
+        let vectors = found
+            .into_iter()
+            .map(as_vector)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                MemoryError::EmbeddingFailed(format!(
+                    "Response path '{}' does not lead to numeric vectors",
+                    self.config.response
+                ))
+            })?;
+
+        if vectors.len() != inputs {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Embedding service returned {} vectors for {} inputs",
+                vectors.len(),
+                inputs
+            )));
+        }
+        if let Some(v) = vectors.iter().find(|v| v.len() != self.config.dimension) {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Embedding service returned {}-dim vectors, expected {}",
+                v.len(),
+                self.config.dimension
+            )));
+        }
+        Ok(vectors)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HttpEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.dimension
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        if self.batched {
+            for batch in texts.chunks(self.config.batch_size.max(1)) {
+                let body = fill(&self.request, TEXTS, &serde_json::json!(batch));
+                vectors.extend(self.extract(&self.call(&body).await?, batch.len())?);
+            }
+        } else {
+            for text in texts {
+                let body = fill(&self.request, TEXT, &Value::String(text.clone()));
+                vectors.extend(self.extract(&self.call(&body).await?, 1)?);
+            }
+        }
+        Ok(vectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn templates_expand_vars_and_keep_markers() {
+        let config = HttpEmbeddingConfig::from_yaml(
+            r#"
+url: "https://embed.local/models/{model}/embed"
+vars: { model: e5 }
+request: { inputs: "{{texts}}", model: "{model}" }
+response: "data.*.embedding"
+dimension: 2
+"#,
+        )
+        .unwrap();
+        let provider = HttpEmbeddingProvider::new(config).unwrap();
+        assert_eq!(provider.url, "https://embed.local/models/e5/embed");
+        assert!(provider.batched);
+
+        let body = fill(&provider.request, TEXTS, &json!(["a", "b"]));
+        assert_eq!(body, json!({ "inputs": ["a", "b"], "model": "e5" }));
+
+        let missing = HttpEmbeddingConfig::new("https://x/{nope}", json!("{{text}}"), "", 2);
+        assert!(HttpEmbeddingProvider::new(missing).is_err());
+        let no_marker = HttpEmbeddingConfig::new("https://x", json!({ "q": "hi" }), "", 2);
+        assert!(HttpEmbeddingProvider::new(no_marker).is_err());
+    }
+
+    #[test]
+    fn response_paths_select_one_vector_per_input() {
+        let provider = HttpEmbeddingProvider::new(HttpEmbeddingConfig::new(
+            "https://x",
+            json!({ "inputs": "{{texts}}" }),
+            "data.*.embedding",
+            2,
+        ))
+        .unwrap();
+        let body = json!({ "data": [{ "embedding": [1.0, 0.0] }, { "embedding": [0.0, 1.0] }] });
+        assert_eq!(
+            provider.extract(&body, 2).unwrap(),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]]
+        );
+        assert!(provider.extract(&body, 3).is_err());
+
+        let nested = HttpEmbeddingProvider::new(HttpEmbeddingConfig::new(
+            "https://x",
+            json!({ "inputs": "{{texts}}" }),
+            "result.vectors",
+            2,
+        ))
+        .unwrap();
+        let body = json!({ "result": { "vectors": [[1.0, 2.0], [3.0, 4.0]] } });
+        assert_eq!(nested.extract(&body, 2).unwrap().len(), 2);
+        assert!(nested.extract(&json!({ "result": {} }), 2).is_err());
+    }
+}
@@ -1,7 +1,9 @@
+use crate::deadline::{Deadline, TimedResults};
 use crate::embedding::EmbeddingProvider;
 use crate::error::{MemoryError, Result};
 use crate::types::{Memory, MemorySearchResult, MemoryType};
 use crate::vector_backend::VectorBackend;
+use crate::vector_chunks::{aggregate_chunk_hits, VectorChunk};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -18,6 +20,10 @@ pub struct HybridSearchConfig {
     pub weight_importance: f32,
     pub weight_recency: f32,
     pub weight_graph: f32,
+
+    /// Give up on the remaining stages after this long and rank what was found
+    #[serde(default)]
+    pub timeout: Option<std::time::Duration>,
 }
 
 impl Default for HybridSearchConfig {
@@ -32,6 +38,7 @@ impl Default for HybridSearchConfig {
             weight_importance: 0.1,
             weight_recency: 0.2,
             weight_graph: 0.15,
+            timeout: None,
         }
     }
 }
@@ -44,6 +51,9 @@ pub struct RetrievalExplanation {
     pub recency: f32,
     pub graph: f32,
     pub notes: Vec<String>,
+    /// Best-scoring span of a memory embedded in chunks
+    #[serde(default)]
+    pub chunk: Option<VectorChunk>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +68,7 @@ pub struct ExplainedSearchResult {
 struct ScoreParts {
     bm25_raw: Option<f32>,
     vector_raw: Option<f32>,
+    chunk: Option<VectorChunk>,
     graph_raw: f32,
 }
 
@@ -85,6 +96,14 @@ fn normalize_scores(values: &HashMap<String, f32>) -> HashMap<String, f32> {
         .collect()
 }
 
+/// A vector backend and the embedder its vectors were made with
+pub type VectorSource<'a> = (&'a Arc<dyn VectorBackend>, &'a Arc<dyn EmbeddingProvider>);
+
+/// Fuse BM25, vector and graph signals into one ranking.
+///
+/// Embedding, vector search, graph expansion and loading each run under
+/// `deadline`; once it expires the remaining work is skipped and the candidates
+/// found so far are ranked, with `timed_out` set.
 #[allow(clippy::too_many_arguments)]
 pub async fn hybrid_rank(
     query: &str,
@@ -110,10 +129,55 @@ pub async fn hybrid_rank(
         + Sync,
     cfg: &HybridSearchConfig,
     filter_type: Option<MemoryType>,
-) -> Result<Vec<ExplainedSearchResult>> {
+    deadline: &Deadline,
+) -> Result<TimedResults<ExplainedSearchResult>> {
+    let sources: Vec<VectorSource<'_>> = vector_backend.zip(embedder).into_iter().collect();
+    hybrid_rank_sources(
+        query,
+        bm25_results,
+        &sources,
+        load_memory,
+        get_neighbors,
+        cfg,
+        filter_type,
+        deadline,
+    )
+    .await
+}
+
+/// [`hybrid_rank`] over several vector sources, e.g. one per memory type.
+/// A memory found in more than one keeps its best vector score.
+#[allow(clippy::too_many_arguments)]
+pub async fn hybrid_rank_sources(
+    query: &str,
+    bm25_results: Vec<MemorySearchResult>,
+    vector_sources: &[VectorSource<'_>],
+    load_memory: impl Fn(
+            &str,
+        )
+            -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<Memory>>> + Send>>
+        + Send
+        + Sync,
+    get_neighbors: impl Fn(
+            &str,
+            u32,
+        ) -> std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                        Output = Result<(Vec<Memory>, Vec<crate::types::Association>)>,
+                    > + Send,
+            >,
+        > + Send
+        + Sync,
+    cfg: &HybridSearchConfig,
+    filter_type: Option<MemoryType>,
+    deadline: &Deadline,
+) -> Result<TimedResults<ExplainedSearchResult>> {
     let mut parts: HashMap<String, ScoreParts> = HashMap::new();
+    let mut timed_out = false;
 
     let mut bm25_map: HashMap<String, f32> = HashMap::new();
+    let mut bm25_memories: HashMap<String, Memory> = HashMap::new();
     for r in bm25_results.into_iter().take(cfg.bm25_limit) {
         if r.memory.forgotten {
             continue;
@@ -125,22 +189,37 @@ pub async fn hybrid_rank(
         }
         bm25_map.insert(r.memory.id.clone(), r.score);
         parts.entry(r.memory.id.clone()).or_default().bm25_raw = Some(r.score);
+        bm25_memories.insert(r.memory.id.clone(), r.memory);
     }
 
     let mut vector_map: HashMap<String, f32> = HashMap::new();
-    if let (Some(vb), Some(emb)) = (vector_backend, embedder) {
-        let embedded = emb
-            .embed(&[query.to_string()])
-            .await
-            .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
-        let vec = embedded.first().ok_or_else(|| {
-            MemoryError::VectorDb("Embedding provider returned no vectors".into())
-        })?;
-
-        let hits = vb.search(vec, cfg.vector_limit).await?;
-        for h in hits {
-            vector_map.insert(h.id.clone(), h.score);
-            parts.entry(h.id).or_default().vector_raw = Some(h.score);
+    for &(vb, emb) in vector_sources {
+        if timed_out {
+            break;
+        }
+        let search = async {
+            let embedded = emb
+                .embed(&[query.to_string()])
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+            let vec = embedded.first().ok_or_else(|| {
+                MemoryError::VectorDb("Embedding provider returned no vectors".into())
+            })?;
+            vb.search(vec, cfg.vector_limit).await
+        };
+        match deadline.run(search).await {
+            Some(hits) => {
+                for h in aggregate_chunk_hits(hits?) {
+                    if vector_map.get(&h.memory_id).is_some_and(|&s| s >= h.score) {
+                        continue;
+                    }
+                    vector_map.insert(h.memory_id.clone(), h.score);
+                    let entry = parts.entry(h.memory_id).or_default();
+                    entry.vector_raw = Some(h.score);
+                    entry.chunk = h.chunk;
+                }
+            }
+            None => timed_out = true,
         }
     }
 
@@ -156,7 +235,17 @@ pub async fn hybrid_rank(
 
     let mut expanded: HashSet<String> = HashSet::new();
     for (seed_id, seed_score) in seed_ids.into_iter().take(10) {
-        let (neighbors, assocs) = get_neighbors(&seed_id, cfg.neighbor_depth).await?;
+        if timed_out {
+            break;
+        }
+        let Some(neighbors) = deadline
+            .run(get_neighbors(&seed_id, cfg.neighbor_depth))
+            .await
+        else {
+            timed_out = true;
+            break;
+        };
+        let (neighbors, assocs) = neighbors?;
 
         // Map target ids to relation multipliers.
         let mut rel_mult: HashMap<String, f32> = HashMap::new();
@@ -196,8 +285,19 @@ pub async fn hybrid_rank(
 
     let mut scored: Vec<(ExplainedSearchResult, f32)> = Vec::new();
     for (id, p) in parts {
-        let Some(memory) = load_memory(&id).await? else {
-            continue;
+        let memory = match bm25_memories.remove(&id) {
+            Some(memory) => memory,
+            None if timed_out => continue,
+            None => match deadline.run(load_memory(&id)).await {
+                Some(memory) => match memory? {
+                    Some(memory) => memory,
+                    None => continue,
+                },
+                None => {
+                    timed_out = true;
+                    continue;
+                }
+            },
         };
         if memory.forgotten {
             continue;
@@ -222,6 +322,7 @@ pub async fn hybrid_rank(
             recency,
             graph,
             notes: Vec::new(),
+            chunk: p.chunk.filter(|_| vector.is_some()),
         };
 
         if bm25.is_some() {
@@ -229,10 +330,15 @@ pub async fn hybrid_rank(
                 .notes
                 .push("Matched BM25 full-text search".to_string());
         }
-        if vector.is_some() {
-            explanation
+        match (vector, explanation.chunk) {
+            (Some(_), Some(chunk)) => explanation.notes.push(format!(
+                "Matched semantic vector search in chunk {} (bytes {}..{})",
+                chunk.index, chunk.start, chunk.end
+            )),
+            (Some(_), None) => explanation
                 .notes
-                .push("Matched semantic vector search".to_string());
+                .push("Matched semantic vector search".to_string()),
+            _ => {}
         }
         if graph > 0.0 {
             explanation
@@ -264,5 +370,8 @@ pub async fn hybrid_rank(
         r.rank = i + 1;
     }
 
-    Ok(scored.into_iter().map(|(r, _)| r).collect())
+    Ok(TimedResults {
+        results: scored.into_iter().map(|(r, _)| r).collect(),
+        timed_out,
+    })
 }
@@ -0,0 +1,380 @@
+//! Memory hygiene report
+//!
+//! `MemorySystem::hygiene_report` scans the store for problems that slowly
+//! degrade recall and suggests a fix for each:
+//! - **duplicate clusters**: memories quality scoring flagged as near-duplicates → merge,
+//! - **contradictions**: active memories linked by `Contradicts` → resolve,
+//! - **never accessed**: old memories nothing has ever recalled → forget,
+//! - **huge memories**: walls of text that dilute search → split,
+//! - **dangling associations**: edges to missing or forgotten memories → delete,
+//! - **low-confidence beliefs** that are pinned, important or often recalled → verify.
+//!
+//! The same report is printed by `goldfish doctor --report`.
+
+use crate::error::{MemoryError, Result};
+use crate::store::MemoryStore;
+use crate::types::{Memory, MemoryId};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Thresholds for [`hygiene_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HygieneConfig {
+    /// Memories never accessed for this many days are reported
+    pub stale_after_days: i64,
+    /// Memories longer than this many characters are reported
+    pub huge_memory_chars: usize,
+    /// Confidence below which a surfaced belief is reported
+    pub low_confidence: f32,
+    /// Importance at which a memory counts as surfaced in contexts
+    pub surfaced_importance: f32,
+    /// Access count at which a memory counts as surfaced in contexts
+    pub surfaced_accesses: i64,
+    /// Maximum issues reported per kind
+    pub max_per_kind: usize,
+}
+
+impl Default for HygieneConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_days: 90,
+            huge_memory_chars: 4000,
+            low_confidence: 0.4,
+            surfaced_importance: 0.7,
+            surfaced_accesses: 3,
+            max_per_kind: 50,
+        }
+    }
+}
+
+/// Kind of hygiene problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HygieneIssueKind {
+    DuplicateCluster,
+    Contradiction,
+    NeverAccessed,
+    HugeMemory,
+    DanglingAssociation,
+    LowConfidenceSurfaced,
+}
+
+impl std::fmt::Display for HygieneIssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::DuplicateCluster => "duplicate clusters",
+            Self::Contradiction => "contradictions",
+            Self::NeverAccessed => "never-accessed old memories",
+            Self::HugeMemory => "huge memories",
+            Self::DanglingAssociation => "dangling associations",
+            Self::LowConfidenceSurfaced => "low-confidence surfaced beliefs",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Suggested fix for a hygiene issue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "target")]
+pub enum HygieneAction {
+    /// Fold the other memories into this one
+    Merge(MemoryId),
+    /// Decide which side of a contradiction holds and forget or update the other
+    Resolve,
+    /// Forget (soft delete) the memory
+    Forget,
+    /// Split the memory into smaller ones
+    Split,
+    /// Delete the association with this ID
+    DeleteAssociation(String),
+    /// Check the belief against a reliable source
+    Verify,
+}
+
+impl std::fmt::Display for HygieneAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Merge(target) => write!(f, "merge into {target}"),
+            Self::Resolve => f.write_str("resolve"),
+            Self::Forget => f.write_str("forget"),
+            Self::Split => f.write_str("split"),
+            Self::DeleteAssociation(id) => write!(f, "delete association {id}"),
+            Self::Verify => f.write_str("verify"),
+        }
+    }
+}
+
+/// One problem found by the hygiene scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HygieneIssue {
+    pub kind: HygieneIssueKind,
+    pub memory_ids: Vec<MemoryId>,
+    pub detail: String,
+    pub suggested: HygieneAction,
+}
+
+/// Result of [`hygiene_report`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HygieneReport {
+    pub generated_at: DateTime<Utc>,
+    pub issues: Vec<HygieneIssue>,
+}
+
+impl HygieneReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues per kind
+    pub fn counts(&self) -> BTreeMap<HygieneIssueKind, usize> {
+        let mut counts = BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.kind).or_default() += 1;
+        }
+        counts
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    /// The report grouped by kind, one line per issue with its suggested action
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if self.is_clean() {
+            out.push_str("No hygiene issues found\n");
+            return out;
+        }
+        for (kind, count) in self.counts() {
+            let _ = writeln!(out, "{kind} ({count}):");
+            for issue in self.issues.iter().filter(|i| i.kind == kind) {
+                let _ = writeln!(out, "  - {} -> {}", issue.detail, issue.suggested);
+            }
+        }
+        out
+    }
+}
+
+/// Scan `store` for hygiene problems
+pub async fn hygiene_report(store: &MemoryStore, config: &HygieneConfig) -> Result<HygieneReport> {
+    let limit = config.max_per_kind as i64;
+    let now = store.now();
+    let mut issues = Vec::new();
+
+    for cluster in duplicate_clusters(store.duplicate_links().await?)
+        .into_iter()
+        .take(config.max_per_kind)
+    {
+        issues.push(HygieneIssue {
+            kind: HygieneIssueKind::DuplicateCluster,
+            detail: format!("{} near-identical memories", cluster.len()),
+            suggested: HygieneAction::Merge(cluster[0].clone()),
+            memory_ids: cluster,
+        });
+    }
+
+    for association in store.active_contradictions(limit).await? {
+        issues.push(HygieneIssue {
+            kind: HygieneIssueKind::Contradiction,
+            detail: format!(
+                "{} contradicts {}",
+                association.source_id, association.target_id
+            ),
+            memory_ids: vec![association.source_id, association.target_id],
+            suggested: HygieneAction::Resolve,
+        });
+    }
+
+    let stale_before = now - Duration::days(config.stale_after_days);
+    for memory in store.never_accessed_before(stale_before, limit).await? {
+        let age = (now - memory.created_at).num_days();
+        issues.push(memory_issue(
+            HygieneIssueKind::NeverAccessed,
+            &memory,
+            format!("never accessed in {age} days"),
+            HygieneAction::Forget,
+        ));
+    }
+
+    for memory in store.oversized(config.huge_memory_chars, limit).await? {
+        let chars = memory.content.chars().count();
+        issues.push(memory_issue(
+            HygieneIssueKind::HugeMemory,
+            &memory,
+            format!("{chars} characters"),
+            HygieneAction::Split,
+        ));
+    }
+
+    for association in store.dangling_associations(limit).await? {
+        issues.push(HygieneIssue {
+            kind: HygieneIssueKind::DanglingAssociation,
+            detail: format!(
+                "{} {} {} points at a missing or forgotten memory",
+                association.source_id, association.relation_type, association.target_id
+            ),
+            memory_ids: vec![association.source_id, association.target_id],
+            suggested: HygieneAction::DeleteAssociation(association.id),
+        });
+    }
+
+    for memory in store
+        .low_confidence_surfaced(
+            config.low_confidence,
+            config.surfaced_importance,
+            config.surfaced_accesses,
+            limit,
+        )
+        .await?
+    {
+        let confidence = memory.confidence.score;
+        issues.push(memory_issue(
+            HygieneIssueKind::LowConfidenceSurfaced,
+            &memory,
+            format!("confidence {confidence:.2} but surfaced in contexts"),
+            HygieneAction::Verify,
+        ));
+    }
+
+    Ok(HygieneReport {
+        generated_at: now,
+        issues,
+    })
+}
+
+fn memory_issue(
+    kind: HygieneIssueKind,
+    memory: &Memory,
+    why: String,
+    suggested: HygieneAction,
+) -> HygieneIssue {
+    HygieneIssue {
+        kind,
+        memory_ids: vec![memory.id.clone()],
+        detail: format!("{}: {why}", preview(&memory.content)),
+        suggested,
+    }
+}
+
+fn preview(content: &str) -> String {
+    const MAX: usize = 60;
+    let mut chars = content.chars();
+    let head: String = chars.by_ref().take(MAX).collect();
+    if chars.next().is_some() {
+        format!("\"{head}...\"")
+    } else {
+        format!("\"{head}\"")
+    }
+}
+
+/// Group `(memory, duplicate_of)` links into clusters, each led by the memory
+/// the others duplicate
+fn duplicate_clusters(links: Vec<(MemoryId, MemoryId)>) -> Vec<Vec<MemoryId>> {
+    let parent: HashMap<MemoryId, MemoryId> = links.into_iter().collect();
+    let root_of = |id: &MemoryId| {
+        let mut current = id;
+        // Bounded walk: a chain never needs more steps than there are links
+        for _ in 0..parent.len() {
+            match parent.get(current) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        current.clone()
+    };
+
+    let mut clusters: BTreeMap<MemoryId, Vec<MemoryId>> = BTreeMap::new();
+    for id in parent.keys() {
+        let root = root_of(id);
+        let cluster = clusters.entry(root.clone()).or_insert_with(|| vec![root]);
+        if !cluster.contains(id) {
+            cluster.push(id.clone());
+        }
+    }
+    let mut clusters: Vec<Vec<MemoryId>> = clusters.into_values().collect();
+    for cluster in &mut clusters {
+        cluster[1..].sort();
+    }
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HygieneAction, HygieneIssueKind};
+    use crate::{Memory, MemorySystem, MemoryType, MockClock, RelationType};
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn report_flags_each_kind_of_problem() {
+        let dir = tempfile::tempdir().unwrap();
+        let clock = Arc::new(MockClock::default());
+        let memory_system = MemorySystem::new(dir.path())
+            .await
+            .unwrap()
+            .with_clock(clock.clone());
+
+        let old = memory_system.new_memory("Office wifi password rotated", MemoryType::Fact);
+        memory_system.save(&old).await.unwrap();
+        clock.advance(Duration::days(120));
+
+        let deploy = memory_system.new_memory(
+            "Deploys to production run every Tuesday at 14:00 UTC",
+            MemoryType::Fact,
+        );
+        let dup = memory_system.new_memory(
+            "Deploys to production run every Tuesday at 14:00 UTC.",
+            MemoryType::Fact,
+        );
+        let friday =
+            memory_system.new_memory("Deploys to production run on Fridays", MemoryType::Fact);
+        let huge = memory_system.new_memory("log line ".repeat(600), MemoryType::Observation);
+        let gone = memory_system.new_memory("Temporary note about the release", MemoryType::Fact);
+        for memory in [&deploy, &dup, &friday, &huge, &gone] {
+            memory_system.save(memory).await.unwrap();
+        }
+        memory_system
+            .associate(&friday.id, &deploy.id, RelationType::Contradicts)
+            .await
+            .unwrap();
+        memory_system
+            .associate(&deploy.id, &gone.id, RelationType::RelatedTo)
+            .await
+            .unwrap();
+        memory_system.forget(&gone.id).await.unwrap();
+
+        let mut shaky = Memory::new("The CEO prefers tabs", MemoryType::Preference);
+        shaky.confidence.score = 0.1;
+        shaky.pinned = true;
+        memory_system.save(&shaky).await.unwrap();
+
+        let report = memory_system.hygiene_report().await.unwrap();
+        let of = |kind| {
+            report
+                .issues
+                .iter()
+                .filter(|i| i.kind == kind)
+                .collect::<Vec<_>>()
+        };
+
+        let duplicates = of(HygieneIssueKind::DuplicateCluster);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates[0].suggested,
+            HygieneAction::Merge(deploy.id.clone())
+        );
+        assert!(duplicates[0].memory_ids.contains(&dup.id));
+        assert_eq!(of(HygieneIssueKind::Contradiction).len(), 1);
+        assert_eq!(of(HygieneIssueKind::NeverAccessed)[0].memory_ids, [old.id]);
+        assert_eq!(of(HygieneIssueKind::HugeMemory)[0].memory_ids, [huge.id]);
+        assert_eq!(of(HygieneIssueKind::DanglingAssociation).len(), 1);
+        assert_eq!(
+            of(HygieneIssueKind::LowConfidenceSurfaced)[0].memory_ids,
+            [shaky.id]
+        );
+        assert!(report.to_text().contains("huge memories (1):"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Language of memories
+//!
+//! Every memory records the language it was written in as a lowercase
+//! ISO 639-1 code (`"en"`, `"de"`, `"ja"`). Unless the caller sets
+//! [`Memory::language`](crate::Memory::language) explicitly, it is detected on
+//! save by [`detect_language`]: the script for non-Latin text, common function
+//! words for Latin text. Detection is a cheap heuristic and gives up
+//! (`None`) on short or ambiguous text rather than guess.
+//!
+//! The language can be used as a search filter
+//! ([`SearchConfig::language`](crate::SearchConfig::language)), and a
+//! [`Translator`] attached to a [`MemoryCortex`](crate::MemoryCortex) lets a
+//! [`ContextWindow`](crate::ContextWindow) with a target language translate
+//! memories into the conversation language before they are included:
+//!
+//! ```rust,ignore
+//! let cortex = cortex.with_translator(Arc::new(MyTranslator::new()));
+//! let context = ContextWindow::new(2000).with_language("de").build(&cortex).await?;
+//! ```
+
+use crate::error::Result;
+use crate::types::Memory;
+use async_trait::async_trait;
+
+/// Latin-script languages told apart by their most common words
+const FUNCTION_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "with", "for", "was", "on",
+            "this", "be", "have", "not", "you", "my", "at",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "ein", "eine", "mit", "auf", "für",
+            "sich", "dem", "den", "zu", "es", "auch", "wir", "sind",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "de", "que", "en", "un", "una", "por", "con",
+            "para", "no", "se", "del", "está", "pero", "muy",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "de", "des", "un", "une", "que", "en", "dans", "pour",
+            "pas", "je", "il", "sur", "avec", "du", "au",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "le", "e", "è", "di", "che", "un", "una", "per", "non", "con", "sono",
+            "del", "della", "gli", "ma", "anche", "nel", "questo",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "e", "é", "de", "que", "um", "uma", "para", "não", "com", "do",
+            "da", "em", "no", "na", "mas", "está",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "dat", "niet", "ik", "met", "op", "voor",
+            "zijn", "er", "te", "ook", "maar", "wij", "naar", "wordt",
+        ],
+    ),
+];
+
+/// Function-word hits needed before a Latin-script guess is made
+const MIN_WORD_HITS: usize = 2;
+
+/// Translates memory content for context windows
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// Name for logs
+    fn name(&self) -> &str;
+
+    /// Translate `text` from `from` (when known) into `to`, both ISO 639-1 codes
+    async fn translate(&self, text: &str, from: Option<&str>, to: &str) -> Result<String>;
+}
+
+/// Lowercase primary subtag of a language tag: `"en-US"` and `"EN"` become `"en"`
+pub fn normalize_language(tag: &str) -> String {
+    tag.trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Best guess at the ISO 639-1 language of `text`, `None` when unsure
+pub fn detect_language(text: &str) -> Option<String> {
+    detect_script(text).or_else(|| detect_latin(text))
+}
+
+/// The memory's language, detected from its content when not set
+pub(crate) fn memory_language(memory: &Memory) -> Option<String> {
+    match &memory.language {
+        Some(language) => Some(normalize_language(language)),
+        None => detect_language(&memory.content),
+    }
+}
+
+/// Languages with a script of their own, by majority of letters
+fn detect_script(text: &str) -> Option<String> {
+    let (mut letters, mut kana, mut han, mut hangul) = (0usize, 0usize, 0usize, 0usize);
+    let mut other: Vec<(&str, usize)> = Vec::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let language = match c as u32 {
+            0x3040..=0x30FF => {
+                kana += 1;
+                continue;
+            }
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => {
+                han += 1;
+                continue;
+            }
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => {
+                hangul += 1;
+                continue;
+            }
+            0x0400..=0x04FF => "ru",
+            0x0600..=0x06FF => "ar",
+            0x0590..=0x05FF => "he",
+            0x0900..=0x097F => "hi",
+            0x0370..=0x03FF => "el",
+            0x0E00..=0x0E7F => "th",
+            _ => continue,
+        };
+        match other.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, n)) => *n += 1,
+            None => other.push((language, 1)),
+        }
+    }
+    if letters == 0 {
+        return None;
+    }
+
+    // Japanese mixes kanji with kana; Chinese has no kana
+    let cjk = if kana > 0 {
+        Some(("ja", kana + han))
+    } else if han > 0 {
+        Some(("zh", han))
+    } else if hangul > 0 {
+        Some(("ko", hangul))
+    } else {
+        None
+    };
+    cjk.into_iter()
+        .chain(other)
+        .max_by_key(|(_, n)| *n)
+        .filter(|(_, n)| n * 2 > letters)
+        .map(|(language, _)| language.to_string())
+}
+
+/// Latin-script languages by function-word hits, if one clearly leads
+fn detect_latin(text: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut scores: Vec<(&str, usize)> = FUNCTION_WORDS
+        .iter()
+        .map(|(language, common)| {
+            let hits = words.iter().filter(|w| common.contains(w)).count();
+            (*language, hits)
+        })
+        .collect();
+    scores.sort_by_key(|s| std::cmp::Reverse(s.1));
+
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= MIN_WORD_HITS && best > second => {
+            Some(language.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_languages() {
+        let cases = [
+            ("The deploy is on Tuesday and it is not optional", "en"),
+            ("Der Kunde ist mit der Lieferung nicht zufrieden", "de"),
+            ("El cliente prefiere que la reunión sea por la tarde", "es"),
+            ("Le client préfère les réunions dans la matinée", "fr"),
+            ("Пользователь предпочитает тёмную тему", "ru"),
+            ("ユーザーはダークモードを好みます", "ja"),
+            ("用户喜欢深色模式", "zh"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text).as_deref(), Some(expected), "{text}");
+        }
+        assert_eq!(detect_language("OK"), None);
+        assert_eq!(detect_language("1234 5678"), None);
+        assert_eq!(normalize_language("pt_BR"), "pt");
+    }
+}
@@ -1,67 +1,230 @@
 //! # Goldfish - Agentic Memory Cortex for AI Agents
 
-pub mod cache;
+pub mod analytics;
+pub mod anonymize;
+pub mod archive;
+pub mod backup;
 pub mod benchmark_suites;
+pub mod cache;
+#[cfg(feature = "chat-connectors")]
+pub mod chat_connector;
+pub mod classify;
+pub mod clock;
 pub mod confidence;
+pub mod connectors;
+pub mod context_trace;
 pub mod cortex;
+pub mod cortex_manager;
+pub mod deadline;
+pub mod decisions;
+#[cfg(feature = "imap")]
+pub mod email_connector;
 pub mod embedding;
+pub mod embedding_migration;
+pub mod embedding_queue;
+pub mod encryption;
 pub mod error;
 pub mod eval_harness;
+pub mod experiments;
+pub mod exporters;
+pub mod generation;
+#[cfg(feature = "http-embeddings")]
+pub mod http_embedding;
 pub mod hybrid_retrieval;
+pub mod hygiene;
+pub mod injection;
+pub mod language;
 pub mod maintenance;
+pub mod manifest;
+pub mod memory_query;
+pub mod middleware;
+pub mod migration_hook;
+pub mod numeric_range;
+#[cfg(feature = "ollama")]
+pub mod ollama;
+#[cfg(feature = "openai")]
+pub mod openai;
+pub mod platform_fs;
+pub mod profile;
+pub mod provenance;
 pub mod pulses;
+pub mod quality;
+pub mod query_expansion;
+pub mod query_log;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod review;
+pub mod sandbox;
+pub mod saved_search;
 pub mod search;
+pub mod session;
+pub mod shard;
+pub mod sources;
 pub mod storage_backend;
 pub mod store;
+pub mod store_generation;
 pub mod synthesis;
+pub mod tagging;
 pub mod temporal;
+pub mod testing;
+pub mod timeline;
 pub mod types;
+pub mod validation;
 pub mod vector_backend;
+pub mod vector_chunks;
+pub mod vector_migration;
 pub mod vector_search;
 pub mod versioning;
 
+pub use analytics::{
+    extract_entities, ActivityBucket, AnalyticsReport, EntityCount, MemoryAnalytics, TagTrend,
+    TimeBucket, TrendDirection, TypeDistributionPoint, VelocityPoint,
+};
+pub use anonymize::Anonymizer;
+pub use archive::{ArchiveHeader, ArchiveRecord, ArchiveReport, ImportReport, ARCHIVE_VERSION};
+pub use backup::{BackupReport, RestoreReport};
+pub use benchmark_suites::{
+    aggregate_metrics, evaluate_query, failed_gates, regressions, BenchmarkMemory, BenchmarkQuery,
+    BenchmarkReport, MetricGate, QueryMetrics, RetrievalMetrics,
+};
 pub use cache::{
     CacheConfig, CacheConfigBuilder, CacheKey, CacheManager, CacheStats, CachedMemoryOperations,
     L1Cache,
 };
-pub use benchmark_suites::{
-    aggregate_metrics, evaluate_query, BenchmarkQuery, BenchmarkReport, QueryMetrics,
-    RetrievalMetrics,
+#[cfg(feature = "chat-connectors")]
+pub use chat_connector::{
+    parse_discord_messages, parse_slack_messages, ChannelCursor, ChatCheckpoint, ChatConnector,
+    ChatMessage, ChatPlatform,
 };
+pub use classify::{
+    Classification, ClassificationRule, EmbeddingClassifier, MemoryClassifier, RuleBasedClassifier,
+};
+pub use clock::{Clock, IdGenerator, MockClock, SequentialIds, SystemClock, UuidIds};
 pub use confidence::{
     ConfidenceConfig, ConfidenceFactors, ConfidenceTier, MemoryConfidence, SourceReliability,
     VerificationStatus,
 };
+pub use connectors::{
+    parse_feed, Connector, ConnectorBatch, ConnectorEpisode, ConnectorHandle, ConnectorRun,
+    ConnectorScheduler, FeedConnector, FeedEntry, JsonHttpConnector,
+};
+pub use context_trace::{context_hash, ContextTrace, TracedMemory};
 pub use cortex::{
-    ContextWindow, Experience, ImportanceCalculator, ImportanceWeights, MemoryCortex,
-    RecallWeights,
-    MemorySummary, WorkingMemory, WorkingMemoryItem,
+    BatchRecall, ContextSection, ContextWindow, CortexConfig, EpisodeMood, EpisodeTree, Experience,
+    HandoffBundle, HandoffImport, ImportanceCalculator, ImportanceWeights, MemoryCortex,
+    MemorySummary, MoodSummary, PoolSelection, QueryRecall, RecallWeights, SharedPool, StalePin,
+    TypeSelection, WorkingMemory, WorkingMemoryItem, WorkingMemoryPulseConfig,
+    HANDOFF_FORMAT_VERSION,
 };
-pub use embedding::{EmbeddingProvider, HashEmbeddingProvider};
+pub use cortex_manager::{CortexManager, CortexManagerConfig, FleetMaintenanceReport, FleetStats};
+pub use deadline::{Deadline, TimedResults};
+pub use decisions::{DecisionOutcome, DecisionRecord, DECISION_METADATA_KEY};
+#[cfg(feature = "imap")]
+pub use email_connector::{parse_email, Email, FolderCursor, ImapCheckpoint, ImapConnector};
+pub use embedding::{
+    EmbeddingNormalization, EmbeddingProvider, HashEmbeddingConfig, HashEmbeddingProvider,
+};
+pub use embedding_migration::{
+    EmbeddingMigrationConfig, EmbeddingMigrationProgress, EmbeddingMigrationReport,
+};
+pub use embedding_queue::{EmbeddingQueue, EmbeddingQueueConfig};
+pub use encryption::EncryptionKey;
 pub use error::{MemoryError, Result};
 pub use eval_harness::{
-    print_results, run_standard_eval, BenchmarkResults, EvalHarness, RetrievalTestCase,
+    compare_embedding_recall, embedding_recall_at_k, print_results, run_embedding_eval,
+    run_search_replica_eval, run_standard_eval, search_qps, standard_embedding_suite,
+    BenchmarkResults, EmbeddingRecall, EmbeddingRecallComparison, EmbeddingTestCase, EvalHarness,
+    RetrievalTestCase, SearchQps,
+};
+pub use experiments::{ExperimentRouter, ExperimentSearch, ExperimentVariant, VariantMetrics};
+pub use exporters::{
+    export_documents, markdown_file_name, render_markdown, ExportBatch, ExportDocument,
+    ExportHandle, ExportLink, ExportRun, ExportScheduler, Exporter, MarkdownExporter,
+    NotionExporter, DEFAULT_EXPORT_TYPES, NOTION_API_VERSION,
+};
+pub use generation::{GenerationOptions, TextGenerator, TextStream};
+#[cfg(feature = "http-embeddings")]
+pub use http_embedding::{HttpAuth, HttpEmbeddingConfig, HttpEmbeddingProvider};
+pub use hybrid_retrieval::{
+    ExplainedSearchResult, HybridSearchConfig, RetrievalExplanation, VectorSource,
 };
-pub use hybrid_retrieval::{ExplainedSearchResult, HybridSearchConfig, RetrievalExplanation};
+pub use hygiene::{HygieneAction, HygieneConfig, HygieneIssue, HygieneIssueKind, HygieneReport};
+pub use injection::{
+    InjectionClassifier, InjectionScreen, ScreenAction, ScreenVerdict, Screened, FILTERED_MARKER,
+    INJECTION_TAG,
+};
+pub use language::{detect_language, normalize_language, Translator};
 pub use maintenance::{
-    run_maintenance, MaintenanceConfig, MaintenanceConfigBuilder, MaintenanceReport,
+    apply_importance_budget, enforce_quota, memory_bytes, run_maintenance, run_maintenance_with,
+    Eviction, EvictionPolicy, ImportanceAdjustment, ImportanceBudget, ImportanceBudgetReport,
+    MaintenanceConfig, MaintenanceConfigBuilder, MaintenanceReport, PurgeCleanup, QuotaReport,
+    StoreQuota, THROTTLE_BATCH,
 };
+pub use manifest::{DataDirManifest, EmbeddingInfo, UpgradeRecord, MANIFEST_FILE};
+pub use memory_query::MemoryQuery;
+pub use middleware::{MemoryMiddleware, MiddlewareChain, SaveDecision};
+pub use migration_hook::MigrationHook;
+pub use numeric_range::{extract_numeric_ranges, NumericRange};
+#[cfg(feature = "ollama")]
+pub use ollama::{OllamaConfig, OllamaEmbeddingProvider, OllamaGenerator, OLLAMA_DEFAULT_BATCH};
+#[cfg(feature = "openai")]
+pub use openai::{OpenAiConfig, OpenAiEmbeddingProvider, OPENAI_MAX_BATCH};
+pub use profile::ResourceProfile;
+pub use provenance::{BeliefExplanation, ProvenanceNode, DEFAULT_PROVENANCE_DEPTH};
 pub use pulses::{
-    pulse, ChangeType, GoldfishPulses, Pulse, PulseConfig, PulseFilter, PulseStats, PulseType,
+    pulse, AttentionItem, ChangeType, GoldfishPulses, Pulse, PulseConfig, PulseFilter, PulseStats,
+    PulseType,
+};
+pub use quality::{CurationAction, CurationItem, QualityScore, QualityScorer, QualityWeights};
+pub use query_expansion::{ExpandedQuery, ExpansionSource, ExpansionTerm, QueryExpansion};
+pub use query_log::{QueryLogConfig, QueryLogEntry, QueryLogger, QueryStats};
+#[cfg(feature = "remote")]
+pub use remote::{RemoteClient, RemoteMemory};
+pub use review::{ReviewItem, ReviewPolicy, ReviewStatus};
+pub use sandbox::{MemorySandbox, SandboxChange, SandboxCommitError};
+pub use saved_search::{SavedSearch, SavedSearchHit};
+pub use search::{
+    MemorySearch, SearchConfig, SearchFallback, SearchMode, SearchOutcome, SearchSort, SearcherPool,
 };
-pub use search::{MemorySearch, SearchConfig, SearchMode, SearchSort};
+pub use session::{Session, SessionInfo};
+pub use shard::{ShardSearchResult, ShardedMemorySystem, SHARDS_DIR};
+pub use sources::{content_hash, split_sections, SourceDocument, SourceSync};
+#[cfg(feature = "libsql")]
+pub use storage_backend::LibsqlStorageBackend;
+#[cfg(feature = "postgres")]
+pub use storage_backend::PostgresStorageBackend;
 pub use storage_backend::StorageBackend;
-pub use store::{MemoryStore, SortOrder};
+pub use store::{
+    AccessBucket, CitedMemory, DayCount, MemoryPage, MemoryStore, NamespaceStats, SampleFilter,
+    SortOrder, SqliteJournalMode, SqliteSynchronous, SqliteTuning, StoreAnalytics,
+    StoreTransaction, TypeStats, DEFAULT_NAMESPACE,
+};
+pub use store_generation::{Generational, StoreGeneration};
 pub use synthesis::{Insight, InsightType, SynthesisConfig, SynthesisEngine};
+pub use tagging::{extract_keywords, AutoTagConfig, AutoTagger, CorpusStats, KeywordMethod, Topic};
 pub use temporal::{
-    Episode, TemporalConfig, TemporalMode, TemporalPreset, TemporalQuery, TemporalSearchResult,
+    Episode, Frequency, Recurrence, RecurrenceDetector, RecurringPattern, TemporalConfig,
+    TemporalMode, TemporalPreset, TemporalQuery, TemporalSearchResult, RECURRENCE_METADATA_KEY,
+    RECURRING_TAG,
 };
+pub use testing::{assert_recall, assert_recall_within, load_fixture, Fixture, LoadedFixture};
+pub use timeline::{MemoryTimeline, TimelineEvent, TimelineEventKind};
 pub use types::{
-    Association, CreateAssociationInput, CreateMemoryInput, Memory, MemoryId, MemorySearchResult,
-    MemoryType, RelationType, SessionId,
+    Association, BatchSaveFailure, BatchSaveReport, CreateAssociationInput, CreateMemoryInput,
+    Memory, MemoryId, MemorySearchResult, MemoryType, RelationType, SessionId,
+};
+pub use validation::{split_content, ContentValidation, OversizeBehavior};
+pub use vector_backend::{
+    vector_payload, AnnParams, FileVectorBackend, InMemoryVectorBackend, VectorBackend,
+    VectorCollection, VectorFilter, VectorIndexStats, VectorMaintenanceReport, VectorSearchHit,
+    DEFAULT_COLLECTION,
 };
-pub use vector_backend::{VectorBackend, VectorSearchHit};
-pub use vector_search::{generate_embedding, VectorIndex, VectorSearchConfig};
+pub use vector_chunks::{
+    aggregate_chunk_hits, chunk_spans, chunk_vector_id, split_vector_id, ChunkingConfig,
+    MemoryVectorHit, VectorChunk,
+};
+pub use vector_migration::{DualWriteVectorBackend, ParityReport, VectorBackfillReport};
+pub use vector_search::{generate_embedding, VectorIndex, VectorQuantization, VectorSearchConfig};
 pub use versioning::{
     ChangeType as VersionChangeType, ConflictResolution, FieldChange, FieldChangeKind,
     MemoryBranch, MemoryDiff, MemoryVersion, StorageMode, VersionAuthor, VersionConflict,
@@ -69,26 +232,142 @@ pub use versioning::{
     VersioningStats,
 };
 
-use sqlx::sqlite::SqliteConnectOptions;
-use sqlx::SqlitePool;
+use embedding_queue::ChunkRecord;
 use std::path::Path;
 use std::sync::Arc;
 
-/// Main memory system - SQLite only for simplicity
+/// A vector backend paired with the embedding provider that fills it
+type VectorRoute = (Arc<dyn VectorBackend>, Arc<dyn EmbeddingProvider>);
+
+/// One vector to write for a memory: the whole content or one chunk of it
+struct VectorUnit {
+    id: String,
+    text: String,
+    payload: serde_json::Value,
+}
+
+/// How [`MemorySystem::new_with_options`] opens a data directory
+#[derive(Debug, Clone, Default)]
+pub struct MemorySystemOptions {
+    pub sqlite: SqliteTuning,
+    /// Encrypt the database with SQLCipher and keep the text index in memory
+    /// only (requires the `encryption` feature)
+    pub encryption_key: Option<EncryptionKey>,
+    /// Memory and CPU footprint, see [`ResourceProfile`]
+    pub profile: ResourceProfile,
+    /// Metadata keys queried often enough to deserve an indexed column, see
+    /// [`MemoryStore::index_metadata_key`]
+    pub indexed_metadata_keys: Vec<String>,
+    /// Metadata keys indexed as numeric fields of the text index, see
+    /// [`MemorySearch::with_numeric_fields`]
+    pub numeric_fields: Vec<String>,
+    /// Application migrations run after the crate's own, see [`MigrationHook`]
+    pub migrations: Vec<MigrationHook>,
+}
+
+impl MemorySystemOptions {
+    /// The [`LowMemory`](ResourceProfile::LowMemory) profile with
+    /// [`SqliteTuning::low_memory`]
+    pub fn low_memory() -> Self {
+        Self::default()
+            .with_profile(ResourceProfile::LowMemory)
+            .with_sqlite(SqliteTuning::low_memory())
+    }
+
+    pub fn with_profile(mut self, profile: ResourceProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn with_indexed_metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.indexed_metadata_keys.push(key.into());
+        self
+    }
+
+    pub fn with_numeric_field(mut self, key: impl Into<String>) -> Self {
+        self.numeric_fields.push(key.into());
+        self
+    }
+
+    pub fn with_sqlite(mut self, sqlite: SqliteTuning) -> Self {
+        self.sqlite = sqlite;
+        self
+    }
+
+    pub fn with_encryption_key(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Run `hook` when a data directory is opened, once per database
+    pub fn with_migration(mut self, hook: MigrationHook) -> Self {
+        self.migrations.push(hook);
+        self
+    }
+}
+
+impl From<SqliteTuning> for MemorySystemOptions {
+    fn from(sqlite: SqliteTuning) -> Self {
+        Self {
+            sqlite,
+            ..Self::default()
+        }
+    }
+}
+
+/// Main memory system
+///
+/// Memories, associations and the graph live in a [`StorageBackend`], the
+/// SQLite store in the data directory unless [`with_backend`](Self::with_backend)
+/// supplies another one.
 #[derive(Clone)]
 pub struct MemorySystem {
     store: Arc<MemoryStore>,
+    /// Alternate system of record; `None` uses `store`
+    backend: Option<Arc<dyn StorageBackend>>,
     search: MemorySearch,
-    data_dir: std::path::PathBuf,
+    /// `None` when built with [`in_memory`](Self::in_memory)
+    data_dir: Option<std::path::PathBuf>,
     pulses: Arc<GoldfishPulses>,
     vector: Option<Arc<dyn VectorBackend>>,
     embedder: Option<Arc<dyn EmbeddingProvider>>,
+    type_vectors: std::collections::HashMap<MemoryType, VectorRoute>,
+    middleware: MiddlewareChain,
+    classifier: Arc<dyn MemoryClassifier>,
+    analytics: Arc<MemoryAnalytics>,
+    validation: ContentValidation,
+    quality: QualityScorer,
+    review: ReviewPolicy,
+    query_log: Option<Arc<QueryLogger>>,
+    manifest: Arc<std::sync::Mutex<DataDirManifest>>,
+    profile: ResourceProfile,
+    injection_screen: Option<InjectionScreen>,
+    /// Set by [`with_embedding_queue`](Self::with_embedding_queue)
+    embedding_queue: Option<EmbeddingQueue>,
+    /// Set by [`with_chunked_embeddings`](Self::with_chunked_embeddings)
+    chunking: Option<ChunkingConfig>,
+}
+
+/// Drops a purged memory from the text index and every vector backend
+struct IndexCleanup<'a>(&'a MemorySystem);
+
+#[async_trait::async_trait]
+impl PurgeCleanup for IndexCleanup<'_> {
+    async fn before_purge(&self, id: &str) -> Result<()> {
+        self.0.search.delete_memory(id)?;
+        self.0.delete_vectors(id).await
+    }
+
+    async fn forget(&self, _store: &MemoryStore, id: &str) -> Result<bool> {
+        self.0.forget(id).await
+    }
 }
 
 impl std::fmt::Debug for MemorySystem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MemorySystem")
             .field("data_dir", &self.data_dir)
+            .field("middleware", &self.middleware)
             .finish()
     }
 }
@@ -96,260 +375,2558 @@ impl std::fmt::Debug for MemorySystem {
 impl MemorySystem {
     /// Create a new memory system (SQLite only)
     pub async fn new(data_dir: impl AsRef<Path>) -> Result<Self> {
-        let data_dir = data_dir.as_ref().to_path_buf();
-        std::fs::create_dir_all(&data_dir)?;
+        Self::new_with_options(data_dir, MemorySystemOptions::default()).await
+    }
 
-        let sqlite_path = data_dir.join("memories.db");
-        let options = SqliteConnectOptions::new()
-            .filename(&sqlite_path)
-            .create_if_missing(true);
+    /// Like [`new`](Self::new), with the SQLite tuning, encryption key and
+    /// resource profile taken from `options` (a bare [`SqliteTuning`] also
+    /// works). The low-memory profile also attaches a quantized
+    /// [`FileVectorBackend`] in `<data_dir>/vectors`.
+    pub async fn new_with_options(
+        data_dir: impl AsRef<Path>,
+        options: impl Into<MemorySystemOptions>,
+    ) -> Result<Self> {
+        let options = options.into();
+        let data_dir = platform_fs::long_path(data_dir.as_ref());
+        std::fs::create_dir_all(&data_dir)?;
+        let manifest = DataDirManifest::open(&data_dir)?;
 
-        let pool = SqlitePool::connect_with(options).await?;
+        let pool = options
+            .sqlite
+            .connect_encrypted(
+                data_dir.join(backup::DATABASE_FILE),
+                options.encryption_key.as_ref(),
+            )
+            .await?;
 
         // Run migrations
         sqlx::migrate!("./migrations")
             .run(&pool)
             .await
             .map_err(|e| MemoryError::Database(e.into()))?;
+        migration_hook::run_migration_hooks(&pool, &options.migrations).await?;
 
         let store = MemoryStore::new(pool);
-        let search = MemorySearch::with_dir(Arc::clone(&store), &data_dir)?;
+        for key in &options.indexed_metadata_keys {
+            store.index_metadata_key(key).await?;
+        }
+        // An on-disk Tantivy index would hold memory content in plaintext
+        let mut search = if options.encryption_key.is_some() {
+            MemorySearch::new(Arc::clone(&store))
+        } else {
+            MemorySearch::with_dir(Arc::clone(&store), &data_dir)?
+        };
+        search = search.with_numeric_fields(options.numeric_fields.iter().cloned())?;
+        if let Some(heap) = options.profile.writer_heap() {
+            search = search.with_writer_heap(heap);
+        }
         search.reindex_all().await?;
         let pulses = Arc::new(GoldfishPulses::default());
+        let mut analytics = MemoryAnalytics::new(Arc::clone(&store));
+        if !options.profile.cache_analytics() {
+            analytics = analytics.with_ttl(std::time::Duration::ZERO);
+        }
+        let analytics = Arc::new(analytics);
+        let vectors_dir = data_dir.join("vectors");
 
-        Ok(Self {
+        let system = Self {
             store,
+            backend: None,
             search,
-            data_dir,
+            data_dir: Some(data_dir),
             pulses,
             vector: None,
             embedder: None,
-        })
+            type_vectors: std::collections::HashMap::new(),
+            middleware: MiddlewareChain::new(),
+            classifier: Arc::new(RuleBasedClassifier::default()),
+            analytics,
+            validation: ContentValidation::default(),
+            quality: QualityScorer::default(),
+            review: ReviewPolicy::default(),
+            query_log: None,
+            manifest: Arc::new(std::sync::Mutex::new(manifest)),
+            profile: options.profile,
+            injection_screen: None,
+            embedding_queue: None,
+            chunking: None,
+        };
+
+        if options.profile != ResourceProfile::LowMemory {
+            return Ok(system);
+        }
+        const DIMENSION: usize = 384;
+        let mut vectors = FileVectorBackend::new(vectors_dir, DIMENSION)
+            .with_quantization(options.profile.vector_quantization());
+        if let Some(key) = &options.encryption_key {
+            vectors = vectors.with_encryption(key)?;
+        }
+        vectors.ensure_ready().await?;
+        Ok(system.with_vector_backend(
+            Arc::new(vectors),
+            Arc::new(HashEmbeddingProvider::new(DIMENSION)),
+        ))
+    }
+
+    /// A memory system that never touches disk: in-memory SQLite, a RAM text
+    /// index and an [`InMemoryVectorBackend`] filled by the hash embedder.
+    /// Meant for tests; everything is gone when the last clone is dropped.
+    pub async fn in_memory() -> Result<Self> {
+        let store = MemoryStore::connect_in_memory().await;
+        let search = MemorySearch::new(Arc::clone(&store));
+        let analytics = Arc::new(MemoryAnalytics::new(Arc::clone(&store)));
+
+        let system = Self {
+            store,
+            backend: None,
+            search,
+            data_dir: None,
+            pulses: Arc::new(GoldfishPulses::default()),
+            vector: None,
+            embedder: None,
+            type_vectors: std::collections::HashMap::new(),
+            middleware: MiddlewareChain::new(),
+            classifier: Arc::new(RuleBasedClassifier::default()),
+            analytics,
+            validation: ContentValidation::default(),
+            quality: QualityScorer::default(),
+            review: ReviewPolicy::default(),
+            query_log: None,
+            manifest: Arc::new(std::sync::Mutex::new(DataDirManifest::new())),
+            profile: ResourceProfile::Standard,
+            injection_screen: None,
+            embedding_queue: None,
+            chunking: None,
+        };
+
+        const DIMENSION: usize = 384;
+        Ok(system.with_vector_backend(
+            Arc::new(InMemoryVectorBackend::new(DIMENSION)),
+            Arc::new(HashEmbeddingProvider::new(DIMENSION)),
+        ))
     }
 
     /// Save a memory
+    ///
+    /// Registered middleware runs first and may rewrite or veto the memory, so the
+    /// persisted copy can differ from `memory`.
     pub async fn save(&self, memory: &Memory) -> Result<()> {
-        self.store.save(memory).await?;
-        self.search.index_memory(memory)?;
+        self.save_chunked(memory).await.map(|_| ())
+    }
 
-        if let (Some(vector), Some(embedder)) = (&self.vector, &self.embedder) {
-            let vectors = embedder
-                .embed(std::slice::from_ref(&memory.content))
-                .await
-                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
-            if let Some(v) = vectors.first() {
-                vector.upsert(&memory.id, v, None).await?;
-            }
+    /// Save a memory, returning the IDs of every memory written.
+    ///
+    /// Content is checked against the configured [`ContentValidation`]. When oversized
+    /// content is split ([`OversizeBehavior::Chunk`]), `memory` keeps the first chunk
+    /// and its ID; each further chunk is saved as a copy with a new ID, a `chunk`
+    /// metadata entry and a [`RelationType::PartOf`] association to the first.
+    ///
+    /// A memory the [`ReviewPolicy`] holds back is queued instead of written; its
+    /// ID is returned all the same and becomes loadable once [approved](Self::approve).
+    pub async fn save_chunked(&self, memory: &Memory) -> Result<Vec<MemoryId>> {
+        let mut memory = memory.clone();
+        self.middleware.before_save(&mut memory).await?;
+
+        if let Some(reason) = self.review.review_reason(&memory) {
+            let id = memory.id.clone();
+            self.submit_for_review(memory, reason).await?;
+            return Ok(vec![id]);
+        }
+        self.write_chunked(memory).await
+    }
+
+    /// Validate, split and persist a memory that passed middleware and review
+    async fn write_chunked(&self, mut memory: Memory) -> Result<Vec<MemoryId>> {
+        let mut chunks = self.validation.apply(&memory.content)?.into_iter();
+        memory.content = chunks.next().unwrap_or_default();
+        let rest: Vec<String> = chunks.collect();
+        let total = rest.len() + 1;
+
+        self.persist(&memory).await?;
+        self.middleware.after_save(&memory).await?;
+        let mut ids = vec![memory.id.clone()];
+
+        for (i, content) in rest.into_iter().enumerate() {
+            let mut part = memory.clone();
+            part.id = self.store.next_id();
+            part.content = content;
+            part.metadata = Some(validation::chunk_metadata(
+                part.metadata.take(),
+                &memory.id,
+                i + 1,
+                total,
+            ));
+
+            self.persist(&part).await?;
+            self.store_association(&Association::new(
+                part.id.clone(),
+                memory.id.clone(),
+                RelationType::PartOf,
+            ))
+            .await?;
+            self.middleware.after_save(&part).await?;
+            ids.push(part.id);
         }
 
+        Ok(ids)
+    }
+
+    /// Write a memory to the store, the text index and its vector backend
+    async fn persist(&self, memory: &Memory) -> Result<()> {
+        let quality = self.assess_quality(memory).await?;
+        self.backend().save_memory(memory).await?;
+        if self.backend.is_some() {
+            self.store.save(memory).await?;
+        }
+        self.store.set_quality(&memory.id, &quality).await?;
+        self.store
+            .record_version(memory, VersionAuthor::Agent, "created")
+            .await?;
+        self.search.index_memory(memory)?;
+        self.queue_vector(memory).await?;
+        self.pulses.emit(pulse::new_memory(memory.clone())).await;
         Ok(())
     }
 
-    /// Load a memory by ID
-    pub async fn load(&self, id: &str) -> Result<Option<Memory>> {
-        self.store.load(id).await
+    /// Score a memory against its closest existing memories by full-text overlap
+    async fn assess_quality(&self, memory: &Memory) -> Result<QualityScore> {
+        let config = SearchConfig {
+            max_results: 5,
+            boost_recent: false,
+            ..SearchConfig::default()
+        };
+        let neighbors: Vec<Memory> = self
+            .search
+            .search_fallback(&memory.content, &config, SearchFallback::AnyTerm)
+            .await?
+            .into_iter()
+            .map(|r| r.memory)
+            .collect();
+        Ok(self.quality.score(memory, &neighbors))
     }
 
-    /// Update a memory
-    pub async fn update(&self, memory: &Memory) -> Result<()> {
-        self.store.update(memory).await?;
-        self.search.index_memory(memory)?;
+    /// Replace the quality scorer applied on save and update
+    pub fn with_quality_scorer(mut self, scorer: QualityScorer) -> Self {
+        self.quality = scorer;
+        self
+    }
 
-        if let (Some(vector), Some(embedder)) = (&self.vector, &self.embedder) {
-            let vectors = embedder
-                .embed(std::slice::from_ref(&memory.content))
-                .await
-                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
-            if let Some(v) = vectors.first() {
-                vector.upsert(&memory.id, v, None).await?;
-            }
-        }
+    /// Quality score of a memory, computed when it was last saved or updated
+    pub async fn quality(&self, id: &str) -> Result<Option<QualityScore>> {
+        self.store.get_quality(id).await
+    }
 
-        Ok(())
+    /// Memories scoring below `threshold` that have not been reviewed yet, worst
+    /// first, each with a suggested [`CurationAction`]
+    pub async fn curation_queue(&self, threshold: f32, limit: i64) -> Result<Vec<CurationItem>> {
+        Ok(self
+            .store
+            .low_quality(threshold, limit)
+            .await?
+            .into_iter()
+            .map(|(memory, quality)| CurationItem::new(memory, quality))
+            .collect())
     }
 
-    /// Delete a memory
-    pub async fn delete(&self, id: &str) -> Result<()> {
-        self.store.delete(id).await?;
-        self.search.delete_memory(id)?;
+    /// Resolve a curation queue entry. `Merge` adds the memory's tags to the target,
+    /// links the two and forgets the memory. Returns `false` if a memory is missing.
+    pub async fn resolve_curation(&self, id: &str, action: CurationAction) -> Result<bool> {
+        match action {
+            CurationAction::Keep => self.store.mark_quality_reviewed(id).await,
+            CurationAction::Forget => {
+                self.store.mark_quality_reviewed(id).await?;
+                self.forget(id).await
+            }
+            CurationAction::Merge(target_id) => {
+                if target_id == id {
+                    return Err(MemoryError::InvalidOperation(
+                        "Cannot merge a memory into itself".to_string(),
+                    ));
+                }
+                let (Some(memory), Some(mut target)) =
+                    (self.load(id).await?, self.load(&target_id).await?)
+                else {
+                    return Ok(false);
+                };
 
-        if let Some(vector) = &self.vector {
-            vector.delete(id).await?;
+                for tag in memory.tags {
+                    if !target.tags.contains(&tag) {
+                        target.tags.push(tag);
+                    }
+                }
+                target.importance = target.importance.max(memory.importance);
+                target.updated_at = self.store.now();
+                self.update(&target).await?;
+                self.associate(id, &target_id, RelationType::RelatedTo)
+                    .await?;
+                self.store.mark_quality_reviewed(id).await?;
+                self.forget(id).await
+            }
         }
+    }
 
-        Ok(())
+    /// Read the time from `clock` when stamping, decaying and searching memories
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        let store = self.store.with_clock(clock);
+        self.with_store(store)
     }
 
-    /// Soft delete (forget) a memory
-    pub async fn forget(&self, id: &str) -> Result<bool> {
-        self.store.forget(id).await
+    /// Mint memory IDs with `ids`
+    pub fn with_id_generator(self, ids: Arc<dyn IdGenerator>) -> Self {
+        let store = self.store.with_id_generator(ids);
+        self.with_store(store)
     }
 
-    /// Restore a forgotten memory
-    pub async fn restore(&self, id: &str) -> Result<bool> {
-        self.store.restore(id).await
+    /// Keep memories, associations, episodes and summaries in `backend`
+    /// instead of the SQLite store.
+    ///
+    /// Reads of memories and the graph go to `backend`. Every write through
+    /// this system, pins and bulk edits included, goes to `backend` first and
+    /// is mirrored into the local SQLite store, which keeps serving full-text
+    /// search, version history, quality scores, analytics and the other
+    /// features the [`StorageBackend`] trait does not cover. Operations that
+    /// copy the local store wholesale (backups, restores and archives) would
+    /// miss whatever only `backend` holds, so they fail with
+    /// [`MemoryError::InvalidOperation`].
+    ///
+    /// ```rust,ignore
+    /// let backend = PostgresStorageBackend::connect("postgres://goldfish@db/goldfish").await?;
+    /// let memory = MemorySystem::new("./data").await?.with_backend(Arc::new(backend));
+    /// ```
+    pub fn with_backend(mut self, backend: Arc<dyn StorageBackend>) -> Self {
+        self.backend = Some(backend);
+        self
     }
 
-    /// Search memories (simple text match for now)
-    pub async fn search(&self, query: &str) -> Result<Vec<MemorySearchResult>> {
-        self.search.search(query, &SearchConfig::default()).await
+    /// The storage backend memories are read from and written to
+    pub fn backend(&self) -> &dyn StorageBackend {
+        match &self.backend {
+            Some(backend) => backend.as_ref(),
+            None => self.store.as_ref(),
+        }
     }
 
-    /// Search with custom configuration
-    pub async fn search_with_config(
-        &self,
-        query: &str,
-        config: &SearchConfig,
-    ) -> Result<Vec<MemorySearchResult>> {
-        self.search.search(query, config).await
+    /// Write an association to the backend, mirrored locally like memories
+    async fn store_association(&self, association: &Association) -> Result<()> {
+        self.backend().create_association(association).await?;
+        if self.backend.is_some() {
+            self.store.create_association(association).await?;
+        }
+        Ok(())
     }
 
-    /// Get memories by type
-    pub async fn get_by_type(&self, memory_type: MemoryType, limit: i64) -> Result<Vec<Memory>> {
-        self.store.get_by_type(memory_type, limit).await
+    fn with_store(mut self, store: Arc<MemoryStore>) -> Self {
+        self.search = self.search.with_store(Arc::clone(&store));
+        self.analytics = Arc::new(MemoryAnalytics::new(Arc::clone(&store)));
+        self.store = store;
+        self
     }
 
-    /// Get high-importance memories
-    pub async fn get_high_importance(&self, threshold: f32, limit: i64) -> Result<Vec<Memory>> {
-        self.store.get_high_importance(threshold, limit).await
+    /// A new memory stamped with the configured clock and ID generator
+    pub fn new_memory(&self, content: impl Into<String>, memory_type: MemoryType) -> Memory {
+        self.store.new_memory(content, memory_type)
     }
 
-    /// Create an association between memories
-    pub async fn associate(
-        &self,
-        source_id: &str,
-        target_id: &str,
-        relation_type: RelationType,
-    ) -> Result<()> {
-        let association = Association::new(source_id, target_id, relation_type);
-        self.store.create_association(&association).await
+    /// Hold back new memories matching `policy` for review
+    pub fn with_review_policy(mut self, policy: ReviewPolicy) -> Self {
+        self.review = policy;
+        self
     }
 
-    /// Get associations for a memory
-    pub async fn get_associations(&self, memory_id: &str) -> Result<Vec<Association>> {
-        self.store.get_associations(memory_id).await
+    async fn submit_for_review(&self, memory: Memory, reason: String) -> Result<()> {
+        let item = ReviewItem {
+            memory,
+            status: ReviewStatus::PendingReview,
+            reason,
+            submitted_at: self.store.now(),
+            reviewed_at: None,
+            reviewer: None,
+            note: None,
+        };
+        self.store.enqueue_review(&item).await?;
+        self.pulses
+            .emit(Pulse::ReviewRequested {
+                timestamp: item.submitted_at,
+                memory: item.memory,
+                reason: item.reason,
+            })
+            .await;
+        Ok(())
     }
 
-    /// Get memory neighbors in the graph
-    pub async fn get_neighbors(
+    /// Memories waiting for review, oldest first
+    pub async fn pending_reviews(&self, limit: i64) -> Result<Vec<ReviewItem>> {
+        self.store
+            .list_reviews(Some(ReviewStatus::PendingReview), limit)
+            .await
+    }
+
+    /// Review queue entries with `status`, or all of them, oldest first
+    pub async fn reviews(
         &self,
-        memory_id: &str,
-        depth: u32,
-    ) -> Result<(Vec<Memory>, Vec<Association>)> {
-        self.store.get_neighbors(memory_id, depth, &[]).await
+        status: Option<ReviewStatus>,
+        limit: i64,
+    ) -> Result<Vec<ReviewItem>> {
+        self.store.list_reviews(status, limit).await
     }
 
-    /// Run maintenance tasks
-    pub async fn run_maintenance(&self, config: &MaintenanceConfig) -> Result<MaintenanceReport> {
-        maintenance::run_maintenance(&self.store, config).await
+    /// The review queue entry for a memory
+    pub async fn review(&self, id: &str) -> Result<Option<ReviewItem>> {
+        self.store.get_review(id).await
     }
 
-    /// Get the underlying store
-    pub fn store(&self) -> &MemoryStore {
-        &self.store
+    /// Approve a pending memory and save it. Returns `false` if the memory is
+    /// not pending review.
+    pub async fn approve(&self, id: &str, reviewer: Option<&str>) -> Result<bool> {
+        let Some(item) = self.store.get_review(id).await? else {
+            return Ok(false);
+        };
+        if item.status != ReviewStatus::PendingReview {
+            return Ok(false);
+        }
+
+        self.write_chunked(item.memory).await?;
+        self.store
+            .resolve_review(id, ReviewStatus::Approved, reviewer, None)
+            .await?;
+        self.emit_review_resolved(id, true, reviewer).await;
+        Ok(true)
     }
 
-    /// Get the search interface
-    pub fn search_interface(&self) -> &MemorySearch {
-        &self.search
+    /// Reject a pending memory; it is never saved. Returns `false` if the
+    /// memory is not pending review.
+    pub async fn reject(
+        &self,
+        id: &str,
+        reviewer: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<bool> {
+        let rejected = self
+            .store
+            .resolve_review(id, ReviewStatus::Rejected, reviewer, note)
+            .await?;
+        if rejected {
+            self.emit_review_resolved(id, false, reviewer).await;
+        }
+        Ok(rejected)
     }
 
-    /// Get the pulses system for subscribing to events
-    pub fn pulses(&self) -> &GoldfishPulses {
-        &self.pulses
+    async fn emit_review_resolved(&self, id: &str, approved: bool, reviewer: Option<&str>) {
+        self.pulses
+            .emit(Pulse::ReviewResolved {
+                memory_id: id.to_string(),
+                approved,
+                reviewer: reviewer.map(str::to_string),
+                timestamp: self.store.now(),
+            })
+            .await;
     }
 
-    /// Attach a vector backend and embedding provider to enable hybrid retrieval.
-    ///
-    /// This does not change the existing API surface; it only enables the additional
-    /// `hybrid_search` method and keeps vectors up-to-date on save/update/delete.
-    pub fn with_vector_backend(
-        mut self,
-        vector: Arc<dyn VectorBackend>,
-        embedder: Arc<dyn EmbeddingProvider>,
-    ) -> Self {
-        self.vector = Some(vector);
-        self.embedder = Some(embedder);
+    /// Replace the content validation rules applied on save and update
+    pub fn with_validation(mut self, validation: ContentValidation) -> Self {
+        self.validation = validation;
         self
     }
 
-    /// Hybrid retrieval: BM25 (Tantivy) + vector + recency + importance + graph neighborhood.
-    pub async fn hybrid_search(
+    /// Screen search results for prompt injection; flagged memories are
+    /// reported as [`Pulse::InjectionSuspected`] on this system's pulses
+    pub fn with_injection_screen(mut self, screen: InjectionScreen) -> Self {
+        self.injection_screen = Some(screen.with_pulses(Arc::clone(&self.pulses)));
+        self
+    }
+
+    /// Run recalled results through the injection screen, if any
+    async fn screen_results<T>(
         &self,
-        query: &str,
-        cfg: &HybridSearchConfig,
-        filter_type: Option<MemoryType>,
-    ) -> Result<Vec<ExplainedSearchResult>> {
-        let bm25_cfg = SearchConfig {
-            mode: SearchMode::FullText,
-            max_results: cfg.bm25_limit.max(cfg.max_results),
-            memory_type: filter_type,
-            ..SearchConfig::default()
+        results: Vec<T>,
+        memory_of: fn(&mut T) -> &mut Memory,
+    ) -> Result<Vec<T>> {
+        let Some(screen) = &self.injection_screen else {
+            return Ok(results);
         };
+        let mut kept = Vec::with_capacity(results.len());
+        for mut result in results {
+            let memory = memory_of(&mut result);
+            match screen.screen(memory.clone()).await? {
+                Some(screened) => *memory = screened,
+                None => continue,
+            }
+            kept.push(result);
+        }
+        Ok(kept)
+    }
 
-        let bm25 = self.search.search(query, &bm25_cfg).await?;
+    /// Create and save a memory from input.
+    ///
+    /// If `input.memory_type` is `None`, the configured classifier assigns the type
+    /// (and the importance, unless one was given) and the classification is recorded
+    /// under the `classification` metadata key.
+    ///
+    /// Returns the memory as persisted, after middleware and content validation.
+    /// A memory held back for review is returned as submitted.
+    pub async fn create(&self, input: CreateMemoryInput) -> Result<Memory> {
+        let CreateMemoryInput {
+            content,
+            memory_type,
+            importance,
+            source,
+            session_id,
+            metadata,
+        } = input;
 
-        hybrid_retrieval::hybrid_rank(
-            query,
-            bm25,
-            self.vector.as_ref(),
-            self.embedder.as_ref(),
-            |id| {
-                let store = Arc::clone(&self.store);
-                let id = id.to_string();
-                Box::pin(async move { store.load(&id).await })
-            },
-            |id, depth| {
-                let store = Arc::clone(&self.store);
-                let id = id.to_string();
-                Box::pin(async move { store.get_neighbors(&id, depth, &[]).await })
-            },
-            cfg,
-            filter_type,
-        )
-        .await
-    }
+        let mut memory = match memory_type {
+            Some(memory_type) => {
+                let mut memory = Memory::new(content, memory_type);
+                memory.metadata = metadata;
+                memory
+            }
+            None => {
+                let classification = self.classifier.classify(&content).await?;
+                let mut metadata = match metadata {
+                    Some(serde_json::Value::Object(map)) => map,
+                    Some(other) => {
+                        let mut map = serde_json::Map::new();
+                        map.insert("value".to_string(), other);
+                        map
+                    }
+                    None => serde_json::Map::new(),
+                };
+                metadata.insert("classification".to_string(), classification.to_metadata());
 
-    /// Search memories by time range
-    pub async fn search_temporal(
-        &self,
-        _query: &str,
-        temporal: &temporal::TemporalQuery,
-    ) -> Result<Vec<MemorySearchResult>> {
-        let time_filter = temporal.to_sql_filter();
-        let memories = self.store.query_with_filter(&time_filter, 1000).await?;
+                Memory::new(content, classification.memory_type)
+                    .with_importance(classification.importance)
+                    .with_metadata(serde_json::Value::Object(metadata))
+            }
+        };
 
-        let results: Vec<MemorySearchResult> = memories
-            .into_iter()
-            .enumerate()
-            .map(|(i, memory)| MemorySearchResult {
-                memory,
-                score: 1.0 - (i as f32 / 100.0),
-                rank: i + 1,
-            })
-            .collect();
+        if let Some(importance) = importance {
+            memory = memory.with_importance(importance);
+        }
+        memory.priority = memory.importance;
+        memory.source = source;
+        memory.session_id = session_id;
 
-        Ok(results)
+        self.save(&memory).await?;
+        Ok(self
+            .backend()
+            .load_memory(&memory.id)
+            .await?
+            .unwrap_or(memory))
     }
 
-    /// Get memories from today
-    pub async fn get_today(&self) -> Result<Vec<Memory>> {
-        let today = chrono::Utc::now().date_naive();
-        let filter = format!("date(created_at) = '{}'", today);
-        self.store.query_with_filter(&filter, 100).await
+    /// [`create`](Self::create) every input, carrying on past failures. Each
+    /// failure is reported with the input's position in `inputs`.
+    pub async fn save_batch(&self, inputs: Vec<CreateMemoryInput>) -> BatchSaveReport {
+        let mut report = BatchSaveReport::default();
+        for (index, input) in inputs.into_iter().enumerate() {
+            match self.create(input).await {
+                Ok(memory) => report.saved.push(memory.id),
+                Err(e) => report.failed.push(BatchSaveFailure {
+                    index,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        report
     }
 
-    /// Get memories from yesterday
-    pub async fn get_yesterday(&self) -> Result<Vec<Memory>> {
-        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1)).date_naive();
-        let filter = format!("date(created_at) = '{}'", yesterday);
-        self.store.query_with_filter(&filter, 100).await
+    /// Vector backend and embedder responsible for a memory type: the type-specific
+    /// route if one was registered, otherwise the default backend.
+    fn vector_route(&self, memory_type: Option<MemoryType>) -> Option<VectorSource<'_>> {
+        if let Some((vector, embedder)) = memory_type.and_then(|t| self.type_vectors.get(&t)) {
+            return Some((vector, embedder));
+        }
+        match (&self.vector, &self.embedder) {
+            (Some(vector), Some(embedder)) => Some((vector, embedder)),
+            _ => None,
+        }
+    }
+
+    /// Vector routes a search covers: the route of its type filter, or without
+    /// one the default backend and every per-type backend. Embedders are
+    /// checked against the manifest on the way.
+    fn search_routes(&self, memory_type: Option<MemoryType>) -> Result<Vec<VectorSource<'_>>> {
+        let mut routes: Vec<VectorSource<'_>> = Vec::new();
+        if let Some(route) = self.vector_route(memory_type) {
+            self.check_embedder(memory_type, route.1)?;
+            routes.push(route);
+        }
+        if memory_type.is_none() {
+            for (vector, embedder) in self.type_vectors.values() {
+                if !routes.iter().any(|(known, _)| Arc::ptr_eq(known, vector)) {
+                    routes.push((vector, embedder));
+                }
+            }
+        }
+        Ok(routes)
+    }
+
+    fn lock_manifest(&self) -> std::sync::MutexGuard<'_, DataDirManifest> {
+        self.manifest.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The data directory manifest as currently recorded
+    pub fn manifest(&self) -> DataDirManifest {
+        self.lock_manifest().clone()
+    }
+
+    /// Verify the embedder of a vector route against the manifest. Only the default
+    /// route is tracked; per-type routes manage their own models.
+    fn check_embedder(
+        &self,
+        memory_type: Option<MemoryType>,
+        embedder: &Arc<dyn EmbeddingProvider>,
+    ) -> Result<()> {
+        if memory_type.is_some_and(|t| self.type_vectors.contains_key(&t)) {
+            return Ok(());
+        }
+        let mut manifest = self.lock_manifest();
+        if manifest.check_embedding(embedder.name(), embedder.dimension())? {
+            if let Some(data_dir) = &self.data_dir {
+                manifest.save(data_dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Embed a memory and upsert it into the vector backend for its type
+    async fn upsert_vector(&self, memory: &Memory) -> Result<()> {
+        if let Some((vector, embedder)) = self.vector_route(Some(memory.memory_type)) {
+            self.check_embedder(Some(memory.memory_type), embedder)?;
+            let (units, chunks) = self.vector_units(memory);
+            let texts: Vec<String> = units.iter().map(|u| u.text.clone()).collect();
+            let vectors = embedder
+                .embed(&texts)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+            for (unit, v) in units.into_iter().zip(&vectors) {
+                vector.upsert(&unit.id, v, Some(unit.payload)).await?;
+            }
+            let stale =
+                vector_chunks::replace_chunks(&self.store, vector.as_ref(), &memory.id, &chunks)
+                    .await?;
+            if let Some(queue) = &self.embedding_queue {
+                stale.iter().for_each(|id| queue.cancel(id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Vectors to write for a memory: one, or one per chunk when chunking is
+    /// on and the content is long, along with the chunks. Nothing is written;
+    /// once the vectors are, [`vector_chunks::replace_chunks`] records the
+    /// chunks and drops what an earlier chunking left behind.
+    fn vector_units(&self, memory: &Memory) -> (Vec<VectorUnit>, Vec<VectorChunk>) {
+        let chunks = self
+            .chunking
+            .map(|config| vector_chunks::chunk_spans(&memory.content, &config))
+            .unwrap_or_default();
+
+        let payload = vector_backend::vector_payload(memory, self.store.namespace());
+        if chunks.is_empty() {
+            let unit = VectorUnit {
+                id: memory.id.clone(),
+                text: memory.content.clone(),
+                payload,
+            };
+            return (vec![unit], chunks);
+        }
+        let units = chunks
+            .iter()
+            .map(|chunk| {
+                let mut payload = payload.clone();
+                if let Some(fields) = payload.as_object_mut() {
+                    fields.insert("memory_id".into(), memory.id.clone().into());
+                    fields.insert("chunk".into(), chunk.index.into());
+                    fields.insert("chunk_start".into(), chunk.start.into());
+                    fields.insert("chunk_end".into(), chunk.end.into());
+                }
+                VectorUnit {
+                    id: vector_chunks::chunk_vector_id(&memory.id, chunk.index),
+                    text: chunk.text(&memory.content).to_string(),
+                    payload,
+                }
+            })
+            .collect();
+        (units, chunks)
+    }
+
+    /// Every vector ID a memory may be stored under: its own and its chunks'
+    async fn vector_ids(&self, id: &str) -> Result<Vec<String>> {
+        let mut ids = vec![id.to_string()];
+        for chunk in self.store.vector_chunks(id).await? {
+            ids.push(vector_chunks::chunk_vector_id(id, chunk.index));
+        }
+        Ok(ids)
+    }
+
+    /// Remove a memory's vectors, chunks included, from every backend
+    async fn delete_vectors(&self, id: &str) -> Result<()> {
+        for vector_id in self.vector_ids(id).await? {
+            if let Some(queue) = &self.embedding_queue {
+                queue.cancel(&vector_id);
+            }
+            for vector in self.vector_backends() {
+                vector.delete(&vector_id).await?;
+            }
+        }
+        self.store.save_vector_chunks(id, &[]).await
+    }
+
+    /// Hand a memory to the embedding queue if there is one, else upsert it now
+    async fn queue_vector(&self, memory: &Memory) -> Result<()> {
+        let Some(queue) = &self.embedding_queue else {
+            return self.upsert_vector(memory).await;
+        };
+        if let Some((vector, embedder)) = self.vector_route(Some(memory.memory_type)) {
+            self.check_embedder(Some(memory.memory_type), embedder)?;
+            let (units, chunks) = self.vector_units(memory);
+            let last = units.len().saturating_sub(1);
+            for (i, unit) in units.into_iter().enumerate() {
+                // The memory's last vector records its chunks once written
+                let chunks = (i == last).then(|| ChunkRecord {
+                    store: Arc::clone(&self.store),
+                    memory_id: memory.id.clone(),
+                    chunks: chunks.clone(),
+                });
+                queue
+                    .enqueue(embedding_queue::EmbeddingJob {
+                        id: unit.id,
+                        content: unit.text,
+                        payload: unit.payload,
+                        vector: Arc::clone(vector),
+                        embedder: Arc::clone(embedder),
+                        chunks,
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Embed vectors in the background instead of during `save` and `update`.
+    ///
+    /// Spawns the queue's worker, so it must be called within a Tokio runtime.
+    /// See [`embedding_queue`] for the trade-offs.
+    pub fn with_embedding_queue(mut self, config: EmbeddingQueueConfig) -> Self {
+        self.embedding_queue = Some(EmbeddingQueue::spawn(config));
+        self
+    }
+
+    /// Embed memories longer than `config.max_chars` as one vector per chunk.
+    ///
+    /// Searches score a memory by its best chunk. Memories saved before keep
+    /// their single vector until they are updated or re-embedded.
+    pub fn with_chunked_embeddings(mut self, config: ChunkingConfig) -> Self {
+        self.chunking = Some(config);
+        self
+    }
+
+    /// Memories whose vectors are still queued; always 0 without a queue
+    pub fn pending_embeddings(&self) -> usize {
+        self.embedding_queue
+            .as_ref()
+            .map_or(0, EmbeddingQueue::pending)
+    }
+
+    /// Wait for every queued vector to be written. Fails with the memories that
+    /// could not be embedded since the last flush.
+    pub async fn flush_embeddings(&self) -> Result<()> {
+        match &self.embedding_queue {
+            Some(queue) => queue.flush().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Load a memory by ID
+    pub async fn load(&self, id: &str) -> Result<Option<Memory>> {
+        self.backend().load_memory(id).await
+    }
+
+    /// Current store generation; unchanged means nothing was written since
+    pub async fn generation(&self) -> Result<StoreGeneration> {
+        self.store.generation().await
+    }
+
+    /// [`load`](Self::load) with the generation it was read at
+    pub async fn load_with_generation(&self, id: &str) -> Result<Generational<Option<Memory>>> {
+        let generation = self.store.generation().await?;
+        Ok(Generational::new(generation, self.load(id).await?))
+    }
+
+    /// Update a memory
+    ///
+    /// Content is validated like on save, but oversized content is never split here.
+    pub async fn update(&self, memory: &Memory) -> Result<()> {
+        let mut memory = memory.clone();
+        self.middleware.before_save(&mut memory).await?;
+        memory.content = self.validation.apply_single(&memory.content)?;
+        let memory = &memory;
+
+        self.backend().update_memory(memory).await?;
+        if self.backend.is_some() {
+            self.store.update(memory).await?;
+        }
+        self.store
+            .record_version(memory, VersionAuthor::Agent, "updated")
+            .await?;
+        let quality = self.assess_quality(memory).await?;
+        self.store.set_quality(&memory.id, &quality).await?;
+        self.search.index_memory(memory)?;
+
+        self.queue_vector(memory).await?;
+
+        self.middleware.after_save(memory).await
+    }
+
+    /// Delete a memory. Its version history is kept, ending in a forgotten
+    /// snapshot, so [`load_as_of`](Self::load_as_of) still sees earlier states.
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        if let Some(mut memory) = self.backend().load_memory(id).await? {
+            memory.forgotten = true;
+            memory.updated_at = self.store.now();
+            self.store
+                .record_version(&memory, VersionAuthor::Agent, "deleted")
+                .await?;
+        }
+        self.backend().delete_memory(id).await?;
+        if self.backend.is_some() {
+            self.store.delete(id).await?;
+        }
+        self.search.delete_memory(id)?;
+        self.delete_vectors(id).await?;
+
+        Ok(())
+    }
+
+    /// Soft delete (forget) a memory
+    pub async fn forget(&self, id: &str) -> Result<bool> {
+        let forgotten = self.backend().forget_memory(id).await?;
+        if self.backend.is_some() {
+            self.store.forget(id).await?;
+        }
+        if forgotten {
+            self.record_current_version(id, "forgotten").await?;
+        }
+        Ok(forgotten)
+    }
+
+    /// Forget every active memory matching `query` in one transaction,
+    /// returning the IDs forgotten. With `dry_run` nothing changes and the IDs
+    /// are the ones that would be forgotten.
+    pub async fn forget_where(&self, query: &MemoryQuery, dry_run: bool) -> Result<Vec<MemoryId>> {
+        let ids = self.store.forget_where(query, dry_run).await?;
+        if dry_run {
+            return Ok(ids);
+        }
+        for id in &ids {
+            if self.backend.is_some() {
+                self.backend().forget_memory(id).await?;
+            }
+            self.record_current_version(id, "forgotten").await?;
+        }
+        Ok(ids)
+    }
+
+    /// Change a memory's type, returning whether it exists. The text index is
+    /// updated, and the vector moves when the new type routes to another backend.
+    pub async fn retype(&self, id: &str, memory_type: MemoryType) -> Result<bool> {
+        let Some(mut memory) = self.backend().load_memory(id).await? else {
+            return Ok(false);
+        };
+        let previous = memory.memory_type;
+        if previous == memory_type {
+            return Ok(true);
+        }
+        memory.memory_type = memory_type;
+        memory.updated_at = self.store.now();
+
+        self.backend().update_memory(&memory).await?;
+        if self.backend.is_some() {
+            self.store.update(&memory).await?;
+        }
+        self.record_edit(&memory, "retyped").await?;
+
+        let old_route = self
+            .vector_route(Some(previous))
+            .map(|(v, _)| Arc::clone(v));
+        let new_route = self
+            .vector_route(Some(memory_type))
+            .map(|(v, _)| Arc::clone(v));
+        if let Some(old) = old_route {
+            if !new_route.is_some_and(|new| Arc::ptr_eq(&old, &new)) {
+                for vector_id in self.vector_ids(id).await? {
+                    old.delete(&vector_id).await?;
+                }
+            }
+        }
+        // Upserted on an unchanged backend too: the payload carries the type
+        self.upsert_vector(&memory).await?;
+        Ok(true)
+    }
+
+    /// Add `tag` to every memory matching `query` in one transaction,
+    /// returning the IDs of the memories that changed
+    pub async fn add_tag_where(&self, query: &MemoryQuery, tag: &str) -> Result<Vec<MemoryId>> {
+        let changed = self.store.add_tag_where(query, tag).await?;
+        self.finish_bulk_edit(changed, "tag added").await
+    }
+
+    /// Remove `tag` from every memory matching `query` in one transaction,
+    /// returning the IDs of the memories that changed
+    pub async fn remove_tag_where(&self, query: &MemoryQuery, tag: &str) -> Result<Vec<MemoryId>> {
+        let changed = self.store.remove_tag_where(query, tag).await?;
+        self.finish_bulk_edit(changed, "tag removed").await
+    }
+
+    /// Set metadata `key` to `value`, or remove it when `None`, on every memory
+    /// matching `query` in one transaction, returning the IDs that changed
+    pub async fn set_metadata_where(
+        &self,
+        query: &MemoryQuery,
+        key: &str,
+        value: Option<serde_json::Value>,
+    ) -> Result<Vec<MemoryId>> {
+        let changed = self.store.set_metadata_where(query, key, value).await?;
+        self.finish_bulk_edit(changed, "metadata edited").await
+    }
+
+    /// Mirror bulk-edited memories to the storage backend, then version and
+    /// reindex them, refreshing the tags and metadata in their vector payloads
+    async fn finish_bulk_edit(&self, changed: Vec<Memory>, reason: &str) -> Result<Vec<MemoryId>> {
+        for memory in &changed {
+            if self.backend.is_some() {
+                self.backend().update_memory(memory).await?;
+            }
+            self.record_edit(memory, reason).await?;
+            self.queue_vector(memory).await?;
+        }
+        Ok(changed.into_iter().map(|m| m.id).collect())
+    }
+
+    /// Record a version of an edited memory and refresh its text index entry
+    async fn record_edit(&self, memory: &Memory, reason: &str) -> Result<()> {
+        self.store
+            .record_version(memory, VersionAuthor::Agent, reason)
+            .await?;
+        self.search.index_memory(memory)
+    }
+
+    /// Restore a forgotten memory
+    pub async fn restore(&self, id: &str) -> Result<bool> {
+        let restored = self.backend().restore_memory(id).await?;
+        if self.backend.is_some() {
+            self.store.restore(id).await?;
+        }
+        if restored {
+            self.record_current_version(id, "restored").await?;
+        }
+        Ok(restored)
+    }
+
+    async fn record_current_version(&self, id: &str, reason: &str) -> Result<()> {
+        if let Some(memory) = self.backend().load_memory(id).await? {
+            self.store
+                .record_version(&memory, VersionAuthor::Agent, reason)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Version history of a memory, oldest first. Saves, updates, forgets, restores
+    /// and deletes through `MemorySystem` each record a version.
+    pub async fn history(&self, id: &str) -> Result<Vec<MemoryVersion>> {
+        self.store.memory_versions(id).await
+    }
+
+    /// A copy-on-write overlay for speculative changes. Nothing reaches this
+    /// memory system until [`MemorySandbox::commit`].
+    pub fn sandbox(&self) -> MemorySandbox {
+        MemorySandbox::new(self.clone())
+    }
+
+    /// Why the agent believes a memory: its causes, corroborating and contradicting
+    /// memories, the beliefs it superseded and its own revisions.
+    /// Returns `None` if the memory does not exist.
+    pub async fn explain_belief(&self, id: &str) -> Result<Option<BeliefExplanation>> {
+        provenance::explain_belief(&self.store, id, DEFAULT_PROVENANCE_DEPTH).await
+    }
+
+    /// Everything that happened to a memory across versions, episodes,
+    /// associations, accesses and citations, oldest first.
+    /// Returns `None` if the memory does not exist.
+    pub async fn get_memory_timeline(&self, id: &str) -> Result<Option<MemoryTimeline>> {
+        timeline::memory_timeline(&self.store, id).await
+    }
+
+    /// Scan for duplicates, contradictions, stale, huge and low-confidence memories
+    /// and dangling associations, each with a suggested fix
+    pub async fn hygiene_report(&self) -> Result<HygieneReport> {
+        self.hygiene_report_with(&HygieneConfig::default()).await
+    }
+
+    /// [`hygiene_report`](Self::hygiene_report) with custom thresholds
+    pub async fn hygiene_report_with(&self, config: &HygieneConfig) -> Result<HygieneReport> {
+        hygiene::hygiene_report(&self.store, config).await
+    }
+
+    /// Track a source document and save each of its sections as a memory of
+    /// `memory_type`. Ingesting a URI that is already tracked re-syncs it.
+    pub async fn ingest_source(
+        &self,
+        uri: &str,
+        content: &str,
+        memory_type: MemoryType,
+    ) -> Result<SourceSync> {
+        sources::sync_source(self, uri, content, Some(memory_type)).await
+    }
+
+    /// Re-sync a tracked source after it changed. Sections that are unchanged keep
+    /// their memory; new and edited sections are saved as new memories and the
+    /// memories they replace are forgotten. Does nothing if the content hash is
+    /// unchanged. Fails with [`MemoryError::NotFound`] for an unknown source.
+    pub async fn resync_source(&self, uri: &str, new_content: &str) -> Result<SourceSync> {
+        sources::sync_source(self, uri, new_content, None).await
+    }
+
+    /// A tracked source document
+    pub async fn source(&self, uri: &str) -> Result<Option<SourceDocument>> {
+        self.store.get_source(uri).await
+    }
+
+    /// All tracked source documents
+    pub async fn sources(&self) -> Result<Vec<SourceDocument>> {
+        self.store.list_sources().await
+    }
+
+    /// Current memories derived from a source, in document order
+    pub async fn source_memories(&self, uri: &str) -> Result<Vec<Memory>> {
+        Ok(self
+            .store
+            .source_memories(uri)
+            .await?
+            .into_iter()
+            .map(|(_, memory)| memory)
+            .collect())
+    }
+
+    /// A memory as it was at `at`, reconstructed from its version history.
+    ///
+    /// Returns `None` if the memory did not exist yet, or was forgotten or deleted
+    /// at that time. Changes made before version history was recorded, or directly
+    /// through the store, are not visible.
+    pub async fn load_as_of(
+        &self,
+        id: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Memory>> {
+        Ok(self
+            .store
+            .version_as_of(id, at)
+            .await?
+            .map(|v| v.memory)
+            .filter(|m| !m.forgotten))
+    }
+
+    /// Full-text search over the memories as they were at `at`: what the agent
+    /// believed then. Recency boosting is not applied.
+    pub async fn search_as_of(
+        &self,
+        query: &str,
+        at: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let memories: Vec<Memory> = self
+            .store
+            .versions_as_of(at)
+            .await?
+            .into_iter()
+            .map(|v| v.memory)
+            .filter(|m| !m.forgotten)
+            .collect();
+        let config = SearchConfig {
+            max_results: limit,
+            boost_recent: false,
+            ..SearchConfig::default()
+        };
+        self.search.search_snapshot(query, &config, &memories)
+    }
+
+    /// Persistently pin a memory so maintenance never prunes or consolidates it
+    pub async fn pin(&self, id: &str) -> Result<bool> {
+        self.set_pinned(id, true).await
+    }
+
+    /// Remove a persistent pin
+    pub async fn unpin(&self, id: &str) -> Result<bool> {
+        self.set_pinned(id, false).await
+    }
+
+    async fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool> {
+        if self.backend.is_some() {
+            let Some(mut memory) = self.backend().load_memory(id).await? else {
+                return Ok(false);
+            };
+            memory.pinned = pinned;
+            memory.updated_at = self.store.now();
+            self.backend().update_memory(&memory).await?;
+        }
+        self.store.set_pinned(id, pinned).await
+    }
+
+    /// Get pinned memories
+    pub async fn get_pinned(&self, limit: i64) -> Result<Vec<Memory>> {
+        self.store.get_pinned(limit).await
+    }
+
+    /// Search memories (simple text match for now)
+    pub async fn search(&self, query: &str) -> Result<Vec<MemorySearchResult>> {
+        let started = std::time::Instant::now();
+        let config = SearchConfig::default();
+        let query = self.middleware.before_recall(query).await?;
+        let results = self.search.search(&query, &config).await?;
+        let results = self.screen_results(results, |r| &mut r.memory).await?;
+        let memories = results.iter().map(|r| &r.memory);
+        self.log_query("search", &query, &config, started, memories, false)
+            .await;
+        Ok(results)
+    }
+
+    /// [`search`](Self::search) with the generation it was read at
+    pub async fn search_with_generation(
+        &self,
+        query: &str,
+    ) -> Result<Generational<Vec<MemorySearchResult>>> {
+        let generation = self.store.generation().await?;
+        Ok(Generational::new(generation, self.search(query).await?))
+    }
+
+    /// Log searches to the `query_log` table, sampled per `config`; see
+    /// [`slow_queries`](Self::slow_queries)
+    pub fn with_query_log(mut self, config: QueryLogConfig) -> Self {
+        self.query_log = Some(Arc::new(QueryLogger::new(config)));
+        self
+    }
+
+    /// Record a finished search if the query log samples it. Logging failures
+    /// are only traced; they never fail the search.
+    async fn log_query<'a>(
+        &self,
+        kind: &str,
+        query: &str,
+        config: &impl serde::Serialize,
+        started: std::time::Instant,
+        results: impl ExactSizeIterator<Item = &'a Memory>,
+        timed_out: bool,
+    ) {
+        let Some(logger) = &self.query_log else {
+            return;
+        };
+        let latency = started.elapsed();
+        let result_count = results.len();
+        if !logger.should_log(latency, result_count) {
+            return;
+        }
+        let entry = QueryLogEntry {
+            query: query.to_string(),
+            kind: kind.to_string(),
+            config_hash: query_log::config_hash(config),
+            latency_ms: latency.as_secs_f64() * 1000.0,
+            result_count,
+            top_ids: results
+                .take(logger.config().top_ids)
+                .map(|m| m.id.clone())
+                .collect(),
+            timed_out,
+            logged_at: self.store.now(),
+        };
+        if let Err(e) = self.store.log_query(&entry).await {
+            tracing::warn!("Failed to write query log entry: {e}");
+        }
+    }
+
+    /// Per-query latency and emptiness over the query log of the last `since`,
+    /// slowest p95 first
+    pub async fn slow_queries(&self, since: chrono::Duration) -> Result<Vec<QueryStats>> {
+        let entries = self
+            .store
+            .query_log_since(self.store.now() - since, 100_000)
+            .await?;
+        Ok(query_log::analyze(&entries))
+    }
+
+    /// Search with custom configuration. If `config.timeout` expires, whatever was
+    /// found so far is returned; use [`search_within`](Self::search_within) to learn
+    /// whether that happened.
+    pub async fn search_with_config(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let deadline = Deadline::from_timeout(config.timeout);
+        let found = self.search_within(query, config, &deadline).await?;
+        if found.timed_out {
+            tracing::warn!("Search for {query:?} timed out");
+        }
+        Ok(found.results)
+    }
+
+    /// Search until `deadline` expires or is cancelled, then give up with
+    /// `timed_out` set instead of waiting
+    pub async fn search_within(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+        deadline: &Deadline,
+    ) -> Result<TimedResults<MemorySearchResult>> {
+        let started = std::time::Instant::now();
+        let query = self.middleware.before_recall(query).await?;
+        let mut found = match deadline.run(self.search.search(&query, config)).await {
+            Some(results) => TimedResults::complete(results?),
+            None => TimedResults::partial(Vec::new()),
+        };
+        found.results = self
+            .screen_results(found.results, |r| &mut r.memory)
+            .await?;
+        self.log_query(
+            "search",
+            &query,
+            config,
+            started,
+            found.results.iter().map(|r| &r.memory),
+            found.timed_out,
+        )
+        .await;
+        Ok(found)
+    }
+
+    /// Search, retrying with `config.fallbacks` while nothing matches. The outcome
+    /// records which fallback produced the results, if any, and whether
+    /// `config.timeout` cut the retries short.
+    pub async fn search_with_fallback(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<SearchOutcome> {
+        let started = std::time::Instant::now();
+        let mut outcome = self.run_search_with_fallback(query, config).await?;
+        outcome.results = self
+            .screen_results(outcome.results, |r| &mut r.memory)
+            .await?;
+        let memories = outcome.results.iter().map(|r| &r.memory);
+        self.log_query(
+            "fallback",
+            query,
+            config,
+            started,
+            memories,
+            outcome.timed_out,
+        )
+        .await;
+        Ok(outcome)
+    }
+
+    async fn run_search_with_fallback(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<SearchOutcome> {
+        let deadline = Deadline::from_timeout(config.timeout);
+        let timed_out = SearchOutcome {
+            timed_out: true,
+            ..SearchOutcome::default()
+        };
+
+        let query = self.middleware.before_recall(query).await?;
+        let Some(results) = deadline.run(self.search.search(&query, config)).await else {
+            return Ok(timed_out);
+        };
+        let results = results?;
+        if !results.is_empty() || config.mode != SearchMode::FullText {
+            return Ok(SearchOutcome {
+                results,
+                fallback: None,
+                timed_out: false,
+            });
+        }
+
+        for &fallback in &config.fallbacks {
+            let attempt = async {
+                match fallback {
+                    SearchFallback::VectorOnly => self.search_vector_only(&query, config).await,
+                    _ => self.search.search_fallback(&query, config, fallback).await,
+                }
+            };
+            let Some(results) = deadline.run(attempt).await else {
+                return Ok(timed_out);
+            };
+            let results = results?;
+            if !results.is_empty() {
+                return Ok(SearchOutcome {
+                    results,
+                    fallback: Some(fallback),
+                    timed_out: false,
+                });
+            }
+        }
+
+        Ok(SearchOutcome::default())
+    }
+
+    /// Semantic search over the vector backends alone. Empty without a backend.
+    async fn search_vector_only(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let mut filter = VectorFilter::new().with_namespace(self.store.namespace());
+        if let Some(memory_type) = config.memory_type {
+            filter = filter.with_memory_type(memory_type);
+        }
+        let limit = config.max_results.saturating_mul(4).max(config.max_results);
+
+        let mut hits = Vec::new();
+        for (vector, embedder) in self.search_routes(config.memory_type)? {
+            let embedded = embedder
+                .embed(&[query.to_string()])
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+            let Some(query_vector) = embedded.first() else {
+                continue;
+            };
+            hits.extend(vector.search_filtered(query_vector, limit, &filter).await?);
+        }
+        let mut results = Vec::new();
+        for hit in vector_chunks::aggregate_chunk_hits(hits) {
+            let Some(memory) = self.backend().load_memory(&hit.memory_id).await? else {
+                continue;
+            };
+            if memory.forgotten
+                || !config.matches_valence(&memory)
+                || !config.matches_language(&memory)
+                || !config.matches_numeric(&memory)
+                || !config.matches_session(&memory)
+                || config.memory_type.is_some_and(|t| memory.memory_type != t)
+            {
+                continue;
+            }
+            let score = hit.score * config.valence_factor(&memory);
+            results.push(MemorySearchResult {
+                memory,
+                score,
+                rank: 0,
+            });
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(config.max_results);
+        for (i, r) in results.iter_mut().enumerate() {
+            r.rank = i + 1;
+        }
+        Ok(results)
+    }
+
+    /// Save a standing query that `watch_saved_searches` evaluates against every
+    /// new memory. Fails if the name is taken or the query does not parse.
+    pub async fn create_saved_search(
+        &self,
+        name: &str,
+        query: &str,
+        config: SearchConfig,
+    ) -> Result<SavedSearch> {
+        self.save_search(SavedSearch::new(name, query, config))
+            .await
+    }
+
+    /// Persist a fully built saved search, e.g. one with a webhook
+    pub async fn save_search(&self, search: SavedSearch) -> Result<SavedSearch> {
+        self.search.validate_query(&search.query, &search.config)?;
+        self.store.create_saved_search(&search).await?;
+        Ok(search)
+    }
+
+    /// All saved searches
+    pub async fn saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        self.store.list_saved_searches().await
+    }
+
+    /// Set or clear the webhook called for hits of a saved search
+    pub async fn set_saved_search_webhook(&self, name: &str, url: Option<&str>) -> Result<()> {
+        if !self.store.set_saved_search_webhook(name, url).await? {
+            return Err(MemoryError::NotFound(format!("saved search '{name}'")));
+        }
+        Ok(())
+    }
+
+    /// Delete a saved search. Returns `false` if it did not exist.
+    pub async fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        self.store.delete_saved_search(name).await
+    }
+
+    /// Open a session; save memories with its ID (see
+    /// [`Memory::with_session_id`]) to record them under it
+    pub async fn start_session(&self, metadata: Option<serde_json::Value>) -> Result<Session> {
+        let session = Session {
+            id: self.store.next_id(),
+            metadata,
+            started_at: self.store.now(),
+            ended_at: None,
+            summary: None,
+        };
+        self.store.create_session(&session).await?;
+        Ok(session)
+    }
+
+    /// Close a session and store a summary of the memories saved in it
+    pub async fn end_session(&self, id: &str) -> Result<SessionInfo> {
+        let info = self
+            .store
+            .get_session(id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(format!("session '{id}'")))?;
+        if !info.session.is_active() {
+            return Err(MemoryError::InvalidOperation(format!(
+                "Session '{id}' has already ended"
+            )));
+        }
+
+        let memories = self
+            .store
+            .query(&MemoryQuery::new().with_session(id), i64::MAX)
+            .await?;
+        let summary = session::summarize_session(&memories);
+        self.store
+            .end_session(id, self.store.now(), summary.as_deref())
+            .await?;
+
+        self.store
+            .get_session(id)
+            .await?
+            .ok_or_else(|| MemoryError::NotFound(format!("session '{id}'")))
+    }
+
+    /// A session with its memory count and time span
+    pub async fn get_session(&self, id: &str) -> Result<Option<SessionInfo>> {
+        self.store.get_session(id).await
+    }
+
+    /// Sessions with their memory counts and time spans, most recently started first
+    pub async fn list_sessions(&self, limit: i64, offset: i64) -> Result<Vec<SessionInfo>> {
+        self.store.list_sessions(limit, offset).await
+    }
+
+    /// Search memories of `session_id` plus durable memories (those saved
+    /// outside any session)
+    pub async fn search_session(
+        &self,
+        query: &str,
+        session_id: &str,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let config = SearchConfig::default().with_session(session_id);
+        self.search_with_config(query, &config).await
+    }
+
+    /// Evaluate every saved search against `memory`. Each match is counted, emitted
+    /// as a [`Pulse::SavedSearchHit`] and sent to the search's webhook, if any.
+    pub async fn evaluate_saved_searches(&self, memory: &Memory) -> Result<Vec<SavedSearchHit>> {
+        let mut hits = Vec::new();
+        for search in self.store.list_saved_searches().await? {
+            let score = match self
+                .search
+                .match_memory(&search.query, &search.config, memory)
+            {
+                Ok(Some(score)) => score,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Saved search '{}' failed: {}", search.name, e);
+                    continue;
+                }
+            };
+
+            let hit = SavedSearchHit {
+                search_id: search.id.clone(),
+                search_name: search.name.clone(),
+                query: search.query.clone(),
+                memory: memory.clone(),
+                score,
+                matched_at: self.store.now(),
+            };
+            self.store
+                .record_saved_search_hit(&search.id, hit.matched_at)
+                .await?;
+            self.pulses
+                .emit(Pulse::SavedSearchHit {
+                    search_id: hit.search_id.clone(),
+                    search_name: hit.search_name.clone(),
+                    memory: memory.clone(),
+                    score,
+                    timestamp: hit.matched_at,
+                })
+                .await;
+            if let Some(url) = search.webhook_url {
+                saved_search::spawn_webhook(url, hit.clone());
+            }
+            hits.push(hit);
+        }
+        Ok(hits)
+    }
+
+    /// Evaluate saved searches on every [`Pulse::NewMemory`] from this system, in a
+    /// background task. Abort the returned handle to stop watching.
+    pub fn watch_saved_searches(&self) -> tokio::task::JoinHandle<()> {
+        // The clone shares the index reader, so a memory is matchable by the
+        // time its pulse arrives here
+        let system = self.clone();
+        let mut receiver = self.pulses.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(Pulse::NewMemory { memory, .. }) => {
+                        if let Err(e) = system.evaluate_saved_searches(&memory).await {
+                            tracing::warn!("Saved search evaluation failed: {}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!("Saved search watcher missed {} pulses", missed);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Get memories by type
+    pub async fn get_by_type(&self, memory_type: MemoryType, limit: i64) -> Result<Vec<Memory>> {
+        self.backend().get_by_type(memory_type, limit).await
+    }
+
+    /// Memories whose ID starts with `prefix`; see [`MemoryStore::find_by_id_prefix`]
+    pub async fn find_by_id_prefix(&self, prefix: &str, limit: i64) -> Result<Vec<Memory>> {
+        self.store.find_by_id_prefix(prefix, limit).await
+    }
+
+    /// One page of memories, newest first; see [`MemoryStore::list_page`]
+    pub async fn list_page(&self, cursor: Option<&str>, page_size: usize) -> Result<MemoryPage> {
+        self.store.list_page(cursor, page_size).await
+    }
+
+    /// Uniform random sample of up to `n` memories, for quality review
+    pub async fn sample(&self, n: usize, filter: &SampleFilter) -> Result<Vec<Memory>> {
+        self.store.sample(n, filter).await
+    }
+
+    /// Random sample of up to `n` memories with every memory type represented
+    pub async fn sample_stratified(&self, n: usize, filter: &SampleFilter) -> Result<Vec<Memory>> {
+        self.store.sample_stratified(n, filter).await
+    }
+
+    /// Get high-importance memories
+    pub async fn get_high_importance(&self, threshold: f32, limit: i64) -> Result<Vec<Memory>> {
+        self.store.get_high_importance(threshold, limit).await
+    }
+
+    /// Create an association between memories
+    pub async fn associate(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation_type: RelationType,
+    ) -> Result<()> {
+        let association = Association::new(source_id, target_id, relation_type);
+        self.store_association(&association).await
+    }
+
+    /// Get associations for a memory
+    pub async fn get_associations(&self, memory_id: &str) -> Result<Vec<Association>> {
+        self.backend().get_associations(memory_id).await
+    }
+
+    /// Get memory neighbors in the graph
+    pub async fn get_neighbors(
+        &self,
+        memory_id: &str,
+        depth: u32,
+    ) -> Result<(Vec<Memory>, Vec<Association>)> {
+        self.backend().get_neighbors(memory_id, depth, &[]).await
+    }
+
+    /// Record which memories were used to produce a generated answer.
+    ///
+    /// Citing is usage feedback: each newly cited memory counts as an access
+    /// and gets a small importance boost. Citing the same memory twice for one
+    /// answer is a no-op. The citations and the updated counters are written
+    /// in one transaction. Returns the number of memories newly cited.
+    pub async fn record_citations(
+        &self,
+        answer_id: &str,
+        memory_ids: &[MemoryId],
+    ) -> Result<usize> {
+        const IMPORTANCE_BOOST: f32 = 0.02;
+
+        let mut memories = Vec::new();
+        for id in memory_ids {
+            if let Some(memory) = self.backend().load_memory(id).await? {
+                memories.push(memory);
+            }
+        }
+
+        let mut cited = Vec::new();
+        let mut tx = self.store.begin().await?;
+        for mut memory in memories {
+            if !tx.record_citation(answer_id, &memory.id).await? {
+                continue;
+            }
+
+            let now = self.store.now();
+            memory.access_count += 1;
+            memory.last_accessed_at = now;
+            memory.importance = (memory.importance + IMPORTANCE_BOOST).min(1.0);
+            memory.updated_at = now;
+            tx.update(&memory).await?;
+            cited.push(memory);
+        }
+        tx.commit().await?;
+
+        for memory in &cited {
+            if self.backend.is_some() {
+                self.backend().update_memory(memory).await?;
+            }
+            self.search.index_memory(memory)?;
+        }
+        Ok(cited.len())
+    }
+
+    /// Get the most frequently cited memories
+    pub async fn most_cited(&self, limit: i64) -> Result<Vec<CitedMemory>> {
+        self.store.most_cited(limit).await
+    }
+
+    /// Run maintenance tasks, including vector backend upkeep when enabled.
+    /// Memories evicted by a [`StoreQuota`] are emitted as
+    /// [`Pulse::MemoryEvicted`]. Forgotten memories purged by
+    /// `forgotten_retention_days` are also dropped from the text index and
+    /// every vector backend.
+    ///
+    /// The resource profile's throttle applies when `config` sets none.
+    pub async fn run_maintenance(&self, config: &MaintenanceConfig) -> Result<MaintenanceReport> {
+        let mut config = config.clone();
+        config.throttle = config.throttle.or(self.profile.maintenance_throttle());
+        let mut report =
+            maintenance::run_maintenance_with(&self.store, &config, &IndexCleanup(self)).await?;
+        for eviction in report.quota.iter().flat_map(|q| &q.evictions) {
+            self.pulses
+                .emit(Pulse::MemoryEvicted {
+                    memory_id: eviction.memory_id.clone(),
+                    memory_type: eviction.memory_type,
+                    importance: eviction.importance,
+                    timestamp: self.store.now(),
+                })
+                .await;
+        }
+        if config.enable_vector_maintenance {
+            report.vector = self.maintain_vectors().await?;
+        }
+        Ok(report)
+    }
+
+    /// The default vector backend followed by any per-type backends
+    fn vector_backends(&self) -> Vec<&Arc<dyn VectorBackend>> {
+        self.vector
+            .iter()
+            .chain(self.type_vectors.values().map(|(vector, _)| vector))
+            .collect()
+    }
+
+    /// Layout statistics (row counts, fragmentation) for every attached vector backend
+    pub async fn vector_stats(&self) -> Result<Vec<VectorIndexStats>> {
+        let mut stats = Vec::new();
+        for vector in self.vector_backends() {
+            stats.push(vector.stats().await?);
+        }
+        Ok(stats)
+    }
+
+    /// Compact and optimize every attached vector backend
+    pub async fn maintain_vectors(&self) -> Result<Vec<VectorMaintenanceReport>> {
+        let mut reports = Vec::new();
+        for vector in self.vector_backends() {
+            reports.push(vector.maintain().await?);
+        }
+        Ok(reports)
+    }
+
+    /// Rebuild the index structures of every attached vector backend
+    pub async fn rebuild_vectors(&self) -> Result<Vec<VectorMaintenanceReport>> {
+        let mut reports = Vec::new();
+        for vector in self.vector_backends() {
+            reports.push(vector.rebuild().await?);
+        }
+        Ok(reports)
+    }
+
+    /// Snapshot the running system into `path`, which must be missing or empty.
+    ///
+    /// The database is copied consistently while writes continue; the manifest
+    /// and vector index directories of the data directory are copied next to
+    /// it, so the backup opens with [`new`](Self::new) as a data directory of
+    /// its own. The text index is not copied, since [`new`](Self::new) rebuilds
+    /// it from the database. Vector backends outside the data directory are not
+    /// copied; [`restore_from`](Self::restore_from) re-embeds into them.
+    pub async fn backup_to(&self, path: impl AsRef<Path>) -> Result<BackupReport> {
+        self.ensure_local_backend("backup")?;
+        let path = path.as_ref();
+        backup::ensure_empty_target(path)?;
+        std::fs::create_dir_all(path)?;
+
+        let database = path.join(backup::DATABASE_FILE);
+        self.store.backup_into(&database).await?;
+        let database_bytes = std::fs::metadata(&database)?.len();
+
+        let mut copied = Vec::new();
+        let mut total_bytes = database_bytes;
+        if let Some(data_dir) = &self.data_dir {
+            for dir in backup::INDEX_DIRS {
+                let from = data_dir.join(dir);
+                if from.is_dir() {
+                    total_bytes += backup::copy_dir(&from, &path.join(dir))?;
+                    copied.push(dir.to_string());
+                }
+            }
+        }
+        self.manifest().save(path)?;
+        copied.push(MANIFEST_FILE.to_string());
+
+        Ok(BackupReport {
+            path: path.to_path_buf(),
+            database_bytes,
+            copied,
+            total_bytes,
+        })
+    }
+
+    /// Replace everything in the running system with the backup at `path`.
+    ///
+    /// The database is restored in one transaction (all namespaces). The index
+    /// directories of a live system are in use, so instead of copying them the
+    /// text index is rebuilt and every restored memory is re-embedded; vectors
+    /// of memories that are not in the backup are removed.
+    pub async fn restore_from(&self, path: impl AsRef<Path>) -> Result<RestoreReport> {
+        self.ensure_local_backend("restore")?;
+        let previous = self.active_memories().await?;
+        // Chunk offsets are replaced along with the database
+        let mut previous_vectors = std::collections::HashMap::new();
+        for memory in &previous {
+            previous_vectors.insert(memory.id.clone(), self.vector_ids(&memory.id).await?);
+        }
+
+        self.store
+            .restore_from_snapshot(path.as_ref().join(backup::DATABASE_FILE))
+            .await?;
+        let reindexed = self.search.reindex_all().await?;
+        let restored = self.active_memories().await?;
+        self.analytics.invalidate().await;
+
+        let mut report = RestoreReport {
+            memories: restored.len(),
+            reindexed,
+            ..Default::default()
+        };
+        for memory in &restored {
+            if self.vector_route(Some(memory.memory_type)).is_some() {
+                self.upsert_vector(memory).await?;
+                report.vectors_upserted += 1;
+            }
+        }
+        let kept: std::collections::HashSet<&str> =
+            restored.iter().map(|m| m.id.as_str()).collect();
+        for memory in previous.iter().filter(|m| !kept.contains(m.id.as_str())) {
+            for vector_id in previous_vectors.get(&memory.id).into_iter().flatten() {
+                for vector in self.vector_backends() {
+                    vector.delete(vector_id).await?;
+                }
+            }
+            report.vectors_removed += 1;
+        }
+
+        Ok(report)
+    }
+
+    // ─── Archives ─────────────────────────────────────────────────────────
+
+    /// Write the whole namespace (memories, associations, experiences,
+    /// summaries and vectors) to a single portable archive at `path`, see
+    /// [`archive`]
+    pub async fn export_archive(&self, path: impl AsRef<Path>) -> Result<ArchiveReport> {
+        use tokio::io::AsyncWriteExt;
+
+        self.ensure_local_backend("export_archive")?;
+        let path = path.as_ref();
+        let mut out = tokio::io::BufWriter::new(tokio::fs::File::create(path).await?);
+        let mut report = ArchiveReport::default();
+
+        archive::write_line(&mut out, &ArchiveHeader::new(self.store.namespace())).await?;
+        let memories = self.store.all_memories().await?;
+        for memory in &memories {
+            archive::write_line(&mut out, &ArchiveRecord::Memory(Box::new(memory.clone()))).await?;
+            report.memories += 1;
+        }
+        for association in self.store.all_associations().await? {
+            archive::write_line(&mut out, &ArchiveRecord::Association(association)).await?;
+            report.associations += 1;
+        }
+        for experience in self.store.list_experiences(i64::MAX, 0).await? {
+            archive::write_line(&mut out, &ArchiveRecord::Experience(experience)).await?;
+            report.experiences += 1;
+        }
+        for summary in self.store.get_summaries().await? {
+            archive::write_line(&mut out, &ArchiveRecord::Summary(summary)).await?;
+            report.summaries += 1;
+        }
+
+        for memory in memories.iter().filter(|m| !m.forgotten) {
+            let Some((_, embedder)) = self.vector_route(Some(memory.memory_type)) else {
+                continue;
+            };
+            let vectors = embedder
+                .embed(std::slice::from_ref(&memory.content))
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+            if let Some(vector) = vectors.into_iter().next() {
+                let record = ArchiveRecord::Vector {
+                    memory_id: memory.id.clone(),
+                    provider: embedder.name().to_string(),
+                    vector,
+                };
+                archive::write_line(&mut out, &record).await?;
+                report.vectors += 1;
+            }
+        }
+
+        out.flush().await?;
+        report.bytes = tokio::fs::metadata(path).await?.len();
+        Ok(report)
+    }
+
+    /// Write an archive like [`export_archive`](Self::export_archive), with
+    /// every text rewritten by `anonymizer` and without vectors, which would
+    /// give the original text away. The anonymizer first learns the terms of
+    /// the whole namespace; see [`anonymize`].
+    pub async fn export_anonymized(
+        &self,
+        path: impl AsRef<Path>,
+        anonymizer: &mut Anonymizer,
+    ) -> Result<ArchiveReport> {
+        use tokio::io::AsyncWriteExt;
+
+        self.ensure_local_backend("export_anonymized")?;
+        let memories = self.store.all_memories().await?;
+        let associations = self.store.all_associations().await?;
+        let experiences = self.store.list_experiences(i64::MAX, 0).await?;
+        let summaries = self.store.get_summaries().await?;
+        for memory in &memories {
+            anonymizer.learn_memory(memory);
+        }
+        for experience in &experiences {
+            anonymizer.learn(&experience.title);
+            anonymizer.learn(&experience.context);
+            if let Some(summary) = &experience.summary {
+                anonymizer.learn(summary);
+            }
+        }
+        for summary in &summaries {
+            anonymizer.learn(&summary.summary_text);
+        }
+
+        let path = path.as_ref();
+        let mut out = tokio::io::BufWriter::new(tokio::fs::File::create(path).await?);
+        let mut report = ArchiveReport::default();
+        archive::write_line(&mut out, &ArchiveHeader::new(self.store.namespace())).await?;
+        for memory in &memories {
+            let record = ArchiveRecord::Memory(Box::new(anonymizer.memory(memory)));
+            archive::write_line(&mut out, &record).await?;
+            report.memories += 1;
+        }
+        for association in associations {
+            archive::write_line(&mut out, &ArchiveRecord::Association(association)).await?;
+            report.associations += 1;
+        }
+        for experience in &experiences {
+            let record = ArchiveRecord::Experience(anonymizer.experience(experience));
+            archive::write_line(&mut out, &record).await?;
+            report.experiences += 1;
+        }
+        for summary in &summaries {
+            let record = ArchiveRecord::Summary(anonymizer.summary(summary));
+            archive::write_line(&mut out, &record).await?;
+            report.summaries += 1;
+        }
+
+        out.flush().await?;
+        report.bytes = tokio::fs::metadata(path).await?.len();
+        Ok(report)
+    }
+
+    /// Load an archive written by [`export_archive`](Self::export_archive)
+    /// into this system's namespace, then rebuild the text index and vectors.
+    /// Records whose ID already exists are skipped, so importing twice is
+    /// harmless.
+    pub async fn import_archive(&self, path: impl AsRef<Path>) -> Result<ImportReport> {
+        use tokio::io::AsyncBufReadExt;
+
+        self.ensure_local_backend("import_archive")?;
+        let file = tokio::fs::File::open(path.as_ref()).await?;
+        let mut lines = tokio::io::BufReader::new(file).lines();
+        let header = ArchiveHeader::parse(&lines.next_line().await?.unwrap_or_default())?;
+        tracing::info!(
+            "Importing archive of namespace '{}' written by goldfish {}",
+            header.namespace,
+            header.goldfish_version
+        );
+
+        let existing: std::collections::HashSet<MemoryId> = self
+            .store
+            .all_memories()
+            .await?
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        let mut report = ImportReport::default();
+        let mut imported = Vec::new();
+        let mut experiences = Vec::new();
+        let mut summaries = Vec::new();
+        let mut vectors = std::collections::HashMap::new();
+
+        let mut tx = self.store.begin().await?;
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: ArchiveRecord = serde_json::from_str(&line)
+                .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+            match record {
+                ArchiveRecord::Memory(memory) if existing.contains(&memory.id) => {
+                    report.skipped += 1;
+                }
+                ArchiveRecord::Memory(memory) => {
+                    tx.save(&memory).await?;
+                    imported.push(*memory);
+                }
+                ArchiveRecord::Association(association) => {
+                    tx.create_association(&association).await?;
+                    report.associations += 1;
+                }
+                ArchiveRecord::Experience(experience) => experiences.push(experience),
+                ArchiveRecord::Summary(summary) => summaries.push(summary),
+                ArchiveRecord::Vector {
+                    memory_id,
+                    provider,
+                    vector,
+                } => {
+                    vectors.insert(memory_id, (provider, vector));
+                }
+            }
+        }
+        tx.commit().await?;
+        report.memories = imported.len();
+
+        let known: std::collections::HashSet<String> = self
+            .store
+            .list_experiences(i64::MAX, 0)
+            .await?
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        for experience in experiences {
+            if known.contains(&experience.id) {
+                report.skipped += 1;
+            } else {
+                self.store.save_experience(&experience).await?;
+                report.experiences += 1;
+            }
+        }
+        let known: std::collections::HashSet<String> = self
+            .store
+            .get_summaries()
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        for summary in summaries {
+            if known.contains(&summary.id) {
+                report.skipped += 1;
+            } else {
+                self.store.save_summary(&summary).await?;
+                report.summaries += 1;
+            }
+        }
+
+        report.reindexed = self.search.reindex_all().await?;
+        for memory in imported.iter().filter(|m| !m.forgotten) {
+            let Some((backend, embedder)) = self.vector_route(Some(memory.memory_type)) else {
+                continue;
+            };
+            match vectors.remove(&memory.id) {
+                Some((provider, vector))
+                    if provider == embedder.name() && vector.len() == embedder.dimension() =>
+                {
+                    self.check_embedder(Some(memory.memory_type), embedder)?;
+                    let payload = vector_backend::vector_payload(memory, self.store.namespace());
+                    backend.upsert(&memory.id, &vector, Some(payload)).await?;
+                    report.vectors_imported += 1;
+                }
+                _ => {
+                    self.upsert_vector(memory).await?;
+                    report.vectors_reembedded += 1;
+                }
+            }
+        }
+        self.analytics.invalidate().await;
+
+        Ok(report)
+    }
+
+    // ─── Vector migration ─────────────────────────────────────────────────
+
+    /// Embed every active memory on the default vector route into `dual`'s
+    /// secondary, `batch_size` memories per embedding call. Writes made
+    /// meanwhile already reach both backends through `dual`.
+    pub async fn backfill_vectors(
+        &self,
+        dual: &DualWriteVectorBackend,
+        batch_size: usize,
+    ) -> Result<VectorBackfillReport> {
+        let embedder = self.default_embedder()?;
+        let mut report = VectorBackfillReport::default();
+        let mut cursor = None;
+        loop {
+            let page = self
+                .store
+                .list_page(cursor.as_deref(), batch_size.max(1))
+                .await?;
+            report.scanned += page.memories.len();
+            let (batch, routed): (Vec<Memory>, Vec<Memory>) = page
+                .memories
+                .into_iter()
+                .partition(|m| !self.type_vectors.contains_key(&m.memory_type));
+            report.skipped += routed.len();
+
+            let contents: Vec<String> = batch.iter().map(|m| m.content.clone()).collect();
+            let vectors = embedder
+                .embed(&contents)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+            for (memory, vector) in batch.iter().zip(&vectors) {
+                dual.backfill(&memory.id, vector).await?;
+                report.upserted += 1;
+            }
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(report),
+            }
+        }
+    }
+
+    /// Run [`backfill_vectors`](Self::backfill_vectors) on a background task
+    pub fn spawn_vector_backfill(
+        &self,
+        dual: Arc<DualWriteVectorBackend>,
+        batch_size: usize,
+    ) -> tokio::task::JoinHandle<Result<VectorBackfillReport>> {
+        let system = self.clone();
+        tokio::spawn(async move { system.backfill_vectors(&dual, batch_size).await })
+    }
+
+    /// Re-embed every active memory on the default vector route with
+    /// `new_embedder` and switch to the rebuilt index, see
+    /// [`embedding_migration`]. Clones made before keep the old route.
+    pub async fn migrate_embeddings(
+        &mut self,
+        new_embedder: Arc<dyn EmbeddingProvider>,
+        config: EmbeddingMigrationConfig,
+    ) -> Result<EmbeddingMigrationReport> {
+        let started = std::time::Instant::now();
+        self.flush_embeddings().await?;
+        let dimension = new_embedder.dimension();
+
+        let (target, staging) = match &config.target {
+            Some(target) => (Arc::clone(target), None),
+            None => {
+                let data_dir = self.data_dir.as_ref().ok_or_else(|| {
+                    MemoryError::Configuration(
+                        "Migrating embeddings without a data directory needs a target backend"
+                            .to_string(),
+                    )
+                })?;
+                if data_dir.join("vectors").join(".salt").exists() {
+                    return Err(MemoryError::Configuration(
+                        "Migrating an encrypted vector index needs a target backend".to_string(),
+                    ));
+                }
+                let staging = data_dir.join(embedding_migration::STAGING_DIR);
+                platform_fs::remove_dir_all_async(&staging).await?;
+                let backend = FileVectorBackend::new(&staging, dimension)
+                    .with_quantization(self.profile.vector_quantization());
+                backend.ensure_ready().await?;
+                (Arc::new(backend) as Arc<dyn VectorBackend>, Some(staging))
+            }
+        };
+        if target.dimension() != dimension {
+            return Err(MemoryError::Configuration(format!(
+                "Target vector backend '{}' has {} dims, but '{}' produces {}",
+                target.name(),
+                target.dimension(),
+                new_embedder.name(),
+                dimension
+            )));
+        }
+
+        let to = EmbeddingInfo {
+            provider: new_embedder.name().to_string(),
+            dimension,
+        };
+        let mut report = EmbeddingMigrationReport {
+            from: self.lock_manifest().embedding.clone(),
+            to: to.clone(),
+            memories: 0,
+            vectors: 0,
+            skipped: 0,
+            backend: target.name().to_string(),
+            duration_ms: 0,
+        };
+        let chunks = match self
+            .fill_migration_target(&target, &new_embedder, &config, &mut report)
+            .await
+        {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                if let Some(staging) = &staging {
+                    let _ = platform_fs::remove_dir_all_async(staging).await;
+                }
+                return Err(e);
+            }
+        };
+
+        // Swap the staged directory in: the old index stays whole until the
+        // new one is complete
+        let target = match (&staging, &self.data_dir) {
+            (Some(staging), Some(data_dir)) => {
+                drop(target);
+                let live = data_dir.join("vectors");
+                if let Err(e) = embedding_migration::swap_in(staging, &live).await {
+                    let _ = platform_fs::remove_dir_all_async(staging).await;
+                    return Err(e.into());
+                }
+                let backend = FileVectorBackend::new(&live, dimension)
+                    .with_quantization(self.profile.vector_quantization());
+                backend.ensure_ready().await?;
+                Arc::new(backend) as Arc<dyn VectorBackend>
+            }
+            _ => target,
+        };
+
+        {
+            let mut manifest = self.lock_manifest();
+            manifest.embedding = Some(to);
+            manifest.vector_backend = Some(target.name().to_string());
+            if let Some(data_dir) = &self.data_dir {
+                manifest.save(data_dir)?;
+            }
+        }
+        self.vector = Some(target);
+        self.embedder = Some(new_embedder);
+        for (id, memory_chunks) in chunks {
+            if self.store.vector_chunks(&id).await? != memory_chunks {
+                self.store.save_vector_chunks(&id, &memory_chunks).await?;
+            }
+        }
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+
+    /// Embed the default route's memories into `target`, a page at a time,
+    /// returning each memory's chunks. They are recorded only once the
+    /// target goes live, as until then the recorded chunks describe the old
+    /// index.
+    async fn fill_migration_target(
+        &self,
+        target: &Arc<dyn VectorBackend>,
+        embedder: &Arc<dyn EmbeddingProvider>,
+        config: &EmbeddingMigrationConfig,
+        report: &mut EmbeddingMigrationReport,
+    ) -> Result<Vec<(MemoryId, Vec<VectorChunk>)>> {
+        let mut chunks = Vec::new();
+        let total = self.store.count_all().await?.max(0) as usize;
+        let mut done = 0;
+        let mut cursor = None;
+        loop {
+            let page = self
+                .store
+                .list_page(cursor.as_deref(), config.batch_size.max(1))
+                .await?;
+            done += page.memories.len();
+            let (batch, routed): (Vec<Memory>, Vec<Memory>) = page
+                .memories
+                .into_iter()
+                .partition(|m| !self.type_vectors.contains_key(&m.memory_type));
+            report.skipped += routed.len();
+
+            let mut units = Vec::new();
+            for memory in &batch {
+                let (memory_units, memory_chunks) = self.vector_units(memory);
+                units.extend(memory_units);
+                chunks.push((memory.id.clone(), memory_chunks));
+            }
+            if !units.is_empty() {
+                let texts: Vec<String> = units.iter().map(|u| u.text.clone()).collect();
+                let vectors = embedder
+                    .embed(&texts)
+                    .await
+                    .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+                if vectors.len() != texts.len() {
+                    return Err(MemoryError::VectorDb(format!(
+                        "{} returned {} embeddings for {} inputs",
+                        embedder.name(),
+                        vectors.len(),
+                        texts.len()
+                    )));
+                }
+                for (unit, vector) in units.into_iter().zip(&vectors) {
+                    target.upsert(&unit.id, vector, Some(unit.payload)).await?;
+                    report.vectors += 1;
+                }
+            }
+            report.memories += batch.len();
+            config.report_progress(EmbeddingMigrationProgress { done, total });
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(chunks),
+            }
+        }
+    }
+
+    /// Check `dual`'s backends against each other, probing with the
+    /// embeddings of up to `sample_size` memories spread over the store
+    pub async fn verify_vector_parity(
+        &self,
+        dual: &DualWriteVectorBackend,
+        sample_size: usize,
+    ) -> Result<ParityReport> {
+        let embedder = self.default_embedder()?;
+        let memories: Vec<Memory> = self
+            .active_memories()
+            .await?
+            .into_iter()
+            .filter(|m| !self.type_vectors.contains_key(&m.memory_type))
+            .collect();
+        let step = (memories.len() / sample_size.max(1)).max(1);
+        let contents: Vec<String> = memories
+            .iter()
+            .step_by(step)
+            .take(sample_size)
+            .map(|m| m.content.clone())
+            .collect();
+        let probes = embedder
+            .embed(&contents)
+            .await
+            .map_err(|e| MemoryError::VectorDb(format!("Embedding failed: {e}")))?;
+        dual.verify_parity(&probes, 10).await
+    }
+
+    fn default_embedder(&self) -> Result<&Arc<dyn EmbeddingProvider>> {
+        self.embedder
+            .as_ref()
+            .ok_or_else(|| MemoryError::Configuration("No vector backend configured".to_string()))
+    }
+
+    /// Backups cover the local store; a custom backend needs its own tooling
+    fn ensure_local_backend(&self, operation: &str) -> Result<()> {
+        if self.backend.is_some() {
+            return Err(MemoryError::InvalidOperation(format!(
+                "{operation} is not supported with a custom storage backend"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Every active memory, read page by page
+    async fn active_memories(&self) -> Result<Vec<Memory>> {
+        let mut memories = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.store.list_page(cursor.as_deref(), 1000).await?;
+            memories.extend(page.memories);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(memories),
+            }
+        }
+    }
+
+    /// Get the underlying store
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    /// Get the search interface
+    pub fn search_interface(&self) -> &MemorySearch {
+        &self.search
+    }
+
+    /// Get the pulses system for subscribing to events
+    pub fn pulses(&self) -> &GoldfishPulses {
+        &self.pulses
+    }
+
+    /// Get the analytics interface (results are cached for a short TTL)
+    pub fn analytics(&self) -> &MemoryAnalytics {
+        &self.analytics
+    }
+
+    /// Attach a vector backend and embedding provider to enable hybrid retrieval.
+    ///
+    /// This does not change the existing API surface; it only enables the additional
+    /// `hybrid_search` method and keeps vectors up-to-date on save/update/delete.
+    ///
+    /// The embedder is checked against the data directory manifest on first use:
+    /// writing or searching with a model of a different dimension than the one the
+    /// stored vectors were built with fails with [`MemoryError::Configuration`].
+    pub fn with_vector_backend(
+        mut self,
+        vector: Arc<dyn VectorBackend>,
+        embedder: Arc<dyn EmbeddingProvider>,
+    ) -> Self {
+        {
+            let mut manifest = self.lock_manifest();
+            if manifest.record_vector_backend(vector.name()) {
+                if let Some(data_dir) = &self.data_dir {
+                    if let Err(e) = manifest.save(data_dir) {
+                        tracing::warn!("Failed to update data directory manifest: {}", e);
+                    }
+                }
+            }
+        }
+        self.vector = Some(vector);
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Use a dedicated vector backend and embedding model for one memory type.
+    ///
+    /// Memories of this type are embedded with `embedder` and stored in `vector`
+    /// instead of the default backend. `hybrid_search` with a matching type filter
+    /// queries this backend alone; unfiltered searches query it alongside the
+    /// default backend.
+    pub fn with_type_vector_backend(
+        mut self,
+        memory_type: MemoryType,
+        vector: Arc<dyn VectorBackend>,
+        embedder: Arc<dyn EmbeddingProvider>,
+    ) -> Self {
+        self.type_vectors.insert(memory_type, (vector, embedder));
+        self
+    }
+
+    /// Use a dedicated embedding model for one memory type, storing its vectors in a
+    /// named collection of the default vector backend.
+    ///
+    /// Must be called after [`with_vector_backend`](Self::with_vector_backend); fails if
+    /// there is no default backend or it does not support collections.
+    pub fn with_type_vector_collection(
+        self,
+        memory_type: MemoryType,
+        collection: &str,
+        embedder: Arc<dyn EmbeddingProvider>,
+    ) -> Result<Self> {
+        let vector = self.vector.clone().ok_or_else(|| {
+            MemoryError::Configuration(
+                "with_type_vector_collection requires a default vector backend".to_string(),
+            )
+        })?;
+        let scoped = VectorCollection::new(vector, collection)?;
+        Ok(self.with_type_vector_backend(memory_type, Arc::new(scoped), embedder))
+    }
+
+    /// Append a middleware layer to the save/recall pipeline.
+    ///
+    /// Layers run in the order they were added.
+    pub fn with_middleware(mut self, layer: Arc<dyn MemoryMiddleware>) -> Self {
+        self.middleware.push(layer);
+        self
+    }
+
+    /// Replace the classifier used for inputs without a memory type
+    pub fn with_classifier(mut self, classifier: Arc<dyn MemoryClassifier>) -> Self {
+        self.classifier = classifier;
+        self
+    }
+
+    /// Hybrid retrieval: BM25 (Tantivy) + vector + recency + importance + graph neighborhood.
+    pub async fn hybrid_search(
+        &self,
+        query: &str,
+        cfg: &HybridSearchConfig,
+        filter_type: Option<MemoryType>,
+    ) -> Result<Vec<ExplainedSearchResult>> {
+        let deadline = Deadline::from_timeout(cfg.timeout);
+        let found = self
+            .hybrid_search_within(query, cfg, filter_type, &deadline)
+            .await?;
+        if found.timed_out {
+            tracing::warn!("Hybrid search for {query:?} timed out");
+        }
+        Ok(found.results)
+    }
+
+    /// Hybrid search until `deadline` expires or is cancelled. Stages that did not
+    /// finish in time are skipped and the candidates found so far are ranked.
+    pub async fn hybrid_search_within(
+        &self,
+        query: &str,
+        cfg: &HybridSearchConfig,
+        filter_type: Option<MemoryType>,
+        deadline: &Deadline,
+    ) -> Result<TimedResults<ExplainedSearchResult>> {
+        let started = std::time::Instant::now();
+        let query = self.middleware.before_recall(query).await?;
+        let query = query.as_str();
+        let bm25_cfg = SearchConfig {
+            mode: SearchMode::FullText,
+            max_results: cfg.bm25_limit.max(cfg.max_results),
+            memory_type: filter_type,
+            ..SearchConfig::default()
+        };
+
+        let Some(bm25) = deadline.run(self.search.search(query, &bm25_cfg)).await else {
+            self.log_query("hybrid", query, cfg, started, std::iter::empty(), true)
+                .await;
+            return Ok(TimedResults::partial(Vec::new()));
+        };
+        let bm25 = bm25?;
+        let routes = self.search_routes(filter_type)?;
+
+        let mut found = hybrid_retrieval::hybrid_rank_sources(
+            query,
+            bm25,
+            &routes,
+            |id| {
+                let store = Arc::clone(&self.store);
+                let id = id.to_string();
+                Box::pin(async move { store.load(&id).await })
+            },
+            |id, depth| {
+                let store = Arc::clone(&self.store);
+                let id = id.to_string();
+                Box::pin(async move { store.get_neighbors(&id, depth, &[]).await })
+            },
+            cfg,
+            filter_type,
+            deadline,
+        )
+        .await?;
+        found.results = self
+            .screen_results(found.results, |r| &mut r.memory)
+            .await?;
+
+        let memories = found.results.iter().map(|r| &r.memory);
+        self.log_query("hybrid", query, cfg, started, memories, found.timed_out)
+            .await;
+        Ok(found)
+    }
+
+    /// Hybrid search with the config `router` assigns to `session_id`. The
+    /// results are tagged with the variant and counted in its metrics.
+    pub async fn hybrid_search_experiment(
+        &self,
+        router: &ExperimentRouter,
+        session_id: &str,
+        query: &str,
+        filter_type: Option<MemoryType>,
+    ) -> Result<ExperimentSearch> {
+        let variant = router.assign(session_id).ok_or_else(|| {
+            MemoryError::Configuration(format!("experiment {} has no variants", router.name()))
+        })?;
+
+        let started = std::time::Instant::now();
+        let deadline = Deadline::from_timeout(variant.config.timeout);
+        let found = self
+            .hybrid_search_within(query, &variant.config, filter_type, &deadline)
+            .await?;
+        router.record_query(
+            &variant.name,
+            found.results.len(),
+            found.timed_out,
+            started.elapsed(),
+        );
+
+        let tag = format!("experiment {}: variant {}", router.name(), variant.name);
+        let results = found
+            .results
+            .into_iter()
+            .map(|mut r| {
+                r.explanation.notes.push(tag.clone());
+                r
+            })
+            .collect();
+
+        Ok(ExperimentSearch {
+            experiment: router.name().to_string(),
+            variant: variant.name.clone(),
+            session_id: session_id.to_string(),
+            results,
+            timed_out: found.timed_out,
+        })
+    }
+
+    /// Search memories by time range
+    pub async fn search_temporal(
+        &self,
+        _query: &str,
+        temporal: &temporal::TemporalQuery,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let memories = self.backend().query_temporal(temporal, 1000).await?;
+
+        let results: Vec<MemorySearchResult> = memories
+            .into_iter()
+            .enumerate()
+            .map(|(i, memory)| MemorySearchResult {
+                memory,
+                score: 1.0 - (i as f32 / 100.0),
+                rank: i + 1,
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Get memories from today
+    pub async fn get_today(&self) -> Result<Vec<Memory>> {
+        let today = self.store.now().date_naive();
+        self.store
+            .query(&MemoryQuery::new().on_day(today), 100)
+            .await
+    }
+
+    /// Get memories from yesterday
+    pub async fn get_yesterday(&self) -> Result<Vec<Memory>> {
+        let yesterday = (self.store.now() - chrono::Duration::days(1)).date_naive();
+        self.store
+            .query(&MemoryQuery::new().on_day(yesterday), 100)
+            .await
     }
 
     /// Get memories from last N days
     pub async fn get_last_days(&self, n: i64) -> Result<Vec<Memory>> {
-        let days_ago = (chrono::Utc::now() - chrono::Duration::days(n)).date_naive();
-        let filter = format!("date(created_at) >= '{}'", days_ago);
-        self.store.query_with_filter(&filter, 1000).await
+        let days_ago = (self.store.now() - chrono::Duration::days(n)).date_naive();
+        self.store
+            .query(&MemoryQuery::new().since_day(days_ago), 1000)
+            .await
     }
 }
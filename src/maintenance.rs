@@ -1,10 +1,16 @@
-//! Memory maintenance: decay, prune, merge
+//! Memory maintenance: decay, prune, merge, importance budget, store quota,
+//! retention of forgotten memories
 
 use crate::error::Result;
-use crate::types::MemoryType;
+use crate::types::{Memory, MemoryId, MemoryType};
+use crate::vector_backend::VectorMaintenanceReport;
 use crate::MemoryStore;
 
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Maintenance configuration
 #[derive(Debug, Clone)]
@@ -29,6 +35,24 @@ pub struct MaintenanceConfig {
     pub consolidation_age_days: i64,
     /// Importance threshold for consolidation
     pub consolidation_threshold: f32,
+    /// Whether to compact and optimize attached vector backends
+    pub enable_vector_maintenance: bool,
+    /// Days of access history kept for activity analytics (`None` keeps everything)
+    pub access_log_retention_days: Option<i64>,
+    /// Days a forgotten memory is kept before it is purged for good, with its
+    /// version history, text index entry and vectors (`None` keeps them forever)
+    pub forgotten_retention_days: Option<i64>,
+    /// Share of its weight an association loses per day since it was created,
+    /// reinforced or last decayed, so stale links count less in graph
+    /// traversal (`None` keeps weights as they are)
+    pub association_decay_rate: Option<f32>,
+    /// Cap on the share of high-importance memories (`None` disables it)
+    pub importance_budget: Option<ImportanceBudget>,
+    /// Size limits enforced by eviction (`None` leaves the store unbounded)
+    pub quota: Option<StoreQuota>,
+    /// Pause after every [`THROTTLE_BATCH`] writes of decay, pruning and
+    /// eviction, so maintenance doesn't starve other work on slow devices
+    pub throttle: Option<Duration>,
 }
 
 impl Default for MaintenanceConfig {
@@ -44,10 +68,238 @@ impl Default for MaintenanceConfig {
             enable_consolidation: false, // Disabled by default
             consolidation_age_days: 30,
             consolidation_threshold: 0.3,
+            enable_vector_maintenance: true,
+            access_log_retention_days: Some(365),
+            forgotten_retention_days: None,
+            association_decay_rate: None,
+            importance_budget: None,
+            quota: None,
+            throttle: None,
         }
     }
 }
 
+impl MaintenanceConfig {
+    /// Defaults plus a throttle and a shorter access log, for embedded devices
+    pub fn low_memory() -> Self {
+        Self {
+            access_log_retention_days: Some(30),
+            throttle: Some(Duration::from_millis(50)),
+            ..Self::default()
+        }
+    }
+}
+
+/// Writes between two pauses of a throttled maintenance run
+pub const THROTTLE_BATCH: usize = 100;
+
+/// Counts maintenance writes and sleeps every [`THROTTLE_BATCH`] of them
+struct Throttle {
+    pause: Option<Duration>,
+    writes: usize,
+}
+
+impl Throttle {
+    fn new(pause: Option<Duration>) -> Self {
+        Self { pause, writes: 0 }
+    }
+
+    async fn wrote(&mut self) {
+        self.writes += 1;
+        if let Some(pause) = self.pause {
+            if self.writes.is_multiple_of(THROTTLE_BATCH) {
+                tokio::time::sleep(pause).await;
+            }
+        }
+    }
+}
+
+/// Smallest gap the importance budget leaves between rescaled memories
+const MIN_BUDGET_STEP: f32 = 0.001;
+
+/// Keeps importance discriminative: at most `max_high_fraction` of memories may sit
+/// at or above `high_threshold`. The excess is rescaled, in order, into the band
+/// just below the threshold, so relative ranking is preserved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportanceBudget {
+    /// Importance at or above which a memory counts as high-importance
+    pub high_threshold: f32,
+    /// Largest share of memories allowed at or above `high_threshold`
+    pub max_high_fraction: f32,
+}
+
+impl Default for ImportanceBudget {
+    fn default() -> Self {
+        Self {
+            high_threshold: 0.8,
+            max_high_fraction: 0.1,
+        }
+    }
+}
+
+/// One importance change made by the budget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportanceAdjustment {
+    pub memory_id: MemoryId,
+    pub old_importance: f32,
+    pub new_importance: f32,
+}
+
+/// What the importance budget found and changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportanceBudgetReport {
+    /// Memories considered (non-forgotten)
+    pub total: usize,
+    /// Memories at or above the threshold before and after
+    pub high_before: usize,
+    pub high_after: usize,
+    pub adjustments: Vec<ImportanceAdjustment>,
+}
+
+impl ImportanceBudget {
+    /// Plan adjustments for `(id, importance, exempt)` entries. Exempt memories
+    /// (pinned, identity) count against the budget but are never rescaled.
+    pub fn plan(&self, entries: &[(MemoryId, f32, bool)]) -> ImportanceBudgetReport {
+        let threshold = self.high_threshold.clamp(0.0, 1.0);
+        let allowed = (entries.len() as f32 * self.max_high_fraction.clamp(0.0, 1.0)) as usize;
+
+        let mut sorted: Vec<&(MemoryId, f32, bool)> = entries.iter().collect();
+        sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let high: Vec<_> = sorted.iter().filter(|e| e.1 >= threshold).collect();
+        let exempt_high = high.iter().filter(|e| e.2).count();
+        let keep = allowed.saturating_sub(exempt_high);
+        let excess: Vec<_> = high.iter().filter(|e| !e.2).skip(keep).collect();
+
+        let mut report = ImportanceBudgetReport {
+            total: entries.len(),
+            high_before: high.len(),
+            high_after: high.len() - excess.len(),
+            adjustments: Vec::new(),
+        };
+        if excess.is_empty() {
+            return report;
+        }
+
+        // Map the excess, in order, into (floor, ceiling] below the threshold.
+        // When the next memory down sits right under the threshold the band is
+        // too narrow, so fall back to a minimum step to keep the order.
+        let ceiling = (threshold - 0.01).max(0.0);
+        let floor = sorted
+            .iter()
+            .map(|e| e.1)
+            .find(|i| *i < threshold)
+            .unwrap_or(threshold - 0.2)
+            .clamp(0.0, ceiling);
+        let step = ((ceiling - floor) / excess.len() as f32).max(MIN_BUDGET_STEP);
+        for (i, entry) in excess.iter().enumerate() {
+            let new_importance = (ceiling - step * i as f32).max(0.0);
+            report.adjustments.push(ImportanceAdjustment {
+                memory_id: entry.0.clone(),
+                old_importance: entry.1,
+                new_importance,
+            });
+        }
+
+        report
+    }
+}
+
+/// Which memories go first when a [`StoreQuota`] is exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// Lowest importance first, least recently accessed breaking ties
+    #[default]
+    LowestImportance,
+    /// Events, oldest first, then everything else by lowest importance
+    OldestEventsFirst,
+}
+
+/// Soft limits on the size of a store. Maintenance forgets memories chosen by
+/// `policy` until the active memories fit again; pinned memories and
+/// `protected_types` are never evicted, so the store can stay over quota if
+/// only those remain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreQuota {
+    pub max_memories: Option<usize>,
+    /// Limit on the content and metadata bytes of active memories
+    pub max_bytes: Option<u64>,
+    pub policy: EvictionPolicy,
+    pub protected_types: Vec<MemoryType>,
+}
+
+impl Default for StoreQuota {
+    fn default() -> Self {
+        Self {
+            max_memories: None,
+            max_bytes: None,
+            policy: EvictionPolicy::default(),
+            protected_types: vec![MemoryType::Identity],
+        }
+    }
+}
+
+/// One memory evicted to get back under quota
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Eviction {
+    pub memory_id: MemoryId,
+    pub memory_type: MemoryType,
+    /// Stored importance at eviction time
+    pub importance: f32,
+    pub bytes: u64,
+}
+
+/// What quota enforcement found and did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub memories_before: usize,
+    pub bytes_before: u64,
+    pub memories_after: usize,
+    pub bytes_after: u64,
+    /// In eviction order
+    pub evictions: Vec<Eviction>,
+    /// Still over quota because only protected memories are left
+    pub over_quota: bool,
+}
+
+impl StoreQuota {
+    pub fn with_max_memories(mut self, max_memories: usize) -> Self {
+        self.max_memories = Some(max_memories);
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_protected_types(mut self, types: impl IntoIterator<Item = MemoryType>) -> Self {
+        self.protected_types = types.into_iter().collect();
+        self
+    }
+
+    fn exceeded(&self, memories: usize, bytes: u64) -> bool {
+        self.max_memories.is_some_and(|max| memories > max)
+            || self.max_bytes.is_some_and(|max| bytes > max)
+    }
+}
+
+/// Bytes a memory counts against [`StoreQuota::max_bytes`]: its content and
+/// serialized metadata
+pub fn memory_bytes(memory: &Memory) -> u64 {
+    let metadata = memory
+        .metadata
+        .as_ref()
+        .map(|m| m.to_string().len())
+        .unwrap_or(0);
+    (memory.content.len() + metadata) as u64
+}
+
 /// Maintenance report
 #[derive(Debug, Default)]
 pub struct MaintenanceReport {
@@ -61,21 +313,70 @@ pub struct MaintenanceReport {
     pub consolidated: usize,
     /// Total memories checked
     pub checked: usize,
+    /// One entry per vector backend that was maintained
+    pub vector: Vec<VectorMaintenanceReport>,
+    /// Number of access log entries removed by retention
+    pub access_events_pruned: u64,
+    /// Forgotten memories purged by retention
+    pub purged: Vec<MemoryId>,
+    /// Number of association weights decayed
+    pub associations_decayed: usize,
+    /// Importance rescaling, when a budget is configured
+    pub importance_budget: Option<ImportanceBudgetReport>,
+    /// Evictions, when a quota is configured
+    pub quota: Option<QuotaReport>,
+}
+
+/// Removes what a forgotten memory leaves outside the database, such as text
+/// index entries and vectors, just before retention purges its row. Quota
+/// evictions also go through it, so the owner can forget in its own backends.
+#[async_trait]
+pub trait PurgeCleanup: Send + Sync {
+    /// Drop everything kept for `id` outside the store
+    async fn before_purge(&self, id: &str) -> Result<()>;
+
+    /// Forget `id` to bring the store back under quota. The default forgets it
+    /// in the store alone.
+    async fn forget(&self, store: &MemoryStore, id: &str) -> Result<bool> {
+        store.forget(id).await
+    }
 }
 
-/// Run maintenance tasks
+/// Cleanup for a store with nothing kept outside it
+struct StoreOnly;
+
+#[async_trait]
+impl PurgeCleanup for StoreOnly {
+    async fn before_purge(&self, _id: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Run maintenance tasks. Purged forgotten memories lose only their rows; use
+/// [`run_maintenance_with`] to clean up indexes kept elsewhere.
 pub async fn run_maintenance(
     memory_store: &Arc<MemoryStore>,
     config: &MaintenanceConfig,
+) -> Result<MaintenanceReport> {
+    run_maintenance_with(memory_store, config, &StoreOnly).await
+}
+
+/// Run maintenance tasks, calling `cleanup` for each forgotten memory just
+/// before it is purged
+pub async fn run_maintenance_with(
+    memory_store: &Arc<MemoryStore>,
+    config: &MaintenanceConfig,
+    cleanup: &dyn PurgeCleanup,
 ) -> Result<MaintenanceReport> {
     let mut report = MaintenanceReport::default();
+    let mut throttle = Throttle::new(config.throttle);
 
     if config.enable_decay {
-        report.decayed = apply_decay(memory_store, config.decay_rate).await?;
+        report.decayed = apply_decay(memory_store, config.decay_rate, &mut throttle).await?;
     }
 
     if config.enable_pruning {
-        report.pruned = prune_memories(memory_store, config).await?;
+        report.pruned = prune_memories(memory_store, config, &mut throttle).await?;
     }
 
     if config.enable_merging {
@@ -83,11 +384,98 @@ pub async fn run_maintenance(
             merge_similar_memories(memory_store, config.merge_similarity_threshold).await?;
     }
 
+    if let Some(budget) = &config.importance_budget {
+        report.importance_budget = Some(apply_importance_budget(memory_store, budget).await?);
+    }
+
+    if let Some(quota) = &config.quota {
+        report.quota =
+            Some(enforce_quota_throttled(memory_store, quota, cleanup, &mut throttle).await?);
+    }
+
+    if let Some(days) = config.access_log_retention_days {
+        let cutoff = memory_store.now() - chrono::Duration::days(days);
+        report.access_events_pruned = memory_store.prune_access_log(cutoff).await?;
+    }
+
+    if let Some(days) = config.forgotten_retention_days {
+        let cutoff = memory_store.now() - chrono::Duration::days(days);
+        report.purged = purge_forgotten(memory_store, cutoff, cleanup, &mut throttle).await?;
+    }
+
+    if let Some(rate) = config.association_decay_rate {
+        report.associations_decayed = decay_associations(memory_store, rate, &mut throttle).await?;
+    }
+
     Ok(report)
 }
 
+/// Permanently delete memories forgotten before `cutoff`, one at a time, so an
+/// error leaves every memory either purged everywhere or still in the store
+async fn purge_forgotten(
+    memory_store: &Arc<MemoryStore>,
+    cutoff: DateTime<Utc>,
+    cleanup: &dyn PurgeCleanup,
+    throttle: &mut Throttle,
+) -> Result<Vec<MemoryId>> {
+    let mut purged = Vec::new();
+    loop {
+        let batch = memory_store
+            .forgotten_before(cutoff, THROTTLE_BATCH as i64)
+            .await?;
+        if batch.is_empty() {
+            break;
+        }
+        for id in batch {
+            cleanup.before_purge(&id).await?;
+            memory_store.purge(&id).await?;
+            purged.push(id);
+            throttle.wrote().await;
+        }
+    }
+
+    tracing::debug!("Purged {} forgotten memories", purged.len());
+    Ok(purged)
+}
+
+/// Scale down association weights by `rate` per day since each was last
+/// decayed. Associations decayed less than a day ago are left for the next run.
+async fn decay_associations(
+    memory_store: &Arc<MemoryStore>,
+    rate: f32,
+    throttle: &mut Throttle,
+) -> Result<usize> {
+    let now = memory_store.now();
+    let keep_per_day = 1.0 - rate.clamp(0.0, 1.0);
+    let mut decayed = 0;
+    loop {
+        let batch = memory_store
+            .associations_decayed_before(now - chrono::Duration::days(1), THROTTLE_BATCH as i64)
+            .await?;
+        if batch.is_empty() {
+            break;
+        }
+        for (association, decayed_at) in batch {
+            let days = (now - decayed_at).num_seconds() as f32 / 86_400.0;
+            let weight = association.weight * keep_per_day.powf(days);
+            memory_store
+                .set_association_weight(&association.id, weight, now)
+                .await?;
+            decayed += 1;
+            throttle.wrote().await;
+        }
+    }
+
+    tracing::debug!("Decayed {} association weights", decayed);
+    Ok(decayed)
+}
+
 /// Apply importance decay based on age and access patterns
-async fn apply_decay(memory_store: &Arc<MemoryStore>, decay_rate: f32) -> Result<usize> {
+async fn apply_decay(
+    memory_store: &Arc<MemoryStore>,
+    decay_rate: f32,
+    throttle: &mut Throttle,
+) -> Result<usize> {
     let mut decayed_count = 0;
 
     // Get all memories that can decay
@@ -95,7 +483,7 @@ async fn apply_decay(memory_store: &Arc<MemoryStore>, decay_rate: f32) -> Result
         let memories = memory_store.get_by_type(*mem_type, 1000).await?;
 
         for mut memory in memories {
-            let now = chrono::Utc::now();
+            let now = memory_store.now();
             let days_old = (now - memory.updated_at).num_days();
             let days_since_access = (now - memory.last_accessed_at).num_days();
 
@@ -117,6 +505,7 @@ async fn apply_decay(memory_store: &Arc<MemoryStore>, decay_rate: f32) -> Result
                 memory.updated_at = now;
                 memory_store.update(&memory).await?;
                 decayed_count += 1;
+                throttle.wrote().await;
             }
         }
     }
@@ -125,10 +514,104 @@ async fn apply_decay(memory_store: &Arc<MemoryStore>, decay_rate: f32) -> Result
     Ok(decayed_count)
 }
 
+/// Rescale importance so the share of high-importance memories stays within budget
+pub async fn apply_importance_budget(
+    memory_store: &Arc<MemoryStore>,
+    budget: &ImportanceBudget,
+) -> Result<ImportanceBudgetReport> {
+    let snapshot = memory_store.importance_snapshot().await?;
+    let report = budget.plan(&snapshot);
+
+    for adjustment in &report.adjustments {
+        memory_store
+            .set_importance(&adjustment.memory_id, adjustment.new_importance)
+            .await?;
+    }
+
+    tracing::debug!(
+        "Importance budget rescaled {} of {} high-importance memories",
+        report.adjustments.len(),
+        report.high_before
+    );
+    Ok(report)
+}
+
+/// Forget memories until the store fits `quota` again
+pub async fn enforce_quota(
+    memory_store: &Arc<MemoryStore>,
+    quota: &StoreQuota,
+) -> Result<QuotaReport> {
+    enforce_quota_throttled(memory_store, quota, &StoreOnly, &mut Throttle::new(None)).await
+}
+
+async fn enforce_quota_throttled(
+    memory_store: &Arc<MemoryStore>,
+    quota: &StoreQuota,
+    cleanup: &dyn PurgeCleanup,
+    throttle: &mut Throttle,
+) -> Result<QuotaReport> {
+    let (memories, bytes) = memory_store.quota_usage().await?;
+    let mut report = QuotaReport {
+        memories_before: memories,
+        bytes_before: bytes,
+        memories_after: memories,
+        bytes_after: bytes,
+        ..Default::default()
+    };
+
+    while quota.exceeded(report.memories_after, report.bytes_after) {
+        // Enough candidates for the count limit; a byte limit may need more batches
+        let wanted = quota
+            .max_memories
+            .map_or(1, |max| report.memories_after.saturating_sub(max))
+            .clamp(1, THROTTLE_BATCH);
+        let candidates = memory_store
+            .eviction_candidates(quota.policy, &quota.protected_types, wanted)
+            .await?;
+        let mut evicted = false;
+        for memory in candidates {
+            if !quota.exceeded(report.memories_after, report.bytes_after) {
+                break;
+            }
+            if !cleanup.forget(memory_store, &memory.id).await? {
+                continue;
+            }
+            evicted = true;
+            let bytes = memory_bytes(&memory);
+            report.memories_after -= 1;
+            report.bytes_after = report.bytes_after.saturating_sub(bytes);
+            report.evictions.push(Eviction {
+                memory_id: memory.id,
+                memory_type: memory.memory_type,
+                importance: memory.importance,
+                bytes,
+            });
+            throttle.wrote().await;
+        }
+        if !evicted {
+            break;
+        }
+    }
+    report.over_quota = quota.exceeded(report.memories_after, report.bytes_after);
+
+    if report.over_quota {
+        tracing::warn!(
+            "Store still over quota after evicting {} memories: {} memories, {} bytes left",
+            report.evictions.len(),
+            report.memories_after,
+            report.bytes_after
+        );
+    } else {
+        tracing::debug!("Quota evicted {} memories", report.evictions.len());
+    }
+    Ok(report)
+}
+
 /// Prune old, low-importance memories
 async fn prune_memories(
     memory_store: &Arc<MemoryStore>,
     config: &MaintenanceConfig,
+    throttle: &mut Throttle,
 ) -> Result<usize> {
     let candidates = memory_store
         .get_pruning_candidates(config.prune_threshold, config.min_age_days)
@@ -140,6 +623,7 @@ async fn prune_memories(
         // Soft delete (forget) rather than hard delete
         if memory_store.forget(&memory.id).await? {
             pruned_count += 1;
+            throttle.wrote().await;
         }
     }
 
@@ -200,6 +684,41 @@ impl MaintenanceConfigBuilder {
         self
     }
 
+    pub fn enable_vector_maintenance(mut self, enable: bool) -> Self {
+        self.config.enable_vector_maintenance = enable;
+        self
+    }
+
+    pub fn access_log_retention_days(mut self, days: Option<i64>) -> Self {
+        self.config.access_log_retention_days = days;
+        self
+    }
+
+    pub fn forgotten_retention_days(mut self, days: Option<i64>) -> Self {
+        self.config.forgotten_retention_days = days;
+        self
+    }
+
+    pub fn association_decay_rate(mut self, rate: Option<f32>) -> Self {
+        self.config.association_decay_rate = rate;
+        self
+    }
+
+    pub fn importance_budget(mut self, budget: Option<ImportanceBudget>) -> Self {
+        self.config.importance_budget = budget;
+        self
+    }
+
+    pub fn quota(mut self, quota: Option<StoreQuota>) -> Self {
+        self.config.quota = quota;
+        self
+    }
+
+    pub fn throttle(mut self, pause: Option<Duration>) -> Self {
+        self.config.throttle = pause;
+        self
+    }
+
     pub fn build(self) -> MaintenanceConfig {
         self.config
     }
@@ -210,3 +729,257 @@ impl Default for MaintenanceConfigBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{enforce_quota, EvictionPolicy, ImportanceBudget, StoreQuota};
+    use crate::embedding::HashEmbeddingProvider;
+    use crate::vector_backend::{FileVectorBackend, VectorBackend};
+    use crate::{
+        Clock, MaintenanceConfigBuilder, Memory, MemoryStore, MemorySystem, MemoryType, MockClock,
+    };
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn retention_counts_from_the_forget_and_cleans_up_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+        let clock = Arc::new(MockClock::default());
+        let vectors = Arc::new(FileVectorBackend::new(dir.path().join("vectors"), 16));
+        vectors.ensure_ready().await.unwrap();
+        let memory = MemorySystem::new(dir.path())
+            .await
+            .unwrap()
+            .with_clock(clock.clone())
+            .with_vector_backend(vectors.clone(), Arc::new(HashEmbeddingProvider::new(16)));
+
+        let stale = memory.new_memory("Rotate the staging certificates", MemoryType::Fact);
+        let recent = memory.new_memory("Rotate the billing keys", MemoryType::Fact);
+        memory.save(&stale).await.unwrap();
+        memory.save(&recent).await.unwrap();
+        memory.forget(&stale.id).await.unwrap();
+
+        // Editing a forgotten memory moves `updated_at` but not the forget time
+        clock.advance(Duration::days(10));
+        let mut edited = memory.load(&stale.id).await.unwrap().unwrap();
+        edited.importance = 0.9;
+        edited.updated_at = clock.now();
+        memory.update(&edited).await.unwrap();
+        memory.forget(&recent.id).await.unwrap();
+
+        let config = MaintenanceConfigBuilder::new()
+            .enable_decay(false)
+            .enable_pruning(false)
+            .forgotten_retention_days(Some(5))
+            .build();
+        let report = memory.run_maintenance(&config).await.unwrap();
+        assert_eq!(report.purged, vec![stale.id.clone()]);
+
+        assert!(memory.load(&stale.id).await.unwrap().is_none());
+        assert!(memory.history(&stale.id).await.unwrap().is_empty());
+        assert!(memory.load(&recent.id).await.unwrap().is_some());
+        let hits = memory.search("staging certificates").await.unwrap();
+        assert!(hits.iter().all(|hit| hit.memory.id != stale.id));
+        assert_eq!(vectors.stats().await.unwrap().rows, 1);
+    }
+
+    async fn save_with(
+        store: &MemoryStore,
+        content: &str,
+        memory_type: MemoryType,
+        importance: f32,
+    ) -> String {
+        let mut memory = Memory::new(content, memory_type);
+        memory.importance = importance;
+        store.save(&memory).await.unwrap();
+        memory.id
+    }
+
+    #[tokio::test]
+    async fn quota_evicts_lowest_importance_and_skips_pinned_and_protected() {
+        let store = MemoryStore::connect_in_memory().await;
+        let low = save_with(&store, "Lunch was pasta", MemoryType::Event, 0.1).await;
+        let pinned = save_with(&store, "Never deploy on Friday", MemoryType::Fact, 0.05).await;
+        store.set_pinned(&pinned, true).await.unwrap();
+        let identity = save_with(&store, "I am the release bot", MemoryType::Identity, 0.0).await;
+        let mid = save_with(&store, "Standups are at ten", MemoryType::Fact, 0.5).await;
+        let high = save_with(&store, "Prod runs in eu-west-1", MemoryType::Fact, 0.9).await;
+
+        let quota = StoreQuota::default().with_max_memories(3);
+        let report = enforce_quota(&store, &quota).await.unwrap();
+        let evicted: Vec<_> = report
+            .evictions
+            .iter()
+            .map(|e| e.memory_id.clone())
+            .collect();
+        assert_eq!(evicted, [low.clone(), mid.clone()]);
+        assert_eq!(report.memories_before, 5);
+        assert_eq!(report.memories_after, 3);
+        assert!(!report.over_quota);
+        for id in [&pinned, &identity, &high] {
+            assert!(!store.load(id).await.unwrap().unwrap().forgotten);
+        }
+
+        // Only pinned and protected memories would be left to evict
+        let report = enforce_quota(&store, &StoreQuota::default().with_max_memories(1))
+            .await
+            .unwrap();
+        assert_eq!(report.evictions.len(), 1);
+        assert_eq!(report.evictions[0].memory_id, high);
+        assert!(report.over_quota);
+        assert_eq!(report.memories_after, 2);
+    }
+
+    #[tokio::test]
+    async fn quota_evicts_oldest_events_first_then_by_importance_and_bytes() {
+        let store = MemoryStore::connect_in_memory().await;
+        let start = chrono::Utc::now();
+        let mut ids = Vec::new();
+        for (i, (content, memory_type, importance)) in [
+            ("Deploy 41 finished", MemoryType::Event, 0.9),
+            ("Deploy 42 finished", MemoryType::Event, 0.2),
+            ("Builds use the nightly toolchain", MemoryType::Fact, 0.1),
+            ("Staging resets on Sunday", MemoryType::Fact, 0.6),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut memory = Memory::new(content, memory_type);
+            memory.importance = importance;
+            memory.created_at = start + Duration::minutes(i as i64);
+            store.save(&memory).await.unwrap();
+            ids.push((memory.id, content.len() as u64));
+        }
+
+        let quota = StoreQuota::default()
+            .with_max_bytes(ids[3].1 + 1)
+            .with_policy(EvictionPolicy::OldestEventsFirst);
+        let report = enforce_quota(&store, &quota).await.unwrap();
+        let evicted: Vec<_> = report
+            .evictions
+            .iter()
+            .map(|e| e.memory_id.clone())
+            .collect();
+        assert_eq!(
+            evicted,
+            [ids[0].0.clone(), ids[1].0.clone(), ids[2].0.clone()]
+        );
+        assert_eq!(
+            report.bytes_before,
+            ids.iter().map(|(_, bytes)| bytes).sum::<u64>()
+        );
+        assert_eq!(report.bytes_after, ids[3].1);
+        assert_eq!(store.quota_usage().await.unwrap(), (1, ids[3].1));
+    }
+
+    #[tokio::test]
+    async fn quota_evictions_go_through_memory_system_forget() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(dir.path()).await.unwrap();
+        let mut old = memory.new_memory("The old office wifi password", MemoryType::Fact);
+        old.importance = 0.1;
+        let kept = memory.new_memory("The office moved to Pier 9", MemoryType::Fact);
+        memory.save(&old).await.unwrap();
+        memory.save(&kept).await.unwrap();
+
+        let config = MaintenanceConfigBuilder::new()
+            .enable_decay(false)
+            .enable_pruning(false)
+            .quota(Some(StoreQuota::default().with_max_memories(1)))
+            .build();
+        let report = memory.run_maintenance(&config).await.unwrap();
+        assert_eq!(report.quota.unwrap().evictions[0].memory_id, old.id);
+
+        let history = memory.history(&old.id).await.unwrap();
+        assert_eq!(
+            history.last().unwrap().change_reason.as_deref(),
+            Some("forgotten")
+        );
+    }
+
+    fn budget_entries(importances: &[(f32, bool)]) -> Vec<(String, f32, bool)> {
+        importances
+            .iter()
+            .enumerate()
+            .map(|(i, (importance, exempt))| (format!("m{i}"), *importance, *exempt))
+            .collect()
+    }
+
+    #[test]
+    fn budget_keeps_order_when_the_next_memory_sits_just_below_the_threshold() {
+        let budget = ImportanceBudget {
+            high_threshold: 0.8,
+            max_high_fraction: 0.0,
+        };
+        let entries = budget_entries(&[(0.95, false), (0.9, false), (0.85, false), (0.795, false)]);
+        let report = budget.plan(&entries);
+
+        assert_eq!(report.high_after, 0);
+        let rescaled: Vec<f32> = report
+            .adjustments
+            .iter()
+            .map(|a| a.new_importance)
+            .collect();
+        assert_eq!(rescaled.len(), 3);
+        assert!(rescaled.iter().all(|i| *i < 0.8));
+        assert!(rescaled[0] > rescaled[1] && rescaled[1] > rescaled[2]);
+    }
+
+    #[test]
+    fn budget_counts_exempt_memories_but_never_rescales_them() {
+        let budget = ImportanceBudget {
+            high_threshold: 0.8,
+            max_high_fraction: 0.2,
+        };
+        // 10 memories allow 2 high; the pinned one takes a slot
+        let mut importances = vec![(0.99, true), (0.95, false), (0.9, false), (0.85, false)];
+        importances.extend(std::iter::repeat_n((0.3, false), 6));
+        let entries = budget_entries(&importances);
+        let report = budget.plan(&entries);
+
+        assert_eq!(report.high_before, 4);
+        assert_eq!(report.high_after, 2);
+        let ids: Vec<&str> = report
+            .adjustments
+            .iter()
+            .map(|a| a.memory_id.as_str())
+            .collect();
+        assert_eq!(ids, ["m2", "m3"]);
+    }
+
+    #[tokio::test]
+    async fn association_weights_decay_per_day_since_the_last_decay() {
+        let clock = Arc::new(MockClock::default());
+        let store = MemoryStore::connect_in_memory()
+            .await
+            .with_clock(clock.clone());
+        let from = save_with(&store, "Deploys need approval", MemoryType::Decision, 0.5).await;
+        let to = save_with(
+            &store,
+            "A solo deploy broke billing",
+            MemoryType::Event,
+            0.5,
+        )
+        .await;
+        let mut link =
+            crate::Association::new(&from, &to, crate::RelationType::CausedBy).with_weight(0.8);
+        link.created_at = clock.now();
+        store.create_association(&link).await.unwrap();
+
+        let config = MaintenanceConfigBuilder::new()
+            .enable_decay(false)
+            .enable_pruning(false)
+            .association_decay_rate(Some(0.5))
+            .build();
+        clock.advance(Duration::days(2));
+        let report = super::run_maintenance(&store, &config).await.unwrap();
+        assert_eq!(report.associations_decayed, 1);
+        let weight = store.get_associations(&from).await.unwrap()[0].weight;
+        assert!((weight - 0.2).abs() < 1e-4, "weight was {weight}");
+
+        // Decayed less than a day ago, so left alone
+        clock.advance(Duration::hours(12));
+        let report = super::run_maintenance(&store, &config).await.unwrap();
+        assert_eq!(report.associations_decayed, 0);
+    }
+}
@@ -0,0 +1,305 @@
+//! Data directory manifest
+//!
+//! A data directory holds `memories.db`, `tantivy_index/` and the vector store
+//! (`vectors/` or `vectors_lancedb/`). `goldfish.manifest.json` records the layout
+//! format version, the vector backend and the embedding model used to build the
+//! vectors, so that opening a directory with an incompatible build or model fails
+//! with a clear error instead of an obscure one deep inside an index.
+//!
+//! Directories written before the manifest existed (format 0) are upgraded in
+//! place on open; every upgrade is recorded in the manifest's `upgrades` history.
+
+use crate::error::{MemoryError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File name of the manifest inside the data directory
+pub const MANIFEST_FILE: &str = "goldfish.manifest.json";
+
+/// Layout version written by this build
+pub const CURRENT_FORMAT_VERSION: u32 = 1;
+
+/// Embedding model the vectors in a data directory were built with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingInfo {
+    /// Provider name (e.g. `hash`, `ollama`)
+    pub provider: String,
+    /// Vector dimension
+    pub dimension: usize,
+}
+
+/// One applied layout upgrade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeRecord {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub upgraded_at: DateTime<Utc>,
+    /// Human-readable description of each change made
+    pub actions: Vec<String>,
+}
+
+/// Contents of `goldfish.manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDirManifest {
+    pub format_version: u32,
+    /// Crate version that last wrote the manifest
+    pub goldfish_version: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Name of the vector backend last used with this directory
+    #[serde(default)]
+    pub vector_backend: Option<String>,
+    /// Embedding model of the default vector index
+    #[serde(default)]
+    pub embedding: Option<EmbeddingInfo>,
+    #[serde(default)]
+    pub upgrades: Vec<UpgradeRecord>,
+}
+
+impl DataDirManifest {
+    pub(crate) fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            format_version: CURRENT_FORMAT_VERSION,
+            goldfish_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now,
+            updated_at: now,
+            vector_backend: None,
+            embedding: None,
+            upgrades: Vec::new(),
+        }
+    }
+
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(MANIFEST_FILE)
+    }
+
+    /// Read the manifest, or `None` if the directory has none
+    pub fn load(data_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(data_dir);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            MemoryError::Configuration(format!(
+                "Data directory manifest {} is corrupt: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Write the manifest atomically (temp file + rename)
+    pub fn save(&mut self, data_dir: &Path) -> Result<()> {
+        self.updated_at = Utc::now();
+        self.goldfish_version = env!("CARGO_PKG_VERSION").to_string();
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+
+        crate::platform_fs::write_atomic(&Self::path(data_dir), &json)?;
+        Ok(())
+    }
+
+    /// Open the manifest of `data_dir`, creating it for a new directory and
+    /// upgrading older layouts in place.
+    ///
+    /// Fails if the directory was written by a newer, incompatible format.
+    pub fn open(data_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(data_dir)?;
+
+        let mut manifest = match Self::load(data_dir)? {
+            Some(manifest) => manifest,
+            None if is_legacy_layout(data_dir) => {
+                let mut manifest = Self::new();
+                manifest.format_version = 0;
+                manifest
+            }
+            None => {
+                let mut manifest = Self::new();
+                manifest.save(data_dir)?;
+                return Ok(manifest);
+            }
+        };
+
+        if manifest.format_version > CURRENT_FORMAT_VERSION {
+            return Err(MemoryError::Configuration(format!(
+                "Data directory {} uses layout format {} (written by goldfish {}), but this \
+                 build supports up to format {}; upgrade goldfish to open it",
+                data_dir.display(),
+                manifest.format_version,
+                manifest.goldfish_version,
+                CURRENT_FORMAT_VERSION
+            )));
+        }
+
+        if manifest.format_version < CURRENT_FORMAT_VERSION {
+            manifest.upgrade(data_dir)?;
+            manifest.save(data_dir)?;
+        }
+
+        Ok(manifest)
+    }
+
+    /// Apply upgrade steps until the manifest reaches [`CURRENT_FORMAT_VERSION`]
+    fn upgrade(&mut self, data_dir: &Path) -> Result<()> {
+        while self.format_version < CURRENT_FORMAT_VERSION {
+            let from = self.format_version;
+            let actions = match from {
+                0 => upgrade_v0_to_v1(data_dir, self)?,
+                other => {
+                    return Err(MemoryError::Configuration(format!(
+                        "No upgrade path from data directory format {other}"
+                    )))
+                }
+            };
+            for action in &actions {
+                tracing::info!(
+                    "Upgraded {} (format {}): {}",
+                    data_dir.display(),
+                    from,
+                    action
+                );
+            }
+            self.format_version = from + 1;
+            self.upgrades.push(UpgradeRecord {
+                from_version: from,
+                to_version: self.format_version,
+                upgraded_at: Utc::now(),
+                actions,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check `provider`/`dimension` against the recorded embedding model, recording
+    /// it if none is known yet. Returns `true` if the manifest changed.
+    pub fn check_embedding(&mut self, provider: &str, dimension: usize) -> Result<bool> {
+        match &self.embedding {
+            Some(info) if info.dimension != dimension => Err(MemoryError::Configuration(format!(
+                "Vectors in this data directory were built with the '{}' embedder ({} dims), \
+                 but the configured '{}' embedder produces {} dims; rebuild the vector index \
+                 or configure the original model",
+                info.provider, info.dimension, provider, dimension
+            ))),
+            Some(info) if info.provider == provider => Ok(false),
+            Some(info) => {
+                tracing::warn!(
+                    "Embedding provider changed from '{}' to '{}' with the same dimension; \
+                     existing vectors may not be comparable",
+                    info.provider,
+                    provider
+                );
+                self.embedding = Some(EmbeddingInfo {
+                    provider: provider.to_string(),
+                    dimension,
+                });
+                Ok(true)
+            }
+            None => {
+                self.embedding = Some(EmbeddingInfo {
+                    provider: provider.to_string(),
+                    dimension,
+                });
+                Ok(true)
+            }
+        }
+    }
+
+    /// Record the vector backend in use. Returns `true` if the manifest changed.
+    pub fn record_vector_backend(&mut self, name: &str) -> bool {
+        match self.vector_backend.as_deref() {
+            Some(previous) if previous == name => false,
+            previous => {
+                if let Some(previous) = previous {
+                    tracing::warn!(
+                        "Vector backend changed from '{}' to '{}'; vectors from the previous \
+                         backend are not migrated",
+                        previous,
+                        name
+                    );
+                }
+                self.vector_backend = Some(name.to_string());
+                true
+            }
+        }
+    }
+}
+
+/// Whether `data_dir` holds data written before manifests existed
+fn is_legacy_layout(data_dir: &Path) -> bool {
+    ["memories.db", "tantivy_index", "vectors", "vectors_lancedb"]
+        .iter()
+        .any(|entry| data_dir.join(entry).exists())
+}
+
+/// Format 0 -> 1: record the backends found on disk and move aside a full-text
+/// index whose schema no longer matches (it is rebuilt from SQLite on open).
+fn upgrade_v0_to_v1(data_dir: &Path, manifest: &mut DataDirManifest) -> Result<Vec<String>> {
+    let mut actions = Vec::new();
+
+    if data_dir.join("vectors_lancedb").exists() {
+        manifest.vector_backend = Some("lancedb".to_string());
+    } else if data_dir.join("vectors").exists() {
+        manifest.vector_backend = Some("file".to_string());
+    }
+    if let Some(backend) = &manifest.vector_backend {
+        actions.push(format!("detected '{backend}' vector backend"));
+    }
+
+    let index_dir = data_dir.join("tantivy_index");
+    if index_dir.exists() && !crate::search::MemorySearch::index_is_compatible(&index_dir) {
+        let backup = data_dir.join(format!(
+            "tantivy_index.v0-{}.bak",
+            Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        crate::platform_fs::rename(&index_dir, &backup)?;
+        actions.push(format!(
+            "moved incompatible full-text index to {} for rebuild",
+            backup.display()
+        ));
+    }
+
+    actions.push("wrote manifest".to_string());
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_and_rejects_newer_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = DataDirManifest::open(dir.path()).unwrap();
+        assert_eq!(manifest.format_version, CURRENT_FORMAT_VERSION);
+        assert!(manifest.upgrades.is_empty());
+
+        let mut newer = manifest.clone();
+        newer.format_version = CURRENT_FORMAT_VERSION + 1;
+        newer.save(dir.path()).unwrap();
+        let err = DataDirManifest::open(dir.path()).unwrap_err();
+        assert!(matches!(err, MemoryError::Configuration(_)));
+    }
+
+    #[test]
+    fn upgrades_legacy_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("vectors")).unwrap();
+        std::fs::write(dir.path().join("memories.db"), b"").unwrap();
+
+        let manifest = DataDirManifest::open(dir.path()).unwrap();
+        assert_eq!(manifest.vector_backend.as_deref(), Some("file"));
+        assert_eq!(manifest.upgrades.len(), 1);
+        assert_eq!(manifest.upgrades[0].from_version, 0);
+    }
+
+    #[test]
+    fn embedding_dimension_mismatch_is_an_error() {
+        let mut manifest = DataDirManifest::new();
+        assert!(manifest.check_embedding("hash", 384).unwrap());
+        assert!(!manifest.check_embedding("hash", 384).unwrap());
+        assert!(manifest.check_embedding("ollama", 768).is_err());
+    }
+}
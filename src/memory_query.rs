@@ -0,0 +1,403 @@
+//! Typed memory queries
+//!
+//! A [`MemoryQuery`] describes which memories to fetch (types, tags, a time
+//! range, an importance range, a session) and compiles to a `WHERE` clause
+//! whose values are all bound parameters. It replaces the raw SQL filter
+//! strings of [`MemoryStore::query_with_filter`](crate::MemoryStore::query_with_filter):
+//!
+//! ```rust,ignore
+//! let query = MemoryQuery::new()
+//!     .with_type(MemoryType::Decision)
+//!     .with_tag("deploy")
+//!     .since(Utc::now() - Duration::days(7))
+//!     .with_min_importance(0.5);
+//! let decisions = store.query(&query, 50).await?;
+//! ```
+
+use crate::store::SortOrder;
+use crate::temporal::{TemporalMode, TemporalQuery};
+use crate::types::MemoryType;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::ops::Bound;
+
+/// Which memories to fetch; every condition set must hold
+#[derive(Debug, Clone)]
+pub struct MemoryQuery {
+    /// Only these types; any type when empty
+    pub memory_types: Vec<MemoryType>,
+    /// Only memories carrying every one of these tags
+    pub tags: Vec<String>,
+    /// Timestamp the time range applies to
+    pub time_column: TemporalMode,
+    pub after: Bound<DateTime<Utc>>,
+    pub before: Bound<DateTime<Utc>>,
+    pub min_importance: Bound<f32>,
+    pub max_importance: Bound<f32>,
+    /// Only memories saved in this session
+    pub session_id: Option<String>,
+    /// Also return forgotten memories
+    pub include_forgotten: bool,
+    /// Leave out pinned memories
+    pub exclude_pinned: bool,
+    pub order: SortOrder,
+}
+
+/// A value bound to a placeholder of [`MemoryQuery::to_sql`]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum QueryValue {
+    Text(String),
+    Integer(i64),
+    Real(f32),
+    Time(DateTime<Utc>),
+}
+
+impl Default for MemoryQuery {
+    fn default() -> Self {
+        Self {
+            memory_types: Vec::new(),
+            tags: Vec::new(),
+            time_column: TemporalMode::Created,
+            after: Bound::Unbounded,
+            before: Bound::Unbounded,
+            min_importance: Bound::Unbounded,
+            max_importance: Bound::Unbounded,
+            session_id: None,
+            include_forgotten: false,
+            exclude_pinned: false,
+            order: SortOrder::Recent,
+        }
+    }
+}
+
+impl MemoryQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also match `memory_type`
+    pub fn with_type(mut self, memory_type: MemoryType) -> Self {
+        if !self.memory_types.contains(&memory_type) {
+            self.memory_types.push(memory_type);
+        }
+        self
+    }
+
+    pub fn with_types(self, memory_types: impl IntoIterator<Item = MemoryType>) -> Self {
+        memory_types.into_iter().fold(self, Self::with_type)
+    }
+
+    /// Also require `tag`
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    pub fn with_tags<S: Into<String>>(self, tags: impl IntoIterator<Item = S>) -> Self {
+        tags.into_iter().fold(self, Self::with_tag)
+    }
+
+    /// Apply the time range to this timestamp instead of `created_at`
+    pub fn with_time_column(mut self, column: TemporalMode) -> Self {
+        self.time_column = column;
+        self
+    }
+
+    /// Bounds on the time column
+    pub fn with_time_range(
+        mut self,
+        after: Bound<DateTime<Utc>>,
+        before: Bound<DateTime<Utc>>,
+    ) -> Self {
+        self.after = after;
+        self.before = before;
+        self
+    }
+
+    /// Time column at or after `start`
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.after = Bound::Included(start);
+        self
+    }
+
+    /// Time column strictly before `end`
+    pub fn before(mut self, end: DateTime<Utc>) -> Self {
+        self.before = Bound::Excluded(end);
+        self
+    }
+
+    /// Time column on the UTC day `day`
+    pub fn on_day(self, day: NaiveDate) -> Self {
+        let start = start_of_day(day);
+        self.since(start).before(start + Duration::days(1))
+    }
+
+    /// Time column on or after the UTC day `day`
+    pub fn since_day(self, day: NaiveDate) -> Self {
+        self.since(start_of_day(day))
+    }
+
+    /// Time column before the UTC day `day`
+    pub fn before_day(self, day: NaiveDate) -> Self {
+        self.before(start_of_day(day))
+    }
+
+    /// Bounds on importance
+    pub fn with_importance_range(mut self, min: Bound<f32>, max: Bound<f32>) -> Self {
+        self.min_importance = min;
+        self.max_importance = max;
+        self
+    }
+
+    /// Importance at least `min`
+    pub fn with_min_importance(mut self, min: f32) -> Self {
+        self.min_importance = Bound::Included(min);
+        self
+    }
+
+    /// Importance at most `max`
+    pub fn with_max_importance(mut self, max: f32) -> Self {
+        self.max_importance = Bound::Included(max);
+        self
+    }
+
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn with_forgotten(mut self, include_forgotten: bool) -> Self {
+        self.include_forgotten = include_forgotten;
+        self
+    }
+
+    pub fn without_pinned(mut self) -> Self {
+        self.exclude_pinned = true;
+        self
+    }
+
+    pub fn with_order(mut self, order: SortOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Conditions joined by `AND` with a `?` placeholder per value, and the
+    /// values in placeholder order. Column names are the only text that is
+    /// not bound, and they come from a fixed set.
+    pub(crate) fn to_sql(&self) -> (String, Vec<QueryValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if !self.include_forgotten {
+            conditions.push("forgotten = 0".to_string());
+        }
+        if self.exclude_pinned {
+            conditions.push("pinned = 0".to_string());
+        }
+        if !self.memory_types.is_empty() {
+            conditions.push(format!(
+                "memory_type IN ({})",
+                placeholders(self.memory_types.len())
+            ));
+            values.extend(
+                self.memory_types
+                    .iter()
+                    .map(|t| QueryValue::Text(t.to_string())),
+            );
+        }
+        if !self.tags.is_empty() {
+            conditions.push(format!(
+                "id IN (SELECT memory_id FROM memory_tags \
+                 WHERE namespace = memories.namespace AND tag IN ({}) \
+                 GROUP BY memory_id HAVING COUNT(DISTINCT tag) = ?)",
+                placeholders(self.tags.len())
+            ));
+            values.extend(self.tags.iter().cloned().map(QueryValue::Text));
+            values.push(QueryValue::Integer(self.tags.len() as i64));
+        }
+
+        let column = time_column(self.time_column);
+        push_bound(
+            &mut conditions,
+            &mut values,
+            column,
+            ">",
+            self.after.map(QueryValue::Time),
+        );
+        push_bound(
+            &mut conditions,
+            &mut values,
+            column,
+            "<",
+            self.before.map(QueryValue::Time),
+        );
+        push_bound(
+            &mut conditions,
+            &mut values,
+            "importance",
+            ">",
+            self.min_importance.map(QueryValue::Real),
+        );
+        push_bound(
+            &mut conditions,
+            &mut values,
+            "importance",
+            "<",
+            self.max_importance.map(QueryValue::Real),
+        );
+
+        if let Some(session_id) = &self.session_id {
+            conditions.push("session_id = ?".to_string());
+            values.push(QueryValue::Text(session_id.clone()));
+        }
+
+        if conditions.is_empty() {
+            conditions.push("1 = 1".to_string());
+        }
+        (conditions.join(" AND "), values)
+    }
+}
+
+impl From<&TemporalQuery> for MemoryQuery {
+    fn from(query: &TemporalQuery) -> Self {
+        Self::new().with_time_column(query.mode).with_time_range(
+            query.start.map_or(Bound::Unbounded, Bound::Included),
+            query.end.map_or(Bound::Unbounded, Bound::Included),
+        )
+    }
+}
+
+/// Column holding the timestamp a temporal mode refers to
+pub(crate) fn time_column(mode: TemporalMode) -> &'static str {
+    match mode {
+        TemporalMode::Created => "created_at",
+        TemporalMode::Updated => "updated_at",
+        TemporalMode::LastAccessed => "last_accessed_at",
+    }
+}
+
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(chrono::NaiveTime::MIN).and_utc()
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+/// `column > ?` or `column >= ?` (resp. `<`) for a bounded side
+fn push_bound(
+    conditions: &mut Vec<String>,
+    values: &mut Vec<QueryValue>,
+    column: &str,
+    op: &str,
+    bound: Bound<QueryValue>,
+) {
+    let (op, value) = match bound {
+        Bound::Included(value) => (format!("{op}="), value),
+        Bound::Excluded(value) => (op.to_string(), value),
+        Bound::Unbounded => return,
+    };
+    conditions.push(format!("{column} {op} ?"));
+    values.push(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiles_to_bound_parameters() {
+        let since = Utc::now();
+        let query = MemoryQuery::new()
+            .with_types([MemoryType::Fact, MemoryType::Decision])
+            .with_tag("o'brien")
+            .since(since)
+            .with_importance_range(Bound::Unbounded, Bound::Excluded(0.5))
+            .with_session("s1")
+            .without_pinned();
+
+        let (sql, values) = query.to_sql();
+        assert!(!sql.contains("o'brien") && !sql.contains("s1"));
+        assert_eq!(sql.matches('?').count(), values.len());
+        assert!(sql.contains("created_at >= ?"));
+        assert!(sql.contains("importance < ?"));
+        assert!(sql.starts_with("forgotten = 0 AND pinned = 0"));
+        assert_eq!(
+            values,
+            vec![
+                QueryValue::Text("fact".into()),
+                QueryValue::Text("decision".into()),
+                QueryValue::Text("o'brien".into()),
+                QueryValue::Integer(1),
+                QueryValue::Time(since),
+                QueryValue::Real(0.5),
+                QueryValue::Text("s1".into()),
+            ]
+        );
+
+        let (sql, values) = MemoryQuery::new().with_forgotten(true).to_sql();
+        assert_eq!((sql.as_str(), values.len()), ("1 = 1", 0));
+    }
+
+    #[tokio::test]
+    async fn forget_where_counts_matches_and_honours_dry_run() {
+        let store = crate::MemoryStore::connect_in_memory().await;
+        let old = store.new_memory("Saw a heron", MemoryType::Observation);
+        store.save(&old).await.unwrap();
+        let fact = store.new_memory("Herons eat fish", MemoryType::Fact);
+        store.save(&fact).await.unwrap();
+
+        let query = MemoryQuery::new().with_type(MemoryType::Observation);
+        let would = store.forget_where(&query, true).await.unwrap();
+        assert_eq!(would, vec![old.id.clone()]);
+        assert!(!store.load(&old.id).await.unwrap().unwrap().forgotten);
+
+        assert_eq!(store.forget_where(&query, false).await.unwrap(), would);
+        assert!(store.load(&old.id).await.unwrap().unwrap().forgotten);
+        assert!(!store.load(&fact.id).await.unwrap().unwrap().forgotten);
+        assert!(store.forget_where(&query, false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn bulk_edits_change_only_what_differs() {
+        let store = crate::MemoryStore::connect_in_memory().await;
+        let mut tagged = store.new_memory("Deploy freeze on Fridays", MemoryType::Fact);
+        tagged.tags.push("deploy".to_string());
+        store.save(&tagged).await.unwrap();
+        let plain = store.new_memory("Prefers dark mode", MemoryType::Preference);
+        store.save(&plain).await.unwrap();
+
+        let all = MemoryQuery::new();
+        let added = store.add_tag_where(&all, "deploy").await.unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].id, plain.id);
+        assert_eq!(store.get_by_tag("deploy", 10).await.unwrap().len(), 2);
+
+        let facts = MemoryQuery::new().with_type(MemoryType::Fact);
+        let removed = store.remove_tag_where(&facts, "deploy").await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(removed[0].tags.is_empty());
+        let still_tagged = store.get_by_tag("deploy", 10).await.unwrap();
+        assert_eq!(still_tagged.len(), 1);
+        assert_eq!(still_tagged[0].id, plain.id);
+
+        let edited = store
+            .set_metadata_where(&all, "team", Some(serde_json::json!("infra")))
+            .await
+            .unwrap();
+        assert_eq!(edited.len(), 2);
+        assert!(store
+            .set_metadata_where(&all, "team", Some(serde_json::json!("infra")))
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(store.retype(&plain.id, MemoryType::Fact).await.unwrap());
+        let retyped = store.load(&plain.id).await.unwrap().unwrap();
+        assert_eq!(retyped.memory_type, MemoryType::Fact);
+        assert_eq!(retyped.metadata.unwrap()["team"], "infra");
+        assert!(!store.retype("missing", MemoryType::Fact).await.unwrap());
+    }
+}
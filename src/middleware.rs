@@ -0,0 +1,181 @@
+//! Memory lifecycle hooks
+//!
+//! Middleware lets user code observe and change memories as they flow through
+//! the save, recall and context-building pipelines: auto-tagging, classification,
+//! redaction, auditing and so on, without forking the pipeline itself.
+//!
+//! Layers run in registration order. A layer can mutate the memory before it is
+//! persisted, veto the save entirely, rewrite recall queries, or post-process the
+//! context string handed to the LLM.
+
+use crate::error::{MemoryError, Result};
+use crate::types::Memory;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Outcome of a `on_before_save` hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveDecision {
+    /// Continue with the (possibly mutated) memory
+    Continue,
+    /// Abort the save with a reason
+    Veto(String),
+}
+
+/// A hook into the memory lifecycle. Every method has a no-op default, so
+/// implementors only override the stages they care about.
+#[async_trait]
+pub trait MemoryMiddleware: Send + Sync {
+    /// Name used in logs and veto errors
+    fn name(&self) -> &'static str;
+
+    /// Called before a memory is saved or updated. May mutate the memory or veto the write.
+    async fn on_before_save(&self, _memory: &mut Memory) -> Result<SaveDecision> {
+        Ok(SaveDecision::Continue)
+    }
+
+    /// Called after a memory has been persisted and indexed
+    async fn on_after_save(&self, _memory: &Memory) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before a recall/search query runs. May rewrite the query.
+    async fn on_before_recall(&self, _query: &mut String) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after a context window is assembled. May rewrite the context.
+    async fn on_context_build(&self, _context: &mut String) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Ordered list of middleware layers
+#[derive(Clone, Default)]
+pub struct MiddlewareChain {
+    layers: Vec<Arc<dyn MemoryMiddleware>>,
+}
+
+impl std::fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.layers.iter().map(|l| l.name()))
+            .finish()
+    }
+}
+
+impl MiddlewareChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a layer to the end of the chain
+    pub fn push(&mut self, layer: Arc<dyn MemoryMiddleware>) {
+        self.layers.push(layer);
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Run all `on_before_save` hooks. Returns an error if any layer vetoes.
+    pub async fn before_save(&self, memory: &mut Memory) -> Result<()> {
+        for layer in &self.layers {
+            if let SaveDecision::Veto(reason) = layer.on_before_save(memory).await? {
+                return Err(MemoryError::InvalidOperation(format!(
+                    "Save of memory {} vetoed by {}: {}",
+                    memory.id,
+                    layer.name(),
+                    reason
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run all `on_after_save` hooks
+    pub async fn after_save(&self, memory: &Memory) -> Result<()> {
+        for layer in &self.layers {
+            layer.on_after_save(memory).await?;
+        }
+        Ok(())
+    }
+
+    /// Run all `on_before_recall` hooks and return the rewritten query
+    pub async fn before_recall(&self, query: &str) -> Result<String> {
+        let mut query = query.to_string();
+        for layer in &self.layers {
+            layer.on_before_recall(&mut query).await?;
+        }
+        Ok(query)
+    }
+
+    /// Run all `on_context_build` hooks and return the rewritten context
+    pub async fn context_build(&self, context: String) -> Result<String> {
+        let mut context = context;
+        for layer in &self.layers {
+            layer.on_context_build(&mut context).await?;
+        }
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    struct Redact;
+
+    #[async_trait]
+    impl MemoryMiddleware for Redact {
+        fn name(&self) -> &'static str {
+            "redact"
+        }
+
+        async fn on_before_save(&self, memory: &mut Memory) -> Result<SaveDecision> {
+            if memory.content.contains("password") {
+                return Ok(SaveDecision::Veto("contains a secret".to_string()));
+            }
+            memory.content = memory.content.replace("alice@example.com", "[email]");
+            Ok(SaveDecision::Continue)
+        }
+
+        async fn on_context_build(&self, context: &mut String) -> Result<()> {
+            context.push_str("-- redacted --");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn chain_mutates_and_vetoes() {
+        let mut chain = MiddlewareChain::new();
+        chain.push(Arc::new(Redact));
+
+        let mut memory = Memory::new("Contact alice@example.com", MemoryType::Fact);
+        chain.before_save(&mut memory).await.unwrap();
+        assert_eq!(memory.content, "Contact [email]");
+
+        let mut secret = Memory::new("my password is hunter2", MemoryType::Fact);
+        let err = chain.before_save(&mut secret).await.unwrap_err();
+        assert!(matches!(err, MemoryError::InvalidOperation(_)));
+
+        let context = chain.context_build(String::new()).await.unwrap();
+        assert_eq!(context, "-- redacted --");
+    }
+
+    #[tokio::test]
+    async fn create_returns_the_memory_middleware_saved() {
+        let memory = crate::MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_middleware(Arc::new(Redact));
+        let input = crate::CreateMemoryInput::new("Contact alice@example.com", MemoryType::Fact);
+        let created = memory.create(input).await.unwrap();
+        assert_eq!(created.content, "Contact [email]");
+    }
+}
@@ -0,0 +1,121 @@
+//! User-defined schema migrations
+//!
+//! Applications that keep their own tables next to goldfish's register
+//! [`MigrationHook`]s on [`MemorySystemOptions`](crate::MemorySystemOptions).
+//! When a data directory is opened they run in the same SQLite pool, after
+//! the crate's own migrations, in ascending version order. Each hook runs once
+//! per database, in a transaction of its own; applied versions are recorded in
+//! the `user_migrations` table.
+//!
+//! ```rust,ignore
+//! let options = MemorySystemOptions::default().with_migration(MigrationHook::new(
+//!     1,
+//!     "ticket links",
+//!     "CREATE TABLE ticket_links (memory_id TEXT NOT NULL, ticket TEXT NOT NULL)",
+//! ));
+//! let memory = MemorySystem::new_with_options("./data", options).await?;
+//! ```
+//!
+//! Like the crate's migrations, a hook must not change once applied: opening a
+//! database whose recorded SQL for a version differs from the registered hook
+//! fails rather than leaving the schema out of step.
+
+use crate::error::{MemoryError, Result};
+use sqlx::{Row, SqlitePool};
+
+/// One user migration: SQL (possibly several statements) applied once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationHook {
+    /// Applied in ascending order; unique among the registered hooks
+    pub version: i64,
+    pub description: String,
+    pub sql: String,
+}
+
+impl MigrationHook {
+    pub fn new(version: i64, description: impl Into<String>, sql: impl Into<String>) -> Self {
+        Self {
+            version,
+            description: description.into(),
+            sql: sql.into(),
+        }
+    }
+}
+
+/// Apply the hooks not yet recorded in `user_migrations`, lowest version
+/// first. Returns the versions applied by this call.
+pub async fn run_migration_hooks(pool: &SqlitePool, hooks: &[MigrationHook]) -> Result<Vec<i64>> {
+    let mut hooks: Vec<&MigrationHook> = hooks.iter().collect();
+    hooks.sort_by_key(|hook| hook.version);
+    if let Some(pair) = hooks.windows(2).find(|w| w[0].version == w[1].version) {
+        return Err(MemoryError::Configuration(format!(
+            "Migration version {} is registered more than once",
+            pair[0].version
+        )));
+    }
+
+    let applied: Vec<(i64, String)> = sqlx::query("SELECT version, sql FROM user_migrations")
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| (row.get("version"), row.get("sql")))
+        .collect();
+
+    let mut ran = Vec::new();
+    for hook in hooks {
+        if let Some((_, sql)) = applied.iter().find(|(version, _)| *version == hook.version) {
+            if *sql != hook.sql {
+                return Err(MemoryError::Configuration(format!(
+                    "Migration {} ({}) was changed after it was applied",
+                    hook.version, hook.description
+                )));
+            }
+            continue;
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(&hook.sql).execute(&mut *tx).await?;
+        sqlx::query("INSERT INTO user_migrations (version, description, sql) VALUES (?, ?, ?)")
+            .bind(hook.version)
+            .bind(&hook.description)
+            .bind(&hook.sql)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        ran.push(hook.version);
+    }
+    Ok(ran)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[tokio::test]
+    async fn hooks_run_once_in_version_order() {
+        let store = MemoryStore::connect_in_memory().await;
+        let pool = store.pool();
+        let hooks = vec![
+            MigrationHook::new(
+                2,
+                "ticket index",
+                "CREATE INDEX idx_ticket_links ON ticket_links(ticket)",
+            ),
+            MigrationHook::new(
+                1,
+                "ticket links",
+                "CREATE TABLE ticket_links (memory_id TEXT NOT NULL, ticket TEXT NOT NULL);
+                 INSERT INTO ticket_links VALUES ('m1', 'OPS-1');",
+            ),
+        ];
+
+        assert_eq!(run_migration_hooks(pool, &hooks).await.unwrap(), vec![1, 2]);
+        assert!(run_migration_hooks(pool, &hooks).await.unwrap().is_empty());
+
+        let changed = [MigrationHook::new(1, "ticket links", "SELECT 1")];
+        assert!(run_migration_hooks(pool, &changed).await.is_err());
+        let duplicate = [hooks[0].clone(), hooks[0].clone()];
+        assert!(run_migration_hooks(pool, &duplicate).await.is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Numeric range filters over memory metadata
+//!
+//! Memories often carry numeric facts in their metadata: a price, a version,
+//! a count. A [`NumericRange`] keeps only memories whose value under a
+//! top-level metadata key lies within bounds. Ranges can be set on
+//! [`SearchConfig::numeric_ranges`](crate::SearchConfig::numeric_ranges) or
+//! written into a full-text query:
+//!
+//! ```text
+//! laptop price < 1000 rating >= 4.5
+//! ```
+//!
+//! Query ranges are recognised for keys indexed as numeric fields (see
+//! [`MemorySearch::with_numeric_fields`](crate::MemorySearch::with_numeric_fields));
+//! Tantivy then filters on its fast fields. Ranges on other keys still work
+//! from `SearchConfig`, checked against each candidate's metadata.
+
+use crate::types::Memory;
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+
+/// Comparison operators of the query syntax, longest first
+const OPERATORS: [&str; 5] = ["<=", ">=", "<", ">", "="];
+
+/// Bounds on the numeric value of a metadata key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumericRange {
+    /// Top-level metadata key
+    pub key: String,
+    pub lower: Bound<f64>,
+    pub upper: Bound<f64>,
+}
+
+impl NumericRange {
+    pub fn new(key: impl Into<String>, lower: Bound<f64>, upper: Bound<f64>) -> Self {
+        Self {
+            key: key.into(),
+            lower,
+            upper,
+        }
+    }
+
+    /// `key < max`
+    pub fn below(key: impl Into<String>, max: f64) -> Self {
+        Self::new(key, Bound::Unbounded, Bound::Excluded(max))
+    }
+
+    /// `key <= max`
+    pub fn at_most(key: impl Into<String>, max: f64) -> Self {
+        Self::new(key, Bound::Unbounded, Bound::Included(max))
+    }
+
+    /// `key > min`
+    pub fn above(key: impl Into<String>, min: f64) -> Self {
+        Self::new(key, Bound::Excluded(min), Bound::Unbounded)
+    }
+
+    /// `key >= min`
+    pub fn at_least(key: impl Into<String>, min: f64) -> Self {
+        Self::new(key, Bound::Included(min), Bound::Unbounded)
+    }
+
+    /// `min <= key <= max`
+    pub fn between(key: impl Into<String>, min: f64, max: f64) -> Self {
+        Self::new(key, Bound::Included(min), Bound::Included(max))
+    }
+
+    /// Whether `value` lies within the bounds
+    pub fn contains(&self, value: f64) -> bool {
+        let above = match self.lower {
+            Bound::Included(min) => value >= min,
+            Bound::Excluded(min) => value > min,
+            Bound::Unbounded => true,
+        };
+        let below = match self.upper {
+            Bound::Included(max) => value <= max,
+            Bound::Excluded(max) => value < max,
+            Bound::Unbounded => true,
+        };
+        above && below
+    }
+
+    /// Whether the memory's value lies within the bounds; memories without a
+    /// numeric value under the key never match
+    pub fn matches(&self, memory: &Memory) -> bool {
+        metadata_number(memory, &self.key).is_some_and(|value| self.contains(value))
+    }
+
+    /// Parse `key<op>value` with `op` one of `<`, `<=`, `>`, `>=`, `=`
+    fn parse(token: &str) -> Option<Self> {
+        let at = token.find(['<', '>', '='])?;
+        let (key, rest) = token.split_at(at);
+        let op = OPERATORS.iter().find(|op| rest.starts_with(**op))?;
+        let value: f64 = rest[op.len()..].parse().ok()?;
+        if key.is_empty() || !value.is_finite() {
+            return None;
+        }
+        Some(match *op {
+            "<" => Self::below(key, value),
+            "<=" => Self::at_most(key, value),
+            ">" => Self::above(key, value),
+            ">=" => Self::at_least(key, value),
+            _ => Self::between(key, value, value),
+        })
+    }
+}
+
+/// Numeric value of a top-level metadata key; numeric strings count
+pub fn metadata_number(memory: &Memory, key: &str) -> Option<f64> {
+    match memory.metadata.as_ref()?.get(key)? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Split `price < 100`-style comparisons on `keys` out of a query. Returns the
+/// rest of the query and the ranges found; comparisons on other keys stay in
+/// the query.
+pub fn extract_numeric_ranges(query: &str, keys: &[String]) -> (String, Vec<NumericRange>) {
+    if keys.is_empty() {
+        return (query.to_string(), Vec::new());
+    }
+
+    // Glue "price < 100" into "price<100" so each comparison is one token
+    let mut compact = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            let after_operator = compact.ends_with(['<', '>', '=']);
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            let before_operator = chars.peek().is_some_and(|c| matches!(c, '<' | '>' | '='));
+            if after_operator || before_operator {
+                continue;
+            }
+        }
+        compact.push(c);
+    }
+
+    let mut rest = Vec::new();
+    let mut ranges = Vec::new();
+    for token in compact.split_whitespace() {
+        match NumericRange::parse(token) {
+            Some(range) if keys.contains(&range.key) => ranges.push(range),
+            _ => rest.push(token),
+        }
+    }
+    (rest.join(" "), ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    #[test]
+    fn extracts_comparisons_on_known_keys() {
+        let keys = vec!["price".to_string(), "rating".to_string()];
+        let (rest, ranges) =
+            extract_numeric_ranges("laptop price < 1000 rating>=4.5 year>2020", &keys);
+        assert_eq!(rest, "laptop year>2020");
+        assert_eq!(
+            ranges,
+            vec![
+                NumericRange::below("price", 1000.0),
+                NumericRange::at_least("rating", 4.5),
+            ]
+        );
+
+        let mut memory = Memory::new("Laptop offer", MemoryType::Fact);
+        memory.metadata = Some(serde_json::json!({ "price": 999, "rating": "4.7" }));
+        assert!(ranges.iter().all(|r| r.matches(&memory)));
+        assert!(!NumericRange::above("price", 999.0).matches(&memory));
+        assert!(!NumericRange::above("weight", 1.0).matches(&memory));
+    }
+}
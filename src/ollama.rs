@@ -0,0 +1,405 @@
+//! Ollama integration for local models
+//!
+//! - [`OllamaEmbeddingProvider`] implements [`EmbeddingProvider`] via `POST /api/embed`
+//!   in batches, falling back to `/api/embeddings` on older servers.
+//! - [`OllamaGenerator`] implements [`TextGenerator`] via `POST /api/generate`,
+//!   including streaming (newline-delimited JSON).
+//!
+//! The host defaults to `OLLAMA_HOST` or `http://localhost:11434`.
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::{MemoryError, Result};
+use crate::generation::{GenerationOptions, TextGenerator, TextStream};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Connection settings shared by the Ollama embedding provider and generator
+#[derive(Debug, Clone)]
+pub struct OllamaConfig {
+    /// Base URL of the Ollama server
+    pub host: String,
+    /// Model name (e.g. `nomic-embed-text`, `llama3.1`)
+    pub model: String,
+    /// Request timeout
+    pub timeout: Duration,
+    /// How long Ollama keeps the model loaded after a request (e.g. `"5m"`)
+    pub keep_alive: Option<String>,
+}
+
+impl OllamaConfig {
+    pub fn new(model: impl Into<String>) -> Self {
+        let host = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|h| !h.trim().is_empty())
+            .map(|h| {
+                if h.starts_with("http://") || h.starts_with("https://") {
+                    h
+                } else {
+                    format!("http://{h}")
+                }
+            })
+            .unwrap_or_else(|| "http://localhost:11434".to_string());
+
+        Self {
+            host,
+            model: model.into(),
+            timeout: Duration::from_secs(120),
+            keep_alive: None,
+        }
+    }
+
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.host.trim_end_matches('/'), path)
+    }
+
+    fn client(&self) -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Response of the single-prompt `/api/embeddings` endpoint
+#[derive(Deserialize)]
+struct LegacyEmbedResponse {
+    embedding: Vec<f32>,
+}
+
+/// Inputs sent per `/api/embed` request by default
+pub const OLLAMA_DEFAULT_BATCH: usize = 64;
+
+/// Embedding provider backed by a local Ollama model.
+///
+/// Texts are sent in batches to `/api/embed`. Servers older than Ollama 0.3
+/// only have the single-prompt `/api/embeddings`; on a 404 the provider
+/// switches to it for the rest of its life.
+#[derive(Debug, Clone)]
+pub struct OllamaEmbeddingProvider {
+    client: reqwest::Client,
+    config: OllamaConfig,
+    dimension: usize,
+    batch_size: usize,
+    legacy_endpoint: Arc<AtomicBool>,
+}
+
+impl OllamaEmbeddingProvider {
+    /// `dimension` must match the model's output size (e.g. 768 for `nomic-embed-text`)
+    pub fn new(config: OllamaConfig, dimension: usize) -> Result<Self> {
+        Ok(Self {
+            client: config.client()?,
+            config,
+            dimension,
+            batch_size: OLLAMA_DEFAULT_BATCH,
+            legacy_endpoint: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Inputs per request; larger calls are split
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn config(&self) -> &OllamaConfig {
+        &self.config
+    }
+
+    fn request_body(&self, key: &str, input: serde_json::Value) -> serde_json::Value {
+        let mut body = serde_json::json!({ "model": self.config.model });
+        body[key] = input;
+        if let Some(keep_alive) = &self.config.keep_alive {
+            body["keep_alive"] = serde_json::json!(keep_alive);
+        }
+        body
+    }
+
+    async fn post(&self, path: &str, body: &serde_json::Value) -> Result<reqwest::Response> {
+        self.client
+            .post(self.config.url(path))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Ollama request failed: {e}")))
+    }
+
+    /// Embed one batch with `/api/embed`; `None` when the server lacks it
+    async fn embed_batch(&self, texts: &[String]) -> Result<Option<Vec<Vec<f32>>>> {
+        let body = self.request_body("input", serde_json::json!(texts));
+        let response = self.post("/api/embed", &body).await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            let text = response.text().await.unwrap_or_default();
+            // A missing model is also a 404, with an error naming the model
+            if !text.contains("model") {
+                return Ok(None);
+            }
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Ollama returned {status}: {text}"
+            )));
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Ollama returned {status}: {text}"
+            )));
+        }
+
+        let parsed: EmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Invalid Ollama response: {e}")))?;
+        Ok(Some(parsed.embeddings))
+    }
+
+    /// Embed one text with the pre-0.3 `/api/embeddings` endpoint
+    async fn embed_legacy(&self, text: &str) -> Result<Vec<f32>> {
+        let body = self.request_body("prompt", serde_json::json!(text));
+        let response = self.post("/api/embeddings", &body).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Ollama returned {status}: {text}"
+            )));
+        }
+
+        let parsed: LegacyEmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Invalid Ollama response: {e}")))?;
+        Ok(parsed.embedding)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            if !self.legacy_endpoint.load(Ordering::Relaxed) {
+                if let Some(vectors) = self.embed_batch(batch).await? {
+                    embeddings.extend(vectors);
+                    continue;
+                }
+                tracing::info!("Ollama has no /api/embed; falling back to /api/embeddings");
+                self.legacy_endpoint.store(true, Ordering::Relaxed);
+            }
+            for text in batch {
+                embeddings.push(self.embed_legacy(text).await?);
+            }
+        }
+
+        if embeddings.len() != texts.len() {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Ollama returned {} embeddings for {} inputs",
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+        if let Some(v) = embeddings.iter().find(|v| v.len() != self.dimension) {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Model {} produced {}-dim vectors, expected {}",
+                self.config.model,
+                v.len(),
+                self.dimension
+            )));
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[derive(Deserialize)]
+struct GenerateChunk {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+}
+
+/// Text generator backed by a local Ollama model
+#[derive(Debug, Clone)]
+pub struct OllamaGenerator {
+    client: reqwest::Client,
+    config: OllamaConfig,
+}
+
+impl OllamaGenerator {
+    pub fn new(config: OllamaConfig) -> Result<Self> {
+        Ok(Self {
+            client: config.client()?,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &OllamaConfig {
+        &self.config
+    }
+
+    fn request_body(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        stream: bool,
+    ) -> serde_json::Value {
+        let mut model_options = serde_json::Map::new();
+        if let Some(t) = options.temperature {
+            model_options.insert("temperature".into(), serde_json::json!(t));
+        }
+        if let Some(n) = options.max_tokens {
+            model_options.insert("num_predict".into(), serde_json::json!(n));
+        }
+        if !options.stop.is_empty() {
+            model_options.insert("stop".into(), serde_json::json!(options.stop));
+        }
+
+        let mut body = serde_json::json!({
+            "model": self.config.model,
+            "prompt": prompt,
+            "stream": stream,
+            "options": model_options,
+        });
+        if let Some(system) = &options.system {
+            body["system"] = serde_json::json!(system);
+        }
+        if let Some(keep_alive) = &self.config.keep_alive {
+            body["keep_alive"] = serde_json::json!(keep_alive);
+        }
+        body
+    }
+
+    async fn send(&self, body: &serde_json::Value) -> Result<reqwest::Response> {
+        let response = self
+            .client
+            .post(self.config.url("/api/generate"))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| MemoryError::Generation(format!("Ollama request failed: {e}")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(MemoryError::Generation(format!(
+                "Ollama returned {status}: {text}"
+            )));
+        }
+        Ok(response)
+    }
+}
+
+fn parse_chunk(line: &str) -> Result<GenerateChunk> {
+    serde_json::from_str(line)
+        .map_err(|e| MemoryError::Generation(format!("Invalid Ollama stream chunk: {e}")))
+}
+
+#[async_trait]
+impl TextGenerator for OllamaGenerator {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    async fn generate(&self, prompt: &str, options: &GenerationOptions) -> Result<String> {
+        let body = self.request_body(prompt, options, false);
+        let response = self.send(&body).await?;
+        let chunk: GenerateChunk = response
+            .json()
+            .await
+            .map_err(|e| MemoryError::Generation(format!("Invalid Ollama response: {e}")))?;
+        Ok(chunk.response)
+    }
+
+    async fn generate_stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+    ) -> Result<TextStream> {
+        let body = self.request_body(prompt, options, true);
+        let response = self.send(&body).await?;
+        let bytes = Box::pin(response.bytes_stream());
+
+        // Ollama streams one JSON object per line; a line may span several HTTP chunks.
+        let stream = futures::stream::unfold(
+            (bytes, Vec::<u8>::new(), false),
+            |(mut bytes, mut buf, done)| async move {
+                if done {
+                    return None;
+                }
+                loop {
+                    if let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                        let line: Vec<u8> = buf.drain(..=pos).collect();
+                        let line = String::from_utf8_lossy(&line);
+                        let line = line.trim();
+                        if line.is_empty() {
+                            continue;
+                        }
+                        return Some(match parse_chunk(line) {
+                            Ok(chunk) => (Ok(chunk.response), (bytes, buf, chunk.done)),
+                            Err(e) => (Err(e), (bytes, buf, true)),
+                        });
+                    }
+
+                    match bytes.next().await {
+                        Some(Ok(data)) => buf.extend_from_slice(&data),
+                        Some(Err(e)) => {
+                            let err = MemoryError::Generation(format!("Ollama stream failed: {e}"));
+                            return Some((Err(err), (bytes, buf, true)));
+                        }
+                        None => {
+                            let rest = String::from_utf8_lossy(&buf).trim().to_string();
+                            buf.clear();
+                            if rest.is_empty() {
+                                return None;
+                            }
+                            return Some(match parse_chunk(&rest) {
+                                Ok(chunk) => (Ok(chunk.response), (bytes, buf, true)),
+                                Err(e) => (Err(e), (bytes, buf, true)),
+                            });
+                        }
+                    }
+                }
+            },
+        )
+        .filter(|fragment| {
+            let keep = !matches!(fragment, Ok(text) if text.is_empty());
+            async move { keep }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,326 @@
+//! OpenAI-compatible embeddings
+//!
+//! [`OpenAiEmbeddingProvider`] implements [`EmbeddingProvider`] via
+//! `POST {base_url}/embeddings`, the API OpenAI, Azure OpenAI (with a deployment
+//! base URL), vLLM, LiteLLM, Together and others expose.
+//!
+//! Rate limits (`429`) and server errors are retried with exponential backoff,
+//! honouring `Retry-After` when the server sends one. Other errors fail fast.
+//!
+//! The base URL defaults to `OPENAI_BASE_URL` or `https://api.openai.com/v1`,
+//! the key to `OPENAI_API_KEY`.
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::{MemoryError, Result};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Most inputs OpenAI accepts in one embeddings request
+pub const OPENAI_MAX_BATCH: usize = 2048;
+
+/// Connection and retry settings for [`OpenAiEmbeddingProvider`]
+#[derive(Clone)]
+pub struct OpenAiConfig {
+    /// Base URL up to and including the version, e.g. `https://api.openai.com/v1`
+    pub base_url: String,
+    /// Sent as a bearer token; local servers often need none
+    pub api_key: Option<String>,
+    /// Model name (e.g. `text-embedding-3-small`)
+    pub model: String,
+    /// Ask the model to shorten its vectors (only `text-embedding-3` and later)
+    pub dimensions: Option<usize>,
+    /// Inputs per request; larger calls are split
+    pub batch_size: usize,
+    /// Request timeout
+    pub timeout: Duration,
+    /// Retries after the first attempt for rate limits and server errors
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each further one
+    pub initial_backoff: Duration,
+    /// Upper bound on any single wait, including `Retry-After`
+    pub max_backoff: Duration,
+}
+
+impl std::fmt::Debug for OpenAiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAiConfig")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("model", &self.model)
+            .field("dimensions", &self.dimensions)
+            .field("batch_size", &self.batch_size)
+            .field("timeout", &self.timeout)
+            .field("max_retries", &self.max_retries)
+            .finish()
+    }
+}
+
+impl OpenAiConfig {
+    pub fn new(model: impl Into<String>) -> Self {
+        let non_empty = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        Self {
+            base_url: non_empty("OPENAI_BASE_URL")
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            api_key: non_empty("OPENAI_API_KEY"),
+            model: model.into(),
+            dimensions: None,
+            batch_size: OPENAI_MAX_BATCH,
+            timeout: Duration::from_secs(60),
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, OPENAI_MAX_BATCH);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    fn url(&self) -> String {
+        format!("{}/embeddings", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Wait before retry number `retry` (0-based), preferring the server's `Retry-After`
+    fn backoff(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry));
+        retry_after.unwrap_or(exponential).min(self.max_backoff)
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+/// `Retry-After` in seconds; the HTTP-date form is not used by embedding APIs
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Embedding provider for OpenAI and compatible servers
+#[derive(Debug, Clone)]
+pub struct OpenAiEmbeddingProvider {
+    client: reqwest::Client,
+    config: OpenAiConfig,
+    dimension: usize,
+}
+
+impl OpenAiEmbeddingProvider {
+    /// `dimension` must match the model's output size (e.g. 1536 for
+    /// `text-embedding-3-small`), or `config.dimensions` when set
+    pub fn new(config: OpenAiConfig, dimension: usize) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+
+        Ok(Self {
+            client,
+            config,
+            dimension,
+        })
+    }
+
+    pub fn config(&self) -> &OpenAiConfig {
+        &self.config
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut body = serde_json::json!({
+            "model": self.config.model,
+            "input": texts,
+            "encoding_format": "float",
+        });
+        if let Some(dimensions) = self.config.dimensions {
+            body["dimensions"] = serde_json::json!(dimensions);
+        }
+
+        let mut retry = 0;
+        let response = loop {
+            let mut request = self.client.post(self.config.url()).json(&body);
+            if let Some(key) = &self.config.api_key {
+                request = request.bearer_auth(key);
+            }
+
+            let (error, wait) = match request.send().await {
+                Ok(response) if response.status().is_success() => break response,
+                Ok(response) if is_retryable(response.status()) => {
+                    let status = response.status();
+                    let wait = retry_after(response.headers());
+                    let text = response.text().await.unwrap_or_default();
+                    (format!("OpenAI returned {status}: {text}"), wait)
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(MemoryError::EmbeddingFailed(format!(
+                        "OpenAI returned {status}: {text}"
+                    )));
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    (format!("OpenAI request failed: {e}"), None)
+                }
+                Err(e) => {
+                    return Err(MemoryError::EmbeddingFailed(format!(
+                        "OpenAI request failed: {e}"
+                    )))
+                }
+            };
+
+            if retry >= self.config.max_retries {
+                return Err(MemoryError::EmbeddingFailed(format!(
+                    "{error} (gave up after {retry} retries)"
+                )));
+            }
+            let wait = self.config.backoff(retry, wait);
+            tracing::warn!("{error}; retrying in {wait:?}");
+            tokio::time::sleep(wait).await;
+            retry += 1;
+        };
+
+        let parsed: EmbeddingsResponse = response
+            .json()
+            .await
+            .map_err(|e| MemoryError::EmbeddingFailed(format!("Invalid OpenAI response: {e}")))?;
+        self.parse_vectors(parsed, texts.len())
+    }
+
+    /// Order vectors by input index and check their count and size
+    fn parse_vectors(&self, parsed: EmbeddingsResponse, inputs: usize) -> Result<Vec<Vec<f32>>> {
+        let mut data = parsed.data;
+        data.sort_by_key(|d| d.index);
+
+        if data.len() != inputs || data.iter().enumerate().any(|(i, d)| d.index != i) {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "OpenAI returned {} embeddings for {} inputs",
+                data.len(),
+                inputs
+            )));
+        }
+        if let Some(d) = data.iter().find(|d| d.embedding.len() != self.dimension) {
+            return Err(MemoryError::EmbeddingFailed(format!(
+                "Model {} produced {}-dim vectors, expected {}",
+                self.config.model,
+                d.embedding.len(),
+                self.dimension
+            )));
+        }
+
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.config.batch_size.max(1)) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_prefers_retry_after_up_to_the_cap() {
+        let config = OpenAiConfig::new("text-embedding-3-small")
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(config.backoff(0, None), Duration::from_millis(100));
+        assert_eq!(config.backoff(2, None), Duration::from_millis(400));
+        assert_eq!(config.backoff(10, None), Duration::from_secs(1));
+        assert_eq!(
+            config.backoff(0, Some(Duration::from_millis(250))),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            config.backoff(0, Some(Duration::from_secs(60))),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn vectors_follow_input_order() {
+        let provider =
+            OpenAiEmbeddingProvider::new(OpenAiConfig::new("text-embedding-3-small"), 2).unwrap();
+        let parsed: EmbeddingsResponse = serde_json::from_str(
+            r#"{"data": [
+                {"index": 1, "embedding": [0.0, 1.0]},
+                {"index": 0, "embedding": [1.0, 0.0]}
+            ]}"#,
+        )
+        .unwrap();
+        let vectors = provider.parse_vectors(parsed, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        let short: EmbeddingsResponse =
+            serde_json::from_str(r#"{"data": [{"index": 0, "embedding": [1.0]}]}"#).unwrap();
+        assert!(provider.parse_vectors(short, 1).is_err());
+    }
+}
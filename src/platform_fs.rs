@@ -0,0 +1,359 @@
+//! Filesystem operations that hold up on Windows and macOS
+//!
+//! The data directory code assumes Unix semantics: a file can be replaced or
+//! removed while something else has it open, and paths are practically
+//! unlimited in length. On Windows, virus scanners, indexers and backup agents
+//! open freshly written files for a moment without sharing delete access, so
+//! a rename or removal right after a write can fail with a sharing violation;
+//! and paths longer than `MAX_PATH` (260 characters) fail unless written in
+//! the extended `\\?\` form.
+//!
+//! The helpers here retry such transient failures with a short backoff,
+//! replace files through a temporary sibling so readers never see half a file,
+//! and extend long paths. On Unix they reduce to the plain `std::fs` calls,
+//! retrying only `EBUSY` (which macOS reports for files on busy network
+//! volumes).
+//!
+//! SQLite needs no help: its Windows VFS already retries I/O errors caused by
+//! virus scanners, and `busy_timeout` covers lock contention between
+//! connections.
+
+use std::ffi::OsString;
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Attempts before a transient failure is returned
+const RETRY_ATTEMPTS: u32 = 8;
+
+/// Delay after the first failure, doubled after each further one: about 2.5s
+/// in total before giving up
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Absolute paths at least this long get the `\\?\` prefix on Windows. Lower
+/// than `MAX_PATH` because creating a directory needs room for an 8.3 name.
+#[cfg(windows)]
+const LONG_PATH_THRESHOLD: usize = 248;
+
+/// Whether `err` is likely to go away on its own: an interrupted call, or on
+/// Windows a file briefly held open by another process.
+///
+/// Windows reports a file whose deletion is pending as "access denied", so
+/// that error is retried too; a file that is really read-only fails after the
+/// last attempt.
+pub fn is_transient(err: &io::Error) -> bool {
+    if matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+    ) {
+        return true;
+    }
+    match err.raw_os_error() {
+        // ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION,
+        // ERROR_USER_MAPPED_FILE
+        #[cfg(windows)]
+        Some(code) => matches!(code, 5 | 32 | 33 | 1224),
+        // EBUSY
+        #[cfg(unix)]
+        Some(code) => code == 16,
+        _ => false,
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt)
+}
+
+/// Run `op` until it succeeds, fails for good, or runs out of attempts;
+/// `transient` decides which errors are worth another try. The backoff blocks
+/// the thread, so async code should use [`retry_async`] instead.
+pub fn retry_with<T, E>(
+    transient: impl Fn(&E) -> bool,
+    mut op: impl FnMut() -> std::result::Result<T, E>,
+) -> std::result::Result<T, E> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if attempt + 1 < RETRY_ATTEMPTS && transient(&e) => {
+                std::thread::sleep(backoff(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// [`retry_with`] for I/O errors, using [`is_transient`]
+pub fn retry<T>(op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    retry_with(is_transient, op)
+}
+
+/// [`retry`] for async I/O, sleeping on the runtime instead of the thread
+pub async fn retry_async<T, F, Fut>(mut op: F) -> io::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e) if attempt + 1 < RETRY_ATTEMPTS && is_transient(&e) => {
+                tokio::time::sleep(backoff(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Rename a file or directory, retrying transient failures. A file at `to`
+/// is replaced; a directory at `to` must not exist.
+pub fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    retry(|| std::fs::rename(from, to))
+}
+
+/// Remove a directory tree, retrying transient failures. A directory that is
+/// already gone counts as removed.
+pub fn remove_dir_all(path: &Path) -> io::Result<()> {
+    retry(|| match std::fs::remove_dir_all(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    })
+}
+
+/// [`rename`] for async code, backing off on the runtime
+pub async fn rename_async(from: &Path, to: &Path) -> io::Result<()> {
+    retry_async(|| tokio::fs::rename(from, to)).await
+}
+
+/// [`remove_dir_all`] for async code, backing off on the runtime
+pub async fn remove_dir_all_async(path: &Path) -> io::Result<()> {
+    retry_async(|| async {
+        match tokio::fs::remove_dir_all(path).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    })
+    .await
+}
+
+/// Remove a file, retrying transient failures. A file that is already gone
+/// counts as removed.
+pub async fn remove_file(path: &Path) -> io::Result<()> {
+    retry_async(|| async {
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    })
+    .await
+}
+
+/// Replace `path` with `data` through a temporary file next to it, so
+/// readers see the old content or the new, never a partial write. The data
+/// is not synced to disk.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp = temp_sibling(path);
+    let written = retry(|| std::fs::File::create(&tmp))
+        .and_then(|mut file| file.write_all(data))
+        .and_then(|()| rename(&tmp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// [`write_atomic`] on the blocking thread pool
+pub async fn write_atomic_async(path: PathBuf, data: Vec<u8>) -> io::Result<()> {
+    tokio::task::spawn_blocking(move || write_atomic(&path, &data))
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Hidden temporary name in the directory of `path`; its `.tmp` extension
+/// keeps it out of directory scans for `.bin` or `.json` files
+fn temp_sibling(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+    path.with_file_name(name)
+}
+
+/// `path` in a form that still works beyond `MAX_PATH` on Windows: made
+/// absolute, with `.` and `..` resolved, and given the `\\?\` prefix once it
+/// is long. Short paths, and every path on other platforms, are returned
+/// unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        windows_long_path(path).unwrap_or_else(|| path.to_path_buf())
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(windows)]
+fn windows_long_path(path: &Path) -> Option<PathBuf> {
+    use std::path::{Component, Prefix};
+
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    if absolute.as_os_str().len() < LONG_PATH_THRESHOLD {
+        return None;
+    }
+
+    // Windows does not normalize `\\?\` paths, so resolve them here
+    let mut verbatim = OsString::new();
+    let mut parts = Vec::new();
+    for component in absolute.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(letter) => {
+                    verbatim.push(format!(r"\\?\{}:", char::from(letter)));
+                }
+                Prefix::UNC(server, share) => {
+                    verbatim.push(r"\\?\UNC\");
+                    verbatim.push(server);
+                    verbatim.push(r"\");
+                    verbatim.push(share);
+                }
+                // Already verbatim, or a device path
+                _ => return None,
+            },
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part),
+        }
+    }
+    for part in parts {
+        verbatim.push(r"\");
+        verbatim.push(part);
+    }
+    Some(PathBuf::from(verbatim))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Memory, MemoryType};
+    use crate::vector_backend::{FileVectorBackend, VectorBackend};
+    use crate::MemorySystem;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn transient_failures_are_retried_and_others_are_not() {
+        let calls = AtomicU32::new(0);
+        let result = retry(|| match calls.fetch_add(1, Ordering::SeqCst) {
+            0 | 1 => Err(io::Error::from(io::ErrorKind::Interrupted)),
+            n => Ok(n),
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: io::Result<()> = retry(|| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn atomic_writes_replace_files_without_leaving_temporaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v.bin");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        remove_dir_all(&dir.path().join("missing")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn async_renames_replace_files_and_removals_tolerate_missing_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("staged.bin");
+        let to = dir.path().join("live.bin");
+        std::fs::write(&from, b"new").unwrap();
+        std::fs::write(&to, b"old").unwrap();
+        rename_async(&from, &to).await.unwrap();
+        assert_eq!(std::fs::read(&to).unwrap(), b"new");
+        assert!(!from.exists());
+        assert!(rename_async(&from, &to).await.is_err());
+
+        write_atomic_async(to.clone(), b"newer".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&to).unwrap(), b"newer");
+
+        let tree = dir.path().join("tree");
+        std::fs::create_dir_all(tree.join("nested")).unwrap();
+        std::fs::write(tree.join("nested/a.bin"), b"a").unwrap();
+        remove_dir_all_async(&tree).await.unwrap();
+        assert!(!tree.exists());
+        remove_dir_all_async(&tree).await.unwrap();
+        remove_file(&to).await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn long_windows_paths_become_verbatim() {
+        let long = Path::new(r"C:\data")
+            .join("x".repeat(250))
+            .join("..")
+            .join("goldfish");
+        let extended = long_path(&long);
+        assert_eq!(extended, Path::new(r"\\?\C:\data\goldfish"));
+        assert_eq!(long_path(Path::new(r"C:\data")), Path::new(r"C:\data"));
+    }
+
+    #[tokio::test]
+    async fn data_dir_beyond_max_path_round_trips() {
+        let root = tempfile::tempdir().unwrap();
+        let mut data_dir = root.path().to_path_buf();
+        for i in 0..6 {
+            data_dir.push(format!("{i}-{}", "nested-directory-name-".repeat(3)));
+        }
+        assert!(data_dir.as_os_str().len() > 260);
+
+        let note = Memory::new("Backups run nightly at two", MemoryType::Fact);
+        {
+            let vectors = FileVectorBackend::new(data_dir.join("vectors"), 16);
+            vectors.ensure_ready().await.unwrap();
+            let memory = MemorySystem::new(&data_dir)
+                .await
+                .unwrap()
+                .with_vector_backend(
+                    Arc::new(vectors),
+                    Arc::new(crate::embedding::HashEmbeddingProvider::new(16)),
+                );
+            memory.save(&note).await.unwrap();
+        }
+
+        let memory = MemorySystem::new(&data_dir).await.unwrap();
+        assert!(memory.load(&note.id).await.unwrap().is_some());
+        let hits = memory.search("backups").await.unwrap();
+        assert_eq!(hits[0].memory.id, note.id);
+
+        let vectors = FileVectorBackend::new(data_dir.join("vectors"), 16);
+        vectors.ensure_ready().await.unwrap();
+        assert_eq!(vectors.stats().await.unwrap().rows, 1);
+        let leftovers = std::fs::read_dir(data_dir.join("vectors"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+}
@@ -0,0 +1,113 @@
+//! Resource profiles
+//!
+//! A [`ResourceProfile`] trades speed for footprint. [`LowMemory`] is meant for
+//! embedded devices: a small Tantivy writer heap, no analytics cache, a
+//! two-connection SQLite pool with a small page cache, an int8-quantized
+//! [`FileVectorBackend`] and throttled maintenance.
+//!
+//! ```rust,ignore
+//! let memory = MemorySystem::new_with_options("./data", MemorySystemOptions::low_memory()).await?;
+//! ```
+//!
+//! [`LowMemory`]: ResourceProfile::LowMemory
+//! [`FileVectorBackend`]: crate::FileVectorBackend
+
+use crate::error::MemoryError;
+use crate::vector_search::VectorQuantization;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Tantivy writer heap of the low-memory profile; Tantivy's minimum is 15 MB
+/// with one indexing thread
+const LOW_MEMORY_WRITER_HEAP: usize = 15_000_000;
+
+/// How much memory and CPU a [`MemorySystem`](crate::MemorySystem) may use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceProfile {
+    #[default]
+    Standard,
+    LowMemory,
+}
+
+impl ResourceProfile {
+    /// Tantivy writer heap in bytes (`None` keeps each writer's default)
+    pub fn writer_heap(&self) -> Option<usize> {
+        match self {
+            Self::Standard => None,
+            Self::LowMemory => Some(LOW_MEMORY_WRITER_HEAP),
+        }
+    }
+
+    /// Whether analytics reports are cached between calls
+    pub fn cache_analytics(&self) -> bool {
+        matches!(self, Self::Standard)
+    }
+
+    /// Encoding of vectors in the file backend the profile attaches
+    pub fn vector_quantization(&self) -> VectorQuantization {
+        match self {
+            Self::Standard => VectorQuantization::None,
+            Self::LowMemory => VectorQuantization::Int8,
+        }
+    }
+
+    /// Pause between batches of maintenance writes, unless the maintenance
+    /// config sets its own
+    pub fn maintenance_throttle(&self) -> Option<Duration> {
+        match self {
+            Self::Standard => None,
+            Self::LowMemory => Some(Duration::from_millis(50)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::LowMemory => "low_memory",
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ResourceProfile {
+    type Err = MemoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "standard" => Ok(Self::Standard),
+            "low_memory" => Ok(Self::LowMemory),
+            other => Err(MemoryError::Configuration(format!(
+                "Unknown resource profile '{other}' (expected standard or low_memory)"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_parse_from_config_strings() {
+        assert_eq!(
+            "low-memory".parse::<ResourceProfile>().unwrap(),
+            ResourceProfile::LowMemory
+        );
+        assert_eq!(
+            serde_json::from_str::<ResourceProfile>(r#""low_memory""#).unwrap(),
+            ResourceProfile::LowMemory
+        );
+        assert!("tiny".parse::<ResourceProfile>().is_err());
+
+        let low = ResourceProfile::LowMemory;
+        assert_eq!(low.vector_quantization(), VectorQuantization::Int8);
+        assert!(!low.cache_analytics());
+        assert!(ResourceProfile::Standard.writer_heap().is_none());
+    }
+}
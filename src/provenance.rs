@@ -0,0 +1,323 @@
+//! Belief provenance: why does the agent believe this?
+//!
+//! [`explain_belief`] walks a memory's associations and version history into a
+//! [`BeliefExplanation`]:
+//! - **causes**: `CausedBy` / `ResultOf` edges, followed transitively,
+//! - **corroborating** (`RelatedTo`) and **contradicting** (`Contradicts`) memories,
+//! - **supersedes** / **superseded_by**: `Updates` chains in both directions,
+//! - **revisions**: earlier versions of the memory itself.
+//!
+//! The explanation serializes to JSON and renders as an indented text tree with
+//! [`BeliefExplanation::to_text`].
+
+use crate::error::{MemoryError, Result};
+use crate::store::MemoryStore;
+use crate::types::{Association, Memory, RelationType};
+use crate::versioning::MemoryVersion;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// How deep `MemorySystem::explain_belief` follows causal and `Updates` chains
+pub const DEFAULT_PROVENANCE_DEPTH: u32 = 3;
+
+/// A memory linked to the belief, with the memories further up its chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceNode {
+    pub memory: Memory,
+    pub relation: RelationType,
+    pub weight: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ProvenanceNode>,
+}
+
+/// Structured provenance of a memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeliefExplanation {
+    pub memory: Memory,
+    /// Distinct `source` values of the memory and its causes
+    pub sources: Vec<String>,
+    /// Memories the belief was caused by or results from
+    pub causes: Vec<ProvenanceNode>,
+    pub corroborating: Vec<ProvenanceNode>,
+    pub contradicting: Vec<ProvenanceNode>,
+    /// Older beliefs this memory updates
+    pub supersedes: Vec<ProvenanceNode>,
+    /// Newer memories that update this one
+    pub superseded_by: Vec<ProvenanceNode>,
+    /// Recorded versions of the memory, oldest first
+    pub revisions: Vec<MemoryVersion>,
+}
+
+impl BeliefExplanation {
+    /// The explanation as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    /// The explanation as an indented, human-readable tree
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Belief: {} [{}, confidence {:.2}{}]",
+            self.memory.content,
+            self.memory.memory_type,
+            self.memory.confidence.score,
+            if self.memory.forgotten {
+                ", forgotten"
+            } else {
+                ""
+            }
+        );
+        if !self.sources.is_empty() {
+            let _ = writeln!(out, "Sources: {}", self.sources.join(", "));
+        }
+
+        for (title, nodes) in [
+            ("Caused by", &self.causes),
+            ("Corroborated by", &self.corroborating),
+            ("Contradicted by", &self.contradicting),
+            ("Supersedes", &self.supersedes),
+            ("Superseded by", &self.superseded_by),
+        ] {
+            if !nodes.is_empty() {
+                let _ = writeln!(out, "{title}:");
+                write_nodes(&mut out, nodes, 1);
+            }
+        }
+
+        if self.revisions.len() > 1 {
+            let _ = writeln!(out, "Revisions:");
+            for version in &self.revisions {
+                let _ = writeln!(
+                    out,
+                    "  v{} {} {}: {}",
+                    version.version_number,
+                    version.created_at.format("%Y-%m-%d %H:%M"),
+                    version.change_reason.as_deref().unwrap_or("changed"),
+                    version.memory.content
+                );
+            }
+        }
+        out
+    }
+
+    /// Every memory in the causal tree, the belief first
+    fn causal_memories(&self) -> Vec<&Memory> {
+        fn collect<'a>(nodes: &'a [ProvenanceNode], out: &mut Vec<&'a Memory>) {
+            for node in nodes {
+                out.push(&node.memory);
+                collect(&node.children, out);
+            }
+        }
+        let mut memories = vec![&self.memory];
+        collect(&self.causes, &mut memories);
+        memories
+    }
+}
+
+fn write_nodes(out: &mut String, nodes: &[ProvenanceNode], indent: usize) {
+    for node in nodes {
+        let _ = writeln!(
+            out,
+            "{}- {} ({}, weight {:.2}{})",
+            "  ".repeat(indent),
+            node.memory.content,
+            node.relation,
+            node.weight,
+            if node.memory.forgotten {
+                ", forgotten"
+            } else {
+                ""
+            }
+        );
+        write_nodes(out, &node.children, indent + 1);
+    }
+}
+
+/// Explain the memory `id`, following causal and `Updates` chains up to `depth`
+/// hops. Returns `None` if the memory does not exist.
+pub async fn explain_belief(
+    store: &MemoryStore,
+    id: &str,
+    depth: u32,
+) -> Result<Option<BeliefExplanation>> {
+    let Some(memory) = store.load(id).await? else {
+        return Ok(None);
+    };
+
+    let mut visited = HashSet::from([id.to_string()]);
+    let causes = walk(
+        store,
+        id.to_string(),
+        &[RelationType::CausedBy, RelationType::ResultOf],
+        depth,
+        &mut visited,
+    )
+    .await?;
+    let supersedes = walk(
+        store,
+        id.to_string(),
+        &[RelationType::Updates],
+        depth,
+        &mut visited,
+    )
+    .await?;
+
+    let mut corroborating = Vec::new();
+    let mut contradicting = Vec::new();
+    let mut superseded_by = Vec::new();
+    for association in store.get_associations(id).await? {
+        let incoming = association.target_id == id;
+        let bucket = match association.relation_type {
+            RelationType::RelatedTo => &mut corroborating,
+            RelationType::Contradicts => &mut contradicting,
+            RelationType::Updates if incoming => &mut superseded_by,
+            _ => continue,
+        };
+        let other = if incoming {
+            &association.source_id
+        } else {
+            &association.target_id
+        };
+        if let Some(node) = leaf(store, other, &association).await? {
+            bucket.push(node);
+        }
+    }
+
+    let mut explanation = BeliefExplanation {
+        memory,
+        sources: Vec::new(),
+        causes,
+        corroborating,
+        contradicting,
+        supersedes,
+        superseded_by,
+        revisions: store.memory_versions(id).await?,
+    };
+    let mut sources = Vec::new();
+    for source in explanation
+        .causal_memories()
+        .into_iter()
+        .filter_map(|m| m.source.clone())
+    {
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    explanation.sources = sources;
+
+    Ok(Some(explanation))
+}
+
+/// Outgoing edges of `relations` from `id`, followed recursively
+fn walk<'a>(
+    store: &'a MemoryStore,
+    id: String,
+    relations: &'a [RelationType],
+    depth: u32,
+    visited: &'a mut HashSet<String>,
+) -> BoxFuture<'a, Result<Vec<ProvenanceNode>>> {
+    Box::pin(async move {
+        let mut nodes = Vec::new();
+        if depth == 0 {
+            return Ok(nodes);
+        }
+        for association in store.get_associations(&id).await? {
+            if association.source_id != id
+                || !relations.contains(&association.relation_type)
+                || !visited.insert(association.target_id.clone())
+            {
+                continue;
+            }
+            if let Some(mut node) = leaf(store, &association.target_id, &association).await? {
+                node.children = walk(
+                    store,
+                    association.target_id.clone(),
+                    relations,
+                    depth - 1,
+                    visited,
+                )
+                .await?;
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    })
+}
+
+async fn leaf(
+    store: &MemoryStore,
+    id: &str,
+    association: &Association,
+) -> Result<Option<ProvenanceNode>> {
+    Ok(store.load(id).await?.map(|memory| ProvenanceNode {
+        memory,
+        relation: association.relation_type,
+        weight: association.weight,
+        children: Vec::new(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Memory, MemorySystem, MemoryType, RelationType};
+
+    #[tokio::test]
+    async fn explanation_walks_causes_and_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let mut incident = Memory::new("The outage was caused by a full disk", MemoryType::Event);
+        incident.source = Some("postmortem".to_string());
+        let alert = Memory::new("Disk usage alert fired at 95%", MemoryType::Event);
+        let old_rule = Memory::new("Keep disks below 90% full", MemoryType::Decision);
+        let rule = Memory::new("Keep disks below 80% full", MemoryType::Decision);
+        let agrees = Memory::new("Ops agreed on the 80% disk limit", MemoryType::Fact);
+        for memory in [&incident, &alert, &old_rule, &rule, &agrees] {
+            memory_system.save(memory).await.unwrap();
+        }
+        memory_system
+            .associate(&rule.id, &incident.id, RelationType::CausedBy)
+            .await
+            .unwrap();
+        memory_system
+            .associate(&incident.id, &alert.id, RelationType::ResultOf)
+            .await
+            .unwrap();
+        memory_system
+            .associate(&rule.id, &old_rule.id, RelationType::Updates)
+            .await
+            .unwrap();
+        memory_system
+            .associate(&agrees.id, &rule.id, RelationType::RelatedTo)
+            .await
+            .unwrap();
+
+        let explanation = memory_system
+            .explain_belief(&rule.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(explanation.causes.len(), 1);
+        assert_eq!(explanation.causes[0].memory.id, incident.id);
+        assert_eq!(explanation.causes[0].children[0].memory.id, alert.id);
+        assert_eq!(explanation.supersedes[0].memory.id, old_rule.id);
+        assert_eq!(explanation.corroborating[0].memory.id, agrees.id);
+        assert_eq!(explanation.sources, ["postmortem"]);
+
+        let old = memory_system
+            .explain_belief(&old_rule.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.superseded_by[0].memory.id, rule.id);
+
+        let text = explanation.to_text();
+        assert!(text.contains("Caused by:\n  - The outage was caused by a full disk"));
+        assert!(text.contains("    - Disk usage alert fired at 95% (result_of"));
+        assert!(explanation.to_json().unwrap().contains("\"superseded_by\""));
+    }
+}
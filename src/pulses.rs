@@ -31,6 +31,7 @@
 //! }
 //! ```
 
+use crate::injection::ScreenAction;
 use crate::types::{Association, Memory, MemoryId, MemoryType};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -133,6 +134,66 @@ pub enum Pulse {
         success: bool,
         timestamp: DateTime<Utc>,
     },
+
+    /// A new memory matched a saved search
+    SavedSearchHit {
+        search_id: String,
+        search_name: String,
+        memory: Memory,
+        score: f32,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A new memory was held back for review
+    ReviewRequested {
+        memory: Memory,
+        reason: String,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A reviewer approved or rejected a pending memory
+    ReviewResolved {
+        memory_id: MemoryId,
+        approved: bool,
+        reviewer: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A memory was forgotten to keep the store within its quota
+    MemoryEvicted {
+        memory_id: MemoryId,
+        memory_type: MemoryType,
+        /// Stored importance at eviction time
+        importance: f32,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A recalled memory looked like a prompt injection
+    InjectionSuspected {
+        memory_id: MemoryId,
+        memory_type: MemoryType,
+        /// Matched phrases and classifier verdicts
+        reasons: Vec<String>,
+        /// What the screen did with the memory
+        action: ScreenAction,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A cortex's working memory changed, or a periodic snapshot of it
+    WorkingMemoryChanged {
+        /// Items in working memory, highest attention first
+        items: Vec<AttentionItem>,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+/// A working-memory item as reported by [`Pulse::WorkingMemoryChanged`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttentionItem {
+    pub memory_id: MemoryId,
+    pub pinned: bool,
+    /// `None` unless attention scores are enabled in the pulse config
+    pub attention_score: Option<f32>,
 }
 
 impl Pulse {
@@ -151,6 +212,12 @@ impl Pulse {
             Pulse::MaintenanceCompleted { timestamp, .. } => *timestamp,
             Pulse::SearchPerformed { timestamp, .. } => *timestamp,
             Pulse::BatchCompleted { timestamp, .. } => *timestamp,
+            Pulse::SavedSearchHit { timestamp, .. } => *timestamp,
+            Pulse::ReviewRequested { timestamp, .. } => *timestamp,
+            Pulse::ReviewResolved { timestamp, .. } => *timestamp,
+            Pulse::MemoryEvicted { timestamp, .. } => *timestamp,
+            Pulse::InjectionSuspected { timestamp, .. } => *timestamp,
+            Pulse::WorkingMemoryChanged { timestamp, .. } => *timestamp,
         }
     }
 
@@ -164,6 +231,11 @@ impl Pulse {
             Pulse::MemoryDeleted { memory_id, .. } => Some(memory_id),
             Pulse::ConfidenceChanged { memory_id, .. } => Some(memory_id),
             Pulse::ContradictionDetected { memory_id, .. } => Some(memory_id),
+            Pulse::SavedSearchHit { memory, .. } => Some(&memory.id),
+            Pulse::ReviewRequested { memory, .. } => Some(&memory.id),
+            Pulse::ReviewResolved { memory_id, .. } => Some(memory_id),
+            Pulse::MemoryEvicted { memory_id, .. } => Some(memory_id),
+            Pulse::InjectionSuspected { memory_id, .. } => Some(memory_id),
             _ => None,
         }
     }
@@ -252,6 +324,59 @@ impl Pulse {
                     operation, count, success
                 )
             }
+            Pulse::SavedSearchHit {
+                search_name,
+                memory,
+                ..
+            } => {
+                format!(
+                    "Memory {} matched saved search '{}'",
+                    memory.id, search_name
+                )
+            }
+            Pulse::ReviewRequested { memory, reason, .. } => {
+                format!("Memory {} awaits review: {}", memory.id, reason)
+            }
+            Pulse::ReviewResolved {
+                memory_id,
+                approved,
+                ..
+            } => {
+                let verdict = if *approved { "approved" } else { "rejected" };
+                format!("Memory {} {}", memory_id, verdict)
+            }
+            Pulse::MemoryEvicted {
+                memory_id,
+                memory_type,
+                importance,
+                ..
+            } => {
+                format!(
+                    "{} memory {} evicted over quota (importance {:.2})",
+                    memory_type, memory_id, importance
+                )
+            }
+            Pulse::InjectionSuspected {
+                memory_id,
+                reasons,
+                action,
+                ..
+            } => {
+                format!(
+                    "Memory {} looks like a prompt injection ({}), action {:?}",
+                    memory_id,
+                    reasons.join(", "),
+                    action
+                )
+            }
+            Pulse::WorkingMemoryChanged { items, .. } => {
+                let pinned = items.iter().filter(|item| item.pinned).count();
+                format!(
+                    "Working memory holds {} items ({} pinned)",
+                    items.len(),
+                    pinned
+                )
+            }
         }
     }
 }
@@ -324,11 +449,19 @@ impl PulseFilter {
         // Check memory type filter
         if let Some(ref mem_types) = self.memory_types {
             match pulse {
-                Pulse::NewMemory { memory, .. } => {
+                Pulse::NewMemory { memory, .. }
+                | Pulse::SavedSearchHit { memory, .. }
+                | Pulse::ReviewRequested { memory, .. } => {
                     if !mem_types.contains(&memory.memory_type) {
                         return false;
                     }
                 }
+                Pulse::MemoryEvicted { memory_type, .. }
+                | Pulse::InjectionSuspected { memory_type, .. } => {
+                    if !mem_types.contains(memory_type) {
+                        return false;
+                    }
+                }
                 _ => return false, // Non-memory pulses don't match memory type filter
             }
         }
@@ -388,6 +521,12 @@ pub enum PulseType {
     MaintenanceCompleted,
     SearchPerformed,
     BatchCompleted,
+    SavedSearchHit,
+    ReviewRequested,
+    ReviewResolved,
+    MemoryEvicted,
+    InjectionSuspected,
+    WorkingMemoryChanged,
 }
 
 impl From<&Pulse> for PulseType {
@@ -405,6 +544,12 @@ impl From<&Pulse> for PulseType {
             Pulse::MaintenanceCompleted { .. } => PulseType::MaintenanceCompleted,
             Pulse::SearchPerformed { .. } => PulseType::SearchPerformed,
             Pulse::BatchCompleted { .. } => PulseType::BatchCompleted,
+            Pulse::SavedSearchHit { .. } => PulseType::SavedSearchHit,
+            Pulse::ReviewRequested { .. } => PulseType::ReviewRequested,
+            Pulse::ReviewResolved { .. } => PulseType::ReviewResolved,
+            Pulse::MemoryEvicted { .. } => PulseType::MemoryEvicted,
+            Pulse::InjectionSuspected { .. } => PulseType::InjectionSuspected,
+            Pulse::WorkingMemoryChanged { .. } => PulseType::WorkingMemoryChanged,
         }
     }
 }
@@ -457,7 +602,10 @@ impl GoldfishPulses {
             *stats.by_type.entry(pulse_type).or_insert(0) += 1;
         }
 
-        // Send to all subscribers
+        // Send to all subscribers; with none, there is nobody to tell
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
         if let Err(e) = self.sender.send(pulse) {
             tracing::warn!("Failed to emit pulse: {}", e);
         }
@@ -663,4 +811,46 @@ mod tests {
 
         assert!(!filter.matches(&pulse));
     }
+
+    #[tokio::test]
+    async fn cortex_reports_working_memory_changes() {
+        use crate::cortex::{MemoryCortex, WorkingMemoryPulseConfig};
+        use crate::vector_backend::InMemoryVectorBackend;
+
+        let pulses = Arc::new(GoldfishPulses::default());
+        let mut subscriber = pulses.subscribe_filtered(
+            PulseFilter::new().with_pulse_type(PulseType::WorkingMemoryChanged),
+        );
+        let cortex = MemoryCortex::from_store(
+            std::env::temp_dir(),
+            crate::MemoryStore::connect_in_memory().await,
+            Arc::new(InMemoryVectorBackend::new(384)),
+        )
+        .with_pulses(
+            Arc::clone(&pulses),
+            WorkingMemoryPulseConfig::default().with_attention(false),
+        );
+
+        let memory = cortex.new_memory("Ship on Friday", MemoryType::Decision);
+        cortex.remember(&memory).await.unwrap();
+        cortex.pin(&memory.id).await;
+        cortex.pin(&memory.id).await; // no change, no pulse
+
+        let expect = |pinned| {
+            vec![AttentionItem {
+                memory_id: memory.id.clone(),
+                pinned,
+                attention_score: None,
+            }]
+        };
+        for pinned in [false, true] {
+            match subscriber.recv().await {
+                Some(Pulse::WorkingMemoryChanged { items, .. }) => {
+                    assert_eq!(items, expect(pinned))
+                }
+                other => panic!("unexpected pulse {other:?}"),
+            }
+        }
+        assert!(subscriber.try_recv().is_none());
+    }
 }
@@ -0,0 +1,290 @@
+//! Memory quality scoring and curation
+//!
+//! Every memory gets a [`QualityScore`] on save, combining:
+//! - **length**: very short fragments and walls of text score low,
+//! - **specificity**: share of content words plus numbers and named entities,
+//! - **duplication**: 1 minus the word overlap with the closest existing memory,
+//! - **confidence**: the memory's confidence score,
+//! - **source**: the reliability of its source.
+//!
+//! Memories scoring below a threshold form the curation queue
+//! (`MemorySystem::curation_queue`), where each can be kept, merged into its
+//! near-duplicate or forgotten.
+
+use crate::analytics::extract_entities;
+use crate::types::{Memory, MemoryId};
+use crate::vector_search::is_stopword;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Weights of the quality components. They need not sum to 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityWeights {
+    pub length: f32,
+    pub specificity: f32,
+    pub duplication: f32,
+    pub confidence: f32,
+    pub source: f32,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self {
+            length: 0.2,
+            specificity: 0.25,
+            duplication: 0.25,
+            confidence: 0.15,
+            source: 0.15,
+        }
+    }
+}
+
+/// Quality of a memory, overall and per component (all 0.0 - 1.0)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityScore {
+    pub score: f32,
+    pub length: f32,
+    pub specificity: f32,
+    pub duplication: f32,
+    pub confidence: f32,
+    pub source: f32,
+    /// The most similar existing memory, when similar enough to suggest a merge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<MemoryId>,
+}
+
+/// Computes [`QualityScore`]s
+#[derive(Debug, Clone)]
+pub struct QualityScorer {
+    pub weights: QualityWeights,
+    /// Word overlap above which the closest memory is reported as a duplicate
+    pub duplicate_threshold: f32,
+}
+
+impl Default for QualityScorer {
+    fn default() -> Self {
+        Self {
+            weights: QualityWeights::default(),
+            duplicate_threshold: 0.8,
+        }
+    }
+}
+
+impl QualityScorer {
+    pub fn new(weights: QualityWeights) -> Self {
+        Self {
+            weights,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_duplicate_threshold(mut self, threshold: f32) -> Self {
+        self.duplicate_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Score `memory` against `neighbors`, the existing memories most likely to
+    /// duplicate it (e.g. its top full-text hits). `memory` itself is ignored.
+    pub fn score(&self, memory: &Memory, neighbors: &[Memory]) -> QualityScore {
+        let words = content_words(&memory.content);
+
+        let closest = neighbors
+            .iter()
+            .filter(|m| m.id != memory.id && !m.forgotten)
+            .map(|m| (m, jaccard(&words, &content_words(&m.content))))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let similarity = closest.map_or(0.0, |(_, s)| s);
+        let duplicate_of = closest
+            .filter(|(_, s)| *s >= self.duplicate_threshold)
+            .map(|(m, _)| m.id.clone());
+
+        let length = length_score(memory.content.split_whitespace().count());
+        let specificity = specificity_score(&memory.content);
+        let duplication = 1.0 - similarity;
+        let confidence = memory.confidence.score.clamp(0.0, 1.0);
+        let source = source_score(memory);
+
+        let w = &self.weights;
+        let total = w.length + w.specificity + w.duplication + w.confidence + w.source;
+        let score = if total > 0.0 {
+            (w.length * length
+                + w.specificity * specificity
+                + w.duplication * duplication
+                + w.confidence * confidence
+                + w.source * source)
+                / total
+        } else {
+            0.0
+        };
+
+        QualityScore {
+            score,
+            length,
+            specificity,
+            duplication,
+            confidence,
+            source,
+            duplicate_of,
+        }
+    }
+}
+
+/// Suggested way to resolve a curation queue entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "target")]
+pub enum CurationAction {
+    /// Keep the memory and take it off the queue
+    Keep,
+    /// Fold the memory into a near-duplicate, then forget it
+    Merge(MemoryId),
+    /// Forget (soft delete) the memory
+    Forget,
+}
+
+/// A low-quality memory awaiting review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurationItem {
+    pub memory: Memory,
+    pub quality: QualityScore,
+    pub suggested: CurationAction,
+}
+
+impl CurationItem {
+    /// Suggest merging duplicates, forgetting very low scores and reviewing the rest
+    pub fn new(memory: Memory, quality: QualityScore) -> Self {
+        let suggested = match &quality.duplicate_of {
+            Some(target) => CurationAction::Merge(target.clone()),
+            None if quality.score < 0.25 => CurationAction::Forget,
+            None => CurationAction::Keep,
+        };
+        Self {
+            memory,
+            quality,
+            suggested,
+        }
+    }
+}
+
+/// Lowercased words of the content, without stopwords
+fn content_words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .filter(|w| !is_stopword(w))
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// Ramps up to 1.0 at 6 words, stays there up to 300, then tapers to 0.5
+fn length_score(words: usize) -> f32 {
+    match words {
+        0 => 0.0,
+        1..=5 => words as f32 / 6.0,
+        6..=300 => 1.0,
+        _ => (1.0 - (words - 300) as f32 / 1000.0).max(0.5),
+    }
+}
+
+fn specificity_score(text: &str) -> f32 {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let content = tokens
+        .iter()
+        .filter(|t| !is_stopword(&t.to_lowercase()))
+        .count() as f32
+        / tokens.len() as f32;
+    let specifics = tokens
+        .iter()
+        .filter(|t| t.chars().any(|c| c.is_ascii_digit()))
+        .count()
+        + extract_entities(text).len();
+
+    0.6 * content + 0.4 * (specifics as f32 / 3.0).min(1.0)
+}
+
+fn source_score(memory: &Memory) -> f32 {
+    let reliability = memory.confidence.factors.source_reliability.clamp(0.0, 1.0);
+    let attributed = if memory.source.is_some() { 0.1 } else { 0.0 };
+    (reliability + attributed).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CurationAction, CurationItem, QualityScorer};
+    use crate::{Memory, MemorySystem, MemoryType};
+
+    #[test]
+    fn fragments_and_duplicates_score_low() {
+        let scorer = QualityScorer::default();
+        let existing = Memory::new(
+            "Deploys to production run every Tuesday at 14:00 UTC",
+            MemoryType::Fact,
+        );
+        let specific = Memory::new(
+            "Billing moved from Stripe to Adyen in March 2025 to cut fees by 0.4%",
+            MemoryType::Fact,
+        );
+        let fragment = Memory::new("ok", MemoryType::Fact);
+        let duplicate = Memory::new(
+            "Deploys to production run every Tuesday at 14:00 UTC.",
+            MemoryType::Fact,
+        );
+
+        let neighbors = vec![existing.clone()];
+        let specific_q = scorer.score(&specific, &neighbors);
+        let fragment_q = scorer.score(&fragment, &neighbors);
+        let duplicate_q = scorer.score(&duplicate, &neighbors);
+
+        assert!(specific_q.score > fragment_q.score);
+        assert!(specific_q.score > duplicate_q.score);
+        assert_eq!(
+            duplicate_q.duplicate_of.as_deref(),
+            Some(existing.id.as_str())
+        );
+        assert_eq!(
+            CurationItem::new(duplicate, duplicate_q).suggested,
+            CurationAction::Merge(existing.id)
+        );
+    }
+
+    #[tokio::test]
+    async fn curation_queue_lists_and_resolves_low_quality() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let junk = Memory::new("ok", MemoryType::Fact);
+        let good = Memory::new(
+            "The staging cluster runs Postgres 16 on three nodes in Frankfurt",
+            MemoryType::Fact,
+        );
+        memory_system.save(&junk).await.unwrap();
+        memory_system.save(&good).await.unwrap();
+
+        let good_quality = memory_system.quality(&good.id).await.unwrap().unwrap();
+        let queue = memory_system
+            .curation_queue(good_quality.score, 10)
+            .await
+            .unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].memory.id, junk.id);
+
+        assert!(memory_system
+            .resolve_curation(&junk.id, CurationAction::Keep)
+            .await
+            .unwrap());
+        let queue = memory_system
+            .curation_queue(good_quality.score, 10)
+            .await
+            .unwrap();
+        assert!(queue.is_empty());
+    }
+}
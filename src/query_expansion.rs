@@ -0,0 +1,261 @@
+//! Query expansion for short agent queries
+//!
+//! [`QueryExpansion`] widens a query before the final retrieval pass with:
+//! - a user-supplied synonym map,
+//! - tags and entities of the top BM25 hits (pseudo-relevance feedback), and
+//! - tags of memories associated with those hits in the graph.
+//!
+//! Enable it with `SearchConfig::with_expansion`. Expansion terms are added as
+//! optional, down-weighted clauses, so the original query still dominates ranking.
+
+use crate::analytics::extract_entities;
+use crate::search::query_terms;
+use crate::types::Memory;
+use crate::vector_search::is_stopword;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Where an expansion term came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpansionSource {
+    Synonym,
+    Tag,
+    Entity,
+    Association,
+}
+
+/// A term added to the query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpansionTerm {
+    pub term: String,
+    pub source: ExpansionSource,
+    /// Boost applied to the term in the final query
+    pub weight: f32,
+}
+
+/// A query together with the terms expansion added to it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpandedQuery {
+    pub original: String,
+    pub terms: Vec<ExpansionTerm>,
+}
+
+impl ExpandedQuery {
+    /// Whether any terms were added
+    pub fn is_expanded(&self) -> bool {
+        !self.terms.is_empty()
+    }
+
+    /// The original query followed by the expansion terms, for logging
+    pub fn to_query_string(&self) -> String {
+        std::iter::once(self.original.as_str())
+            .chain(self.terms.iter().map(|t| t.term.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Query expansion settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryExpansion {
+    /// Lowercase word or phrase -> synonyms. Synonyms are always added.
+    pub synonyms: HashMap<String, Vec<String>>,
+    /// How many top BM25 hits to mine for tags and entities
+    pub feedback_docs: usize,
+    /// Maximum number of terms to add, synonyms included
+    pub max_terms: usize,
+    /// Mine tags of the feedback hits
+    pub use_tags: bool,
+    /// Mine named entities from the content of the feedback hits
+    pub use_entities: bool,
+    /// Mine tags of memories associated with the feedback hits
+    pub use_associations: bool,
+    /// Boost of expansion terms relative to the original query (1.0)
+    pub weight: f32,
+}
+
+impl Default for QueryExpansion {
+    fn default() -> Self {
+        Self {
+            synonyms: HashMap::new(),
+            feedback_docs: 5,
+            max_terms: 8,
+            use_tags: true,
+            use_entities: true,
+            use_associations: true,
+            weight: 0.5,
+        }
+    }
+}
+
+impl QueryExpansion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add synonyms for a word or phrase (matched case-insensitively)
+    pub fn with_synonyms<I, S>(mut self, term: &str, synonyms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.synonyms
+            .entry(term.trim().to_lowercase())
+            .or_default()
+            .extend(synonyms.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_feedback_docs(mut self, feedback_docs: usize) -> Self {
+        self.feedback_docs = feedback_docs;
+        self
+    }
+
+    pub fn with_max_terms(mut self, max_terms: usize) -> Self {
+        self.max_terms = max_terms;
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.max(0.0);
+        self
+    }
+
+    pub fn with_associations(mut self, enabled: bool) -> Self {
+        self.use_associations = enabled;
+        self
+    }
+
+    /// Synonyms of the whole query and of each of its words
+    fn synonyms_of(&self, query: &str, words: &[String]) -> Vec<String> {
+        let phrase = words.join(" ");
+        std::iter::once(&phrase)
+            .chain(words.iter())
+            .filter_map(|key| self.synonyms.get(key))
+            .flatten()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty() && !query.eq_ignore_ascii_case(s))
+            .collect()
+    }
+
+    /// Expand `query` from the top hits of a first retrieval pass (best first) and
+    /// the graph neighbours of those hits.
+    pub fn expand(&self, query: &str, feedback: &[Memory], neighbors: &[Memory]) -> ExpandedQuery {
+        let words = query_terms(query);
+        let mut seen: HashSet<String> = words.iter().cloned().collect();
+        let mut terms = Vec::new();
+
+        for synonym in self.synonyms_of(query, &words) {
+            if terms.len() >= self.max_terms {
+                break;
+            }
+            if seen.insert(synonym.clone()) {
+                terms.push(ExpansionTerm {
+                    term: synonym,
+                    source: ExpansionSource::Synonym,
+                    weight: self.weight,
+                });
+            }
+        }
+
+        // Candidates from feedback hits, weighted by the rank of the hit
+        let mut candidates: HashMap<String, (f32, ExpansionSource)> = HashMap::new();
+        let mut add = |term: &str, score: f32, source: ExpansionSource| {
+            let term = term.trim().to_lowercase();
+            if term.chars().count() < 2 || is_stopword(&term) {
+                return;
+            }
+            let entry = candidates.entry(term).or_insert((0.0, source));
+            entry.0 += score;
+        };
+        for (rank, memory) in feedback.iter().take(self.feedback_docs).enumerate() {
+            let score = 1.0 / (rank + 1) as f32;
+            if self.use_tags {
+                for tag in &memory.tags {
+                    add(tag, score, ExpansionSource::Tag);
+                }
+            }
+            if self.use_entities {
+                for entity in extract_entities(&memory.content) {
+                    add(&entity, score, ExpansionSource::Entity);
+                }
+            }
+        }
+        if self.use_associations {
+            for memory in neighbors {
+                for tag in &memory.tags {
+                    add(tag, 0.5, ExpansionSource::Association);
+                }
+            }
+        }
+
+        let mut ranked: Vec<_> = candidates
+            .into_iter()
+            .filter(|(term, _)| !seen.contains(term))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.1 .0
+                .partial_cmp(&a.1 .0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        let top_score = ranked.first().map(|(_, (s, _))| *s).unwrap_or(1.0);
+        for (term, (score, source)) in ranked {
+            if terms.len() >= self.max_terms {
+                break;
+            }
+            if seen.insert(term.clone()) {
+                terms.push(ExpansionTerm {
+                    term,
+                    source,
+                    weight: self.weight * score / top_score,
+                });
+            }
+        }
+
+        ExpandedQuery {
+            original: query.to_string(),
+            terms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExpansionSource, QueryExpansion};
+    use crate::{Memory, MemoryType};
+
+    fn tagged(content: &str, memory_type: MemoryType, tags: &[&str]) -> Memory {
+        let mut memory = Memory::new(content, memory_type);
+        memory.tags = tags.iter().map(|t| t.to_string()).collect();
+        memory
+    }
+
+    #[test]
+    fn synonyms_come_first_and_feedback_fills_the_rest() {
+        let expansion = QueryExpansion::new()
+            .with_synonyms("db", ["database"])
+            .with_max_terms(3);
+        let feedback = vec![
+            tagged(
+                "We chose Postgres for billing",
+                MemoryType::Decision,
+                &["database", "infra"],
+            ),
+            tagged("Backups run nightly", MemoryType::Fact, &["infra"]),
+        ];
+
+        let expanded = expansion.expand("db choice", &feedback, &[]);
+        let terms: Vec<_> = expanded.terms.iter().map(|t| t.term.as_str()).collect();
+        assert_eq!(terms, ["database", "infra", "postgres"]);
+        assert_eq!(expanded.terms[0].source, ExpansionSource::Synonym);
+        assert_eq!(expanded.terms[1].source, ExpansionSource::Tag);
+        assert_eq!(expanded.terms[2].source, ExpansionSource::Entity);
+        assert_eq!(
+            expanded.to_query_string(),
+            "db choice database infra postgres"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Sampled query log and slow-query analysis
+//!
+//! With [`MemorySystem::with_query_log`] every search is timed and a sample of
+//! them is written to the `query_log` table: the query, a hash of the search
+//! config, latency, result count and the top result IDs. [`analyze`] groups
+//! the log by query so operators can see which recalls are slow or keep
+//! coming back empty (`goldfish slow-queries --p95`).
+//!
+//! ```rust,ignore
+//! let memory = MemorySystem::new("./data").await?.with_query_log(
+//!     QueryLogConfig::default()
+//!         .with_sample_rate(0.1)
+//!         .with_slow_threshold(Duration::from_millis(250)),
+//! );
+//! ```
+//!
+//! [`MemorySystem::with_query_log`]: crate::MemorySystem::with_query_log
+
+use crate::embedding::{fnv1a, FNV_OFFSET};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Which queries get logged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryLogConfig {
+    /// Share of queries logged, 0.0 to 1.0
+    pub sample_rate: f64,
+    /// Always log queries at least this slow, whatever the sample rate
+    pub slow_threshold: Option<Duration>,
+    /// Always log queries that found nothing
+    pub log_empty: bool,
+    /// Number of result IDs kept per entry
+    pub top_ids: usize,
+}
+
+impl Default for QueryLogConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 1.0,
+            slow_threshold: None,
+            log_empty: true,
+            top_ids: 5,
+        }
+    }
+}
+
+impl QueryLogConfig {
+    pub fn with_sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    pub fn with_log_empty(mut self, log_empty: bool) -> Self {
+        self.log_empty = log_empty;
+        self
+    }
+
+    pub fn with_top_ids(mut self, top_ids: usize) -> Self {
+        self.top_ids = top_ids;
+        self
+    }
+}
+
+/// Decides which queries to log. Sampling is by count rather than chance, so
+/// a rate of 0.1 logs exactly every tenth query.
+#[derive(Debug)]
+pub struct QueryLogger {
+    config: QueryLogConfig,
+    seen: AtomicU64,
+}
+
+impl QueryLogger {
+    pub fn new(config: QueryLogConfig) -> Self {
+        Self {
+            config,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &QueryLogConfig {
+        &self.config
+    }
+
+    /// Whether a query with this latency and result count should be logged
+    pub fn should_log(&self, latency: Duration, result_count: usize) -> bool {
+        let n = self.seen.fetch_add(1, Ordering::Relaxed);
+        if self
+            .config
+            .slow_threshold
+            .is_some_and(|threshold| latency >= threshold)
+            || (self.config.log_empty && result_count == 0)
+        {
+            return true;
+        }
+        let rate = self.config.sample_rate;
+        ((n + 1) as f64 * rate).floor() > (n as f64 * rate).floor()
+    }
+}
+
+/// One logged query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryLogEntry {
+    pub query: String,
+    /// Search path, e.g. `fulltext` or `hybrid`
+    pub kind: String,
+    /// Hash of the search config, see [`config_hash`]
+    pub config_hash: String,
+    pub latency_ms: f64,
+    pub result_count: usize,
+    pub top_ids: Vec<String>,
+    pub timed_out: bool,
+    pub logged_at: DateTime<Utc>,
+}
+
+/// Stable short hash of a search config, so entries can be grouped by the
+/// settings they ran with
+pub fn config_hash<T: Serialize>(config: &T) -> String {
+    let json = serde_json::to_string(config).unwrap_or_default();
+    format!("{:016x}", fnv1a(json.as_bytes(), FNV_OFFSET))
+}
+
+/// Latency and emptiness of one query across the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStats {
+    pub query: String,
+    pub kind: String,
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    /// Share of runs that found nothing
+    pub empty_rate: f64,
+    pub timed_out: usize,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Group log entries by (normalized query, kind), slowest p95 first
+pub fn analyze(entries: &[QueryLogEntry]) -> Vec<QueryStats> {
+    let mut groups: HashMap<(String, &str), Vec<&QueryLogEntry>> = HashMap::new();
+    for entry in entries {
+        let key = (entry.query.trim().to_lowercase(), entry.kind.as_str());
+        groups.entry(key).or_default().push(entry);
+    }
+
+    let mut stats: Vec<QueryStats> = groups
+        .into_iter()
+        .map(|((query, kind), runs)| {
+            let mut latencies: Vec<f64> = runs.iter().map(|e| e.latency_ms).collect();
+            latencies.sort_by(|a, b| a.total_cmp(b));
+            let count = runs.len();
+            QueryStats {
+                query,
+                kind: kind.to_string(),
+                count,
+                mean_ms: latencies.iter().sum::<f64>() / count as f64,
+                p50_ms: percentile(&latencies, 0.50),
+                p95_ms: percentile(&latencies, 0.95),
+                max_ms: latencies.last().copied().unwrap_or_default(),
+                empty_rate: runs.iter().filter(|e| e.result_count == 0).count() as f64
+                    / count as f64,
+                timed_out: runs.iter().filter(|e| e.timed_out).count(),
+                last_seen: runs.iter().map(|e| e.logged_at).max().unwrap_or_default(),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+    stats
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(query: &str, latency_ms: f64, result_count: usize) -> QueryLogEntry {
+        QueryLogEntry {
+            query: query.to_string(),
+            kind: "hybrid".to_string(),
+            config_hash: String::new(),
+            latency_ms,
+            result_count,
+            top_ids: Vec::new(),
+            timed_out: false,
+            logged_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn samples_by_count_and_groups_by_query() {
+        let logger = QueryLogger::new(
+            QueryLogConfig::default()
+                .with_sample_rate(0.25)
+                .with_slow_threshold(Duration::from_millis(500)),
+        );
+        let logged = (0..8)
+            .filter(|_| logger.should_log(Duration::from_millis(5), 3))
+            .count();
+        assert_eq!(logged, 2);
+        assert!(logger.should_log(Duration::from_secs(1), 3));
+        assert!(logger.should_log(Duration::from_millis(1), 0));
+
+        let mut entries: Vec<_> = (1..=20)
+            .map(|i| entry("Deploy plan", i as f64, 2))
+            .collect();
+        entries.push(entry("lunch", 1.0, 0));
+        let stats = analyze(&entries);
+
+        assert_eq!(stats[0].query, "deploy plan");
+        assert_eq!(stats[0].count, 20);
+        assert_eq!(stats[0].p95_ms, 19.0);
+        assert_eq!(stats[0].max_ms, 20.0);
+        assert_eq!(stats[1].empty_rate, 1.0);
+    }
+}
@@ -0,0 +1,246 @@
+//! Client for a running goldfish-server
+//!
+//! [`RemoteClient`] talks to the server's REST API, for tools that must not
+//! open the data directory themselves, e.g. because the server holds the only
+//! write lock. The goldfish CLI uses it for `--remote`:
+//!
+//! ```rust,ignore
+//! let client = RemoteClient::new("http://localhost:3000")?.with_api_key("s3cret");
+//! let saved = client.create("Deploys happen on Tuesdays", MemoryType::Fact, None).await?;
+//! let hits = client.search("deploy", Some(MemoryType::Fact), 5).await?;
+//! ```
+//!
+//! The API key is sent as a bearer token and checked against the server's
+//! `GOLDFISH_API_KEY`.
+
+use crate::error::{MemoryError, Result};
+use crate::store::{MemoryPage, StoreAnalytics};
+use crate::types::{Memory, MemorySearchResult, MemoryType};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Memory summary returned by the server's create endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteMemory {
+    pub id: String,
+    pub content: String,
+    pub memory_type: String,
+    pub importance: f32,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// REST client for goldfish-server
+#[derive(Clone)]
+pub struct RemoteClient {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl std::fmt::Debug for RemoteClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteClient")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl RemoteClient {
+    /// `base_url` is the server root, e.g. `http://localhost:3000`
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| MemoryError::Configuration(format!("Failed to build HTTP client: {e}")))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        })
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    /// Send a request; `None` when the server answers 404
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Option<reqwest::Response>> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| MemoryError::Remote(format!("{}: {e}", self.base_url)))?;
+        match response.status() {
+            status if status.is_success() => Ok(Some(response)),
+            StatusCode::NOT_FOUND => Ok(None),
+            StatusCode::UNAUTHORIZED => Err(MemoryError::Remote(format!(
+                "{} rejected the API key",
+                self.base_url
+            ))),
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                Err(MemoryError::Remote(format!(
+                    "{} returned {status}: {text}",
+                    self.base_url
+                )))
+            }
+        }
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Option<T>> {
+        match self.send(request).await? {
+            Some(response) => response
+                .json()
+                .await
+                .map(Some)
+                .map_err(|e| MemoryError::Remote(format!("Invalid response: {e}"))),
+            None => Ok(None),
+        }
+    }
+
+    fn not_found(&self, path: &str) -> MemoryError {
+        MemoryError::Remote(format!("{}{} not found", self.base_url, path))
+    }
+
+    /// Whether the server answers its health check
+    pub async fn health(&self) -> Result<bool> {
+        Ok(self
+            .send(self.request(reqwest::Method::GET, "/health"))
+            .await?
+            .is_some())
+    }
+
+    pub async fn create(
+        &self,
+        content: &str,
+        memory_type: MemoryType,
+        importance: Option<f32>,
+    ) -> Result<RemoteMemory> {
+        let body = serde_json::json!({
+            "content": content,
+            "memory_type": memory_type.to_string(),
+            "importance": importance,
+        });
+        self.json(
+            self.request(reqwest::Method::POST, "/v1/memory")
+                .json(&body),
+        )
+        .await?
+        .ok_or_else(|| self.not_found("/v1/memory"))
+    }
+
+    /// Ranked hits with full memories, optionally only of `memory_type`.
+    /// The server applies the type before the limit.
+    pub async fn search(
+        &self,
+        query: &str,
+        memory_type: Option<MemoryType>,
+        limit: usize,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let mut request = self
+            .request(reqwest::Method::GET, "/v1/recall")
+            .query(&[("q", query), ("limit", &limit.to_string())]);
+        if let Some(memory_type) = memory_type {
+            request = request.query(&[("type", memory_type.to_string())]);
+        }
+        self.json(request)
+            .await?
+            .ok_or_else(|| self.not_found("/v1/recall"))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<Memory>> {
+        self.json(self.request(reqwest::Method::GET, &memory_path(id, "")))
+            .await
+    }
+
+    /// Permanently delete a memory; `false` if it does not exist
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let request = self.request(reqwest::Method::DELETE, &memory_path(id, ""));
+        Ok(self.send(request).await?.is_some())
+    }
+
+    /// Soft delete a memory; `false` if it does not exist or is forgotten already
+    pub async fn forget(&self, id: &str) -> Result<bool> {
+        let request = self.request(reqwest::Method::POST, &memory_path(id, "/forget"));
+        Ok(self.send(request).await?.is_some())
+    }
+
+    pub async fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool> {
+        let method = if pinned {
+            reqwest::Method::POST
+        } else {
+            reqwest::Method::DELETE
+        };
+        let request = self.request(method, &memory_path(id, "/pin"));
+        Ok(self.send(request).await?.is_some())
+    }
+
+    /// One page of memories, newest first, like [`MemoryStore::list_page`](crate::MemoryStore::list_page)
+    pub async fn list_page(&self, cursor: Option<&str>, limit: usize) -> Result<MemoryPage> {
+        let mut request = self
+            .request(reqwest::Method::GET, "/v1/memories")
+            .query(&[("limit", limit)]);
+        if let Some(cursor) = cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        self.json(request)
+            .await?
+            .ok_or_else(|| self.not_found("/v1/memories"))
+    }
+
+    /// Store statistics with per-day counts for the last `days` days
+    pub async fn stats(&self, days: i64) -> Result<StoreAnalytics> {
+        let request = self
+            .request(reqwest::Method::GET, "/v1/stats")
+            .query(&[("days", days)]);
+        self.json(request)
+            .await?
+            .ok_or_else(|| self.not_found("/v1/stats"))
+    }
+}
+
+/// `/v1/memory/<id><suffix>` with the ID percent-encoded
+fn memory_path(id: &str, suffix: &str) -> String {
+    let mut encoded = String::with_capacity(id.len());
+    for byte in id.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    format!("/v1/memory/{encoded}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_paths_encode_ids() {
+        assert_eq!(memory_path("6f1c-42", "/pin"), "/v1/memory/6f1c-42/pin");
+        assert_eq!(memory_path("a/b c", ""), "/v1/memory/a%2Fb%20c");
+    }
+}
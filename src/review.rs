@@ -0,0 +1,156 @@
+//! Review workflow for memories from low-trust sources
+//!
+//! A [`ReviewPolicy`] decides which new memories need a human look before the
+//! agent may rely on them. Such memories are held in a review queue in the
+//! [`ReviewStatus::PendingReview`] state instead of being stored, so recall,
+//! search and the context window never see them. Approving a memory saves it
+//! as usual; rejecting it keeps only the queue entry as an audit record.
+//!
+//! Submissions and decisions are announced as [`Pulse::ReviewRequested`] and
+//! [`Pulse::ReviewResolved`] so reviewers can be notified.
+//!
+//! ```rust,ignore
+//! let memory = MemorySystem::new("./data").await?
+//!     .with_review_policy(ReviewPolicy::default().with_low_trust_source("web:"));
+//! memory.save(&scraped).await?; // queued, not recalled
+//! memory.approve(&scraped.id, Some("dana")).await?; // now saved and searchable
+//! ```
+//!
+//! [`Pulse::ReviewRequested`]: crate::Pulse::ReviewRequested
+//! [`Pulse::ReviewResolved`]: crate::Pulse::ReviewResolved
+
+use crate::types::Memory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a memory is in the review workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    PendingReview,
+    Approved,
+    Rejected,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::PendingReview => "pending_review",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending_review" => Some(ReviewStatus::PendingReview),
+            "approved" => Some(ReviewStatus::Approved),
+            "rejected" => Some(ReviewStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which new memories must be reviewed. The default reviews nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewPolicy {
+    /// Memories whose `source` starts with one of these need review
+    pub low_trust_sources: Vec<String>,
+    /// Memories whose source reliability is below this need review
+    pub min_source_reliability: Option<f32>,
+}
+
+impl ReviewPolicy {
+    pub fn with_low_trust_source(mut self, prefix: impl Into<String>) -> Self {
+        self.low_trust_sources.push(prefix.into());
+        self
+    }
+
+    pub fn with_min_source_reliability(mut self, reliability: f32) -> Self {
+        self.min_source_reliability = Some(reliability.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Why `memory` needs review, or `None` if it can be saved directly
+    pub fn review_reason(&self, memory: &Memory) -> Option<String> {
+        if let Some(source) = &memory.source {
+            if let Some(prefix) = self
+                .low_trust_sources
+                .iter()
+                .find(|prefix| source.starts_with(prefix.as_str()))
+            {
+                return Some(format!("source {source} matches low-trust source {prefix}"));
+            }
+        }
+        let reliability = memory.confidence.factors.source_reliability;
+        match self.min_source_reliability {
+            Some(min) if reliability < min => Some(format!(
+                "source reliability {reliability:.2} is below {min:.2}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// A memory in the review queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    /// The memory as submitted; saved unchanged on approval
+    pub memory: Memory,
+    pub status: ReviewStatus,
+    /// Why the policy held the memory back
+    pub reason: String,
+    pub submitted_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub reviewer: Option<String>,
+    /// Reviewer's comment, e.g. why a memory was rejected
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Memory, MemorySystem, MemoryType, ReviewPolicy, ReviewStatus};
+
+    #[tokio::test]
+    async fn low_trust_memories_wait_for_approval() {
+        let memory = MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_review_policy(ReviewPolicy::default().with_low_trust_source("web:"));
+
+        let scraped = Memory::new(
+            "The API rate limit is 100 requests per minute",
+            MemoryType::Fact,
+        )
+        .with_source("web:forum");
+        let rumor = Memory::new("The API rate limit will double next year", MemoryType::Fact)
+            .with_source("web:blog");
+        memory.save(&scraped).await.unwrap();
+        memory.save(&rumor).await.unwrap();
+
+        assert!(memory.load(&scraped.id).await.unwrap().is_none());
+        assert!(memory.search("rate limit").await.unwrap().is_empty());
+        assert_eq!(memory.pending_reviews(10).await.unwrap().len(), 2);
+
+        assert!(memory.approve(&scraped.id, Some("dana")).await.unwrap());
+        assert!(memory
+            .reject(&rumor.id, Some("dana"), Some("speculation"))
+            .await
+            .unwrap());
+        assert!(!memory.approve(&rumor.id, None).await.unwrap());
+
+        let found = memory.search("rate limit").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].memory.id, scraped.id);
+        let rejected = memory.review(&rumor.id).await.unwrap().unwrap();
+        assert_eq!(rejected.status, ReviewStatus::Rejected);
+        assert_eq!(rejected.note.as_deref(), Some("speculation"));
+        assert!(memory.pending_reviews(10).await.unwrap().is_empty());
+    }
+}
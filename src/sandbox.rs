@@ -0,0 +1,356 @@
+//! Sandboxes for speculative memory changes
+//!
+//! `MemorySystem::sandbox` returns a [`MemorySandbox`]: a copy-on-write overlay
+//! over the real memory system. Saves, updates, forgets, restores and deletes only
+//! touch the overlay; loads and searches see the overlay on top of the real store.
+//! The agent can explore a hypothetical, then [`commit`](MemorySandbox::commit)
+//! the changes (replayed in order, each recording a version like any other
+//! change) or [`discard`](MemorySandbox::discard) them. A commit that fails
+//! partway hands back a sandbox holding the changes it did not apply.
+
+use crate::error::{MemoryError, Result};
+use crate::search::SearchConfig;
+use crate::types::{Memory, MemoryId, MemorySearchResult};
+use crate::MemorySystem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A change recorded in a sandbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "op")]
+pub enum SandboxChange {
+    Save { memory: Memory },
+    Update { memory: Memory },
+    Forget { id: MemoryId },
+    Restore { id: MemoryId },
+    Delete { id: MemoryId },
+}
+
+#[derive(Default)]
+struct Overlay {
+    /// Current state of every memory touched in the sandbox; `None` if deleted
+    memories: HashMap<MemoryId, Option<Memory>>,
+    changes: Vec<SandboxChange>,
+}
+
+/// A [`MemorySandbox::commit`] that stopped at a failing change
+#[derive(Debug, thiserror::Error)]
+#[error("Sandbox commit stopped after {applied} changes: {error}")]
+pub struct SandboxCommitError {
+    /// Changes applied to the real memory system before the failure
+    pub applied: usize,
+    /// Why the next change failed
+    #[source]
+    pub error: MemoryError,
+    /// The sandbox, holding the failed change and every change after it
+    pub remaining: MemorySandbox,
+}
+
+impl From<SandboxCommitError> for MemoryError {
+    fn from(e: SandboxCommitError) -> Self {
+        e.error
+    }
+}
+
+/// Copy-on-write overlay over a [`MemorySystem`]
+pub struct MemorySandbox {
+    base: MemorySystem,
+    overlay: Mutex<Overlay>,
+}
+
+impl std::fmt::Debug for MemorySandbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemorySandbox")
+            .field("changes", &self.lock().changes.len())
+            .finish()
+    }
+}
+
+impl MemorySandbox {
+    pub(crate) fn new(base: MemorySystem) -> Self {
+        Self {
+            base,
+            overlay: Mutex::new(Overlay::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Overlay> {
+        self.overlay.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, id: &str, memory: Option<Memory>, change: SandboxChange) {
+        let mut overlay = self.lock();
+        overlay.memories.insert(id.to_string(), memory);
+        overlay.changes.push(change);
+    }
+
+    /// Save a memory in the sandbox
+    pub async fn save(&self, memory: &Memory) -> Result<()> {
+        self.record(
+            &memory.id,
+            Some(memory.clone()),
+            SandboxChange::Save {
+                memory: memory.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Update a memory in the sandbox
+    pub async fn update(&self, memory: &Memory) -> Result<()> {
+        self.record(
+            &memory.id,
+            Some(memory.clone()),
+            SandboxChange::Update {
+                memory: memory.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Forget a memory in the sandbox. Returns `false` if it is missing or
+    /// already forgotten.
+    pub async fn forget(&self, id: &str) -> Result<bool> {
+        self.set_forgotten(id, true).await
+    }
+
+    /// Restore a forgotten memory in the sandbox
+    pub async fn restore(&self, id: &str) -> Result<bool> {
+        self.set_forgotten(id, false).await
+    }
+
+    async fn set_forgotten(&self, id: &str, forgotten: bool) -> Result<bool> {
+        let Some(mut memory) = self.load(id).await? else {
+            return Ok(false);
+        };
+        if memory.forgotten == forgotten {
+            return Ok(false);
+        }
+        memory.forgotten = forgotten;
+        memory.updated_at = self.base.store.now();
+
+        let change = if forgotten {
+            SandboxChange::Forget { id: id.to_string() }
+        } else {
+            SandboxChange::Restore { id: id.to_string() }
+        };
+        self.record(id, Some(memory), change);
+        Ok(true)
+    }
+
+    /// Delete a memory in the sandbox
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.record(id, None, SandboxChange::Delete { id: id.to_string() });
+        Ok(())
+    }
+
+    /// Load a memory as the sandbox sees it
+    pub async fn load(&self, id: &str) -> Result<Option<Memory>> {
+        let overlaid = self.lock().memories.get(id).cloned();
+        match overlaid {
+            Some(memory) => Ok(memory),
+            None => self.base.load(id).await,
+        }
+    }
+
+    /// Search the real store with sandbox changes applied.
+    ///
+    /// Memories changed in the sandbox are scored against each other rather than
+    /// the whole corpus, so their scores are only roughly comparable.
+    pub async fn search(&self, query: &str) -> Result<Vec<MemorySearchResult>> {
+        self.search_with_config(query, &SearchConfig::default())
+            .await
+    }
+
+    /// Search with custom configuration and sandbox changes applied
+    pub async fn search_with_config(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let (touched, changed): (Vec<MemoryId>, Vec<Memory>) = {
+            let overlay = self.lock();
+            (
+                overlay.memories.keys().cloned().collect(),
+                overlay.memories.values().flatten().cloned().collect(),
+            )
+        };
+
+        let widened = SearchConfig {
+            max_results: config.max_results + touched.len(),
+            ..config.clone()
+        };
+        let mut results: Vec<MemorySearchResult> = self
+            .base
+            .search_with_config(query, &widened)
+            .await?
+            .into_iter()
+            .filter(|r| !touched.contains(&r.memory.id))
+            .collect();
+        results.extend(self.base.search.search_snapshot(query, config, &changed)?);
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(config.max_results);
+        for (i, r) in results.iter_mut().enumerate() {
+            r.rank = i + 1;
+        }
+        Ok(results)
+    }
+
+    /// Changes recorded so far, in order
+    pub fn changes(&self) -> Vec<SandboxChange> {
+        self.lock().changes.clone()
+    }
+
+    /// Whether the sandbox has no changes
+    pub fn is_empty(&self) -> bool {
+        self.lock().changes.is_empty()
+    }
+
+    /// Apply the changes to the real memory system, in order, and return how many
+    /// were applied. Stops at the first failing change; the error carries the
+    /// sandbox with that change and the ones after it, so they are not lost.
+    pub async fn commit(self) -> std::result::Result<usize, SandboxCommitError> {
+        let changes = std::mem::take(&mut self.lock().changes);
+        let total = changes.len();
+        let mut pending = changes.into_iter();
+        while let Some(change) = pending.next() {
+            if let Err(error) = self.apply(&change).await {
+                let mut overlay = self.lock();
+                overlay.changes.push(change);
+                overlay.changes.extend(pending);
+                let applied = total - overlay.changes.len();
+                drop(overlay);
+                return Err(SandboxCommitError {
+                    applied,
+                    error,
+                    remaining: self,
+                });
+            }
+        }
+        Ok(total)
+    }
+
+    async fn apply(&self, change: &SandboxChange) -> Result<()> {
+        match change {
+            SandboxChange::Save { memory } => self.base.save(memory).await,
+            SandboxChange::Update { memory } => self.base.update(memory).await,
+            SandboxChange::Forget { id } => self.base.forget(id).await.map(|_| ()),
+            SandboxChange::Restore { id } => self.base.restore(id).await.map(|_| ()),
+            SandboxChange::Delete { id } => self.base.delete(id).await,
+        }
+    }
+
+    /// Drop the changes without applying them
+    pub fn discard(self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SandboxChange;
+    use crate::error::Result;
+    use crate::{Memory, MemoryMiddleware, MemorySystem, MemoryType, SaveDecision};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    struct RejectDrafts;
+
+    #[async_trait]
+    impl MemoryMiddleware for RejectDrafts {
+        fn name(&self) -> &'static str {
+            "reject-drafts"
+        }
+
+        async fn on_before_save(&self, memory: &mut Memory) -> Result<SaveDecision> {
+            if memory.content.starts_with("DRAFT") {
+                return Ok(SaveDecision::Veto("drafts are not saved".to_string()));
+            }
+            Ok(SaveDecision::Continue)
+        }
+    }
+
+    #[tokio::test]
+    async fn sandbox_changes_stay_isolated_until_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let current = Memory::new("The launch is planned for March", MemoryType::Fact);
+        memory_system.save(&current).await.unwrap();
+
+        let sandbox = memory_system.sandbox();
+        let hypothetical = Memory::new("The launch slips to June", MemoryType::Fact);
+        sandbox.save(&hypothetical).await.unwrap();
+        assert!(sandbox.forget(&current.id).await.unwrap());
+
+        let ids: Vec<_> = sandbox
+            .search("launch")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.memory.id)
+            .collect();
+        assert_eq!(ids, [hypothetical.id.as_str()]);
+        assert!(memory_system
+            .load(&hypothetical.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(memory_system.search("launch").await.unwrap().len(), 1);
+
+        sandbox.discard();
+        assert!(
+            !memory_system
+                .load(&current.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .forgotten
+        );
+
+        let sandbox = memory_system.sandbox();
+        sandbox.save(&hypothetical).await.unwrap();
+        sandbox.forget(&current.id).await.unwrap();
+        assert_eq!(sandbox.commit().await.unwrap(), 2);
+
+        let results = memory_system.search("launch").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, hypothetical.id);
+    }
+
+    #[tokio::test]
+    async fn failed_commit_hands_back_the_unapplied_changes() {
+        let memory_system = MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_middleware(Arc::new(RejectDrafts));
+        let first = Memory::new("Budget approved", MemoryType::Fact);
+        let draft = Memory::new("DRAFT: hire two engineers", MemoryType::Fact);
+        let last = Memory::new("Office moves in May", MemoryType::Fact);
+
+        let sandbox = memory_system.sandbox();
+        for memory in [&first, &draft, &last] {
+            sandbox.save(memory).await.unwrap();
+        }
+        let failed = sandbox.commit().await.unwrap_err();
+        assert_eq!(failed.applied, 1);
+        assert_eq!(
+            failed.remaining.changes(),
+            [
+                SandboxChange::Save {
+                    memory: draft.clone()
+                },
+                SandboxChange::Save {
+                    memory: last.clone()
+                },
+            ]
+        );
+        assert!(memory_system.load(&first.id).await.unwrap().is_some());
+        assert!(memory_system.load(&last.id).await.unwrap().is_none());
+        assert!(failed.remaining.load(&last.id).await.unwrap().is_some());
+    }
+}
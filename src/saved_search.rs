@@ -0,0 +1,171 @@
+//! Saved searches (standing queries)
+//!
+//! A [`SavedSearch`] is a named query plus [`SearchConfig`], persisted per
+//! namespace. `MemorySystem::watch_saved_searches` evaluates every saved search
+//! against each new memory; a match emits a [`Pulse::SavedSearchHit`] and, when
+//! the search has a webhook and the `webhooks` feature is enabled, POSTs the
+//! [`SavedSearchHit`] as JSON to it.
+//!
+//! [`Pulse::SavedSearchHit`]: crate::Pulse::SavedSearchHit
+
+use crate::search::SearchConfig;
+use crate::types::Memory;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A persisted standing query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: String,
+    /// Unique within a namespace
+    pub name: String,
+    pub query: String,
+    pub config: SearchConfig,
+    /// URL that receives a JSON POST for each hit
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_hit_at: Option<DateTime<Utc>>,
+    pub hit_count: i64,
+}
+
+impl SavedSearch {
+    pub fn new(name: impl Into<String>, query: impl Into<String>, config: SearchConfig) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.into(),
+            query: query.into(),
+            config,
+            webhook_url: None,
+            created_at: Utc::now(),
+            last_hit_at: None,
+            hit_count: 0,
+        }
+    }
+
+    pub fn with_webhook(mut self, url: impl Into<String>) -> Self {
+        self.webhook_url = Some(url.into());
+        self
+    }
+}
+
+/// A new memory that matched a saved search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearchHit {
+    pub search_id: String,
+    pub search_name: String,
+    pub query: String,
+    pub memory: Memory,
+    pub score: f32,
+    pub matched_at: DateTime<Utc>,
+}
+
+/// POST a hit to a webhook in the background. Failures are logged, not retried.
+pub(crate) fn spawn_webhook(url: String, hit: SavedSearchHit) {
+    #[cfg(feature = "webhooks")]
+    tokio::spawn(async move {
+        let result = reqwest::Client::new()
+            .post(&url)
+            .json(&hit)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            tracing::warn!(
+                "Saved search '{}' webhook to {} failed: {}",
+                hit.search_name,
+                url,
+                e
+            );
+        }
+    });
+
+    #[cfg(not(feature = "webhooks"))]
+    tracing::warn!(
+        "Saved search '{}' has webhook {} but the `webhooks` feature is disabled",
+        hit.search_name,
+        url
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Memory, MemorySystem, MemoryType, Pulse, PulseFilter, PulseType, SearchConfig};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn saved_search_fires_on_matching_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        memory_system
+            .create_saved_search("deploys", "deploy", SearchConfig::default())
+            .await
+            .unwrap();
+        assert!(memory_system
+            .create_saved_search("deploys", "release", SearchConfig::default())
+            .await
+            .is_err());
+
+        let mut hits = memory_system
+            .pulses()
+            .subscribe_filtered(PulseFilter::new().with_pulse_type(PulseType::SavedSearchHit));
+        let watcher = memory_system.watch_saved_searches();
+
+        let unrelated = Memory::new("Lunch is at noon", MemoryType::Fact);
+        let matching = Memory::new("We deploy on Fridays", MemoryType::Fact);
+        memory_system.save(&unrelated).await.unwrap();
+        memory_system.save(&matching).await.unwrap();
+
+        let pulse = tokio::time::timeout(Duration::from_secs(5), hits.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match pulse {
+            Pulse::SavedSearchHit {
+                search_name,
+                memory,
+                ..
+            } => {
+                assert_eq!(search_name, "deploys");
+                assert_eq!(memory.id, matching.id);
+            }
+            other => panic!("unexpected pulse {other:?}"),
+        }
+        watcher.abort();
+
+        let saved = memory_system.saved_searches().await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].hit_count, 1);
+        assert!(memory_system.delete_saved_search("deploys").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn saved_search_fires_for_memories_saved_through_a_clone() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+        memory_system
+            .create_saved_search("outages", "outage", SearchConfig::default())
+            .await
+            .unwrap();
+
+        let mut hits = memory_system
+            .pulses()
+            .subscribe_filtered(PulseFilter::new().with_pulse_type(PulseType::SavedSearchHit));
+        let watcher = memory_system.watch_saved_searches();
+
+        let writer = memory_system.clone();
+        let outage = Memory::new("The outage was caused by DNS", MemoryType::Event);
+        writer.save(&outage).await.unwrap();
+
+        // Well under the index reload delay: the hit must not wait for it
+        let pulse = tokio::time::timeout(Duration::from_millis(300), hits.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match pulse {
+            Pulse::SavedSearchHit { memory, .. } => assert_eq!(memory.id, outage.id),
+            other => panic!("unexpected pulse {other:?}"),
+        }
+        watcher.abort();
+    }
+}
@@ -7,13 +7,22 @@
 //! - Importance-weighted result ranking
 
 use crate::error::{MemoryError, Result};
-use crate::types::{Memory, MemorySearchResult, MemoryType};
+use crate::language::normalize_language;
+use crate::numeric_range::{extract_numeric_ranges, metadata_number, NumericRange};
+use crate::platform_fs;
+use crate::query_expansion::{ExpandedQuery, QueryExpansion};
+use crate::types::{Memory, MemorySearchResult, MemoryType, SessionId};
 use crate::MemoryStore;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tantivy::collector::TopDocs;
-use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, QueryParser};
+use tantivy::query::{
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, Query, QueryParser, RangeQuery,
+    TermQuery,
+};
 use tantivy::schema::*;
 use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, Term};
 
@@ -47,6 +56,53 @@ pub enum SearchSort {
     LastAccess,
 }
 
+/// Retry applied when a query returns no hits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SearchFallback {
+    /// Fuzzy term matching (edit distance 1) to absorb typos
+    Fuzzy,
+    /// Each query word as its own term, ignoring query syntax; any match counts
+    AnyTerm,
+    /// Semantic search over the vector backend only
+    VectorOnly,
+}
+
+impl SearchFallback {
+    /// Fuzzy, then any-term, then vector-only
+    pub const CASCADE: [SearchFallback; 3] = [
+        SearchFallback::Fuzzy,
+        SearchFallback::AnyTerm,
+        SearchFallback::VectorOnly,
+    ];
+}
+
+/// Results of a search together with the fallback that produced them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchOutcome {
+    pub results: Vec<MemorySearchResult>,
+    /// `None` when the original query matched (or nothing matched at all)
+    pub fallback: Option<SearchFallback>,
+    /// `config.timeout` expired before a stage finished
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+impl SearchOutcome {
+    /// Whether the results came from a fallback rather than the original query
+    pub fn used_fallback(&self) -> bool {
+        self.fallback.is_some()
+    }
+}
+
+/// Lowercased alphanumeric words of a query, with query syntax stripped
+pub(crate) fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
 /// Search configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchConfig {
@@ -58,6 +114,33 @@ pub struct SearchConfig {
     pub fuzzy: bool,
     /// Boost recently accessed memories in scoring
     pub boost_recent: bool,
+    /// Only return memories whose emotional valence lies in `[min, max]`
+    #[serde(default)]
+    pub valence_range: Option<(f32, f32)>,
+    /// Scale scores by `1 + valence_boost * valence`. Positive values favour
+    /// positive memories, negative values favour negative ones, 0 disables.
+    #[serde(default)]
+    pub valence_boost: f32,
+    /// Fallbacks tried in order when a full-text query returns no hits.
+    /// Only used by `search_with_fallback`.
+    #[serde(default)]
+    pub fallbacks: Vec<SearchFallback>,
+    /// Expand full-text queries with synonyms, feedback terms and graph neighbours
+    #[serde(default)]
+    pub expansion: Option<QueryExpansion>,
+    /// Give up after this long and return what was found. Honoured by
+    /// `MemorySystem`'s search methods.
+    #[serde(default)]
+    pub timeout: Option<std::time::Duration>,
+    /// Only return memories in this language (ISO 639-1)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Only return memories whose numeric metadata values lie in all of these
+    #[serde(default)]
+    pub numeric_ranges: Vec<NumericRange>,
+    /// Only return memories saved in this session or outside any session
+    #[serde(default)]
+    pub session: Option<SessionId>,
 }
 
 impl Default for SearchConfig {
@@ -69,11 +152,130 @@ impl Default for SearchConfig {
             max_results: 10,
             fuzzy: false,
             boost_recent: true,
+            valence_range: None,
+            valence_boost: 0.0,
+            fallbacks: Vec::new(),
+            expansion: None,
+            timeout: None,
+            language: None,
+            numeric_ranges: Vec::new(),
+            session: None,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Retry empty full-text queries with the given fallbacks, in order
+    pub fn with_fallbacks(mut self, fallbacks: impl IntoIterator<Item = SearchFallback>) -> Self {
+        self.fallbacks = fallbacks.into_iter().collect();
+        self
+    }
+
+    /// Retry empty full-text queries with the full `SearchFallback::CASCADE`
+    pub fn with_fallback_cascade(self) -> Self {
+        self.with_fallbacks(SearchFallback::CASCADE)
+    }
+
+    /// Expand full-text queries before the final retrieval pass
+    pub fn with_expansion(mut self, expansion: QueryExpansion) -> Self {
+        self.expansion = Some(expansion);
+        self
+    }
+
+    /// Only return memories in `language` (ISO 639-1, e.g. `"de"`)
+    pub fn with_language(mut self, language: impl AsRef<str>) -> Self {
+        self.language = Some(normalize_language(language.as_ref()));
+        self
+    }
+
+    /// Scope results to `session_id` plus durable memories (those saved
+    /// outside any session)
+    pub fn with_session(mut self, session_id: impl Into<SessionId>) -> Self {
+        self.session = Some(session_id.into());
+        self
+    }
+
+    /// Whether a memory passes the session scope
+    pub fn matches_session(&self, memory: &Memory) -> bool {
+        match (&self.session, &memory.session_id) {
+            (Some(session), Some(memory_session)) => session == memory_session,
+            _ => true,
+        }
+    }
+
+    /// Whether a memory passes the valence filter
+    pub fn matches_valence(&self, memory: &Memory) -> bool {
+        match self.valence_range {
+            Some((min, max)) => memory.emotional_valence >= min && memory.emotional_valence <= max,
+            None => true,
+        }
+    }
+
+    /// Whether a memory passes the language filter; memories of unknown
+    /// language never do
+    pub fn matches_language(&self, memory: &Memory) -> bool {
+        match &self.language {
+            Some(language) => memory
+                .language
+                .as_deref()
+                .is_some_and(|l| normalize_language(l) == *language),
+            None => true,
+        }
+    }
+
+    /// Score multiplier from the valence boost (never negative)
+    pub fn valence_factor(&self, memory: &Memory) -> f32 {
+        (1.0 + self.valence_boost * memory.emotional_valence).max(0.0)
+    }
+
+    /// Only return memories whose metadata value under `range.key` is in range
+    pub fn with_numeric_range(mut self, range: NumericRange) -> Self {
+        self.numeric_ranges.push(range);
+        self
+    }
+
+    /// Whether a memory passes every numeric range filter
+    pub fn matches_numeric(&self, memory: &Memory) -> bool {
+        self.numeric_ranges
+            .iter()
+            .all(|range| range.matches(memory))
+    }
+
+    /// How many candidates to fetch so that post-filtering can still fill `max_results`
+    fn candidate_limit(&self) -> usize {
+        if self.valence_range.is_some()
+            || self.language.is_some()
+            || !self.numeric_ranges.is_empty()
+            || self.session.is_some()
+        {
+            self.max_results.saturating_mul(4)
+        } else {
+            self.max_results
         }
     }
 }
 
+/// Prefix of the Tantivy fields holding numeric metadata values
+const NUMERIC_FIELD_PREFIX: &str = "num_";
+
+/// Whether opening the index failed on a file briefly held by another process,
+/// e.g. a virus scanner on Windows
+fn is_transient(err: &tantivy::TantivyError) -> bool {
+    use tantivy::directory::error::{OpenDirectoryError, OpenReadError, OpenWriteError};
+    use tantivy::TantivyError;
+
+    let io_error = match err {
+        TantivyError::IoError(e)
+        | TantivyError::OpenDirectoryError(OpenDirectoryError::IoError { io_error: e, .. })
+        | TantivyError::OpenReadError(OpenReadError::IoError { io_error: e, .. })
+        | TantivyError::OpenWriteError(OpenWriteError::IoError { io_error: e, .. }) => e,
+        _ => return false,
+    };
+    platform_fs::is_transient(io_error)
+}
+
 /// Tantivy schema field handles
+#[derive(Clone)]
 struct SchemaFields {
     id: Field,
     content: Field,
@@ -81,6 +283,39 @@ struct SchemaFields {
     source: Field,
     tags: Field,
     importance: Field,
+    /// Numeric fast fields by metadata key
+    numeric: Vec<(String, Field)>,
+}
+
+impl SchemaFields {
+    /// Field handles of an existing index's schema
+    fn from_schema(schema: &Schema) -> Result<Self> {
+        let field = |name: &str| {
+            schema.get_field(name).map_err(|_| {
+                MemoryError::SearchIndex(format!("Index schema has no '{name}' field"))
+            })
+        };
+        let numeric = schema
+            .fields()
+            .filter_map(|(field, entry)| {
+                let key = entry.name().strip_prefix(NUMERIC_FIELD_PREFIX)?;
+                Some((key.to_string(), field))
+            })
+            .collect();
+        Ok(Self {
+            id: field("id")?,
+            content: field("content")?,
+            memory_type: field("memory_type")?,
+            source: field("source")?,
+            tags: field("tags")?,
+            importance: field("importance")?,
+            numeric,
+        })
+    }
+
+    fn numeric_keys(&self) -> Vec<String> {
+        self.numeric.iter().map(|(key, _)| key.clone()).collect()
+    }
 }
 
 /// Full-text memory search powered by Tantivy
@@ -90,6 +325,12 @@ pub struct MemorySearch {
     reader: IndexReader,
     schema: Schema,
     fields: SchemaFields,
+    /// Replicas only search; another handle (or process) owns the index writer
+    read_only: bool,
+    /// Single-threaded writer heap in bytes, overriding the per-operation defaults
+    writer_heap: Option<usize>,
+    /// Directory of an on-disk index; `None` for RAM indexes
+    index_path: Option<PathBuf>,
 }
 
 impl std::fmt::Debug for MemorySearch {
@@ -97,6 +338,7 @@ impl std::fmt::Debug for MemorySearch {
         f.debug_struct("MemorySearch")
             .field("store", &"<MemoryStore>")
             .field("index", &"<TantivyIndex>")
+            .field("read_only", &self.read_only)
             .finish()
     }
 }
@@ -106,28 +348,22 @@ impl Clone for MemorySearch {
         Self {
             store: Arc::clone(&self.store),
             index: self.index.clone(),
-            reader: self
-                .index
-                .reader_builder()
-                .reload_policy(ReloadPolicy::OnCommitWithDelay)
-                .try_into()
-                .expect("Failed to create index reader"),
+            // Shared, so a commit through one clone is visible to all of them at
+            // once rather than after the reload delay
+            reader: self.reader.clone(),
             schema: self.schema.clone(),
-            fields: SchemaFields {
-                id: self.fields.id,
-                content: self.fields.content,
-                memory_type: self.fields.memory_type,
-                source: self.fields.source,
-                tags: self.fields.tags,
-                importance: self.fields.importance,
-            },
+            fields: self.fields.clone(),
+            read_only: self.read_only,
+            writer_heap: self.writer_heap,
+            index_path: self.index_path.clone(),
         }
     }
 }
 
 impl MemorySearch {
-    /// Build the Tantivy schema for memory indexing
-    fn build_schema() -> (Schema, SchemaFields) {
+    /// Build the Tantivy schema for memory indexing, with a numeric fast field
+    /// for each of `numeric_keys`
+    fn build_schema(numeric_keys: &[String]) -> (Schema, SchemaFields) {
         let mut schema_builder = Schema::builder();
 
         let id = schema_builder.add_text_field("id", STRING | STORED);
@@ -136,6 +372,16 @@ impl MemorySearch {
         let source = schema_builder.add_text_field("source", STRING | STORED);
         let tags = schema_builder.add_text_field("tags", TEXT | STORED);
         let importance = schema_builder.add_f64_field("importance", FAST | STORED);
+        let numeric = numeric_keys
+            .iter()
+            .map(|key| {
+                let name = format!("{NUMERIC_FIELD_PREFIX}{key}");
+                (
+                    key.clone(),
+                    schema_builder.add_f64_field(&name, INDEXED | FAST),
+                )
+            })
+            .collect();
 
         let schema = schema_builder.build();
         let fields = SchemaFields {
@@ -145,21 +391,45 @@ impl MemorySearch {
             source,
             tags,
             importance,
+            numeric,
         };
 
         (schema, fields)
     }
 
+    /// Whether the index in `index_path` exists and uses the current schema,
+    /// with any numeric fields
+    pub(crate) fn index_is_compatible(index_path: &Path) -> bool {
+        let Ok(index) = Index::open_in_dir(index_path) else {
+            return false;
+        };
+        let (expected, _) = Self::build_schema(&[]);
+        let entries = |schema: &Schema| -> Vec<FieldEntry> {
+            schema.fields().map(|(_, entry)| entry.clone()).collect()
+        };
+        let (actual, expected) = (entries(&index.schema()), entries(&expected));
+        actual.len() >= expected.len()
+            && actual[..expected.len()] == expected[..]
+            && actual[expected.len()..]
+                .iter()
+                .all(|entry| entry.name().starts_with(NUMERIC_FIELD_PREFIX))
+    }
+
     /// Create a new MemorySearch with Tantivy index at the given directory
     pub fn with_dir(store: Arc<MemoryStore>, index_dir: impl AsRef<Path>) -> Result<Self> {
-        let (schema, fields) = Self::build_schema();
+        let (schema, _) = Self::build_schema(&[]);
         let index_path = index_dir.as_ref().join("tantivy_index");
         std::fs::create_dir_all(&index_path)
             .map_err(|e| MemoryError::SearchIndex(format!("Failed to create index dir: {}", e)))?;
 
-        let index = Index::create_in_dir(&index_path, schema.clone())
-            .or_else(|_| Index::open_in_dir(&index_path))
-            .map_err(|e| MemoryError::SearchIndex(format!("Failed to open index: {}", e)))?;
+        let index = platform_fs::retry_with(is_transient, || {
+            Index::create_in_dir(&index_path, schema.clone())
+                .or_else(|_| Index::open_in_dir(&index_path))
+        })
+        .map_err(|e| MemoryError::SearchIndex(format!("Failed to open index: {}", e)))?;
+        // An existing index keeps its numeric fields
+        let schema = index.schema();
+        let fields = SchemaFields::from_schema(&schema)?;
 
         let reader = index
             .reader_builder()
@@ -173,12 +443,94 @@ impl MemorySearch {
             reader,
             schema,
             fields,
+            read_only: false,
+            writer_heap: None,
+            index_path: Some(index_path),
         })
     }
 
+    /// Open the existing index under `index_dir` for searching only.
+    ///
+    /// Any number of read-only handles, in this process or others, can search an
+    /// index while a single writer updates it; they pick up the writer's commits
+    /// shortly after each one. Write methods on a read-only handle fail with
+    /// [`MemoryError::InvalidOperation`].
+    pub fn open_read_only(store: Arc<MemoryStore>, index_dir: impl AsRef<Path>) -> Result<Self> {
+        let index_path = index_dir.as_ref().join("tantivy_index");
+        let index = platform_fs::retry_with(is_transient, || Index::open_in_dir(&index_path))
+            .map_err(|e| {
+                MemoryError::SearchIndex(format!(
+                    "Failed to open index {} for reading: {}",
+                    index_path.display(),
+                    e
+                ))
+            })?;
+        if !Self::index_is_compatible(&index_path) {
+            return Err(MemoryError::SearchIndex(format!(
+                "Index {} uses an incompatible schema; reindex it from the writer",
+                index_path.display()
+            )));
+        }
+
+        let schema = index.schema();
+        let fields = SchemaFields::from_schema(&schema)?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()
+            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create reader: {}", e)))?;
+
+        Ok(Self {
+            store,
+            index,
+            reader,
+            schema,
+            fields,
+            read_only: true,
+            writer_heap: None,
+            index_path: Some(index_path),
+        })
+    }
+
+    /// A read-only handle on the same index with its own reader
+    pub fn read_replica(&self) -> Self {
+        Self {
+            read_only: true,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Pick up the latest commit now instead of waiting for the reload policy
+    pub fn reload(&self) -> Result<()> {
+        self.reader
+            .reload()
+            .map_err(|e| MemoryError::SearchIndex(format!("Failed to reload reader: {}", e)))
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(MemoryError::InvalidOperation(
+                "Search index replica is read-only".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The same index over another handle to the store, e.g. one with a different clock
+    pub(crate) fn with_store(&self, store: Arc<MemoryStore>) -> Self {
+        Self {
+            store,
+            ..self.clone()
+        }
+    }
+
     /// Create a new MemorySearch with an in-memory Tantivy index (for testing)
     pub fn new(store: Arc<MemoryStore>) -> Self {
-        let (schema, fields) = Self::build_schema();
+        let (schema, fields) = Self::build_schema(&[]);
         let index = Index::create_in_ram(schema.clone());
 
         let reader = index
@@ -193,32 +545,114 @@ impl MemorySearch {
             reader,
             schema,
             fields,
+            read_only: false,
+            writer_heap: None,
+            index_path: None,
         }
     }
 
+    /// Index with a single writer thread and at most `bytes` of heap instead
+    /// of the defaults (15 MB per write, 50 MB to reindex). Tantivy needs at
+    /// least 15 MB.
+    pub fn with_writer_heap(mut self, bytes: usize) -> Self {
+        self.writer_heap = Some(bytes);
+        self
+    }
+
+    /// Index these top-level metadata keys as numeric fast fields, so range
+    /// filters on them run inside Tantivy and `price < 100` works in queries.
+    ///
+    /// Changing the keys of an index recreates it empty; run
+    /// [`reindex_all`](Self::reindex_all) afterwards. Read replicas of the old
+    /// index must be reopened.
+    pub fn with_numeric_fields<K: Into<String>>(
+        mut self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Self> {
+        let mut numeric_keys: Vec<String> = Vec::new();
+        for key in keys {
+            let key = key.into();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(MemoryError::Validation(format!(
+                    "Invalid numeric field '{key}': use ASCII letters, digits or '_'"
+                )));
+            }
+            if !numeric_keys.contains(&key) {
+                numeric_keys.push(key);
+            }
+        }
+        numeric_keys.sort();
+        if numeric_keys == self.fields.numeric_keys() {
+            return Ok(self);
+        }
+        self.ensure_writable()?;
+
+        let (schema, fields) = Self::build_schema(&numeric_keys);
+        let index = match &self.index_path {
+            Some(path) => {
+                platform_fs::remove_dir_all(path)?;
+                std::fs::create_dir_all(path)?;
+                Index::create_in_dir(path, schema.clone())
+                    .map_err(|e| MemoryError::SearchIndex(format!("Failed to create index: {e}")))?
+            }
+            None => Index::create_in_ram(schema.clone()),
+        };
+        self.reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()
+            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create reader: {}", e)))?;
+        self.index = index;
+        self.schema = schema;
+        self.fields = fields;
+        Ok(self)
+    }
+
+    /// Metadata keys indexed as numeric fields
+    pub fn numeric_fields(&self) -> Vec<String> {
+        self.fields.numeric_keys()
+    }
+
+    fn writer(&self, default_heap: usize) -> Result<IndexWriter> {
+        match self.writer_heap {
+            Some(heap) => self.index.writer_with_num_threads(1, heap),
+            None => self.index.writer(default_heap),
+        }
+        .map_err(|e| MemoryError::SearchIndex(format!("Failed to create writer: {}", e)))
+    }
+
+    /// The Tantivy document for a memory
+    fn memory_doc(&self, memory: &Memory) -> tantivy::TantivyDocument {
+        let tags_str = memory.tags.join(" ");
+        let source_str = memory.source.as_deref().unwrap_or("");
+
+        let mut document = doc!(
+            self.fields.id => memory.id.as_str(),
+            self.fields.content => memory.content.as_str(),
+            self.fields.memory_type => memory.memory_type.to_string(),
+            self.fields.source => source_str,
+            self.fields.tags => tags_str.as_str(),
+            self.fields.importance => memory.importance as f64,
+        );
+        for (key, field) in &self.fields.numeric {
+            if let Some(value) = metadata_number(memory, key) {
+                document.add_f64(*field, value);
+            }
+        }
+        document
+    }
+
     /// Index a single memory into the Tantivy index
     pub fn index_memory(&self, memory: &Memory) -> Result<()> {
-        let mut writer: IndexWriter = self
-            .index
-            .writer(15_000_000)
-            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create writer: {}", e)))?;
+        self.ensure_writable()?;
+        let mut writer = self.writer(15_000_000)?;
 
         // Delete any existing document with this ID
         let id_term = Term::from_field_text(self.fields.id, &memory.id);
         writer.delete_term(id_term);
 
-        let tags_str = memory.tags.join(" ");
-        let source_str = memory.source.as_deref().unwrap_or("");
-
         writer
-            .add_document(doc!(
-                self.fields.id => memory.id.as_str(),
-                self.fields.content => memory.content.as_str(),
-                self.fields.memory_type => memory.memory_type.to_string(),
-                self.fields.source => source_str,
-                self.fields.tags => tags_str.as_str(),
-                self.fields.importance => memory.importance as f64,
-            ))
+            .add_document(self.memory_doc(memory))
             .map_err(|e| MemoryError::SearchIndex(format!("Failed to add document: {}", e)))?;
 
         writer
@@ -235,10 +669,8 @@ impl MemorySearch {
 
     /// Delete a memory document from the Tantivy index by ID
     pub fn delete_memory(&self, id: &str) -> Result<()> {
-        let mut writer: IndexWriter = self
-            .index
-            .writer(15_000_000)
-            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create writer: {}", e)))?;
+        self.ensure_writable()?;
+        let mut writer = self.writer(15_000_000)?;
 
         let id_term = Term::from_field_text(self.fields.id, id);
         writer.delete_term(id_term);
@@ -256,10 +688,8 @@ impl MemorySearch {
 
     /// Reindex all memories from the store
     pub async fn reindex_all(&self) -> Result<usize> {
-        let mut writer: IndexWriter = self
-            .index
-            .writer(50_000_000)
-            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create writer: {}", e)))?;
+        self.ensure_writable()?;
+        let mut writer = self.writer(50_000_000)?;
 
         // Clear existing index
         writer
@@ -271,21 +701,9 @@ impl MemorySearch {
         for mem_type in MemoryType::ALL {
             let memories = self.store.get_by_type(*mem_type, 10_000).await?;
             for memory in &memories {
-                let tags_str = memory.tags.join(" ");
-                let source_str = memory.source.as_deref().unwrap_or("");
-
-                writer
-                    .add_document(doc!(
-                        self.fields.id => memory.id.as_str(),
-                        self.fields.content => memory.content.as_str(),
-                        self.fields.memory_type => memory.memory_type.to_string(),
-                        self.fields.source => source_str,
-                        self.fields.tags => tags_str.as_str(),
-                        self.fields.importance => memory.importance as f64,
-                    ))
-                    .map_err(|e| {
-                        MemoryError::SearchIndex(format!("Failed to add document: {}", e))
-                    })?;
+                writer.add_document(self.memory_doc(memory)).map_err(|e| {
+                    MemoryError::SearchIndex(format!("Failed to add document: {}", e))
+                })?;
 
                 count += 1;
             }
@@ -305,10 +723,8 @@ impl MemorySearch {
 
     /// Remove a memory from the search index
     pub fn remove_memory(&self, memory_id: &str) -> Result<()> {
-        let mut writer: IndexWriter = self
-            .index
-            .writer(15_000_000)
-            .map_err(|e| MemoryError::SearchIndex(format!("Failed to create writer: {}", e)))?;
+        self.ensure_writable()?;
+        let mut writer = self.writer(15_000_000)?;
 
         let id_term = Term::from_field_text(self.fields.id, memory_id);
         writer.delete_term(id_term);
@@ -331,7 +747,10 @@ impl MemorySearch {
         config: &SearchConfig,
     ) -> Result<Vec<MemorySearchResult>> {
         match config.mode {
-            SearchMode::FullText => self.search_fulltext(query, config).await,
+            SearchMode::FullText => match &config.expansion {
+                Some(expansion) => self.search_expanded(query, config, expansion).await,
+                None => self.search_fulltext(query, config).await,
+            },
             SearchMode::Text => self.search_text_fallback(query, config).await,
             SearchMode::Recent => self.search_metadata(config).await,
             SearchMode::Important => self.search_metadata(config).await,
@@ -339,119 +758,384 @@ impl MemorySearch {
         }
     }
 
-    /// Full-text search using Tantivy
-    async fn search_fulltext(
+    /// Search, retrying with `config.fallbacks` in order while a full-text query
+    /// returns nothing. `SearchFallback::VectorOnly` needs a vector backend and is
+    /// skipped here; `MemorySystem::search_with_fallback` runs it.
+    pub async fn search_with_fallback(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<SearchOutcome> {
+        let results = self.search(query, config).await?;
+        if !results.is_empty() || config.mode != SearchMode::FullText {
+            return Ok(SearchOutcome {
+                results,
+                fallback: None,
+                timed_out: false,
+            });
+        }
+
+        for fallback in &config.fallbacks {
+            let results = self.search_fallback(query, config, *fallback).await?;
+            if !results.is_empty() {
+                return Ok(SearchOutcome {
+                    results,
+                    fallback: Some(*fallback),
+                    timed_out: false,
+                });
+            }
+        }
+
+        Ok(SearchOutcome::default())
+    }
+
+    /// Run a single text fallback. Returns no results for `VectorOnly`.
+    pub async fn search_fallback(
         &self,
         query: &str,
         config: &SearchConfig,
+        fallback: SearchFallback,
     ) -> Result<Vec<MemorySearchResult>> {
-        let searcher = self.reader.searcher();
+        match fallback {
+            SearchFallback::Fuzzy => {
+                let fuzzy = SearchConfig {
+                    fuzzy: true,
+                    ..config.clone()
+                };
+                self.search_fulltext(query, &fuzzy).await
+            }
+            SearchFallback::AnyTerm => self.search_any_term(query, config).await,
+            SearchFallback::VectorOnly => Ok(Vec::new()),
+        }
+    }
 
-        let scored_ids = if config.fuzzy {
-            // Fuzzy search: build fuzzy term queries for each word
-            let words: Vec<&str> = query.split_whitespace().collect();
-            let mut subqueries: Vec<(Occur, Box<dyn tantivy::query::Query>)> = Vec::new();
-
-            for word in &words {
-                let term = Term::from_field_text(self.fields.content, word);
-                let fuzzy = FuzzyTermQuery::new(term, 1, true);
-                subqueries.push((Occur::Should, Box::new(fuzzy)));
+    /// Match any single query word in content or tags
+    async fn search_any_term(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let subqueries: Vec<(Occur, Box<dyn Query>)> = query_terms(query)
+            .iter()
+            .flat_map(|word| self.term_clauses(word, 1.0))
+            .collect();
+        if subqueries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let scored_ids = self.top_ids(&BooleanQuery::new(subqueries), config, "Any-term search")?;
+        self.score_hits(scored_ids, config).await
+    }
+
+    /// Optional clauses matching `word` in content or tags, boosted by `boost`
+    fn term_clauses(&self, word: &str, boost: f32) -> Vec<(Occur, Box<dyn Query>)> {
+        [self.fields.content, self.fields.tags]
+            .into_iter()
+            .map(|field| {
+                let term = TermQuery::new(
+                    Term::from_field_text(field, word),
+                    IndexRecordOption::WithFreqs,
+                );
+                let clause: Box<dyn Query> = Box::new(BoostQuery::new(Box::new(term), boost));
+                (Occur::Should, clause)
+            })
+            .collect()
+    }
+
+    /// Parse a query over content and tags, restricted to `config.memory_type`
+    /// and to numeric ranges, from `config` or written into the query
+    fn parse_fulltext(&self, query: &str, config: &SearchConfig) -> Result<Box<dyn Query>> {
+        let (query, mut ranges) = extract_numeric_ranges(query, &self.fields.numeric_keys());
+        let query_parser =
+            QueryParser::for_index(&self.index, vec![self.fields.content, self.fields.tags]);
+
+        // With fallbacks configured, malformed syntax should not end the cascade
+        let parsed_query: Box<dyn Query> = if query.trim().is_empty() && !ranges.is_empty() {
+            Box::new(AllQuery)
+        } else if config.fallbacks.is_empty() {
+            query_parser
+                .parse_query(&query)
+                .map_err(|e| MemoryError::SearchIndex(format!("Query parse failed: {}", e)))?
+        } else {
+            query_parser.parse_query_lenient(&query).0
+        };
+
+        ranges.extend(config.numeric_ranges.iter().cloned());
+        let filtered = self.filter_by_ranges(parsed_query, &ranges);
+        Ok(self.filter_by_type(filtered, config))
+    }
+
+    /// Require each range on a numeric field; ranges on other keys are left
+    /// to post-filtering
+    fn filter_by_ranges(&self, query: Box<dyn Query>, ranges: &[NumericRange]) -> Box<dyn Query> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = ranges
+            .iter()
+            .filter(|range| self.fields.numeric.iter().any(|(key, _)| *key == range.key))
+            .map(|range| {
+                let field = format!("{NUMERIC_FIELD_PREFIX}{}", range.key);
+                let range_query = RangeQuery::new_f64_bounds(field, range.lower, range.upper);
+                (Occur::Must, Box::new(range_query) as Box<dyn Query>)
+            })
+            .collect();
+        if clauses.is_empty() {
+            return query;
+        }
+        clauses.push((Occur::Must, query));
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    /// Require `config.memory_type`, if set
+    fn filter_by_type(&self, query: Box<dyn Query>, config: &SearchConfig) -> Box<dyn Query> {
+        match config.memory_type {
+            Some(mem_type) => {
+                let type_term =
+                    Term::from_field_text(self.fields.memory_type, &mem_type.to_string());
+                let type_query = TermQuery::new(type_term, IndexRecordOption::Basic);
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Must, query),
+                    (Occur::Must, Box::new(type_query)),
+                ]))
             }
+            None => query,
+        }
+    }
 
-            let combined = BooleanQuery::new(subqueries);
+    /// Run a Tantivy query and return `(memory id, score)` pairs
+    fn top_ids(
+        &self,
+        query: &dyn Query,
+        config: &SearchConfig,
+        what: &str,
+    ) -> Result<Vec<(String, f32)>> {
+        let searcher = self.reader.searcher();
+        let top_docs = searcher
+            .search(query, &TopDocs::with_limit(config.candidate_limit()))
+            .map_err(|e| MemoryError::SearchIndex(format!("{} failed: {}", what, e)))?;
 
-            let top_docs = searcher
-                .search(&combined, &TopDocs::with_limit(config.max_results))
-                .map_err(|e| MemoryError::SearchIndex(format!("Fuzzy search failed: {}", e)))?;
+        Ok(top_docs
+            .into_iter()
+            .filter_map(|(score, doc_address)| {
+                let doc: tantivy::TantivyDocument = searcher.doc(doc_address).ok()?;
+                let id = doc.get_first(self.fields.id)?.as_str()?.to_string();
+                Some((id, score))
+            })
+            .collect())
+    }
 
-            top_docs
+    /// Expand a query from the top hits of a first full-text pass, their graph
+    /// neighbours and the expansion's synonym map
+    pub async fn expand_query(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+        expansion: &QueryExpansion,
+    ) -> Result<ExpandedQuery> {
+        let feedback_config = SearchConfig {
+            max_results: expansion.feedback_docs,
+            expansion: None,
+            ..config.clone()
+        };
+        let feedback: Vec<Memory> = if expansion.feedback_docs == 0 {
+            Vec::new()
+        } else {
+            self.search_fulltext(query, &feedback_config)
+                .await?
                 .into_iter()
-                .filter_map(|(score, doc_address)| {
-                    let doc: tantivy::TantivyDocument = searcher.doc(doc_address).ok()?;
-                    let id = doc.get_first(self.fields.id)?.as_str()?.to_string();
-                    Some((id, score))
-                })
-                .collect::<Vec<_>>()
+                .map(|r| r.memory)
+                .collect()
+        };
+
+        let mut neighbors = Vec::new();
+        if expansion.use_associations {
+            let feedback_ids: Vec<String> = feedback.iter().map(|m| m.id.clone()).collect();
+            for memory in &feedback {
+                let (found, _) = self
+                    .store
+                    .get_neighbors(&memory.id, 1, &feedback_ids)
+                    .await?;
+                neighbors.extend(found.into_iter().filter(|m| !m.forgotten));
+            }
+        }
+
+        Ok(expansion.expand(query, &feedback, &neighbors))
+    }
+
+    /// Full-text search over the original query plus its expansion terms
+    async fn search_expanded(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+        expansion: &QueryExpansion,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let expanded = self.expand_query(query, config, expansion).await?;
+        let base = SearchConfig {
+            expansion: None,
+            ..config.clone()
+        };
+        if !expanded.is_expanded() {
+            return self.search_fulltext(query, &base).await;
+        }
+
+        let original =
+            QueryParser::for_index(&self.index, vec![self.fields.content, self.fields.tags])
+                .parse_query_lenient(query)
+                .0;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Should, original)];
+        for term in &expanded.terms {
+            for word in query_terms(&term.term) {
+                clauses.extend(self.term_clauses(&word, term.weight));
+            }
+        }
+
+        let combined =
+            self.filter_by_ranges(Box::new(BooleanQuery::new(clauses)), &base.numeric_ranges);
+        let combined = self.filter_by_type(combined, &base);
+        let scored_ids = self.top_ids(combined.as_ref(), &base, "Expanded search")?;
+        self.score_hits(scored_ids, &base).await
+    }
+
+    /// Full-text search using Tantivy
+    async fn search_fulltext(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        let scored_ids = if config.fuzzy {
+            self.top_ids(&self.fuzzy_query(query), config, "Fuzzy search")?
         } else {
             // Standard query parser search
-            let query_parser =
-                QueryParser::for_index(&self.index, vec![self.fields.content, self.fields.tags]);
-
-            let parsed_query = query_parser
-                .parse_query(query)
-                .map_err(|e| MemoryError::SearchIndex(format!("Query parse failed: {}", e)))?;
-
-            // Optional: filter by memory type
-            let final_query: Box<dyn tantivy::query::Query> =
-                if let Some(ref mem_type) = config.memory_type {
-                    let type_term =
-                        Term::from_field_text(self.fields.memory_type, &mem_type.to_string());
-                    let type_query =
-                        tantivy::query::TermQuery::new(type_term, IndexRecordOption::Basic);
-                    Box::new(BooleanQuery::new(vec![
-                        (Occur::Must, parsed_query),
-                        (Occur::Must, Box::new(type_query)),
-                    ]))
-                } else {
-                    parsed_query
-                };
+            let parsed_query = self.parse_fulltext(query, config)?;
+            self.top_ids(parsed_query.as_ref(), config, "Search")?
+        };
 
-            let top_docs = searcher
-                .search(&final_query, &TopDocs::with_limit(config.max_results))
-                .map_err(|e| MemoryError::SearchIndex(format!("Search failed: {}", e)))?;
+        self.score_hits(scored_ids, config).await
+    }
 
-            top_docs
-                .into_iter()
-                .filter_map(|(score, doc_address)| {
-                    let doc: tantivy::TantivyDocument = searcher.doc(doc_address).ok()?;
-                    let id = doc.get_first(self.fields.id)?.as_str()?.to_string();
-                    Some((id, score))
-                })
-                .collect::<Vec<_>>()
-        };
+    /// Fuzzy term queries (edit distance 1) for each word, any of which may match
+    fn fuzzy_query(&self, query: &str) -> BooleanQuery {
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = Vec::new();
 
-        // Load full memories from store and build results
-        let mut results = Vec::new();
-        for (rank, (id, tantivy_score)) in scored_ids.into_iter().enumerate() {
-            if let Ok(Some(memory)) = self.store.load(&id).await {
-                // Skip forgotten memories
-                if memory.forgotten {
-                    continue;
-                }
+        for word in query_terms(query) {
+            let term = Term::from_field_text(self.fields.content, &word);
+            let fuzzy = FuzzyTermQuery::new(term, 1, true);
+            subqueries.push((Occur::Should, Box::new(fuzzy)));
+        }
 
-                // Combine Tantivy BM25 score with importance
-                let mut score = tantivy_score;
+        BooleanQuery::new(subqueries)
+    }
 
-                // Boost by importance
-                score *= 1.0 + memory.importance * 0.5;
+    /// Check that `query` is valid under `config` without running it
+    pub fn validate_query(&self, query: &str, config: &SearchConfig) -> Result<()> {
+        if config.mode == SearchMode::FullText && !config.fuzzy {
+            self.parse_fulltext(query, config)?;
+        }
+        Ok(())
+    }
 
-                // Recency boost
-                if config.boost_recent {
-                    let hours_ago =
-                        (chrono::Utc::now() - memory.last_accessed_at).num_hours() as f32;
-                    let recency = 1.0 / (1.0 + hours_ago * 0.01);
-                    score *= 1.0 + recency * 0.3;
-                }
+    /// The full-text score `memory` gets for `query` under `config`, or `None` if the
+    /// search would not return it. The memory must already be indexed.
+    pub fn match_memory(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+        memory: &Memory,
+    ) -> Result<Option<f32>> {
+        if memory.forgotten
+            || !config.matches_valence(memory)
+            || !config.matches_language(memory)
+            || !config.matches_numeric(memory)
+            || !config.matches_session(memory)
+            || config.memory_type.is_some_and(|t| memory.memory_type != t)
+        {
+            return Ok(None);
+        }
 
-                results.push(MemorySearchResult {
-                    memory,
-                    score,
-                    rank: rank + 1,
-                });
+        let text_query: Box<dyn Query> = match config.mode {
+            SearchMode::FullText if config.fuzzy => Box::new(self.fuzzy_query(query)),
+            SearchMode::FullText => self.parse_fulltext(query, config)?,
+            SearchMode::Text => {
+                let matched = memory
+                    .content
+                    .to_lowercase()
+                    .contains(&query.to_lowercase());
+                return Ok(matched.then_some(memory.importance));
             }
-        }
+            // Metadata searches return any memory that passes the filters
+            SearchMode::Recent | SearchMode::Important | SearchMode::Typed => {
+                return Ok(Some(memory.importance));
+            }
+        };
 
-        // Re-sort by combined score and update ranks
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        for (i, r) in results.iter_mut().enumerate() {
-            r.rank = i + 1;
+        let id_query = TermQuery::new(
+            Term::from_field_text(self.fields.id, &memory.id),
+            IndexRecordOption::Basic,
+        );
+        let combined = BooleanQuery::new(vec![
+            (Occur::Must, text_query),
+            (Occur::Must, Box::new(id_query)),
+        ]);
+        let single = SearchConfig {
+            max_results: 1,
+            valence_range: None,
+            ..config.clone()
+        };
+        let hits = self.top_ids(&combined, &single, "Match")?;
+        Ok(hits.first().map(|(_, score)| *score))
+    }
+
+    /// Full-text search over `memories` instead of the store, e.g. historical
+    /// snapshots of them. Builds a throwaway in-memory index.
+    pub fn search_snapshot(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+        memories: &[Memory],
+    ) -> Result<Vec<MemorySearchResult>> {
+        let mut snapshot = Self::new(Arc::clone(&self.store));
+        snapshot.writer_heap = self.writer_heap;
+        let mut writer = snapshot.writer(15_000_000)?;
+        for memory in memories {
+            writer
+                .add_document(snapshot.memory_doc(memory))
+                .map_err(|e| MemoryError::SearchIndex(format!("Failed to add document: {}", e)))?;
         }
+        writer
+            .commit()
+            .map_err(|e| MemoryError::SearchIndex(format!("Failed to commit: {}", e)))?;
+        snapshot
+            .reader
+            .reload()
+            .map_err(|e| MemoryError::SearchIndex(format!("Failed to reload reader: {}", e)))?;
 
-        Ok(results)
+        let scored_ids = if config.fuzzy {
+            snapshot.top_ids(&snapshot.fuzzy_query(query), config, "Snapshot search")?
+        } else {
+            let parsed_query = snapshot.parse_fulltext(query, config)?;
+            snapshot.top_ids(parsed_query.as_ref(), config, "Snapshot search")?
+        };
+
+        let by_id: HashMap<&str, &Memory> = memories.iter().map(|m| (m.id.as_str(), m)).collect();
+        let hits = scored_ids
+            .into_iter()
+            .filter_map(|(id, score)| by_id.get(id.as_str()).map(|m| ((*m).clone(), score)))
+            .collect();
+        Ok(rank_hits(hits, config, self.store.now()))
+    }
+
+    /// Load hit memories from the store, apply filters and boosts, and rank them
+    async fn score_hits(
+        &self,
+        scored_ids: Vec<(String, f32)>,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        // Load full memories from store
+        let mut hits = Vec::new();
+        for (id, tantivy_score) in scored_ids {
+            if let Ok(Some(memory)) = self.store.load(&id).await {
+                hits.push((memory, tantivy_score));
+            }
+        }
+        Ok(rank_hits(hits, config, self.store.now()))
     }
 
     /// Fallback: simple text contains matching (for when Tantivy is unavailable)
@@ -474,13 +1158,18 @@ impl MemorySearch {
             let memories = self.store.get_by_type(*mem_type, 1000).await?;
 
             for memory in memories {
-                if memory.forgotten {
+                if memory.forgotten
+                    || !config.matches_valence(&memory)
+                    || !config.matches_language(&memory)
+                    || !config.matches_numeric(&memory)
+                    || !config.matches_session(&memory)
+                {
                     continue;
                 }
 
                 let content_lower = memory.content.to_lowercase();
                 if content_lower.contains(&query_lower) {
-                    let score = memory.importance;
+                    let score = memory.importance * config.valence_factor(&memory);
                     results.push(MemorySearchResult {
                         memory,
                         score,
@@ -519,8 +1208,15 @@ impl MemorySearch {
             all_memories.extend(memories);
         }
 
-        // Filter out forgotten
-        all_memories.retain(|m| !m.forgotten);
+        // Filter out forgotten memories and those failing the valence, language,
+        // numeric or session filters
+        all_memories.retain(|m| {
+            !m.forgotten
+                && config.matches_valence(m)
+                && config.matches_language(m)
+                && config.matches_numeric(m)
+                && config.matches_session(m)
+        });
 
         // Sort by the requested mode
         match config.sort_by {
@@ -532,13 +1228,13 @@ impl MemorySearch {
                 });
             }
             SearchSort::MostAccessed => {
-                all_memories.sort_by(|a, b| b.access_count.cmp(&a.access_count));
+                all_memories.sort_by_key(|m| std::cmp::Reverse(m.access_count));
             }
             SearchSort::LastAccess => {
-                all_memories.sort_by(|a, b| b.last_accessed_at.cmp(&a.last_accessed_at));
+                all_memories.sort_by_key(|m| std::cmp::Reverse(m.last_accessed_at));
             }
             SearchSort::Recent => {
-                all_memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                all_memories.sort_by_key(|m| std::cmp::Reverse(m.created_at));
             }
         }
 
@@ -548,7 +1244,7 @@ impl MemorySearch {
             .into_iter()
             .enumerate()
             .map(|(i, memory)| MemorySearchResult {
-                score: memory.importance,
+                score: memory.importance * config.valence_factor(&memory),
                 memory,
                 rank: i + 1,
             })
@@ -558,9 +1254,134 @@ impl MemorySearch {
     }
 }
 
+/// Apply filters and boosts to full-text hits and rank them
+fn rank_hits(
+    hits: Vec<(Memory, f32)>,
+    config: &SearchConfig,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<MemorySearchResult> {
+    let mut results = Vec::new();
+    for (rank, (memory, tantivy_score)) in hits.into_iter().enumerate() {
+        // Skip forgotten memories and those outside the valence range or language
+        if memory.forgotten
+            || !config.matches_valence(&memory)
+            || !config.matches_language(&memory)
+            || !config.matches_numeric(&memory)
+            || !config.matches_session(&memory)
+            || config.memory_type.is_some_and(|t| memory.memory_type != t)
+        {
+            continue;
+        }
+
+        // Combine Tantivy BM25 score with importance
+        let mut score = tantivy_score;
+
+        // Boost by importance
+        score *= 1.0 + memory.importance * 0.5;
+
+        // Recency boost
+        if config.boost_recent {
+            let hours_ago = (now - memory.last_accessed_at).num_hours() as f32;
+            let recency = 1.0 / (1.0 + hours_ago * 0.01);
+            score *= 1.0 + recency * 0.3;
+        }
+
+        score *= config.valence_factor(&memory);
+
+        results.push(MemorySearchResult {
+            memory,
+            score,
+            rank: rank + 1,
+        });
+    }
+
+    // Re-sort by combined score and update ranks
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.truncate(config.max_results);
+    for (i, r) in results.iter_mut().enumerate() {
+        r.rank = i + 1;
+    }
+
+    results
+}
+
+/// Round-robin pool of read-only [`MemorySearch`] replicas for high-QPS search.
+///
+/// Each replica has its own index reader, so concurrent queries don't contend on
+/// one handle. Writes still go through a single writable [`MemorySearch`].
+/// Cloning the pool is cheap and shares the replicas.
+#[derive(Debug, Clone)]
+pub struct SearcherPool {
+    replicas: Arc<Vec<MemorySearch>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl SearcherPool {
+    pub fn new(replicas: Vec<MemorySearch>) -> Result<Self> {
+        if replicas.is_empty() {
+            return Err(MemoryError::Configuration(
+                "A searcher pool needs at least one replica".to_string(),
+            ));
+        }
+        Ok(Self {
+            replicas: Arc::new(replicas),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// `n` replicas of an index already open in this process
+    pub fn from_search(search: &MemorySearch, n: usize) -> Result<Self> {
+        Self::new((0..n).map(|_| search.read_replica()).collect())
+    }
+
+    /// `n` replicas of the index under `index_dir`, e.g. one written by another process
+    pub fn open(store: Arc<MemoryStore>, index_dir: impl AsRef<Path>, n: usize) -> Result<Self> {
+        let replicas = (0..n)
+            .map(|_| MemorySearch::open_read_only(Arc::clone(&store), index_dir.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(replicas)
+    }
+
+    pub fn len(&self) -> usize {
+        self.replicas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replicas.is_empty()
+    }
+
+    /// The next replica in round-robin order
+    pub fn acquire(&self) -> &MemorySearch {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.replicas.len();
+        &self.replicas[i]
+    }
+
+    /// Search on the next replica
+    pub async fn search(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<MemorySearchResult>> {
+        self.acquire().search(query, config).await
+    }
+
+    /// Reload every replica's reader
+    pub fn reload(&self) -> Result<()> {
+        self.replicas.iter().try_for_each(MemorySearch::reload)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Memory, MemorySystem, MemoryType};
+    use super::{MemorySearch, SearchFallback, SearcherPool};
+    use crate::{
+        Memory, MemoryStore, MemorySystem, MemorySystemOptions, MemoryType, NumericRange,
+        QueryExpansion, SearchConfig,
+    };
 
     #[tokio::test]
     async fn fulltext_search_finds_saved_memory() {
@@ -573,4 +1394,137 @@ mod tests {
         let results = memory_system.search("memory-safe").await.unwrap();
         assert!(results.iter().any(|r| r.memory.id == memory.id));
     }
+
+    #[tokio::test]
+    async fn numeric_ranges_filter_in_queries_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = MemorySystemOptions::default().with_numeric_field("price");
+        let memory_system = MemorySystem::new_with_options(dir.path(), options)
+            .await
+            .unwrap();
+
+        for (content, price) in [("Cheap laptop offer", 450), ("Premium laptop offer", 2100)] {
+            let mut memory = Memory::new(content, MemoryType::Fact);
+            memory.metadata = Some(serde_json::json!({ "price": price }));
+            memory_system.save(&memory).await.unwrap();
+        }
+
+        let results = memory_system.search("laptop price < 1000").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.content, "Cheap laptop offer");
+
+        let config =
+            SearchConfig::default().with_numeric_range(NumericRange::at_least("price", 1000.0));
+        let results = memory_system
+            .search_with_config("laptop", &config)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.content, "Premium laptop offer");
+    }
+
+    #[tokio::test]
+    async fn valence_range_filters_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let happy = Memory::new("The launch went great", MemoryType::Event).with_valence(0.8);
+        let sad = Memory::new("The launch was delayed again", MemoryType::Event).with_valence(-0.7);
+        memory_system.save(&happy).await.unwrap();
+        memory_system.save(&sad).await.unwrap();
+
+        let config = SearchConfig {
+            valence_range: Some((0.0, 1.0)),
+            ..SearchConfig::default()
+        };
+        let results = memory_system
+            .search_with_config("launch", &config)
+            .await
+            .unwrap();
+        assert!(results.iter().any(|r| r.memory.id == happy.id));
+        assert!(results.iter().all(|r| r.memory.id != sad.id));
+    }
+
+    #[tokio::test]
+    async fn fallback_cascade_recovers_from_typos() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let memory = Memory::new("Deployment runs every Tuesday", MemoryType::Fact);
+        memory_system.save(&memory).await.unwrap();
+
+        let strict = memory_system
+            .search_with_fallback("deploymnet", &SearchConfig::default())
+            .await
+            .unwrap();
+        assert!(strict.results.is_empty());
+        assert_eq!(strict.fallback, None);
+
+        let config = SearchConfig::default().with_fallback_cascade();
+        let fuzzy = memory_system
+            .search_with_fallback("deploymnet", &config)
+            .await
+            .unwrap();
+        assert_eq!(fuzzy.fallback, Some(SearchFallback::Fuzzy));
+        assert_eq!(fuzzy.results[0].memory.id, memory.id);
+
+        let exact = memory_system
+            .search_with_fallback("tuesday", &config)
+            .await
+            .unwrap();
+        assert!(!exact.used_fallback());
+        assert_eq!(exact.results.len(), 1);
+
+        // Both terms are required by the query syntax; any-term relaxes that
+        let any_term_only = SearchConfig::default().with_fallbacks([SearchFallback::AnyTerm]);
+        let any_term = memory_system
+            .search_with_fallback("+tuesday +friday", &any_term_only)
+            .await
+            .unwrap();
+        assert_eq!(any_term.fallback, Some(SearchFallback::AnyTerm));
+        assert_eq!(any_term.results[0].memory.id, memory.id);
+    }
+
+    #[tokio::test]
+    async fn expansion_adds_synonyms_before_retrieval() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+
+        let memory = Memory::new("Picked Postgres as the database", MemoryType::Decision);
+        memory_system.save(&memory).await.unwrap();
+
+        let plain = memory_system.search("db").await.unwrap();
+        assert!(plain.is_empty());
+
+        let config = SearchConfig::default()
+            .with_expansion(QueryExpansion::new().with_synonyms("db", ["database"]));
+        let results = memory_system
+            .search_with_config("db", &config)
+            .await
+            .unwrap();
+        assert_eq!(results[0].memory.id, memory.id);
+    }
+
+    #[tokio::test]
+    async fn read_replicas_see_writer_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MemoryStore::connect_in_memory().await;
+        let writer = MemorySearch::with_dir(store.clone(), dir.path()).unwrap();
+        let pool = SearcherPool::open(store.clone(), dir.path(), 3).unwrap();
+        assert_eq!(pool.len(), 3);
+
+        let memory = Memory::new("Replicas share one index", MemoryType::Fact);
+        store.save(&memory).await.unwrap();
+        writer.index_memory(&memory).unwrap();
+        pool.reload().unwrap();
+
+        for _ in 0..pool.len() {
+            let results = pool
+                .search("replicas", &SearchConfig::default())
+                .await
+                .unwrap();
+            assert_eq!(results[0].memory.id, memory.id);
+        }
+        assert!(pool.acquire().index_memory(&memory).is_err());
+    }
 }
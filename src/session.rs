@@ -0,0 +1,123 @@
+//! Sessions (conversations)
+//!
+//! A session groups the memories saved during one conversation or task run:
+//! [`MemorySystem::start_session`] opens one, memories saved with its
+//! [`SessionId`] belong to it, and [`MemorySystem::end_session`] closes it and
+//! stores a short summary of what it produced. Recall can be scoped to "this
+//! session plus durable memories" (those saved outside any session) with
+//! [`SearchConfig::with_session`](crate::SearchConfig::with_session).
+//!
+//! ```rust,ignore
+//! let session = memory.start_session(Some(json!({ "user": "ana" }))).await?;
+//! memory.save(&Memory::new("Prefers metric units", MemoryType::Preference)
+//!     .with_session_id(&session.id)).await?;
+//! let hits = memory.search_session("units", &session.id).await?;
+//! let ended = memory.end_session(&session.id).await?;
+//! ```
+//!
+//! [`MemorySystem::start_session`]: crate::MemorySystem::start_session
+//! [`MemorySystem::end_session`]: crate::MemorySystem::end_session
+
+use crate::types::{Memory, SessionId};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most important memories listed in a session summary
+pub const SESSION_SUMMARY_ITEMS: usize = 5;
+
+/// A session as stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: SessionId,
+    /// Caller-supplied context: user, channel, task, ...
+    pub metadata: Option<serde_json::Value>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the session is active
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Written when the session ends
+    pub summary: Option<String>,
+}
+
+impl Session {
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+}
+
+/// A session with the memories saved in it, as listed by
+/// [`MemorySystem::list_sessions`](crate::MemorySystem::list_sessions)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    #[serde(flatten)]
+    pub session: Session,
+    /// Memories saved in the session and not forgotten
+    pub memory_count: u64,
+    pub first_memory_at: Option<DateTime<Utc>>,
+    pub last_memory_at: Option<DateTime<Utc>>,
+}
+
+impl SessionInfo {
+    /// From the start of the session to its end, or to its last memory while
+    /// it is still active
+    pub fn span(&self) -> Duration {
+        let end = self
+            .session
+            .ended_at
+            .or(self.last_memory_at)
+            .unwrap_or(self.session.started_at);
+        (end - self.session.started_at).max(Duration::zero())
+    }
+}
+
+/// Summary of a session's memories: how many were saved, then the first line
+/// of the most important ones. `None` when the session saved nothing.
+pub fn summarize_session(memories: &[Memory]) -> Option<String> {
+    if memories.is_empty() {
+        return None;
+    }
+
+    let mut ranked: Vec<&Memory> = memories.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.importance
+            .partial_cmp(&a.importance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut summary = format!(
+        "{} {} saved",
+        memories.len(),
+        if memories.len() == 1 {
+            "memory"
+        } else {
+            "memories"
+        }
+    );
+    for memory in ranked.into_iter().take(SESSION_SUMMARY_ITEMS) {
+        let line = memory.content.lines().next().unwrap_or_default();
+        let line: String = line.chars().take(200).collect();
+        summary.push_str(&format!("\n- [{}] {}", memory.memory_type, line));
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    #[test]
+    fn summary_lists_most_important_first() {
+        assert!(summarize_session(&[]).is_none());
+
+        let memories = vec![
+            Memory::new("Asked about the weather", MemoryType::Event).with_importance(0.2),
+            Memory::new("Prefers metric units\nsaid twice", MemoryType::Preference)
+                .with_importance(0.9),
+        ];
+        let summary = summarize_session(&memories).unwrap();
+        assert_eq!(
+            summary,
+            "2 memories saved\n- [preference] Prefers metric units\n- [event] Asked about the weather"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! One database per agent
+//!
+//! A [`ShardedMemorySystem`] keeps each namespace (typically an agent id) in
+//! a data directory of its own under `<root>/shards/<namespace>`, with its own
+//! SQLite file, text index and manifest. Agents on a shared host can't see or
+//! lock each other's data, and one agent's store can be backed up, moved or
+//! deleted by handling a single directory.
+//!
+//! Shards are opened lazily on first use and kept open until
+//! [`close`](ShardedMemorySystem::close)d. [`search_all`] and
+//! [`search_shards`] fan a query out over several shards and merge the
+//! results by score.
+//!
+//! ```rust,ignore
+//! let shards = ShardedMemorySystem::new("./data")?;
+//! shards.shard("planner").await?.save(&memory).await?;
+//! let hits = shards.search_all("deploy schedule", &SearchConfig::default()).await?;
+//! ```
+//!
+//! [`search_all`]: ShardedMemorySystem::search_all
+//! [`search_shards`]: ShardedMemorySystem::search_shards
+
+use crate::error::{MemoryError, Result};
+use crate::search::SearchConfig;
+use crate::types::MemorySearchResult;
+use crate::{MemorySystem, MemorySystemOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Directory under the root that holds one data directory per shard
+pub const SHARDS_DIR: &str = "shards";
+
+/// Configures each shard right after it is opened, e.g. to attach a vector backend
+type ShardInit = Arc<dyn Fn(&str, MemorySystem) -> MemorySystem + Send + Sync>;
+
+/// A search hit and the shard it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardSearchResult {
+    pub namespace: String,
+    #[serde(flatten)]
+    pub result: MemorySearchResult,
+}
+
+/// Memory systems routed by namespace, each in its own data directory
+#[derive(Clone)]
+pub struct ShardedMemorySystem {
+    root: PathBuf,
+    options: MemorySystemOptions,
+    init: Option<ShardInit>,
+    open: Arc<Mutex<HashMap<String, Arc<MemorySystem>>>>,
+}
+
+impl std::fmt::Debug for ShardedMemorySystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShardedMemorySystem")
+            .field("root", &self.root)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShardedMemorySystem {
+    /// Shards under `<root>/shards`; nothing is opened until first use
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(root.join(SHARDS_DIR))?;
+        Ok(Self {
+            root,
+            options: MemorySystemOptions::default(),
+            init: None,
+            open: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Open every shard with these options
+    pub fn with_options(mut self, options: impl Into<MemorySystemOptions>) -> Self {
+        self.options = options.into();
+        self
+    }
+
+    /// Run `init` on each shard as it is opened, with the shard's namespace
+    pub fn with_shard_init(
+        mut self,
+        init: impl Fn(&str, MemorySystem) -> MemorySystem + Send + Sync + 'static,
+    ) -> Self {
+        self.init = Some(Arc::new(init));
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Data directory of `namespace`'s shard, whether or not it exists yet
+    pub fn shard_dir(&self, namespace: &str) -> Result<PathBuf> {
+        validate_shard_name(namespace)?;
+        Ok(self.root.join(SHARDS_DIR).join(namespace))
+    }
+
+    /// The shard for `namespace`, opened (and created) on first use
+    pub async fn shard(&self, namespace: &str) -> Result<Arc<MemorySystem>> {
+        let dir = self.shard_dir(namespace)?;
+        let mut open = self.open.lock().await;
+        if let Some(shard) = open.get(namespace) {
+            return Ok(Arc::clone(shard));
+        }
+
+        let mut system = MemorySystem::new_with_options(&dir, self.options.clone()).await?;
+        if let Some(init) = &self.init {
+            system = init(namespace, system);
+        }
+        let shard = Arc::new(system);
+        open.insert(namespace.to_string(), Arc::clone(&shard));
+        Ok(shard)
+    }
+
+    /// Namespaces with a shard on disk, sorted
+    pub fn namespaces(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(self.root.join(SHARDS_DIR))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                if validate_shard_name(name).is_ok() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Namespaces whose shard is currently open, sorted
+    pub async fn open_namespaces(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.open.lock().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Drop the open handle of a shard; it is reopened on next use. Returns
+    /// whether it was open. Clones of the handle keep the shard alive.
+    pub async fn close(&self, namespace: &str) -> bool {
+        self.open.lock().await.remove(namespace).is_some()
+    }
+
+    /// Search every shard on disk
+    pub async fn search_all(
+        &self,
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<ShardSearchResult>> {
+        let namespaces = self.namespaces()?;
+        let namespaces: Vec<&str> = namespaces.iter().map(String::as_str).collect();
+        self.search_shards(&namespaces, query, config).await
+    }
+
+    /// Search the given shards concurrently and merge their results by score,
+    /// keeping the best `config.max_results` overall
+    pub async fn search_shards(
+        &self,
+        namespaces: &[&str],
+        query: &str,
+        config: &SearchConfig,
+    ) -> Result<Vec<ShardSearchResult>> {
+        let searches = namespaces.iter().map(|namespace| async move {
+            let shard = self.shard(namespace).await?;
+            let results = shard.search_with_config(query, config).await?;
+            Ok::<_, MemoryError>(
+                results
+                    .into_iter()
+                    .map(|result| ShardSearchResult {
+                        namespace: namespace.to_string(),
+                        result,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        });
+        let mut merged: Vec<ShardSearchResult> = futures::future::try_join_all(searches)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        merged.sort_by(|a, b| {
+            b.result
+                .score
+                .partial_cmp(&a.result.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        merged.truncate(config.max_results);
+        for (i, hit) in merged.iter_mut().enumerate() {
+            hit.result.rank = i + 1;
+        }
+        Ok(merged)
+    }
+}
+
+/// Shard names become directory names, so keep them simple
+fn validate_shard_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(MemoryError::Validation(format!(
+            "Invalid shard namespace '{name}': use 1-128 ASCII letters, digits, '_', '-' or '.'"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Memory, MemoryType};
+
+    #[tokio::test]
+    async fn shards_are_isolated_and_searchable_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let shards = ShardedMemorySystem::new(dir.path()).unwrap();
+
+        let planner = shards.shard("planner").await.unwrap();
+        planner
+            .save(&Memory::new("Deploy window is Tuesday", MemoryType::Fact))
+            .await
+            .unwrap();
+        shards
+            .shard("coder")
+            .await
+            .unwrap()
+            .save(&Memory::new(
+                "Deploy script lives in ops/",
+                MemoryType::Fact,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(planner.search("script").await.unwrap().len(), 0);
+        assert!(dir.path().join("shards/coder/memories.db").exists());
+        assert_eq!(shards.namespaces().unwrap(), vec!["coder", "planner"]);
+
+        let hits = shards
+            .search_all("deploy", &SearchConfig::default())
+            .await
+            .unwrap();
+        let mut found: Vec<_> = hits.iter().map(|h| h.namespace.as_str()).collect();
+        found.sort();
+        assert_eq!(found, vec!["coder", "planner"]);
+        assert!(shards.shard("../escape").await.is_err());
+    }
+}
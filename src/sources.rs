@@ -0,0 +1,225 @@
+//! Source documents and re-sync
+//!
+//! A [`SourceDocument`] records a document the agent learned from (a wiki page,
+//! a README, a ticket) by URI, together with a hash of the content last
+//! ingested. The document is split into sections on blank lines and each
+//! section is saved as a memory linked back to the source.
+//!
+//! When the document changes, [`MemorySystem::resync_source`] compares the new
+//! sections with the derived memories: identical sections keep their memory,
+//! new or edited sections become new memories, and memories whose section
+//! disappeared are forgotten. An edited section's new memory
+//! [`Updates`](crate::RelationType::Updates) the memory it replaces.
+//!
+//! ```rust,ignore
+//! memory.ingest_source("wiki://deploys", &page, MemoryType::Fact).await?;
+//! // ... the page is edited ...
+//! let sync = memory.resync_source("wiki://deploys", &edited_page).await?;
+//! println!("{} new, {} superseded", sync.created.len(), sync.superseded.len());
+//! ```
+
+use crate::embedding::{fnv1a, FNV_OFFSET};
+use crate::error::{MemoryError, Result};
+use crate::types::{MemoryId, MemoryType, RelationType};
+use crate::MemorySystem;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A tracked source document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceDocument {
+    pub uri: String,
+    /// [`content_hash`] of the content last synced
+    pub content_hash: String,
+    /// Type given to memories derived from this source
+    pub memory_type: MemoryType,
+    pub created_at: DateTime<Utc>,
+    pub last_synced: DateTime<Utc>,
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceSync {
+    pub uri: String,
+    /// The content differed from the last synced version
+    pub changed: bool,
+    /// Memories created for new or edited sections
+    pub created: Vec<MemoryId>,
+    /// Memories forgotten because their section was removed or edited
+    pub superseded: Vec<MemoryId>,
+    /// Sections whose memory was kept as is
+    pub unchanged: usize,
+}
+
+/// Stable hash of a document's content, as 16 hex digits
+pub fn content_hash(content: &str) -> String {
+    format!("{:016x}", fnv1a(content.as_bytes(), FNV_OFFSET))
+}
+
+/// Split a document into sections on blank lines
+pub fn split_sections(content: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        sections.push(current.join("\n"));
+    }
+    sections
+}
+
+/// Sync `content` into the source at `uri`. Creates the source when
+/// `memory_type` is given and it does not exist yet.
+pub(crate) async fn sync_source(
+    memory: &MemorySystem,
+    uri: &str,
+    content: &str,
+    memory_type: Option<MemoryType>,
+) -> Result<SourceSync> {
+    let store = &memory.store;
+    let now = store.now();
+    let hash = content_hash(content);
+    let existing = store.get_source(uri).await?;
+
+    let mut source = match (existing, memory_type) {
+        (Some(source), _) => source,
+        (None, Some(memory_type)) => SourceDocument {
+            uri: uri.to_string(),
+            content_hash: String::new(),
+            memory_type,
+            created_at: now,
+            last_synced: now,
+        },
+        (None, None) => return Err(MemoryError::NotFound(format!("source {uri}"))),
+    };
+
+    let old = store.source_memories(uri).await?;
+    let mut sync = SourceSync {
+        uri: uri.to_string(),
+        ..Default::default()
+    };
+
+    if source.content_hash == hash {
+        sync.unchanged = old.len();
+        source.last_synced = now;
+        store.upsert_source(&source).await?;
+        return Ok(sync);
+    }
+    sync.changed = true;
+
+    // Keep memories whose section is still present verbatim
+    let sections = split_sections(content);
+    let mut matched = vec![false; old.len()];
+    let mut kept: Vec<Option<MemoryId>> = Vec::with_capacity(sections.len());
+    for section in &sections {
+        let found = old
+            .iter()
+            .enumerate()
+            .find(|(i, (_, m))| !matched[*i] && m.content == *section)
+            .map(|(i, _)| i);
+        kept.push(found.map(|i| {
+            matched[i] = true;
+            old[i].1.id.clone()
+        }));
+    }
+
+    for (index, (section, kept)) in sections.iter().zip(kept).enumerate() {
+        if let Some(id) = kept {
+            store.link_source_memory(uri, &id, index).await?;
+            sync.unchanged += 1;
+            continue;
+        }
+
+        let derived = store
+            .new_memory(section.clone(), source.memory_type)
+            .with_source(uri)
+            .with_metadata(serde_json::json!({
+                "source_uri": uri,
+                "source_section": index,
+            }));
+        let ids = memory.save_chunked(&derived).await?;
+        for id in &ids {
+            store.link_source_memory(uri, id, index).await?;
+        }
+
+        // An edited section replaces the old memory at the same position
+        if let Some(i) = (0..old.len()).find(|&i| !matched[i] && old[i].0 == index) {
+            memory
+                .associate(&ids[0], &old[i].1.id, RelationType::Updates)
+                .await?;
+        }
+        sync.created.extend(ids);
+    }
+
+    for (i, (_, old)) in old.iter().enumerate() {
+        if !matched[i] {
+            memory.forget(&old.id).await?;
+            store.unlink_source_memory(uri, &old.id).await?;
+            sync.superseded.push(old.id.clone());
+        }
+    }
+
+    source.content_hash = hash;
+    source.last_synced = now;
+    store.upsert_source(&source).await?;
+    Ok(sync)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MemorySystem, MemoryType, RelationType};
+
+    #[tokio::test]
+    async fn resync_supersedes_changed_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(dir.path()).await.unwrap();
+        let uri = "wiki://deploys";
+
+        let first = memory
+            .ingest_source(
+                uri,
+                "Deploys run on Tuesday.\n\nRollbacks need a lead.",
+                MemoryType::Fact,
+            )
+            .await
+            .unwrap();
+        assert_eq!(first.created.len(), 2);
+
+        let same = memory
+            .resync_source(uri, "Deploys run on Tuesday.\n\nRollbacks need a lead.")
+            .await
+            .unwrap();
+        assert!(!same.changed);
+        assert_eq!(same.unchanged, 2);
+
+        let sync = memory
+            .resync_source(uri, "Deploys run on Thursday.\n\nRollbacks need a lead.")
+            .await
+            .unwrap();
+        assert!(sync.changed);
+        assert_eq!(sync.unchanged, 1);
+        assert_eq!(sync.created.len(), 1);
+        assert_eq!(sync.superseded, vec![first.created[0].clone()]);
+
+        let old = memory.load(&first.created[0]).await.unwrap().unwrap();
+        assert!(old.forgotten);
+        let associations = memory.get_associations(&sync.created[0]).await.unwrap();
+        assert!(associations
+            .iter()
+            .any(|a| a.target_id == old.id && a.relation_type == RelationType::Updates));
+
+        let derived = memory.source_memories(uri).await.unwrap();
+        assert_eq!(derived.len(), 2);
+        assert_eq!(derived[0].content, "Deploys run on Thursday.");
+
+        assert!(memory.resync_source("wiki://missing", "x").await.is_err());
+    }
+}
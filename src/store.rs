@@ -1,23 +1,168 @@
 //! Memory graph storage using SQLite
 
+pub mod analytics;
+
+use crate::clock::{Clock, IdGenerator, SystemClock, UuidIds};
 use crate::confidence::VerificationStatus;
+use crate::context_trace::{ContextTrace, TracedMemory};
 use crate::cortex::{Experience, MemorySummary};
+use crate::decisions::DECISION_METADATA_KEY;
+use crate::encryption::EncryptionKey;
 use crate::error::{MemoryError, Result};
+use crate::language::memory_language;
+use crate::maintenance::EvictionPolicy;
+use crate::memory_query::{MemoryQuery, QueryValue};
+use crate::quality::QualityScore;
+use crate::query_log::QueryLogEntry;
+use crate::review::{ReviewItem, ReviewStatus};
+use crate::saved_search::SavedSearch;
+use crate::session::{Session, SessionInfo};
+use crate::sources::SourceDocument;
+use crate::store_generation::StoreGeneration;
 use crate::types::{Association, Memory, MemoryId, MemoryType, RelationType};
+use crate::vector_chunks::VectorChunk;
+use crate::versioning::{MemoryVersion, VersionAuthor, VersionId};
 
+use futures::future::BoxFuture;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
+use std::time::Duration;
+
+pub use analytics::{AccessBucket, DayCount, StoreAnalytics, TypeStats};
+pub use sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
+
+/// Columns selected whenever a full `Memory` is loaded
+const MEMORY_COLUMNS: &str = "id, content, memory_type, importance, created_at, updated_at, \
+     last_accessed_at, access_count, source, session_id, forgotten, metadata, \
+     confidence_score, confidence_data, verification_status, tags, pinned, emotional_valence, \
+     language";
+
+/// Namespace used by [`MemoryStore::new`]
+pub const DEFAULT_NAMESPACE: &str = "";
+
+/// Connection settings for the SQLite database of a data directory.
+///
+/// The defaults match what the store always used: WAL journal, `FULL`
+/// synchronous, a 5 second busy timeout and 10 pooled connections. Under many
+/// concurrent writers, a longer busy timeout (and `NORMAL` synchronous, which is
+/// safe with WAL) avoids `database is locked` errors.
+#[derive(Debug, Clone)]
+pub struct SqliteTuning {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    /// How long a connection waits for a lock held by another writer
+    pub busy_timeout: Duration,
+    pub max_connections: u32,
+    /// Page cache per connection; SQLite's default (about 2 MiB) when unset
+    pub cache_size_kib: Option<u32>,
+}
+
+impl Default for SqliteTuning {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Full,
+            busy_timeout: Duration::from_secs(5),
+            max_connections: 10,
+            cache_size_kib: None,
+        }
+    }
+}
+
+impl SqliteTuning {
+    pub fn with_journal_mode(mut self, journal_mode: SqliteJournalMode) -> Self {
+        self.journal_mode = journal_mode;
+        self
+    }
+
+    pub fn with_synchronous(mut self, synchronous: SqliteSynchronous) -> Self {
+        self.synchronous = synchronous;
+        self
+    }
+
+    pub fn with_busy_timeout(mut self, busy_timeout: Duration) -> Self {
+        self.busy_timeout = busy_timeout;
+        self
+    }
+
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    pub fn with_cache_size_kib(mut self, cache_size_kib: u32) -> Self {
+        self.cache_size_kib = Some(cache_size_kib);
+        self
+    }
+
+    /// Two connections with a 512 KiB page cache each, for embedded devices
+    pub fn low_memory() -> Self {
+        Self::default()
+            .with_synchronous(SqliteSynchronous::Normal)
+            .with_max_connections(2)
+            .with_cache_size_kib(512)
+    }
+
+    /// Open (creating if missing) the database at `path` with these settings
+    pub async fn connect(&self, path: impl AsRef<std::path::Path>) -> Result<SqlitePool> {
+        self.connect_encrypted(path, None).await
+    }
+
+    /// Like [`connect`](Self::connect), keying the database with SQLCipher
+    /// when `key` is given (requires the `encryption` feature)
+    pub async fn connect_encrypted(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        key: Option<&EncryptionKey>,
+    ) -> Result<SqlitePool> {
+        let mut options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(self.journal_mode)
+            .synchronous(self.synchronous)
+            .busy_timeout(self.busy_timeout);
+        if let Some(key) = key {
+            if !cfg!(feature = "encryption") {
+                return Err(crate::encryption::disabled());
+            }
+            // sqlx issues `key` before any other pragma, as SQLCipher requires
+            options = options.pragma("key", key.sqlcipher_pragma());
+        }
+        if let Some(kib) = self.cache_size_kib {
+            // Negative values are KiB rather than pages
+            options = options.pragma("cache_size", format!("-{kib}"));
+        }
+
+        Ok(SqlitePoolOptions::new()
+            .max_connections(self.max_connections)
+            .connect_with(options)
+            .await?)
+    }
+}
 
-/// Memory store for CRUD and graph operations
+/// Memory store for CRUD and graph operations.
+///
+/// Memories, experiences and summaries belong to a namespace; every query only
+/// sees rows of the store's own namespace, so several stores created with
+/// [`scoped`](Self::scoped) can share one database.
+///
+/// Timestamps come from the store's [`Clock`] and new IDs from its
+/// [`IdGenerator`]; both can be replaced for deterministic tests.
 #[derive(Clone)]
 pub struct MemoryStore {
     pool: SqlitePool,
+    namespace: String,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
 }
 
 impl std::fmt::Debug for MemoryStore {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MemoryStore")
             .field("pool", &"<SqlitePool>")
+            .field("namespace", &self.namespace)
+            .field("clock", &self.clock)
             .finish()
     }
 }
@@ -25,7 +170,61 @@ impl std::fmt::Debug for MemoryStore {
 impl MemoryStore {
     /// Create a new memory store with the given SQLite pool
     pub fn new(pool: SqlitePool) -> Arc<Self> {
-        Arc::new(Self { pool })
+        Arc::new(Self {
+            pool,
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(UuidIds),
+        })
+    }
+
+    /// A store over the same pool that only sees `namespace`
+    pub fn scoped(&self, namespace: impl Into<String>) -> Arc<Self> {
+        Arc::new(Self {
+            namespace: namespace.into(),
+            ..self.clone()
+        })
+    }
+
+    /// A copy of this store that reads the time from `clock`
+    pub fn with_clock(&self, clock: Arc<dyn Clock>) -> Arc<Self> {
+        Arc::new(Self {
+            clock,
+            ..self.clone()
+        })
+    }
+
+    /// A copy of this store that mints IDs with `ids`
+    pub fn with_id_generator(&self, ids: Arc<dyn IdGenerator>) -> Arc<Self> {
+        Arc::new(Self {
+            ids,
+            ..self.clone()
+        })
+    }
+
+    /// The store's clock
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// The current time according to the store's clock
+    pub fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now()
+    }
+
+    /// A new ID from the store's ID generator
+    pub fn next_id(&self) -> String {
+        self.ids.next_id()
+    }
+
+    /// A new memory stamped with the store's clock and ID generator
+    pub fn new_memory(&self, content: impl Into<String>, memory_type: MemoryType) -> Memory {
+        Memory::new_at(content, memory_type, self.next_id(), self.now())
+    }
+
+    /// Namespace this store reads and writes
+    pub fn namespace(&self) -> &str {
+        &self.namespace
     }
 
     /// Get a reference to the SQLite pool
@@ -35,21 +234,31 @@ impl MemoryStore {
 
     /// Save a new memory
     pub async fn save(&self, memory: &Memory) -> Result<()> {
+        self.insert_memory(&self.pool, memory).await
+    }
+
+    async fn insert_memory<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        memory: &Memory,
+    ) -> Result<()> {
         let metadata_json = memory
             .metadata
             .as_ref()
             .and_then(|m| serde_json::to_string(m).ok());
 
         let confidence_json = serde_json::to_string(&memory.confidence).ok();
+        let tags_json = tags_to_json(&memory.tags);
 
         sqlx::query(
             r#"
             INSERT INTO memories (
                 id, content, memory_type, importance, created_at, updated_at,
                 last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                confidence_score, confidence_data, verification_status
+                confidence_score, confidence_data, verification_status, tags, pinned,
+                emotional_valence, language, namespace, forgotten_at
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&memory.id)
@@ -67,7 +276,13 @@ impl MemoryStore {
         .bind(memory.confidence.score)
         .bind(confidence_json)
         .bind(memory.confidence.status.to_string())
-        .execute(&self.pool)
+        .bind(tags_json)
+        .bind(memory.pinned)
+        .bind(memory.emotional_valence)
+        .bind(memory_language(memory))
+        .bind(&self.namespace)
+        .bind(memory.forgotten.then_some(memory.updated_at))
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -75,30 +290,141 @@ impl MemoryStore {
 
     /// Load a memory by ID
     pub async fn load(&self, id: &str) -> Result<Option<Memory>> {
-        let row = sqlx::query(
+        self.load_with(&self.pool, id).await
+    }
+
+    /// Memories whose ID starts with `prefix`, newest first, forgotten ones included
+    pub async fn find_by_id_prefix(&self, prefix: &str, limit: i64) -> Result<Vec<Memory>> {
+        let pattern = format!(
+            "{}%",
+            prefix
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                   confidence_score, confidence_data, verification_status
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE id = ?
-            "#,
-        )
+            WHERE id LIKE ? ESCAPE '\' AND namespace = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#
+        ))
+        .bind(pattern)
+        .bind(&self.namespace)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    async fn load_with<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        id: &str,
+    ) -> Result<Option<Memory>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE id = ? AND namespace = ?
+            "#
+        ))
         .bind(id)
-        .fetch_optional(&self.pool)
+        .bind(&self.namespace)
+        .fetch_optional(executor)
         .await?;
 
         Ok(row.map(|row| row_to_memory(&row)))
     }
 
+    /// Whether a memory with this ID exists, forgotten or not
+    pub async fn exists(&self, id: &str) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM memories WHERE id = ? AND namespace = ?)",
+        )
+        .bind(id)
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Current generation of the database, bumped by every mutation.
+    ///
+    /// Shared by all namespaces of the database; see [`crate::store_generation`].
+    pub async fn generation(&self) -> Result<StoreGeneration> {
+        let generation: i64 =
+            sqlx::query_scalar("SELECT generation FROM store_generation WHERE id = 1")
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(StoreGeneration(generation as u64))
+    }
+
+    /// Number of active (non-forgotten) memories
+    pub async fn count_all(&self) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM memories WHERE forgotten = 0 AND namespace = ?",
+        )
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Number of active memories of `memory_type`
+    pub async fn count_by_type(&self, memory_type: MemoryType) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM memories \
+             WHERE memory_type = ? AND forgotten = 0 AND namespace = ?",
+        )
+        .bind(memory_type.to_string())
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Number of associations whose source memory is in this namespace
+    pub async fn count_associations(&self) -> Result<i64> {
+        let count = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM associations a
+            JOIN memories m ON m.id = a.source_id
+            WHERE m.namespace = ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Update an existing memory
     pub async fn update(&self, memory: &Memory) -> Result<()> {
+        self.update_with(&self.pool, memory).await
+    }
+
+    async fn update_with<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        memory: &Memory,
+    ) -> Result<()> {
         let metadata_json = memory
             .metadata
             .as_ref()
             .and_then(|m| serde_json::to_string(m).ok());
 
         let confidence_json = serde_json::to_string(&memory.confidence).ok();
+        let tags_json = tags_to_json(&memory.tags);
 
         sqlx::query(
             r#"
@@ -106,8 +432,10 @@ impl MemoryStore {
             SET content = ?, memory_type = ?, importance = ?, updated_at = ?,
                 last_accessed_at = ?, access_count = ?, source = ?, session_id = ?,
                 forgotten = ?, metadata = ?, confidence_score = ?, confidence_data = ?,
-                verification_status = ?
-            WHERE id = ?
+                verification_status = ?, tags = ?, pinned = ?, emotional_valence = ?,
+                language = ?,
+                forgotten_at = CASE WHEN ? THEN COALESCE(forgotten_at, ?) END
+            WHERE id = ? AND namespace = ?
             "#,
         )
         .bind(&memory.content)
@@ -123,39 +451,84 @@ impl MemoryStore {
         .bind(memory.confidence.score)
         .bind(confidence_json)
         .bind(memory.confidence.status.to_string())
+        .bind(tags_json)
+        .bind(memory.pinned)
+        .bind(memory.emotional_valence)
+        .bind(memory_language(memory))
+        .bind(memory.forgotten)
+        .bind(memory.updated_at)
         .bind(&memory.id)
-        .execute(&self.pool)
+        .bind(&self.namespace)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
-    /// Delete a memory permanently
+    /// Delete a memory permanently, together with its associations and episode links
     pub async fn delete(&self, id: &str) -> Result<()> {
-        // First delete associations
+        let mut tx = self.pool.begin().await?;
+        self.delete_with(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete a memory and its links inside `tx`, returning whether it existed
+    async fn delete_with(&self, conn: &mut sqlx::SqliteConnection, id: &str) -> Result<bool> {
+        let owned = sqlx::query("SELECT 1 FROM memories WHERE id = ? AND namespace = ?")
+            .bind(id)
+            .bind(&self.namespace)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if owned.is_none() {
+            return Ok(false);
+        }
+
+        // First delete associations and episode links
         sqlx::query("DELETE FROM associations WHERE source_id = ? OR target_id = ?")
             .bind(id)
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("DELETE FROM experience_memories WHERE memory_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("DELETE FROM memory_accesses WHERE memory_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
             .await?;
 
         // Then delete the memory
         sqlx::query("DELETE FROM memories WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *conn)
             .await?;
 
-        Ok(())
+        Ok(true)
     }
 
     /// Soft delete (forget) a memory
     pub async fn forget(&self, id: &str) -> Result<bool> {
+        self.forget_with(&self.pool, id).await
+    }
+
+    async fn forget_with<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        id: &str,
+    ) -> Result<bool> {
         let result = sqlx::query(
-            "UPDATE memories SET forgotten = 1, updated_at = ? WHERE id = ? AND forgotten = 0",
+            r#"
+            UPDATE memories SET forgotten = 1, updated_at = ?, forgotten_at = ?
+            WHERE id = ? AND namespace = ? AND forgotten = 0
+            "#,
         )
-        .bind(chrono::Utc::now())
+        .bind(self.now())
+        .bind(self.now())
         .bind(id)
-        .execute(&self.pool)
+        .bind(&self.namespace)
+        .execute(executor)
         .await?;
 
         Ok(result.rows_affected() > 0)
@@ -164,43 +537,162 @@ impl MemoryStore {
     /// Restore a forgotten memory
     pub async fn restore(&self, id: &str) -> Result<bool> {
         let result = sqlx::query(
-            "UPDATE memories SET forgotten = 0, updated_at = ? WHERE id = ? AND forgotten = 1",
+            r#"
+            UPDATE memories SET forgotten = 0, updated_at = ?, forgotten_at = NULL
+            WHERE id = ? AND namespace = ? AND forgotten = 1
+            "#,
+        )
+        .bind(self.now())
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Persistently pin or unpin a memory. Returns false if it doesn't exist.
+    pub async fn set_pinned(&self, id: &str, pinned: bool) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE memories SET pinned = ?, updated_at = ? WHERE id = ? AND namespace = ?",
         )
-        .bind(chrono::Utc::now())
+        .bind(pinned)
+        .bind(self.now())
         .bind(id)
+        .bind(&self.namespace)
         .execute(&self.pool)
         .await?;
 
         Ok(result.rows_affected() > 0)
     }
 
+    /// Get pinned (non-forgotten) memories, most important first
+    pub async fn get_pinned(&self, limit: i64) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE pinned = 1 AND forgotten = 0 AND namespace = ?
+            ORDER BY importance DESC, updated_at DESC
+            LIMIT ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
     /// Record access to a memory
     pub async fn record_access(&self, id: &str) -> Result<()> {
-        let now = chrono::Utc::now();
+        let now = self.now();
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
             r#"
             UPDATE memories
             SET last_accessed_at = ?, access_count = access_count + 1
-            WHERE id = ?
+            WHERE id = ? AND namespace = ?
+            "#,
+        )
+        .bind(now)
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO memory_accesses (memory_id, accessed_at)
+            SELECT id, ? FROM memories WHERE id = ? AND namespace = ?
             "#,
         )
         .bind(now)
         .bind(id)
+        .bind(&self.namespace)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete access log entries older than `before`, returning how many were removed
+    pub async fn prune_access_log(&self, before: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM memory_accesses
+            WHERE accessed_at < ?
+              AND memory_id IN (SELECT id FROM memories WHERE namespace = ?)
+            "#,
+        )
+        .bind(before)
+        .bind(&self.namespace)
         .execute(&self.pool)
         .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// IDs of unpinned memories forgotten before `before`, longest forgotten first
+    pub async fn forgotten_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<MemoryId>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT id FROM memories
+            WHERE namespace = ? AND forgotten = 1 AND pinned = 0 AND forgotten_at < ?
+            ORDER BY forgotten_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
 
+    /// Delete a memory like [`delete`](Self::delete), and its version history
+    /// and chunk offsets with it, so nothing of its content is left in the
+    /// database. Everything goes in one transaction.
+    pub async fn purge(&self, id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        if self.delete_with(&mut tx, id).await? {
+            sqlx::query("DELETE FROM memory_versions WHERE memory_id = ? AND namespace = ?")
+                .bind(id)
+                .bind(&self.namespace)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM vector_chunks WHERE memory_id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
     /// Create an association between memories
     pub async fn create_association(&self, association: &Association) -> Result<()> {
+        Self::upsert_association(&self.pool, association).await
+    }
+
+    async fn upsert_association<'e>(
+        executor: impl sqlx::SqliteExecutor<'e>,
+        association: &Association,
+    ) -> Result<()> {
         sqlx::query(
             r#"
             INSERT INTO associations (id, source_id, target_id, relation_type, weight, created_at)
             VALUES (?, ?, ?, ?, ?, ?)
             ON CONFLICT(source_id, target_id, relation_type) DO UPDATE SET
-                weight = excluded.weight
+                weight = excluded.weight,
+                decayed_at = excluded.created_at
             "#,
         )
         .bind(&association.id)
@@ -209,9 +701,88 @@ impl MemoryStore {
         .bind(association.relation_type.to_string())
         .bind(association.weight)
         .bind(association.created_at)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Create or update many associations in one transaction
+    pub async fn create_associations(&self, associations: &[Association]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for association in associations {
+            Self::upsert_association(&mut *tx, association).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete every association to or from a memory of this namespace,
+    /// returning how many were removed
+    pub async fn delete_associations_for(&self, memory_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM associations
+            WHERE (source_id = ?1 OR target_id = ?1)
+              AND EXISTS (SELECT 1 FROM memories WHERE id = ?1 AND namespace = ?2)
+            "#,
+        )
+        .bind(memory_id)
+        .bind(&self.namespace)
         .execute(&self.pool)
         .await?;
 
+        Ok(result.rows_affected())
+    }
+
+    /// Associations from memories of this namespace whose weight was last
+    /// decayed (or set) before `before`, with that time, oldest first
+    pub async fn associations_decayed_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<(Association, chrono::DateTime<chrono::Utc>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.source_id, a.target_id, a.relation_type, a.weight, a.created_at,
+                   COALESCE(a.decayed_at, a.created_at) AS decayed_at
+            FROM associations a
+            JOIN memories m ON m.id = a.source_id
+            WHERE m.namespace = ? AND COALESCE(a.decayed_at, a.created_at) < ?
+            ORDER BY COALESCE(a.decayed_at, a.created_at) ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let association = row_to_association(row);
+                let decayed_at = row.try_get("decayed_at").unwrap_or(association.created_at);
+                (association, decayed_at)
+            })
+            .collect())
+    }
+
+    /// Set an association's weight after decay, recording when it was decayed
+    pub async fn set_association_weight(
+        &self,
+        id: &str,
+        weight: f32,
+        decayed_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE associations SET weight = ?, decayed_at = ? WHERE id = ?")
+            .bind(weight.clamp(0.0, 1.0))
+            .bind(decayed_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
         Ok(())
     }
 
@@ -322,17 +893,17 @@ impl MemoryStore {
 
     /// Get memories by type
     pub async fn get_by_type(&self, memory_type: MemoryType, limit: i64) -> Result<Vec<Memory>> {
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE memory_type = ? AND forgotten = 0
+            WHERE memory_type = ? AND forgotten = 0 AND namespace = ?
             ORDER BY importance DESC, updated_at DESC
             LIMIT ?
-            "#,
-        )
+            "#
+        ))
         .bind(memory_type.to_string())
+        .bind(&self.namespace)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
@@ -340,30 +911,161 @@ impl MemoryStore {
         Ok(rows.iter().map(row_to_memory).collect())
     }
 
-    /// Get active (non-forgotten) memories ordered by recency.
-    pub async fn list_active(&self, limit: i64, offset: i64) -> Result<Vec<Memory>> {
-        let rows = sqlx::query(
+    /// Active memories carrying `tag`, most important first
+    pub async fn get_by_tag(&self, tag: &str, limit: i64) -> Result<Vec<Memory>> {
+        self.get_by_tags(&[tag], limit).await
+    }
+
+    /// Active memories carrying every one of `tags`, most important first
+    pub async fn get_by_tags(&self, tags: &[&str], limit: i64) -> Result<Vec<Memory>> {
+        let mut tags = tags.to_vec();
+        tags.sort_unstable();
+        tags.dedup();
+        if tags.is_empty() {
+            return Ok(Vec::new());
+        }
+        // ?1 is the namespace, used twice; the tags follow from ?2
+        let placeholders = (2..tags.len() + 2)
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                   confidence_score, confidence_data, verification_status
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE forgotten = 0
-            ORDER BY updated_at DESC
-            LIMIT ? OFFSET ?
+            WHERE forgotten = 0 AND namespace = ?1 AND id IN (
+                SELECT memory_id FROM memory_tags
+                WHERE namespace = ?1 AND tag IN ({placeholders})
+                GROUP BY memory_id
+                HAVING COUNT(DISTINCT tag) = {}
+            )
+            ORDER BY importance DESC, updated_at DESC
+            LIMIT {limit}
             "#,
-        )
+            tags.len()
+        );
+        let mut query = sqlx::query(&sql).bind(&self.namespace);
+        for tag in tags {
+            query = query.bind(tag);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Decision memories with a decision record but no recorded outcome, oldest first
+    pub async fn decisions_pending_outcome(&self, limit: i64) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE memory_type = ? AND forgotten = 0 AND namespace = ?
+              AND json_extract(metadata, '$.{key}') IS NOT NULL
+              AND json_extract(metadata, '$.{key}.outcome') IS NULL
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#,
+            key = DECISION_METADATA_KEY
+        ))
+        .bind(MemoryType::Decision.to_string())
+        .bind(&self.namespace)
         .bind(limit)
-        .bind(offset)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(rows.iter().map(row_to_memory).collect())
     }
 
-    /// Search text candidates using SQL LIKE over normalized query tokens.
-    pub async fn search_text_candidates(&self, query: &str, limit: i64) -> Result<Vec<Memory>> {
-        let tokens: Vec<String> = query
+    /// Get active (non-forgotten) memories ordered by recency.
+    pub async fn list_active(&self, limit: i64, offset: i64) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE forgotten = 0 AND namespace = ?
+            ORDER BY updated_at DESC
+            LIMIT ? OFFSET ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// One page of active memories, newest first.
+    ///
+    /// Pass the previous page's `next_cursor` to continue. Pages are keyed on
+    /// `(created_at, id)` rather than an offset, so each page costs the same no
+    /// matter how deep it is, and memories saved meanwhile don't shift later pages.
+    pub async fn list_page(&self, cursor: Option<&str>, page_size: usize) -> Result<MemoryPage> {
+        let page_size = page_size.max(1);
+        // One extra row tells whether there is a next page
+        let fetch = page_size as i64 + 1;
+
+        let rows = match cursor.map(PageCursor::decode).transpose()? {
+            Some(after) => {
+                sqlx::query(&format!(
+                    r#"
+                    SELECT {MEMORY_COLUMNS}
+                    FROM memories
+                    WHERE forgotten = 0 AND namespace = ?
+                      AND (created_at < ? OR (created_at = ? AND id < ?))
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT ?
+                    "#
+                ))
+                .bind(&self.namespace)
+                .bind(after.created_at)
+                .bind(after.created_at)
+                .bind(&after.id)
+                .bind(fetch)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(&format!(
+                    r#"
+                    SELECT {MEMORY_COLUMNS}
+                    FROM memories
+                    WHERE forgotten = 0 AND namespace = ?
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT ?
+                    "#
+                ))
+                .bind(&self.namespace)
+                .bind(fetch)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+
+        let mut memories: Vec<Memory> = rows.iter().map(row_to_memory).collect();
+        let next_cursor = if memories.len() > page_size {
+            memories.truncate(page_size);
+            memories.last().map(|m| {
+                PageCursor {
+                    created_at: m.created_at,
+                    id: m.id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(MemoryPage {
+            memories,
+            next_cursor,
+        })
+    }
+
+    /// Search text candidates using SQL LIKE over normalized query tokens.
+    pub async fn search_text_candidates(&self, query: &str, limit: i64) -> Result<Vec<Memory>> {
+        let tokens: Vec<String> = query
             .to_lowercase()
             .split(|c: char| !c.is_alphanumeric())
             .filter(|t| t.len() >= 2)
@@ -374,24 +1076,21 @@ impl MemoryStore {
             return self.list_active(limit, 0).await;
         }
 
-        let clauses = std::iter::repeat("LOWER(content) LIKE ?")
-            .take(tokens.len())
+        let clauses = std::iter::repeat_n("LOWER(content) LIKE ?", tokens.len())
             .collect::<Vec<_>>()
             .join(" OR ");
 
         let sql = format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                   confidence_score, confidence_data, verification_status
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE forgotten = 0 AND ({clauses})
+            WHERE forgotten = 0 AND namespace = ? AND ({clauses})
             ORDER BY importance DESC, updated_at DESC
             LIMIT ?
             "#
         );
 
-        let mut q = sqlx::query(&sql);
+        let mut q = sqlx::query(&sql).bind(&self.namespace);
         for token in &tokens {
             q = q.bind(format!("%{}%", token));
         }
@@ -409,15 +1108,13 @@ impl MemoryStore {
         let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
         let sql = format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                   confidence_score, confidence_data, verification_status
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE forgotten = 0 AND id IN ({placeholders})
+            WHERE forgotten = 0 AND namespace = ? AND id IN ({placeholders})
             "#
         );
 
-        let mut q = sqlx::query(&sql);
+        let mut q = sqlx::query(&sql).bind(&self.namespace);
         for id in ids {
             q = q.bind(id);
         }
@@ -428,17 +1125,17 @@ impl MemoryStore {
 
     /// Get high-importance memories
     pub async fn get_high_importance(&self, threshold: f32, limit: i64) -> Result<Vec<Memory>> {
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE importance >= ? AND forgotten = 0
+            WHERE importance >= ? AND forgotten = 0 AND namespace = ?
             ORDER BY importance DESC, updated_at DESC
             LIMIT ?
-            "#,
-        )
+            "#
+        ))
         .bind(threshold)
+        .bind(&self.namespace)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
@@ -446,6 +1143,80 @@ impl MemoryStore {
         Ok(rows.iter().map(row_to_memory).collect())
     }
 
+    /// Uniform random sample of up to `n` memories matching `filter`
+    pub async fn sample(&self, n: usize, filter: &SampleFilter) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE (?1 IS NULL OR memory_type = ?1)
+              AND (?2 IS NULL OR importance >= ?2)
+              AND (?3 IS NULL OR created_at >= ?3)
+              AND (?4 OR forgotten = 0)
+              AND namespace = ?6
+            ORDER BY RANDOM()
+            LIMIT ?5
+            "#
+        ))
+        .bind(filter.memory_type.map(|t| t.to_string()))
+        .bind(filter.min_importance)
+        .bind(filter.created_after)
+        .bind(filter.include_forgotten)
+        .bind(n as i64)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Random sample of up to `n` memories stratified by type.
+    ///
+    /// Each type receives a share of `n` proportional to how many matching memories it
+    /// has (largest remainder), and every non-empty type gets at least one slot while
+    /// slots remain, so rare types are represented.
+    pub async fn sample_stratified(&self, n: usize, filter: &SampleFilter) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT memory_type, COUNT(*) AS n
+            FROM memories
+            WHERE (?1 IS NULL OR memory_type = ?1)
+              AND (?2 IS NULL OR importance >= ?2)
+              AND (?3 IS NULL OR created_at >= ?3)
+              AND (?4 OR forgotten = 0)
+              AND namespace = ?5
+            GROUP BY memory_type
+            ORDER BY n ASC
+            "#,
+        )
+        .bind(filter.memory_type.map(|t| t.to_string()))
+        .bind(filter.min_importance)
+        .bind(filter.created_after)
+        .bind(filter.include_forgotten)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let counts: Vec<(MemoryType, usize)> = rows
+            .iter()
+            .map(|row| {
+                let memory_type: String = row.get("memory_type");
+                let count: i64 = row.get("n");
+                (parse_memory_type(&memory_type), count.max(0) as usize)
+            })
+            .collect();
+
+        let mut sample = Vec::new();
+        for (memory_type, slots) in allocate_strata(&counts, n) {
+            let stratum = SampleFilter {
+                memory_type: Some(memory_type),
+                ..filter.clone()
+            };
+            sample.extend(self.sample(slots, &stratum).await?);
+        }
+        Ok(sample)
+    }
+
     /// Get memories sorted by various criteria
     pub async fn get_sorted(
         &self,
@@ -453,29 +1224,22 @@ impl MemoryStore {
         limit: i64,
         memory_type: Option<MemoryType>,
     ) -> Result<Vec<Memory>> {
-        let order_clause = match sort {
-            SortOrder::Recent => "ORDER BY created_at DESC",
-            SortOrder::Updated => "ORDER BY updated_at DESC",
-            SortOrder::Importance => "ORDER BY importance DESC, updated_at DESC",
-            SortOrder::MostAccessed => "ORDER BY access_count DESC, created_at DESC",
-            SortOrder::LastAccessed => "ORDER BY last_accessed_at DESC",
-        };
+        let order_clause = sort.order_clause();
 
         let (query_str, type_filter) = if let Some(ref memory_type) = memory_type {
             (
                 format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, session_id, forgotten, metadata \
-                     FROM memories WHERE memory_type = ? AND forgotten = 0 {order_clause} LIMIT ?"
+                    "SELECT {MEMORY_COLUMNS} \
+                     FROM memories WHERE memory_type = ? AND forgotten = 0 AND namespace = ? \
+                     {order_clause} LIMIT ?"
                 ),
                 Some(memory_type.to_string()),
             )
         } else {
             (
                 format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, session_id, forgotten, metadata \
-                     FROM memories WHERE forgotten = 0 {order_clause} LIMIT ?"
+                    "SELECT {MEMORY_COLUMNS} \
+                     FROM memories WHERE forgotten = 0 AND namespace = ? {order_clause} LIMIT ?"
                 ),
                 None,
             )
@@ -484,11 +1248,13 @@ impl MemoryStore {
         let rows = if let Some(type_str) = type_filter {
             sqlx::query(&query_str)
                 .bind(type_str)
+                .bind(&self.namespace)
                 .bind(limit)
                 .fetch_all(&self.pool)
                 .await?
         } else {
             sqlx::query(&query_str)
+                .bind(&self.namespace)
                 .bind(limit)
                 .fetch_all(&self.pool)
                 .await?
@@ -503,43 +1269,302 @@ impl MemoryStore {
         importance_threshold: f32,
         min_age_days: i64,
     ) -> Result<Vec<Memory>> {
-        let cutoff = chrono::Utc::now() - chrono::Duration::days(min_age_days);
+        let cutoff = self.now() - chrono::Duration::days(min_age_days);
 
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata
+            SELECT {MEMORY_COLUMNS}
             FROM memories
             WHERE importance < ?
               AND memory_type != 'identity'
               AND created_at < ?
               AND forgotten = 0
+              AND pinned = 0
+              AND namespace = ?
             ORDER BY importance ASC, created_at ASC
-            "#,
-        )
+            "#
+        ))
         .bind(importance_threshold)
         .bind(cutoff)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// `(id, importance, exempt)` of every non-forgotten memory, highest importance
+    /// first. Pinned and identity memories are exempt from importance budgets.
+    pub async fn importance_snapshot(&self) -> Result<Vec<(MemoryId, f32, bool)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, importance, (pinned = 1 OR memory_type = 'identity') AS exempt
+            FROM memories
+            WHERE forgotten = 0 AND namespace = ?
+            ORDER BY importance DESC, created_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
         .fetch_all(&self.pool)
         .await?;
 
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("id"),
+                    row.try_get("importance").unwrap_or(0.5),
+                    row.try_get("exempt").unwrap_or(false),
+                )
+            })
+            .collect())
+    }
+
+    /// Active memories and their bytes as counted by a
+    /// [`StoreQuota`](crate::StoreQuota): content plus serialized metadata
+    pub async fn quota_usage(&self) -> Result<(usize, u64)> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS memories,
+                   COALESCE(SUM(LENGTH(CAST(content AS BLOB))
+                                + COALESCE(LENGTH(CAST(metadata AS BLOB)), 0)), 0) AS bytes
+            FROM memories
+            WHERE forgotten = 0 AND namespace = ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let memories: i64 = row.get("memories");
+        let bytes: i64 = row.get("bytes");
+        Ok((memories as usize, bytes as u64))
+    }
+
+    /// Up to `limit` active memories a quota may evict, in `policy` order.
+    /// Pinned memories and `protected` types are never candidates.
+    pub async fn eviction_candidates(
+        &self,
+        policy: EvictionPolicy,
+        protected: &[MemoryType],
+        limit: usize,
+    ) -> Result<Vec<Memory>> {
+        let order = match policy {
+            EvictionPolicy::LowestImportance => "importance ASC, last_accessed_at ASC",
+            EvictionPolicy::OldestEventsFirst => {
+                "memory_type = 'event' DESC, \
+                 CASE WHEN memory_type = 'event' THEN created_at END ASC, \
+                 importance ASC, last_accessed_at ASC"
+            }
+        };
+        let protected_filter = if protected.is_empty() {
+            String::new()
+        } else {
+            let placeholders = protected.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            format!("AND memory_type NOT IN ({placeholders})")
+        };
+
+        let sql = format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE forgotten = 0 AND pinned = 0 AND namespace = ? {protected_filter}
+            ORDER BY {order}
+            LIMIT ?
+            "#
+        );
+        let mut query = sqlx::query(&sql).bind(&self.namespace);
+        for memory_type in protected {
+            query = query.bind(memory_type.to_string());
+        }
+        let rows = query.bind(limit as i64).fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Set the importance of a memory without touching anything else
+    pub async fn set_importance(&self, id: &str, importance: f32) -> Result<bool> {
+        self.set_importance_with(&self.pool, id, importance).await
+    }
+
+    async fn set_importance_with<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        id: &str,
+        importance: f32,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE memories SET importance = ?, updated_at = ? WHERE id = ? AND namespace = ?",
+        )
+        .bind(importance.clamp(0.0, 1.0))
+        .bind(self.now())
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Memories matching `query`, in its order; all values are bound parameters
+    pub async fn query(&self, query: &MemoryQuery, limit: i64) -> Result<Vec<Memory>> {
+        let (conditions, values) = query.to_sql();
+        let order_clause = query.order.order_clause();
+        let sql = format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ? AND {conditions}
+            {order_clause}
+            LIMIT ?
+            "#
+        );
+
+        let rows = bind_query_values(sqlx::query(&sql).bind(&self.namespace), values)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
         Ok(rows.iter().map(row_to_memory).collect())
     }
 
+    /// Forget every active memory matching `query` in one transaction and
+    /// return their IDs. With `dry_run` nothing changes; the IDs are the ones
+    /// that would be forgotten. The query's order and forgotten flag are ignored.
+    pub async fn forget_where(&self, query: &MemoryQuery, dry_run: bool) -> Result<Vec<MemoryId>> {
+        let query = query.clone().with_forgotten(false);
+        let (conditions, values) = query.to_sql();
+        let sql = format!("SELECT id FROM memories WHERE namespace = ? AND {conditions}");
+
+        let mut tx = self.pool.begin().await?;
+        let ids: Vec<MemoryId> = bind_query_values(sqlx::query(&sql).bind(&self.namespace), values)
+            .fetch_all(&mut *tx)
+            .await?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+
+        if dry_run {
+            tx.rollback().await?;
+            return Ok(ids);
+        }
+        for id in &ids {
+            self.forget_with(&mut *tx, id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    /// Change a memory's type, returning whether it exists
+    pub async fn retype(&self, id: &str, memory_type: MemoryType) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE memories SET memory_type = ?, updated_at = ? WHERE id = ? AND namespace = ?",
+        )
+        .bind(memory_type.to_string())
+        .bind(self.now())
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Add `tag` to every memory matching `query`, returning the memories it
+    /// was added to, as saved
+    pub async fn add_tag_where(&self, query: &MemoryQuery, tag: &str) -> Result<Vec<Memory>> {
+        self.edit_where(query, |memory| {
+            if memory.tags.iter().any(|t| t == tag) {
+                return false;
+            }
+            memory.tags.push(tag.to_string());
+            true
+        })
+        .await
+    }
+
+    /// Remove `tag` from every memory matching `query`, returning the memories
+    /// it was removed from, as saved
+    pub async fn remove_tag_where(&self, query: &MemoryQuery, tag: &str) -> Result<Vec<Memory>> {
+        self.edit_where(query, |memory| {
+            let before = memory.tags.len();
+            memory.tags.retain(|t| t != tag);
+            memory.tags.len() != before
+        })
+        .await
+    }
+
+    /// Set metadata `key` to `value` (or remove it when `None`) on every memory
+    /// matching `query`, returning the memories that changed, as saved
+    pub async fn set_metadata_where(
+        &self,
+        query: &MemoryQuery,
+        key: &str,
+        value: Option<serde_json::Value>,
+    ) -> Result<Vec<Memory>> {
+        self.edit_where(query, |memory| match &value {
+            Some(value) => {
+                let metadata = memory.metadata.get_or_insert_with(|| serde_json::json!({}));
+                let Some(object) = metadata.as_object_mut() else {
+                    return false;
+                };
+                object.insert(key.to_string(), value.clone()).as_ref() != Some(value)
+            }
+            None => memory
+                .metadata
+                .as_mut()
+                .and_then(|m| m.as_object_mut())
+                .is_some_and(|object| object.remove(key).is_some()),
+        })
+        .await
+    }
+
+    /// Apply `edit` to every memory matching `query` in one transaction,
+    /// writing back those it reports as changed
+    async fn edit_where(
+        &self,
+        query: &MemoryQuery,
+        mut edit: impl FnMut(&mut Memory) -> bool,
+    ) -> Result<Vec<Memory>> {
+        let (conditions, values) = query.to_sql();
+        let sql =
+            format!("SELECT {MEMORY_COLUMNS} FROM memories WHERE namespace = ? AND {conditions}");
+
+        let mut tx = self.pool.begin().await?;
+        let rows = bind_query_values(sqlx::query(&sql).bind(&self.namespace), values)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let now = self.now();
+        let mut changed = Vec::new();
+        for mut memory in rows.iter().map(row_to_memory) {
+            if edit(&mut memory) {
+                memory.updated_at = now;
+                self.update_with(&mut *tx, &memory).await?;
+                changed.push(memory);
+            }
+        }
+        tx.commit().await?;
+
+        Ok(changed)
+    }
+
     /// Query memories with a custom SQL filter
+    #[deprecated(note = "interpolates raw SQL; use `MemoryStore::query` with a `MemoryQuery`")]
     pub async fn query_with_filter(&self, filter: &str, limit: i64) -> Result<Vec<Memory>> {
         let query = format!(
             r#"
-            SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, session_id, forgotten, metadata,
-                   confidence_score, confidence_data, verification_status
+            SELECT {MEMORY_COLUMNS}
             FROM memories
-            WHERE forgotten = 0 AND ({filter})
+            WHERE forgotten = 0 AND namespace = ? AND ({filter})
             ORDER BY created_at DESC
             LIMIT ?
             "#
         );
 
         let rows = sqlx::query(&query)
+            .bind(&self.namespace)
             .bind(limit)
             .fetch_all(&self.pool)
             .await?;
@@ -549,8 +1574,6 @@ impl MemoryStore {
 
     /// Create an in-memory store for testing
     pub async fn connect_in_memory() -> Arc<Self> {
-        use sqlx::sqlite::SqliteConnectOptions;
-
         let options = SqliteConnectOptions::new()
             .in_memory(true)
             .create_if_missing(true);
@@ -567,93 +1590,367 @@ impl MemoryStore {
             .await
             .expect("migrations");
 
-        Arc::new(Self { pool })
+        Self::new(pool)
     }
 
-    // ─── Experience (Episodic Memory) CRUD ─────────────────────────────────
+    // ─── Transactions ──────────────────────────────────────────────────────
 
-    /// Save a new experience
-    pub async fn save_experience(&self, experience: &Experience) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO experiences (id, title, context, started_at, ended_at, importance)
-            VALUES (?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&experience.id)
-        .bind(&experience.title)
-        .bind(&experience.context)
-        .bind(experience.started_at)
-        .bind(experience.ended_at)
-        .bind(experience.importance)
-        .execute(&self.pool)
-        .await?;
+    /// Start a transaction. Its writes become visible on
+    /// [`commit`](StoreTransaction::commit); dropping it rolls them back.
+    pub async fn begin(&self) -> Result<StoreTransaction> {
+        Ok(StoreTransaction {
+            tx: self.pool.begin().await?,
+            store: self.clone(),
+        })
+    }
 
-        // Insert memory links
-        for mem_id in &experience.memory_ids {
-            let _ = self.add_memory_to_experience(&experience.id, mem_id).await;
+    /// Run `f` in a transaction: committed if it returns `Ok`, rolled back if
+    /// any step fails.
+    ///
+    /// The writes go straight to SQLite; a [`MemorySystem`](crate::MemorySystem)
+    /// built on this store does not index them for search or embed them.
+    ///
+    /// ```rust,ignore
+    /// store
+    ///     .transaction(move |tx| {
+    ///         Box::pin(async move {
+    ///             tx.save(&memory).await?;
+    ///             tx.create_association(&Association::new(&memory.id, &goal_id, RelationType::PartOf)).await?;
+    ///             tx.create_association(&Association::new(&memory.id, &cause_id, RelationType::CausedBy)).await?;
+    ///             tx.set_importance(&goal_id, 0.9).await?;
+    ///             Ok(())
+    ///         })
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: for<'t> FnOnce(&'t mut StoreTransaction) -> BoxFuture<'t, Result<T>>,
+    {
+        let mut tx = self.begin().await?;
+        match f(&mut tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
         }
+    }
 
+    // ─── Backup ────────────────────────────────────────────────────────────
+
+    /// Write a consistent copy of the whole database (every namespace) to
+    /// `path` while it stays in use. `path` must not exist yet.
+    pub async fn backup_into(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref().to_string_lossy().into_owned();
+        sqlx::query("VACUUM INTO ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
-    /// Add a memory to an experience
-    pub async fn add_memory_to_experience(
-        &self,
-        experience_id: &str,
-        memory_id: &str,
-    ) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT OR IGNORE INTO experience_memories (experience_id, memory_id, added_at)
-            VALUES (?, ?, ?)
-            "#,
-        )
-        .bind(experience_id)
-        .bind(memory_id)
-        .bind(chrono::Utc::now())
-        .execute(&self.pool)
-        .await?;
+    /// Replace the contents of the whole database (every namespace) with the
+    /// snapshot at `path`, in one transaction.
+    ///
+    /// Tables are matched by name and columns by name, so a snapshot taken
+    /// before a later migration restores into the current schema; columns it
+    /// lacks get their defaults.
+    pub async fn restore_from_snapshot(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(MemoryError::NotFound(format!(
+                "snapshot {}",
+                path.display()
+            )));
+        }
 
-        Ok(())
+        // ATTACH is per connection, so everything runs on one
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE ? AS snapshot")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await?;
+
+        let restored = Self::copy_snapshot_tables(&mut conn).await;
+
+        sqlx::query("DETACH DATABASE snapshot")
+            .execute(&mut *conn)
+            .await?;
+        restored
     }
 
-    /// Update an experience (end time, importance)
-    pub async fn update_experience(&self, experience: &Experience) -> Result<()> {
-        sqlx::query(
+    async fn copy_snapshot_tables(conn: &mut sqlx::SqliteConnection) -> Result<()> {
+        use sqlx::Connection;
+
+        let tables: Vec<String> = sqlx::query_scalar(
             r#"
-            UPDATE experiences
-            SET title = ?, context = ?, ended_at = ?, importance = ?
-            WHERE id = ?
+            SELECT name FROM main.sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations'
             "#,
         )
-        .bind(&experience.title)
-        .bind(&experience.context)
-        .bind(experience.ended_at)
-        .bind(experience.importance)
-        .bind(&experience.id)
-        .execute(&self.pool)
+        .fetch_all(&mut *conn)
         .await?;
 
+        let mut tx = conn.begin().await?;
+        // Rows are deleted and re-inserted in any order
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await?;
+
+        for table in &tables {
+            sqlx::query(&format!("DELETE FROM main.\"{table}\""))
+                .execute(&mut *tx)
+                .await?;
+
+            let columns: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT m.name FROM pragma_table_info(?1, 'main') m
+                JOIN pragma_table_info(?1, 'snapshot') s ON s.name = m.name
+                "#,
+            )
+            .bind(table)
+            .fetch_all(&mut *tx)
+            .await?;
+            if columns.is_empty() {
+                continue;
+            }
+
+            let columns = columns
+                .iter()
+                .map(|c| format!("\"{c}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            sqlx::query(&format!(
+                "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM snapshot.\"{table}\""
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
 
-    /// Load an experience by ID with its memory IDs
-    pub async fn load_experience(&self, id: &str) -> Result<Option<Experience>> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, title, context, started_at, ended_at, importance
-            FROM experiences
-            WHERE id = ?
-            "#,
-        )
-        .bind(id)
-        .fetch_optional(&self.pool)
-        .await?;
+    // ─── Indexed Metadata ──────────────────────────────────────────────────
 
-        match row {
-            Some(row) => {
-                let exp_id: String = row.try_get("id").unwrap_or_default();
+    /// Materialize top-level metadata `key` into an indexed generated column
+    /// so [`get_by_metadata`](Self::get_by_metadata) doesn't scan every row.
+    /// Returns `false` when the key was already indexed.
+    pub async fn index_metadata_key(&self, key: &str) -> Result<bool> {
+        let column = metadata_column(key)?;
+        if self.indexed_metadata_keys().await?.iter().any(|k| k == key) {
+            return Ok(false);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        // Only VIRTUAL generated columns can be added to an existing table
+        sqlx::query(&format!(
+            "ALTER TABLE memories ADD COLUMN {column} \
+             GENERATED ALWAYS AS (json_extract(metadata, '$.{key}')) VIRTUAL"
+        ))
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(&format!(
+            "CREATE INDEX IF NOT EXISTS idx_memories_{column} ON memories(namespace, {column})"
+        ))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Metadata keys with an indexed column, in declaration order
+    pub async fn indexed_metadata_keys(&self) -> Result<Vec<String>> {
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_xinfo('memories') ORDER BY cid")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                name.strip_prefix(METADATA_COLUMN_PREFIX)
+                    .map(str::to_string)
+            })
+            .collect())
+    }
+
+    /// Active memories whose top-level metadata `key` equals `value`, most
+    /// important first. Uses the key's column when it is
+    /// [indexed](Self::index_metadata_key), `json_extract` otherwise.
+    pub async fn get_by_metadata(
+        &self,
+        key: &str,
+        value: impl Into<serde_json::Value>,
+    ) -> Result<Vec<Memory>> {
+        let column = metadata_column(key)?;
+        let field = if self.indexed_metadata_keys().await?.iter().any(|k| k == key) {
+            column
+        } else {
+            format!("json_extract(metadata, '$.{key}')")
+        };
+        let sql = format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ? AND {field} = ? AND forgotten = 0
+            ORDER BY importance DESC, updated_at DESC
+            "#
+        );
+        let query = sqlx::query(&sql).bind(&self.namespace);
+        // json_extract yields SQL text, numbers and 0/1 for booleans
+        let query = match value.into() {
+            serde_json::Value::String(s) => query.bind(s),
+            serde_json::Value::Bool(b) => query.bind(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64()),
+            },
+            other => {
+                return Err(MemoryError::InvalidOperation(format!(
+                    "Cannot match metadata '{key}' against {other}: use a string, number or bool"
+                )))
+            }
+        };
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    // ─── Archive ───────────────────────────────────────────────────────────
+
+    /// Every memory of the namespace, forgotten ones included, oldest first
+    pub async fn all_memories(&self) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ?
+            ORDER BY created_at ASC, id ASC
+            "#
+        ))
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Every association whose source memory is in the namespace
+    pub async fn all_associations(&self) -> Result<Vec<Association>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.source_id, a.target_id, a.relation_type, a.weight, a.created_at
+            FROM associations a
+            JOIN memories m ON m.id = a.source_id
+            WHERE m.namespace = ?
+            ORDER BY a.created_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_association).collect())
+    }
+
+    // ─── Experience (Episodic Memory) CRUD ─────────────────────────────────
+
+    /// Save a new experience
+    pub async fn save_experience(&self, experience: &Experience) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiences (
+                id, title, context, started_at, ended_at, importance, namespace, parent_id,
+                summary
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&experience.id)
+        .bind(&experience.title)
+        .bind(&experience.context)
+        .bind(experience.started_at)
+        .bind(experience.ended_at)
+        .bind(experience.importance)
+        .bind(&self.namespace)
+        .bind(&experience.parent_id)
+        .bind(&experience.summary)
+        .execute(&self.pool)
+        .await?;
+
+        // Insert memory links
+        for mem_id in &experience.memory_ids {
+            let _ = self.add_memory_to_experience(&experience.id, mem_id).await;
+        }
+
+        Ok(())
+    }
+
+    /// Add a memory to an experience
+    pub async fn add_memory_to_experience(
+        &self,
+        experience_id: &str,
+        memory_id: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO experience_memories (experience_id, memory_id, added_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(experience_id)
+        .bind(memory_id)
+        .bind(self.now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update an experience (end time, importance, parent, summary)
+    pub async fn update_experience(&self, experience: &Experience) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE experiences
+            SET title = ?, context = ?, ended_at = ?, importance = ?, parent_id = ?, summary = ?
+            WHERE id = ? AND namespace = ?
+            "#,
+        )
+        .bind(&experience.title)
+        .bind(&experience.context)
+        .bind(experience.ended_at)
+        .bind(experience.importance)
+        .bind(&experience.parent_id)
+        .bind(&experience.summary)
+        .bind(&experience.id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Load an experience by ID with its memory IDs
+    pub async fn load_experience(&self, id: &str) -> Result<Option<Experience>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, title, context, started_at, ended_at, importance, parent_id, summary
+            FROM experiences
+            WHERE id = ? AND namespace = ?
+            "#,
+        )
+        .bind(id)
+        .bind(&self.namespace)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let exp_id: String = row.try_get("id").unwrap_or_default();
                 let memory_ids = self.get_experience_memory_ids(&exp_id).await?;
 
                 Ok(Some(Experience {
@@ -666,6 +1963,8 @@ impl MemoryStore {
                         .unwrap_or_else(|_| chrono::Utc::now()),
                     ended_at: row.try_get("ended_at").ok(),
                     importance: row.try_get("importance").unwrap_or(0.5),
+                    parent_id: row.try_get("parent_id").ok().flatten(),
+                    summary: row.try_get("summary").ok().flatten(),
                 }))
             }
             None => Ok(None),
@@ -676,19 +1975,66 @@ impl MemoryStore {
     pub async fn list_experiences(&self, limit: i64, offset: i64) -> Result<Vec<Experience>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, title, context, started_at, ended_at, importance
+            SELECT id, title, context, started_at, ended_at, importance, parent_id, summary
+            FROM experiences
+            WHERE namespace = ?
+            ORDER BY started_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.rows_to_experiences(&rows).await
+    }
+
+    /// Direct sub-episodes of an experience, oldest first
+    pub async fn child_experiences(&self, parent_id: &str) -> Result<Vec<Experience>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, title, context, started_at, ended_at, importance, parent_id, summary
+            FROM experiences
+            WHERE namespace = ? AND parent_id = ?
+            ORDER BY started_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(parent_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.rows_to_experiences(&rows).await
+    }
+
+    /// Top-level experiences (no parent), newest first
+    pub async fn root_experiences(&self, limit: i64, offset: i64) -> Result<Vec<Experience>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, title, context, started_at, ended_at, importance, parent_id, summary
             FROM experiences
+            WHERE namespace = ? AND parent_id IS NULL
             ORDER BY started_at DESC
             LIMIT ? OFFSET ?
             "#,
         )
+        .bind(&self.namespace)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
         .await?;
 
+        self.rows_to_experiences(&rows).await
+    }
+
+    async fn rows_to_experiences(
+        &self,
+        rows: &[sqlx::sqlite::SqliteRow],
+    ) -> Result<Vec<Experience>> {
         let mut experiences = Vec::new();
-        for row in &rows {
+        for row in rows {
             let exp_id: String = row.try_get("id").unwrap_or_default();
             let memory_ids = self.get_experience_memory_ids(&exp_id).await?;
 
@@ -702,6 +2048,8 @@ impl MemoryStore {
                     .unwrap_or_else(|_| chrono::Utc::now()),
                 ended_at: row.try_get("ended_at").ok(),
                 importance: row.try_get("importance").unwrap_or(0.5),
+                parent_id: row.try_get("parent_id").ok().flatten(),
+                summary: row.try_get("summary").ok().flatten(),
             });
         }
 
@@ -711,77 +2059,1804 @@ impl MemoryStore {
     /// Get memory IDs for an experience
     async fn get_experience_memory_ids(&self, experience_id: &str) -> Result<Vec<MemoryId>> {
         let rows = sqlx::query(
-            "SELECT memory_id FROM experience_memories WHERE experience_id = ? ORDER BY added_at",
+            "SELECT memory_id FROM experience_memories WHERE experience_id = ? ORDER BY added_at",
+        )
+        .bind(experience_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| r.try_get("memory_id").unwrap_or_default())
+            .collect())
+    }
+
+    // ─── Memory Summary CRUD ──────────────────────────────────────────────
+
+    /// Save a memory summary
+    pub async fn save_summary(&self, summary: &MemorySummary) -> Result<()> {
+        let original_ids_json = serde_json::to_string(&summary.original_memory_ids)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO memory_summaries (
+                id, summary_text, original_memory_ids, memory_type, created_at, importance,
+                namespace
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&summary.id)
+        .bind(&summary.summary_text)
+        .bind(&original_ids_json)
+        .bind(summary.memory_type.to_string())
+        .bind(summary.created_at)
+        .bind(summary.importance)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get all memory summaries
+    pub async fn get_summaries(&self) -> Result<Vec<MemorySummary>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, summary_text, original_memory_ids, memory_type, created_at, importance
+            FROM memory_summaries
+            WHERE namespace = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut summaries = Vec::new();
+        for row in &rows {
+            let original_ids_json: String = row.try_get("original_memory_ids").unwrap_or_default();
+            let original_memory_ids: Vec<MemoryId> =
+                serde_json::from_str(&original_ids_json).unwrap_or_default();
+
+            let mem_type_str: String = row.try_get("memory_type").unwrap_or_default();
+
+            summaries.push(MemorySummary {
+                id: row.try_get("id").unwrap_or_default(),
+                summary_text: row.try_get("summary_text").unwrap_or_default(),
+                original_memory_ids,
+                memory_type: parse_memory_type(&mem_type_str),
+                created_at: row
+                    .try_get("created_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                importance: row.try_get("importance").unwrap_or(0.5),
+            });
+        }
+
+        Ok(summaries)
+    }
+
+    // ─── Citations ─────────────────────────────────────────────────────────
+
+    /// Record that a memory was cited by a generated answer.
+    ///
+    /// Returns `false` if this answer already cited the memory.
+    pub async fn record_citation(&self, answer_id: &str, memory_id: &str) -> Result<bool> {
+        self.record_citation_with(&self.pool, answer_id, memory_id)
+            .await
+    }
+
+    async fn record_citation_with<'e>(
+        &self,
+        executor: impl sqlx::SqliteExecutor<'e>,
+        answer_id: &str,
+        memory_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO citations (answer_id, memory_id, cited_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(answer_id)
+        .bind(memory_id)
+        .bind(self.now())
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the memory IDs cited by an answer
+    pub async fn get_citations(&self, answer_id: &str) -> Result<Vec<MemoryId>> {
+        let rows =
+            sqlx::query("SELECT memory_id FROM citations WHERE answer_id = ? ORDER BY cited_at")
+                .bind(answer_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| r.try_get("memory_id").unwrap_or_default())
+            .collect())
+    }
+
+    /// Get the most frequently cited (non-forgotten) memories
+    pub async fn most_cited(&self, limit: i64) -> Result<Vec<CitedMemory>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT m.*,
+                   COUNT(c.answer_id) AS citation_count,
+                   MAX(c.cited_at) AS last_cited_at
+            FROM citations c
+            JOIN memories m ON m.id = c.memory_id
+            WHERE m.forgotten = 0 AND m.namespace = ?
+            GROUP BY m.id
+            ORDER BY citation_count DESC, last_cited_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CitedMemory {
+                memory: row_to_memory(row),
+                citation_count: row.try_get("citation_count").unwrap_or(0),
+                last_cited_at: row
+                    .try_get("last_cited_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            })
+            .collect())
+    }
+
+    // ─── Sessions ──────────────────────────────────────────────────────────
+
+    /// Persist a new session
+    pub async fn create_session(&self, session: &Session) -> Result<()> {
+        let metadata = session
+            .metadata
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, namespace, metadata, started_at, ended_at, summary)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&session.id)
+        .bind(&self.namespace)
+        .bind(metadata)
+        .bind(session.started_at)
+        .bind(session.ended_at)
+        .bind(&session.summary)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A session with its memory count and time span
+    pub async fn get_session(&self, id: &str) -> Result<Option<SessionInfo>> {
+        let sql = format!("{SESSION_INFO_SELECT} WHERE s.namespace = ? AND s.id = ? GROUP BY s.id");
+        let row = sqlx::query(&sql)
+            .bind(&self.namespace)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(row_to_session_info).transpose()
+    }
+
+    /// Sessions with their memory counts and time spans, most recently started first
+    pub async fn list_sessions(&self, limit: i64, offset: i64) -> Result<Vec<SessionInfo>> {
+        let sql = format!(
+            "{SESSION_INFO_SELECT} WHERE s.namespace = ? GROUP BY s.id \
+             ORDER BY s.started_at DESC LIMIT ? OFFSET ?"
+        );
+        let rows = sqlx::query(&sql)
+            .bind(&self.namespace)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(row_to_session_info).collect()
+    }
+
+    /// Mark an active session ended. Returns `false` if it does not exist or
+    /// has already ended.
+    pub async fn end_session(
+        &self,
+        id: &str,
+        ended_at: chrono::DateTime<chrono::Utc>,
+        summary: Option<&str>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions SET ended_at = ?, summary = ?
+            WHERE id = ? AND namespace = ? AND ended_at IS NULL
+            "#,
+        )
+        .bind(ended_at)
+        .bind(summary)
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ─── Vector Chunks ─────────────────────────────────────────────────────
+
+    /// Replace the chunk offsets recorded for a memory's vectors
+    pub async fn save_vector_chunks(&self, memory_id: &str, chunks: &[VectorChunk]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM vector_chunks WHERE memory_id = ?")
+            .bind(memory_id)
+            .execute(&mut *tx)
+            .await?;
+        for chunk in chunks {
+            sqlx::query(
+                r#"
+                INSERT INTO vector_chunks (memory_id, chunk, start_offset, end_offset)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(memory_id)
+            .bind(chunk.index as i64)
+            .bind(chunk.start as i64)
+            .bind(chunk.end as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Chunks a memory is embedded as, in order; empty for a single vector
+    pub async fn vector_chunks(&self, memory_id: &str) -> Result<Vec<VectorChunk>> {
+        let rows = sqlx::query(
+            "SELECT chunk, start_offset, end_offset FROM vector_chunks WHERE memory_id = ? ORDER BY chunk",
+        )
+        .bind(memory_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| VectorChunk {
+                index: row.try_get::<i64, _>("chunk").unwrap_or(0) as usize,
+                start: row.try_get::<i64, _>("start_offset").unwrap_or(0) as usize,
+                end: row.try_get::<i64, _>("end_offset").unwrap_or(0) as usize,
+            })
+            .collect())
+    }
+
+    // ─── Saved Searches ────────────────────────────────────────────────────
+
+    /// Persist a new saved search. Names are unique within the namespace.
+    pub async fn create_saved_search(&self, search: &SavedSearch) -> Result<()> {
+        let config = serde_json::to_string(&search.config)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO saved_searches
+                (id, namespace, name, query, config, webhook_url,
+                 created_at, last_hit_at, hit_count)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&search.id)
+        .bind(&self.namespace)
+        .bind(&search.name)
+        .bind(&search.query)
+        .bind(config)
+        .bind(&search.webhook_url)
+        .bind(search.created_at)
+        .bind(search.last_hit_at)
+        .bind(search.hit_count)
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                Err(MemoryError::InvalidOperation(format!(
+                    "Saved search '{}' already exists",
+                    search.name
+                )))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get a saved search by name
+    pub async fn get_saved_search(&self, name: &str) -> Result<Option<SavedSearch>> {
+        let row = sqlx::query("SELECT * FROM saved_searches WHERE namespace = ? AND name = ?")
+            .bind(&self.namespace)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(row_to_saved_search).transpose()
+    }
+
+    /// All saved searches, oldest first
+    pub async fn list_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let rows =
+            sqlx::query("SELECT * FROM saved_searches WHERE namespace = ? ORDER BY created_at")
+                .bind(&self.namespace)
+                .fetch_all(&self.pool)
+                .await?;
+
+        rows.iter().map(row_to_saved_search).collect()
+    }
+
+    /// Set or clear the webhook of a saved search. Returns `false` if it does not exist.
+    pub async fn set_saved_search_webhook(&self, name: &str, url: Option<&str>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE saved_searches SET webhook_url = ? WHERE namespace = ? AND name = ?",
+        )
+        .bind(url)
+        .bind(&self.namespace)
+        .bind(name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a saved search. Returns `false` if it does not exist.
+    pub async fn delete_saved_search(&self, name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM saved_searches WHERE namespace = ? AND name = ?")
+            .bind(&self.namespace)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count a hit against a saved search
+    pub async fn record_saved_search_hit(
+        &self,
+        id: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE saved_searches SET hit_count = hit_count + 1, last_hit_at = ?
+            WHERE id = ? AND namespace = ?
+            "#,
+        )
+        .bind(at)
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ─── Sources ───────────────────────────────────────────────────────────
+
+    /// Insert or replace a tracked source document
+    pub async fn upsert_source(&self, source: &SourceDocument) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO sources
+                (namespace, uri, content_hash, memory_type, created_at, last_synced)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (namespace, uri) DO UPDATE SET
+                content_hash = excluded.content_hash,
+                memory_type = excluded.memory_type,
+                last_synced = excluded.last_synced
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&source.uri)
+        .bind(&source.content_hash)
+        .bind(source.memory_type.to_string())
+        .bind(source.created_at)
+        .bind(source.last_synced)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a tracked source document by URI
+    pub async fn get_source(&self, uri: &str) -> Result<Option<SourceDocument>> {
+        let row = sqlx::query("SELECT * FROM sources WHERE namespace = ? AND uri = ?")
+            .bind(&self.namespace)
+            .bind(uri)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(row_to_source).transpose()
+    }
+
+    /// All tracked source documents, by URI
+    pub async fn list_sources(&self) -> Result<Vec<SourceDocument>> {
+        let rows = sqlx::query("SELECT * FROM sources WHERE namespace = ? ORDER BY uri")
+            .bind(&self.namespace)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(row_to_source).collect()
+    }
+
+    /// Record that `memory_id` was derived from section `section` of a source
+    pub async fn link_source_memory(
+        &self,
+        uri: &str,
+        memory_id: &str,
+        section: usize,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO source_memories (namespace, uri, memory_id, section)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (namespace, uri, memory_id) DO UPDATE SET
+                section = excluded.section
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(uri)
+        .bind(memory_id)
+        .bind(section as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove the link between a source and a derived memory
+    pub async fn unlink_source_memory(&self, uri: &str, memory_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM source_memories WHERE namespace = ? AND uri = ? AND memory_id = ?",
+        )
+        .bind(&self.namespace)
+        .bind(uri)
+        .bind(memory_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Non-forgotten memories derived from a source, with their section index,
+    /// in document order
+    pub async fn source_memories(&self, uri: &str) -> Result<Vec<(usize, Memory)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT memory_id, section FROM source_memories
+            WHERE namespace = ? AND uri = ?
+            ORDER BY section, rowid
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(uri)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut links = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let id: String = row.try_get("memory_id")?;
+            let section: i64 = row.try_get("section")?;
+            links.push((id, section as usize));
+        }
+
+        let ids: Vec<String> = links.iter().map(|(id, _)| id.clone()).collect();
+        let mut memories: std::collections::HashMap<MemoryId, Memory> = self
+            .load_many(&ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id.clone(), m))
+            .collect();
+
+        Ok(links
+            .into_iter()
+            .filter_map(|(id, section)| memories.remove(&id).map(|m| (section, m)))
+            .collect())
+    }
+
+    // ─── Connector Checkpoints ─────────────────────────────────────────────
+
+    /// The last checkpoint saved by a connector
+    pub async fn connector_checkpoint(&self, connector: &str) -> Result<Option<String>> {
+        let row = sqlx::query(
+            "SELECT checkpoint FROM connector_checkpoints WHERE namespace = ? AND connector = ?",
+        )
+        .bind(&self.namespace)
+        .bind(connector)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| r.try_get("checkpoint")).transpose()?)
+    }
+
+    /// Save a connector's checkpoint, replacing the previous one
+    pub async fn set_connector_checkpoint(&self, connector: &str, checkpoint: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO connector_checkpoints (namespace, connector, checkpoint, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (namespace, connector) DO UPDATE SET
+                checkpoint = excluded.checkpoint,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(connector)
+        .bind(checkpoint)
+        .bind(self.now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The experience a connector created for an episode key
+    pub async fn connector_episode(&self, connector: &str, key: &str) -> Result<Option<String>> {
+        let row = sqlx::query(
+            r#"
+            SELECT experience_id FROM connector_episodes
+            WHERE namespace = ? AND connector = ? AND episode_key = ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(connector)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| r.try_get("experience_id")).transpose()?)
+    }
+
+    /// Remember the experience created for a connector's episode key
+    pub async fn set_connector_episode(
+        &self,
+        connector: &str,
+        key: &str,
+        experience_id: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO connector_episodes (namespace, connector, episode_key, experience_id)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (namespace, connector, episode_key) DO UPDATE SET
+                experience_id = excluded.experience_id
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(connector)
+        .bind(key)
+        .bind(experience_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ─── Review Queue ──────────────────────────────────────────────────────
+
+    /// Add a memory to the review queue, replacing an earlier entry for its ID
+    pub async fn enqueue_review(&self, item: &ReviewItem) -> Result<()> {
+        let memory = serde_json::to_string(&item.memory)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO review_queue
+                (namespace, memory_id, memory, status, reason, submitted_at, reviewed_at,
+                 reviewer, note)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (namespace, memory_id) DO UPDATE SET
+                memory = excluded.memory,
+                status = excluded.status,
+                reason = excluded.reason,
+                submitted_at = excluded.submitted_at,
+                reviewed_at = excluded.reviewed_at,
+                reviewer = excluded.reviewer,
+                note = excluded.note
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&item.memory.id)
+        .bind(memory)
+        .bind(item.status.as_str())
+        .bind(&item.reason)
+        .bind(item.submitted_at)
+        .bind(item.reviewed_at)
+        .bind(&item.reviewer)
+        .bind(&item.note)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The review queue entry for a memory
+    pub async fn get_review(&self, memory_id: &str) -> Result<Option<ReviewItem>> {
+        let row = sqlx::query("SELECT * FROM review_queue WHERE namespace = ? AND memory_id = ?")
+            .bind(&self.namespace)
+            .bind(memory_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(row_to_review).transpose()
+    }
+
+    /// Review queue entries, oldest first; `None` lists every status
+    pub async fn list_reviews(
+        &self,
+        status: Option<ReviewStatus>,
+        limit: i64,
+    ) -> Result<Vec<ReviewItem>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM review_queue
+            WHERE namespace = ? AND (? IS NULL OR status = ?)
+            ORDER BY submitted_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(status.map(|s| s.as_str()))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_review).collect()
+    }
+
+    /// Move a pending entry to `status`. Returns `false` if there is no
+    /// pending entry for the memory.
+    pub async fn resolve_review(
+        &self,
+        memory_id: &str,
+        status: ReviewStatus,
+        reviewer: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE review_queue
+            SET status = ?, reviewed_at = ?, reviewer = ?, note = ?
+            WHERE namespace = ? AND memory_id = ? AND status = 'pending_review'
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(self.now())
+        .bind(reviewer)
+        .bind(note)
+        .bind(&self.namespace)
+        .bind(memory_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ─── Query Log ─────────────────────────────────────────────────────────
+
+    /// Append an entry to the query log
+    pub async fn log_query(&self, entry: &QueryLogEntry) -> Result<()> {
+        let top_ids = serde_json::to_string(&entry.top_ids)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO query_log (
+                namespace, query, kind, config_hash, latency_ms, result_count,
+                top_ids, timed_out, logged_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&entry.query)
+        .bind(&entry.kind)
+        .bind(&entry.config_hash)
+        .bind(entry.latency_ms)
+        .bind(entry.result_count as i64)
+        .bind(top_ids)
+        .bind(entry.timed_out)
+        .bind(entry.logged_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Query log entries since `since`, newest first
+    pub async fn query_log_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<QueryLogEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT query, kind, config_hash, latency_ms, result_count, top_ids, timed_out, logged_at
+            FROM query_log
+            WHERE namespace = ? AND logged_at >= ?
+            ORDER BY logged_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_query_log_entry).collect()
+    }
+
+    /// Drop query log entries older than `before`, returning how many were removed
+    pub async fn purge_query_log(&self, before: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM query_log WHERE namespace = ? AND logged_at < ?")
+            .bind(&self.namespace)
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // ─── Quality ───────────────────────────────────────────────────────────
+
+    /// Store the quality score of a memory
+    pub async fn set_quality(&self, id: &str, quality: &QualityScore) -> Result<bool> {
+        let quality_json = serde_json::to_string(quality)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        let result = sqlx::query(
+            "UPDATE memories SET quality = ?, quality_data = ? WHERE id = ? AND namespace = ?",
+        )
+        .bind(quality.score)
+        .bind(quality_json)
+        .bind(id)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the quality score of a memory, if one was computed
+    pub async fn get_quality(&self, id: &str) -> Result<Option<QualityScore>> {
+        let row = sqlx::query("SELECT quality_data FROM memories WHERE id = ? AND namespace = ?")
+            .bind(id)
+            .bind(&self.namespace)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row
+            .and_then(|r| {
+                r.try_get::<Option<String>, _>("quality_data")
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Unreviewed, unpinned, non-forgotten memories scoring below `threshold`,
+    /// worst first
+    pub async fn low_quality(
+        &self,
+        threshold: f32,
+        limit: i64,
+    ) -> Result<Vec<(Memory, QualityScore)>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}, quality_data
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0 AND pinned = 0 AND quality_reviewed = 0
+              AND quality IS NOT NULL AND quality < ?
+            ORDER BY quality ASC
+            LIMIT ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(threshold)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let json: String = row.try_get("quality_data").ok()?;
+                let quality = serde_json::from_str(&json).ok()?;
+                Some((row_to_memory(row), quality))
+            })
+            .collect())
+    }
+
+    /// Take a memory off the curation queue
+    pub async fn mark_quality_reviewed(&self, id: &str) -> Result<bool> {
+        let result =
+            sqlx::query("UPDATE memories SET quality_reviewed = 1 WHERE id = ? AND namespace = ?")
+                .bind(id)
+                .bind(&self.namespace)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ─── Hygiene ───────────────────────────────────────────────────────────
+
+    /// `(memory, duplicate_of)` pairs recorded by quality scoring, between active memories
+    pub async fn duplicate_links(&self) -> Result<Vec<(MemoryId, MemoryId)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT m.id, json_extract(m.quality_data, '$.duplicate_of') AS duplicate_of
+            FROM memories m
+            JOIN memories d
+              ON d.id = json_extract(m.quality_data, '$.duplicate_of')
+             AND d.namespace = m.namespace AND d.forgotten = 0
+            WHERE m.namespace = ? AND m.forgotten = 0
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| Some((row.try_get("id").ok()?, row.try_get("duplicate_of").ok()?)))
+            .collect())
+    }
+
+    /// `Contradicts` associations between two active memories
+    pub async fn active_contradictions(&self, limit: i64) -> Result<Vec<Association>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.source_id, a.target_id, a.relation_type, a.weight, a.created_at
+            FROM associations a
+            JOIN memories s ON s.id = a.source_id AND s.namespace = ? AND s.forgotten = 0
+            JOIN memories t ON t.id = a.target_id AND t.namespace = ? AND t.forgotten = 0
+            WHERE a.relation_type = ?
+            ORDER BY a.created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&self.namespace)
+        .bind(RelationType::Contradicts.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_association).collect())
+    }
+
+    /// Associations from an active memory to one that is missing or forgotten
+    pub async fn dangling_associations(&self, limit: i64) -> Result<Vec<Association>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.source_id, a.target_id, a.relation_type, a.weight, a.created_at
+            FROM associations a
+            LEFT JOIN memories s ON s.id = a.source_id
+            LEFT JOIN memories t ON t.id = a.target_id
+            WHERE (s.namespace = ? OR t.namespace = ?)
+              AND (s.id IS NULL OR t.id IS NULL OR s.forgotten = 1 OR t.forgotten = 1)
+            ORDER BY a.created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&self.namespace)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_association).collect())
+    }
+
+    /// Unpinned active memories created before `before` and never accessed, oldest first
+    pub async fn never_accessed_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0 AND pinned = 0
+              AND access_count = 0 AND created_at < ?
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Active memories longer than `chars` characters, longest first
+    pub async fn oversized(&self, chars: usize, limit: i64) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0 AND length(content) > ?
+            ORDER BY length(content) DESC
+            LIMIT ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(chars as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    /// Active memories with confidence below `max_confidence` that are likely to
+    /// reach the context window: pinned, at least `min_importance`, or accessed at
+    /// least `min_accesses` times. Least confident first.
+    pub async fn low_confidence_surfaced(
+        &self,
+        max_confidence: f32,
+        min_importance: f32,
+        min_accesses: i64,
+        limit: i64,
+    ) -> Result<Vec<Memory>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {MEMORY_COLUMNS}
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0 AND confidence_score < ?
+              AND (pinned = 1 OR importance >= ? OR access_count >= ?)
+            ORDER BY confidence_score ASC
+            LIMIT ?
+            "#
+        ))
+        .bind(&self.namespace)
+        .bind(max_confidence)
+        .bind(min_importance)
+        .bind(min_accesses)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_memory).collect())
+    }
+
+    // ─── Versions ──────────────────────────────────────────────────────────
+
+    /// Append a snapshot of `memory` to its version history
+    pub async fn record_version(
+        &self,
+        memory: &Memory,
+        author: VersionAuthor,
+        reason: &str,
+    ) -> Result<MemoryVersion> {
+        let snapshot =
+            serde_json::to_string(memory).map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        let author_json = serde_json::to_string(&author)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+
+        let mut tx = self.pool.begin().await?;
+        let previous = sqlx::query(
+            r#"
+            SELECT id, version_number FROM memory_versions
+            WHERE namespace = ? AND memory_id = ?
+            ORDER BY version_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(&memory.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (previous_version_id, version_number) = match previous {
+            Some(row) => (
+                Some(VersionId(row.try_get("id")?)),
+                row.try_get::<i64, _>("version_number")? as u32 + 1,
+            ),
+            None => (None, 1),
+        };
+
+        let version = MemoryVersion {
+            version_id: VersionId(self.next_id()),
+            memory_id: memory.id.clone(),
+            version_number,
+            memory: memory.clone(),
+            created_at: self.now(),
+            author,
+            change_reason: Some(reason.to_string()),
+            previous_version_id,
+            diff: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO memory_versions
+                (id, namespace, memory_id, version_number, snapshot, author,
+                 change_reason, previous_version_id, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&version.version_id.0)
+        .bind(&self.namespace)
+        .bind(&version.memory_id)
+        .bind(version.version_number as i64)
+        .bind(snapshot)
+        .bind(author_json)
+        .bind(&version.change_reason)
+        .bind(version.previous_version_id.as_ref().map(|v| v.0.as_str()))
+        .bind(version.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(version)
+    }
+
+    /// Version history of a memory, oldest first
+    pub async fn memory_versions(&self, memory_id: &str) -> Result<Vec<MemoryVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM memory_versions
+            WHERE namespace = ? AND memory_id = ?
+            ORDER BY version_number
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(memory_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_version).collect()
+    }
+
+    /// When a memory was accessed, oldest first, as far back as the access log goes
+    pub async fn memory_accesses(
+        &self,
+        memory_id: &str,
+    ) -> Result<Vec<chrono::DateTime<chrono::Utc>>> {
+        let accesses = sqlx::query_scalar(
+            r#"
+            SELECT a.accessed_at
+            FROM memory_accesses a
+            JOIN memories m ON m.id = a.memory_id
+            WHERE a.memory_id = ? AND m.namespace = ?
+            ORDER BY a.accessed_at
+            "#,
+        )
+        .bind(memory_id)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accesses)
+    }
+
+    /// Episodes a memory was added to, as (episode ID, title, added at), oldest first
+    pub async fn memory_episodes(
+        &self,
+        memory_id: &str,
+    ) -> Result<Vec<(String, String, chrono::DateTime<chrono::Utc>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.title, em.added_at
+            FROM experience_memories em
+            JOIN experiences e ON e.id = em.experience_id
+            WHERE em.memory_id = ? AND e.namespace = ?
+            ORDER BY em.added_at
+            "#,
+        )
+        .bind(memory_id)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("id"), row.get("title"), row.get("added_at")))
+            .collect())
+    }
+
+    /// Answers that cited a memory, as (answer ID, cited at), oldest first
+    pub async fn memory_citations(
+        &self,
+        memory_id: &str,
+    ) -> Result<Vec<(String, chrono::DateTime<chrono::Utc>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.answer_id, c.cited_at
+            FROM citations c
+            JOIN memories m ON m.id = c.memory_id
+            WHERE c.memory_id = ? AND m.namespace = ?
+            ORDER BY c.cited_at
+            "#,
+        )
+        .bind(memory_id)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("answer_id"), row.get("cited_at")))
+            .collect())
+    }
+
+    /// The latest version of a memory recorded at or before `at`
+    pub async fn version_as_of(
+        &self,
+        memory_id: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<MemoryVersion>> {
+        let row = sqlx::query(
+            r#"
+            SELECT * FROM memory_versions
+            WHERE namespace = ? AND memory_id = ? AND created_at <= ?
+            ORDER BY version_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(memory_id)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(row_to_version).transpose()
+    }
+
+    /// The latest version of every memory recorded at or before `at`
+    pub async fn versions_as_of(
+        &self,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<MemoryVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT v.* FROM memory_versions v
+            WHERE v.namespace = ?1 AND v.created_at <= ?2
+              AND v.version_number = (
+                  SELECT MAX(w.version_number) FROM memory_versions w
+                  WHERE w.namespace = ?1 AND w.memory_id = v.memory_id AND w.created_at <= ?2
+              )
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(at)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_version).collect()
+    }
+
+    /// A specific version of a memory
+    pub async fn memory_version(
+        &self,
+        memory_id: &str,
+        version_number: u32,
+    ) -> Result<Option<MemoryVersion>> {
+        let row = sqlx::query(
+            r#"
+            SELECT * FROM memory_versions
+            WHERE namespace = ? AND memory_id = ? AND version_number = ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(memory_id)
+        .bind(version_number as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(row_to_version).transpose()
+    }
+
+    /// The newest version of a memory
+    pub async fn latest_version(&self, memory_id: &str) -> Result<Option<MemoryVersion>> {
+        let row = sqlx::query(
+            r#"
+            SELECT * FROM memory_versions
+            WHERE namespace = ? AND memory_id = ?
+            ORDER BY version_number DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(memory_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(row_to_version).transpose()
+    }
+
+    // ─── Context Traces ────────────────────────────────────────────────────
+
+    /// Version number matching the memory as stored now, recording a new
+    /// version when the history is behind (memories written through the
+    /// cortex aren't versioned on save). `None` when the memory doesn't exist.
+    pub async fn traced_version(&self, memory_id: &str) -> Result<Option<u32>> {
+        let Some(memory) = self.load(memory_id).await? else {
+            return Ok(None);
+        };
+        if let Some(latest) = self.latest_version(memory_id).await? {
+            let snapshot = &latest.memory;
+            if snapshot.content == memory.content
+                && snapshot.memory_type == memory.memory_type
+                && snapshot.importance == memory.importance
+                && snapshot.created_at == memory.created_at
+            {
+                return Ok(Some(latest.version_number));
+            }
+        }
+        let author = VersionAuthor::Automation {
+            process: "context trace".to_string(),
+        };
+        let version = self
+            .record_version(&memory, author, "context trace")
+            .await?;
+        Ok(Some(version.version_number))
+    }
+
+    pub async fn save_context_trace(&self, trace: &ContextTrace) -> Result<()> {
+        let memories = serde_json::to_string(&trace.memories)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO context_traces
+                (id, namespace, content_hash, context, memories, experience_id, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&trace.id)
+        .bind(&self.namespace)
+        .bind(&trace.content_hash)
+        .bind(&trace.context)
+        .bind(memories)
+        .bind(&trace.experience_id)
+        .bind(trace.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn context_trace(&self, id: &str) -> Result<Option<ContextTrace>> {
+        let row = sqlx::query("SELECT * FROM context_traces WHERE namespace = ? AND id = ?")
+            .bind(&self.namespace)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(row_to_context_trace).transpose()
+    }
+
+    /// Every trace of a rendered context with this hash, newest first
+    pub async fn context_traces_by_hash(&self, content_hash: &str) -> Result<Vec<ContextTrace>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM context_traces
+            WHERE namespace = ? AND content_hash = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(content_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(row_to_context_trace).collect()
+    }
+
+    /// The memories of a trace as they were when it was built, in render
+    /// order. Entries without a version are skipped.
+    pub async fn trace_memories(&self, trace: &ContextTrace) -> Result<Vec<Memory>> {
+        let mut memories = Vec::with_capacity(trace.memories.len());
+        for traced in &trace.memories {
+            let Some(number) = traced.version else {
+                continue;
+            };
+            let version = self
+                .memory_version(&traced.memory_id, number)
+                .await?
+                .ok_or_else(|| {
+                    MemoryError::NotFound(format!(
+                        "version {number} of memory {}",
+                        traced.memory_id
+                    ))
+                })?;
+            memories.push(version.memory);
+        }
+        Ok(memories)
+    }
+
+    // ─── Analytics ─────────────────────────────────────────────────────────
+
+    /// Emotional valence of non-forgotten memories created since `since`, oldest first
+    pub async fn get_valence_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(MemoryId, f32, chrono::DateTime<chrono::Utc>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, emotional_valence, created_at
+            FROM memories
+            WHERE forgotten = 0 AND namespace = ? AND created_at >= ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("id"),
+                    row.get("emotional_valence"),
+                    row.get("created_at"),
+                )
+            })
+            .collect())
+    }
+
+    /// Average valence of each episode started since `since`:
+    /// (experience id, title, average valence, memory count)
+    pub async fn episode_valence_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(String, String, f32, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.title, AVG(m.emotional_valence) AS avg_valence, COUNT(m.id) AS n
+            FROM experiences e
+            JOIN experience_memories em ON em.experience_id = e.id
+            JOIN memories m ON m.id = em.memory_id
+            WHERE m.forgotten = 0 AND e.namespace = ? AND e.started_at >= ?
+            GROUP BY e.id, e.title
+            ORDER BY e.started_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let avg: f64 = row.get("avg_valence");
+                (row.get("id"), row.get("title"), avg as f32, row.get("n"))
+            })
+            .collect())
+    }
+
+    /// Tag usage among non-forgotten memories created in `[start, end)`, most used first
+    pub async fn tag_counts_between(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.value AS tag, COUNT(*) AS n
+            FROM memories m, json_each(m.tags) t
+            WHERE m.forgotten = 0 AND m.tags IS NOT NULL AND m.namespace = ?
+              AND m.created_at >= ? AND m.created_at < ?
+            GROUP BY t.value
+            ORDER BY n DESC, tag ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(start)
+        .bind(end)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("tag"), row.get("n")))
+            .collect())
+    }
+
+    /// Memories created since `since`, counted per time bucket and type.
+    ///
+    /// Buckets are `bucket_secs` wide, aligned to `origin_secs` after the Unix epoch.
+    /// Returns (bucket start as Unix seconds, memory type, count) ordered by bucket.
+    pub async fn creation_counts_by_bucket(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        bucket_secs: i64,
+        origin_secs: i64,
+    ) -> Result<Vec<(i64, MemoryType, i64)>> {
+        let bucket_secs = bucket_secs.max(1);
+        let rows = sqlx::query(
+            r#"
+            SELECT ((CAST(strftime('%s', created_at) AS INTEGER) - ?1) / ?2) * ?2 + ?1 AS bucket,
+                   memory_type,
+                   COUNT(*) AS n
+            FROM memories
+            WHERE forgotten = 0 AND namespace = ?4 AND created_at >= ?3
+            GROUP BY bucket, memory_type
+            ORDER BY bucket ASC
+            "#,
+        )
+        .bind(origin_secs)
+        .bind(bucket_secs)
+        .bind(since)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let memory_type: String = row.get("memory_type");
+                (
+                    row.get("bucket"),
+                    parse_memory_type(&memory_type),
+                    row.get("n"),
+                )
+            })
+            .collect())
+    }
+
+    /// Memories created and accessed since `since`, counted per `bucket_secs`-wide bucket.
+    ///
+    /// Returns (bucket start as Unix seconds, created, accessed) ordered by bucket;
+    /// buckets with no activity are omitted.
+    pub async fn activity_counts(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        bucket_secs: i64,
+        origin_secs: i64,
+    ) -> Result<Vec<(i64, i64, i64)>> {
+        let bucket_secs = bucket_secs.max(1);
+        let rows = sqlx::query(
+            r#"
+            SELECT ((ts - ?1) / ?2) * ?2 + ?1 AS bucket,
+                   SUM(created) AS created,
+                   SUM(accessed) AS accessed
+            FROM (
+                SELECT CAST(strftime('%s', created_at) AS INTEGER) AS ts, 1 AS created, 0 AS accessed
+                FROM memories WHERE created_at >= ?3 AND namespace = ?4
+                UNION ALL
+                SELECT CAST(strftime('%s', a.accessed_at) AS INTEGER), 0, 1
+                FROM memory_accesses a
+                JOIN memories m ON m.id = a.memory_id
+                WHERE a.accessed_at >= ?3 AND m.namespace = ?4
+            )
+            GROUP BY bucket
+            ORDER BY bucket ASC
+            "#,
         )
-        .bind(experience_id)
+        .bind(origin_secs)
+        .bind(bucket_secs)
+        .bind(since)
+        .bind(&self.namespace)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(rows
             .iter()
-            .map(|r| r.try_get("memory_id").unwrap_or_default())
+            .map(|row| (row.get("bucket"), row.get("created"), row.get("accessed")))
             .collect())
     }
 
-    // ─── Memory Summary CRUD ──────────────────────────────────────────────
+    /// Content of non-forgotten memories created since `since`, newest first
+    pub async fn contents_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT content FROM memories
+            WHERE forgotten = 0 AND namespace = ? AND created_at >= ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
 
-    /// Save a memory summary
-    pub async fn save_summary(&self, summary: &MemorySummary) -> Result<()> {
-        let original_ids_json = serde_json::to_string(&summary.original_memory_ids)
-            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        Ok(rows.iter().map(|row| row.get("content")).collect())
+    }
 
-        sqlx::query(
+    // ─── Namespaces ────────────────────────────────────────────────────────
+    //
+    // These look across every namespace in the database, regardless of the
+    // store's own scope.
+
+    /// All namespaces holding memories or experiences, sorted
+    pub async fn list_namespaces(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query(
             r#"
-            INSERT INTO memory_summaries (id, summary_text, original_memory_ids, memory_type, created_at, importance)
-            VALUES (?, ?, ?, ?, ?, ?)
+            SELECT namespace FROM memories
+            UNION
+            SELECT namespace FROM experiences
+            ORDER BY namespace
             "#,
         )
-        .bind(&summary.id)
-        .bind(&summary.summary_text)
-        .bind(&original_ids_json)
-        .bind(summary.memory_type.to_string())
-        .bind(summary.created_at)
-        .bind(summary.importance)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(rows.iter().map(|row| row.get("namespace")).collect())
     }
 
-    /// Get all memory summaries
-    pub async fn get_summaries(&self) -> Result<Vec<MemorySummary>> {
+    /// Memory and experience counts of every namespace, sorted by namespace
+    pub async fn namespace_stats(&self) -> Result<Vec<NamespaceStats>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, summary_text, original_memory_ids, memory_type, created_at, importance
-            FROM memory_summaries
-            ORDER BY created_at DESC
+            SELECT ns.namespace,
+                   (SELECT COUNT(*) FROM memories m
+                    WHERE m.namespace = ns.namespace AND m.forgotten = 0) AS memories,
+                   (SELECT COUNT(*) FROM memories m
+                    WHERE m.namespace = ns.namespace AND m.forgotten = 1) AS forgotten,
+                   (SELECT COUNT(*) FROM experiences e
+                    WHERE e.namespace = ns.namespace) AS experiences,
+                   (SELECT MAX(m.updated_at) FROM memories m
+                    WHERE m.namespace = ns.namespace) AS last_updated_at
+            FROM (
+                SELECT namespace FROM memories
+                UNION
+                SELECT namespace FROM experiences
+            ) ns
+            ORDER BY ns.namespace
             "#,
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let mut summaries = Vec::new();
-        for row in &rows {
-            let original_ids_json: String = row.try_get("original_memory_ids").unwrap_or_default();
-            let original_memory_ids: Vec<MemoryId> =
-                serde_json::from_str(&original_ids_json).unwrap_or_default();
+        Ok(rows
+            .iter()
+            .map(|row| NamespaceStats {
+                namespace: row.get("namespace"),
+                memories: row.try_get("memories").unwrap_or(0),
+                forgotten: row.try_get("forgotten").unwrap_or(0),
+                experiences: row.try_get("experiences").unwrap_or(0),
+                last_updated_at: row
+                    .try_get::<Option<chrono::DateTime<chrono::Utc>>, _>("last_updated_at")
+                    .ok()
+                    .flatten(),
+            })
+            .collect())
+    }
+}
 
-            let mem_type_str: String = row.try_get("memory_type").unwrap_or_default();
+/// Writes that commit or roll back together, see [`MemoryStore::transaction`]
+///
+/// Reads see the transaction's own uncommitted writes. All operations are
+/// scoped to the namespace of the store that began the transaction.
+pub struct StoreTransaction {
+    tx: sqlx::Transaction<'static, sqlx::Sqlite>,
+    store: MemoryStore,
+}
 
-            summaries.push(MemorySummary {
-                id: row.try_get("id").unwrap_or_default(),
-                summary_text: row.try_get("summary_text").unwrap_or_default(),
-                original_memory_ids,
-                memory_type: parse_memory_type(&mem_type_str),
-                created_at: row
-                    .try_get("created_at")
-                    .unwrap_or_else(|_| chrono::Utc::now()),
-                importance: row.try_get("importance").unwrap_or(0.5),
-            });
+impl std::fmt::Debug for StoreTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreTransaction")
+            .field("namespace", &self.store.namespace)
+            .finish()
+    }
+}
+
+impl StoreTransaction {
+    /// The store that began the transaction, for its clock and ID generator
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    pub async fn save(&mut self, memory: &Memory) -> Result<()> {
+        self.store.insert_memory(&mut *self.tx, memory).await
+    }
+
+    pub async fn load(&mut self, id: &str) -> Result<Option<Memory>> {
+        self.store.load_with(&mut *self.tx, id).await
+    }
+
+    pub async fn update(&mut self, memory: &Memory) -> Result<()> {
+        self.store.update_with(&mut *self.tx, memory).await
+    }
+
+    pub async fn forget(&mut self, id: &str) -> Result<bool> {
+        self.store.forget_with(&mut *self.tx, id).await
+    }
+
+    pub async fn set_importance(&mut self, id: &str, importance: f32) -> Result<bool> {
+        self.store
+            .set_importance_with(&mut *self.tx, id, importance)
+            .await
+    }
+
+    pub async fn create_association(&mut self, association: &Association) -> Result<()> {
+        MemoryStore::upsert_association(&mut *self.tx, association).await
+    }
+
+    pub async fn record_citation(&mut self, answer_id: &str, memory_id: &str) -> Result<bool> {
+        self.store
+            .record_citation_with(&mut *self.tx, answer_id, memory_id)
+            .await
+    }
+
+    pub async fn commit(self) -> Result<()> {
+        Ok(self.tx.commit().await?)
+    }
+
+    pub async fn rollback(self) -> Result<()> {
+        Ok(self.tx.rollback().await?)
+    }
+}
+
+/// A memory together with how often it has been cited
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CitedMemory {
+    /// The cited memory
+    pub memory: Memory,
+    /// Number of distinct answers that cited it
+    pub citation_count: i64,
+    /// When it was last cited
+    pub last_cited_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A page of [`MemoryStore::list_page`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MemoryPage {
+    pub memories: Vec<Memory>,
+    /// Cursor for the following page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Position after the last memory of a page, encoded as `<created_at nanos>:<id>`
+struct PageCursor {
+    created_at: chrono::DateTime<chrono::Utc>,
+    id: String,
+}
+
+impl PageCursor {
+    fn encode(&self) -> String {
+        let nanos = self.created_at.timestamp_nanos_opt().unwrap_or_default();
+        format!("{nanos}:{}", self.id)
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let invalid = || MemoryError::InvalidOperation(format!("invalid page cursor {cursor:?}"));
+        let (nanos, id) = cursor.split_once(':').ok_or_else(invalid)?;
+        let nanos: i64 = nanos.parse().map_err(|_| invalid())?;
+        if id.is_empty() {
+            return Err(invalid());
         }
+        Ok(Self {
+            created_at: chrono::DateTime::from_timestamp_nanos(nanos),
+            id: id.to_string(),
+        })
+    }
+}
 
-        Ok(summaries)
+/// Row counts of one namespace, see [`MemoryStore::namespace_stats`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NamespaceStats {
+    pub namespace: String,
+    /// Non-forgotten memories
+    pub memories: i64,
+    /// Forgotten (soft-deleted) memories
+    pub forgotten: i64,
+    pub experiences: i64,
+    /// Most recent memory update, if the namespace has memories
+    pub last_updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Restricts which memories [`MemoryStore::sample`] draws from
+#[derive(Debug, Clone, Default)]
+pub struct SampleFilter {
+    /// Only this type
+    pub memory_type: Option<MemoryType>,
+    /// Only memories at least this important
+    pub min_importance: Option<f32>,
+    /// Only memories created at or after this time
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Also draw from forgotten memories
+    pub include_forgotten: bool,
+}
+
+impl SampleFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_type = Some(memory_type);
+        self
+    }
+
+    pub fn with_min_importance(mut self, min_importance: f32) -> Self {
+        self.min_importance = Some(min_importance);
+        self
+    }
+
+    pub fn with_created_after(mut self, created_after: chrono::DateTime<chrono::Utc>) -> Self {
+        self.created_after = Some(created_after);
+        self
+    }
+
+    pub fn with_forgotten(mut self, include_forgotten: bool) -> Self {
+        self.include_forgotten = include_forgotten;
+        self
+    }
+}
+
+/// Split `n` sample slots across strata proportionally to their sizes.
+///
+/// Every non-empty stratum gets one slot first (smallest strata first, while slots
+/// remain); the rest is shared by largest remainder. No stratum gets more slots than
+/// it has members.
+fn allocate_strata(counts: &[(MemoryType, usize)], n: usize) -> Vec<(MemoryType, usize)> {
+    let mut strata: Vec<(MemoryType, usize)> =
+        counts.iter().filter(|(_, c)| *c > 0).copied().collect();
+    strata.sort_by_key(|(_, c)| *c);
+
+    let mut slots = vec![0usize; strata.len()];
+    let mut remaining = n;
+    for slot in slots.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        *slot = 1;
+        remaining -= 1;
+    }
+
+    let spare: usize = strata.iter().zip(&slots).map(|((_, c), s)| c - s).sum();
+    if remaining > 0 && spare > 0 {
+        let quota = remaining.min(spare);
+        let mut remainders = Vec::with_capacity(strata.len());
+        let mut given = 0;
+        for (i, ((_, count), slot)) in strata.iter().zip(slots.iter_mut()).enumerate() {
+            let exact = (count - *slot) as f64 * quota as f64 / spare as f64;
+            let whole = exact.floor() as usize;
+            *slot += whole;
+            given += whole;
+            remainders.push((exact - whole as f64, i));
+        }
+        remainders.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        for (_, i) in remainders {
+            if given >= quota {
+                break;
+            }
+            if slots[i] < strata[i].1 {
+                slots[i] += 1;
+                given += 1;
+            }
+        }
     }
+
+    strata
+        .into_iter()
+        .zip(slots)
+        .filter(|(_, s)| *s > 0)
+        .map(|((t, _), s)| (t, s))
+        .collect()
 }
 
 /// Sort order for queries
@@ -800,7 +3875,174 @@ pub enum SortOrder {
     LastAccessed,
 }
 
+impl SortOrder {
+    fn order_clause(self) -> &'static str {
+        match self {
+            SortOrder::Recent => "ORDER BY created_at DESC",
+            SortOrder::Updated => "ORDER BY updated_at DESC",
+            SortOrder::Importance => "ORDER BY importance DESC, updated_at DESC",
+            SortOrder::MostAccessed => "ORDER BY access_count DESC, created_at DESC",
+            SortOrder::LastAccessed => "ORDER BY last_accessed_at DESC",
+        }
+    }
+}
+
+/// Sessions joined with the count and time span of their live memories
+const SESSION_INFO_SELECT: &str = r#"
+    SELECT s.id, s.metadata, s.started_at, s.ended_at, s.summary,
+           COUNT(m.id) AS memory_count,
+           MIN(m.created_at) AS first_memory_at,
+           MAX(m.created_at) AS last_memory_at
+    FROM sessions s
+    LEFT JOIN memories m
+        ON m.session_id = s.id AND m.namespace = s.namespace AND m.forgotten = 0
+"#;
+
+fn row_to_session_info(row: &sqlx::sqlite::SqliteRow) -> Result<SessionInfo> {
+    let metadata: Option<String> = row.try_get("metadata")?;
+    let memory_count: i64 = row.try_get("memory_count")?;
+    Ok(SessionInfo {
+        session: Session {
+            id: row.try_get("id")?,
+            metadata: metadata
+                .map(|m| serde_json::from_str(&m))
+                .transpose()
+                .map_err(|e| MemoryError::Serialization(e.to_string()))?,
+            started_at: row.try_get("started_at")?,
+            ended_at: row.try_get("ended_at")?,
+            summary: row.try_get("summary")?,
+        },
+        memory_count: memory_count.max(0) as u64,
+        first_memory_at: row.try_get("first_memory_at")?,
+        last_memory_at: row.try_get("last_memory_at")?,
+    })
+}
+
 /// Helper: Convert database row to Memory
+fn row_to_saved_search(row: &sqlx::sqlite::SqliteRow) -> Result<SavedSearch> {
+    let config: String = row.try_get("config")?;
+    Ok(SavedSearch {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        query: row.try_get("query")?,
+        config: serde_json::from_str(&config)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?,
+        webhook_url: row.try_get("webhook_url")?,
+        created_at: row.try_get("created_at")?,
+        last_hit_at: row.try_get("last_hit_at")?,
+        hit_count: row.try_get("hit_count")?,
+    })
+}
+
+fn row_to_source(row: &sqlx::sqlite::SqliteRow) -> Result<SourceDocument> {
+    let memory_type: String = row.try_get("memory_type")?;
+    Ok(SourceDocument {
+        uri: row.try_get("uri")?,
+        content_hash: row.try_get("content_hash")?,
+        memory_type: parse_memory_type(&memory_type),
+        created_at: row.try_get("created_at")?,
+        last_synced: row.try_get("last_synced")?,
+    })
+}
+
+fn row_to_query_log_entry(row: &sqlx::sqlite::SqliteRow) -> Result<QueryLogEntry> {
+    let top_ids: String = row.try_get("top_ids")?;
+    let result_count: i64 = row.try_get("result_count")?;
+    Ok(QueryLogEntry {
+        query: row.try_get("query")?,
+        kind: row.try_get("kind")?,
+        config_hash: row.try_get("config_hash")?,
+        latency_ms: row.try_get("latency_ms")?,
+        result_count: result_count.max(0) as usize,
+        top_ids: serde_json::from_str(&top_ids).unwrap_or_default(),
+        timed_out: row.try_get("timed_out")?,
+        logged_at: row.try_get("logged_at")?,
+    })
+}
+
+fn row_to_review(row: &sqlx::sqlite::SqliteRow) -> Result<ReviewItem> {
+    let memory: String = row.try_get("memory")?;
+    let status: String = row.try_get("status")?;
+    Ok(ReviewItem {
+        memory: serde_json::from_str(&memory)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?,
+        status: ReviewStatus::parse(&status).unwrap_or(ReviewStatus::PendingReview),
+        reason: row.try_get("reason")?,
+        submitted_at: row.try_get("submitted_at")?,
+        reviewed_at: row.try_get("reviewed_at")?,
+        reviewer: row.try_get("reviewer")?,
+        note: row.try_get("note")?,
+    })
+}
+
+fn row_to_context_trace(row: &sqlx::sqlite::SqliteRow) -> Result<ContextTrace> {
+    let memories: String = row.try_get("memories")?;
+    let memories: Vec<TracedMemory> =
+        serde_json::from_str(&memories).map_err(|e| MemoryError::Serialization(e.to_string()))?;
+    Ok(ContextTrace {
+        id: row.try_get("id")?,
+        content_hash: row.try_get("content_hash")?,
+        context: row.try_get("context")?,
+        memories,
+        experience_id: row.try_get("experience_id")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn row_to_version(row: &sqlx::sqlite::SqliteRow) -> Result<MemoryVersion> {
+    let snapshot: String = row.try_get("snapshot")?;
+    let author: String = row.try_get("author")?;
+    let version_number: i64 = row.try_get("version_number")?;
+    let previous_version_id: Option<String> = row.try_get("previous_version_id")?;
+    Ok(MemoryVersion {
+        version_id: VersionId(row.try_get("id")?),
+        memory_id: row.try_get("memory_id")?,
+        version_number: version_number as u32,
+        memory: serde_json::from_str(&snapshot)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))?,
+        created_at: row.try_get("created_at")?,
+        author: serde_json::from_str(&author).unwrap_or(VersionAuthor::Unknown),
+        change_reason: row.try_get("change_reason")?,
+        previous_version_id: previous_version_id.map(VersionId),
+        diff: None,
+    })
+}
+
+/// Prefix of generated metadata columns on `memories`
+const METADATA_COLUMN_PREFIX: &str = "meta_";
+
+/// Column for an indexed metadata key; keys are restricted to identifiers
+/// because they end up in DDL and JSON paths
+fn metadata_column(key: &str) -> Result<String> {
+    let valid = !key.is_empty()
+        && key.len() <= 64
+        && key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(format!("{METADATA_COLUMN_PREFIX}{key}"))
+    } else {
+        Err(MemoryError::Validation(format!(
+            "Invalid metadata key '{key}': use 1-64 ASCII letters, digits or '_', not starting with a digit"
+        )))
+    }
+}
+
+/// Bind the values of [`MemoryQuery::to_sql`] in placeholder order
+fn bind_query_values<'q>(
+    mut query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    values: Vec<QueryValue>,
+) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+    for value in values {
+        query = match value {
+            QueryValue::Text(text) => query.bind(text),
+            QueryValue::Integer(n) => query.bind(n),
+            QueryValue::Real(x) => query.bind(x),
+            QueryValue::Time(time) => query.bind(time),
+        };
+    }
+    query
+}
+
 fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
     use crate::confidence::MemoryConfidence;
     use sqlx::Row;
@@ -827,14 +4069,21 @@ fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
         confidence.status = parse_verification_status(&status_str);
     }
 
+    let tags = row
+        .try_get::<Option<String>, _>("tags")
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok())
+        .unwrap_or_default();
+
     Memory {
         id: row.try_get("id").unwrap_or_default(),
         content: row.try_get("content").unwrap_or_default(),
         memory_type,
         importance: row.try_get("importance").unwrap_or(0.5),
         priority: row.try_get("importance").unwrap_or(0.5),
-        emotional_valence: 0.0,
-        tags: Vec::new(),
+        emotional_valence: row.try_get("emotional_valence").unwrap_or(0.0),
+        tags,
         created_at: row
             .try_get("created_at")
             .unwrap_or_else(|_| chrono::Utc::now()),
@@ -845,16 +4094,28 @@ fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
             .try_get("last_accessed_at")
             .unwrap_or_else(|_| chrono::Utc::now()),
         access_count: row.try_get("access_count").unwrap_or(0),
-        source: row.try_get("source").ok(),
-        session_id: row.try_get("session_id").ok(),
+        // Decoded as options: a NULL read as `String` comes back empty
+        source: row.try_get("source").ok().flatten(),
+        session_id: row.try_get("session_id").ok().flatten(),
         forgotten: row.try_get::<bool, _>("forgotten").unwrap_or(false),
+        pinned: row.try_get::<bool, _>("pinned").unwrap_or(false),
+        language: row.try_get("language").ok().flatten(),
         metadata,
         confidence,
     }
 }
 
+/// Helper: Serialize tags for storage (NULL when there are none)
+fn tags_to_json(tags: &[String]) -> Option<String> {
+    if tags.is_empty() {
+        None
+    } else {
+        serde_json::to_string(tags).ok()
+    }
+}
+
 /// Helper: Parse verification status from string
-fn parse_verification_status(s: &str) -> VerificationStatus {
+pub(crate) fn parse_verification_status(s: &str) -> VerificationStatus {
     match s {
         "unverified" => VerificationStatus::Unverified,
         "tentative" => VerificationStatus::Tentative,
@@ -867,7 +4128,7 @@ fn parse_verification_status(s: &str) -> VerificationStatus {
 }
 
 /// Helper: Parse memory type from string
-fn parse_memory_type(s: &str) -> MemoryType {
+pub(crate) fn parse_memory_type(s: &str) -> MemoryType {
     match s {
         "fact" => MemoryType::Fact,
         "preference" => MemoryType::Preference,
@@ -902,7 +4163,7 @@ fn row_to_association(row: &sqlx::sqlite::SqliteRow) -> Association {
 }
 
 /// Helper: Parse relation type from string
-fn parse_relation_type(s: &str) -> RelationType {
+pub(crate) fn parse_relation_type(s: &str) -> RelationType {
     match s {
         "related_to" => RelationType::RelatedTo,
         "updates" => RelationType::Updates,
@@ -913,3 +4174,481 @@ fn parse_relation_type(s: &str) -> RelationType {
         _ => RelationType::RelatedTo,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tagged_memories_are_found_by_every_tag() {
+        let store = MemoryStore::connect_in_memory().await;
+        let mut deploy = Memory::new("Deploys happen on Tuesdays", MemoryType::Fact);
+        deploy.tags = vec!["deploy".to_string(), "ops".to_string()];
+        let mut ops = Memory::new("Pager rotation is weekly", MemoryType::Fact);
+        ops.tags = vec!["ops".to_string()];
+        store.save(&deploy).await.unwrap();
+        store.save(&ops).await.unwrap();
+
+        let found = store.get_by_tag("deploy", 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, deploy.id);
+        assert_eq!(store.get_by_tag("ops", 10).await.unwrap().len(), 2);
+        let both = store.get_by_tags(&["ops", "deploy"], 10).await.unwrap();
+        assert_eq!(both.len(), 1);
+        assert!(store.get_by_tag("missing", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_source_and_session_load_as_none() {
+        let store = MemoryStore::connect_in_memory().await;
+        let bare = Memory::new("No provenance here", MemoryType::Fact);
+        let mut sourced = Memory::new("From the postmortem", MemoryType::Fact);
+        sourced.source = Some("postmortem".to_string());
+        sourced.session_id = Some("session-1".to_string());
+        store.save(&bare).await.unwrap();
+        store.save(&sourced).await.unwrap();
+
+        let bare = store.load(&bare.id).await.unwrap().unwrap();
+        assert_eq!(bare.source, None);
+        assert_eq!(bare.session_id, None);
+        let sourced = store.load(&sourced.id).await.unwrap().unwrap();
+        assert_eq!(sourced.source.as_deref(), Some("postmortem"));
+        assert_eq!(sourced.session_id.as_deref(), Some("session-1"));
+    }
+
+    #[tokio::test]
+    async fn citations_count_once_per_answer_as_usage() {
+        let system = crate::MemorySystem::in_memory().await.unwrap();
+        let memory = Memory::new("Rollbacks need a feature flag", MemoryType::Fact);
+        system.save(&memory).await.unwrap();
+        let before = system.store().load(&memory.id).await.unwrap().unwrap();
+
+        let ids = vec![memory.id.clone(), memory.id.clone(), "missing".to_string()];
+        assert_eq!(system.record_citations("answer-1", &ids).await.unwrap(), 1);
+        assert_eq!(system.record_citations("answer-1", &ids).await.unwrap(), 0);
+        assert_eq!(system.record_citations("answer-2", &ids).await.unwrap(), 1);
+
+        let cited = system.most_cited(10).await.unwrap();
+        assert_eq!(cited.len(), 1);
+        assert_eq!(cited[0].citation_count, 2);
+        let after = system.store().load(&memory.id).await.unwrap().unwrap();
+        assert_eq!(after.access_count, before.access_count + 2);
+        assert!(after.importance > before.importance);
+        assert_eq!(after.confidence, before.confidence);
+    }
+
+    #[tokio::test]
+    async fn failed_transaction_rolls_back_earlier_writes() {
+        let store = MemoryStore::connect_in_memory().await;
+        let goal = Memory::new("Ship the importer", MemoryType::Goal);
+        store.save(&goal).await.unwrap();
+        let step = Memory::new("Wrote the CSV parser", MemoryType::Event);
+
+        let (step_id, goal_id) = (step.id.clone(), goal.id.clone());
+        let result: Result<()> = store
+            .transaction(move |tx| {
+                Box::pin(async move {
+                    tx.save(&step).await?;
+                    tx.create_association(&Association::new(
+                        &step.id,
+                        &goal.id,
+                        RelationType::PartOf,
+                    ))
+                    .await?;
+                    tx.set_importance(&goal.id, 0.2).await?;
+                    assert!(tx.load(&step.id).await?.is_some());
+                    Err(MemoryError::InvalidOperation("step failed".to_string()))
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(MemoryError::InvalidOperation(_))));
+
+        assert!(store.load(&step_id).await.unwrap().is_none());
+        assert!(store.get_associations(&goal_id).await.unwrap().is_empty());
+        let goal = store.load(&goal_id).await.unwrap().unwrap();
+        assert_ne!(goal.importance, 0.2);
+
+        let mut tx = store.begin().await.unwrap();
+        tx.forget(&goal_id).await.unwrap();
+        drop(tx);
+        assert!(!store.load(&goal_id).await.unwrap().unwrap().forgotten);
+    }
+
+    #[tokio::test]
+    async fn metadata_lookups_match_with_and_without_an_index() {
+        let store = MemoryStore::connect_in_memory().await;
+        let ops = Memory::new("Ops owns the pager", MemoryType::Fact).with_metadata(
+            serde_json::json!({"team": "ops", "priority": 2, "urgent": true, "ratio": 0.5}),
+        );
+        let web = Memory::new("Web owns the CDN", MemoryType::Fact).with_metadata(
+            serde_json::json!({"team": "web", "priority": 3, "urgent": false, "ratio": 1.5}),
+        );
+        store.save(&ops).await.unwrap();
+        store.save(&web).await.unwrap();
+
+        for key in ["", "1team", "team.name", "team'; DROP TABLE memories; --"] {
+            assert!(matches!(
+                store.index_metadata_key(key).await,
+                Err(MemoryError::Validation(_))
+            ));
+            assert!(matches!(
+                store.get_by_metadata(key, "ops").await,
+                Err(MemoryError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            store
+                .get_by_metadata("team", serde_json::json!(["ops"]))
+                .await,
+            Err(MemoryError::InvalidOperation(_))
+        ));
+
+        let ids = |found: Vec<Memory>| found.into_iter().map(|m| m.id).collect::<Vec<_>>();
+        for indexed in [false, true] {
+            if indexed {
+                for key in ["team", "priority", "urgent", "ratio"] {
+                    assert!(store.index_metadata_key(key).await.unwrap());
+                }
+                assert!(!store.index_metadata_key("team").await.unwrap());
+                assert_eq!(
+                    store.indexed_metadata_keys().await.unwrap(),
+                    ["team", "priority", "urgent", "ratio"]
+                );
+                let plan: Vec<String> = sqlx::query(
+                    "EXPLAIN QUERY PLAN SELECT id FROM memories WHERE namespace = ? AND meta_team = ?",
+                )
+                .bind(DEFAULT_NAMESPACE)
+                .bind("ops")
+                .fetch_all(store.pool())
+                .await
+                .unwrap()
+                .iter()
+                .map(|row| row.get("detail"))
+                .collect();
+                assert!(plan
+                    .iter()
+                    .any(|step| step.contains("idx_memories_meta_team")));
+            }
+            assert_eq!(
+                ids(store.get_by_metadata("team", "ops").await.unwrap()),
+                [ops.id.as_str()]
+            );
+            assert_eq!(
+                ids(store.get_by_metadata("priority", 3).await.unwrap()),
+                [web.id.as_str()]
+            );
+            assert_eq!(
+                ids(store.get_by_metadata("urgent", true).await.unwrap()),
+                [ops.id.as_str()]
+            );
+            assert_eq!(
+                ids(store.get_by_metadata("urgent", false).await.unwrap()),
+                [web.id.as_str()]
+            );
+            assert_eq!(
+                ids(store.get_by_metadata("ratio", 0.5).await.unwrap()),
+                [ops.id.as_str()]
+            );
+            assert!(store
+                .get_by_metadata("team", "db")
+                .await
+                .unwrap()
+                .is_empty());
+            assert!(store
+                .get_by_metadata("missing", "ops")
+                .await
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn list_page_breaks_created_at_ties_by_id_and_ends_without_a_cursor() {
+        let store = MemoryStore::connect_in_memory().await;
+        let created_at = chrono::Utc::now();
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let mut memory = Memory::new(format!("Standup note {i}"), MemoryType::Event);
+            memory.created_at = created_at;
+            store.save(&memory).await.unwrap();
+            ids.push(memory.id);
+        }
+        ids.sort_by(|a, b| b.cmp(a));
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let page = store.list_page(cursor.as_deref(), 2).await.unwrap();
+            pages += 1;
+            seen.extend(page.memories.into_iter().map(|m| m.id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(seen, ids);
+
+        // A page that exactly fits the rest has no next cursor either
+        let page = store.list_page(None, 5).await.unwrap();
+        assert_eq!(page.memories.len(), 5);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn list_page_rejects_invalid_cursors_and_survives_stale_ones() {
+        let store = MemoryStore::connect_in_memory().await;
+        let start = chrono::Utc::now();
+        let mut ids = Vec::new();
+        for i in 0..4 {
+            let mut memory = Memory::new(format!("Release note {i}"), MemoryType::Fact);
+            memory.created_at = start + chrono::Duration::seconds(i);
+            store.save(&memory).await.unwrap();
+            ids.push(memory.id);
+        }
+
+        for cursor in ["", "garbage", "12:", "soon:abc"] {
+            assert!(matches!(
+                store.list_page(Some(cursor), 2).await,
+                Err(MemoryError::InvalidOperation(_))
+            ));
+        }
+
+        // The memory a cursor points at may be gone by the next request
+        let first = store.list_page(None, 2).await.unwrap();
+        assert_eq!(first.memories[1].id, ids[2]);
+        store.delete(&ids[2]).await.unwrap();
+        let second = store
+            .list_page(first.next_cursor.as_deref(), 2)
+            .await
+            .unwrap();
+        let second: Vec<_> = second.memories.into_iter().map(|m| m.id).collect();
+        assert_eq!(second, [ids[1].clone(), ids[0].clone()]);
+    }
+
+    #[tokio::test]
+    async fn pins_persist_and_keep_memories_out_of_pruning() {
+        let store = MemoryStore::connect_in_memory().await;
+        let old = chrono::Utc::now() - chrono::Duration::days(60);
+        let mut ids = Vec::new();
+        for content in ["Office plants need water", "Never deploy on Friday"] {
+            let mut memory = Memory::new(content, MemoryType::Fact);
+            memory.importance = 0.05;
+            memory.created_at = old;
+            store.save(&memory).await.unwrap();
+            ids.push(memory.id);
+        }
+        let (plain, pinned) = (&ids[0], &ids[1]);
+
+        assert!(store.set_pinned(pinned, true).await.unwrap());
+        assert!(!store.set_pinned("missing", true).await.unwrap());
+        assert!(store.load(pinned).await.unwrap().unwrap().pinned);
+
+        // A full update keeps the flag it was loaded with
+        let mut edited = store.load(pinned).await.unwrap().unwrap();
+        edited.content = "Never deploy on Friday afternoon".to_string();
+        store.update(&edited).await.unwrap();
+        let found: Vec<_> = store
+            .get_pinned(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(&found, std::slice::from_ref(pinned));
+
+        let prunable: Vec<_> = store
+            .get_pruning_candidates(0.1, 30)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(&prunable, std::slice::from_ref(plain));
+
+        store.forget(pinned).await.unwrap();
+        assert!(store.get_pinned(10).await.unwrap().is_empty());
+        store.set_pinned(plain, true).await.unwrap();
+        store.set_pinned(plain, false).await.unwrap();
+        assert!(!store.load(plain).await.unwrap().unwrap().pinned);
+    }
+
+    #[test]
+    fn strata_give_rare_types_a_slot_and_split_the_rest_proportionally() {
+        let counts = [
+            (MemoryType::Fact, 90),
+            (MemoryType::Goal, 9),
+            (MemoryType::Identity, 1),
+            (MemoryType::Event, 0),
+        ];
+        let slots = allocate_strata(&counts, 10);
+        assert_eq!(
+            slots,
+            [
+                (MemoryType::Identity, 1),
+                (MemoryType::Goal, 2),
+                (MemoryType::Fact, 7)
+            ]
+        );
+        assert_eq!(slots.iter().map(|(_, s)| s).sum::<usize>(), 10);
+
+        // Fewer slots than strata: the smallest types go first
+        assert_eq!(
+            allocate_strata(&counts, 2),
+            [(MemoryType::Identity, 1), (MemoryType::Goal, 1)]
+        );
+        // Never more slots than members
+        let slots = allocate_strata(&counts, 500);
+        assert_eq!(slots.iter().map(|(_, s)| s).sum::<usize>(), 100);
+    }
+
+    #[tokio::test]
+    async fn samples_respect_filters_and_cover_every_type() {
+        let store = MemoryStore::connect_in_memory().await;
+        for i in 0..12 {
+            let mut memory = Memory::new(format!("Build {i} passed"), MemoryType::Event);
+            memory.importance = if i % 2 == 0 { 0.9 } else { 0.1 };
+            store.save(&memory).await.unwrap();
+        }
+        let mut goal = Memory::new("Ship the mobile app", MemoryType::Goal);
+        goal.importance = 0.3;
+        store.save(&goal).await.unwrap();
+        let forgotten = Memory::new("Old team name", MemoryType::Identity);
+        store.save(&forgotten).await.unwrap();
+        store.forget(&forgotten.id).await.unwrap();
+
+        let important = SampleFilter::new().with_min_importance(0.8);
+        let sample = store.sample(100, &important).await.unwrap();
+        assert_eq!(sample.len(), 6);
+        assert!(sample.iter().all(|m| m.importance >= 0.8));
+        assert_eq!(
+            store.sample(3, &SampleFilter::new()).await.unwrap().len(),
+            3
+        );
+
+        let stratified = store
+            .sample_stratified(4, &SampleFilter::new())
+            .await
+            .unwrap();
+        assert_eq!(stratified.len(), 4);
+        assert!(stratified.iter().any(|m| m.id == goal.id));
+        assert!(stratified.iter().all(|m| !m.forgotten));
+
+        let with_forgotten = store
+            .sample_stratified(20, &SampleFilter::new().with_forgotten(true))
+            .await
+            .unwrap();
+        assert_eq!(with_forgotten.len(), 14);
+        assert!(with_forgotten.iter().any(|m| m.id == forgotten.id));
+    }
+
+    #[tokio::test]
+    async fn tuning_sets_the_pragmas_and_pool_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let tuning = SqliteTuning::default()
+            .with_journal_mode(SqliteJournalMode::Wal)
+            .with_synchronous(SqliteSynchronous::Normal)
+            .with_busy_timeout(Duration::from_millis(2500))
+            .with_max_connections(0);
+        assert_eq!(tuning.max_connections, 1);
+
+        let pool = tuning
+            .connect(dir.path().join("memories.db"))
+            .await
+            .unwrap();
+        let pragma = |name: &'static str| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query(&format!("PRAGMA {name}"))
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(pragma("journal_mode").await.get::<String, _>(0), "wal");
+        // NORMAL is 1
+        assert_eq!(pragma("synchronous").await.get::<i64, _>(0), 1);
+        assert_eq!(pragma("busy_timeout").await.get::<i64, _>(0), 2500);
+        assert_eq!(pool.options().get_max_connections(), 1);
+        assert!(dir.path().join("memories.db").exists());
+    }
+
+    #[tokio::test]
+    async fn counts_and_existence_stay_in_the_namespace() {
+        let store = MemoryStore::connect_in_memory().await;
+        let other = store.scoped("tenant-b");
+        let fact = Memory::new("Deploys happen on Tuesday", MemoryType::Fact);
+        let goal = Memory::new("Ship the v2 API", MemoryType::Goal);
+        let stale = Memory::new("Deploys happen on Monday", MemoryType::Fact);
+        for memory in [&fact, &goal, &stale] {
+            store.save(memory).await.unwrap();
+        }
+        other
+            .save(&Memory::new("Tenant B fact", MemoryType::Fact))
+            .await
+            .unwrap();
+        store
+            .create_association(&Association::new(
+                &fact.id,
+                &goal.id,
+                RelationType::RelatedTo,
+            ))
+            .await
+            .unwrap();
+        store.forget(&stale.id).await.unwrap();
+
+        assert_eq!(store.count_all().await.unwrap(), 2);
+        assert_eq!(store.count_by_type(MemoryType::Fact).await.unwrap(), 1);
+        assert_eq!(store.count_by_type(MemoryType::Event).await.unwrap(), 0);
+        assert_eq!(store.count_associations().await.unwrap(), 1);
+        assert_eq!(other.count_all().await.unwrap(), 1);
+        assert_eq!(other.count_associations().await.unwrap(), 0);
+
+        // Forgotten memories still exist; other namespaces' memories do not
+        assert!(store.exists(&stale.id).await.unwrap());
+        assert!(!other.exists(&fact.id).await.unwrap());
+        assert!(!store.exists("missing").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn associations_are_created_in_bulk_and_deleted_per_memory() {
+        let store = MemoryStore::connect_in_memory().await;
+        let hub = Memory::new("Billing service", MemoryType::Fact);
+        let spokes: Vec<Memory> = (0..3)
+            .map(|i| Memory::new(format!("Billing incident {i}"), MemoryType::Event))
+            .collect();
+        store.save(&hub).await.unwrap();
+        for spoke in &spokes {
+            store.save(spoke).await.unwrap();
+        }
+        let mut links: Vec<Association> = spokes
+            .iter()
+            .map(|s| Association::new(&s.id, &hub.id, RelationType::RelatedTo))
+            .collect();
+        links.push(Association::new(
+            &spokes[0].id,
+            &spokes[1].id,
+            RelationType::RelatedTo,
+        ));
+        store.create_associations(&links).await.unwrap();
+        // Re-sending a link updates it instead of adding another
+        store
+            .create_associations(&[links[0].clone().with_weight(0.9)])
+            .await
+            .unwrap();
+        assert_eq!(store.count_associations().await.unwrap(), 4);
+
+        // Memories of other namespaces keep their links
+        assert_eq!(
+            store
+                .scoped("tenant-b")
+                .delete_associations_for(&hub.id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(store.delete_associations_for(&hub.id).await.unwrap(), 3);
+        assert_eq!(store.count_associations().await.unwrap(), 1);
+    }
+}
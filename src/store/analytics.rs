@@ -0,0 +1,284 @@
+//! SQL-side aggregate statistics
+//!
+//! [`MemoryStore::aggregate_stats`] counts memories per type and per day,
+//! averages confidence and importance, and buckets access counts without
+//! loading a single memory, so it stays cheap on large stores.
+//!
+//! For heavier analysis, [`MemoryStore::export_analytics`] writes one flat JSON
+//! object per memory that DuckDB (or anything else that reads JSON lines)
+//! can query directly:
+//!
+//! ```sql
+//! SELECT memory_type, date_trunc('week', created_at) AS week, count(*)
+//! FROM read_json_auto('memories.jsonl')
+//! GROUP BY ALL ORDER BY week;
+//! ```
+
+use super::{parse_memory_type, MemoryStore};
+use crate::error::{MemoryError, Result};
+use crate::types::MemoryType;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+
+/// Lower bounds of the access-count histogram buckets
+const ACCESS_BUCKETS: [i64; 6] = [0, 1, 2, 5, 10, 100];
+
+/// Aggregate statistics of a store's namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreAnalytics {
+    /// Memories, forgotten ones included
+    pub total: usize,
+    /// Memories not forgotten
+    pub active: usize,
+    pub forgotten: usize,
+    pub pinned: usize,
+    /// Over active memories; 0 when there are none
+    pub avg_confidence: f32,
+    pub avg_importance: f32,
+    /// Active memories per type, most common first
+    pub by_type: Vec<TypeStats>,
+    /// Active memories created per UTC day since the requested date, oldest first
+    pub per_day: Vec<DayCount>,
+    /// Active memories by how often they were accessed
+    pub access_histogram: Vec<AccessBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeStats {
+    pub memory_type: MemoryType,
+    pub count: usize,
+    pub avg_confidence: f32,
+    pub avg_importance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayCount {
+    pub day: NaiveDate,
+    pub count: usize,
+}
+
+/// Memories accessed between `min_accesses` and `max_accesses` times (inclusive)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessBucket {
+    pub min_accesses: i64,
+    /// `None` for the open-ended last bucket
+    pub max_accesses: Option<i64>,
+    pub count: usize,
+}
+
+/// One memory as written by [`MemoryStore::export_analytics`]
+#[derive(Debug, Serialize)]
+struct AnalyticsRow {
+    id: String,
+    memory_type: String,
+    importance: f32,
+    confidence: f32,
+    access_count: i64,
+    pinned: bool,
+    forgotten: bool,
+    emotional_valence: f32,
+    tags: Vec<String>,
+    content_bytes: i64,
+    created_at: DateTime<Utc>,
+    last_accessed_at: DateTime<Utc>,
+}
+
+impl MemoryStore {
+    /// Aggregate statistics computed in SQL, with daily counts from `since` on
+    pub async fn aggregate_stats(&self, since: DateTime<Utc>) -> Result<StoreAnalytics> {
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS total,
+                   COALESCE(SUM(forgotten = 0), 0) AS active,
+                   COALESCE(SUM(pinned = 1 AND forgotten = 0), 0) AS pinned,
+                   AVG(CASE WHEN forgotten = 0 THEN confidence_score END) AS avg_confidence,
+                   AVG(CASE WHEN forgotten = 0 THEN importance END) AS avg_importance
+            FROM memories
+            WHERE namespace = ?
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+        let total: i64 = totals.get("total");
+        let active: i64 = totals.get("active");
+
+        let by_type = sqlx::query(
+            r#"
+            SELECT memory_type,
+                   COUNT(*) AS n,
+                   AVG(confidence_score) AS avg_confidence,
+                   AVG(importance) AS avg_importance
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0
+            GROUP BY memory_type
+            ORDER BY n DESC, memory_type ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| TypeStats {
+            memory_type: parse_memory_type(&row.get::<String, _>("memory_type")),
+            count: row.get::<i64, _>("n") as usize,
+            avg_confidence: average(row, "avg_confidence"),
+            avg_importance: average(row, "avg_importance"),
+        })
+        .collect();
+
+        let per_day = sqlx::query(
+            r#"
+            SELECT date(created_at) AS day, COUNT(*) AS n
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0 AND created_at >= ?
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .filter_map(|row| {
+            let day: String = row.get("day");
+            Some(DayCount {
+                day: NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok()?,
+                count: row.get::<i64, _>("n") as usize,
+            })
+        })
+        .collect();
+
+        let access_rows = sqlx::query(
+            r#"
+            SELECT CASE
+                       WHEN access_count >= 100 THEN 100
+                       WHEN access_count >= 10 THEN 10
+                       WHEN access_count >= 5 THEN 5
+                       WHEN access_count >= 2 THEN 2
+                       WHEN access_count >= 1 THEN 1
+                       ELSE 0
+                   END AS bucket,
+                   COUNT(*) AS n
+            FROM memories
+            WHERE namespace = ? AND forgotten = 0
+            GROUP BY bucket
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+        let access_histogram = ACCESS_BUCKETS
+            .iter()
+            .enumerate()
+            .map(|(i, &min)| AccessBucket {
+                min_accesses: min,
+                max_accesses: ACCESS_BUCKETS.get(i + 1).map(|next| next - 1),
+                count: access_rows
+                    .iter()
+                    .find(|row| row.get::<i64, _>("bucket") == min)
+                    .map_or(0, |row| row.get::<i64, _>("n") as usize),
+            })
+            .collect();
+
+        Ok(StoreAnalytics {
+            total: total as usize,
+            active: active as usize,
+            forgotten: (total - active) as usize,
+            pinned: totals.get::<i64, _>("pinned") as usize,
+            avg_confidence: average(&totals, "avg_confidence"),
+            avg_importance: average(&totals, "avg_importance"),
+            by_type,
+            per_day,
+            access_histogram,
+        })
+    }
+
+    /// Write every memory of the namespace as a flat JSON line (no content,
+    /// only its size) for DuckDB's `read_json_auto`. Returns the row count.
+    pub async fn export_analytics(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, memory_type, importance, confidence_score, access_count, pinned,
+                   forgotten, emotional_valence, tags, length(CAST(content AS BLOB)) AS bytes,
+                   created_at, last_accessed_at
+            FROM memories
+            WHERE namespace = ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut out = tokio::io::BufWriter::new(tokio::fs::File::create(path).await?);
+        for row in &rows {
+            let tags: Option<String> = row.try_get("tags")?;
+            let record = AnalyticsRow {
+                id: row.try_get("id")?,
+                memory_type: row.try_get("memory_type")?,
+                importance: row.try_get("importance")?,
+                confidence: row.try_get("confidence_score").unwrap_or(0.5),
+                access_count: row.try_get("access_count")?,
+                pinned: row.try_get("pinned")?,
+                forgotten: row.try_get("forgotten")?,
+                emotional_valence: row.try_get("emotional_valence")?,
+                tags: tags
+                    .and_then(|t| serde_json::from_str(&t).ok())
+                    .unwrap_or_default(),
+                content_bytes: row.try_get("bytes")?,
+                created_at: row.try_get("created_at")?,
+                last_accessed_at: row.try_get("last_accessed_at")?,
+            };
+            let mut line = serde_json::to_vec(&record)
+                .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+            line.push(b'\n');
+            out.write_all(&line).await?;
+        }
+        out.flush().await?;
+        Ok(rows.len())
+    }
+}
+
+/// An `AVG(...)` column, which is NULL over no rows
+fn average(row: &sqlx::sqlite::SqliteRow, column: &str) -> f32 {
+    row.try_get::<Option<f64>, _>(column)
+        .ok()
+        .flatten()
+        .unwrap_or(0.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Memory;
+
+    #[tokio::test]
+    async fn aggregates_without_loading_memories() {
+        let store = MemoryStore::connect_in_memory().await;
+        let mut fact = Memory::new("Deploys happen on Tuesdays", MemoryType::Fact);
+        fact.access_count = 3;
+        store.save(&fact).await.unwrap();
+        store
+            .save(&Memory::new("Prefers dark mode", MemoryType::Preference))
+            .await
+            .unwrap();
+        let gone = Memory::new("Old office address", MemoryType::Fact);
+        store.save(&gone).await.unwrap();
+        store.forget(&gone.id).await.unwrap();
+
+        let stats = store
+            .aggregate_stats(Utc::now() - chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!((stats.total, stats.active, stats.forgotten), (3, 2, 1));
+        assert_eq!(stats.by_type.len(), 2);
+        assert_eq!(stats.per_day.iter().map(|d| d.count).sum::<usize>(), 2);
+        let accessed: Vec<_> = stats.access_histogram.iter().map(|b| b.count).collect();
+        assert_eq!(accessed, vec![1, 0, 1, 0, 0, 0]);
+    }
+}
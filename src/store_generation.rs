@@ -0,0 +1,136 @@
+//! Store generations for cheap change detection
+//!
+//! Every database carries a [`StoreGeneration`] that triggers bump on each
+//! mutation of memories, associations, episodes and summaries. Recording an
+//! access does not count: it is a side effect of reading.
+//!
+//! A client that remembers the generation of its last read can skip the read
+//! entirely while the generation is unchanged. The server exposes it as an
+//! `ETag`, answering `If-None-Match` with `304 Not Modified`:
+//!
+//! ```rust,ignore
+//! let found = cortex.recall_with_generation("deploy window", 10).await?;
+//! cache.insert(found.generation, found.value);
+//! // later
+//! if cortex.generation().await? == cached_generation { /* reuse the cache */ }
+//! ```
+//!
+//! The generation is per database, not per namespace: a write in any
+//! namespace of a shared database bumps it for all of them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Monotonically increasing count of mutations to a store
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct StoreGeneration(pub u64);
+
+impl StoreGeneration {
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Strong entity tag for HTTP responses, e.g. `"g42"`
+    pub fn etag(self) -> String {
+        format!("\"g{}\"", self.0)
+    }
+
+    /// Whether an `If-None-Match` header value names this generation.
+    ///
+    /// Accepts `*`, comma-separated lists and weak (`W/`) tags.
+    pub fn matches_if_none_match(self, header: &str) -> bool {
+        let etag = self.etag();
+        header
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+    }
+}
+
+impl fmt::Display for StoreGeneration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::ops::Add for StoreGeneration {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+/// A read result with the generation the store was at before the read.
+///
+/// The generation is taken first, so a write racing the read makes the
+/// result look older than it is, never newer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Generational<T> {
+    pub generation: StoreGeneration,
+    pub value: T,
+}
+
+impl<T> Generational<T> {
+    pub fn new(generation: StoreGeneration, value: T) -> Self {
+        Self { generation, value }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Generational<U> {
+        Generational {
+            generation: self.generation,
+            value: f(self.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use crate::types::{Association, MemoryType, RelationType};
+
+    #[test]
+    fn if_none_match_accepts_lists_weak_tags_and_wildcard() {
+        let generation = StoreGeneration(7);
+        assert_eq!(generation.etag(), "\"g7\"");
+        assert!(generation.matches_if_none_match("\"g7\""));
+        assert!(generation.matches_if_none_match("\"g3\", W/\"g7\""));
+        assert!(generation.matches_if_none_match("*"));
+        assert!(!generation.matches_if_none_match("\"g70\""));
+    }
+
+    #[tokio::test]
+    async fn mutations_bump_the_generation_but_accesses_do_not() {
+        let store = MemoryStore::connect_in_memory().await;
+        let start = store.generation().await.unwrap();
+
+        let memory = store.new_memory("Deploys happen on Tuesdays", MemoryType::Fact);
+        store.save(&memory).await.unwrap();
+        let saved = store.generation().await.unwrap();
+        assert!(saved > start);
+
+        store.record_access(&memory.id).await.unwrap();
+        store.load(&memory.id).await.unwrap();
+        assert_eq!(store.generation().await.unwrap(), saved);
+
+        let other = store.new_memory("Release checklist", MemoryType::Fact);
+        store.save(&other).await.unwrap();
+        store
+            .create_association(&Association::new(
+                &memory.id,
+                &other.id,
+                RelationType::RelatedTo,
+            ))
+            .await
+            .unwrap();
+        let linked = store.generation().await.unwrap();
+        assert!(linked > saved);
+
+        store.forget(&memory.id).await.unwrap();
+        assert!(store.generation().await.unwrap() > linked);
+    }
+}
@@ -0,0 +1,441 @@
+//! Automatic tagging on save
+//!
+//! [`AutoTagger`] is a [`MemoryMiddleware`] that fills in `Memory::tags` with:
+//! - keywords extracted by RAKE or TF-IDF (backed by [`CorpusStats`]), and
+//! - topic tags from a configurable taxonomy, assigned by embedding similarity.
+//!
+//! Register it with `MemorySystem::with_middleware` or `MemoryCortex::with_middleware`.
+
+use crate::embedding::EmbeddingProvider;
+use crate::error::Result;
+use crate::middleware::{MemoryMiddleware, SaveDecision};
+use crate::types::Memory;
+use crate::vector_search::{cosine_similarity, is_stopword};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// Words that carry no topical meaning on top of the embedding stopwords
+fn is_keyword_stopword(token: &str) -> bool {
+    is_stopword(token)
+        || matches!(
+            token,
+            "i" | "me"
+                | "my"
+                | "we"
+                | "our"
+                | "you"
+                | "your"
+                | "he"
+                | "she"
+                | "they"
+                | "them"
+                | "their"
+                | "his"
+                | "her"
+                | "its"
+                | "have"
+                | "has"
+                | "had"
+                | "will"
+                | "would"
+                | "can"
+                | "could"
+                | "should"
+                | "not"
+                | "no"
+                | "so"
+                | "if"
+                | "then"
+                | "than"
+                | "from"
+                | "about"
+                | "into"
+                | "there"
+                | "what"
+                | "which"
+                | "who"
+                | "when"
+                | "where"
+                | "how"
+                | "all"
+                | "also"
+                | "just"
+                | "very"
+                | "some"
+                | "any"
+                | "more"
+                | "most"
+        )
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|t| t.trim_matches('-').to_lowercase())
+        .filter(|t| t.len() >= 2)
+        .collect()
+}
+
+/// Document frequencies over the memories seen so far, used for TF-IDF weighting
+#[derive(Debug, Clone, Default)]
+pub struct CorpusStats {
+    doc_count: usize,
+    doc_freq: HashMap<String, usize>,
+}
+
+impl CorpusStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build stats from an existing set of documents
+    pub fn from_documents<'a>(docs: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut stats = Self::new();
+        for doc in docs {
+            stats.add_document(doc);
+        }
+        stats
+    }
+
+    /// Count each distinct term of `text` once
+    pub fn add_document(&mut self, text: &str) {
+        self.doc_count += 1;
+        let unique: HashSet<String> = tokenize(text).into_iter().collect();
+        for term in unique {
+            *self.doc_freq.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.doc_count
+    }
+
+    pub fn doc_freq(&self, term: &str) -> usize {
+        self.doc_freq.get(term).copied().unwrap_or(0)
+    }
+
+    /// Smoothed inverse document frequency
+    pub fn idf(&self, term: &str) -> f32 {
+        let n = self.doc_count as f32;
+        let df = self.doc_freq(term) as f32;
+        ((1.0 + n) / (1.0 + df)).ln() + 1.0
+    }
+}
+
+/// How keywords are extracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeywordMethod {
+    /// Rapid Automatic Keyword Extraction: phrases split on stopwords, scored by degree/frequency
+    #[default]
+    Rake,
+    /// Single terms scored by term frequency × inverse document frequency
+    TfIdf,
+}
+
+/// Extract up to `max` keywords from `text`, highest scoring first
+pub fn extract_keywords(
+    text: &str,
+    method: KeywordMethod,
+    stats: &CorpusStats,
+    max: usize,
+) -> Vec<String> {
+    let mut scored = match method {
+        KeywordMethod::Rake => rake_scores(text),
+        KeywordMethod::TfIdf => tfidf_scores(text, stats),
+    };
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    scored.into_iter().take(max).map(|(k, _)| k).collect()
+}
+
+fn tfidf_scores(text: &str, stats: &CorpusStats) -> Vec<(String, f32)> {
+    let tokens: Vec<String> = tokenize(text)
+        .into_iter()
+        .filter(|t| !is_keyword_stopword(t) && !t.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    let total = tokens.len().max(1) as f32;
+
+    let mut tf: HashMap<String, f32> = HashMap::new();
+    for t in tokens {
+        *tf.entry(t).or_insert(0.0) += 1.0;
+    }
+
+    tf.into_iter()
+        .map(|(term, count)| {
+            let score = (count / total) * stats.idf(&term);
+            (term, score)
+        })
+        .collect()
+}
+
+fn rake_scores(text: &str) -> Vec<(String, f32)> {
+    // Candidate phrases are maximal runs of non-stopwords within a sentence fragment.
+    let mut phrases: Vec<Vec<String>> = Vec::new();
+    for fragment in text.split(['.', ',', ';', ':', '!', '?', '\n']) {
+        let mut current = Vec::new();
+        for token in tokenize(fragment) {
+            if is_keyword_stopword(&token) || token.chars().all(|c| c.is_ascii_digit()) {
+                if !current.is_empty() {
+                    phrases.push(std::mem::take(&mut current));
+                }
+            } else {
+                current.push(token);
+            }
+        }
+        if !current.is_empty() {
+            phrases.push(current);
+        }
+    }
+
+    let mut freq: HashMap<&str, f32> = HashMap::new();
+    let mut degree: HashMap<&str, f32> = HashMap::new();
+    for phrase in &phrases {
+        // Long phrases are rarely useful tags; cap the degree contribution.
+        let len = phrase.len().min(3) as f32;
+        for word in phrase {
+            *freq.entry(word.as_str()).or_insert(0.0) += 1.0;
+            *degree.entry(word.as_str()).or_insert(0.0) += len;
+        }
+    }
+
+    let mut scores: HashMap<String, f32> = HashMap::new();
+    for phrase in phrases.iter().filter(|p| p.len() <= 3) {
+        let score: f32 = phrase
+            .iter()
+            .map(|w| degree[w.as_str()] / freq[w.as_str()])
+            .sum();
+        let key = phrase.join(" ");
+        let entry = scores.entry(key).or_insert(0.0);
+        *entry = entry.max(score);
+    }
+    scores.into_iter().collect()
+}
+
+/// Normalize a keyword or topic name into a tag (`"Machine Learning"` -> `"machine-learning"`)
+pub fn normalize_tag(raw: &str) -> String {
+    raw.split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A topic in the tagging taxonomy
+#[derive(Debug, Clone)]
+pub struct Topic {
+    /// Tag applied when the topic matches
+    pub tag: String,
+    /// Description or example text the memory content is compared against
+    pub description: String,
+}
+
+impl Topic {
+    pub fn new(tag: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Configuration for [`AutoTagger`]
+#[derive(Debug, Clone)]
+pub struct AutoTagConfig {
+    /// Keyword extraction method
+    pub method: KeywordMethod,
+    /// Maximum keyword tags per memory
+    pub max_keywords: usize,
+    /// Maximum topic tags per memory
+    pub max_topics: usize,
+    /// Minimum cosine similarity for a topic to be assigned
+    pub topic_threshold: f32,
+    /// Keep tags the caller already set
+    pub keep_existing: bool,
+}
+
+impl Default for AutoTagConfig {
+    fn default() -> Self {
+        Self {
+            method: KeywordMethod::Rake,
+            max_keywords: 5,
+            max_topics: 2,
+            topic_threshold: 0.35,
+            keep_existing: true,
+        }
+    }
+}
+
+/// Middleware that tags memories on save
+pub struct AutoTagger {
+    config: AutoTagConfig,
+    stats: RwLock<CorpusStats>,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+    taxonomy: Vec<Topic>,
+    topic_vectors: tokio::sync::OnceCell<Vec<Vec<f32>>>,
+}
+
+impl Default for AutoTagger {
+    fn default() -> Self {
+        Self::new(AutoTagConfig::default())
+    }
+}
+
+impl AutoTagger {
+    pub fn new(config: AutoTagConfig) -> Self {
+        Self {
+            config,
+            stats: RwLock::new(CorpusStats::new()),
+            embedder: None,
+            taxonomy: Vec::new(),
+            topic_vectors: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// Seed the corpus statistics (e.g. from existing memories)
+    pub fn with_corpus_stats(mut self, stats: CorpusStats) -> Self {
+        self.stats = RwLock::new(stats);
+        self
+    }
+
+    /// Enable topic tagging against a taxonomy
+    pub fn with_taxonomy(
+        mut self,
+        embedder: Arc<dyn EmbeddingProvider>,
+        taxonomy: Vec<Topic>,
+    ) -> Self {
+        self.embedder = Some(embedder);
+        self.taxonomy = taxonomy;
+        self.topic_vectors = tokio::sync::OnceCell::new();
+        self
+    }
+
+    /// Snapshot of the corpus statistics gathered so far
+    pub fn corpus_stats(&self) -> CorpusStats {
+        self.stats.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Compute tags for a piece of content (keywords first, then topics)
+    pub async fn tags_for(&self, content: &str) -> Result<Vec<String>> {
+        let keywords = {
+            let stats = self.stats.read().unwrap_or_else(|e| e.into_inner());
+            extract_keywords(
+                content,
+                self.config.method,
+                &stats,
+                self.config.max_keywords,
+            )
+        };
+        let mut tags: Vec<String> = keywords.into_iter().map(|k| normalize_tag(&k)).collect();
+        tags.extend(self.topics_for(content).await?);
+        Ok(tags)
+    }
+
+    async fn topics_for(&self, content: &str) -> Result<Vec<String>> {
+        let Some(embedder) = &self.embedder else {
+            return Ok(Vec::new());
+        };
+        if self.taxonomy.is_empty() || self.config.max_topics == 0 {
+            return Ok(Vec::new());
+        }
+
+        let topic_vectors = self
+            .topic_vectors
+            .get_or_try_init(|| async {
+                let texts: Vec<String> = self
+                    .taxonomy
+                    .iter()
+                    .map(|t| format!("{} {}", t.tag, t.description))
+                    .collect();
+                embedder.embed(&texts).await
+            })
+            .await?;
+
+        let query = embedder.embed(&[content.to_string()]).await?;
+        let Some(query) = query.first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut scored: Vec<(&Topic, f32)> = self
+            .taxonomy
+            .iter()
+            .zip(topic_vectors)
+            .map(|(topic, v)| (topic, cosine_similarity(query, v)))
+            .filter(|(_, sim)| *sim >= self.config.topic_threshold)
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(scored
+            .into_iter()
+            .take(self.config.max_topics)
+            .map(|(topic, _)| normalize_tag(&topic.tag))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl MemoryMiddleware for AutoTagger {
+    fn name(&self) -> &'static str {
+        "auto_tagger"
+    }
+
+    async fn on_before_save(&self, memory: &mut Memory) -> Result<SaveDecision> {
+        let tags = self.tags_for(&memory.content).await?;
+
+        if !self.config.keep_existing {
+            memory.tags.clear();
+        }
+        for tag in tags {
+            if !memory.tags.contains(&tag) {
+                memory.tags.push(tag);
+            }
+        }
+
+        Ok(SaveDecision::Continue)
+    }
+
+    async fn on_after_save(&self, memory: &Memory) -> Result<()> {
+        self.stats
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .add_document(&memory.content);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MemoryType;
+
+    #[test]
+    fn rake_prefers_multiword_phrases() {
+        let keywords = extract_keywords(
+            "The user is migrating the billing service to Postgres. Billing service owners agreed.",
+            KeywordMethod::Rake,
+            &CorpusStats::new(),
+            3,
+        );
+        assert!(keywords.iter().any(|k| k == "billing service"));
+    }
+
+    #[test]
+    fn tfidf_downweights_common_terms() {
+        let stats = CorpusStats::from_documents([
+            "project update meeting",
+            "project planning",
+            "project retro",
+        ]);
+        let keywords = extract_keywords("project kubernetes", KeywordMethod::TfIdf, &stats, 1);
+        assert_eq!(keywords, vec!["kubernetes".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn tagger_adds_keyword_tags() {
+        let tagger = AutoTagger::default();
+        let mut memory = Memory::new("Deploying the search cluster on Friday", MemoryType::Event);
+        tagger.on_before_save(&mut memory).await.unwrap();
+        assert!(memory.tags.iter().any(|t| t.contains("search-cluster")));
+    }
+}
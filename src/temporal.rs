@@ -6,9 +6,30 @@
 //! - "Memories from the past week"
 //!
 //! Based on research in episodic memory and temporal cognition.
+//!
+//! [`RecurrenceDetector`] finds events that keep happening on a schedule
+//! ("standup every weekday at 09:30") and describes them as a
+//! [`RecurringPattern`] with an RRULE-like [`Recurrence`], whose
+//! [`next_occurrences`](Recurrence::next_occurrences) an agent can plan with.
 
-use chrono::{DateTime, Datelike, Duration, Utc};
+use crate::types::{Memory, MemoryId, MemoryType};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Metadata key a pattern memory keeps its [`RecurringPattern`] under
+pub const RECURRENCE_METADATA_KEY: &str = "recurrence";
+
+/// Tag of memories recording a recurring pattern
+pub const RECURRING_TAG: &str = "recurring";
+
+const WEEKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
 
 /// Temporal query specifications
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +171,7 @@ impl TemporalQuery {
     }
 
     /// Convert to SQL WHERE clause
+    #[deprecated(note = "interpolates timestamps into SQL; use `MemoryQuery::from(&query)`")]
     pub fn to_sql_filter(&self) -> String {
         let column = match self.mode {
             TemporalMode::Created => "created_at",
@@ -398,6 +420,298 @@ impl Default for TemporalConfig {
     }
 }
 
+/// How often a recurrence repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+}
+
+/// A schedule in the spirit of an iCalendar RRULE: every day, or on some
+/// weekdays, at a fixed UTC time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Days a weekly recurrence falls on, Monday first; empty when daily
+    pub by_day: Vec<Weekday>,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl Recurrence {
+    /// `FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=30`
+    pub fn to_rrule(&self) -> String {
+        let mut rule = match self.frequency {
+            Frequency::Daily => "FREQ=DAILY".to_string(),
+            Frequency::Weekly => format!(
+                "FREQ=WEEKLY;BYDAY={}",
+                self.by_day
+                    .iter()
+                    .map(|day| day.to_string()[..2].to_uppercase())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+        rule.push_str(&format!(";BYHOUR={};BYMINUTE={}", self.hour, self.minute));
+        rule
+    }
+
+    /// "every weekday at 09:30 UTC"
+    pub fn describe(&self) -> String {
+        let days = match self.frequency {
+            Frequency::Daily => "every day".to_string(),
+            Frequency::Weekly if self.by_day == WEEKDAYS => "every weekday".to_string(),
+            Frequency::Weekly => {
+                let names: Vec<String> = self.by_day.iter().map(weekday_name).collect();
+                format!("every {}", names.join(", "))
+            }
+        };
+        format!("{days} at {:02}:{:02} UTC", self.hour, self.minute)
+    }
+
+    /// Whether the recurrence falls on `day`
+    pub fn falls_on(&self, day: NaiveDate) -> bool {
+        match self.frequency {
+            Frequency::Daily => true,
+            Frequency::Weekly => self.by_day.contains(&day.weekday()),
+        }
+    }
+
+    /// The next `n` occurrences strictly after `after`
+    pub fn next_occurrences(&self, after: DateTime<Utc>, n: usize) -> Vec<DateTime<Utc>> {
+        let time = NaiveTime::from_hms_opt(self.hour, self.minute, 0).unwrap_or(NaiveTime::MIN);
+        if self.frequency == Frequency::Weekly && self.by_day.is_empty() {
+            return Vec::new();
+        }
+        after
+            .date_naive()
+            .iter_days()
+            .filter(|day| self.falls_on(*day))
+            .map(|day| day.and_time(time).and_utc())
+            .filter(|at| *at > after)
+            .take(n)
+            .collect()
+    }
+}
+
+/// Events that recur on a schedule, as found by [`RecurrenceDetector`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecurringPattern {
+    /// What recurs: the events' shared content, numbers removed
+    pub label: String,
+    pub recurrence: Recurrence,
+    /// The recurrence as an RRULE string
+    pub rrule: String,
+    /// How many events matched
+    pub occurrences: usize,
+    /// How regularly the events kept to the schedule (0.0 - 1.0)
+    pub confidence: f32,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// The event memories the pattern was found in
+    pub memory_ids: Vec<MemoryId>,
+}
+
+impl RecurringPattern {
+    /// The next `n` occurrences strictly after `after`
+    pub fn next_occurrences(&self, after: DateTime<Utc>, n: usize) -> Vec<DateTime<Utc>> {
+        self.recurrence.next_occurrences(after, n)
+    }
+
+    /// Searchable text of the pattern memory
+    pub fn content(&self) -> String {
+        format!("Recurring: {} {}", self.label, self.recurrence.describe())
+    }
+
+    /// The pattern stored in `memory`'s metadata, if it records one
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        let pattern = memory.metadata.as_ref()?.get(RECURRENCE_METADATA_KEY)?;
+        serde_json::from_value(pattern.clone()).ok()
+    }
+}
+
+/// Finds schedules in event memories.
+///
+/// Events are grouped by content with numbers removed, so "Standup 3" and
+/// "Standup 4" are one series. A series becomes a pattern when it has enough
+/// events, most of them near the same time of day, on days that repeat from
+/// week to week.
+#[derive(Debug, Clone)]
+pub struct RecurrenceDetector {
+    min_occurrences: usize,
+    time_tolerance_minutes: i64,
+    min_confidence: f32,
+}
+
+impl Default for RecurrenceDetector {
+    fn default() -> Self {
+        Self {
+            min_occurrences: 3,
+            time_tolerance_minutes: 20,
+            min_confidence: 0.6,
+        }
+    }
+}
+
+impl RecurrenceDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events needed before a series counts as recurring
+    pub fn with_min_occurrences(mut self, min_occurrences: usize) -> Self {
+        self.min_occurrences = min_occurrences.max(2);
+        self
+    }
+
+    /// How far from the usual time of day an event may be and still count
+    pub fn with_time_tolerance_minutes(mut self, minutes: i64) -> Self {
+        self.time_tolerance_minutes = minutes.max(0);
+        self
+    }
+
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Recurring patterns among the event memories in `memories`, most
+    /// confident first. Other memory types are ignored.
+    pub fn detect(&self, memories: &[Memory]) -> Vec<RecurringPattern> {
+        let mut series: HashMap<String, Vec<&Memory>> = HashMap::new();
+        for memory in memories {
+            if memory.memory_type != MemoryType::Event || memory.forgotten {
+                continue;
+            }
+            let label = series_label(&memory.content);
+            if !label.is_empty() {
+                series.entry(label).or_default().push(memory);
+            }
+        }
+
+        let mut patterns: Vec<RecurringPattern> = series
+            .into_iter()
+            .filter_map(|(label, events)| self.detect_series(label, events))
+            .collect();
+        patterns.sort_by(|a, b| {
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        patterns
+    }
+
+    fn detect_series(&self, label: String, mut events: Vec<&Memory>) -> Option<RecurringPattern> {
+        if events.len() < self.min_occurrences {
+            return None;
+        }
+        events.sort_by_key(|m| m.created_at);
+
+        // Usual time of day: the median, then how many events keep near it
+        let mut minutes: Vec<i64> = events.iter().map(|m| minute_of_day(m.created_at)).collect();
+        minutes.sort_unstable();
+        let usual = minutes[minutes.len() / 2];
+        let on_time: Vec<&Memory> = events
+            .iter()
+            .copied()
+            .filter(|m| {
+                let diff = (minute_of_day(m.created_at) - usual).abs();
+                diff.min(24 * 60 - diff) <= self.time_tolerance_minutes
+            })
+            .collect();
+        if on_time.len() < self.min_occurrences {
+            return None;
+        }
+
+        let days: BTreeSet<NaiveDate> = on_time.iter().map(|m| m.created_at.date_naive()).collect();
+        let first = *days.first()?;
+        let last = *days.last()?;
+        let weeks = ((last - first).num_days() / 7 + 1) as usize;
+
+        // A weekday is part of the schedule when it shows up in at least half the weeks
+        let mut per_weekday: HashMap<Weekday, usize> = HashMap::new();
+        for day in &days {
+            *per_weekday.entry(day.weekday()).or_default() += 1;
+        }
+        let mut by_day: Vec<Weekday> = per_weekday
+            .into_iter()
+            .filter(|(_, n)| n * 2 >= weeks)
+            .map(|(day, _)| day)
+            .collect();
+        by_day.sort_by_key(|day| day.num_days_from_monday());
+
+        let recurrence = if by_day.len() == 7 {
+            Recurrence {
+                frequency: Frequency::Daily,
+                by_day: Vec::new(),
+                hour: (usual / 60) as u32,
+                minute: (usual % 60) as u32,
+            }
+        } else {
+            Recurrence {
+                frequency: Frequency::Weekly,
+                by_day,
+                hour: (usual / 60) as u32,
+                minute: (usual % 60) as u32,
+            }
+        };
+
+        // Share of events on time, times share of scheduled days that had one
+        let scheduled = first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .filter(|day| recurrence.falls_on(*day))
+            .count();
+        let hit = days.iter().filter(|day| recurrence.falls_on(**day)).count();
+        let punctuality = on_time.len() as f32 / events.len() as f32;
+        let coverage = hit as f32 / scheduled.max(1) as f32;
+        let confidence = (punctuality * coverage).clamp(0.0, 1.0);
+        if confidence < self.min_confidence {
+            return None;
+        }
+
+        Some(RecurringPattern {
+            label,
+            rrule: recurrence.to_rrule(),
+            recurrence,
+            occurrences: on_time.len(),
+            confidence,
+            first_seen: on_time[0].created_at,
+            last_seen: on_time[on_time.len() - 1].created_at,
+            memory_ids: on_time.iter().map(|m| m.id.clone()).collect(),
+        })
+    }
+}
+
+/// Lowercased content with numbers and punctuation removed
+fn series_label(content: &str) -> String {
+    content
+        .to_lowercase()
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn minute_of_day(at: DateTime<Utc>) -> i64 {
+    (at.hour() * 60 + at.minute()) as i64
+}
+
+fn weekday_name(day: &Weekday) -> String {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+    .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,6 +748,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_sql_filter() {
         let start = Utc::now();
         let end = start + Duration::hours(1);
@@ -444,4 +759,71 @@ mod tests {
         assert!(sql.contains("created_at"));
         assert!(sql.contains("BETWEEN"));
     }
+
+    #[test]
+    fn test_detects_weekday_standup() {
+        // Monday 2025-03-03 through Friday 2025-03-21, standup around 09:30
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let mut memories = Vec::new();
+        for (i, day) in monday.iter_days().take(19).enumerate() {
+            if day.weekday().number_from_monday() > 5 {
+                continue;
+            }
+            let at = day
+                .and_hms_opt(9, 28 + (i % 4) as u32, 0)
+                .unwrap()
+                .and_utc();
+            memories.push(Memory::new_at(
+                format!("Standup #{i} with the team"),
+                MemoryType::Event,
+                format!("standup-{i}"),
+                at,
+            ));
+        }
+        let lunch = monday.and_hms_opt(12, 0, 0).unwrap().and_utc();
+        memories.push(Memory::new_at(
+            "Lunch with Ana",
+            MemoryType::Event,
+            "lunch",
+            lunch,
+        ));
+
+        let patterns = RecurrenceDetector::new().detect(&memories);
+        assert_eq!(patterns.len(), 1);
+        let pattern = &patterns[0];
+        assert_eq!(pattern.label, "standup with the team");
+        assert_eq!(pattern.occurrences, 15);
+        assert_eq!(pattern.recurrence.by_day, WEEKDAYS);
+        assert_eq!(
+            pattern.rrule,
+            "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=30"
+        );
+        assert!(pattern.content().contains("every weekday at 09:30 UTC"));
+
+        // Friday evening: next are Monday and Tuesday morning
+        let friday = NaiveDate::from_ymd_opt(2025, 3, 21).unwrap();
+        let next = pattern.next_occurrences(friday.and_hms_opt(18, 0, 0).unwrap().and_utc(), 2);
+        assert_eq!(
+            next,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 3, 24)
+                    .unwrap()
+                    .and_hms_opt(9, 30, 0)
+                    .unwrap()
+                    .and_utc(),
+                NaiveDate::from_ymd_opt(2025, 3, 25)
+                    .unwrap()
+                    .and_hms_opt(9, 30, 0)
+                    .unwrap()
+                    .and_utc(),
+            ]
+        );
+
+        let memory = Memory::new(pattern.content(), MemoryType::Observation)
+            .with_metadata(serde_json::json!({ RECURRENCE_METADATA_KEY: pattern }));
+        assert_eq!(
+            RecurringPattern::from_memory(&memory).as_ref(),
+            Some(pattern)
+        );
+    }
 }
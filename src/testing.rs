@@ -0,0 +1,256 @@
+//! Fixtures and golden-dataset helpers for retrieval regression tests
+//!
+//! A fixture is a YAML or JSON file describing memories, associations and
+//! episodes. [`load_fixture`] ingests it into a fresh memory system in a
+//! temporary directory, using the fixture's IDs as the real memory IDs, so
+//! tests can assert on retrieval with [`assert_recall`]:
+//!
+//! ```yaml
+//! memories:
+//!   - id: deploy-day
+//!     content: Deploys to production run every Tuesday
+//!     type: fact
+//!     tags: [ops]
+//!   - id: freeze
+//!     content: No deploys during the December freeze
+//!     type: decision
+//! associations:
+//!   - { from: freeze, to: deploy-day, relation: updates }
+//! episodes:
+//!   - title: Release planning
+//!     memories: [deploy-day, freeze]
+//! ```
+//!
+//! ```rust,ignore
+//! let memory = load_fixture("tests/fixtures/deploys.yaml").await?;
+//! assert_recall(&memory, "when do we deploy", &["deploy-day"]).await;
+//! ```
+
+use crate::cortex::Experience;
+use crate::error::{MemoryError, Result};
+use crate::types::{Association, Memory, MemoryId, MemoryType, RelationType};
+use crate::MemorySystem;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Memories, associations and episodes to load together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fixture {
+    pub memories: Vec<FixtureMemory>,
+    pub associations: Vec<FixtureAssociation>,
+    pub episodes: Vec<FixtureEpisode>,
+}
+
+/// A memory in a fixture. Unset fields take `Memory::new` defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureMemory {
+    /// Used as the memory ID, so associations, episodes and assertions can refer to it
+    pub id: MemoryId,
+    pub content: String,
+    #[serde(rename = "type", default = "default_memory_type")]
+    pub memory_type: MemoryType,
+    #[serde(default)]
+    pub importance: Option<f32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+fn default_memory_type() -> MemoryType {
+    MemoryType::Fact
+}
+
+/// An association between two fixture memories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureAssociation {
+    pub from: MemoryId,
+    pub to: MemoryId,
+    pub relation: RelationType,
+    #[serde(default)]
+    pub weight: Option<f32>,
+}
+
+/// An episode grouping fixture memories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureEpisode {
+    pub title: String,
+    #[serde(default)]
+    pub context: String,
+    #[serde(default)]
+    pub memories: Vec<MemoryId>,
+}
+
+impl Fixture {
+    /// Parse a fixture file; `.json` files are read as JSON, anything else as YAML
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&text),
+            _ => Self::from_yaml(&text),
+        }
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        serde_yaml::from_str(text).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    /// Save the fixture's memories, then its associations and episodes, into `memory`
+    pub async fn load_into(&self, memory: &MemorySystem) -> Result<()> {
+        for fixture in &self.memories {
+            let mut m = Memory::new_at(
+                fixture.content.clone(),
+                fixture.memory_type,
+                fixture.id.clone(),
+                fixture.created_at.unwrap_or_else(|| memory.store.now()),
+            );
+            if let Some(importance) = fixture.importance {
+                m = m.with_importance(importance);
+            }
+            if let Some(source) = &fixture.source {
+                m = m.with_source(source.clone());
+            }
+            if let Some(metadata) = &fixture.metadata {
+                m = m.with_metadata(metadata.clone());
+            }
+            m.tags = fixture.tags.clone();
+            memory.save(&m).await?;
+        }
+
+        let associations: Vec<Association> = self
+            .associations
+            .iter()
+            .map(|fixture| {
+                let association = Association::new(&fixture.from, &fixture.to, fixture.relation);
+                match fixture.weight {
+                    Some(weight) => association.with_weight(weight),
+                    None => association,
+                }
+            })
+            .collect();
+        memory.store.create_associations(&associations).await?;
+
+        for fixture in &self.episodes {
+            let mut episode = Experience::new(fixture.title.clone(), fixture.context.clone());
+            episode.id = memory.store.next_id();
+            episode.started_at = memory.store.now();
+            for id in &fixture.memories {
+                episode.add_memory(id.clone());
+            }
+            memory.store.save_experience(&episode).await?;
+        }
+        Ok(())
+    }
+}
+
+/// A memory system loaded from a fixture. Derefs to [`MemorySystem`]; the
+/// temporary data directory is removed on drop.
+pub struct LoadedFixture {
+    memory: MemorySystem,
+    _dir: tempfile::TempDir,
+}
+
+impl std::ops::Deref for LoadedFixture {
+    type Target = MemorySystem;
+
+    fn deref(&self) -> &MemorySystem {
+        &self.memory
+    }
+}
+
+/// Load a YAML or JSON fixture into a fresh memory system
+pub async fn load_fixture(path: impl AsRef<Path>) -> Result<LoadedFixture> {
+    load(&Fixture::from_path(path)?).await
+}
+
+/// Load an already parsed fixture into a fresh memory system
+pub async fn load(fixture: &Fixture) -> Result<LoadedFixture> {
+    let dir = tempfile::tempdir()?;
+    let memory = MemorySystem::new(dir.path()).await?;
+    fixture.load_into(&memory).await?;
+    Ok(LoadedFixture { memory, _dir: dir })
+}
+
+/// Assert that searching `query` returns every ID in `expected` within the top
+/// `expected.len()` results, in any order. Panics with the actual IDs otherwise.
+pub async fn assert_recall(memory: &MemorySystem, query: &str, expected: &[&str]) {
+    assert_recall_within(memory, query, expected, expected.len()).await
+}
+
+/// Like [`assert_recall`], but allows the expected IDs anywhere in the top `k`
+pub async fn assert_recall_within(memory: &MemorySystem, query: &str, expected: &[&str], k: usize) {
+    let config = crate::search::SearchConfig {
+        max_results: k.max(1),
+        ..Default::default()
+    };
+    let actual: Vec<MemoryId> = match memory.search_with_config(query, &config).await {
+        Ok(results) => results.into_iter().map(|r| r.memory.id).collect(),
+        Err(e) => panic!("search for {query:?} failed: {e}"),
+    };
+    let missing: Vec<&str> = expected
+        .iter()
+        .copied()
+        .filter(|id| !actual.iter().any(|a| a == id))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "recall for {query:?}: missing {missing:?} in top {k}, got {actual:?}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_recall, assert_recall_within, load, Fixture};
+
+    const FIXTURE: &str = r#"
+memories:
+  - id: deploy-day
+    content: Deploys to production run every Tuesday afternoon
+    type: fact
+    tags: [ops]
+  - id: freeze
+    content: No production deploys during the December freeze
+    type: decision
+    importance: 0.9
+  - id: lunch
+    content: The team orders pizza on Fridays
+associations:
+  - { from: freeze, to: deploy-day, relation: updates, weight: 0.8 }
+episodes:
+  - title: Release planning
+    memories: [deploy-day, freeze]
+"#;
+
+    #[tokio::test]
+    async fn fixture_loads_and_recall_asserts() {
+        let fixture = Fixture::from_yaml(FIXTURE).unwrap();
+        let memory = load(&fixture).await.unwrap();
+
+        let freeze = memory.load("freeze").await.unwrap().unwrap();
+        assert_eq!(freeze.importance, 0.9);
+        let associations = memory.get_associations("deploy-day").await.unwrap();
+        assert_eq!(associations.len(), 1);
+        assert_eq!(associations[0].weight, 0.8);
+
+        assert_recall(&memory, "pizza", &["lunch"]).await;
+        assert_recall_within(&memory, "production deploys", &["freeze", "deploy-day"], 3).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "missing [\"lunch\"]")]
+    async fn assert_recall_reports_missing_ids() {
+        let memory = load(&Fixture::from_yaml(FIXTURE).unwrap()).await.unwrap();
+        assert_recall(&memory, "December freeze", &["lunch"]).await;
+    }
+}
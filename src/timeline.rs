@@ -0,0 +1,239 @@
+//! Memory timelines: everything that happened to one memory, in order
+//!
+//! [`memory_timeline`] stitches together what the store knows about a memory
+//! across tables: its creation and recorded versions, confidence changes
+//! between versions, the episodes it was added to, associations to other
+//! memories, accesses and citations. It is the place to start when debugging
+//! why the agent believes something; [`explain_belief`](crate::provenance::explain_belief)
+//! then shows the memories behind it.
+
+use crate::error::{MemoryError, Result};
+use crate::store::MemoryStore;
+use crate::types::{Memory, MemoryId, RelationType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Confidence moves smaller than this between versions are not reported
+const CONFIDENCE_EPSILON: f32 = 1e-3;
+
+/// Something that happened to a memory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    Created,
+    /// A version was recorded (save, update, forget, restore, ...)
+    Version {
+        version_number: u32,
+        author: String,
+        reason: Option<String>,
+    },
+    ConfidenceChanged {
+        from: f32,
+        to: f32,
+    },
+    AddedToEpisode {
+        episode_id: String,
+        title: String,
+    },
+    Associated {
+        association_id: String,
+        other_id: MemoryId,
+        relation_type: RelationType,
+        /// Whether this memory is the source of the association
+        outgoing: bool,
+        weight: f32,
+    },
+    Accessed,
+    Cited {
+        answer_id: String,
+    },
+}
+
+/// A timestamped [`TimelineEventKind`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: TimelineEventKind,
+}
+
+/// A memory and its history, oldest event first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryTimeline {
+    pub memory: Memory,
+    pub events: Vec<TimelineEvent>,
+}
+
+impl MemoryTimeline {
+    /// The timeline as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    /// One line per event
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Timeline of {}: {}",
+            self.memory.id, self.memory.content
+        );
+        for event in &self.events {
+            let line = match &event.kind {
+                TimelineEventKind::Created => "created".to_string(),
+                TimelineEventKind::Version {
+                    version_number,
+                    author,
+                    reason,
+                } => format!(
+                    "version {version_number} by {author}: {}",
+                    reason.as_deref().unwrap_or("changed")
+                ),
+                TimelineEventKind::ConfidenceChanged { from, to } => {
+                    format!("confidence {from:.2} -> {to:.2}")
+                }
+                TimelineEventKind::AddedToEpisode { episode_id, title } => {
+                    format!("added to episode \"{title}\" ({episode_id})")
+                }
+                TimelineEventKind::Associated {
+                    other_id,
+                    relation_type,
+                    outgoing,
+                    weight,
+                    ..
+                } => {
+                    let arrow = if *outgoing { "->" } else { "<-" };
+                    format!("{arrow} {other_id} ({relation_type}, weight {weight:.2})")
+                }
+                TimelineEventKind::Accessed => "accessed".to_string(),
+                TimelineEventKind::Cited { answer_id } => format!("cited by answer {answer_id}"),
+            };
+            let _ = writeln!(out, "  {} {line}", event.at.format("%Y-%m-%d %H:%M:%S"));
+        }
+        out
+    }
+}
+
+/// Everything the store knows happened to memory `id`, oldest first.
+/// Returns `None` if the memory does not exist.
+pub async fn memory_timeline(store: &MemoryStore, id: &str) -> Result<Option<MemoryTimeline>> {
+    let Some(memory) = store.load(id).await? else {
+        return Ok(None);
+    };
+
+    let mut events = vec![TimelineEvent {
+        at: memory.created_at,
+        kind: TimelineEventKind::Created,
+    }];
+
+    let versions = store.memory_versions(id).await?;
+    for (i, version) in versions.iter().enumerate() {
+        events.push(TimelineEvent {
+            at: version.created_at,
+            kind: TimelineEventKind::Version {
+                version_number: version.version_number,
+                author: version.author.to_string(),
+                reason: version.change_reason.clone(),
+            },
+        });
+        if let Some(previous) = i.checked_sub(1).map(|p| &versions[p]) {
+            let from = previous.memory.confidence.score;
+            let to = version.memory.confidence.score;
+            if (to - from).abs() > CONFIDENCE_EPSILON {
+                events.push(TimelineEvent {
+                    at: version.created_at,
+                    kind: TimelineEventKind::ConfidenceChanged { from, to },
+                });
+            }
+        }
+    }
+
+    for (episode_id, title, at) in store.memory_episodes(id).await? {
+        events.push(TimelineEvent {
+            at,
+            kind: TimelineEventKind::AddedToEpisode { episode_id, title },
+        });
+    }
+
+    for association in store.get_associations(id).await? {
+        let outgoing = association.source_id == id;
+        let other_id = if outgoing {
+            association.target_id
+        } else {
+            association.source_id
+        };
+        events.push(TimelineEvent {
+            at: association.created_at,
+            kind: TimelineEventKind::Associated {
+                association_id: association.id,
+                other_id,
+                relation_type: association.relation_type,
+                outgoing,
+                weight: association.weight,
+            },
+        });
+    }
+
+    for at in store.memory_accesses(id).await? {
+        events.push(TimelineEvent {
+            at,
+            kind: TimelineEventKind::Accessed,
+        });
+    }
+
+    for (answer_id, at) in store.memory_citations(id).await? {
+        events.push(TimelineEvent {
+            at,
+            kind: TimelineEventKind::Cited { answer_id },
+        });
+    }
+
+    // Stable: events at the same instant keep the order they were gathered in
+    events.sort_by_key(|event| event.at);
+
+    Ok(Some(MemoryTimeline { memory, events }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Association, MemoryType};
+    use crate::versioning::VersionAuthor;
+
+    #[tokio::test]
+    async fn timeline_orders_events_across_tables() {
+        let store = MemoryStore::connect_in_memory().await;
+        let memory = store.new_memory("Deploys happen on Tuesdays", MemoryType::Fact);
+        store.save(&memory).await.unwrap();
+        let other = store.new_memory("Release checklist", MemoryType::Fact);
+        store.save(&other).await.unwrap();
+
+        store
+            .record_version(&memory, VersionAuthor::Agent, "created")
+            .await
+            .unwrap();
+        store
+            .create_association(&Association::new(
+                &other.id,
+                &memory.id,
+                RelationType::RelatedTo,
+            ))
+            .await
+            .unwrap();
+        store.record_access(&memory.id).await.unwrap();
+        store.record_citation("answer-1", &memory.id).await.unwrap();
+
+        let timeline = memory_timeline(&store, &memory.id).await.unwrap().unwrap();
+        let kinds: Vec<&TimelineEventKind> = timeline.events.iter().map(|e| &e.kind).collect();
+        assert_eq!(kinds[0], &TimelineEventKind::Created);
+        assert!(timeline.events.windows(2).all(|w| w[0].at <= w[1].at));
+        assert!(kinds.contains(&&TimelineEventKind::Accessed));
+        assert!(kinds.iter().any(|k| matches!(
+            k,
+            TimelineEventKind::Associated { outgoing: false, other_id, .. } if *other_id == other.id
+        )));
+        assert!(timeline.to_text().contains("cited by answer answer-1"));
+        assert!(memory_timeline(&store, "missing").await.unwrap().is_none());
+    }
+}
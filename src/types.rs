@@ -42,6 +42,12 @@ pub struct Memory {
     pub session_id: Option<SessionId>,
     /// Whether this memory is forgotten (soft delete)
     pub forgotten: bool,
+    /// Pinned memories are never pruned or consolidated and always make the context window
+    #[serde(default)]
+    pub pinned: bool,
+    /// ISO 639-1 language of the content; detected on save when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
     /// Additional metadata (flexible key-value storage)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
@@ -57,12 +63,20 @@ pub struct Memory {
 impl Memory {
     /// Create a new memory with default values
     pub fn new(content: impl Into<String>, memory_type: MemoryType) -> Self {
-        let now = Utc::now();
-        let id = Uuid::new_v4().to_string();
+        Self::new_at(content, memory_type, Uuid::new_v4().to_string(), Utc::now())
+    }
+
+    /// Create a new memory with a given ID, created at `now`
+    pub fn new_at(
+        content: impl Into<String>,
+        memory_type: MemoryType,
+        id: impl Into<MemoryId>,
+        now: DateTime<Utc>,
+    ) -> Self {
         let importance = memory_type.default_importance();
 
         Self {
-            id,
+            id: id.into(),
             content: content.into(),
             memory_type,
             importance,
@@ -76,17 +90,37 @@ impl Memory {
             source: None,
             session_id: None,
             forgotten: false,
+            pinned: false,
+            language: None,
             metadata: None,
             confidence: MemoryConfidence::new(),
         }
     }
 
+    /// Set the content language (ISO 639-1) instead of detecting it on save
+    pub fn with_language(mut self, language: impl AsRef<str>) -> Self {
+        self.language = Some(crate::language::normalize_language(language.as_ref()));
+        self
+    }
+
     /// Set custom importance
     pub fn with_importance(mut self, importance: f32) -> Self {
         self.importance = importance.clamp(0.0, 1.0);
         self
     }
 
+    /// Set emotional valence (-1.0 negative .. 1.0 positive)
+    pub fn with_valence(mut self, valence: f32) -> Self {
+        self.emotional_valence = valence.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Pin or unpin the memory
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
     /// Set the source
     pub fn with_source(mut self, source: impl Into<String>) -> Self {
         self.source = Some(source.into());
@@ -316,13 +350,22 @@ pub struct MemorySearchResult {
 }
 
 /// Input for creating a memory
-#[derive(Debug, Clone)]
+///
+/// Deserializes from records like `{"content": "...", "memory_type": "fact"}`;
+/// every field but `content` is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateMemoryInput {
     pub content: String,
-    pub memory_type: MemoryType,
+    /// Memory type; when `None` the type is assigned by a classifier on save
+    #[serde(default)]
+    pub memory_type: Option<MemoryType>,
+    #[serde(default)]
     pub importance: Option<f32>,
+    #[serde(default)]
     pub source: Option<String>,
+    #[serde(default)]
     pub session_id: Option<SessionId>,
+    #[serde(default)]
     pub metadata: Option<serde_json::Value>,
 }
 
@@ -330,7 +373,19 @@ impl CreateMemoryInput {
     pub fn new(content: impl Into<String>, memory_type: MemoryType) -> Self {
         Self {
             content: content.into(),
-            memory_type,
+            memory_type: Some(memory_type),
+            importance: None,
+            source: None,
+            session_id: None,
+            metadata: None,
+        }
+    }
+
+    /// Create input without a memory type, leaving it to the classifier
+    pub fn untyped(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            memory_type: None,
             importance: None,
             source: None,
             session_id: None,
@@ -359,6 +414,22 @@ impl CreateMemoryInput {
     }
 }
 
+/// Outcome of [`MemorySystem::save_batch`](crate::MemorySystem::save_batch)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSaveReport {
+    /// IDs of the memories saved, in input order
+    pub saved: Vec<MemoryId>,
+    /// Inputs that failed, by position in the batch
+    pub failed: Vec<BatchSaveFailure>,
+}
+
+/// One input [`MemorySystem::save_batch`](crate::MemorySystem::save_batch) could not save
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSaveFailure {
+    pub index: usize,
+    pub error: String,
+}
+
 /// Input for creating an association
 #[derive(Debug, Clone)]
 pub struct CreateAssociationInput {
@@ -0,0 +1,288 @@
+//! Content validation on save
+//!
+//! [`ContentValidation`] sanitizes memory content before it reaches SQLite and the
+//! search indexes: Unicode NFC normalization, line-ending normalization, control
+//! character stripping and a maximum length. Oversized content is rejected with
+//! [`MemoryError::Validation`], truncated, or split into chunks depending on
+//! [`OversizeBehavior`].
+
+use crate::error::{MemoryError, Result};
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+/// What to do with content longer than [`ContentValidation::max_content_chars`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizeBehavior {
+    /// Fail with [`MemoryError::Validation`]
+    #[default]
+    Reject,
+    /// Keep the first `max_content_chars` characters
+    Truncate,
+    /// Split into several memories of at most `max_content_chars` characters each,
+    /// linked to the first one with [`RelationType::PartOf`](crate::types::RelationType::PartOf)
+    Chunk,
+}
+
+/// Validation and normalization rules applied to memory content on save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentValidation {
+    /// Maximum content length in characters (`0` disables the limit)
+    pub max_content_chars: usize,
+    /// Apply Unicode NFC normalization and convert `\r\n`/`\r` to `\n`
+    pub normalize_unicode: bool,
+    /// Remove control characters other than newline and tab
+    pub strip_control_chars: bool,
+    /// Reject content that is empty after sanitizing
+    pub reject_empty: bool,
+    /// Handling of content over the length limit
+    pub oversize: OversizeBehavior,
+    /// Upper bound on chunks produced by [`OversizeBehavior::Chunk`]
+    pub max_chunks: usize,
+}
+
+impl Default for ContentValidation {
+    fn default() -> Self {
+        Self {
+            max_content_chars: 100_000,
+            normalize_unicode: true,
+            strip_control_chars: true,
+            reject_empty: true,
+            oversize: OversizeBehavior::Reject,
+            max_chunks: 64,
+        }
+    }
+}
+
+impl ContentValidation {
+    /// No normalization and no limits
+    pub fn disabled() -> Self {
+        Self {
+            max_content_chars: 0,
+            normalize_unicode: false,
+            strip_control_chars: false,
+            reject_empty: false,
+            oversize: OversizeBehavior::Reject,
+            max_chunks: 0,
+        }
+    }
+
+    pub fn with_max_content_chars(mut self, max_content_chars: usize) -> Self {
+        self.max_content_chars = max_content_chars;
+        self
+    }
+
+    pub fn with_oversize(mut self, oversize: OversizeBehavior) -> Self {
+        self.oversize = oversize;
+        self
+    }
+
+    pub fn with_max_chunks(mut self, max_chunks: usize) -> Self {
+        self.max_chunks = max_chunks;
+        self
+    }
+
+    /// Normalize and strip content without applying the length limit
+    pub fn sanitize(&self, content: &str) -> String {
+        let content = if self.normalize_unicode {
+            content
+                .replace("\r\n", "\n")
+                .replace('\r', "\n")
+                .nfc()
+                .collect::<String>()
+        } else {
+            content.to_string()
+        };
+
+        if self.strip_control_chars {
+            content
+                .chars()
+                .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+                .collect()
+        } else {
+            content
+        }
+    }
+
+    /// Sanitize content and enforce the length limit.
+    ///
+    /// Returns one string unless the content is oversized and the behavior is
+    /// [`OversizeBehavior::Chunk`], in which case the chunks are returned in order.
+    pub fn apply(&self, content: &str) -> Result<Vec<String>> {
+        let content = self.sanitize(content);
+        if self.reject_empty && content.trim().is_empty() {
+            return Err(MemoryError::Validation(
+                "Memory content is empty".to_string(),
+            ));
+        }
+
+        let len = content.chars().count();
+        if self.max_content_chars == 0 || len <= self.max_content_chars {
+            return Ok(vec![content]);
+        }
+
+        match self.oversize {
+            OversizeBehavior::Reject => Err(self.too_long(len)),
+            OversizeBehavior::Truncate => {
+                Ok(vec![content.chars().take(self.max_content_chars).collect()])
+            }
+            OversizeBehavior::Chunk => {
+                let chunks = split_content(&content, self.max_content_chars);
+                if self.max_chunks > 0 && chunks.len() > self.max_chunks {
+                    return Err(MemoryError::Validation(format!(
+                        "Memory content of {len} characters would need {} chunks (max {})",
+                        chunks.len(),
+                        self.max_chunks
+                    )));
+                }
+                Ok(chunks)
+            }
+        }
+    }
+
+    /// Like [`apply`](Self::apply) but never splits: oversized content configured to
+    /// chunk is rejected. Used where a single memory must stay a single memory.
+    pub fn apply_single(&self, content: &str) -> Result<String> {
+        let mut pieces = self.apply(content)?;
+        if pieces.len() > 1 {
+            return Err(self.too_long(content.chars().count()));
+        }
+        Ok(pieces.remove(0))
+    }
+
+    fn too_long(&self, len: usize) -> MemoryError {
+        MemoryError::Validation(format!(
+            "Memory content is {len} characters, over the limit of {}",
+            self.max_content_chars
+        ))
+    }
+}
+
+/// Metadata for the `index`-th (0-based) of `total` chunks split from memory `parent_id`,
+/// merged into the memory's existing metadata under the `chunk` key
+pub(crate) fn chunk_metadata(
+    existing: Option<serde_json::Value>,
+    parent_id: &str,
+    index: usize,
+    total: usize,
+) -> serde_json::Value {
+    let mut map = match existing {
+        Some(serde_json::Value::Object(map)) => map,
+        Some(other) => {
+            let mut map = serde_json::Map::new();
+            map.insert("value".to_string(), other);
+            map
+        }
+        None => serde_json::Map::new(),
+    };
+    map.insert(
+        "chunk".to_string(),
+        serde_json::json!({ "parent_id": parent_id, "index": index, "total": total }),
+    );
+    serde_json::Value::Object(map)
+}
+
+/// Split text into pieces of at most `max_chars` characters, preferring paragraph,
+/// then line, then sentence, then word boundaries.
+pub fn split_content(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        if rest.chars().count() <= max_chars {
+            chunks.push(rest.to_string());
+            break;
+        }
+
+        // Byte offsets just past the first `max_chars` and `max_chars + 1` characters.
+        // Separators end in whitespace that is trimmed off, so a boundary may use one
+        // extra character.
+        let offset = |n: usize| {
+            rest.char_indices()
+                .nth(n)
+                .map(|(i, _)| i)
+                .unwrap_or(rest.len())
+        };
+        let limit = offset(max_chars);
+        let window = &rest[..offset(max_chars + 1)];
+
+        let cut = ["\n\n", "\n", ". ", "! ", "? ", " "]
+            .iter()
+            .find_map(|sep| {
+                window
+                    .rfind(sep)
+                    .map(|i| i + sep.len())
+                    .filter(|i| *i > limit / 2)
+            })
+            .unwrap_or(limit);
+
+        let (head, tail) = rest.split_at(cut);
+        let head = head.trim();
+        if !head.is_empty() {
+            chunks.push(head.to_string());
+        }
+        rest = tail.trim_start();
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_control_chars_and_line_endings() {
+        let v = ContentValidation::default();
+        assert_eq!(v.sanitize("a\r\nb\u{0}c\u{7}\td"), "a\nbc\td");
+        // "e" + combining acute accent becomes a single precomposed character
+        assert_eq!(v.sanitize("caf\u{65}\u{301}"), "caf\u{e9}");
+    }
+
+    #[test]
+    fn oversize_behaviors() {
+        let text = "First sentence here. Second one here. Third one.";
+
+        let reject = ContentValidation::default().with_max_content_chars(20);
+        assert!(matches!(
+            reject.apply(text),
+            Err(MemoryError::Validation(_))
+        ));
+
+        let truncate = reject.clone().with_oversize(OversizeBehavior::Truncate);
+        assert_eq!(truncate.apply(text).unwrap(), vec!["First sentence here."]);
+
+        let chunk = reject.with_oversize(OversizeBehavior::Chunk);
+        let chunks = chunk.apply(text).unwrap();
+        assert_eq!(
+            chunks,
+            vec!["First sentence here.", "Second one here.", "Third one."]
+        );
+        assert!(chunk.apply_single(text).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_content() {
+        let v = ContentValidation::default();
+        assert!(v.apply(" \u{0} \n").is_err());
+    }
+
+    #[tokio::test]
+    async fn save_batch_reports_invalid_inputs_by_position() {
+        let memory = crate::MemorySystem::in_memory().await.unwrap();
+        let inputs: Vec<crate::CreateMemoryInput> = [
+            r#"{"content": "Deploys happen on Tuesdays", "memory_type": "fact"}"#,
+            r#"{"content": "   "}"#,
+            r#"{"content": "Prefers short answers", "importance": 0.9}"#,
+        ]
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+        let report = memory.save_batch(inputs).await;
+        assert_eq!(report.saved.len(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].index, 1);
+    }
+}
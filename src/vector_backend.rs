@@ -1,8 +1,18 @@
-use crate::error::Result;
-use crate::vector_search::{VectorIndex, VectorSearchConfig};
+use crate::encryption::{EncryptionKey, VectorCipher};
+use crate::error::{MemoryError, Result};
+use crate::types::{Memory, MemoryType};
+use crate::vector_search::{
+    cosine_similarity, VectorIndex, VectorQuantization, VectorSearchConfig,
+};
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Name of the collection used when no collection is given
+pub const DEFAULT_COLLECTION: &str = "default";
 
 #[derive(Debug, Clone)]
 pub struct VectorSearchHit {
@@ -12,6 +22,129 @@ pub struct VectorSearchHit {
     pub payload: Option<Value>,
 }
 
+/// Physical layout of a vector backend, used to spot fragmentation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorIndexStats {
+    pub backend: String,
+    /// Stored vectors across all collections
+    pub rows: usize,
+    /// Data fragments (files/segments) holding those vectors
+    pub fragments: usize,
+    /// Fragments considered too small, i.e. candidates for compaction
+    pub small_fragments: usize,
+    /// ANN indices present
+    pub indices: usize,
+}
+
+impl VectorIndexStats {
+    /// Share of fragments that are small (0.0 = compact, 1.0 = fully fragmented)
+    pub fn fragmentation(&self) -> f32 {
+        if self.fragments <= 1 {
+            return 0.0;
+        }
+        self.small_fragments as f32 / self.fragments as f32
+    }
+}
+
+/// Outcome of [`VectorBackend::maintain`] or [`VectorBackend::rebuild`]
+#[derive(Debug, Clone, Default)]
+pub struct VectorMaintenanceReport {
+    pub before: VectorIndexStats,
+    pub after: VectorIndexStats,
+    /// Human-readable list of what was done
+    pub actions: Vec<String>,
+}
+
+/// Metadata constraints for [`VectorBackend::search_filtered`].
+///
+/// Unset fields match everything and every set field must match. Backends
+/// test it against the payload written by [`vector_payload`]; hits stored
+/// without a payload (written before payloads existed) are kept, so callers
+/// still check the memories they load.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VectorFilter {
+    /// Any of these types
+    pub memory_types: Vec<MemoryType>,
+    /// Every one of these tags
+    pub tags: Vec<String>,
+    pub session_id: Option<String>,
+    pub namespace: Option<String>,
+}
+
+impl VectorFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_memory_type(mut self, memory_type: MemoryType) -> Self {
+        self.memory_types.push(memory_type);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memory_types.is_empty()
+            && self.tags.is_empty()
+            && self.session_id.is_none()
+            && self.namespace.is_none()
+    }
+
+    /// Whether a stored payload passes; a missing payload always does
+    pub fn matches(&self, payload: Option<&Value>) -> bool {
+        let Some(payload) = payload else {
+            return true;
+        };
+        let field = |name: &str| payload.get(name).and_then(Value::as_str);
+
+        if !self.memory_types.is_empty()
+            && !self
+                .memory_types
+                .iter()
+                .any(|t| field("memory_type") == Some(t.to_string().as_str()))
+        {
+            return false;
+        }
+        if self.session_id.is_some() && field("session_id") != self.session_id.as_deref() {
+            return false;
+        }
+        if self.namespace.is_some() && field("namespace") != self.namespace.as_deref() {
+            return false;
+        }
+        let tags: Vec<&str> = payload
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        self.tags.iter().all(|tag| tags.contains(&tag.as_str()))
+    }
+}
+
+/// Payload stored with a memory's vector: the fields [`VectorFilter`] tests,
+/// plus the memory's own metadata
+pub fn vector_payload(memory: &Memory, namespace: &str) -> Value {
+    serde_json::json!({
+        "memory_type": memory.memory_type.to_string(),
+        "tags": memory.tags,
+        "session_id": memory.session_id,
+        "namespace": namespace,
+        "metadata": memory.metadata,
+    })
+}
+
 #[async_trait]
 pub trait VectorBackend: Send + Sync {
     fn name(&self) -> &'static str;
@@ -20,29 +153,308 @@ pub trait VectorBackend: Send + Sync {
     async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()>;
     async fn delete(&self, id: &str) -> Result<()>;
     async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>>;
+
+    /// Current layout statistics
+    async fn stats(&self) -> Result<VectorIndexStats> {
+        Ok(VectorIndexStats {
+            backend: self.name().to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Routine upkeep: compact fragments, prune old versions, refresh ANN indices.
+    /// Cheap enough to run on every maintenance pass.
+    async fn maintain(&self) -> Result<VectorMaintenanceReport> {
+        let stats = self.stats().await?;
+        Ok(VectorMaintenanceReport {
+            before: stats.clone(),
+            after: stats,
+            actions: Vec::new(),
+        })
+    }
+
+    /// Full rebuild of the backend's index structures from its stored vectors
+    async fn rebuild(&self) -> Result<VectorMaintenanceReport> {
+        self.maintain().await
+    }
+
+    /// Whether the `*_in` methods accept named collections
+    fn supports_collections(&self) -> bool {
+        false
+    }
+
+    /// Upsert into a named collection (`None` is the default collection)
+    async fn upsert_in(
+        &self,
+        collection: Option<&str>,
+        id: &str,
+        vector: &[f32],
+        payload: Option<Value>,
+    ) -> Result<()> {
+        match named_collection(collection) {
+            None => self.upsert(id, vector, payload).await,
+            Some(name) => Err(collections_unsupported(self.name(), name)),
+        }
+    }
+
+    /// Delete from a named collection (`None` is the default collection)
+    async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+        match named_collection(collection) {
+            None => self.delete(id).await,
+            Some(name) => Err(collections_unsupported(self.name(), name)),
+        }
+    }
+
+    /// Search a named collection (`None` is the default collection)
+    async fn search_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<VectorSearchHit>> {
+        match named_collection(collection) {
+            None => self.search(vector, limit).await,
+            Some(name) => Err(collections_unsupported(self.name(), name)),
+        }
+    }
+
+    /// Nearest vectors whose payload passes `filter`.
+    ///
+    /// The default over-fetches with [`search`](Self::search) and drops hits
+    /// whose returned payload fails; backends that can filter before ranking
+    /// override it.
+    async fn search_filtered(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        if filter.is_empty() {
+            return self.search(vector, limit).await;
+        }
+        let mut hits = self
+            .search(vector, limit.saturating_mul(FILTER_OVERFETCH))
+            .await?;
+        hits.retain(|hit| filter.matches(hit.payload.as_ref()));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Filtered search of a named collection (`None` is the default collection)
+    async fn search_filtered_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        match named_collection(collection) {
+            None => self.search_filtered(vector, limit, filter).await,
+            Some(name) => Err(collections_unsupported(self.name(), name)),
+        }
+    }
+}
+
+/// Candidates fetched per wanted hit when a backend can only filter after ranking
+const FILTER_OVERFETCH: usize = 4;
+
+/// Map `None` and [`DEFAULT_COLLECTION`] to `None`, anything else to the name
+fn named_collection(collection: Option<&str>) -> Option<&str> {
+    collection.filter(|c| *c != DEFAULT_COLLECTION)
+}
+
+fn collections_unsupported(backend: &str, collection: &str) -> MemoryError {
+    MemoryError::InvalidOperation(format!(
+        "Vector backend '{backend}' does not support collections (requested '{collection}')"
+    ))
+}
+
+/// Collection names end up in paths and table names, so keep them simple
+pub fn validate_collection_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(MemoryError::Validation(format!(
+            "Invalid vector collection name '{name}': use 1-64 ASCII letters, digits, '_' or '-'"
+        )))
+    }
+}
+
+/// A single collection of a shared backend, usable wherever a [`VectorBackend`] is expected.
+///
+/// Lets tenants or memory types share one backend instance while keeping their
+/// vectors apart.
+pub struct VectorCollection {
+    inner: Arc<dyn VectorBackend>,
+    collection: String,
+}
+
+impl VectorCollection {
+    pub fn new(inner: Arc<dyn VectorBackend>, collection: impl Into<String>) -> Result<Self> {
+        let collection = collection.into();
+        validate_collection_name(&collection)?;
+        if named_collection(Some(&collection)).is_some() && !inner.supports_collections() {
+            return Err(collections_unsupported(inner.name(), &collection));
+        }
+        Ok(Self { inner, collection })
+    }
+
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+}
+
+#[async_trait]
+impl VectorBackend for VectorCollection {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+        self.inner
+            .upsert_in(Some(&self.collection), id, vector, payload)
+            .await
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.inner.delete_in(Some(&self.collection), id).await
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+        self.inner
+            .search_in(Some(&self.collection), vector, limit)
+            .await
+    }
+
+    async fn search_filtered(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.inner
+            .search_filtered_in(Some(&self.collection), vector, limit, filter)
+            .await
+    }
+}
+
+/// IVF parameters for an approximate nearest-neighbour index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnParams {
+    /// Number of IVF partitions (clusters) the index is built with
+    pub num_partitions: u32,
+    /// Partitions probed per query
+    pub nprobes: usize,
+    /// Re-rank `limit * refine_factor` candidates with exact distances
+    pub refine_factor: Option<u32>,
+}
+
+impl AnnParams {
+    /// Starting point for a corpus of `rows` vectors: about `sqrt(rows)` partitions,
+    /// probing roughly a sixteenth of them.
+    pub fn for_rows(rows: usize) -> Self {
+        let num_partitions = ((rows as f64).sqrt().round() as u32).clamp(1, 4096);
+        let nprobes = (num_partitions as usize / 16).clamp(8, 256);
+        let refine_factor = match rows {
+            0..=9_999 => 2,
+            10_000..=99_999 => 3,
+            _ => 5,
+        };
+        Self {
+            num_partitions,
+            nprobes: nprobes.min(num_partitions as usize),
+            refine_factor: Some(refine_factor),
+        }
+    }
+
+    /// A more accurate (and slower) setting: double `nprobes` until every partition is
+    /// probed, then raise the refine factor. `None` once nothing is left to raise.
+    pub fn escalate(&self) -> Option<Self> {
+        const MAX_REFINE_FACTOR: u32 = 20;
+
+        if self.nprobes < self.num_partitions as usize {
+            return Some(Self {
+                nprobes: (self.nprobes * 2).min(self.num_partitions as usize),
+                ..*self
+            });
+        }
+        let refine = self.refine_factor.unwrap_or(1);
+        if refine < MAX_REFINE_FACTOR {
+            return Some(Self {
+                refine_factor: Some((refine * 2).min(MAX_REFINE_FACTOR)),
+                ..*self
+            });
+        }
+        None
+    }
+}
+
+/// Share of `exact` ids that also appear in `approx` (1.0 when `exact` is empty)
+pub fn recall_at_k(exact: &[String], approx: &[String]) -> f32 {
+    if exact.is_empty() {
+        return 1.0;
+    }
+    let found = exact.iter().filter(|id| approx.contains(id)).count();
+    found as f32 / exact.len() as f32
 }
 
 /// Lightweight file-backed vector backend used as the default fallback.
+///
+/// Named collections live in `<path>/collections/<name>`.
 pub struct FileVectorBackend {
     index: VectorIndex,
     path: PathBuf,
     dimension: usize,
+    collections: RwLock<HashMap<String, Arc<VectorIndex>>>,
+    cipher: Option<Arc<VectorCipher>>,
+    quantization: VectorQuantization,
 }
 
 impl FileVectorBackend {
     pub fn new(path: impl AsRef<Path>, dimension: usize) -> Self {
-        let path = path.as_ref().to_path_buf();
+        let path = crate::platform_fs::long_path(path.as_ref());
         let index = VectorIndex::new(VectorSearchConfig {
             dimension,
             index_path: path.clone(),
+            quantization: VectorQuantization::None,
         });
         Self {
             index,
             path,
             dimension,
+            collections: RwLock::new(HashMap::new()),
+            cipher: None,
+            quantization: VectorQuantization::None,
         }
     }
 
+    /// Encrypt every vector file, collections included, with a key derived
+    /// from `key` and a salt kept in `<path>/.salt` (requires the
+    /// `encryption` feature). Reopening the index needs the same key.
+    pub fn with_encryption(mut self, key: &EncryptionKey) -> Result<Self> {
+        let cipher = Arc::new(VectorCipher::open(&self.path, key)?);
+        self.index = self.index.with_cipher(cipher.clone());
+        self.cipher = Some(cipher);
+        Ok(self)
+    }
+
+    /// Write vectors, collections included, with `quantization`. Existing
+    /// files keep their encoding and stay readable.
+    pub fn with_quantization(mut self, quantization: VectorQuantization) -> Self {
+        self.index = self.index.with_quantization(quantization);
+        self.quantization = quantization;
+        self
+    }
+
     pub async fn ensure_ready(&self) -> Result<()> {
         self.index.init().await
     }
@@ -50,6 +462,69 @@ impl FileVectorBackend {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Index for a named collection, created on first use
+    async fn collection_index(&self, name: &str) -> Result<Arc<VectorIndex>> {
+        if let Some(index) = self.collections.read().await.get(name) {
+            return Ok(index.clone());
+        }
+
+        validate_collection_name(name)?;
+        let mut guard = self.collections.write().await;
+        if let Some(index) = guard.get(name) {
+            return Ok(index.clone());
+        }
+
+        let mut index = VectorIndex::new(VectorSearchConfig {
+            dimension: self.dimension,
+            index_path: self.path.join("collections").join(name),
+            quantization: self.quantization,
+        });
+        if let Some(cipher) = &self.cipher {
+            index = index.with_cipher(cipher.clone());
+        }
+        let index = Arc::new(index);
+        index.init().await?;
+        guard.insert(name.to_string(), index.clone());
+        Ok(index)
+    }
+
+    /// Count stored vectors under `dir` (one `.bin` file each), recursing into collections
+    fn count_vectors(dir: &Path) -> Result<usize> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut count = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                count += Self::count_vectors(&path)?;
+            } else if path.extension().and_then(|s| s.to_str()) == Some("bin") {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn index_for(&self, collection: Option<&str>) -> Result<Option<Arc<VectorIndex>>> {
+        match named_collection(collection) {
+            None => Ok(None),
+            Some(name) => self.collection_index(name).await.map(Some),
+        }
+    }
+}
+
+fn hits_from(results: Vec<(String, f32)>) -> Vec<VectorSearchHit> {
+    results
+        .into_iter()
+        .map(|(id, score)| VectorSearchHit {
+            id,
+            score,
+            payload: None,
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -62,8 +537,8 @@ impl VectorBackend for FileVectorBackend {
         self.dimension
     }
 
-    async fn upsert(&self, id: &str, vector: &[f32], _payload: Option<Value>) -> Result<()> {
-        self.index.store(&id.to_string(), vector.to_vec()).await
+    async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+        self.upsert_in(None, id, vector, payload).await
     }
 
     async fn delete(&self, id: &str) -> Result<()> {
@@ -72,54 +547,286 @@ impl VectorBackend for FileVectorBackend {
 
     async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
         let results = self.index.search(vector, limit).await?;
-        Ok(results
-            .into_iter()
-            .map(|(id, score)| VectorSearchHit {
-                id,
-                score,
-                payload: None,
+        Ok(hits_from(results))
+    }
+
+    async fn search_filtered(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.search_filtered_in(None, vector, limit, filter).await
+    }
+
+    /// One file per vector: there is nothing to compact, so only row counts are reported
+    async fn stats(&self) -> Result<VectorIndexStats> {
+        let path = self.path.clone();
+        let rows = tokio::task::spawn_blocking(move || Self::count_vectors(&path))
+            .await
+            .map_err(|e| MemoryError::Storage(format!("Vector stats task failed: {e}")))??;
+        Ok(VectorIndexStats {
+            backend: self.name().to_string(),
+            rows,
+            ..Default::default()
+        })
+    }
+
+    fn supports_collections(&self) -> bool {
+        true
+    }
+
+    async fn upsert_in(
+        &self,
+        collection: Option<&str>,
+        id: &str,
+        vector: &[f32],
+        payload: Option<Value>,
+    ) -> Result<()> {
+        let named = self.index_for(collection).await?;
+        let index = named.as_deref().unwrap_or(&self.index);
+        let id = id.to_string();
+        index.store(&id, vector.to_vec()).await?;
+        index.store_payload(&id, payload.as_ref()).await
+    }
+
+    async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+        match self.index_for(collection).await? {
+            Some(index) => index.delete(&id.to_string()).await,
+            None => self.delete(id).await,
+        }
+    }
+
+    async fn search_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<VectorSearchHit>> {
+        match self.index_for(collection).await? {
+            Some(index) => Ok(hits_from(index.search(vector, limit).await?)),
+            None => self.search(vector, limit).await,
+        }
+    }
+
+    /// Payloads live next to the vectors, so the filter runs before scoring
+    async fn search_filtered_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        let named = self.index_for(collection).await?;
+        let index = named.as_deref().unwrap_or(&self.index);
+        let results = index
+            .search_where(vector, limit, |payload| filter.matches(payload))
+            .await?;
+        Ok(hits_from(results))
+    }
+}
+
+/// Vectors and payloads of one in-memory collection, by memory ID
+type MemoryCollection = HashMap<String, (Vec<f32>, Option<Value>)>;
+
+/// Vector backend that keeps everything in RAM, for tests and throwaway agents.
+///
+/// Search is an exact scan by cosine similarity.
+pub struct InMemoryVectorBackend {
+    dimension: usize,
+    /// Collections by name
+    collections: RwLock<HashMap<String, MemoryCollection>>,
+}
+
+impl InMemoryVectorBackend {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            collections: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn key(collection: Option<&str>) -> Result<String> {
+        match named_collection(collection) {
+            None => Ok(DEFAULT_COLLECTION.to_string()),
+            Some(name) => {
+                validate_collection_name(name)?;
+                Ok(name.to_string())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl VectorBackend for InMemoryVectorBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+        self.upsert_in(None, id, vector, payload).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.delete_in(None, id).await
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+        self.search_in(None, vector, limit).await
+    }
+
+    async fn stats(&self) -> Result<VectorIndexStats> {
+        let rows = self
+            .collections
+            .read()
+            .await
+            .values()
+            .map(HashMap::len)
+            .sum();
+        Ok(VectorIndexStats {
+            backend: self.name().to_string(),
+            rows,
+            ..Default::default()
+        })
+    }
+
+    fn supports_collections(&self) -> bool {
+        true
+    }
+
+    async fn upsert_in(
+        &self,
+        collection: Option<&str>,
+        id: &str,
+        vector: &[f32],
+        payload: Option<Value>,
+    ) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(MemoryError::VectorDb(format!(
+                "Vector dimension mismatch: expected {}, got {}",
+                self.dimension,
+                vector.len()
+            )));
+        }
+        let key = Self::key(collection)?;
+        self.collections
+            .write()
+            .await
+            .entry(key)
+            .or_default()
+            .insert(id.to_string(), (vector.to_vec(), payload));
+        Ok(())
+    }
+
+    async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+        let key = Self::key(collection)?;
+        if let Some(vectors) = self.collections.write().await.get_mut(&key) {
+            vectors.remove(id);
+        }
+        Ok(())
+    }
+
+    async fn search_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.search_filtered_in(collection, vector, limit, &VectorFilter::default())
+            .await
+    }
+
+    async fn search_filtered(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.search_filtered_in(None, vector, limit, filter).await
+    }
+
+    async fn search_filtered_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        let key = Self::key(collection)?;
+        let collections = self.collections.read().await;
+        let Some(vectors) = collections.get(&key) else {
+            return Ok(Vec::new());
+        };
+        let mut hits: Vec<VectorSearchHit> = vectors
+            .iter()
+            .filter(|(_, (_, payload))| filter.matches(payload.as_ref()))
+            .map(|(id, (v, payload))| VectorSearchHit {
+                id: id.clone(),
+                score: cosine_similarity(vector, v),
+                payload: payload.clone(),
             })
-            .collect())
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
     }
 }
 
 #[cfg(feature = "lancedb")]
 pub mod lancedb {
     use super::*;
-    use crate::error::MemoryError;
-    use ::lancedb::connect;
     use ::lancedb::index::vector::{IvfFlatIndexBuilder, IvfPqIndexBuilder};
     use ::lancedb::index::{Index, IndexType};
-    use ::lancedb::query::{ExecutableQuery, QueryBase};
-    use ::lancedb::table::Table;
-    use ::lancedb::DistanceType;
+    use ::lancedb::query::{ExecutableQuery, QueryBase, Select};
+    use ::lancedb::table::{OptimizeAction, Table};
+    use ::lancedb::{connect, Connection};
     use arrow_array::{
         types::Float32Type, Array, ArrayRef, FixedSizeListArray, Float32Array, Float64Array,
         RecordBatch, RecordBatchIterator, StringArray,
     };
     use arrow_schema::{DataType, Field, Schema};
     use futures::StreamExt;
-    use std::path::{Path, PathBuf};
-    use std::sync::Arc;
-    use tokio::sync::RwLock;
+    use std::collections::HashSet;
+
+    pub use ::lancedb::DistanceType;
 
+    /// ANN index built on each table
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    enum AnnIndexKind {
+    pub enum AnnIndexKind {
+        /// IVF with product quantization: compact, distances are approximate
         IvfPq,
+        /// IVF over the full vectors: larger, exact distances within probed partitions
         IvfFlat,
     }
 
+    /// ANN index and query settings of a [`LanceDbVectorBackend`].
+    ///
+    /// [`LanceDbVectorBackend::new`] reads them from the `GOLDFISH_LANCEDB_*`
+    /// environment variables; [`LanceDbVectorBackend::new_with_config`] takes them as is.
     #[derive(Debug, Clone, Copy)]
-    struct LanceDbAnnConfig {
-        enabled: bool,
-        kind: AnnIndexKind,
-        distance_type: DistanceType,
-        nprobes: usize,
-        refine_factor: Option<u32>,
-        min_rows_for_index: usize,
+    pub struct LanceDbConfig {
+        /// Build an ANN index; without one every search scans the table
+        pub enabled: bool,
+        pub kind: AnnIndexKind,
+        pub distance_type: DistanceType,
+        /// IVF partitions probed per query, when not auto-tuned
+        pub nprobes: usize,
+        /// Re-rank `refine_factor * limit` candidates by exact distance, when not auto-tuned
+        pub refine_factor: Option<u32>,
+        /// Tables smaller than this are scanned without an index
+        pub min_rows_for_index: usize,
+        /// Derive IVF parameters from corpus size and measured recall instead of the
+        /// static `nprobes`/`refine_factor` above
+        pub auto_tune: bool,
+        /// Recall@10 auto-tuning aims for
+        pub target_recall: f32,
     }
 
-    impl Default for LanceDbAnnConfig {
+    impl Default for LanceDbConfig {
         fn default() -> Self {
             Self {
                 enabled: true,
@@ -128,12 +835,58 @@ pub mod lancedb {
                 nprobes: 24,
                 refine_factor: Some(2),
                 min_rows_for_index: 256,
+                auto_tune: true,
+                target_recall: 0.95,
             }
         }
     }
 
-    impl LanceDbAnnConfig {
-        fn from_env() -> Self {
+    impl LanceDbConfig {
+        /// Build an index of `kind`
+        pub fn with_ann(mut self, kind: AnnIndexKind) -> Self {
+            self.enabled = true;
+            self.kind = kind;
+            self
+        }
+
+        /// Never build an ANN index
+        pub fn without_ann(mut self) -> Self {
+            self.enabled = false;
+            self
+        }
+
+        pub fn with_distance_type(mut self, distance_type: DistanceType) -> Self {
+            self.distance_type = distance_type;
+            self
+        }
+
+        pub fn with_nprobes(mut self, nprobes: usize) -> Self {
+            self.nprobes = nprobes.max(1);
+            self
+        }
+
+        pub fn with_refine_factor(mut self, refine_factor: Option<u32>) -> Self {
+            self.refine_factor = refine_factor.map(|f| f.max(1));
+            self
+        }
+
+        pub fn with_min_rows_for_index(mut self, min_rows: usize) -> Self {
+            self.min_rows_for_index = min_rows.max(1);
+            self
+        }
+
+        pub fn with_auto_tune(mut self, auto_tune: bool) -> Self {
+            self.auto_tune = auto_tune;
+            self
+        }
+
+        pub fn with_target_recall(mut self, target_recall: f32) -> Self {
+            self.target_recall = target_recall.clamp(0.0, 1.0);
+            self
+        }
+
+        /// Defaults, overridden by the `GOLDFISH_LANCEDB_*` environment variables
+        pub fn from_env() -> Self {
             let mut cfg = Self::default();
 
             if let Ok(v) = std::env::var("GOLDFISH_LANCEDB_ANN") {
@@ -180,84 +933,263 @@ pub mod lancedb {
                 }
             }
 
+            if let Ok(v) = std::env::var("GOLDFISH_LANCEDB_AUTOTUNE") {
+                let norm = v.trim().to_lowercase();
+                cfg.auto_tune = !matches!(norm.as_str(), "off" | "none" | "false" | "0");
+            }
+
+            if let Ok(v) = std::env::var("GOLDFISH_LANCEDB_TARGET_RECALL") {
+                if let Ok(parsed) = v.parse::<f32>() {
+                    cfg.target_recall = parsed.clamp(0.0, 1.0);
+                }
+            }
+
             cfg
         }
     }
 
+    const DEFAULT_TABLE: &str = "goldfish_vectors";
+
+    /// Stored vectors reused as queries when measuring ANN recall
+    const TUNING_SAMPLE_SIZE: usize = 32;
+    const TUNING_K: usize = 10;
+
+    /// Parameters picked for one table, and the corpus size they were picked at
+    #[derive(Debug, Clone, Copy)]
+    struct TunedAnn {
+        params: AnnParams,
+        rows: usize,
+        recall: f32,
+    }
+
+    /// Default collection maps to `goldfish_vectors`; named collections get their own table.
+    fn table_name(collection: Option<&str>) -> String {
+        match named_collection(collection) {
+            None => DEFAULT_TABLE.to_string(),
+            Some(name) => format!("{DEFAULT_TABLE}__{name}"),
+        }
+    }
+
+    /// Payload fields copied into their own columns so filters run inside LanceDB
+    const FILTER_COLUMNS: [&str; 4] = ["memory_type", "session_id", "namespace", "tags"];
+
+    /// Column value for a payload field; tags are stored as `,a,b,` for `LIKE`
+    fn filter_column_value(payload: &Value, column: &str) -> Option<String> {
+        if column == "tags" {
+            let tags: Vec<&str> = payload
+                .get("tags")?
+                .as_array()?
+                .iter()
+                .filter_map(Value::as_str)
+                .collect();
+            return Some(format!(",{},", tags.join(",")));
+        }
+        payload.get(column)?.as_str().map(str::to_string)
+    }
+
+    fn sql_string(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// SQL predicate for the filter columns. Tag `LIKE` patterns may match too much
+    /// when a tag contains `%` or `_`; the payload check afterwards is exact.
+    fn filter_predicate(filter: &VectorFilter) -> String {
+        let mut clauses = Vec::new();
+        if !filter.memory_types.is_empty() {
+            let types: Vec<String> = filter
+                .memory_types
+                .iter()
+                .map(|t| sql_string(&t.to_string()))
+                .collect();
+            clauses.push(format!("memory_type IN ({})", types.join(", ")));
+        }
+        if let Some(session_id) = &filter.session_id {
+            clauses.push(format!("session_id = {}", sql_string(session_id)));
+        }
+        if let Some(namespace) = &filter.namespace {
+            clauses.push(format!("namespace = {}", sql_string(namespace)));
+        }
+        for tag in &filter.tags {
+            clauses.push(format!("tags LIKE {}", sql_string(&format!("%,{tag},%"))));
+        }
+        clauses.join(" AND ")
+    }
+
     #[derive(Debug)]
     pub struct LanceDbVectorBackend {
         path: PathBuf,
         dimension: usize,
-        table: RwLock<Option<Table>>,
-        ann_cfg: LanceDbAnnConfig,
-        ann_index_ready: RwLock<bool>,
+        tables: RwLock<HashMap<String, Table>>,
+        ann_cfg: LanceDbConfig,
+        ann_index_ready: RwLock<HashSet<String>>,
+        tuned: RwLock<HashMap<String, TunedAnn>>,
+        /// Tables with the filter columns; older tables only filter payloads after ranking
+        filterable: RwLock<HashSet<String>>,
     }
 
     impl LanceDbVectorBackend {
+        /// Backend configured from the environment, see [`LanceDbConfig::from_env`]
         pub fn new(path: impl AsRef<Path>, dimension: usize) -> Self {
+            Self::new_with_config(path, dimension, LanceDbConfig::from_env())
+        }
+
+        pub fn new_with_config(
+            path: impl AsRef<Path>,
+            dimension: usize,
+            config: LanceDbConfig,
+        ) -> Self {
             Self {
                 path: path.as_ref().to_path_buf(),
                 dimension,
-                table: RwLock::new(None),
-                ann_cfg: LanceDbAnnConfig::from_env(),
-                ann_index_ready: RwLock::new(false),
+                tables: RwLock::new(HashMap::new()),
+                ann_cfg: config,
+                ann_index_ready: RwLock::new(HashSet::new()),
+                tuned: RwLock::new(HashMap::new()),
+                filterable: RwLock::new(HashSet::new()),
             }
         }
 
-        pub async fn ensure_ready(&self) -> Result<()> {
-            let _ = self.get_or_init_table().await?;
-            Ok(())
+        pub fn config(&self) -> &LanceDbConfig {
+            &self.ann_cfg
         }
 
-        async fn get_or_init_table(&self) -> Result<Table> {
-            {
-                let guard = self.table.read().await;
-                if let Some(t) = guard.as_ref() {
-                    return Ok(t.clone());
-                }
-            }
+        /// Recreate the ANN index of `collection` now, e.g. after a bulk load, and
+        /// re-tune its query parameters when auto-tuning. Returns `false` without
+        /// touching the table when ANN is disabled or the table is below
+        /// `min_rows_for_index`.
+        pub async fn rebuild_ann_index(&self, collection: Option<&str>) -> Result<bool> {
+            let table = self.get_or_init_table(collection).await?;
+            self.rebuild_table_index(&table).await
+        }
 
-            let mut guard = self.table.write().await;
-            if let Some(t) = guard.as_ref() {
-                return Ok(t.clone());
+        async fn rebuild_table_index(&self, table: &Table) -> Result<bool> {
+            let rows = table
+                .count_rows(None)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB count_rows failed: {e}")))?;
+            if !self.ann_cfg.enabled || rows < self.ann_cfg.min_rows_for_index {
+                return Ok(false);
             }
+            if self.ann_cfg.auto_tune {
+                self.tune(table, true).await?;
+            } else {
+                self.create_ann_index(table, true, None).await?;
+            }
+            self.ann_index_ready
+                .write()
+                .await
+                .insert(table.name().to_string());
+            Ok(true)
+        }
 
-            std::fs::create_dir_all(&self.path)
+        /// Table schema; `filterable` adds the [`VectorFilter`] columns
+        fn schema(&self, filterable: bool) -> Arc<Schema> {
+            let mut fields = vec![
+                Field::new("id", DataType::Utf8, false),
+                Field::new(
+                    "vector",
+                    DataType::FixedSizeList(
+                        Arc::new(Field::new("item", DataType::Float32, true)),
+                        self.dimension as i32,
+                    ),
+                    false,
+                ),
+                Field::new("payload", DataType::Utf8, true),
+            ];
+            if filterable {
+                fields.extend(
+                    FILTER_COLUMNS
+                        .iter()
+                        .map(|name| Field::new(*name, DataType::Utf8, true)),
+                );
+            }
+            Arc::new(Schema::new(fields))
+        }
+
+        pub async fn ensure_ready(&self) -> Result<()> {
+            let _ = self.get_or_init_table(None).await?;
+            Ok(())
+        }
+
+        async fn connect_db(&self) -> Result<Connection> {
+            std::fs::create_dir_all(&self.path)
                 .map_err(|e| MemoryError::VectorDb(format!("Failed to create lancedb dir: {e}")))?;
 
             let uri = self.path.to_string_lossy().to_string();
-            let db = connect(&uri)
+            connect(&uri)
                 .execute()
                 .await
-                .map_err(|e| MemoryError::VectorDb(format!("Failed to connect to lancedb: {e}")))?;
+                .map_err(|e| MemoryError::VectorDb(format!("Failed to connect to lancedb: {e}")))
+        }
 
-            let table_name = "goldfish_vectors";
-            let table = match db.open_table(table_name).execute().await {
-                Ok(t) => t,
-                Err(_) => {
-                    let schema = Arc::new(Schema::new(vec![
-                        Field::new("id", DataType::Utf8, false),
-                        Field::new(
-                            "vector",
-                            DataType::FixedSizeList(
-                                Arc::new(Field::new("item", DataType::Float32, true)),
-                                self.dimension as i32,
-                            ),
-                            false,
-                        ),
-                        Field::new("payload", DataType::Utf8, true),
-                    ]));
-
-                    db.create_empty_table(table_name, schema)
-                        .execute()
-                        .await
-                        .map_err(|e| {
-                            MemoryError::VectorDb(format!("Failed to create lancedb table: {e}"))
-                        })?
+        /// Every goldfish table on disk: the default collection plus named ones
+        async fn all_tables(&self) -> Result<Vec<Table>> {
+            let db = self.connect_db().await?;
+            let names =
+                db.table_names().execute().await.map_err(|e| {
+                    MemoryError::VectorDb(format!("LanceDB table_names failed: {e}"))
+                })?;
+
+            let mut tables = Vec::new();
+            for name in names.iter().filter(|n| n.starts_with(DEFAULT_TABLE)) {
+                if let Some(t) = self.tables.read().await.get(name) {
+                    tables.push(t.clone());
+                    continue;
+                }
+                let table = db.open_table(name).execute().await.map_err(|e| {
+                    MemoryError::VectorDb(format!("Failed to open lancedb table {name}: {e}"))
+                })?;
+                self.tables
+                    .write()
+                    .await
+                    .insert(name.clone(), table.clone());
+                tables.push(table);
+            }
+            Ok(tables)
+        }
+
+        async fn get_or_init_table(&self, collection: Option<&str>) -> Result<Table> {
+            if let Some(name) = named_collection(collection) {
+                validate_collection_name(name)?;
+            }
+            let table_name = table_name(collection);
+
+            {
+                let guard = self.tables.read().await;
+                if let Some(t) = guard.get(&table_name) {
+                    return Ok(t.clone());
                 }
+            }
+
+            let mut guard = self.tables.write().await;
+            if let Some(t) = guard.get(&table_name) {
+                return Ok(t.clone());
+            }
+
+            let db = self.connect_db().await?;
+            let table = match db.open_table(&table_name).execute().await {
+                Ok(t) => t,
+                Err(_) => db
+                    .create_empty_table(&table_name, self.schema(true))
+                    .execute()
+                    .await
+                    .map_err(|e| {
+                        MemoryError::VectorDb(format!("Failed to create lancedb table: {e}"))
+                    })?,
             };
 
-            *guard = Some(table.clone());
+            let schema = table
+                .schema()
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB schema failed: {e}")))?;
+            if FILTER_COLUMNS
+                .iter()
+                .all(|name| schema.field_with_name(name).is_ok())
+            {
+                self.filterable.write().await.insert(table_name.clone());
+            }
+
+            guard.insert(table_name, table.clone());
             Ok(table)
         }
 
@@ -268,13 +1200,13 @@ pub mod lancedb {
 
             {
                 let guard = self.ann_index_ready.read().await;
-                if *guard {
+                if guard.contains(table.name()) {
                     return Ok(());
                 }
             }
 
             let mut guard = self.ann_index_ready.write().await;
-            if *guard {
+            if guard.contains(table.name()) {
                 return Ok(());
             }
 
@@ -295,7 +1227,11 @@ pub mod lancedb {
                     )
             });
             if has_vector_ann {
-                *guard = true;
+                if self.ann_cfg.auto_tune {
+                    // Index built by an earlier process: keep it, only pick query parameters.
+                    self.tune(table, false).await?;
+                }
+                guard.insert(table.name().to_string());
                 return Ok(());
             }
 
@@ -307,26 +1243,196 @@ pub mod lancedb {
                 return Ok(());
             }
 
+            if self.ann_cfg.auto_tune {
+                self.tune(table, true).await?;
+            } else {
+                self.create_ann_index(table, false, None).await?;
+            }
+            guard.insert(table.name().to_string());
+            Ok(())
+        }
+
+        async fn create_ann_index(
+            &self,
+            table: &Table,
+            replace: bool,
+            num_partitions: Option<u32>,
+        ) -> Result<()> {
             let index = match self.ann_cfg.kind {
                 AnnIndexKind::IvfPq => {
-                    Index::IvfPq(IvfPqIndexBuilder::default().distance_type(self.ann_cfg.distance_type))
+                    let mut builder =
+                        IvfPqIndexBuilder::default().distance_type(self.ann_cfg.distance_type);
+                    if let Some(n) = num_partitions {
+                        builder = builder.num_partitions(n);
+                    }
+                    Index::IvfPq(builder)
                 }
                 AnnIndexKind::IvfFlat => {
-                    Index::IvfFlat(
-                        IvfFlatIndexBuilder::default().distance_type(self.ann_cfg.distance_type),
-                    )
+                    let mut builder =
+                        IvfFlatIndexBuilder::default().distance_type(self.ann_cfg.distance_type);
+                    if let Some(n) = num_partitions {
+                        builder = builder.num_partitions(n);
+                    }
+                    Index::IvfFlat(builder)
                 }
             };
 
             table
                 .create_index(&["vector"], index)
                 .name("goldfish_vector_ann".to_string())
-                .replace(false)
+                .replace(replace)
+                .execute()
+                .await
+                .map_err(|e| {
+                    MemoryError::VectorDb(format!("LanceDB ANN index creation failed: {e}"))
+                })
+        }
+
+        /// Pick IVF parameters for `table` from its row count, then raise `nprobes` and the
+        /// refine factor until recall on a sample of stored vectors reaches the target.
+        /// With `build_index`, the ANN index is (re)built with the chosen partition count.
+        async fn tune(&self, table: &Table, build_index: bool) -> Result<TunedAnn> {
+            let rows = table
+                .count_rows(None)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB count_rows failed: {e}")))?;
+            let mut params = AnnParams::for_rows(rows);
+            if build_index {
+                self.create_ann_index(table, true, Some(params.num_partitions))
+                    .await?;
+            }
+
+            let queries = self.sample_vectors(table, TUNING_SAMPLE_SIZE).await?;
+            let mut exact = Vec::with_capacity(queries.len());
+            for q in &queries {
+                exact.push(self.search_ids(table, q, TUNING_K, None).await?);
+            }
+
+            let mut recall = self.measure_recall(table, &queries, &exact, params).await?;
+            while recall < self.ann_cfg.target_recall {
+                let Some(next) = params.escalate() else {
+                    break;
+                };
+                params = next;
+                recall = self.measure_recall(table, &queries, &exact, params).await?;
+            }
+
+            tracing::debug!(
+                "Tuned ANN for {}: {} rows, {:?}, recall@{} {:.3}",
+                table.name(),
+                rows,
+                params,
+                TUNING_K,
+                recall
+            );
+            let tuned = TunedAnn {
+                params,
+                rows,
+                recall,
+            };
+            self.tuned
+                .write()
+                .await
+                .insert(table.name().to_string(), tuned);
+            Ok(tuned)
+        }
+
+        async fn measure_recall(
+            &self,
+            table: &Table,
+            queries: &[Vec<f32>],
+            exact: &[Vec<String>],
+            params: AnnParams,
+        ) -> Result<f32> {
+            if queries.is_empty() {
+                return Ok(1.0);
+            }
+            let mut total = 0.0;
+            for (q, truth) in queries.iter().zip(exact) {
+                let approx = self.search_ids(table, q, TUNING_K, Some(params)).await?;
+                total += recall_at_k(truth, &approx);
+            }
+            Ok(total / queries.len() as f32)
+        }
+
+        /// Ids of the nearest rows; `None` params means exact (flat) search
+        async fn search_ids(
+            &self,
+            table: &Table,
+            vector: &[f32],
+            k: usize,
+            params: Option<AnnParams>,
+        ) -> Result<Vec<String>> {
+            let mut query = table
+                .vector_search(vector)
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB vector_search failed: {e}")))?
+                .distance_type(self.ann_cfg.distance_type)
+                .select(Select::columns(&["id"]))
+                .limit(k);
+            query = match params {
+                Some(p) => {
+                    query = query.nprobes(p.nprobes);
+                    match p.refine_factor {
+                        Some(refine) => query.refine_factor(refine),
+                        None => query,
+                    }
+                }
+                None => query.bypass_vector_index(),
+            };
+
+            let mut stream = query
+                .execute()
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB search failed: {e}")))?;
+            let mut ids = Vec::new();
+            while let Some(batch) = stream.next().await {
+                let batch = batch
+                    .map_err(|e| MemoryError::VectorDb(format!("LanceDB stream error: {e}")))?;
+                if let Some(col) = batch
+                    .column_by_name("id")
+                    .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+                {
+                    ids.extend((0..col.len()).map(|i| col.value(i).to_string()));
+                }
+            }
+            Ok(ids)
+        }
+
+        /// Up to `n` stored vectors, used as a query set for recall measurement
+        async fn sample_vectors(&self, table: &Table, n: usize) -> Result<Vec<Vec<f32>>> {
+            let mut stream = table
+                .query()
+                .select(Select::columns(&["vector"]))
+                .limit(n)
                 .execute()
                 .await
-                .map_err(|e| MemoryError::VectorDb(format!("LanceDB ANN index creation failed: {e}")))?;
+                .map_err(|e| MemoryError::VectorDb(format!("LanceDB sample query failed: {e}")))?;
+
+            let mut vectors = Vec::new();
+            while let Some(batch) = stream.next().await {
+                let batch = batch
+                    .map_err(|e| MemoryError::VectorDb(format!("LanceDB stream error: {e}")))?;
+                let Some(col) = batch
+                    .column_by_name("vector")
+                    .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>())
+                else {
+                    continue;
+                };
+                for row in 0..col.len() {
+                    let values = col.value(row);
+                    if let Some(values) = values.as_any().downcast_ref::<Float32Array>() {
+                        vectors.push(values.values().to_vec());
+                    }
+                }
+            }
+            Ok(vectors)
+        }
 
-            *guard = true;
+        /// Compact small fragments, prune old versions and fold new rows into indices
+        async fn optimize(&self, table: &Table) -> Result<()> {
+            table.optimize(OptimizeAction::All).await.map_err(|e| {
+                MemoryError::VectorDb(format!("LanceDB optimize of {} failed: {e}", table.name()))
+            })?;
             Ok(())
         }
 
@@ -335,6 +1441,7 @@ pub mod lancedb {
             id: &str,
             vector: &[f32],
             payload: Option<Value>,
+            filterable: bool,
         ) -> Result<RecordBatch> {
             if vector.len() != self.dimension {
                 return Err(MemoryError::VectorDb(format!(
@@ -352,23 +1459,18 @@ pub mod lancedb {
             );
             let vectors: ArrayRef = Arc::new(list);
 
-            let payload_str = payload.map(|p| p.to_string());
+            let payload_str = payload.as_ref().map(|p| p.to_string());
             let payloads: ArrayRef = Arc::new(StringArray::from(vec![payload_str]));
 
-            let schema = Arc::new(Schema::new(vec![
-                Field::new("id", DataType::Utf8, false),
-                Field::new(
-                    "vector",
-                    DataType::FixedSizeList(
-                        Arc::new(Field::new("item", DataType::Float32, true)),
-                        self.dimension as i32,
-                    ),
-                    false,
-                ),
-                Field::new("payload", DataType::Utf8, true),
-            ]));
+            let mut columns = vec![ids, vectors, payloads];
+            if filterable {
+                columns.extend(FILTER_COLUMNS.iter().map(|name| {
+                    let value = payload.as_ref().and_then(|p| filter_column_value(p, name));
+                    Arc::new(StringArray::from(vec![value])) as ArrayRef
+                }));
+            }
 
-            RecordBatch::try_new(schema, vec![ids, vectors, payloads])
+            RecordBatch::try_new(self.schema(filterable), columns)
                 .map_err(|e| MemoryError::VectorDb(format!("Failed to build record batch: {e}")))
         }
     }
@@ -384,12 +1486,107 @@ pub mod lancedb {
         }
 
         async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
-            let table = self.get_or_init_table().await?;
+            self.upsert_in(None, id, vector, payload).await
+        }
+
+        async fn delete(&self, id: &str) -> Result<()> {
+            self.delete_in(None, id).await
+        }
+
+        async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+            self.search_in(None, vector, limit).await
+        }
+
+        async fn stats(&self) -> Result<VectorIndexStats> {
+            let mut stats = VectorIndexStats {
+                backend: self.name().to_string(),
+                ..Default::default()
+            };
+            for table in self.all_tables().await? {
+                let table_stats = table.stats().await.map_err(|e| {
+                    MemoryError::VectorDb(format!("LanceDB stats of {} failed: {e}", table.name()))
+                })?;
+                stats.rows += table_stats.num_rows;
+                stats.indices += table_stats.num_indices;
+                stats.fragments += table_stats.fragment_stats.num_fragments;
+                stats.small_fragments += table_stats.fragment_stats.num_small_fragments;
+            }
+            Ok(stats)
+        }
+
+        /// Every upsert is a delete plus an append, so small fragments and deletion
+        /// files pile up; `optimize` merges them and prunes old table versions.
+        async fn maintain(&self) -> Result<VectorMaintenanceReport> {
+            let before = self.stats().await?;
+            let mut actions = Vec::new();
+            for table in self.all_tables().await? {
+                self.optimize(&table).await?;
+                actions.push(format!("optimized {}", table.name()));
+                self.ensure_ann_index(&table).await?;
+
+                let previous = self.tuned.read().await.get(table.name()).copied();
+                if let Some(previous) = previous {
+                    let rows = table.count_rows(None).await.map_err(|e| {
+                        MemoryError::VectorDb(format!("LanceDB count_rows failed: {e}"))
+                    })?;
+                    if rows >= previous.rows.max(1) * 2 {
+                        let tuned = self.tune(&table, true).await?;
+                        actions.push(format!(
+                            "re-tuned ANN index on {} ({} -> {} rows, recall {:.3})",
+                            table.name(),
+                            previous.rows,
+                            tuned.rows,
+                            tuned.recall
+                        ));
+                    }
+                }
+            }
+            let after = self.stats().await?;
+            Ok(VectorMaintenanceReport {
+                before,
+                after,
+                actions,
+            })
+        }
+
+        /// Compact every table and recreate its ANN index from scratch
+        async fn rebuild(&self) -> Result<VectorMaintenanceReport> {
+            let before = self.stats().await?;
+            let mut actions = Vec::new();
+            for table in self.all_tables().await? {
+                self.optimize(&table).await?;
+                actions.push(format!("optimized {}", table.name()));
+
+                if self.rebuild_table_index(&table).await? {
+                    actions.push(format!("rebuilt ANN index on {}", table.name()));
+                }
+            }
+            let after = self.stats().await?;
+            Ok(VectorMaintenanceReport {
+                before,
+                after,
+                actions,
+            })
+        }
+
+        fn supports_collections(&self) -> bool {
+            true
+        }
+
+        async fn upsert_in(
+            &self,
+            collection: Option<&str>,
+            id: &str,
+            vector: &[f32],
+            payload: Option<Value>,
+        ) -> Result<()> {
+            let table = self.get_or_init_table(collection).await?;
+            let filterable = self.filterable.read().await.contains(table.name());
 
             // Best-effort delete first to avoid duplicates without requiring a primary key.
-            let _ = self.delete(id).await;
+            let _ = self.delete_in(collection, id).await;
 
-            let batch = self.batch_for_upsert(id, vector, payload)?;
+            let batch = self.batch_for_upsert(id, vector, payload, filterable)?;
             let schema = batch.schema();
             let batches = Box::new(RecordBatchIterator::new(vec![Ok(batch)], schema));
 
@@ -402,8 +1599,8 @@ pub mod lancedb {
             Ok(())
         }
 
-        async fn delete(&self, id: &str) -> Result<()> {
-            let table = self.get_or_init_table().await?;
+        async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+            let table = self.get_or_init_table(collection).await?;
             let escaped = id.replace('\'', "''");
             table
                 .delete(&format!("id = '{escaped}'"))
@@ -412,7 +1609,34 @@ pub mod lancedb {
             Ok(())
         }
 
-        async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+        async fn search_in(
+            &self,
+            collection: Option<&str>,
+            vector: &[f32],
+            limit: usize,
+        ) -> Result<Vec<VectorSearchHit>> {
+            self.search_filtered_in(collection, vector, limit, &VectorFilter::default())
+                .await
+        }
+
+        async fn search_filtered(
+            &self,
+            vector: &[f32],
+            limit: usize,
+            filter: &VectorFilter,
+        ) -> Result<Vec<VectorSearchHit>> {
+            self.search_filtered_in(None, vector, limit, filter).await
+        }
+
+        /// Tables with filter columns prefilter in LanceDB; older tables over-fetch and
+        /// filter payloads. Either way, payloads are checked again before returning.
+        async fn search_filtered_in(
+            &self,
+            collection: Option<&str>,
+            vector: &[f32],
+            limit: usize,
+            filter: &VectorFilter,
+        ) -> Result<Vec<VectorSearchHit>> {
             if vector.len() != self.dimension {
                 return Err(MemoryError::VectorDb(format!(
                     "Vector dimension mismatch: got {}, expected {}",
@@ -421,7 +1645,7 @@ pub mod lancedb {
                 )));
             }
 
-            let table = self.get_or_init_table().await?;
+            let table = self.get_or_init_table(collection).await?;
             self.ensure_ann_index(&table).await?;
 
             let mut query = table
@@ -429,16 +1653,30 @@ pub mod lancedb {
                 .map_err(|e| MemoryError::VectorDb(format!("LanceDB vector_search failed: {e}")))?;
 
             if self.ann_cfg.enabled {
+                let tuned = self.tuned.read().await.get(table.name()).map(|t| t.params);
+                let (nprobes, refine_factor) = match tuned {
+                    Some(params) => (params.nprobes, params.refine_factor),
+                    None => (self.ann_cfg.nprobes, self.ann_cfg.refine_factor),
+                };
                 query = query
                     .distance_type(self.ann_cfg.distance_type)
-                    .nprobes(self.ann_cfg.nprobes);
-                if let Some(refine) = self.ann_cfg.refine_factor {
+                    .nprobes(nprobes);
+                if let Some(refine) = refine_factor {
                     query = query.refine_factor(refine);
                 }
             }
 
+            let mut fetch = limit;
+            if !filter.is_empty() {
+                if self.filterable.read().await.contains(table.name()) {
+                    query = query.only_if(filter_predicate(filter));
+                } else {
+                    fetch = limit.saturating_mul(FILTER_OVERFETCH);
+                }
+            }
+
             let mut stream = query
-                .limit(limit)
+                .limit(fetch)
                 .execute()
                 .await
                 .map_err(|e| MemoryError::VectorDb(format!("LanceDB search failed: {e}")))?;
@@ -503,7 +1741,9 @@ pub mod lancedb {
                         1.0
                     };
 
-                    hits.push(VectorSearchHit { id, score, payload });
+                    if filter.matches(payload.as_ref()) {
+                        hits.push(VectorSearchHit { id, score, payload });
+                    }
                 }
             }
 
@@ -518,6 +1758,605 @@ pub mod lancedb {
     }
 }
 
+#[cfg(feature = "sqlite-vec")]
+pub mod sqlite_vec {
+    //! Vectors stored next to the memories in `memories.db`, using `vec0` virtual
+    //! tables from the [sqlite-vec](https://github.com/asg017/sqlite-vec) extension.
+
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+    use sqlx::{Row, SqlitePool};
+    use std::collections::HashSet;
+    use std::sync::Once;
+
+    const DEFAULT_TABLE: &str = "vec_memories";
+
+    /// Register sqlite-vec as an auto-extension so every new SQLite connection in
+    /// this process can create `vec0` tables. Safe to call more than once.
+    pub fn register_extension() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| {
+            // SAFETY: `sqlite3_vec_init` has the signature SQLite expects from an
+            // extension entry point; registration happens once, before connections open.
+            unsafe {
+                #[allow(clippy::missing_transmute_annotations)]
+                libsqlite3_sys::sqlite3_auto_extension(Some(std::mem::transmute(
+                    ::sqlite_vec::sqlite3_vec_init as *const (),
+                )));
+            }
+        });
+    }
+
+    /// Returned double-quoted, since collection names may contain `-`
+    fn table_name(collection: Option<&str>) -> String {
+        match named_collection(collection) {
+            None => format!("\"{DEFAULT_TABLE}\""),
+            Some(name) => format!("\"{DEFAULT_TABLE}__{name}\""),
+        }
+    }
+
+    fn to_blob(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// Vector backend living in the same SQLite file as the memories.
+    ///
+    /// Uses cosine distance; scores are reported as `1 - distance`.
+    #[derive(Debug)]
+    pub struct SqliteVecBackend {
+        pool: SqlitePool,
+        dimension: usize,
+        created: RwLock<HashSet<String>>,
+    }
+
+    impl SqliteVecBackend {
+        /// Open (or create) the vector tables in the SQLite database at `path`,
+        /// normally `<data_dir>/memories.db`.
+        pub async fn connect(path: impl AsRef<Path>, dimension: usize) -> Result<Self> {
+            register_extension();
+            let options = SqliteConnectOptions::new()
+                .filename(path.as_ref())
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal);
+            let pool = SqlitePool::connect_with(options).await?;
+            Self::from_pool(pool, dimension).await
+        }
+
+        /// Use an existing pool. Its connections must have been opened after
+        /// [`register_extension`] was called.
+        pub async fn from_pool(pool: SqlitePool, dimension: usize) -> Result<Self> {
+            let version: String = sqlx::query_scalar("SELECT vec_version()")
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("sqlite-vec is not loaded: {e}")))?;
+            tracing::debug!("sqlite-vec {version} loaded");
+
+            let backend = Self {
+                pool,
+                dimension,
+                created: RwLock::new(HashSet::new()),
+            };
+            backend.ensure_table(None).await?;
+            Ok(backend)
+        }
+
+        async fn ensure_table(&self, collection: Option<&str>) -> Result<String> {
+            if let Some(name) = named_collection(collection) {
+                validate_collection_name(name)?;
+            }
+            let table = table_name(collection);
+            if self.created.read().await.contains(&table) {
+                return Ok(table);
+            }
+
+            let sql = format!(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS {table} USING vec0(\
+                 memory_id TEXT PRIMARY KEY, \
+                 embedding float[{}] distance_metric=cosine, \
+                 +payload TEXT)",
+                self.dimension
+            );
+            sqlx::query(&sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Failed to create {table}: {e}")))?;
+
+            self.created.write().await.insert(table.clone());
+            Ok(table)
+        }
+
+        fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+            if vector.len() != self.dimension {
+                return Err(MemoryError::VectorDb(format!(
+                    "Vector dimension mismatch: got {}, expected {}",
+                    vector.len(),
+                    self.dimension
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl VectorBackend for SqliteVecBackend {
+        fn name(&self) -> &'static str {
+            "sqlite-vec"
+        }
+
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+            self.upsert_in(None, id, vector, payload).await
+        }
+
+        async fn delete(&self, id: &str) -> Result<()> {
+            self.delete_in(None, id).await
+        }
+
+        async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+            self.search_in(None, vector, limit).await
+        }
+
+        async fn stats(&self) -> Result<VectorIndexStats> {
+            let tables: Vec<String> = sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND sql LIKE '%USING vec0%'",
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            let mut rows = 0;
+            for table in tables.iter().filter(|t| t.starts_with(DEFAULT_TABLE)) {
+                let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{table}\""))
+                    .fetch_one(&self.pool)
+                    .await?;
+                rows += count as usize;
+            }
+            Ok(VectorIndexStats {
+                backend: self.name().to_string(),
+                rows,
+                ..Default::default()
+            })
+        }
+
+        fn supports_collections(&self) -> bool {
+            true
+        }
+
+        async fn upsert_in(
+            &self,
+            collection: Option<&str>,
+            id: &str,
+            vector: &[f32],
+            payload: Option<Value>,
+        ) -> Result<()> {
+            self.check_dimension(vector)?;
+            let table = self.ensure_table(collection).await?;
+
+            // vec0 tables don't support INSERT OR REPLACE.
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(&format!("DELETE FROM {table} WHERE memory_id = ?"))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!(
+                "INSERT INTO {table} (memory_id, embedding, payload) VALUES (?, ?, ?)"
+            ))
+            .bind(id)
+            .bind(to_blob(vector))
+            .bind(payload.map(|p| p.to_string()))
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            Ok(())
+        }
+
+        async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+            let table = self.ensure_table(collection).await?;
+            sqlx::query(&format!("DELETE FROM {table} WHERE memory_id = ?"))
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        }
+
+        async fn search_in(
+            &self,
+            collection: Option<&str>,
+            vector: &[f32],
+            limit: usize,
+        ) -> Result<Vec<VectorSearchHit>> {
+            self.check_dimension(vector)?;
+            if limit == 0 {
+                return Ok(Vec::new());
+            }
+            let table = self.ensure_table(collection).await?;
+
+            let rows = sqlx::query(&format!(
+                "SELECT memory_id, distance, payload FROM {table} \
+                 WHERE embedding MATCH ? AND k = ? ORDER BY distance"
+            ))
+            .bind(to_blob(vector))
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemoryError::VectorDb(format!("sqlite-vec search failed: {e}")))?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| {
+                    let distance: f64 = row.get("distance");
+                    let payload: Option<String> = row.get("payload");
+                    VectorSearchHit {
+                        id: row.get("memory_id"),
+                        score: 1.0 - distance as f32,
+                        payload: payload.and_then(|p| serde_json::from_str(&p).ok()),
+                    }
+                })
+                .collect())
+        }
+    }
+}
+
+#[cfg(feature = "pgvector")]
+pub mod pgvector {
+    //! Vectors stored in Postgres with the [pgvector](https://github.com/pgvector/pgvector)
+    //! extension, next to the tables of a
+    //! [`PostgresStorageBackend`](crate::PostgresStorageBackend).
+
+    use super::*;
+    use sqlx::postgres::{PgPool, PgPoolOptions};
+    use sqlx::Row;
+    use std::collections::HashSet;
+
+    const DEFAULT_TABLE: &str = "goldfish_vectors";
+
+    /// Approximate nearest-neighbour index built on each vector table
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PgVectorIndex {
+        /// HNSW graph: better recall/speed trade-off, slower to build
+        Hnsw {
+            /// Connections per node
+            m: u32,
+            /// Candidate list size while building
+            ef_construction: u32,
+            /// Candidate list size while searching
+            ef_search: u32,
+        },
+        /// IVF with flat lists: quick to build, best created once data is loaded
+        IvfFlat {
+            /// Number of lists (clusters)
+            lists: u32,
+            /// Lists probed per query
+            probes: u32,
+        },
+        /// Exact scans only
+        Exact,
+    }
+
+    impl Default for PgVectorIndex {
+        fn default() -> Self {
+            Self::Hnsw {
+                m: 16,
+                ef_construction: 64,
+                ef_search: 40,
+            }
+        }
+    }
+
+    impl PgVectorIndex {
+        /// IVF sized like [`AnnParams::for_rows`]
+        pub fn ivfflat_for_rows(rows: usize) -> Self {
+            let params = AnnParams::for_rows(rows);
+            Self::IvfFlat {
+                lists: params.num_partitions,
+                probes: params.nprobes as u32,
+            }
+        }
+
+        fn create_sql(&self, table: &str) -> Option<String> {
+            let (method, with) = match *self {
+                Self::Hnsw {
+                    m, ef_construction, ..
+                } => (
+                    "hnsw",
+                    format!("m = {m}, ef_construction = {ef_construction}"),
+                ),
+                Self::IvfFlat { lists, .. } => ("ivfflat", format!("lists = {lists}")),
+                Self::Exact => return None,
+            };
+            Some(format!(
+                "CREATE INDEX IF NOT EXISTS \"{table}_embedding_idx\" ON \"{table}\" \
+                 USING {method} (embedding vector_cosine_ops) WITH ({with})"
+            ))
+        }
+
+        /// Per-transaction search setting
+        fn search_setting(&self) -> Option<String> {
+            match *self {
+                Self::Hnsw { ef_search, .. } => {
+                    Some(format!("SET LOCAL hnsw.ef_search = {ef_search}"))
+                }
+                Self::IvfFlat { probes, .. } => {
+                    Some(format!("SET LOCAL ivfflat.probes = {probes}"))
+                }
+                Self::Exact => None,
+            }
+        }
+    }
+
+    fn table_name(collection: Option<&str>) -> String {
+        match named_collection(collection) {
+            None => DEFAULT_TABLE.to_string(),
+            Some(name) => format!("{DEFAULT_TABLE}__{name}"),
+        }
+    }
+
+    /// pgvector's text form, `[0.1,0.2,...]`
+    fn to_literal(vector: &[f32]) -> String {
+        let values: Vec<String> = vector.iter().map(f32::to_string).collect();
+        format!("[{}]", values.join(","))
+    }
+
+    /// Vector backend in a Postgres database with the `vector` extension.
+    ///
+    /// Uses cosine distance; scores are reported as `1 - distance`.
+    #[derive(Debug)]
+    pub struct PgVectorBackend {
+        pool: PgPool,
+        dimension: usize,
+        index: PgVectorIndex,
+        created: RwLock<HashSet<String>>,
+    }
+
+    impl PgVectorBackend {
+        /// Connect to `url` and create the default vector table
+        pub async fn connect(url: &str, dimension: usize) -> Result<Self> {
+            let pool = PgPoolOptions::new()
+                .max_connections(10)
+                .connect(url)
+                .await?;
+            Self::from_pool(pool, dimension).await
+        }
+
+        /// Use an existing pool, typically [`PostgresStorageBackend::pool`](crate::PostgresStorageBackend::pool),
+        /// with the default HNSW index
+        pub async fn from_pool(pool: PgPool, dimension: usize) -> Result<Self> {
+            Self::from_pool_with_index(pool, dimension, PgVectorIndex::default()).await
+        }
+
+        /// Use an existing pool and build `index` on each vector table
+        pub async fn from_pool_with_index(
+            pool: PgPool,
+            dimension: usize,
+            index: PgVectorIndex,
+        ) -> Result<Self> {
+            sqlx::query("CREATE EXTENSION IF NOT EXISTS vector")
+                .execute(&pool)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("pgvector is not available: {e}")))?;
+
+            let backend = Self {
+                pool,
+                dimension,
+                index,
+                created: RwLock::new(HashSet::new()),
+            };
+            backend.ensure_table(None).await?;
+            Ok(backend)
+        }
+
+        pub fn index(&self) -> PgVectorIndex {
+            self.index
+        }
+
+        async fn ensure_table(&self, collection: Option<&str>) -> Result<String> {
+            if let Some(name) = named_collection(collection) {
+                validate_collection_name(name)?;
+            }
+            let table = table_name(collection);
+            if self.created.read().await.contains(&table) {
+                return Ok(table);
+            }
+
+            let create = format!(
+                "CREATE TABLE IF NOT EXISTS \"{table}\" (\
+                 memory_id TEXT PRIMARY KEY, \
+                 embedding vector({}) NOT NULL, \
+                 payload JSONB)",
+                self.dimension
+            );
+            sqlx::query(&create)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemoryError::VectorDb(format!("Failed to create {table}: {e}")))?;
+            if let Some(sql) = self.index.create_sql(&table) {
+                sqlx::query(&sql)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| MemoryError::VectorDb(format!("Failed to index {table}: {e}")))?;
+            }
+
+            self.created.write().await.insert(table.clone());
+            Ok(table)
+        }
+
+        /// Vector tables of this backend, default first
+        async fn tables(&self) -> Result<Vec<String>> {
+            let mut tables: Vec<String> = sqlx::query_scalar(
+                "SELECT tablename FROM pg_tables \
+                 WHERE schemaname = current_schema() AND tablename LIKE 'goldfish\\_vectors%'",
+            )
+            .fetch_all(&self.pool)
+            .await?;
+            tables.sort();
+            Ok(tables)
+        }
+
+        fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+            if vector.len() != self.dimension {
+                return Err(MemoryError::VectorDb(format!(
+                    "Vector dimension mismatch: got {}, expected {}",
+                    vector.len(),
+                    self.dimension
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl VectorBackend for PgVectorBackend {
+        fn name(&self) -> &'static str {
+            "pgvector"
+        }
+
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+            self.upsert_in(None, id, vector, payload).await
+        }
+
+        async fn delete(&self, id: &str) -> Result<()> {
+            self.delete_in(None, id).await
+        }
+
+        async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+            self.search_in(None, vector, limit).await
+        }
+
+        async fn stats(&self) -> Result<VectorIndexStats> {
+            let tables = self.tables().await?;
+            let mut rows = 0;
+            for table in &tables {
+                let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{table}\""))
+                    .fetch_one(&self.pool)
+                    .await?;
+                rows += count as usize;
+            }
+            let indices: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM pg_indexes \
+                 WHERE schemaname = current_schema() AND tablename LIKE 'goldfish\\_vectors%' \
+                 AND (indexdef LIKE '%USING hnsw%' OR indexdef LIKE '%USING ivfflat%')",
+            )
+            .fetch_one(&self.pool)
+            .await?;
+
+            Ok(VectorIndexStats {
+                backend: self.name().to_string(),
+                rows,
+                fragments: tables.len(),
+                indices: indices as usize,
+                ..Default::default()
+            })
+        }
+
+        /// `REINDEX` every vector table, e.g. after bulk loading into IVF lists
+        /// sized for an empty table
+        async fn rebuild(&self) -> Result<VectorMaintenanceReport> {
+            let before = self.stats().await?;
+            let mut actions = Vec::new();
+            for table in self.tables().await? {
+                sqlx::query(&format!("REINDEX TABLE \"{table}\""))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| {
+                        MemoryError::VectorDb(format!("Failed to reindex {table}: {e}"))
+                    })?;
+                actions.push(format!("reindexed {table}"));
+            }
+            Ok(VectorMaintenanceReport {
+                before,
+                after: self.stats().await?,
+                actions,
+            })
+        }
+
+        fn supports_collections(&self) -> bool {
+            true
+        }
+
+        async fn upsert_in(
+            &self,
+            collection: Option<&str>,
+            id: &str,
+            vector: &[f32],
+            payload: Option<Value>,
+        ) -> Result<()> {
+            self.check_dimension(vector)?;
+            let table = self.ensure_table(collection).await?;
+
+            sqlx::query(&format!(
+                "INSERT INTO \"{table}\" (memory_id, embedding, payload) \
+                 VALUES ($1, $2::vector, $3::jsonb) \
+                 ON CONFLICT (memory_id) DO UPDATE \
+                 SET embedding = excluded.embedding, payload = excluded.payload"
+            ))
+            .bind(id)
+            .bind(to_literal(vector))
+            .bind(payload.map(|p| p.to_string()))
+            .execute(&self.pool)
+            .await?;
+            Ok(())
+        }
+
+        async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+            let table = self.ensure_table(collection).await?;
+            sqlx::query(&format!("DELETE FROM \"{table}\" WHERE memory_id = $1"))
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        }
+
+        async fn search_in(
+            &self,
+            collection: Option<&str>,
+            vector: &[f32],
+            limit: usize,
+        ) -> Result<Vec<VectorSearchHit>> {
+            self.check_dimension(vector)?;
+            if limit == 0 {
+                return Ok(Vec::new());
+            }
+            let table = self.ensure_table(collection).await?;
+
+            // Search settings only last for the transaction
+            let mut tx = self.pool.begin().await?;
+            if let Some(setting) = self.index.search_setting() {
+                sqlx::query(&setting).execute(&mut *tx).await?;
+            }
+            let rows = sqlx::query(&format!(
+                "SELECT memory_id, (embedding <=> $1::vector)::float8 AS distance, \
+                 payload::text AS payload \
+                 FROM \"{table}\" ORDER BY embedding <=> $1::vector LIMIT $2"
+            ))
+            .bind(to_literal(vector))
+            .bind(limit as i64)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| MemoryError::VectorDb(format!("pgvector search failed: {e}")))?;
+            tx.commit().await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| {
+                    let distance: f64 = row.get("distance");
+                    let payload: Option<String> = row.get("payload");
+                    VectorSearchHit {
+                        id: row.get("memory_id"),
+                        score: 1.0 - distance as f32,
+                        payload: payload.and_then(|p| serde_json::from_str(&p).ok()),
+                    }
+                })
+                .collect())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -553,6 +2392,400 @@ mod tests {
         assert!(hits.iter().all(|h| h.id != "m1"));
     }
 
+    #[tokio::test]
+    async fn file_backend_collections_are_isolated() {
+        let dir = tempdir().expect("tempdir");
+        let backend = Arc::new(FileVectorBackend::new(dir.path().join("vectors"), 4));
+        backend.ensure_ready().await.expect("init");
+
+        let v = [1.0, 0.0, 0.0, 0.0];
+        backend.upsert("shared", &v, None).await.expect("upsert");
+        backend
+            .upsert_in(Some("tenant-a"), "a1", &v, None)
+            .await
+            .expect("upsert_in");
+
+        let tenant = VectorCollection::new(backend.clone(), "tenant-a").expect("collection");
+        let hits = tenant.search(&v, 10).await.expect("search");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "a1");
+
+        let default_hits = backend
+            .search_in(Some(DEFAULT_COLLECTION), &v, 10)
+            .await
+            .expect("search default");
+        assert!(default_hits.iter().all(|h| h.id != "a1"));
+
+        let stats = backend.stats().await.expect("stats");
+        assert_eq!(stats.rows, 2);
+        assert_eq!(stats.fragmentation(), 0.0);
+
+        let escaped = backend.upsert_in(Some("../escape"), "x", &v, None).await;
+        assert!(escaped.is_err());
+    }
+
+    #[tokio::test]
+    async fn file_backend_filters_on_stored_payloads() {
+        use crate::{Memory, MemoryType};
+
+        let dir = tempdir().expect("tempdir");
+        let backend = FileVectorBackend::new(dir.path().join("vectors"), 2);
+        backend.ensure_ready().await.expect("init");
+
+        let mut goal = Memory::new("Ship v2", MemoryType::Goal);
+        goal.tags = vec!["release".to_string()];
+        let fact = Memory::new("Deploys run on Tuesdays", MemoryType::Fact);
+        for (memory, v) in [(&goal, [1.0, 0.0]), (&fact, [0.9, 0.1])] {
+            let payload = vector_payload(memory, "default");
+            backend
+                .upsert(&memory.id, &v, Some(payload))
+                .await
+                .expect("upsert");
+        }
+        backend
+            .upsert("legacy", &[1.0, 0.0], None)
+            .await
+            .expect("upsert");
+
+        let filter = VectorFilter::new().with_memory_type(MemoryType::Fact);
+        let ids: Vec<String> = backend
+            .search_filtered(&[1.0, 0.0], 10, &filter)
+            .await
+            .expect("search")
+            .into_iter()
+            .map(|h| h.id)
+            .collect();
+        assert!(ids.contains(&fact.id));
+        assert!(ids.contains(&"legacy".to_string()));
+        assert!(!ids.contains(&goal.id));
+
+        let tagged = VectorFilter::new()
+            .with_tag("release")
+            .with_namespace("default");
+        let hits = backend
+            .search_filtered(&[1.0, 0.0], 10, &tagged)
+            .await
+            .expect("search");
+        assert!(hits.iter().any(|h| h.id == goal.id));
+        assert!(hits.iter().all(|h| h.id != fact.id));
+
+        backend.delete(&goal.id).await.expect("delete");
+        assert!(!dir
+            .path()
+            .join("vectors")
+            .join(format!("{}.json", goal.id))
+            .exists());
+    }
+
+    #[test]
+    fn fragmentation_is_the_share_of_small_fragments() {
+        let stats = |fragments, small_fragments| VectorIndexStats {
+            fragments,
+            small_fragments,
+            ..Default::default()
+        };
+        assert_eq!(stats(0, 0).fragmentation(), 0.0);
+        assert_eq!(stats(1, 1).fragmentation(), 0.0);
+        assert_eq!(stats(4, 1).fragmentation(), 0.25);
+        assert_eq!(stats(4, 4).fragmentation(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn maintenance_reports_cover_every_attached_backend() {
+        use crate::embedding::HashEmbeddingProvider;
+        use crate::{MaintenanceConfigBuilder, Memory, MemorySystem, MemoryType};
+
+        let dir = tempdir().expect("tempdir");
+        let facts = Arc::new(FileVectorBackend::new(dir.path().join("facts"), 16));
+        facts.ensure_ready().await.expect("init");
+        let memory = MemorySystem::in_memory()
+            .await
+            .expect("in_memory")
+            .with_type_vector_backend(
+                MemoryType::Fact,
+                facts.clone(),
+                Arc::new(HashEmbeddingProvider::new(16)),
+            );
+        memory
+            .save(&Memory::new("Deploys need two approvals", MemoryType::Fact))
+            .await
+            .expect("save");
+        memory
+            .save(&Memory::new("Shipped the billing fix", MemoryType::Event))
+            .await
+            .expect("save");
+
+        let stats = memory.vector_stats().await.expect("stats");
+        let rows: Vec<_> = stats.iter().map(|s| (s.backend.as_str(), s.rows)).collect();
+        assert_eq!(rows, [("memory", 1), ("file", 1)]);
+
+        for reports in [
+            memory.maintain_vectors().await.expect("maintain"),
+            memory.rebuild_vectors().await.expect("rebuild"),
+        ] {
+            assert_eq!(reports.len(), 2);
+            for (report, stats) in reports.iter().zip(&stats) {
+                assert_eq!(&report.before, stats);
+                assert_eq!(report.after, report.before);
+            }
+        }
+
+        let config = MaintenanceConfigBuilder::new()
+            .enable_decay(false)
+            .enable_pruning(false)
+            .enable_vector_maintenance(true)
+            .build();
+        let report = memory.run_maintenance(&config).await.expect("maintenance");
+        assert_eq!(report.vector.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn in_memory_system_indexes_text_and_vectors() {
+        use crate::{Memory, MemorySystem, MemoryType};
+
+        let memory = MemorySystem::in_memory().await.expect("in_memory");
+        let fact = Memory::new("The staging database runs on Postgres 16", MemoryType::Fact);
+        memory.save(&fact).await.expect("save");
+
+        let found = memory.search("staging database").await.expect("search");
+        assert_eq!(found[0].memory.id, fact.id);
+
+        let stats = memory.vector_stats().await.expect("vector stats");
+        assert_eq!(stats[0].backend, "memory");
+        assert_eq!(stats[0].rows, 1);
+    }
+
+    #[tokio::test]
+    async fn payloads_follow_bulk_edits_and_retyping() {
+        use crate::embedding::{EmbeddingProvider, HashEmbeddingProvider};
+        use crate::{Memory, MemoryQuery, MemorySystem, MemoryType};
+
+        let vectors = Arc::new(InMemoryVectorBackend::new(16));
+        let memory = MemorySystem::in_memory()
+            .await
+            .expect("in_memory")
+            .with_vector_backend(vectors.clone(), Arc::new(HashEmbeddingProvider::new(16)));
+        let note = Memory::new("Release train leaves on Thursday", MemoryType::Fact);
+        memory.save(&note).await.expect("save");
+        let query = HashEmbeddingProvider::new(16)
+            .embed(std::slice::from_ref(&note.content))
+            .await
+            .expect("embed")
+            .remove(0);
+        let found = |filter: VectorFilter| {
+            let vectors = vectors.clone();
+            let query = query.clone();
+            async move {
+                vectors
+                    .search_filtered(&query, 10, &filter)
+                    .await
+                    .expect("search")
+                    .len()
+            }
+        };
+
+        let tagged = VectorFilter::new().with_tag("release");
+        assert_eq!(found(tagged.clone()).await, 0);
+        memory
+            .add_tag_where(&MemoryQuery::new(), "release")
+            .await
+            .expect("tag");
+        assert_eq!(found(tagged.clone()).await, 1);
+        memory
+            .remove_tag_where(&MemoryQuery::new(), "release")
+            .await
+            .expect("untag");
+        assert_eq!(found(tagged).await, 0);
+
+        memory
+            .retype(&note.id, MemoryType::Decision)
+            .await
+            .expect("retype");
+        assert_eq!(
+            found(VectorFilter::new().with_memory_type(MemoryType::Decision)).await,
+            1
+        );
+        assert_eq!(
+            found(VectorFilter::new().with_memory_type(MemoryType::Fact)).await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn unfiltered_hybrid_search_covers_every_type_route() {
+        use crate::embedding::HashEmbeddingProvider;
+        use crate::{HybridSearchConfig, Memory, MemorySystem, MemoryType};
+
+        let dir = tempdir().expect("tempdir");
+        let decisions = Arc::new(InMemoryVectorBackend::new(32));
+        let memory = MemorySystem::new(dir.path())
+            .await
+            .expect("system")
+            .with_vector_backend(
+                Arc::new(InMemoryVectorBackend::new(16)),
+                Arc::new(HashEmbeddingProvider::new(16)),
+            )
+            .with_type_vector_backend(
+                MemoryType::Decision,
+                decisions.clone(),
+                Arc::new(HashEmbeddingProvider::new(32)),
+            );
+        let fact = Memory::new("Deploys run after the nightly build", MemoryType::Fact);
+        let decision = Memory::new("Deploys pause during the freeze", MemoryType::Decision);
+        memory.save(&fact).await.expect("save");
+        memory.save(&decision).await.expect("save");
+        assert_eq!(decisions.stats().await.expect("stats").rows, 1);
+
+        let results = memory
+            .hybrid_search("deploys", &HybridSearchConfig::default(), None)
+            .await
+            .expect("search");
+        for saved in [&fact, &decision] {
+            let found = results
+                .iter()
+                .find(|r| r.memory.id == saved.id)
+                .expect("found");
+            assert!(
+                found.explanation.vector.is_some(),
+                "{} has no vector hit",
+                saved.id
+            );
+        }
+
+        let only_decisions = memory
+            .hybrid_search(
+                "deploys",
+                &HybridSearchConfig::default(),
+                Some(MemoryType::Decision),
+            )
+            .await
+            .expect("search");
+        assert_eq!(only_decisions.len(), 1);
+        assert!(only_decisions[0].explanation.vector.is_some());
+    }
+
+    #[test]
+    fn ann_params_scale_with_corpus_and_escalate() {
+        let small = AnnParams::for_rows(1_000);
+        let large = AnnParams::for_rows(1_000_000);
+        assert!(large.num_partitions > small.num_partitions);
+        assert!(large.nprobes >= small.nprobes);
+        assert!(small.nprobes <= small.num_partitions as usize);
+
+        let mut params = small;
+        let mut steps = 0;
+        while let Some(next) = params.escalate() {
+            assert!(next.nprobes >= params.nprobes);
+            params = next;
+            steps += 1;
+        }
+        assert!(steps > 0);
+        assert_eq!(params.nprobes, params.num_partitions as usize);
+
+        let exact = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            recall_at_k(&exact, &["b".to_string(), "c".to_string()]),
+            0.5
+        );
+    }
+
+    #[cfg(feature = "sqlite-vec")]
+    #[tokio::test]
+    async fn sqlite_vec_backend_roundtrip() {
+        let dir = tempdir().expect("tempdir");
+        let backend = crate::vector_backend::sqlite_vec::SqliteVecBackend::connect(
+            dir.path().join("m.db"),
+            4,
+        )
+        .await
+        .expect("sqlite-vec init");
+
+        backend
+            .upsert("m1", &[1.0, 0.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert");
+        backend
+            .upsert("m2", &[0.0, 1.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert");
+        backend
+            .upsert_in(Some("other"), "o1", &[1.0, 0.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert_in");
+
+        let hits = backend
+            .search(&[1.0, 0.0, 0.0, 0.0], 2)
+            .await
+            .expect("search");
+        assert_eq!(hits[0].id, "m1");
+        assert!(hits.iter().all(|h| h.id != "o1"));
+
+        backend.delete("m1").await.expect("delete");
+        let hits = backend
+            .search(&[1.0, 0.0, 0.0, 0.0], 2)
+            .await
+            .expect("search after delete");
+        assert!(hits.iter().all(|h| h.id != "m1"));
+        assert_eq!(backend.stats().await.expect("stats").rows, 2);
+    }
+
+    /// Needs a Postgres server with pgvector; skipped unless
+    /// `GOLDFISH_TEST_PGVECTOR_URL` is set. Drops the vector tables it uses.
+    #[cfg(feature = "pgvector")]
+    #[tokio::test]
+    async fn pgvector_backend_roundtrip() {
+        use crate::vector_backend::pgvector::{PgVectorBackend, PgVectorIndex};
+
+        let Ok(url) = std::env::var("GOLDFISH_TEST_PGVECTOR_URL") else {
+            return;
+        };
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect(&url)
+            .await
+            .expect("connect");
+        for table in ["goldfish_vectors", "goldfish_vectors__other"] {
+            sqlx::query(&format!("DROP TABLE IF EXISTS \"{table}\""))
+                .execute(&pool)
+                .await
+                .expect("drop");
+        }
+        let backend = PgVectorBackend::from_pool_with_index(pool, 4, PgVectorIndex::Exact)
+            .await
+            .expect("pgvector init");
+
+        backend
+            .upsert("m1", &[1.0, 0.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert");
+        backend
+            .upsert(
+                "m1",
+                &[0.9, 0.1, 0.0, 0.0],
+                Some(serde_json::json!({"v": 2})),
+            )
+            .await
+            .expect("upsert again");
+        backend
+            .upsert("m2", &[0.0, 1.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert");
+        backend
+            .upsert_in(Some("other"), "o1", &[1.0, 0.0, 0.0, 0.0], None)
+            .await
+            .expect("upsert_in");
+
+        let hits = backend
+            .search(&[1.0, 0.0, 0.0, 0.0], 2)
+            .await
+            .expect("search");
+        assert_eq!(hits[0].id, "m1");
+        assert_eq!(hits[0].payload, Some(serde_json::json!({"v": 2})));
+        assert!(hits.iter().all(|h| h.id != "o1"));
+
+        backend.delete("m1").await.expect("delete");
+        assert_eq!(backend.stats().await.expect("stats").rows, 2);
+    }
+
     #[cfg(feature = "lancedb")]
     #[tokio::test]
     async fn lancedb_backend_roundtrip() {
@@ -576,4 +2809,43 @@ mod tests {
         assert!(!hits.is_empty());
         assert_eq!(hits[0].id, "m1");
     }
+
+    #[cfg(feature = "lancedb")]
+    #[tokio::test]
+    async fn lancedb_ann_index_rebuilds_on_demand_with_config() {
+        use crate::vector_backend::lancedb::{AnnIndexKind, LanceDbConfig, LanceDbVectorBackend};
+
+        let dir = tempdir().expect("tempdir");
+        let config = LanceDbConfig::default()
+            .with_ann(AnnIndexKind::IvfFlat)
+            .with_auto_tune(false)
+            .with_nprobes(4)
+            .with_min_rows_for_index(16);
+        let backend = LanceDbVectorBackend::new_with_config(dir.path(), 8, config);
+        assert_eq!(backend.config().kind, AnnIndexKind::IvfFlat);
+
+        backend
+            .upsert("m0", &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], None)
+            .await
+            .expect("lancedb upsert");
+        assert!(!backend.rebuild_ann_index(None).await.expect("too small"));
+
+        for i in 1..32 {
+            let mut v = vec![0.0; 8];
+            v[i % 8] = 1.0;
+            v[(i + 1) % 8] = i as f32 / 32.0;
+            backend
+                .upsert(&format!("m{i}"), &v, None)
+                .await
+                .expect("lancedb upsert");
+        }
+        assert!(backend.rebuild_ann_index(None).await.expect("rebuild"));
+        assert!(backend.stats().await.expect("stats").indices >= 1);
+
+        let hits = backend
+            .search(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], 1)
+            .await
+            .expect("lancedb search");
+        assert_eq!(hits.len(), 1);
+    }
 }
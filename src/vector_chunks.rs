@@ -0,0 +1,359 @@
+//! Multi-vector embeddings for long memories
+//!
+//! One vector for a whole transcript or document averages its topics away.
+//! With [`MemorySystem::with_chunked_embeddings`], content longer than
+//! [`ChunkingConfig::max_chars`] is split into overlapping chunks, each
+//! embedded and stored under its own vector ID (`<memory id>#<chunk>`). The
+//! chunk offsets are kept in the store, so the vectors can be replaced and
+//! deleted with the memory.
+//!
+//! Searches map chunk hits back to their memory, scoring it by its best
+//! chunk; hybrid retrieval reports which span matched.
+//!
+//! ```rust,ignore
+//! let memory = MemorySystem::new("./data")
+//!     .await?
+//!     .with_vector_backend(backend, embedder)
+//!     .with_chunked_embeddings(ChunkingConfig::default().with_max_chars(800));
+//! ```
+//!
+//! [`MemorySystem::with_chunked_embeddings`]: crate::MemorySystem::with_chunked_embeddings
+
+use crate::error::Result;
+use crate::store::MemoryStore;
+use crate::vector_backend::{VectorBackend, VectorSearchHit};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Separates the memory ID from the chunk index in chunk vector IDs
+const CHUNK_SEPARATOR: char = '#';
+
+/// How long memories are split before embedding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    /// Content up to this many characters keeps a single vector
+    pub max_chars: usize,
+    /// Characters shared by neighbouring chunks, so a sentence on a boundary
+    /// is whole in at least one of them
+    pub overlap_chars: usize,
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            max_chars: 1000,
+            overlap_chars: 100,
+        }
+    }
+}
+
+impl ChunkingConfig {
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+
+    pub fn with_overlap_chars(mut self, overlap_chars: usize) -> Self {
+        self.overlap_chars = overlap_chars;
+        self
+    }
+}
+
+/// One embedded span of a memory's content (byte offsets)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorChunk {
+    pub index: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl VectorChunk {
+    pub fn text<'a>(&self, content: &'a str) -> &'a str {
+        &content[self.start..self.end]
+    }
+}
+
+/// Spans to embed for `content`; empty when it fits in one vector.
+///
+/// Chunks end at whitespace where possible and always on char boundaries.
+pub fn chunk_spans(content: &str, config: &ChunkingConfig) -> Vec<VectorChunk> {
+    let max_chars = config.max_chars.max(1);
+    if content.chars().count() <= max_chars {
+        return Vec::new();
+    }
+    let overlap = config.overlap_chars.min(max_chars / 2);
+
+    // Byte offset of every char, plus the end of the content
+    let offsets: Vec<usize> = content
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(content.len()))
+        .collect();
+    let total = offsets.len() - 1;
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut end = (start + max_chars).min(total);
+        if end < total {
+            // Back off to the last whitespace in the second half of the window
+            if let Some(cut) = (start + max_chars / 2..end)
+                .rev()
+                .find(|&i| content[offsets[i]..].starts_with(char::is_whitespace))
+            {
+                end = cut + 1;
+            }
+        }
+        chunks.push(VectorChunk {
+            index: chunks.len(),
+            start: offsets[start],
+            end: offsets[end],
+        });
+        if end >= total {
+            break;
+        }
+        start = (end - overlap).max(start + 1);
+    }
+    chunks
+}
+
+/// Vector ID of a memory's chunk
+pub fn chunk_vector_id(memory_id: &str, index: usize) -> String {
+    format!("{memory_id}{CHUNK_SEPARATOR}{index}")
+}
+
+/// Memory ID and chunk index of a vector ID; whole-memory vectors have no index
+pub fn split_vector_id(vector_id: &str) -> (&str, Option<usize>) {
+    match vector_id.rsplit_once(CHUNK_SEPARATOR) {
+        Some((memory_id, index)) => match index.parse() {
+            Ok(index) => (memory_id, Some(index)),
+            Err(_) => (vector_id, None),
+        },
+        None => (vector_id, None),
+    }
+}
+
+/// Record `chunks` as a memory's chunking and delete the vectors the one
+/// recorded before leaves behind: chunks past the new count, and the
+/// whole-memory vector once the memory is chunked. Call it after the new
+/// vectors are written, so a failed embed never costs a memory its old ones.
+/// Returns the vector IDs deleted.
+pub(crate) async fn replace_chunks(
+    store: &MemoryStore,
+    vector: &dyn VectorBackend,
+    memory_id: &str,
+    chunks: &[VectorChunk],
+) -> Result<Vec<String>> {
+    let previous = store.vector_chunks(memory_id).await?;
+    let mut stale: Vec<String> = previous
+        .iter()
+        .filter(|p| p.index >= chunks.len())
+        .map(|p| chunk_vector_id(memory_id, p.index))
+        .collect();
+    if !chunks.is_empty() && previous.is_empty() {
+        stale.push(memory_id.to_string());
+    }
+    for id in &stale {
+        vector.delete(id).await?;
+    }
+    if previous != chunks {
+        store.save_vector_chunks(memory_id, chunks).await?;
+    }
+    Ok(stale)
+}
+
+/// A vector hit mapped back to its memory
+#[derive(Debug, Clone)]
+pub struct MemoryVectorHit {
+    pub memory_id: String,
+    /// Best score over the memory's vectors
+    pub score: f32,
+    /// The chunk that scored best, if it was a chunk
+    pub chunk: Option<VectorChunk>,
+}
+
+/// Collapse chunk hits into one hit per memory, keeping the best chunk.
+/// Order is by score, best first.
+pub fn aggregate_chunk_hits(hits: Vec<VectorSearchHit>) -> Vec<MemoryVectorHit> {
+    let mut best: HashMap<String, MemoryVectorHit> = HashMap::new();
+    for hit in hits {
+        let (memory_id, index) = split_vector_id(&hit.id);
+        let chunk = index.and_then(|index| {
+            let payload = hit.payload.as_ref()?;
+            let offset = |key: &str| payload.get(key)?.as_u64().map(|v| v as usize);
+            Some(VectorChunk {
+                index,
+                start: offset("chunk_start")?,
+                end: offset("chunk_end")?,
+            })
+        });
+        let candidate = MemoryVectorHit {
+            memory_id: memory_id.to_string(),
+            score: hit.score,
+            chunk,
+        };
+        match best.get(memory_id) {
+            Some(existing) if existing.score >= candidate.score => {}
+            _ => {
+                best.insert(candidate.memory_id.clone(), candidate);
+            }
+        }
+    }
+    let mut hits: Vec<MemoryVectorHit> = best.into_values().collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::{EmbeddingProvider, HashEmbeddingProvider};
+    use crate::error::MemoryError;
+    use crate::types::{Memory, MemoryType};
+    use crate::vector_backend::{InMemoryVectorBackend, VectorBackend};
+    use crate::{HybridSearchConfig, MemorySystem};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn long_content_splits_into_overlapping_chunks_on_whitespace() {
+        let config = ChunkingConfig::default()
+            .with_max_chars(40)
+            .with_overlap_chars(10);
+        assert!(chunk_spans("short enough", &config).is_empty());
+
+        let content = "Deploys run on Tuesdays after the freeze. ".repeat(5);
+        let chunks = chunk_spans(&content, &config);
+        assert!(chunks.len() > 2);
+        assert_eq!(chunks[0].start, 0);
+        assert_eq!(chunks.last().unwrap().end, content.len());
+        for pair in chunks.windows(2) {
+            assert!(pair[1].start < pair[0].end, "chunks overlap");
+            assert!(pair[0].text(&content).ends_with(' '));
+        }
+
+        let unicode = "ü".repeat(90);
+        for chunk in chunk_spans(&unicode, &config) {
+            assert!(chunk.text(&unicode).chars().count() <= 40);
+        }
+    }
+
+    #[test]
+    fn chunk_hits_collapse_to_the_best_chunk_per_memory() {
+        let hit = |id: &str, score: f32, start: usize| VectorSearchHit {
+            id: id.to_string(),
+            score,
+            payload: Some(serde_json::json!({ "chunk_start": start, "chunk_end": start + 10 })),
+        };
+        let hits = aggregate_chunk_hits(vec![
+            hit(&chunk_vector_id("m1", 0), 0.4, 0),
+            hit(&chunk_vector_id("m1", 3), 0.9, 30),
+            hit("m2", 0.6, 0),
+        ]);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].memory_id, "m1");
+        assert_eq!(hits[0].chunk.map(|c| (c.index, c.start)), Some((3, 30)));
+        assert!(hits[1].chunk.is_none());
+        assert_eq!(split_vector_id("no-chunk#x"), ("no-chunk#x", None));
+    }
+
+    #[tokio::test]
+    async fn long_memories_are_found_by_their_best_chunk_and_deleted_whole() {
+        let vectors = Arc::new(InMemoryVectorBackend::new(64));
+        let memory = MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_vector_backend(vectors.clone(), Arc::new(HashEmbeddingProvider::new(64)))
+            .with_chunked_embeddings(ChunkingConfig::default().with_max_chars(80));
+
+        let transcript = format!(
+            "{} The kubernetes rollout failed on the canary cluster.",
+            "We talked about lunch plans and the weather for a while. ".repeat(4)
+        );
+        let long = Memory::new(transcript, MemoryType::Event);
+        memory.save(&long).await.unwrap();
+        let chunks = vectors.stats().await.unwrap().rows;
+        assert!(chunks > 1);
+
+        let results = memory
+            .hybrid_search("kubernetes rollout", &HybridSearchConfig::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(results[0].memory.id, long.id);
+        let chunk = results[0].explanation.chunk.expect("matched chunk");
+        assert!(chunk.text(&long.content).contains("kubernetes"));
+
+        let mut short = long.clone();
+        short.content = "Rollout fixed".to_string();
+        memory.update(&short).await.unwrap();
+        assert_eq!(vectors.stats().await.unwrap().rows, 1);
+
+        memory.delete(&long.id).await.unwrap();
+        assert_eq!(vectors.stats().await.unwrap().rows, 0);
+    }
+
+    /// Hash embeddings that fail while `down` is set
+    struct Outage {
+        inner: HashEmbeddingProvider,
+        down: AtomicBool,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for Outage {
+        fn name(&self) -> &'static str {
+            self.inner.name()
+        }
+
+        fn dimension(&self) -> usize {
+            self.inner.dimension()
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err(MemoryError::EmbeddingFailed("unavailable".to_string()));
+            }
+            self.inner.embed(texts).await
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_embeds_keep_the_previous_chunks() {
+        let vectors = Arc::new(InMemoryVectorBackend::new(64));
+        let embedder = Arc::new(Outage {
+            inner: HashEmbeddingProvider::new(64),
+            down: AtomicBool::new(false),
+        });
+        let memory = MemorySystem::in_memory()
+            .await
+            .unwrap()
+            .with_vector_backend(vectors.clone(), embedder.clone())
+            .with_chunked_embeddings(ChunkingConfig::default().with_max_chars(80));
+
+        let long = Memory::new(
+            "Notes from the planning meeting. ".repeat(8),
+            MemoryType::Event,
+        );
+        memory.save(&long).await.unwrap();
+        let rows = vectors.stats().await.unwrap().rows;
+        let chunks = memory.store.vector_chunks(&long.id).await.unwrap();
+        assert_eq!(chunks.len(), rows);
+
+        embedder.down.store(true, Ordering::SeqCst);
+        let mut short = long.clone();
+        short.content = "Planning moved to Monday".to_string();
+        assert!(memory.update(&short).await.is_err());
+        assert_eq!(vectors.stats().await.unwrap().rows, rows);
+        assert_eq!(memory.store.vector_chunks(&long.id).await.unwrap(), chunks);
+
+        embedder.down.store(false, Ordering::SeqCst);
+        memory.update(&short).await.unwrap();
+        assert_eq!(vectors.stats().await.unwrap().rows, 1);
+        assert!(memory
+            .store
+            .vector_chunks(&long.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+//! Zero-downtime migration between vector backends
+//!
+//! A [`DualWriteVectorBackend`] wraps the current backend (the primary) and
+//! the one being migrated to (the secondary). Writes go to both, reads only to
+//! the primary. [`MemorySystem::backfill_vectors`] copies the memories stored
+//! before the switch into the secondary, [`MemorySystem::verify_vector_parity`]
+//! checks that both return the same neighbours, and
+//! [`DualWriteVectorBackend::cutover`] then makes the secondary the primary.
+//! The old backend keeps receiving writes as a warm standby until the dual
+//! backend is replaced by [`primary`](DualWriteVectorBackend::primary).
+//!
+//! ```rust,ignore
+//! let dual = Arc::new(DualWriteVectorBackend::new(file_backend, lancedb_backend)?);
+//! let memory = memory.with_vector_backend(dual.clone(), embedder);
+//!
+//! memory.spawn_vector_backfill(dual.clone(), 256).await??;
+//! let parity = memory.verify_vector_parity(&dual, 100).await?;
+//! if parity.passed {
+//!     dual.cutover()?;
+//! }
+//! ```
+//!
+//! [`MemorySystem::backfill_vectors`]: crate::MemorySystem::backfill_vectors
+//! [`MemorySystem::verify_vector_parity`]: crate::MemorySystem::verify_vector_parity
+
+use crate::error::{MemoryError, Result};
+use crate::vector_backend::{
+    recall_at_k, VectorBackend, VectorFilter, VectorIndexStats, VectorSearchHit,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// What [`MemorySystem::backfill_vectors`](crate::MemorySystem::backfill_vectors) copied
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorBackfillReport {
+    /// Active memories looked at
+    pub scanned: usize,
+    /// Vectors written to the secondary
+    pub upserted: usize,
+    /// Memories routed to a type-specific backend instead
+    pub skipped: usize,
+}
+
+/// Whether the secondary answers like the primary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParityReport {
+    /// Query vectors compared
+    pub probes: usize,
+    /// Neighbours compared per probe
+    pub k: usize,
+    /// Mean share of the primary's top-k also in the secondary's top-k
+    pub mean_recall: f32,
+    /// Worst recall of a single probe
+    pub worst_recall: f32,
+    pub primary_rows: usize,
+    pub secondary_rows: usize,
+    /// Secondary writes that failed since the dual backend was created or
+    /// last cut over
+    pub secondary_failures: u64,
+    pub passed: bool,
+}
+
+/// Writes to two vector backends and reads from the primary, see the
+/// [module docs](self)
+pub struct DualWriteVectorBackend {
+    /// (primary, secondary)
+    backends: RwLock<(Arc<dyn VectorBackend>, Arc<dyn VectorBackend>)>,
+    min_recall: f32,
+    secondary_failures: AtomicU64,
+    verified: Mutex<Option<ParityReport>>,
+}
+
+impl DualWriteVectorBackend {
+    pub fn new(primary: Arc<dyn VectorBackend>, secondary: Arc<dyn VectorBackend>) -> Result<Self> {
+        if primary.dimension() != secondary.dimension() {
+            return Err(MemoryError::Configuration(format!(
+                "Cannot dual-write between '{}' ({} dims) and '{}' ({} dims)",
+                primary.name(),
+                primary.dimension(),
+                secondary.name(),
+                secondary.dimension()
+            )));
+        }
+        Ok(Self {
+            backends: RwLock::new((primary, secondary)),
+            min_recall: 0.95,
+            secondary_failures: AtomicU64::new(0),
+            verified: Mutex::new(None),
+        })
+    }
+
+    /// Mean recall the secondary needs for parity (default 0.95, below 1.0
+    /// so approximate indexes can pass)
+    pub fn with_min_recall(mut self, min_recall: f32) -> Self {
+        self.min_recall = min_recall.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The backend reads are served from
+    pub fn primary(&self) -> Arc<dyn VectorBackend> {
+        self.pair().0
+    }
+
+    /// The backend that only receives writes
+    pub fn secondary(&self) -> Arc<dyn VectorBackend> {
+        self.pair().1
+    }
+
+    pub fn secondary_failures(&self) -> u64 {
+        self.secondary_failures.load(Ordering::Relaxed)
+    }
+
+    fn pair(&self) -> (Arc<dyn VectorBackend>, Arc<dyn VectorBackend>) {
+        self.backends
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Secondary errors don't fail the write; they are counted and block
+    /// cutover until parity is verified again
+    fn secondary_result(&self, secondary: &Arc<dyn VectorBackend>, result: Result<()>) {
+        if let Err(e) = result {
+            self.secondary_failures.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                "Dual-write to vector backend '{}' failed: {e}",
+                secondary.name()
+            );
+        }
+    }
+
+    /// Write a vector to the secondary only, for backfilling
+    pub async fn backfill(&self, id: &str, vector: &[f32]) -> Result<()> {
+        self.secondary().upsert(id, vector, None).await
+    }
+
+    /// Compare the top-`k` neighbours of each probe vector in both backends
+    /// and remember the outcome for [`cutover`](Self::cutover)
+    pub async fn verify_parity(&self, probes: &[Vec<f32>], k: usize) -> Result<ParityReport> {
+        let (primary, secondary) = self.pair();
+        let failures = self.secondary_failures();
+
+        let mut recalls = Vec::with_capacity(probes.len());
+        for probe in probes {
+            let expected = hit_ids(primary.search(probe, k).await?);
+            let actual = hit_ids(secondary.search(probe, k).await?);
+            recalls.push(recall_at_k(&expected, &actual));
+        }
+        let primary_rows = primary.stats().await?.rows;
+        let secondary_rows = secondary.stats().await?.rows;
+
+        let mean_recall = if recalls.is_empty() {
+            1.0
+        } else {
+            recalls.iter().sum::<f32>() / recalls.len() as f32
+        };
+        // Backends that don't count their rows report 0
+        let rows_match = primary_rows == secondary_rows || primary_rows == 0 || secondary_rows == 0;
+        let report = ParityReport {
+            probes: probes.len(),
+            k,
+            mean_recall,
+            worst_recall: recalls.iter().copied().fold(1.0, f32::min),
+            primary_rows,
+            secondary_rows,
+            secondary_failures: failures,
+            passed: mean_recall >= self.min_recall && rows_match,
+        };
+        *self.verified.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+        Ok(report)
+    }
+
+    /// Make the secondary the primary. Requires a passed
+    /// [`verify_parity`](Self::verify_parity) with no failed secondary
+    /// writes since; the old primary keeps receiving writes as a standby.
+    pub fn cutover(&self) -> Result<()> {
+        let mut verified = self.verified.lock().unwrap_or_else(|e| e.into_inner());
+        match verified.as_ref() {
+            Some(report)
+                if report.passed && report.secondary_failures == self.secondary_failures() => {}
+            Some(_) => {
+                return Err(MemoryError::InvalidOperation(
+                    "Vector parity not reached; backfill and verify again before cutover"
+                        .to_string(),
+                ))
+            }
+            None => {
+                return Err(MemoryError::InvalidOperation(
+                    "Verify vector parity before cutover".to_string(),
+                ))
+            }
+        }
+
+        let mut backends = self.backends.write().unwrap_or_else(|e| e.into_inner());
+        let (primary, secondary) = backends.clone();
+        tracing::info!(
+            "Vector cutover: '{}' is now primary, '{}' is standby",
+            secondary.name(),
+            primary.name()
+        );
+        *backends = (secondary, primary);
+        *verified = None;
+        self.secondary_failures.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+fn hit_ids(hits: Vec<VectorSearchHit>) -> Vec<String> {
+    hits.into_iter().map(|hit| hit.id).collect()
+}
+
+#[async_trait]
+impl VectorBackend for DualWriteVectorBackend {
+    fn name(&self) -> &'static str {
+        "dual-write"
+    }
+
+    fn dimension(&self) -> usize {
+        self.primary().dimension()
+    }
+
+    async fn upsert(&self, id: &str, vector: &[f32], payload: Option<Value>) -> Result<()> {
+        let (primary, secondary) = self.pair();
+        primary.upsert(id, vector, payload.clone()).await?;
+        let result = secondary.upsert(id, vector, payload).await;
+        self.secondary_result(&secondary, result);
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let (primary, secondary) = self.pair();
+        primary.delete(id).await?;
+        let result = secondary.delete(id).await;
+        self.secondary_result(&secondary, result);
+        Ok(())
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<VectorSearchHit>> {
+        self.primary().search(vector, limit).await
+    }
+
+    async fn stats(&self) -> Result<VectorIndexStats> {
+        self.primary().stats().await
+    }
+
+    fn supports_collections(&self) -> bool {
+        let (primary, secondary) = self.pair();
+        primary.supports_collections() && secondary.supports_collections()
+    }
+
+    async fn upsert_in(
+        &self,
+        collection: Option<&str>,
+        id: &str,
+        vector: &[f32],
+        payload: Option<Value>,
+    ) -> Result<()> {
+        let (primary, secondary) = self.pair();
+        primary
+            .upsert_in(collection, id, vector, payload.clone())
+            .await?;
+        let result = secondary.upsert_in(collection, id, vector, payload).await;
+        self.secondary_result(&secondary, result);
+        Ok(())
+    }
+
+    async fn delete_in(&self, collection: Option<&str>, id: &str) -> Result<()> {
+        let (primary, secondary) = self.pair();
+        primary.delete_in(collection, id).await?;
+        let result = secondary.delete_in(collection, id).await;
+        self.secondary_result(&secondary, result);
+        Ok(())
+    }
+
+    async fn search_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.primary().search_in(collection, vector, limit).await
+    }
+
+    async fn search_filtered(
+        &self,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.primary().search_filtered(vector, limit, filter).await
+    }
+
+    async fn search_filtered_in(
+        &self,
+        collection: Option<&str>,
+        vector: &[f32],
+        limit: usize,
+        filter: &VectorFilter,
+    ) -> Result<Vec<VectorSearchHit>> {
+        self.primary()
+            .search_filtered_in(collection, vector, limit, filter)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector_backend::InMemoryVectorBackend;
+
+    #[tokio::test]
+    async fn cutover_needs_parity_and_flips_reads() {
+        let old: Arc<dyn VectorBackend> = Arc::new(InMemoryVectorBackend::new(2));
+        let new: Arc<dyn VectorBackend> = Arc::new(InMemoryVectorBackend::new(2));
+        old.upsert("before", &[1.0, 0.0], None).await.unwrap();
+
+        let dual = DualWriteVectorBackend::new(old.clone(), new.clone()).unwrap();
+        dual.upsert("after", &[0.0, 1.0], None).await.unwrap();
+        assert_eq!(new.search(&[0.0, 1.0], 5).await.unwrap().len(), 1);
+        assert!(dual.cutover().is_err());
+
+        let probes = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        assert!(!dual.verify_parity(&probes, 2).await.unwrap().passed);
+        assert!(dual.cutover().is_err());
+
+        dual.backfill("before", &[1.0, 0.0]).await.unwrap();
+        let report = dual.verify_parity(&probes, 2).await.unwrap();
+        assert!(report.passed);
+        assert_eq!(report.mean_recall, 1.0);
+        dual.cutover().unwrap();
+
+        assert_eq!(
+            dual.primary().search(&[1.0, 0.0], 5).await.unwrap().len(),
+            2
+        );
+        dual.delete("after").await.unwrap();
+        assert_eq!(old.search(&[0.0, 1.0], 5).await.unwrap().len(), 1);
+    }
+}
@@ -1,13 +1,29 @@
 //! Vector search integration for semantic similarity
 
+use crate::encryption::{self, EncryptionKey, VectorCipher};
 use crate::error::{MemoryError, Result};
+use crate::platform_fs;
 use crate::types::MemoryId;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// How vectors are encoded on disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VectorQuantization {
+    /// Full f32 components
+    #[default]
+    None,
+    /// One signed byte per component plus a scale, about a quarter of the size
+    Int8,
+}
 
 /// Configuration for vector search
 #[derive(Debug, Clone)]
 pub struct VectorSearchConfig {
     pub dimension: usize,
     pub index_path: std::path::PathBuf,
+    /// Applies to vectors written from now on; both encodings are read
+    pub quantization: VectorQuantization,
 }
 
 impl Default for VectorSearchConfig {
@@ -15,18 +31,78 @@ impl Default for VectorSearchConfig {
         Self {
             dimension: 384, // Default for all-MiniLM-L6-v2
             index_path: std::path::PathBuf::from("./vector_index"),
+            quantization: VectorQuantization::None,
+        }
+    }
+}
+
+/// Prefix of int8-quantized vector files
+const QUANTIZED_MAGIC: &[u8] = b"GFQ8";
+
+fn encode_vector(embedding: &[f32], quantization: VectorQuantization) -> Result<Vec<u8>> {
+    match quantization {
+        VectorQuantization::None => {
+            bincode::serialize(embedding).map_err(|e| MemoryError::Serialization(e.to_string()))
+        }
+        VectorQuantization::Int8 => {
+            let max = embedding.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+            let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+            let mut out = Vec::with_capacity(QUANTIZED_MAGIC.len() + 4 + embedding.len());
+            out.extend_from_slice(QUANTIZED_MAGIC);
+            out.extend_from_slice(&scale.to_le_bytes());
+            out.extend(
+                embedding
+                    .iter()
+                    .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8 as u8),
+            );
+            Ok(out)
         }
     }
 }
 
+fn decode_vector(data: &[u8]) -> Result<Vec<f32>> {
+    match data.strip_prefix(QUANTIZED_MAGIC) {
+        Some(body) if body.len() >= 4 => {
+            let (scale, values) = body.split_at(4);
+            let scale = f32::from_le_bytes([scale[0], scale[1], scale[2], scale[3]]);
+            Ok(values.iter().map(|&b| b as i8 as f32 * scale).collect())
+        }
+        Some(_) => Err(MemoryError::Serialization(
+            "Truncated quantized vector".to_string(),
+        )),
+        None => bincode::deserialize(data).map_err(|e| MemoryError::Serialization(e.to_string())),
+    }
+}
+
 /// A vector index for semantic search
 pub struct VectorIndex {
     config: VectorSearchConfig,
+    cipher: Option<Arc<VectorCipher>>,
 }
 
 impl VectorIndex {
     pub fn new(config: VectorSearchConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            cipher: None,
+        }
+    }
+
+    /// Encrypt vector files with a key derived from `key` (requires the
+    /// `encryption` feature)
+    pub fn with_encryption(self, key: &EncryptionKey) -> Result<Self> {
+        let cipher = VectorCipher::open(&self.config.index_path, key)?;
+        Ok(self.with_cipher(Arc::new(cipher)))
+    }
+
+    pub(crate) fn with_cipher(mut self, cipher: Arc<VectorCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    pub(crate) fn with_quantization(mut self, quantization: VectorQuantization) -> Self {
+        self.config.quantization = quantization;
+        self
     }
 
     pub async fn init(&self) -> Result<()> {
@@ -41,19 +117,97 @@ impl VectorIndex {
         // For now, store in a simple file-based index
         // In production, this would use LanceDB or similar
         let index_file = self.config.index_path.join(format!("{}.bin", memory_id));
-        let data = bincode::serialize(&embedding)
-            .map_err(|e| MemoryError::Serialization(e.to_string()))?;
-        tokio::fs::write(&index_file, data)
+        let mut data = encode_vector(&embedding, self.config.quantization)?;
+        if let Some(cipher) = &self.cipher {
+            data = cipher.encrypt(&data)?;
+        }
+        platform_fs::write_atomic_async(index_file, data)
             .await
             .map_err(|e| MemoryError::Storage(format!("Failed to write vector: {}", e)))?;
         Ok(())
     }
 
+    /// Keep `payload` next to a memory's vector (`<id>.json`); `None` removes it
+    pub async fn store_payload(&self, memory_id: &MemoryId, payload: Option<&Value>) -> Result<()> {
+        let path = self.payload_path(memory_id);
+        let Some(payload) = payload else {
+            if path.exists() {
+                platform_fs::remove_file(&path).await.map_err(|e| {
+                    MemoryError::Storage(format!("Failed to delete vector payload: {}", e))
+                })?;
+            }
+            return Ok(());
+        };
+        let mut data =
+            serde_json::to_vec(payload).map_err(|e| MemoryError::Serialization(e.to_string()))?;
+        if let Some(cipher) = &self.cipher {
+            data = cipher.encrypt(&data)?;
+        }
+        platform_fs::write_atomic_async(path, data)
+            .await
+            .map_err(|e| MemoryError::Storage(format!("Failed to write vector payload: {}", e)))?;
+        Ok(())
+    }
+
+    /// Payload stored with [`store_payload`](Self::store_payload), if any
+    pub async fn load_payload(&self, memory_id: &MemoryId) -> Result<Option<Value>> {
+        let data = match tokio::fs::read(self.payload_path(memory_id)).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(MemoryError::Storage(format!(
+                    "Failed to read vector payload: {}",
+                    e
+                )))
+            }
+        };
+        let data = self.decrypt_if_needed(data)?;
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| MemoryError::Serialization(e.to_string()))
+    }
+
+    fn payload_path(&self, memory_id: &MemoryId) -> std::path::PathBuf {
+        self.config.index_path.join(format!("{}.json", memory_id))
+    }
+
+    fn decrypt_if_needed(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        if !encryption::is_encrypted(&data) {
+            return Ok(data);
+        }
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            MemoryError::Configuration(
+                "Vector index is encrypted but no encryption key was given".to_string(),
+            )
+        })?;
+        cipher.decrypt(&data)
+    }
+
     /// Search for similar vectors using cosine similarity
     pub async fn search(
         &self,
         query_embedding: &[f32],
         limit: usize,
+    ) -> Result<Vec<(MemoryId, f32)>> {
+        self.scan(query_embedding, limit, None::<fn(Option<&Value>) -> bool>)
+            .await
+    }
+
+    /// Like [`search`](Self::search), skipping vectors whose payload fails `keep`
+    pub async fn search_where(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        keep: impl Fn(Option<&Value>) -> bool,
+    ) -> Result<Vec<(MemoryId, f32)>> {
+        self.scan(query_embedding, limit, Some(keep)).await
+    }
+
+    async fn scan(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+        keep: Option<impl Fn(Option<&Value>) -> bool>,
     ) -> Result<Vec<(MemoryId, f32)>> {
         let mut results = Vec::new();
         let mut entries = tokio::fs::read_dir(&self.config.index_path)
@@ -73,12 +227,19 @@ impl VectorIndex {
                     .unwrap_or("")
                     .to_string();
 
+                if let Some(keep) = &keep {
+                    let payload = self.load_payload(&memory_id).await?;
+                    if !keep(payload.as_ref()) {
+                        continue;
+                    }
+                }
+
                 let data = tokio::fs::read(&path)
                     .await
                     .map_err(|e| MemoryError::Storage(format!("Failed to read vector: {}", e)))?;
+                let data = self.decrypt_if_needed(data)?;
 
-                let embedding: Vec<f32> = bincode::deserialize(&data)
-                    .map_err(|e| MemoryError::Serialization(e.to_string()))?;
+                let embedding = decode_vector(&data)?;
 
                 let similarity = cosine_similarity(query_embedding, &embedding);
                 results.push((memory_id, similarity));
@@ -96,16 +257,16 @@ impl VectorIndex {
     pub async fn delete(&self, memory_id: &MemoryId) -> Result<()> {
         let index_file = self.config.index_path.join(format!("{}.bin", memory_id));
         if index_file.exists() {
-            tokio::fs::remove_file(&index_file)
+            platform_fs::remove_file(&index_file)
                 .await
                 .map_err(|e| MemoryError::Storage(format!("Failed to delete vector: {}", e)))?;
         }
-        Ok(())
+        self.store_payload(memory_id, None).await
     }
 }
 
 /// Compute cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
@@ -121,7 +282,8 @@ fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     dot_product / (norm_a * norm_b)
 }
 
-const EMBEDDING_DIM: usize = 384;
+/// Length of the vectors [`generate_embedding`] produces
+pub(crate) const EMBEDDING_DIM: usize = 384;
 const SUBWORD_DIMS: usize = 256;
 const TOKEN_DIMS: usize = 96;
 const GLOBAL_DIMS: usize = 32;
@@ -141,11 +303,10 @@ fn char_bucket(c: char) -> usize {
     }
 }
 
-fn is_stopword(token: &str) -> bool {
+pub(crate) fn is_stopword(token: &str) -> bool {
     matches!(
         token,
-        "a"
-            | "an"
+        "a" | "an"
             | "the"
             | "and"
             | "or"
@@ -229,8 +390,7 @@ fn token_signature(token: &str) -> usize {
         .unwrap_or(OTHER_BUCKET);
     let len = token.chars().count().min(31);
 
-    ((((c1 * ALPHABET_SIZE + c2) * ALPHABET_SIZE + penultimate) * ALPHABET_SIZE + last) * 32)
-        + len
+    ((((c1 * ALPHABET_SIZE + c2) * ALPHABET_SIZE + penultimate) * ALPHABET_SIZE + last) * 32) + len
 }
 
 /// Generate a deterministic subword/text embedding.
@@ -319,6 +479,19 @@ mod tests {
         assert!((cosine_similarity(&a, &c)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_int8_quantization_round_trip() {
+        let v = generate_embedding("Quantized vectors stay close to the original");
+        let encoded = encode_vector(&v, VectorQuantization::Int8).unwrap();
+        assert!(encoded.len() < v.len() * 2);
+        let decoded = decode_vector(&encoded).unwrap();
+        assert_eq!(decoded.len(), v.len());
+        assert!(cosine_similarity(&v, &decoded) > 0.99);
+
+        let full = encode_vector(&v, VectorQuantization::None).unwrap();
+        assert_eq!(decode_vector(&full).unwrap(), v);
+    }
+
     #[test]
     fn test_embedding_dimension_and_nonzero() {
         let v = generate_embedding("I prefer concise technical answers.");
@@ -9,6 +9,8 @@
 //! - **Rollback Support**: Revert to any previous version
 //! - **Branching**: Create divergent versions for speculative scenarios
 //! - **Conflict Detection**: Identify when concurrent changes occur
+//! - **Time Travel**: `MemorySystem::load_as_of` and `search_as_of` read memories
+//!   as they were at a past timestamp
 //!
 //! ## Example
 //!
@@ -378,7 +380,7 @@ impl VersioningEngine {
     /// Get version history for a memory
     pub async fn get_history(&self, memory_id: &MemoryId) -> Result<Vec<MemoryVersion>> {
         let mut versions = self.repository.get_memory_versions(memory_id).await?;
-        versions.sort_by(|a, b| a.version_number.cmp(&b.version_number));
+        versions.sort_by_key(|v| v.version_number);
         Ok(versions)
     }
 
@@ -486,7 +488,7 @@ impl VersioningEngine {
         }
 
         // Sort by creation time
-        mem_versions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        mem_versions.sort_by_key(|v| v.created_at);
 
         let mut conflicts = vec![];
         for window in mem_versions.windows(2) {
@@ -861,6 +863,72 @@ mod tests {
         assert!(diff.changes.iter().any(|c| c.field == "tags"));
     }
 
+    #[tokio::test]
+    async fn load_and_search_as_of_reconstruct_past_state() {
+        use crate::MemorySystem;
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        let memory_system = MemorySystem::new(dir.path()).await.unwrap();
+        let tick = || tokio::time::sleep(Duration::from_millis(10));
+
+        let before = Utc::now();
+        tick().await;
+        let mut fact = create_test_memory("The meeting is on Tuesday");
+        memory_system.save(&fact).await.unwrap();
+        tick().await;
+        let tuesday = Utc::now();
+        tick().await;
+
+        fact.content = "The meeting moved to Thursday".to_string();
+        memory_system.update(&fact).await.unwrap();
+        tick().await;
+        let thursday = Utc::now();
+        tick().await;
+        memory_system.forget(&fact.id).await.unwrap();
+
+        assert!(memory_system
+            .load_as_of(&fact.id, before)
+            .await
+            .unwrap()
+            .is_none());
+        let then = memory_system
+            .load_as_of(&fact.id, tuesday)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(then.content, "The meeting is on Tuesday");
+        assert!(memory_system
+            .load_as_of(&fact.id, Utc::now())
+            .await
+            .unwrap()
+            .is_none());
+
+        let hits = memory_system
+            .search_as_of("tuesday", tuesday, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(memory_system
+            .search_as_of("tuesday", thursday, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(memory_system
+            .search_as_of("thursday", Utc::now(), 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let history = memory_system.history(&fact.id).await.unwrap();
+        let reasons: Vec<_> = history
+            .iter()
+            .map(|v| v.change_reason.as_deref().unwrap())
+            .collect();
+        assert_eq!(reasons, ["created", "updated", "forgotten"]);
+        assert_eq!(history[2].version_number, 3);
+    }
+
     // Dummy repository for testing
     struct DummyRepository;
 